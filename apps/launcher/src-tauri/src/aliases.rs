@@ -0,0 +1,80 @@
+//! Search aliases: a keyword typed as the whole query, like `ff` or `mail`,
+//! puts the result the user picked for it first, above whatever ranking
+//! would have put there.
+
+use crate::providers::SearchResult;
+use crate::settings::SearchAlias;
+
+/// Check an alias before it is saved
+pub fn validate(alias: &SearchAlias) -> Result<(), String> {
+    if alias.keyword.trim().is_empty() {
+        return Err("Alias keyword can't be empty".to_string());
+    }
+    if alias.result.id.is_empty() {
+        return Err("Alias has no result to stand for".to_string());
+    }
+    Ok(())
+}
+
+/// Move the result aliased to `query` to the top of ranked `results`,
+/// adding it from the alias if no provider returned it
+pub fn pin(query: &str, results: &mut Vec<SearchResult>, aliases: &[SearchAlias]) {
+    let query = query.trim();
+    let Some(alias) = aliases
+        .iter()
+        .find(|alias| alias.keyword.eq_ignore_ascii_case(query))
+    else {
+        return;
+    };
+
+    // The provider's copy is fresher than the one saved with the alias
+    let mut pinned = match results.iter().position(|r| r.id == alias.result.id) {
+        Some(index) => results.remove(index),
+        None => alias.result.clone(),
+    };
+    // Above the rest for views that sort by score again, like sections
+    let top = results.first().map_or(0.0, |r| r.score);
+    pinned.score = pinned.score.max(top + 1.0);
+    results.insert(0, pinned);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::{ResultCategory, ResultIcon};
+
+    fn result(id: &str, score: f32) -> SearchResult {
+        SearchResult {
+            id: id.to_string(),
+            title: id.to_string(),
+            subtitle: None,
+            icon: ResultIcon::Emoji("🔎".to_string()),
+            category: ResultCategory::Application,
+            score,
+            aria_label: None,
+        }
+    }
+
+    #[test]
+    fn pins_aliased_result() {
+        let aliases = vec![SearchAlias {
+            keyword: "ff".to_string(),
+            result: result("app:firefox", 0.0),
+        }];
+
+        let mut results = vec![result("file:ff.txt", 90.0), result("app:firefox", 40.0)];
+        pin(" FF ", &mut results, &aliases);
+        assert_eq!(results[0].id, "app:firefox");
+        assert_eq!(results[0].score, 91.0);
+        assert_eq!(results.len(), 2);
+
+        // Added from the alias when no provider found it
+        let mut results = vec![result("file:ff.txt", 90.0)];
+        pin("ff", &mut results, &aliases);
+        assert_eq!(results[0].id, "app:firefox");
+
+        let mut results = vec![result("file:ff.txt", 90.0)];
+        pin("ffx", &mut results, &aliases);
+        assert_eq!(results[0].id, "file:ff.txt");
+    }
+}
@@ -0,0 +1,148 @@
+//! Append-only record of security decisions, such as whether a plugin's
+//! command was allowed to run. Unlike the change history it's never merged
+//! or edited: one JSON line per event, rotated once it grows past
+//! `MAX_LOG_BYTES` with the previous file kept beside it.
+
+use chrono::{DateTime, Utc};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+const MAX_LOG_BYTES: u64 = 1024 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditOutcome {
+    /// Ran, or was let through
+    Allowed,
+    /// Refused by policy without asking
+    Denied,
+    /// The user said no when asked
+    Declined,
+    /// Allowed, but failed to run
+    Failed,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditEvent {
+    pub at: DateTime<Utc>,
+    /// Who asked, like `plugin:weather`
+    pub actor: String,
+    /// What was asked for, like `run_command`
+    pub action: String,
+    /// What it was asked for on, like the command line
+    pub subject: String,
+    pub outcome: AuditOutcome,
+    /// Why it was denied or how it failed
+    pub detail: Option<String>,
+}
+
+impl AuditEvent {
+    pub fn new(actor: &str, action: &str, subject: &str, outcome: AuditOutcome) -> Self {
+        Self {
+            at: Utc::now(),
+            actor: actor.to_string(),
+            action: action.to_string(),
+            subject: subject.to_string(),
+            outcome,
+            detail: None,
+        }
+    }
+
+    pub fn with_detail(mut self, detail: impl Into<String>) -> Self {
+        self.detail = Some(detail.into());
+        self
+    }
+}
+
+lazy_static::lazy_static! {
+    /// Serializes appends and rotation
+    static ref LOG: Mutex<PathBuf> = Mutex::new(
+        dirs::data_dir()
+            .unwrap_or_else(|| PathBuf::from("."))
+            .join("launcher")
+            .join("audit.log")
+    );
+}
+
+fn rotated(path: &Path) -> PathBuf {
+    path.with_extension("log.1")
+}
+
+fn append_to(path: &Path, event: &AuditEvent) -> Result<(), String> {
+    let line = serde_json::to_string(event).map_err(|e| e.to_string())?;
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    }
+    if std::fs::metadata(path).is_ok_and(|m| m.len() >= MAX_LOG_BYTES) {
+        std::fs::rename(path, rotated(path)).map_err(|e| e.to_string())?;
+    }
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .map_err(|e| e.to_string())?;
+    writeln!(file, "{}", line).map_err(|e| e.to_string())
+}
+
+fn read_from(path: &Path, limit: usize) -> Vec<AuditEvent> {
+    let read = |path: &Path| -> Vec<AuditEvent> {
+        std::fs::read_to_string(path)
+            .unwrap_or_default()
+            .lines()
+            .filter_map(|line| serde_json::from_str(line).ok())
+            .collect()
+    };
+    let mut events = read(path);
+    if events.len() < limit {
+        let mut older = read(&rotated(path));
+        older.append(&mut events);
+        events = older;
+    }
+    events.into_iter().rev().take(limit).collect()
+}
+
+/// Add an event to the log. Failing to write it is reported but doesn't
+/// stop what's being logged.
+pub fn record(event: AuditEvent) {
+    let path = LOG.lock();
+    if let Err(e) = append_to(&path, &event) {
+        eprintln!("Failed to write audit log: {}", e);
+    }
+}
+
+/// The latest `limit` events, newest first
+pub fn recent(limit: usize) -> Vec<AuditEvent> {
+    let path = LOG.lock();
+    read_from(&path, limit)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn appends_and_reads_newest_first() {
+        let path = std::env::temp_dir().join(format!("launcher-audit-{}.log", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let _ = std::fs::remove_file(rotated(&path));
+
+        for command in ["ls", "make", "rm -rf /"] {
+            let outcome = if command.starts_with("rm") {
+                AuditOutcome::Denied
+            } else {
+                AuditOutcome::Allowed
+            };
+            let event = AuditEvent::new("plugin:test", "run_command", command, outcome);
+            append_to(&path, &event).unwrap();
+        }
+
+        let events = read_from(&path, 2);
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].subject, "rm -rf /");
+        assert_eq!(events[0].outcome, AuditOutcome::Denied);
+        assert_eq!(events[1].subject, "make");
+        let _ = std::fs::remove_file(&path);
+    }
+}
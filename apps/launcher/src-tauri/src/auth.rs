@@ -1,4 +1,3 @@
-use keyring::Entry;
 use parking_lot::RwLock;
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
@@ -139,26 +138,15 @@ impl WebAuth {
     }
 
     fn save_session(&self, session: &UserSession) -> Result<(), String> {
-        let entry = Entry::new(SERVICE_NAME, AUTH_KEY)
-            .map_err(|e| format!("Failed to create keyring entry: {}", e))?;
-
         let json = serde_json::to_string(session)
             .map_err(|e| format!("Failed to serialize session: {}", e))?;
 
-        entry
-            .set_password(&json)
-            .map_err(|e| format!("Failed to save session: {}", e))?;
-
-        Ok(())
+        crate::secrets::set(SERVICE_NAME, AUTH_KEY, &json)
+            .map_err(|e| format!("Failed to save session: {}", e))
     }
 
     fn load_session(&self) -> Result<UserSession, String> {
-        let entry = Entry::new(SERVICE_NAME, AUTH_KEY)
-            .map_err(|e| format!("Failed to create keyring entry: {}", e))?;
-
-        let json = entry
-            .get_password()
-            .map_err(|e| format!("Failed to load session: {}", e))?;
+        let json = crate::secrets::get(SERVICE_NAME, AUTH_KEY).ok_or("No saved session")?;
 
         let session: UserSession =
             serde_json::from_str(&json).map_err(|e| format!("Failed to parse session: {}", e))?;
@@ -176,14 +164,8 @@ impl WebAuth {
     }
 
     fn delete_session(&self) -> Result<(), String> {
-        let entry = Entry::new(SERVICE_NAME, AUTH_KEY)
-            .map_err(|e| format!("Failed to create keyring entry: {}", e))?;
-
-        entry
-            .delete_credential()
-            .map_err(|e| format!("Failed to delete session: {}", e))?;
-
-        Ok(())
+        crate::secrets::delete(SERVICE_NAME, AUTH_KEY)
+            .map_err(|e| format!("Failed to delete session: {}", e))
     }
 
     pub async fn refresh_session(&self) -> Result<UserSession, String> {
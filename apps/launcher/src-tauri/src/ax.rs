@@ -0,0 +1,35 @@
+//! The macOS Accessibility permission, which reading other apps' menus and
+//! moving their windows needs. Other platforms don't gate these, so the
+//! checks always pass there.
+
+#[cfg(target_os = "macos")]
+#[link(name = "ApplicationServices", kind = "framework")]
+extern "C" {
+    fn AXIsProcessTrusted() -> bool;
+}
+
+/// Whether the launcher has been allowed to control the computer
+#[cfg(target_os = "macos")]
+pub fn is_trusted() -> bool {
+    unsafe { AXIsProcessTrusted() }
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn is_trusted() -> bool {
+    true
+}
+
+/// Open the Privacy & Security pane where the permission is granted
+#[cfg(target_os = "macos")]
+pub fn open_settings() -> Result<(), String> {
+    std::process::Command::new("open")
+        .arg("x-apple.systempreferences:com.apple.preference.security?Privacy_Accessibility")
+        .spawn()
+        .map(|_| ())
+        .map_err(|e| format!("Failed to open System Settings: {}", e))
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn open_settings() -> Result<(), String> {
+    Ok(())
+}
@@ -0,0 +1,123 @@
+use crate::settings::SettingsStore;
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+/// A cache whose memory use is tracked by the [`CacheCoordinator`].
+///
+/// Implementors report an approximate cost in bytes and know how to shrink
+/// themselves when the overall memory budget is exceeded.
+pub trait ManagedCache: Send + Sync {
+    /// Stable name shown in diagnostics, e.g. "plugin_modules"
+    fn cache_name(&self) -> &str;
+
+    /// Approximate memory held by the cache, in bytes
+    fn cost(&self) -> usize;
+
+    /// Evict entries until the cache holds at most `target_bytes`.
+    /// Returns the number of bytes freed.
+    fn evict(&self, target_bytes: usize) -> usize;
+}
+
+/// Memory usage of a single registered cache
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CacheUsage {
+    pub name: String,
+    pub bytes: usize,
+}
+
+/// Memory usage across all registered caches
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CacheUsageReport {
+    pub budget_bytes: usize,
+    pub total_bytes: usize,
+    pub caches: Vec<CacheUsage>,
+}
+
+/// Central registry of caches that enforces the overall memory budget
+pub struct CacheCoordinator {
+    caches: RwLock<Vec<Arc<dyn ManagedCache>>>,
+    settings: Arc<SettingsStore>,
+}
+
+impl CacheCoordinator {
+    pub fn new(settings: Arc<SettingsStore>) -> Self {
+        Self {
+            caches: RwLock::new(Vec::new()),
+            settings,
+        }
+    }
+
+    /// Register a cache so it counts towards the budget
+    pub fn register(&self, cache: Arc<dyn ManagedCache>) {
+        let mut caches = self.caches.write();
+        caches.retain(|c| c.cache_name() != cache.cache_name());
+        caches.push(cache);
+    }
+
+    pub fn budget_bytes(&self) -> usize {
+        self.settings.get().memory_budget_mb * 1024 * 1024
+    }
+
+    pub fn usage(&self) -> CacheUsageReport {
+        let caches: Vec<CacheUsage> = self
+            .caches
+            .read()
+            .iter()
+            .map(|c| CacheUsage {
+                name: c.cache_name().to_string(),
+                bytes: c.cost(),
+            })
+            .collect();
+
+        CacheUsageReport {
+            budget_bytes: self.budget_bytes(),
+            total_bytes: caches.iter().map(|c| c.bytes).sum(),
+            caches,
+        }
+    }
+
+    /// Evict from the largest caches first until total usage fits the budget.
+    /// Returns the number of bytes freed.
+    pub fn enforce_budget(&self) -> usize {
+        let budget = self.budget_bytes();
+        let caches = self.caches.read().clone();
+        let mut freed = 0;
+
+        loop {
+            let mut costs: Vec<(Arc<dyn ManagedCache>, usize)> =
+                caches.iter().map(|c| (c.clone(), c.cost())).collect();
+            let total: usize = costs.iter().map(|(_, cost)| cost).sum();
+            if total <= budget {
+                break;
+            }
+
+            costs.sort_by(|a, b| b.1.cmp(&a.1));
+            let overflow = total - budget;
+
+            let mut freed_this_round = 0;
+            for (cache, cost) in costs {
+                let target = cost.saturating_sub(overflow - freed_this_round);
+                freed_this_round += cache.evict(target);
+                if freed_this_round >= overflow {
+                    break;
+                }
+            }
+
+            if freed_this_round == 0 {
+                // Nothing left that can be evicted
+                break;
+            }
+            freed += freed_this_round;
+        }
+
+        freed
+    }
+
+    /// Empty every registered cache, e.g. when the user clears caches.
+    /// Returns the number of bytes freed.
+    pub fn clear_all(&self) -> usize {
+        let caches = self.caches.read().clone();
+        caches.iter().map(|cache| cache.evict(0)).sum()
+    }
+}
@@ -0,0 +1,334 @@
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command};
+
+/// What part of the screen to capture
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CaptureMode {
+    Full,
+    Window,
+    Region,
+}
+
+impl CaptureMode {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "full" => Some(Self::Full),
+            "window" => Some(Self::Window),
+            "region" => Some(Self::Region),
+            _ => None,
+        }
+    }
+}
+
+/// Folder captures are saved to, from settings or ~/Pictures/Screenshots
+pub fn capture_dir(configured: Option<&str>) -> PathBuf {
+    configured
+        .filter(|p| !p.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| dirs::picture_dir().map(|p| p.join("Screenshots")))
+        .or_else(|| dirs::home_dir().map(|p| p.join("Screenshots")))
+        .unwrap_or_else(|| PathBuf::from("."))
+}
+
+fn capture_path(dir: &Path, prefix: &str, extension: &str) -> Result<PathBuf, String> {
+    std::fs::create_dir_all(dir)
+        .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    let stamp = chrono::Local::now().format("%Y-%m-%d at %H.%M.%S");
+    Ok(crate::file_ops::unique_path(
+        &dir.join(format!("{} {}.{}", prefix, stamp, extension)),
+    ))
+}
+
+fn run(command: &mut Command) -> Result<(), String> {
+    let program = command.get_program().to_string_lossy().to_string();
+    let status = command
+        .status()
+        .map_err(|e| format!("Failed to run {}: {}", program, e))?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(format!("{} exited with {}", program, status))
+    }
+}
+
+/// Take a screenshot with the OS tools and return the saved PNG.
+///
+/// Region and window modes are interactive. If the user cancels, no file is
+/// written and an error is returned.
+pub fn take_screenshot(mode: CaptureMode, dir: &Path) -> Result<PathBuf, String> {
+    let path = capture_path(dir, "Screenshot", "png")?;
+    screenshot_to(mode, &path)?;
+
+    if !path.exists() {
+        return Err("Screenshot cancelled".to_string());
+    }
+    Ok(path)
+}
+
+#[cfg(target_os = "macos")]
+fn screenshot_to(mode: CaptureMode, path: &Path) -> Result<(), String> {
+    let mut command = Command::new("screencapture");
+    command.arg("-x");
+    match mode {
+        CaptureMode::Full => {}
+        CaptureMode::Window => {
+            command.args(["-i", "-w"]);
+        }
+        CaptureMode::Region => {
+            command.args(["-i", "-s"]);
+        }
+    }
+    run(command.arg(path))
+}
+
+#[cfg(target_os = "linux")]
+fn screenshot_to(mode: CaptureMode, path: &Path) -> Result<(), String> {
+    if std::env::var_os("WAYLAND_DISPLAY").is_some() {
+        // grim + slurp work on wlroots compositors; GNOME has its own tool
+        let grim = match mode {
+            CaptureMode::Full => run(Command::new("grim").arg(path)),
+            CaptureMode::Region | CaptureMode::Window => Command::new("slurp")
+                .output()
+                .map_err(|e| format!("Failed to run slurp: {}", e))
+                .and_then(|out| {
+                    if !out.status.success() {
+                        return Err("Screenshot cancelled".to_string());
+                    }
+                    let geometry = String::from_utf8_lossy(&out.stdout).trim().to_string();
+                    run(Command::new("grim").args(["-g", &geometry]).arg(path))
+                }),
+        };
+        // Fall back to the other tools unless the user cancelled the selection
+        if grim.is_ok() || matches!(&grim, Err(e) if e == "Screenshot cancelled") {
+            return grim;
+        }
+    }
+
+    let mut gnome = Command::new("gnome-screenshot");
+    match mode {
+        CaptureMode::Full => {}
+        CaptureMode::Window => {
+            gnome.arg("-w");
+        }
+        CaptureMode::Region => {
+            gnome.arg("-a");
+        }
+    }
+    if run(gnome.arg("-f").arg(path)).is_ok() {
+        return Ok(());
+    }
+
+    // ImageMagick's import works on any X11 session
+    let mut import = Command::new("import");
+    if mode == CaptureMode::Full {
+        import.args(["-window", "root"]);
+    }
+    run(import.arg(path))
+}
+
+#[cfg(target_os = "windows")]
+fn screenshot_to(mode: CaptureMode, path: &Path) -> Result<(), String> {
+    if mode != CaptureMode::Full {
+        return Err("Window and region screenshots are not supported on Windows yet".to_string());
+    }
+
+    let script = format!(
+        "Add-Type -AssemblyName System.Windows.Forms,System.Drawing; \
+         $b = [System.Windows.Forms.SystemInformation]::VirtualScreen; \
+         $bmp = New-Object System.Drawing.Bitmap $b.Width, $b.Height; \
+         $g = [System.Drawing.Graphics]::FromImage($bmp); \
+         $g.CopyFromScreen($b.Left, $b.Top, 0, 0, $bmp.Size); \
+         $bmp.Save('{}', [System.Drawing.Imaging.ImageFormat]::Png)",
+        path.to_string_lossy().replace('\'', "''")
+    );
+    run(Command::new("powershell").args(["-NoProfile", "-Command", &script]))
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+fn screenshot_to(_mode: CaptureMode, _path: &Path) -> Result<(), String> {
+    Err("Screenshots are not supported on this platform".to_string())
+}
+
+/// An in-progress screen recording
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordingInfo {
+    pub path: PathBuf,
+    pub mode: CaptureMode,
+    pub started_at: String,
+}
+
+struct ActiveRecording {
+    child: Child,
+    info: RecordingInfo,
+}
+
+/// Runs at most one screen recording at a time through the OS recorder
+/// (screencapture on macOS, wf-recorder or ffmpeg elsewhere)
+pub struct ScreenRecorder {
+    active: Mutex<Option<ActiveRecording>>,
+}
+
+impl ScreenRecorder {
+    pub fn new() -> Self {
+        Self {
+            active: Mutex::new(None),
+        }
+    }
+
+    pub fn current(&self) -> Option<RecordingInfo> {
+        let mut active = self.active.lock();
+        // The recorder may have exited on its own (e.g. cancelled selection)
+        if let Some(recording) = active.as_mut() {
+            if matches!(recording.child.try_wait(), Ok(Some(_))) {
+                *active = None;
+            }
+        }
+        active.as_ref().map(|r| r.info.clone())
+    }
+
+    pub fn start(&self, mode: CaptureMode, dir: &Path) -> Result<RecordingInfo, String> {
+        if self.current().is_some() {
+            return Err("A recording is already in progress".to_string());
+        }
+
+        let path = capture_path(dir, "Screen Recording", RECORDING_EXTENSION)?;
+        let child = spawn_recorder(mode, &path)?;
+        let info = RecordingInfo {
+            path,
+            mode,
+            started_at: chrono::Local::now().to_rfc3339(),
+        };
+
+        *self.active.lock() = Some(ActiveRecording {
+            child,
+            info: info.clone(),
+        });
+        Ok(info)
+    }
+
+    /// Stop the recording and wait for the recorder to finish writing the file
+    pub fn stop(&self) -> Result<PathBuf, String> {
+        let mut recording = self
+            .active
+            .lock()
+            .take()
+            .ok_or("No recording in progress")?;
+
+        interrupt(&mut recording.child)?;
+        let _ = recording.child.wait();
+
+        if recording.info.path.exists() {
+            Ok(recording.info.path)
+        } else {
+            Err("Recording was not saved".to_string())
+        }
+    }
+}
+
+impl Default for ScreenRecorder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(target_os = "macos")]
+const RECORDING_EXTENSION: &str = "mov";
+#[cfg(not(target_os = "macos"))]
+const RECORDING_EXTENSION: &str = "mp4";
+
+#[cfg(target_os = "macos")]
+fn spawn_recorder(mode: CaptureMode, path: &Path) -> Result<Child, String> {
+    let mut command = Command::new("screencapture");
+    command.args(["-v", "-x"]);
+    if mode != CaptureMode::Full {
+        command.args(["-i", "-s"]);
+    }
+    command
+        .arg(path)
+        .stdin(std::process::Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to start screencapture: {}", e))
+}
+
+#[cfg(target_os = "linux")]
+fn spawn_recorder(mode: CaptureMode, path: &Path) -> Result<Child, String> {
+    if std::env::var_os("WAYLAND_DISPLAY").is_some() {
+        let mut command = Command::new("wf-recorder");
+        if mode != CaptureMode::Full {
+            let out = Command::new("slurp")
+                .output()
+                .map_err(|e| format!("Failed to run slurp: {}", e))?;
+            if !out.status.success() {
+                return Err("Recording cancelled".to_string());
+            }
+            command.args(["-g", String::from_utf8_lossy(&out.stdout).trim()]);
+        }
+        return command
+            .arg("-f")
+            .arg(path)
+            .spawn()
+            .map_err(|e| format!("Failed to start wf-recorder: {}", e));
+    }
+
+    if mode != CaptureMode::Full {
+        return Err("Region recording requires a Wayland session with slurp".to_string());
+    }
+    let display = std::env::var("DISPLAY").unwrap_or_else(|_| ":0".to_string());
+    Command::new("ffmpeg")
+        .args(["-y", "-loglevel", "error", "-f", "x11grab", "-i", &display])
+        .args([
+            "-c:v", "libx264", "-preset", "veryfast", "-pix_fmt", "yuv420p",
+        ])
+        .arg(path)
+        .stdin(std::process::Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to start ffmpeg: {}", e))
+}
+
+#[cfg(target_os = "windows")]
+fn spawn_recorder(mode: CaptureMode, path: &Path) -> Result<Child, String> {
+    if mode != CaptureMode::Full {
+        return Err("Region recording is not supported on Windows yet".to_string());
+    }
+    Command::new("ffmpeg")
+        .args(["-y", "-loglevel", "error", "-f", "gdigrab", "-i", "desktop"])
+        .args([
+            "-c:v", "libx264", "-preset", "veryfast", "-pix_fmt", "yuv420p",
+        ])
+        .arg(path)
+        .stdin(std::process::Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to start ffmpeg: {}", e))
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+fn spawn_recorder(_mode: CaptureMode, _path: &Path) -> Result<Child, String> {
+    Err("Screen recording is not supported on this platform".to_string())
+}
+
+/// Ask the recorder to finish gracefully so the file gets finalized
+#[cfg(unix)]
+fn interrupt(child: &mut Child) -> Result<(), String> {
+    let result = unsafe { libc::kill(child.id() as libc::pid_t, libc::SIGINT) };
+    if result == 0 {
+        Ok(())
+    } else {
+        Err("Failed to stop recording".to_string())
+    }
+}
+
+#[cfg(not(unix))]
+fn interrupt(child: &mut Child) -> Result<(), String> {
+    use std::io::Write;
+
+    // ffmpeg stops cleanly when it reads 'q' from stdin
+    child
+        .stdin
+        .as_mut()
+        .ok_or("Failed to stop recording")?
+        .write_all(b"q")
+        .map_err(|e| format!("Failed to stop recording: {}", e))
+}
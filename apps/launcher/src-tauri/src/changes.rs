@@ -0,0 +1,242 @@
+//! A rolling record of changes to how the launcher is set up: settings,
+//! shortcuts and plugins. When something that worked yesterday doesn't
+//! today, this answers "what changed?" for the user and for support.
+
+use crate::settings::UserSettings;
+use chrono::{DateTime, Utc};
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeSet, VecDeque};
+use std::path::PathBuf;
+
+/// Changes kept, newest first
+const HISTORY_LEN: usize = 500;
+/// Edits of one setting this close together are kept as one change, so
+/// dragging a slider doesn't flood the history
+const MERGE_WINDOW_SECS: i64 = 60;
+/// Settings the launcher changes by itself as its window moves
+const UNTRACKED_SETTINGS: &[&str] = &["window_position", "window_size"];
+/// Settings recorded as shortcut changes
+const SHORTCUT_SETTINGS: &[&str] = &["custom_shortcut", "window_hotkeys"];
+/// Longer values are recorded without the value itself
+const MAX_VALUE_LEN: usize = 200;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangeKind {
+    Setting,
+    Shortcut,
+    PluginInstalled,
+    PluginUpdated,
+    PluginUninstalled,
+    PluginEnabled,
+    PluginDisabled,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Change {
+    pub at: DateTime<Utc>,
+    pub kind: ChangeKind,
+    /// Setting path like `power.pause_on_battery`, or the plugin's name
+    pub subject: String,
+    /// Value or version before the change, if it had a short one
+    pub from: Option<String>,
+    /// Value or version after the change, if it has a short one
+    pub to: Option<String>,
+}
+
+pub struct ChangeLog {
+    history: RwLock<VecDeque<Change>>,
+    path: PathBuf,
+}
+
+/// Settings that differ, as dotted paths with their old and new values.
+/// Objects are compared key by key, lists as a whole.
+fn diff(path: &str, before: &Value, after: &Value, changes: &mut Vec<(String, Value, Value)>) {
+    if before == after {
+        return;
+    }
+    match (before, after) {
+        (Value::Object(before), Value::Object(after)) => {
+            let keys: BTreeSet<&String> = before.keys().chain(after.keys()).collect();
+            for key in keys {
+                let child = if path.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{}.{}", path, key)
+                };
+                let value = |map: &serde_json::Map<String, Value>| {
+                    map.get(key).cloned().unwrap_or(Value::Null)
+                };
+                diff(&child, &value(before), &value(after), changes);
+            }
+        }
+        _ => changes.push((path.to_string(), before.clone(), after.clone())),
+    }
+}
+
+/// How a setting's value shows in the history
+fn describe(value: &Value) -> Option<String> {
+    let text = match value {
+        Value::Null => return None,
+        Value::String(s) => s.clone(),
+        Value::Array(items) if items.len() == 1 => "1 item".to_string(),
+        Value::Array(items) => format!("{} items", items.len()),
+        other => other.to_string(),
+    };
+    (text.len() <= MAX_VALUE_LEN).then_some(text)
+}
+
+impl ChangeLog {
+    pub fn new() -> Self {
+        let path = dirs::data_dir()
+            .unwrap_or_else(|| PathBuf::from("."))
+            .join("launcher")
+            .join("change_history.json");
+
+        let history = crate::journal::load_json_store(&path).unwrap_or_default();
+
+        Self {
+            history: RwLock::new(history),
+            path,
+        }
+    }
+
+    fn save(&self) {
+        let history = self.history.read();
+
+        if let Ok(json) = serde_json::to_string_pretty(&*history) {
+            if let Err(e) = crate::journal::write_json_store(&self.path, &json) {
+                eprintln!("Failed to save change history: {}", e);
+            }
+        }
+    }
+
+    pub fn record(
+        &self,
+        kind: ChangeKind,
+        subject: &str,
+        from: Option<String>,
+        to: Option<String>,
+    ) {
+        self.record_at(kind, subject, from, to, Utc::now());
+        self.save();
+    }
+
+    fn record_at(
+        &self,
+        kind: ChangeKind,
+        subject: &str,
+        from: Option<String>,
+        to: Option<String>,
+        at: DateTime<Utc>,
+    ) {
+        let mut history = self.history.write();
+        if matches!(kind, ChangeKind::Setting | ChangeKind::Shortcut) {
+            let recent = history
+                .iter()
+                .take_while(|c| (at - c.at).num_seconds() < MERGE_WINDOW_SECS)
+                .position(|c| c.kind == kind && c.subject == subject);
+            if let Some(mut change) = recent.and_then(|i| history.remove(i)) {
+                change.at = at;
+                change.to = to;
+                // Changed and changed back
+                if change.from != change.to {
+                    history.push_front(change);
+                }
+                return;
+            }
+        }
+        history.push_front(Change {
+            at,
+            kind,
+            subject: subject.to_string(),
+            from,
+            to,
+        });
+        history.truncate(HISTORY_LEN);
+    }
+
+    /// Record what differs between two versions of the settings
+    pub fn record_settings(&self, before: &UserSettings, after: &UserSettings) {
+        self.record_settings_at(before, after, Utc::now());
+        self.save();
+    }
+
+    fn record_settings_at(&self, before: &UserSettings, after: &UserSettings, at: DateTime<Utc>) {
+        let (Ok(before), Ok(after)) = (serde_json::to_value(before), serde_json::to_value(after))
+        else {
+            return;
+        };
+        let mut changes = Vec::new();
+        diff("", &before, &after, &mut changes);
+
+        for (path, from, to) in changes {
+            let setting = path.split('.').next().unwrap_or_default();
+            if UNTRACKED_SETTINGS.contains(&setting) {
+                continue;
+            }
+            let kind = if SHORTCUT_SETTINGS.contains(&setting) {
+                ChangeKind::Shortcut
+            } else {
+                ChangeKind::Setting
+            };
+            self.record_at(kind, &path, describe(&from), describe(&to), at);
+        }
+    }
+
+    /// Newest first, only those since `since` if given
+    pub fn history(&self, since: Option<DateTime<Utc>>) -> Vec<Change> {
+        self.history
+            .read()
+            .iter()
+            .take_while(|change| !since.is_some_and(|since| change.at < since))
+            .cloned()
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    #[test]
+    fn records_setting_changes() {
+        let log = ChangeLog {
+            history: RwLock::new(VecDeque::new()),
+            path: std::env::temp_dir().join("launcher-test-change-history.json"),
+        };
+        let start = Utc::now();
+        let before = UserSettings::default();
+
+        let mut after = before.clone();
+        after.close_on_blur = !before.close_on_blur;
+        after.window_position = Some((10, 10));
+        after.custom_shortcut = Some("Alt+Space".to_string());
+        log.record_settings_at(&before, &after, start);
+
+        let history = log.history(None);
+        assert_eq!(history.len(), 2);
+        assert!(history.iter().any(|c| c.kind == ChangeKind::Shortcut
+            && c.subject == "custom_shortcut"
+            && c.to.as_deref() == Some("Alt+Space")));
+        assert!(history.iter().all(|c| c.subject != "window_position"));
+
+        // Quick edits of one setting merge, and undoing one drops it
+        log.record_settings_at(&after, &before, start + Duration::seconds(10));
+        assert!(log.history(None).is_empty());
+
+        log.record_at(
+            ChangeKind::PluginInstalled,
+            "Weather",
+            None,
+            Some("1.0.0".to_string()),
+            start + Duration::hours(1),
+        );
+        let since = log.history(Some(start + Duration::minutes(30)));
+        assert_eq!(since.len(), 1);
+        assert_eq!(since[0].kind, ChangeKind::PluginInstalled);
+    }
+}
@@ -0,0 +1,473 @@
+use crate::indexer::IndexedFile;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// A cleanup step that changes the system and needs the user's confirmation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CleanupTask {
+    EmptyTrash,
+    ClearCaches,
+    FlushDns,
+}
+
+impl CleanupTask {
+    pub const ALL: [CleanupTask; 3] = [
+        CleanupTask::EmptyTrash,
+        CleanupTask::ClearCaches,
+        CleanupTask::FlushDns,
+    ];
+
+    pub fn id(&self) -> &'static str {
+        match self {
+            CleanupTask::EmptyTrash => "empty_trash",
+            CleanupTask::ClearCaches => "clear_caches",
+            CleanupTask::FlushDns => "flush_dns",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|task| task.id() == s)
+    }
+}
+
+/// What a task is about to do, shown in the confirmation dialog
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CleanupPreview {
+    pub task: CleanupTask,
+    pub title: String,
+    pub message: String,
+    /// Space the task will free, when it can be measured up front
+    pub bytes: Option<u64>,
+}
+
+/// Emitted while a task or scan runs; `total` is 0 when unknown
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CleanupProgress {
+    /// A `CleanupTask` id, or "largest" for the size scan
+    pub task: String,
+    pub message: String,
+    pub done: u64,
+    pub total: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CleanupReport {
+    pub task: CleanupTask,
+    pub freed_bytes: Option<u64>,
+    pub message: String,
+}
+
+/// A file, or a folder with the total size of the indexed files inside it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SizedEntry {
+    pub path: String,
+    pub size: u64,
+    pub is_dir: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LargestEntries {
+    pub files: Vec<SizedEntry>,
+    pub folders: Vec<SizedEntry>,
+}
+
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KB", "MB", "GB", "TB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} B", bytes)
+    } else {
+        format!("{:.1} {}", size, UNITS[unit])
+    }
+}
+
+pub fn dir_size(path: &Path) -> u64 {
+    walkdir::WalkDir::new(path)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter_map(|e| e.metadata().ok())
+        .filter(|m| m.is_file())
+        .map(|m| m.len())
+        .sum()
+}
+
+#[cfg(target_os = "linux")]
+fn trash_dir() -> Option<PathBuf> {
+    dirs::data_dir().map(|d| d.join("Trash"))
+}
+
+#[cfg(target_os = "macos")]
+fn trash_dir() -> Option<PathBuf> {
+    dirs::home_dir().map(|h| h.join(".Trash"))
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+fn trash_dir() -> Option<PathBuf> {
+    None
+}
+
+/// Caches the launcher can rebuild on its own: the marketplace cache, its
+/// icons and generated QR codes. App icons stay, they are only extracted on
+/// refresh.
+fn launcher_cache_files() -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    if let Some(cache) = dirs::cache_dir().map(|d| d.join("launcher")) {
+        files.extend(
+            walkdir::WalkDir::new(cache)
+                .into_iter()
+                .filter_map(|e| e.ok())
+                .filter(|e| e.file_type().is_file())
+                .map(|e| e.into_path()),
+        );
+    }
+    if let Some(icons) =
+        dirs::data_local_dir().map(|d| d.join("launcher").join("marketplace-icons"))
+    {
+        if let Ok(entries) = std::fs::read_dir(icons) {
+            files.extend(entries.filter_map(|e| e.ok()).map(|e| e.path()));
+        }
+    }
+    if let Some(icons) = dirs::data_local_dir().map(|d| d.join("launcher").join("icons")) {
+        if let Ok(entries) = std::fs::read_dir(icons) {
+            files.extend(
+                entries
+                    .filter_map(|e| e.ok())
+                    .map(|e| e.path())
+                    .filter(|p| {
+                        p.file_name()
+                            .is_some_and(|n| n.to_string_lossy().starts_with("qr-"))
+                    }),
+            );
+        }
+    }
+    files
+}
+
+fn files_size(files: &[PathBuf]) -> u64 {
+    files
+        .iter()
+        .filter_map(|f| std::fs::metadata(f).ok())
+        .map(|m| m.len())
+        .sum()
+}
+
+/// Describe what `task` will do, measuring what it would free where possible
+pub fn preview(task: CleanupTask) -> CleanupPreview {
+    match task {
+        CleanupTask::EmptyTrash => {
+            let bytes = trash_dir().filter(|d| d.exists()).map(|d| dir_size(&d));
+            let message = match bytes {
+                Some(bytes) => format!(
+                    "Permanently delete everything in the trash ({})? This can't be undone.",
+                    format_bytes(bytes)
+                ),
+                None => {
+                    "Permanently delete everything in the trash? This can't be undone.".to_string()
+                }
+            };
+            CleanupPreview {
+                task,
+                title: "Empty Trash".to_string(),
+                message,
+                bytes,
+            }
+        }
+        CleanupTask::ClearCaches => {
+            let bytes = files_size(&launcher_cache_files());
+            CleanupPreview {
+                task,
+                title: "Clear Launcher Caches".to_string(),
+                message: format!(
+                    "Delete {} of cached marketplace data and QR codes and drop in-memory \
+                     caches? They are rebuilt when needed.",
+                    format_bytes(bytes)
+                ),
+                bytes: Some(bytes),
+            }
+        }
+        CleanupTask::FlushDns => CleanupPreview {
+            task,
+            title: "Flush DNS Cache".to_string(),
+            message: "Clear the system's DNS cache? You may be asked for an administrator \
+                      password."
+                .to_string(),
+            bytes: None,
+        },
+    }
+}
+
+/// Delete the trash contents entry by entry, reporting progress
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+pub fn empty_trash(progress: &dyn Fn(CleanupProgress)) -> Result<CleanupReport, String> {
+    let task = CleanupTask::EmptyTrash;
+    let trash = trash_dir().ok_or("Could not find the trash folder")?;
+    // `info` holds the .trashinfo records of the items in `files`
+    let entries: Vec<PathBuf> = ["files", "info", "expunged"]
+        .iter()
+        .filter_map(|sub| std::fs::read_dir(trash.join(sub)).ok())
+        .flat_map(|dir| dir.filter_map(|e| e.ok()).map(|e| e.path()))
+        .collect();
+
+    let total = entries.len() as u64;
+    let mut freed = 0;
+    for (i, entry) in entries.iter().enumerate() {
+        let is_dir = entry.is_dir() && !entry.is_symlink();
+        let size = if is_dir {
+            dir_size(entry)
+        } else {
+            std::fs::symlink_metadata(entry)
+                .map(|m| m.len())
+                .unwrap_or(0)
+        };
+        let removed = if is_dir {
+            std::fs::remove_dir_all(entry)
+        } else {
+            std::fs::remove_file(entry)
+        };
+        match removed {
+            Ok(()) => freed += size,
+            Err(e) => eprintln!("Failed to delete {}: {}", entry.display(), e),
+        }
+        progress(CleanupProgress {
+            task: task.id().to_string(),
+            message: format!("Deleted {} of {} items", i + 1, total),
+            done: i as u64 + 1,
+            total,
+        });
+    }
+
+    Ok(CleanupReport {
+        task,
+        freed_bytes: Some(freed),
+        message: format!("Trash emptied, {} freed", format_bytes(freed)),
+    })
+}
+
+/// Empty the trash through Finder, which also empties the trash folders of
+/// mounted volumes and asks before deleting locked items
+#[cfg(target_os = "macos")]
+pub fn empty_trash(progress: &dyn Fn(CleanupProgress)) -> Result<CleanupReport, String> {
+    let task = CleanupTask::EmptyTrash;
+    let freed = trash_dir().map(|d| dir_size(&d));
+    progress(CleanupProgress {
+        task: task.id().to_string(),
+        message: "Emptying the trash".to_string(),
+        done: 0,
+        total: 0,
+    });
+    run(Command::new("osascript").args(["-e", "tell app \"Finder\" to empty trash"]))?;
+    Ok(CleanupReport {
+        task,
+        freed_bytes: freed,
+        message: "Trash emptied".to_string(),
+    })
+}
+
+#[cfg(target_os = "windows")]
+pub fn empty_trash(progress: &dyn Fn(CleanupProgress)) -> Result<CleanupReport, String> {
+    let task = CleanupTask::EmptyTrash;
+    progress(CleanupProgress {
+        task: task.id().to_string(),
+        message: "Emptying the recycle bin".to_string(),
+        done: 0,
+        total: 0,
+    });
+    run(Command::new("powershell").args([
+        "-NoProfile",
+        "-Command",
+        "Clear-RecycleBin -Force -ErrorAction SilentlyContinue",
+    ]))?;
+    Ok(CleanupReport {
+        task,
+        freed_bytes: None,
+        message: "Recycle bin emptied".to_string(),
+    })
+}
+
+/// Delete the launcher's disk caches. In-memory caches are dropped by the
+/// caller, which owns the `CacheCoordinator`.
+pub fn clear_disk_caches(progress: &dyn Fn(CleanupProgress)) -> Result<u64, String> {
+    let files = launcher_cache_files();
+    let total = files.len() as u64;
+    let mut freed = 0;
+    for (i, file) in files.iter().enumerate() {
+        let size = std::fs::metadata(file).map(|m| m.len()).unwrap_or(0);
+        if std::fs::remove_file(file).is_ok() {
+            freed += size;
+        }
+        if (i + 1) % 50 == 0 || i + 1 == files.len() {
+            progress(CleanupProgress {
+                task: CleanupTask::ClearCaches.id().to_string(),
+                message: format!("Deleted {} of {} cached files", i + 1, total),
+                done: i as u64 + 1,
+                total,
+            });
+        }
+    }
+    Ok(freed)
+}
+
+pub fn flush_dns() -> Result<CleanupReport, String> {
+    flush_dns_command()?;
+    Ok(CleanupReport {
+        task: CleanupTask::FlushDns,
+        freed_bytes: None,
+        message: "DNS cache flushed".to_string(),
+    })
+}
+
+#[cfg(target_os = "linux")]
+fn flush_dns_command() -> Result<(), String> {
+    run(Command::new("resolvectl").arg("flush-caches"))
+        .or_else(|_| run(Command::new("systemd-resolve").arg("--flush-caches")))
+        .map_err(|e| format!("Failed to flush DNS (is systemd-resolved running?): {}", e))
+}
+
+#[cfg(target_os = "macos")]
+fn flush_dns_command() -> Result<(), String> {
+    run(Command::new("osascript").args([
+        "-e",
+        "do shell script \"dscacheutil -flushcache; killall -HUP mDNSResponder\" \
+         with administrator privileges",
+    ]))
+}
+
+#[cfg(target_os = "windows")]
+fn flush_dns_command() -> Result<(), String> {
+    run(Command::new("ipconfig").arg("/flushdns"))
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+fn flush_dns_command() -> Result<(), String> {
+    Err("Flushing DNS is not supported on this platform".to_string())
+}
+
+fn run(command: &mut Command) -> Result<(), String> {
+    let program = command.get_program().to_string_lossy().to_string();
+    let output = command
+        .output()
+        .map_err(|e| format!("Failed to run {}: {}", program, e))?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+        Err(if stderr.is_empty() {
+            format!("{} exited with {}", program, output.status)
+        } else {
+            stderr
+        })
+    }
+}
+
+/// The `limit` largest files, and folders by the total size of the indexed
+/// files below them. Folders are only counted up to the index roots.
+pub fn largest_entries(
+    files: impl IntoIterator<Item = IndexedFile>,
+    roots: &[PathBuf],
+    limit: usize,
+) -> LargestEntries {
+    let mut largest_files: Vec<SizedEntry> = Vec::new();
+    let mut folders: HashMap<PathBuf, u64> = HashMap::new();
+
+    for file in files {
+        if file.is_dir {
+            continue;
+        }
+        let path = PathBuf::from(&file.path);
+        let root = roots.iter().find(|r| path.starts_with(r));
+        for ancestor in path.ancestors().skip(1) {
+            if root.map_or(true, |r| !ancestor.starts_with(r)) {
+                break;
+            }
+            *folders.entry(ancestor.to_path_buf()).or_default() += file.size;
+        }
+
+        largest_files.push(SizedEntry {
+            path: file.path,
+            size: file.size,
+            is_dir: false,
+        });
+        // Keep the working set small on big indexes
+        if largest_files.len() > limit * 4 {
+            largest_files.sort_by(|a, b| b.size.cmp(&a.size));
+            largest_files.truncate(limit);
+        }
+    }
+
+    largest_files.sort_by(|a, b| b.size.cmp(&a.size));
+    largest_files.truncate(limit);
+
+    let mut folders: Vec<SizedEntry> = folders
+        .into_iter()
+        .map(|(path, size)| SizedEntry {
+            path: path.to_string_lossy().into_owned(),
+            size,
+            is_dir: true,
+        })
+        .collect();
+    folders.sort_by(|a, b| b.size.cmp(&a.size).then_with(|| a.path.cmp(&b.path)));
+    folders.truncate(limit);
+
+    LargestEntries {
+        files: largest_files,
+        folders,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn file(path: &str, size: u64) -> IndexedFile {
+        IndexedFile {
+            path: path.to_string(),
+            name: String::new(),
+            extension: None,
+            size,
+            modified: Utc::now(),
+            is_dir: false,
+        }
+    }
+
+    #[test]
+    fn sums_folder_sizes_up_to_the_root() {
+        let roots = vec![PathBuf::from("/home/u/Documents")];
+        let largest = largest_entries(
+            vec![
+                file("/home/u/Documents/a/big.iso", 500),
+                file("/home/u/Documents/a/b/small.txt", 10),
+                file("/home/u/Documents/notes.md", 40),
+            ],
+            &roots,
+            2,
+        );
+
+        let files: Vec<&str> = largest.files.iter().map(|f| f.path.as_str()).collect();
+        assert_eq!(
+            files,
+            ["/home/u/Documents/a/big.iso", "/home/u/Documents/notes.md"]
+        );
+
+        assert_eq!(largest.folders[0].path, "/home/u/Documents");
+        assert_eq!(largest.folders[0].size, 550);
+        assert_eq!(largest.folders[1].path, "/home/u/Documents/a");
+        assert_eq!(largest.folders[1].size, 510);
+    }
+
+    #[test]
+    fn formats_bytes() {
+        assert_eq!(format_bytes(512), "512 B");
+        assert_eq!(format_bytes(1536), "1.5 KB");
+        assert_eq!(format_bytes(3 * 1024 * 1024 * 1024), "3.0 GB");
+        assert_eq!(CleanupTask::parse("flush_dns"), Some(CleanupTask::FlushDns));
+    }
+}
@@ -0,0 +1,334 @@
+//! The system clipboard, shared by providers, commands and plugins. Goes
+//! through the platform's own tools (wl-copy/xclip, pbcopy, PowerShell) so
+//! there's no clipboard connection to keep alive between calls.
+
+use std::process::Command;
+
+/// Pipe `text` into a clipboard helper's stdin, which unlike its command
+/// line other processes can't read
+#[cfg(any(target_os = "linux", target_os = "macos", target_os = "windows"))]
+fn pipe_to(program: &str, args: &[&str], text: &str) -> Result<(), String> {
+    use std::io::Write;
+    use std::process::Stdio;
+
+    let mut child = Command::new(program)
+        .args(args)
+        .stdin(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to run {}: {}", program, e))?;
+
+    child
+        .stdin
+        .take()
+        .ok_or("Failed to open clipboard stdin")?
+        .write_all(text.as_bytes())
+        .map_err(|e| format!("Failed to write to clipboard: {}", e))?;
+
+    let status = child.wait().map_err(|e| e.to_string())?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(format!("{} exited with {}", program, status))
+    }
+}
+
+/// Copy text to the system clipboard
+pub fn copy_text(text: &str) -> Result<(), String> {
+    #[cfg(target_os = "linux")]
+    {
+        if std::env::var_os("WAYLAND_DISPLAY").is_some() {
+            if pipe_to("wl-copy", &[], text).is_ok() {
+                return Ok(());
+            }
+        }
+        pipe_to("xclip", &["-selection", "clipboard"], text)
+            .or_else(|_| pipe_to("xsel", &["--clipboard", "--input"], text))
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        pipe_to("pbcopy", &[], text)
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        // clip.exe mangles non-ASCII text, Set-Clipboard doesn't. The text
+        // comes in on stdin, as UTF-8, so secrets stay off the command line.
+        pipe_to(
+            "powershell",
+            &[
+                "-NoProfile",
+                "-Command",
+                "[Console]::InputEncoding = [Text.Encoding]::UTF8; \
+                 Set-Clipboard -Value ([Console]::In.ReadToEnd())",
+            ],
+            text,
+        )
+    }
+
+    #[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+    {
+        let _ = text;
+        Err("Clipboard is not supported on this platform".to_string())
+    }
+}
+
+/// Copy a PNG image file to the system clipboard
+pub fn copy_image(path: &std::path::Path) -> Result<(), String> {
+    let status = image_copy_command(path)?
+        .status()
+        .map_err(|e| format!("Failed to copy image to clipboard: {}", e))?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err("Failed to copy image to clipboard".to_string())
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn image_copy_command(path: &std::path::Path) -> Result<Command, String> {
+    if std::env::var_os("WAYLAND_DISPLAY").is_some() {
+        let file = std::fs::File::open(path).map_err(|e| e.to_string())?;
+        let mut command = Command::new("wl-copy");
+        command.args(["--type", "image/png"]).stdin(file);
+        return Ok(command);
+    }
+
+    let mut command = Command::new("xclip");
+    command
+        .args(["-selection", "clipboard", "-t", "image/png", "-i"])
+        .arg(path);
+    Ok(command)
+}
+
+#[cfg(target_os = "macos")]
+fn image_copy_command(path: &std::path::Path) -> Result<Command, String> {
+    let script = format!(
+        "set the clipboard to (read (POSIX file \"{}\") as «class PNGf»)",
+        path.to_string_lossy().replace('"', "\\\"")
+    );
+    let mut command = Command::new("osascript");
+    command.args(["-e", &script]);
+    Ok(command)
+}
+
+#[cfg(target_os = "windows")]
+fn image_copy_command(path: &std::path::Path) -> Result<Command, String> {
+    let script = format!(
+        "Add-Type -AssemblyName System.Windows.Forms,System.Drawing; \
+         [System.Windows.Forms.Clipboard]::SetImage([System.Drawing.Image]::FromFile('{}'))",
+        path.to_string_lossy().replace('\'', "''")
+    );
+    let mut command = Command::new("powershell");
+    command.args(["-NoProfile", "-STA", "-Command", &script]);
+    Ok(command)
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+fn image_copy_command(_path: &std::path::Path) -> Result<Command, String> {
+    Err("Clipboard is not supported on this platform".to_string())
+}
+
+/// Read the image on the clipboard as PNG bytes
+pub fn read_image() -> Result<Vec<u8>, String> {
+    let output = image_paste_command()
+        .output()
+        .map_err(|e| format!("Failed to read clipboard: {}", e))?;
+    if !output.status.success() || output.stdout.is_empty() {
+        return Err("No image on the clipboard".to_string());
+    }
+    decode_pasted_image(output.stdout)
+}
+
+#[cfg(target_os = "linux")]
+fn image_paste_command() -> Command {
+    if std::env::var_os("WAYLAND_DISPLAY").is_some() {
+        let mut command = Command::new("wl-paste");
+        command.args(["--no-newline", "--type", "image/png"]);
+        return command;
+    }
+
+    let mut command = Command::new("xclip");
+    command.args(["-selection", "clipboard", "-t", "image/png", "-o"]);
+    command
+}
+
+#[cfg(target_os = "macos")]
+fn image_paste_command() -> Command {
+    let mut command = Command::new("osascript");
+    command.args(["-e", "the clipboard as «class PNGf»"]);
+    command
+}
+
+#[cfg(target_os = "windows")]
+fn image_paste_command() -> Command {
+    let mut command = Command::new("powershell");
+    command.args([
+        "-NoProfile",
+        "-STA",
+        "-Command",
+        "Add-Type -AssemblyName System.Windows.Forms,System.Drawing; \
+         $img = [System.Windows.Forms.Clipboard]::GetImage(); \
+         if ($img -eq $null) { exit 1 }; \
+         $ms = New-Object System.IO.MemoryStream; \
+         $img.Save($ms, [System.Drawing.Imaging.ImageFormat]::Png); \
+         [Convert]::ToBase64String($ms.ToArray())",
+    ]);
+    command
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+fn image_paste_command() -> Command {
+    Command::new("false")
+}
+
+#[cfg(target_os = "macos")]
+fn decode_pasted_image(stdout: Vec<u8>) -> Result<Vec<u8>, String> {
+    // osascript prints the data as «data PNGf89504E47...»
+    let text = String::from_utf8_lossy(&stdout);
+    let hex = text
+        .trim()
+        .strip_prefix("«data PNGf")
+        .and_then(|s| s.strip_suffix('»'))
+        .ok_or("No image on the clipboard")?;
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| {
+            hex.get(i..i + 2)
+                .and_then(|b| u8::from_str_radix(b, 16).ok())
+                .ok_or_else(|| "Invalid clipboard image data".to_string())
+        })
+        .collect()
+}
+
+#[cfg(target_os = "windows")]
+fn decode_pasted_image(stdout: Vec<u8>) -> Result<Vec<u8>, String> {
+    use base64::Engine;
+
+    base64::engine::general_purpose::STANDARD
+        .decode(String::from_utf8_lossy(&stdout).trim())
+        .map_err(|e| format!("Invalid clipboard image data: {}", e))
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+fn decode_pasted_image(stdout: Vec<u8>) -> Result<Vec<u8>, String> {
+    Ok(stdout)
+}
+
+/// Read the text on the clipboard
+pub fn read_text() -> Result<String, String> {
+    let output = text_paste_command()
+        .output()
+        .map_err(|e| format!("Failed to read clipboard: {}", e))?;
+    if !output.status.success() {
+        return Err("No text on the clipboard".to_string());
+    }
+    let text = String::from_utf8_lossy(&output.stdout);
+    // PowerShell appends a line break to whatever it prints
+    #[cfg(target_os = "windows")]
+    let text = text.strip_suffix("\r\n").unwrap_or(&text);
+    Ok(text.to_string())
+}
+
+#[cfg(target_os = "linux")]
+fn text_paste_command() -> Command {
+    if std::env::var_os("WAYLAND_DISPLAY").is_some() {
+        let mut command = Command::new("wl-paste");
+        command.args(["--no-newline", "--type", "text"]);
+        return command;
+    }
+
+    let mut command = Command::new("xclip");
+    command.args(["-selection", "clipboard", "-o"]);
+    command
+}
+
+#[cfg(target_os = "macos")]
+fn text_paste_command() -> Command {
+    Command::new("pbpaste")
+}
+
+#[cfg(target_os = "windows")]
+fn text_paste_command() -> Command {
+    let mut command = Command::new("powershell");
+    command.args([
+        "-NoProfile",
+        "-Command",
+        "[Console]::OutputEncoding = [Text.Encoding]::UTF8; Get-Clipboard -Raw",
+    ]);
+    command
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+fn text_paste_command() -> Command {
+    Command::new("false")
+}
+
+/// Clipboard formats that password managers add to mark their copies as
+/// secret, so history tools leave them alone
+const CONCEALED_FORMATS: &[&str] = &[
+    // macOS, http://nspasteboard.org
+    "org.nspasteboard.ConcealedType",
+    "org.nspasteboard.TransientType",
+    // KeePassXC and KDE apps on Linux
+    "x-kde-passwordManagerHint",
+    // Windows; password managers set these to opt out of clipboard history
+    "ExcludeClipboardContentFromMonitorProcessing",
+    "CanIncludeInClipboardHistory",
+];
+
+/// Whether the clipboard holds a secret, e.g. a password copied from a
+/// password manager. False when the formats can't be listed.
+pub fn is_concealed() -> bool {
+    let Ok(output) = formats_command().output() else {
+        return false;
+    };
+    let formats = String::from_utf8_lossy(&output.stdout);
+    formats
+        .lines()
+        .any(|format| CONCEALED_FORMATS.contains(&format.trim()))
+}
+
+#[cfg(target_os = "linux")]
+fn formats_command() -> Command {
+    if std::env::var_os("WAYLAND_DISPLAY").is_some() {
+        let mut command = Command::new("wl-paste");
+        command.arg("--list-types");
+        return command;
+    }
+
+    let mut command = Command::new("xclip");
+    command.args(["-selection", "clipboard", "-t", "TARGETS", "-o"]);
+    command
+}
+
+#[cfg(target_os = "macos")]
+fn formats_command() -> Command {
+    let mut command = Command::new("osascript");
+    command.args([
+        "-l",
+        "JavaScript",
+        "-e",
+        "ObjC.import('AppKit'); \
+         ObjC.deepUnwrap($.NSPasteboard.generalPasteboard.types).join('\\n')",
+    ]);
+    command
+}
+
+#[cfg(target_os = "windows")]
+fn formats_command() -> Command {
+    let mut command = Command::new("powershell");
+    command.args([
+        "-NoProfile",
+        "-STA",
+        "-Command",
+        "Add-Type -AssemblyName System.Windows.Forms; \
+         [System.Windows.Forms.Clipboard]::GetDataObject().GetFormats()",
+    ]);
+    command
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+fn formats_command() -> Command {
+    Command::new("false")
+}
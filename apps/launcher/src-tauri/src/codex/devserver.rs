@@ -1,3 +1,4 @@
+use crate::settings::Sandbox;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::io::{BufRead, BufReader};
@@ -114,8 +115,8 @@ impl DevServer {
         }
     }
 
-    /// Start the dev server
-    pub fn start(&mut self) -> Result<DevServerInfo, String> {
+    /// Start the dev server; `sandbox` is None in a trusted folder
+    pub fn start(&mut self, sandbox: Option<Sandbox>) -> Result<DevServerInfo, String> {
         if self.is_running {
             return Err("Dev server is already running".to_string());
         }
@@ -133,6 +134,9 @@ impl DevServer {
                 (parts[0], &parts[1..])
             };
 
+        let args: Vec<String> = args.iter().map(|arg| arg.to_string()).collect();
+        let (program, args) = crate::trust::sandboxed_command(sandbox, program, &args)?;
+
         // Spawn the dev server process
         let child = Command::new(program)
             .args(args)
@@ -239,6 +243,7 @@ impl DevServerManager {
         session_id: &str,
         working_dir: &Path,
         command: Option<String>,
+        sandbox: Option<Sandbox>,
     ) -> Result<DevServerInfo, String> {
         let mut servers = self.servers.lock().await;
 
@@ -249,7 +254,7 @@ impl DevServerManager {
 
         // Create and start new server
         let mut server = DevServer::new(working_dir, command, None);
-        let info = server.start()?;
+        let info = server.start(sandbox)?;
         servers.insert(session_id.to_string(), server);
 
         Ok(info)
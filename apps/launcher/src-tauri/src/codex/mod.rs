@@ -192,6 +192,18 @@ impl CodexManager {
         Ok(session_id)
     }
 
+    /// A session in `working_dir` that hasn't ended, or a new one
+    pub async fn find_or_create_session(&self, working_dir: &str) -> Result<String, String> {
+        let path = std::path::Path::new(working_dir);
+        for session in self.sessions.read().await.values() {
+            let ended = session.get_state().await == session::SessionState::Ended;
+            if session.working_dir == path && !ended {
+                return Ok(session.id.clone());
+            }
+        }
+        self.create_session(working_dir).await
+    }
+
     /// Get a session by ID
     pub async fn get_session(&self, session_id: &str) -> Option<CodexSession> {
         self.sessions.read().await.get(session_id).cloned()
@@ -1,3 +1,5 @@
+use crate::providers::ResultContext;
+use crate::settings::Sandbox;
 use serde::{Deserialize, Serialize};
 use std::io::{BufRead, BufReader};
 use std::path::PathBuf;
@@ -24,6 +26,8 @@ pub struct CodexSession {
     output_rx: Arc<Mutex<Option<mpsc::Receiver<SessionMessage>>>>,
     /// Conversation history for context
     history: Arc<RwLock<Vec<HistoryEntry>>>,
+    /// Search results the user handed to this session
+    attachments: Arc<RwLock<Vec<ResultContext>>>,
 }
 
 /// Entry in conversation history
@@ -185,11 +189,17 @@ impl CodexSession {
             output_tx: Arc::new(Mutex::new(None)),
             output_rx: Arc::new(Mutex::new(None)),
             history: Arc::new(RwLock::new(Vec::new())),
+            attachments: Arc::new(RwLock::new(Vec::new())),
         })
     }
 
-    /// Send a message to Codex and get responses
-    pub async fn send_message(&self, user_message: &str) -> Result<(), String> {
+    /// Send a message to Codex and get responses; `sandbox` is None in a
+    /// trusted folder
+    pub async fn send_message(
+        &self,
+        user_message: &str,
+        sandbox: Option<Sandbox>,
+    ) -> Result<(), String> {
         // Add to history
         {
             let mut history = self.history.write().await;
@@ -219,6 +229,7 @@ impl CodexSession {
             let result = run_codex_exec(
                 &working_dir,
                 &prompt,
+                sandbox,
                 tx.clone(),
                 thread_id_arc,
                 history_arc,
@@ -243,6 +254,17 @@ impl CodexSession {
         Ok(())
     }
 
+    /// Tell the session about a search result; later prompts include it
+    pub async fn attach(&self, context: ResultContext) {
+        let mut attachments = self.attachments.write().await;
+        let attached = attachments
+            .iter()
+            .any(|a| a.title == context.title && a.path == context.path);
+        if !attached {
+            attachments.push(context);
+        }
+    }
+
     /// Build the prompt with system context
     async fn build_prompt(&self, user_message: &str) -> String {
         let history = self.history.read().await;
@@ -257,6 +279,17 @@ impl CodexSession {
             context.push_str("\n");
         }
 
+        // Results handed over from search
+        let attachments = self.attachments.read().await;
+        if !attachments.is_empty() {
+            context.push_str("The user is asking about these search results:\n");
+            for attachment in attachments.iter() {
+                context.push_str(&attachment.describe());
+                context.push('\n');
+            }
+            context.push('\n');
+        }
+
         // System instructions for user-friendly behavior and direct action
         let system_prompt = r#"You are helping a non-technical user build software. CRITICAL RULES:
 
@@ -338,6 +371,7 @@ impl Clone for CodexSession {
             output_tx: Arc::clone(&self.output_tx),
             output_rx: Arc::clone(&self.output_rx),
             history: Arc::clone(&self.history),
+            attachments: Arc::clone(&self.attachments),
         }
     }
 }
@@ -355,6 +389,7 @@ pub struct SessionInfo {
 fn run_codex_exec(
     working_dir: &PathBuf,
     prompt: &str,
+    sandbox: Option<Sandbox>,
     tx: mpsc::Sender<SessionMessage>,
     thread_id_arc: Arc<RwLock<Option<String>>>,
     history_arc: Arc<RwLock<Vec<HistoryEntry>>>,
@@ -363,17 +398,15 @@ fn run_codex_exec(
     eprintln!("[Codex] Starting exec in: {}", working_dir.display());
     eprintln!("[Codex] Prompt length: {} chars", prompt.len());
 
-    // Build command with full access to the selected folder
-    // Since the user explicitly selected a folder, we trust it for file operations
+    // Full access only in a folder the user trusts
     // Note: codex exec doesn't support -a flag, it runs non-interactively by default
     let mut cmd = Command::new("codex");
     cmd.args([
         "exec",
         "--json",
         "--skip-git-repo-check",
-        // Use danger-full-access sandbox since user selected this specific folder
         "--sandbox",
-        "danger-full-access",
+        crate::trust::codex_policy(sandbox),
         "-C",
         &working_dir.to_string_lossy(),
         // Use "-" to read prompt from stdin (avoids command line length limits)
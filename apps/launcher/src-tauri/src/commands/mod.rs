@@ -19,6 +19,21 @@ pub struct Command {
     pub source: CommandSource,
     /// Whether this command is currently enabled
     pub enabled: bool,
+    /// What screen readers announce; generated from the name and
+    /// description when registered without one
+    #[serde(default)]
+    pub aria_label: Option<String>,
+}
+
+impl Command {
+    fn fill_aria_label(&mut self) {
+        if self.aria_label.is_none() {
+            self.aria_label = Some(format!(
+                "{} command, {}. Type {} colon to use it",
+                self.name, self.description, self.trigger
+            ));
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -54,6 +69,7 @@ impl CommandRegistry {
                 icon: Some("🤖".to_string()),
                 source: CommandSource::BuiltIn,
                 enabled: true,
+                aria_label: None,
             },
             Command {
                 id: "builtin:ai".to_string(),
@@ -63,6 +79,7 @@ impl CommandRegistry {
                 icon: Some("✨".to_string()),
                 source: CommandSource::BuiltIn,
                 enabled: true,
+                aria_label: None,
             },
             Command {
                 id: "builtin:settings".to_string(),
@@ -72,6 +89,7 @@ impl CommandRegistry {
                 icon: Some("⚙️".to_string()),
                 source: CommandSource::BuiltIn,
                 enabled: true,
+                aria_label: None,
             },
             Command {
                 id: "builtin:theme".to_string(),
@@ -81,6 +99,7 @@ impl CommandRegistry {
                 icon: Some("🎨".to_string()),
                 source: CommandSource::BuiltIn,
                 enabled: true,
+                aria_label: None,
             },
             Command {
                 id: "builtin:reload".to_string(),
@@ -90,6 +109,7 @@ impl CommandRegistry {
                 icon: Some("🔄".to_string()),
                 source: CommandSource::BuiltIn,
                 enabled: true,
+                aria_label: None,
             },
             Command {
                 id: "builtin:plugins".to_string(),
@@ -99,6 +119,18 @@ impl CommandRegistry {
                 icon: Some("🧩".to_string()),
                 source: CommandSource::BuiltIn,
                 enabled: true,
+                aria_label: None,
+            },
+            Command {
+                id: "builtin:rename".to_string(),
+                trigger: "rename".to_string(),
+                name: "Bulk Rename".to_string(),
+                description: "Rename selected files with a pattern like {name}-{n}.{ext} or s/find/replace/"
+                    .to_string(),
+                icon: Some("✏️".to_string()),
+                source: CommandSource::BuiltIn,
+                enabled: true,
+                aria_label: None,
             },
             Command {
                 id: "builtin:quit".to_string(),
@@ -108,11 +140,13 @@ impl CommandRegistry {
                 icon: Some("🚪".to_string()),
                 source: CommandSource::BuiltIn,
                 enabled: true,
+                aria_label: None,
             },
         ];
 
         let mut commands = self.commands.write();
-        for cmd in builtins {
+        for mut cmd in builtins {
+            cmd.fill_aria_label();
             commands.insert(cmd.id.clone(), cmd);
         }
     }
@@ -127,7 +161,7 @@ impl CommandRegistry {
         icon: Option<String>,
     ) {
         let id = format!("plugin:{}:{}", plugin_id, trigger);
-        let command = Command {
+        let mut command = Command {
             id: id.clone(),
             trigger: trigger.to_string(),
             name: name.to_string(),
@@ -135,7 +169,9 @@ impl CommandRegistry {
             icon,
             source: CommandSource::Plugin(plugin_id.to_string()),
             enabled: true,
+            aria_label: None,
         };
+        command.fill_aria_label();
 
         let mut commands = self.commands.write();
         commands.insert(id, command);
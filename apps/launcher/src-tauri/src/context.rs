@@ -0,0 +1,215 @@
+use crate::settings::{AppProfile, SettingsStore};
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+/// The application that had focus when the launcher was summoned
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FrontmostApp {
+    /// Display or process name, e.g. "Terminal" or "firefox"
+    pub name: String,
+    /// Executable path (Windows/Linux) or bundle id (macOS), when available
+    pub identifier: Option<String>,
+}
+
+/// The frontmost app and the profile that applies to it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActiveContext {
+    pub app: Option<FrontmostApp>,
+    pub profile: Option<AppProfile>,
+}
+
+/// Remembers which app the launcher was summoned over and resolves the
+/// matching per-app profile from settings
+pub struct ContextTracker {
+    current: RwLock<Option<FrontmostApp>>,
+    settings: Arc<SettingsStore>,
+}
+
+impl ContextTracker {
+    pub fn new(settings: Arc<SettingsStore>) -> Self {
+        Self {
+            current: RwLock::new(None),
+            settings,
+        }
+    }
+
+    /// Record the app the launcher was summoned over, skipping the
+    /// launcher's own windows so it can run once the launcher has focus.
+    /// Slow (it runs osascript or xdotool), so call it off the main thread.
+    pub fn capture(&self) {
+        *self.current.write() = detect_frontmost_app();
+    }
+
+    pub fn active(&self) -> ActiveContext {
+        let app = self.current.read().clone();
+        let profile = app.as_ref().and_then(|app| {
+            self.settings
+                .get()
+                .app_profiles
+                .into_iter()
+                .find(|p| p.matches(app))
+        });
+        ActiveContext { app, profile }
+    }
+}
+
+impl AppProfile {
+    fn matches(&self, app: &FrontmostApp) -> bool {
+        let name = app.name.to_lowercase();
+        let identifier = app.identifier.as_deref().unwrap_or("").to_lowercase();
+        self.apps.iter().any(|pattern| {
+            let pattern = pattern.to_lowercase();
+            !pattern.is_empty() && (name.contains(&pattern) || identifier.contains(&pattern))
+        })
+    }
+
+    /// Score added to a result from `provider_id`. Keys match a provider id
+    /// or a result id prefix such as `plugin:ssh`.
+    pub fn boost_for(&self, provider_id: &str, result_id: &str) -> f32 {
+        self.provider_boosts
+            .iter()
+            .filter(|(key, _)| {
+                key.as_str() == provider_id || result_id.starts_with(&format!("{}:", key))
+            })
+            .map(|(_, boost)| *boost)
+            .sum()
+    }
+}
+
+/// Lists on-screen windows front to back and returns the owner of the
+/// first ordinary one that isn't the launcher's
+#[cfg(target_os = "macos")]
+const FRONTMOST_SCRIPT: &str = r#"
+ObjC.import('AppKit');
+ObjC.import('CoreGraphics');
+function run(argv) {
+  const own = Number(argv[0]);
+  const windows = ObjC.deepUnwrap(ObjC.castRefToObject(
+    $.CGWindowListCopyWindowInfo($.kCGWindowListOptionOnScreenOnly, $.kCGNullWindowID)));
+  const win = windows.find(w => w.kCGWindowLayer === 0 && w.kCGWindowOwnerPID !== own);
+  if (!win) return '';
+  const app = $.NSRunningApplication.runningApplicationWithProcessIdentifier(win.kCGWindowOwnerPID);
+  const id = app.isNil() ? '' : ObjC.unwrap(app.bundleIdentifier) || '';
+  return win.kCGWindowOwnerName + '\n' + id;
+}
+"#;
+
+#[cfg(target_os = "macos")]
+fn detect_frontmost_app() -> Option<FrontmostApp> {
+    let output = std::process::Command::new("osascript")
+        .args(["-l", "JavaScript", "-e", FRONTMOST_SCRIPT])
+        .arg(std::process::id().to_string())
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let mut lines = stdout.lines();
+    let name = lines.next()?.trim().to_string();
+    if name.is_empty() {
+        return None;
+    }
+    let identifier = lines
+        .next()
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty());
+    Some(FrontmostApp { name, identifier })
+}
+
+#[cfg(target_os = "linux")]
+fn detect_frontmost_app() -> Option<FrontmostApp> {
+    // X11 only; Wayland compositors don't expose the window stack. Windows
+    // are listed bottom to top, so the topmost one that isn't ours is the
+    // one the launcher was summoned over.
+    let output = std::process::Command::new("xprop")
+        .args(["-root", "_NET_CLIENT_LIST_STACKING"])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let (_, windows) = stdout.split_once('#')?;
+    let own = std::process::id().to_string();
+    windows.split(',').rev().find_map(|window| {
+        let output = std::process::Command::new("xdotool")
+            .args(["getwindowpid", window.trim()])
+            .output()
+            .ok()?;
+        let pid = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        if !output.status.success() || pid == own {
+            return None;
+        }
+
+        let name = std::fs::read_to_string(format!("/proc/{}/comm", pid))
+            .ok()?
+            .trim()
+            .to_string();
+        let identifier = std::fs::read_link(format!("/proc/{}/exe", pid))
+            .ok()
+            .map(|p| p.to_string_lossy().to_string());
+        Some(FrontmostApp { name, identifier })
+    })
+}
+
+#[cfg(target_os = "windows")]
+fn detect_frontmost_app() -> Option<FrontmostApp> {
+    use ::windows::core::PWSTR;
+    use ::windows::Win32::Foundation::CloseHandle;
+    use ::windows::Win32::System::Threading::{
+        OpenProcess, QueryFullProcessImageNameW, PROCESS_NAME_WIN32,
+        PROCESS_QUERY_LIMITED_INFORMATION,
+    };
+    use ::windows::Win32::UI::WindowsAndMessaging::{
+        GetForegroundWindow, GetWindow, GetWindowThreadProcessId, IsWindowVisible, GW_HWNDNEXT,
+    };
+
+    unsafe {
+        // Walk down the z-order from the foreground window to the first
+        // visible one that isn't the launcher's
+        let own = std::process::id();
+        let mut hwnd = GetForegroundWindow();
+        let pid = loop {
+            if hwnd.0.is_null() {
+                return None;
+            }
+            let mut pid = 0u32;
+            GetWindowThreadProcessId(hwnd, Some(&mut pid));
+            if pid != 0 && pid != own && IsWindowVisible(hwnd).as_bool() {
+                break pid;
+            }
+            hwnd = GetWindow(hwnd, GW_HWNDNEXT).ok()?;
+        };
+
+        let process = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, pid).ok()?;
+        let mut buffer = [0u16; 1024];
+        let mut len = buffer.len() as u32;
+        let result = QueryFullProcessImageNameW(
+            process,
+            PROCESS_NAME_WIN32,
+            PWSTR(buffer.as_mut_ptr()),
+            &mut len,
+        );
+        let _ = CloseHandle(process);
+        result.ok()?;
+
+        let path = String::from_utf16_lossy(&buffer[..len as usize]);
+        let name = std::path::Path::new(&path)
+            .file_stem()
+            .map(|s| s.to_string_lossy().to_string())
+            .unwrap_or_else(|| path.clone());
+        Some(FrontmostApp {
+            name,
+            identifier: Some(path),
+        })
+    }
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "linux", target_os = "windows")))]
+fn detect_frontmost_app() -> Option<FrontmostApp> {
+    None
+}
@@ -0,0 +1,264 @@
+//! Exchange rates for currency conversion in the calculator, such as
+//! `100 usd to eur`. Rates come from open.er-api.com, which needs no key,
+//! and are fetched again once they're older than `currency_refresh_hours`.
+//! The last rates fetched are kept on disk, so conversion keeps working
+//! offline, flagged as using last known rates.
+
+use crate::settings::SettingsStore;
+use chrono::{DateTime, Utc};
+use parking_lot::{Mutex, RwLock};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+const RATES_URL: &str = "https://open.er-api.com/v6/latest/USD";
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(4);
+/// After a failed fetch, use the last known rates for this long before
+/// trying again, rather than on every keystroke
+const RETRY_AFTER: Duration = Duration::from_secs(60);
+
+/// Symbols and names accepted besides ISO codes
+const ALIASES: &[(&str, &str)] = &[
+    ("$", "USD"),
+    ("dollar", "USD"),
+    ("dollars", "USD"),
+    ("€", "EUR"),
+    ("euro", "EUR"),
+    ("euros", "EUR"),
+    ("£", "GBP"),
+    ("pound", "GBP"),
+    ("pounds", "GBP"),
+    ("¥", "JPY"),
+    ("yen", "JPY"),
+    ("yuan", "CNY"),
+    ("rmb", "CNY"),
+    ("₹", "INR"),
+    ("rupee", "INR"),
+    ("rupees", "INR"),
+    ("₩", "KRW"),
+    ("won", "KRW"),
+    ("₽", "RUB"),
+    ("ruble", "RUB"),
+    ("rubles", "RUB"),
+];
+
+/// A currency's ISO code from its code, symbol or name
+fn currency_code(token: &str) -> Option<String> {
+    let token = token.trim().to_lowercase();
+    if let Some((_, code)) = ALIASES.iter().find(|(alias, _)| *alias == token) {
+        return Some(code.to_string());
+    }
+    let is_code = token.len() == 3 && token.chars().all(|c| c.is_ascii_alphabetic());
+    is_code.then(|| token.to_uppercase())
+}
+
+/// Amount and currencies from `100 usd to eur`, `$20 in gbp` or `5€ to $`
+fn parse(query: &str) -> Option<(f64, String, String)> {
+    let query = query.trim();
+    let (left, right) = [" to ", " in ", " -> ", " => "]
+        .iter()
+        .find_map(|separator| query.split_once(separator))?;
+    let to = currency_code(right)?;
+
+    let left = left.trim();
+    let is_number = |c: char| c.is_ascii_digit() || c == '.' || c == ',';
+    let start = left.find(is_number)?;
+    let end = left[start..]
+        .find(|c: char| !is_number(c))
+        .map_or(left.len(), |i| start + i);
+    let amount: f64 = left[start..end].replace(',', "").parse().ok()?;
+
+    // The currency goes either before the amount or after it
+    let from = match (left[..start].trim(), left[end..].trim()) {
+        ("", "") => return None,
+        (symbol, "") => currency_code(symbol)?,
+        ("", code) => currency_code(code)?,
+        _ => return None,
+    };
+    Some((amount, from, to))
+}
+
+/// Rates against the US dollar
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RatesSnapshot {
+    pub fetched_at: DateTime<Utc>,
+    /// Units of each currency per dollar, by ISO code
+    pub rates: HashMap<String, f64>,
+}
+
+impl RatesSnapshot {
+    fn rate(&self, from: &str, to: &str) -> Option<f64> {
+        let from = *self.rates.get(from)?;
+        let to = *self.rates.get(to)?;
+        (from > 0.0).then_some(to / from)
+    }
+}
+
+#[derive(Deserialize)]
+struct RatesResponse {
+    result: String,
+    #[serde(default)]
+    rates: HashMap<String, f64>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Conversion {
+    pub amount: f64,
+    pub from: String,
+    pub to: String,
+    pub value: f64,
+    /// How much one `from` is worth in `to`
+    pub rate: f64,
+    pub fetched_at: DateTime<Utc>,
+    /// Fresh rates couldn't be fetched, so these are the last known ones
+    pub offline: bool,
+}
+
+pub struct ExchangeRates {
+    client: reqwest::blocking::Client,
+    settings: Arc<SettingsStore>,
+    snapshot: RwLock<Option<RatesSnapshot>>,
+    /// When the last fetch failed, if it did
+    failed_at: Mutex<Option<Instant>>,
+    path: PathBuf,
+}
+
+impl ExchangeRates {
+    pub fn new(settings: Arc<SettingsStore>) -> Self {
+        let path = dirs::data_dir()
+            .unwrap_or_else(|| PathBuf::from("."))
+            .join("launcher")
+            .join("exchange_rates.json");
+
+        Self {
+            client: reqwest::blocking::Client::builder()
+                .timeout(REQUEST_TIMEOUT)
+                .build()
+                .unwrap_or_default(),
+            settings,
+            snapshot: RwLock::new(crate::journal::load_json_store(&path)),
+            failed_at: Mutex::new(None),
+            path,
+        }
+    }
+
+    fn fetch(&self) -> Result<RatesSnapshot, String> {
+        let response: RatesResponse = self
+            .client
+            .get(RATES_URL)
+            .send()
+            .and_then(|r| r.error_for_status())
+            .and_then(|r| r.json())
+            .map_err(|e| format!("Exchange rate request failed: {}", e))?;
+        if response.result != "success" || response.rates.is_empty() {
+            return Err("The exchange rate service returned no rates".to_string());
+        }
+        Ok(RatesSnapshot {
+            fetched_at: Utc::now(),
+            rates: response.rates,
+        })
+    }
+
+    fn save(&self, snapshot: &RatesSnapshot) {
+        if let Ok(json) = serde_json::to_string_pretty(snapshot) {
+            if let Err(e) = crate::journal::write_json_store(&self.path, &json) {
+                eprintln!("Failed to save exchange rates: {}", e);
+            }
+        }
+    }
+
+    /// Rates within the refresh interval, fetching them if needed; the last
+    /// known rates, and true, when that fails
+    fn current(&self) -> Option<(RatesSnapshot, bool)> {
+        let known = self.snapshot.read().clone();
+        let hours = self.settings.get().currency_refresh_hours.max(1);
+        let max_age = chrono::Duration::hours(hours as i64);
+        if let Some(known) = known
+            .as_ref()
+            .filter(|k| Utc::now() - k.fetched_at < max_age)
+        {
+            return Some((known.clone(), false));
+        }
+
+        let failed_recently = self
+            .failed_at
+            .lock()
+            .is_some_and(|at| at.elapsed() < RETRY_AFTER);
+        if !failed_recently {
+            match self.fetch() {
+                Ok(fresh) => {
+                    self.save(&fresh);
+                    *self.snapshot.write() = Some(fresh.clone());
+                    *self.failed_at.lock() = None;
+                    return Some((fresh, false));
+                }
+                Err(e) => {
+                    eprintln!("{}", e);
+                    *self.failed_at.lock() = Some(Instant::now());
+                }
+            }
+        }
+        known.map(|known| (known, true))
+    }
+
+    /// Convert a query like `100 usd to eur`. Blocks while rates are fetched.
+    pub fn convert(&self, query: &str) -> Option<Conversion> {
+        let (amount, from, to) = parse(query)?;
+        // Don't go to the network for codes that aren't currencies
+        if let Some(known) = self.snapshot.read().as_ref() {
+            if !known.rates.contains_key(&from) || !known.rates.contains_key(&to) {
+                return None;
+            }
+        }
+
+        let (snapshot, offline) = self.current()?;
+        let rate = snapshot.rate(&from, &to)?;
+        Some(Conversion {
+            amount,
+            value: amount * rate,
+            from,
+            to,
+            rate,
+            fetched_at: snapshot.fetched_at,
+            offline,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_and_converts_amounts() {
+        assert_eq!(
+            parse("100 usd to eur"),
+            Some((100.0, "USD".to_string(), "EUR".to_string()))
+        );
+        assert_eq!(
+            parse("$1,250.50 in gbp"),
+            Some((1250.5, "USD".to_string(), "GBP".to_string()))
+        );
+        assert_eq!(
+            parse("5€ to yen"),
+            Some((5.0, "EUR".to_string(), "JPY".to_string()))
+        );
+        assert_eq!(parse("how to cook rice"), None);
+        assert_eq!(parse("3 pm in utc"), None);
+        assert_eq!(parse("usd to eur"), None);
+
+        let snapshot = RatesSnapshot {
+            fetched_at: Utc::now(),
+            rates: HashMap::from([
+                ("USD".to_string(), 1.0),
+                ("EUR".to_string(), 0.9),
+                ("GBP".to_string(), 0.75),
+            ]),
+        };
+        assert_eq!(snapshot.rate("USD", "EUR"), Some(0.9));
+        assert!((snapshot.rate("GBP", "EUR").unwrap() - 1.2).abs() < 1e-9);
+        assert_eq!(snapshot.rate("USD", "XYZ"), None);
+    }
+}
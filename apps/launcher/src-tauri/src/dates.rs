@@ -0,0 +1,406 @@
+//! Date and time queries for the calculator: converting a time between
+//! zones (`3pm EST in CET`), the time somewhere (`time in Tokyo`), days to
+//! or from a date (`days until dec 25`) and Unix timestamps
+//! (`unix 1714000000`). Zones are abbreviations, taken at their fixed
+//! offset, IANA names or city names from the tz database.
+
+use chrono::{
+    DateTime, Datelike, FixedOffset, Local, NaiveDate, NaiveDateTime, NaiveTime, Offset, TimeZone,
+    Utc,
+};
+use chrono_tz::Tz;
+use regex::Regex;
+
+lazy_static::lazy_static! {
+    static ref TIME_RE: Regex =
+        Regex::new(r"^(\d{1,2})(?::(\d{2}))?\s*(am|pm)?(?:\s+(.+))?$").unwrap();
+}
+
+/// Abbreviations and their offsets east of UTC, in minutes
+const ABBREVIATIONS: &[(&str, i32)] = &[
+    ("UTC", 0),
+    ("GMT", 0),
+    ("BST", 60),
+    ("CET", 60),
+    ("CEST", 120),
+    ("EET", 120),
+    ("EEST", 180),
+    ("MSK", 180),
+    ("IST", 330),
+    ("SGT", 480),
+    ("HKT", 480),
+    ("JST", 540),
+    ("KST", 540),
+    ("AEST", 600),
+    ("AEDT", 660),
+    ("NZST", 720),
+    ("NZDT", 780),
+    ("HST", -600),
+    ("AKST", -540),
+    ("AKDT", -480),
+    ("PST", -480),
+    ("PDT", -420),
+    ("MST", -420),
+    ("MDT", -360),
+    ("CST", -360),
+    ("CDT", -300),
+    ("EST", -300),
+    ("EDT", -240),
+];
+
+/// Places people ask about that aren't tz database city names
+const CITIES: &[(&str, &str)] = &[
+    ("sf", "America/Los_Angeles"),
+    ("san francisco", "America/Los_Angeles"),
+    ("la", "America/Los_Angeles"),
+    ("seattle", "America/Los_Angeles"),
+    ("nyc", "America/New_York"),
+    ("boston", "America/New_York"),
+    ("washington", "America/New_York"),
+    ("miami", "America/New_York"),
+    ("austin", "America/Chicago"),
+    ("dallas", "America/Chicago"),
+    ("beijing", "Asia/Shanghai"),
+    ("delhi", "Asia/Kolkata"),
+    ("new delhi", "Asia/Kolkata"),
+    ("mumbai", "Asia/Kolkata"),
+    ("bangalore", "Asia/Kolkata"),
+    ("munich", "Europe/Berlin"),
+    ("barcelona", "Europe/Madrid"),
+    ("milan", "Europe/Rome"),
+];
+
+#[derive(Debug, Clone, Copy)]
+enum Zone {
+    Local,
+    Fixed(&'static str, i32),
+    Named(Tz),
+}
+
+impl Zone {
+    fn parse(name: &str) -> Option<Self> {
+        let name = name.trim();
+        if matches!(name, "local" | "here") {
+            return Some(Zone::Local);
+        }
+        let upper = name.to_uppercase();
+        if let Some((abbreviation, minutes)) = ABBREVIATIONS.iter().find(|(a, _)| *a == upper) {
+            return Some(Zone::Fixed(*abbreviation, *minutes));
+        }
+        if let Some((_, tz)) = CITIES.iter().find(|(city, _)| *city == name) {
+            return tz.parse().ok().map(Zone::Named);
+        }
+        // `Europe/Oslo`, or just `oslo`
+        let name = name.replace(' ', "_");
+        chrono_tz::TZ_VARIANTS
+            .iter()
+            .find(|tz| {
+                let last = tz.name().rsplit('/').next().unwrap_or_default();
+                tz.name().eq_ignore_ascii_case(&name) || last.eq_ignore_ascii_case(&name)
+            })
+            .map(|tz| Zone::Named(*tz))
+    }
+
+    fn to_utc(self, local: NaiveDateTime) -> Option<DateTime<Utc>> {
+        let at = match self {
+            Zone::Local => Local
+                .from_local_datetime(&local)
+                .earliest()?
+                .with_timezone(&Utc),
+            Zone::Fixed(_, minutes) => FixedOffset::east_opt(minutes * 60)?
+                .from_local_datetime(&local)
+                .single()?
+                .with_timezone(&Utc),
+            Zone::Named(tz) => tz
+                .from_local_datetime(&local)
+                .earliest()?
+                .with_timezone(&Utc),
+        };
+        Some(at)
+    }
+
+    /// The wall-clock time here at `at`, and its offset from UTC
+    fn localize(self, at: DateTime<Utc>) -> (NaiveDateTime, FixedOffset) {
+        match self {
+            Zone::Local => {
+                let local = at.with_timezone(&Local);
+                (local.naive_local(), *local.offset())
+            }
+            Zone::Fixed(_, minutes) => {
+                let offset = FixedOffset::east_opt(minutes * 60).unwrap_or(Utc.fix());
+                (at.with_timezone(&offset).naive_local(), offset)
+            }
+            Zone::Named(tz) => {
+                let local = at.with_timezone(&tz);
+                (local.naive_local(), local.offset().fix())
+            }
+        }
+    }
+
+    /// `Tokyo (JST)`, `EST` or `local time`
+    fn label(self, at: DateTime<Utc>) -> String {
+        match self {
+            Zone::Local => "local time".to_string(),
+            Zone::Fixed(abbreviation, _) => abbreviation.to_string(),
+            Zone::Named(tz) => {
+                let city = tz.name().rsplit('/').next().unwrap_or_default();
+                let city = city.replace('_', " ");
+                let abbreviation = at.with_timezone(&tz).format("%Z").to_string();
+                if abbreviation.chars().all(|c| c.is_ascii_alphabetic()) && city != abbreviation {
+                    format!("{} ({})", city, abbreviation)
+                } else {
+                    city
+                }
+            }
+        }
+    }
+}
+
+/// A date or time worked out from a query
+#[derive(Debug, Clone)]
+pub struct DateAnswer {
+    /// What gets copied
+    pub value: String,
+    /// The conversion, for the subtitle
+    pub detail: String,
+}
+
+fn format_time(time: NaiveTime, twelve_hour: bool) -> String {
+    if twelve_hour {
+        time.format("%-I:%M %p").to_string()
+    } else {
+        time.format("%H:%M").to_string()
+    }
+}
+
+/// `3pm`, `3:30 pm`, `15:30`, `noon` or `now`, then whatever follows it;
+/// the time is None for `now`
+fn parse_time(input: &str) -> Option<(Option<NaiveTime>, bool, &str)> {
+    for (word, hour) in [("now", None), ("noon", Some(12)), ("midnight", Some(0))] {
+        if let Some(rest) = input.strip_prefix(word) {
+            if rest.is_empty() || rest.starts_with(' ') {
+                let time = hour.and_then(|h| NaiveTime::from_hms_opt(h, 0, 0));
+                return Some((time, false, rest.trim()));
+            }
+        }
+    }
+
+    let caps = TIME_RE.captures(input)?;
+    let hour: u32 = caps[1].parse().ok()?;
+    let minute: u32 = caps.get(2).map_or(Some(0), |m| m.as_str().parse().ok())?;
+    let meridiem = caps.get(3).map(|m| m.as_str());
+    // A bare number is too likely to be something else, like `15 usd in eur`
+    if meridiem.is_none() && caps.get(2).is_none() {
+        return None;
+    }
+    let hour = match meridiem {
+        Some(_) if !(1..=12).contains(&hour) => return None,
+        Some("pm") if hour < 12 => hour + 12,
+        Some("am") if hour == 12 => 0,
+        _ => hour,
+    };
+    let rest = caps.get(4).map_or("", |m| m.as_str());
+    Some((
+        NaiveTime::from_hms_opt(hour, minute, 0)?,
+        meridiem.is_some(),
+        rest,
+    ))
+}
+
+/// `3pm EST in CET`, `15:30 tokyo to london`, `noon in PST`
+fn convert_time(query: &str, now: DateTime<Utc>) -> Option<DateAnswer> {
+    let (left, right) = [" in ", " to "]
+        .iter()
+        .find_map(|separator| query.rsplit_once(separator))?;
+    let to = Zone::parse(right)?;
+    let (time, twelve_hour, from) = parse_time(left.trim())?;
+    let from = if from.is_empty() {
+        Zone::Local
+    } else {
+        Zone::parse(from)?
+    };
+
+    let (today, _) = from.localize(now);
+    let source = match time {
+        Some(time) => today.date().and_time(time),
+        None => today,
+    };
+    let at = from.to_utc(source)?;
+    let (target, _) = to.localize(at);
+
+    let value = format!(
+        "{} {}",
+        format_time(target.time(), twelve_hour),
+        to.label(at)
+    );
+    let day = match (target.date() - source.date()).num_days() {
+        1 => " the next day",
+        -1 => " the day before",
+        _ => "",
+    };
+    let detail = format!(
+        "{} {} = {}{}",
+        format_time(source.time(), twelve_hour),
+        from.label(at),
+        value,
+        day
+    );
+    Some(DateAnswer { value, detail })
+}
+
+/// `time in Tokyo`
+fn time_in(query: &str, now: DateTime<Utc>) -> Option<DateAnswer> {
+    let zone = Zone::parse(query.strip_prefix("time in ")?)?;
+    let (local, offset) = zone.localize(now);
+    Some(DateAnswer {
+        value: format!("{} {}", format_time(local.time(), false), zone.label(now)),
+        detail: format!("{} • UTC{}", local.format("%A, %B %-d"), offset),
+    })
+}
+
+/// `dec 25`, `25 december`, `dec 25 2027` or `2027-12-25`, in `year` unless
+/// it says otherwise; true if it did
+fn parse_date(input: &str, year: i32) -> Option<(NaiveDate, bool)> {
+    let input = input.replace(',', " ");
+    let input = input
+        .split_whitespace()
+        .map(|word| {
+            // `25th`
+            let digits = word.trim_end_matches(|c: char| c.is_ascii_alphabetic());
+            if !digits.is_empty() && digits.chars().all(|c| c.is_ascii_digit()) {
+                digits
+            } else {
+                word
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    if let Ok(date) = NaiveDate::parse_from_str(&input, "%Y-%m-%d") {
+        return Some((date, true));
+    }
+    for format in ["%b %d %Y", "%d %b %Y"] {
+        if let Ok(date) = NaiveDate::parse_from_str(&input, format) {
+            return Some((date, true));
+        }
+    }
+    let with_year = format!("{} {}", input, year);
+    ["%b %d %Y", "%d %b %Y"]
+        .iter()
+        .find_map(|format| NaiveDate::parse_from_str(&with_year, format).ok())
+        .map(|date| (date, false))
+}
+
+/// `days until dec 25`, `days since 2024-01-01`
+fn days_between(query: &str, today: NaiveDate) -> Option<DateAnswer> {
+    let (until, date) = ["days until ", "days till ", "days to "]
+        .iter()
+        .find_map(|prefix| query.strip_prefix(prefix))
+        .map(|date| (true, date))
+        .or_else(|| query.strip_prefix("days since ").map(|date| (false, date)))?;
+
+    let (mut date, has_year) = parse_date(date, today.year())?;
+    // Without a year, the next one coming up or the last one gone by
+    if !has_year {
+        if until && date < today {
+            date = date.with_year(date.year() + 1)?;
+        } else if !until && date > today {
+            date = date.with_year(date.year() - 1)?;
+        }
+    }
+
+    let days = if until {
+        (date - today).num_days()
+    } else {
+        (today - date).num_days()
+    };
+    let value = match days {
+        1 | -1 => format!("{} day", days),
+        _ => format!("{} days", days),
+    };
+    let mut detail = format!(
+        "{} {}",
+        if until { "Until" } else { "Since" },
+        date.format("%A, %B %-d, %Y")
+    );
+    if days.abs() >= 7 {
+        let (weeks, rest) = (days.abs() / 7, days.abs() % 7);
+        detail = format!("{} • {} weeks, {} days", detail, weeks, rest);
+    }
+    Some(DateAnswer { value, detail })
+}
+
+/// `unix 1714000000`, in seconds or milliseconds, or `unix now`
+fn unix(query: &str, now: DateTime<Utc>) -> Option<DateAnswer> {
+    let rest = ["unix", "epoch"]
+        .iter()
+        .find_map(|prefix| query.strip_prefix(prefix))?
+        .trim();
+    if rest.is_empty() || rest == "now" {
+        return Some(DateAnswer {
+            value: now.timestamp().to_string(),
+            detail: format!("Now, {}", now.format("%Y-%m-%dT%H:%M:%SZ")),
+        });
+    }
+
+    let number: i64 = rest.parse().ok()?;
+    // Millisecond timestamps have 13 digits for any date since 2001
+    let (at, unit) = if rest.trim_start_matches('-').len() >= 12 {
+        (DateTime::from_timestamp_millis(number)?, "ms")
+    } else {
+        (DateTime::from_timestamp(number, 0)?, "s")
+    };
+    let local = at.with_timezone(&Local);
+    Some(DateAnswer {
+        value: local.format("%a, %b %-d, %Y %H:%M:%S").to_string(),
+        detail: format!("{} {} = {}", number, unit, at.format("%Y-%m-%dT%H:%M:%SZ")),
+    })
+}
+
+/// Answer a date or time query, if it is one
+pub fn evaluate(query: &str) -> Option<DateAnswer> {
+    let query = query.trim().to_lowercase();
+    let now = Utc::now();
+    unix(&query, now)
+        .or_else(|| days_between(&query, Local::now().date_naive()))
+        .or_else(|| time_in(&query, now))
+        .or_else(|| convert_time(&query, now))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn answers_date_and_time_queries() {
+        let now = Utc.with_ymd_and_hms(2026, 10, 15, 12, 0, 0).unwrap();
+
+        let answer = convert_time("3pm est in cet", now).unwrap();
+        assert_eq!(answer.value, "9:00 PM CET");
+        assert_eq!(answer.detail, "3:00 PM EST = 9:00 PM CET");
+        let answer = convert_time("22:30 utc to tokyo", now).unwrap();
+        assert_eq!(answer.value, "07:30 Tokyo (JST)");
+        assert!(answer.detail.ends_with("the next day"));
+        assert!(convert_time("15 usd in eur", now).is_none());
+        assert!(convert_time("3pm est in narnia", now).is_none());
+
+        let answer = time_in("time in new york", now).unwrap();
+        assert_eq!(answer.value, "08:00 New York (EDT)");
+        assert_eq!(answer.detail, "Thursday, October 15 • UTC-04:00");
+
+        let today = NaiveDate::from_ymd_opt(2026, 10, 15).unwrap();
+        let answer = days_between("days until dec 25", today).unwrap();
+        assert_eq!(answer.value, "71 days");
+        assert!(answer.detail.starts_with("Until Friday, December 25, 2026"));
+        let answer = days_between("days until march 1st", today).unwrap();
+        assert!(answer.detail.contains("2027"));
+        let answer = days_between("days since 2026-10-14", today).unwrap();
+        assert_eq!(answer.value, "1 day");
+
+        let answer = unix("unix 1714000000", now).unwrap();
+        assert_eq!(answer.detail, "1714000000 s = 2024-04-24T23:06:40Z");
+        let answer = unix("unix 1714000000000", now).unwrap();
+        assert_eq!(answer.detail, "1714000000000 ms = 2024-04-24T23:06:40Z");
+        assert_eq!(unix("unix now", now).unwrap().value, "1792065600");
+    }
+}
@@ -0,0 +1,232 @@
+//! Devices attached to the machine, for schedules triggered by a device
+//! connecting or disconnecting: Bluetooth devices through the Bluetooth
+//! provider, USB devices (docks included, which show up as USB hubs) from
+//! sysfs, `system_profiler` or PnP.
+
+use crate::providers::bluetooth::BluetoothProvider;
+use crate::settings::{DeviceBus, DeviceMatch};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+#[cfg(any(target_os = "macos", target_os = "windows"))]
+use std::process::Command;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct ConnectedDevice {
+    pub bus: DeviceBus,
+    /// Bluetooth address, or USB `vendor:product` id in lower-case hex
+    pub id: String,
+    pub name: String,
+}
+
+impl ConnectedDevice {
+    pub fn matches(&self, pattern: &DeviceMatch) -> bool {
+        let id = pattern.id.trim();
+        let name = pattern.name.trim().to_lowercase();
+        self.bus == pattern.bus
+            && (id.is_empty() || self.id.eq_ignore_ascii_case(id))
+            && (name.is_empty() || self.name.to_lowercase().contains(&name))
+    }
+}
+
+fn usb_device(vendor_id: u16, product_id: u16, name: Option<String>) -> ConnectedDevice {
+    let id = format!("{:04x}:{:04x}", vendor_id, product_id);
+    ConnectedDevice {
+        bus: DeviceBus::Usb,
+        name: name
+            .map(|n| n.trim().to_string())
+            .filter(|n| !n.is_empty())
+            .unwrap_or_else(|| id.clone()),
+        id,
+    }
+}
+
+/// A hex id as USB tools print it: `0x046d`, `046d` or `0x046d  (Logitech Inc.)`
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+fn parse_hex_id(value: &serde_json::Value) -> Option<u16> {
+    if let Some(number) = value.as_u64() {
+        return u16::try_from(number).ok();
+    }
+    let text = value.as_str()?.split_whitespace().next()?;
+    u16::from_str_radix(text.trim_start_matches("0x"), 16).ok()
+}
+
+/// USB devices in `system_profiler SPUSBDataType -json` output, a tree of
+/// `_items` whose devices carry `_name`, `vendor_id` and `product_id`
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+fn parse_system_profiler(json: &str) -> Vec<ConnectedDevice> {
+    fn walk(value: &serde_json::Value, devices: &mut Vec<ConnectedDevice>) {
+        if let Some(items) = value.as_array() {
+            items.iter().for_each(|item| walk(item, devices));
+            return;
+        }
+        let vendor =
+            parse_hex_id(&value["vendor_id"]).or(parse_hex_id(&value["USBDeviceKeyVendorID"]));
+        let product =
+            parse_hex_id(&value["product_id"]).or(parse_hex_id(&value["USBDeviceKeyProductID"]));
+        if let (Some(vendor), Some(product)) = (vendor, product) {
+            let name = value["_name"].as_str().map(String::from);
+            devices.push(usb_device(vendor, product, name));
+        }
+        walk(&value["_items"], devices);
+    }
+
+    let Ok(report) = serde_json::from_str::<serde_json::Value>(json) else {
+        return Vec::new();
+    };
+    let mut devices = Vec::new();
+    for key in ["SPUSBDataType", "SPUSBHostDataType"] {
+        walk(&report[key], &mut devices);
+    }
+    devices
+}
+
+/// Vendor and product from a PnP instance id like `USB\VID_046D&PID_C52B\5&2a`
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+fn parse_instance_id(instance_id: &str) -> Option<(u16, u16)> {
+    let upper = instance_id.to_uppercase();
+    let field = |key: &str| {
+        let start = upper.find(key)? + key.len();
+        u16::from_str_radix(upper.get(start..start + 4)?, 16).ok()
+    };
+    Some((field("VID_")?, field("PID_")?))
+}
+
+#[cfg(target_os = "linux")]
+fn usb_devices() -> Result<Vec<ConnectedDevice>, String> {
+    let entries = std::fs::read_dir("/sys/bus/usb/devices")
+        .map_err(|e| format!("Couldn't read USB devices: {}", e))?;
+    let mut devices = Vec::new();
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let read = |file: &str| {
+            std::fs::read_to_string(path.join(file))
+                .ok()
+                .map(|s| s.trim().to_string())
+        };
+        let id = |file: &str| read(file).and_then(|s| u16::from_str_radix(&s, 16).ok());
+        // Interfaces have no ids of their own
+        let (Some(vendor), Some(product)) = (id("idVendor"), id("idProduct")) else {
+            continue;
+        };
+        // Root hubs belong to the USB controllers, not to anything plugged in
+        if vendor == 0x1d6b {
+            continue;
+        }
+        devices.push(usb_device(vendor, product, read("product")));
+    }
+    Ok(devices)
+}
+
+#[cfg(target_os = "macos")]
+fn usb_devices() -> Result<Vec<ConnectedDevice>, String> {
+    let output = Command::new("system_profiler")
+        .args(["SPUSBDataType", "-json"])
+        .output()
+        .map_err(|e| format!("Couldn't read USB devices: {}", e))?;
+    Ok(parse_system_profiler(&String::from_utf8_lossy(
+        &output.stdout,
+    )))
+}
+
+#[cfg(target_os = "windows")]
+fn usb_devices() -> Result<Vec<ConnectedDevice>, String> {
+    const SCRIPT: &str = r#"
+Get-PnpDevice -PresentOnly | Where-Object { $_.InstanceId -like 'USB\VID_*' } |
+    ForEach-Object { [pscustomobject]@{ id = $_.InstanceId; name = $_.FriendlyName } } |
+    ConvertTo-Json -Compress
+"#;
+    #[derive(Deserialize)]
+    struct PnpDevice {
+        id: String,
+        name: Option<String>,
+    }
+
+    let output = Command::new("powershell")
+        .args(["-NoProfile", "-Command", SCRIPT])
+        .output()
+        .map_err(|e| format!("Couldn't read USB devices: {}", e))?;
+    let json = String::from_utf8_lossy(&output.stdout);
+    // A single device comes back as an object rather than a list
+    let pnp: Vec<PnpDevice> = serde_json::from_str(&json)
+        .or_else(|_| serde_json::from_str(&json).map(|device| vec![device]))
+        .unwrap_or_default();
+    Ok(pnp
+        .into_iter()
+        .filter_map(|device| {
+            let (vendor, product) = parse_instance_id(&device.id)?;
+            Some(usb_device(vendor, product, device.name))
+        })
+        .collect())
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+fn usb_devices() -> Result<Vec<ConnectedDevice>, String> {
+    Err("USB devices can't be listed on this platform".to_string())
+}
+
+/// Devices connected now on each of `buses`. A bus that can't be read is
+/// left out rather than reported empty, so it doesn't look like everything
+/// was unplugged.
+pub fn connected(
+    buses: &HashSet<DeviceBus>,
+    bluetooth: &BluetoothProvider,
+) -> HashMap<DeviceBus, HashSet<ConnectedDevice>> {
+    let mut connected = HashMap::new();
+    for bus in buses {
+        let devices = match bus {
+            DeviceBus::Usb => usb_devices(),
+            DeviceBus::Bluetooth => bluetooth.devices(true).map(|devices| {
+                devices
+                    .into_iter()
+                    .filter(|d| d.connected)
+                    .map(|d| ConnectedDevice {
+                        bus: DeviceBus::Bluetooth,
+                        id: d.address,
+                        name: d.name,
+                    })
+                    .collect()
+            }),
+        };
+        match devices {
+            Ok(devices) => {
+                connected.insert(*bus, devices.into_iter().collect());
+            }
+            Err(e) => eprintln!("Failed to read {:?} devices: {}", bus, e),
+        }
+    }
+    connected
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_usb_ids() {
+        let json = r#"{"SPUSBDataType": [{"_name": "USB31Bus", "_items": [
+            {"_name": "CalDigit TS4", "vendor_id": "0x2188", "product_id": "0x0747",
+             "_items": [{"_name": "USB Receiver", "vendor_id": "0x046d  (Logitech Inc.)",
+                         "product_id": "0xc52b"}]}
+        ]}]}"#;
+        let devices = parse_system_profiler(json);
+        assert_eq!(
+            devices,
+            vec![
+                usb_device(0x2188, 0x0747, Some("CalDigit TS4".to_string())),
+                usb_device(0x046d, 0xc52b, Some("USB Receiver".to_string())),
+            ]
+        );
+        assert_eq!(devices[1].id, "046d:c52b");
+        assert!(devices[0].matches(&DeviceMatch {
+            bus: DeviceBus::Usb,
+            id: String::new(),
+            name: "caldigit".to_string(),
+        }));
+
+        assert_eq!(
+            parse_instance_id(r"USB\VID_046D&PID_C52B\5&2A1B"),
+            Some((0x046d, 0xc52b))
+        );
+        assert_eq!(parse_instance_id(r"HID\CONVERTEDDEVICE"), None);
+    }
+}
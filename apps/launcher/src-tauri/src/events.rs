@@ -0,0 +1,175 @@
+//! Typed events between backend subsystems. A subsystem publishes an
+//! [`AppEvent`] on the [`EventBus`] instead of emitting a Tauri event by
+//! name; other subsystems subscribe to react to it without knowing who sent
+//! it, and every event is forwarded to the frontend under its kebab-case
+//! name (`AppEvent::PluginReloaded` as `plugin-reloaded`).
+//!
+//! Replies to the frontend's own requests, like late search results,
+//! progress updates and confirmation prompts, are still emitted directly.
+
+use crate::focus::FocusStatus;
+use crate::latency::{ProviderSlow, SearchTiming};
+use crate::plugins::health::PluginHealthReport;
+use crate::plugins::runtime::PluginReloaded;
+use crate::power::PowerStatus;
+use crate::providers::capture::CaptureSaved;
+use crate::providers::packages::PackageUpdate;
+use crate::providers::vpn::VpnStatus;
+use crate::reminders::Reminder;
+use crate::scheduler::ScheduleRun;
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tauri::{AppHandle, Emitter};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IndexingStatus {
+    pub is_indexing: bool,
+    pub files_indexed: usize,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "event", content = "payload", rename_all = "kebab-case")]
+pub enum AppEvent {
+    /// File indexing started, paused, finished or failed
+    IndexingStatus(IndexingStatus),
+    /// How long a search and each of its providers took
+    SearchTiming(SearchTiming),
+    /// A provider kept missing its latency budget
+    ProviderSlow(ProviderSlow),
+    /// Installed apps were rescanned; how many there are now
+    AppsUpdated(usize),
+    /// A plugin was reloaded from disk in dev mode
+    PluginReloaded(PluginReloaded),
+    /// A plugin kept failing and was disabled
+    PluginQuarantined(PluginHealthReport),
+    /// A deep link asked to install a plugin, by id
+    InstallPlugin(String),
+    MarketplaceUpdated,
+    MarketplaceIconsUpdated,
+    /// A token from a `launcher://auth/callback` link
+    AuthCallback(String),
+    /// An account's token was refreshed, by OAuth provider id
+    OauthTokenRefreshed(String),
+    /// Feeds were refreshed; how many new items came in
+    FeedsUpdated(usize),
+    PackageStatusChanged(Vec<PackageUpdate>),
+    PowerStateChanged(PowerStatus),
+    /// The window switcher's list changed while it's showing
+    WindowsChanged,
+    FocusChanged(FocusStatus),
+    ReminderDue(Reminder),
+    VpnStatusChanged(VpnStatus),
+    ClipboardHistoryUpdated,
+    ScheduleRan(ScheduleRun),
+    /// A screenshot or recording taken from search was saved
+    CaptureSaved(CaptureSaved),
+}
+
+impl AppEvent {
+    /// The frontend event's name and payload
+    fn to_frontend(&self) -> Result<(String, serde_json::Value), String> {
+        let mut value = serde_json::to_value(self).map_err(|e| e.to_string())?;
+        let name = value
+            .get("event")
+            .and_then(|name| name.as_str())
+            .ok_or("Event has no name")?
+            .to_string();
+        let payload = value
+            .get_mut("payload")
+            .map(serde_json::Value::take)
+            .unwrap_or_default();
+        Ok((name, payload))
+    }
+}
+
+type Subscriber = Arc<dyn Fn(&AppEvent) + Send + Sync>;
+
+pub struct EventBus {
+    app: RwLock<Option<AppHandle>>,
+    subscribers: RwLock<Vec<Subscriber>>,
+}
+
+impl EventBus {
+    pub fn new() -> Self {
+        Self {
+            app: RwLock::new(None),
+            subscribers: RwLock::new(Vec::new()),
+        }
+    }
+
+    /// Forward events to the frontend from now on; before this, only
+    /// subscribers see them
+    pub fn attach(&self, app: AppHandle) {
+        *self.app.write() = Some(app);
+    }
+
+    /// Call `subscriber` with every event published from now on, on the
+    /// publisher's thread
+    pub fn subscribe(&self, subscriber: impl Fn(&AppEvent) + Send + Sync + 'static) {
+        self.subscribers.write().push(Arc::new(subscriber));
+    }
+
+    pub fn publish(&self, event: AppEvent) {
+        // Cloned, so subscribers may publish or subscribe themselves
+        let subscribers = self.subscribers.read().clone();
+        for subscriber in subscribers {
+            subscriber(&event);
+        }
+
+        let Some(app) = self.app.read().clone() else {
+            return;
+        };
+        match event.to_frontend() {
+            Ok((name, payload)) => {
+                let _ = app.emit(&name, payload);
+            }
+            Err(e) => eprintln!("Failed to send {:?} to the frontend: {}", event, e),
+        }
+    }
+}
+
+impl Default for EventBus {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use parking_lot::Mutex;
+
+    #[test]
+    fn names_events_and_notifies_subscribers() {
+        let (name, payload) = AppEvent::PluginReloaded(PluginReloaded {
+            plugin_id: "weather".to_string(),
+            error: None,
+        })
+        .to_frontend()
+        .unwrap();
+        assert_eq!(name, "plugin-reloaded");
+        assert_eq!(payload["plugin_id"], "weather");
+
+        let (name, payload) = AppEvent::MarketplaceUpdated.to_frontend().unwrap();
+        assert_eq!(name, "marketplace-updated");
+        assert!(payload.is_null());
+        let (name, _) = AppEvent::OauthTokenRefreshed("github".to_string())
+            .to_frontend()
+            .unwrap();
+        assert_eq!(name, "oauth-token-refreshed");
+
+        let bus = EventBus::new();
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let sink = seen.clone();
+        bus.subscribe(move |event| {
+            if let AppEvent::AppsUpdated(count) = event {
+                sink.lock().push(*count);
+            }
+        });
+        bus.publish(AppEvent::AppsUpdated(42));
+        bus.publish(AppEvent::MarketplaceUpdated);
+        assert_eq!(*seen.lock(), vec![42]);
+    }
+}
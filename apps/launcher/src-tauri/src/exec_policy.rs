@@ -0,0 +1,184 @@
+//! Gate for shell commands that results ask to run, like a plugin result's
+//! `run_command` action. Plugin commands always wait for the user to
+//! confirm them, which is what keeps them in check; the allow and deny
+//! patterns in `command_policy` only narrow what can be asked for. Every
+//! decision goes to the audit log.
+
+use crate::audit::{self, AuditEvent, AuditOutcome};
+use crate::settings::{CommandPolicy, SettingsStore};
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use std::process::Command;
+use std::sync::Arc;
+
+const AUDIT_ACTION: &str = "run_command";
+
+/// A command waiting for the user's confirmation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommandRequest {
+    pub plugin_id: String,
+    pub command: String,
+}
+
+impl CommandRequest {
+    fn audit(&self, outcome: AuditOutcome) -> AuditEvent {
+        let actor = format!("plugin:{}", self.plugin_id);
+        AuditEvent::new(&actor, AUDIT_ACTION, &self.command, outcome)
+    }
+}
+
+type ConfirmListener = Arc<dyn Fn(CommandRequest) + Send + Sync>;
+
+/// Whether `pattern` matches all of `text`, `*` matching any run of
+/// characters
+fn wildcard_match(pattern: &str, text: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or_default();
+    let Some(mut rest) = text.strip_prefix(first) else {
+        return false;
+    };
+    let parts: Vec<&str> = parts.collect();
+    let Some((last, middle)) = parts.split_last() else {
+        // No wildcard: the prefix had to be everything
+        return rest.is_empty();
+    };
+    for part in middle {
+        match rest.find(part) {
+            Some(i) => rest = &rest[i + part.len()..],
+            None => return false,
+        }
+    }
+    rest.ends_with(last)
+}
+
+/// Collapse runs of whitespace, so spacing doesn't change which patterns
+/// match
+fn normalize(command: &str) -> String {
+    command.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Why `command` may not run under `policy`, if it may not
+pub fn check(policy: &CommandPolicy, command: &str) -> Result<(), String> {
+    let command = normalize(command);
+    if command.is_empty() {
+        return Err("The command is empty".to_string());
+    }
+    let matching = |patterns: &[String]| {
+        patterns
+            .iter()
+            .find(|p| wildcard_match(&normalize(p), &command))
+            .cloned()
+    };
+    if let Some(pattern) = matching(&policy.deny) {
+        return Err(format!("Blocked by the command policy ({})", pattern));
+    }
+    if !policy.allow.is_empty() && matching(&policy.allow).is_none() {
+        return Err("Not in the command policy's allow list".to_string());
+    }
+    Ok(())
+}
+
+/// Start `command` in the platform's shell without waiting for it
+fn spawn(command: &str) -> Result<(), String> {
+    #[cfg(target_os = "windows")]
+    let mut shell = {
+        let mut shell = Command::new("cmd");
+        shell.args(["/C", command]);
+        shell
+    };
+    #[cfg(not(target_os = "windows"))]
+    let mut shell = {
+        let mut shell = Command::new("sh");
+        shell.args(["-c", command]);
+        shell
+    };
+    shell
+        .spawn()
+        .map(|_| ())
+        .map_err(|e| format!("Failed to run command: {}", e))
+}
+
+pub struct ExecPolicy {
+    settings: Arc<SettingsStore>,
+    listener: RwLock<Option<ConfirmListener>>,
+}
+
+impl ExecPolicy {
+    pub fn new(settings: Arc<SettingsStore>) -> Self {
+        Self {
+            settings,
+            listener: RwLock::new(None),
+        }
+    }
+
+    /// Asks the user about commands that passed the policy; without one
+    /// plugin commands can't run
+    pub fn set_confirm_listener(&self, listener: impl Fn(CommandRequest) + Send + Sync + 'static) {
+        *self.listener.write() = Some(Arc::new(listener));
+    }
+
+    fn check(&self, request: &CommandRequest) -> Result<(), String> {
+        check(&self.settings.get().command_policy, &request.command).map_err(|reason| {
+            audit::record(request.audit(AuditOutcome::Denied).with_detail(&reason));
+            reason
+        })
+    }
+
+    /// A plugin asks to run `command`: refused right away if the policy
+    /// denies it, otherwise passed on for the user to confirm
+    pub fn request(&self, plugin_id: &str, command: &str) -> Result<(), String> {
+        let request = CommandRequest {
+            plugin_id: plugin_id.to_string(),
+            command: command.to_string(),
+        };
+        self.check(&request)?;
+        let listener = self.listener.read().clone();
+        let listener = listener.ok_or("Command confirmation is not available")?;
+        listener(request);
+        Ok(())
+    }
+
+    /// The user's answer to a request; runs the command if they agreed and
+    /// the policy, which may have changed in the meantime, still allows it
+    pub fn answer(&self, request: CommandRequest, allowed: bool) -> Result<(), String> {
+        if !allowed {
+            audit::record(request.audit(AuditOutcome::Declined));
+            return Ok(());
+        }
+        self.check(&request)?;
+        match spawn(&request.command) {
+            Ok(()) => {
+                audit::record(request.audit(AuditOutcome::Allowed));
+                Ok(())
+            }
+            Err(e) => {
+                audit::record(request.audit(AuditOutcome::Failed).with_detail(&e));
+                Err(e)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn applies_allow_and_deny_patterns() {
+        assert!(wildcard_match("git *", "git status"));
+        assert!(wildcard_match("*| sh", "curl example.com | sh"));
+        assert!(wildcard_match("make", "make"));
+        assert!(!wildcard_match("make", "make install"));
+        assert!(!wildcard_match("git *", "gh pr list"));
+
+        let mut policy = CommandPolicy::default();
+        assert!(check(&policy, "open -a Safari").is_ok());
+        assert!(check(&policy, "sudo  reboot").is_err());
+        assert!(check(&policy, "rm   -rf /").is_err());
+        assert!(check(&policy, "   ").is_err());
+
+        policy.allow = vec!["git *".to_string(), "open *".to_string()];
+        assert!(check(&policy, "git pull").is_ok());
+        assert!(check(&policy, "npm install").is_err());
+    }
+}
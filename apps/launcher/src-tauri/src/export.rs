@@ -0,0 +1,171 @@
+//! Search results written to a file as CSV, JSON or Markdown, for auditing
+//! what's indexed, taking an inventory of installed apps, or handing
+//! results to other tools.
+
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    Csv,
+    Json,
+    Markdown,
+}
+
+/// One result as exported
+#[derive(Debug, Clone, Serialize)]
+pub struct ExportedResult {
+    /// 1 for the top result
+    pub rank: usize,
+    pub id: String,
+    pub title: String,
+    pub subtitle: Option<String>,
+    /// Spoken category name, e.g. "File"
+    pub kind: String,
+    /// Id of the provider that found it
+    pub provider: Option<String>,
+    /// The file or folder on disk it stands for
+    pub path: Option<String>,
+    pub score: f32,
+}
+
+const COLUMNS: [&str; 8] = [
+    "rank", "id", "title", "subtitle", "kind", "provider", "path", "score",
+];
+
+impl ExportedResult {
+    fn fields(&self) -> [String; 8] {
+        [
+            self.rank.to_string(),
+            self.id.clone(),
+            self.title.clone(),
+            self.subtitle.clone().unwrap_or_default(),
+            self.kind.clone(),
+            self.provider.clone().unwrap_or_default(),
+            self.path.clone().unwrap_or_default(),
+            format!("{:.2}", self.score),
+        ]
+    }
+}
+
+/// Quoted when it has to be, per RFC 4180
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn markdown_cell(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('|', "\\|")
+        .replace(['\r', '\n'], " ")
+}
+
+fn csv(results: &[ExportedResult]) -> String {
+    let mut out = COLUMNS.join(",");
+    out.push_str("\r\n");
+    for result in results {
+        let fields: Vec<String> = result
+            .fields()
+            .iter()
+            .map(String::as_str)
+            .map(csv_field)
+            .collect();
+        out.push_str(&fields.join(","));
+        out.push_str("\r\n");
+    }
+    out
+}
+
+fn markdown(query: &str, results: &[ExportedResult]) -> String {
+    let mut out = format!(
+        "# Results for \"{}\"\n\n{} results\n\n",
+        markdown_cell(query),
+        results.len()
+    );
+    out.push_str(&format!("| {} |\n", COLUMNS.join(" | ")));
+    out.push_str(&format!("|{}\n", "---|".repeat(COLUMNS.len())));
+    for result in results {
+        let cells: Vec<String> = result
+            .fields()
+            .iter()
+            .map(String::as_str)
+            .map(markdown_cell)
+            .collect();
+        out.push_str(&format!("| {} |\n", cells.join(" | ")));
+    }
+    out
+}
+
+pub fn render(
+    query: &str,
+    results: &[ExportedResult],
+    format: ExportFormat,
+) -> Result<String, String> {
+    match format {
+        ExportFormat::Csv => Ok(csv(results)),
+        ExportFormat::Json => serde_json::to_string_pretty(&serde_json::json!({
+            "query": query,
+            "results": results,
+        }))
+        .map_err(|e| format!("Failed to serialize results: {}", e)),
+        ExportFormat::Markdown => Ok(markdown(query, results)),
+    }
+}
+
+/// Write the export to `path`, replacing any file there
+pub fn write(path: &Path, contents: &str) -> Result<(), String> {
+    if path.as_os_str().is_empty() {
+        return Err("No file to export to".to_string());
+    }
+    if path.is_dir() {
+        return Err(format!("{} is a folder", path.display()));
+    }
+    std::fs::write(path, contents).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn escapes_csv_and_markdown() {
+        let results = vec![ExportedResult {
+            rank: 1,
+            id: "file:/tmp/a,b.txt".to_string(),
+            title: "a,b \"draft\".txt".to_string(),
+            subtitle: Some("left | right".to_string()),
+            kind: "File".to_string(),
+            provider: Some("files".to_string()),
+            path: Some("/tmp/a,b.txt".to_string()),
+            score: 12.5,
+        }];
+
+        let csv = render("a,b", &results, ExportFormat::Csv).unwrap();
+        let mut lines = csv.lines();
+        assert_eq!(
+            lines.next(),
+            Some("rank,id,title,subtitle,kind,provider,path,score")
+        );
+        assert_eq!(
+            lines.next(),
+            Some(
+                "1,\"file:/tmp/a,b.txt\",\"a,b \"\"draft\"\".txt\",left | right,File,files,\
+                 \"/tmp/a,b.txt\",12.50"
+            )
+        );
+
+        let markdown = render("a|b", &results, ExportFormat::Markdown).unwrap();
+        assert!(markdown.starts_with("# Results for \"a\\|b\""));
+        assert!(markdown.contains("| left \\| right |"));
+
+        let json: serde_json::Value =
+            serde_json::from_str(&render("a,b", &results, ExportFormat::Json).unwrap()).unwrap();
+        assert_eq!(json["results"][0]["provider"], "files");
+        assert_eq!(json["query"], "a,b");
+    }
+}
@@ -0,0 +1,615 @@
+use serde::{Deserialize, Serialize};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+/// Progress of a long-running file operation, emitted as "file-op-progress"
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileOpProgress {
+    pub operation_id: String,
+    pub operation: String,
+    pub processed: usize,
+    pub total: usize,
+    pub current_path: Option<String>,
+    pub done: bool,
+}
+
+/// Archive formats that can be extracted
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArchiveKind {
+    Zip,
+    Tar,
+}
+
+impl ArchiveKind {
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let name = path.file_name()?.to_str()?.to_lowercase();
+        if name.ends_with(".zip") {
+            Some(ArchiveKind::Zip)
+        } else if [".tar", ".tar.gz", ".tgz", ".tar.bz2", ".tar.xz"]
+            .iter()
+            .any(|ext| name.ends_with(ext))
+        {
+            Some(ArchiveKind::Tar)
+        } else {
+            None
+        }
+    }
+}
+
+/// Archive file name without its (possibly double) extension
+fn archive_stem(path: &Path) -> String {
+    let name = path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or("archive");
+    let lower = name.to_lowercase();
+
+    for ext in [".tar.gz", ".tar.bz2", ".tar.xz", ".tgz", ".tar", ".zip"] {
+        if lower.ends_with(ext) {
+            return name[..name.len() - ext.len()].to_string();
+        }
+    }
+    name.to_string()
+}
+
+/// Return `path`, or `name (2).ext`, `name (3).ext`, ... if it already exists
+pub fn unique_path(path: &Path) -> PathBuf {
+    if !path.exists() {
+        return path.to_path_buf();
+    }
+
+    let parent = path.parent().unwrap_or_else(|| Path::new("."));
+    let stem = path
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("file")
+        .to_string();
+    let ext = path.extension().and_then(|e| e.to_str());
+
+    (2..)
+        .map(|n| {
+            let name = match ext {
+                Some(ext) => format!("{} ({}).{}", stem, n, ext),
+                None => format!("{} ({})", stem, n),
+            };
+            parent.join(name)
+        })
+        .find(|candidate| !candidate.exists())
+        .unwrap()
+}
+
+/// Default archive path for compressing `paths`: next to the first item,
+/// named after it when there is one item and "Archive.zip" otherwise
+pub fn default_zip_path(paths: &[PathBuf]) -> Result<PathBuf, String> {
+    let first = paths.first().ok_or("Nothing to compress")?;
+    let parent = first.parent().unwrap_or_else(|| Path::new("."));
+
+    let name = if paths.len() == 1 {
+        let stem = first
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or("Archive");
+        format!("{}.zip", stem)
+    } else {
+        "Archive.zip".to_string()
+    };
+
+    Ok(unique_path(&parent.join(name)))
+}
+
+/// Collect every file and directory under `root`, relative to `base`.
+/// Symlinks are skipped, so a link can't pull in files from elsewhere or
+/// loop forever.
+fn collect_entries(root: &Path, base: &Path, entries: &mut Vec<(PathBuf, String)>) {
+    let Ok(metadata) = std::fs::symlink_metadata(root) else {
+        return;
+    };
+    if metadata.file_type().is_symlink() {
+        return;
+    }
+
+    let relative = root
+        .strip_prefix(base)
+        .unwrap_or(root)
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy().to_string())
+        .collect::<Vec<_>>()
+        .join("/");
+
+    if metadata.is_dir() {
+        entries.push((root.to_path_buf(), format!("{}/", relative)));
+        if let Ok(children) = std::fs::read_dir(root) {
+            let mut children: Vec<PathBuf> = children.flatten().map(|e| e.path()).collect();
+            children.sort();
+            for child in children {
+                collect_entries(&child, base, entries);
+            }
+        }
+    } else {
+        entries.push((root.to_path_buf(), relative));
+    }
+}
+
+/// Compress files and folders into a zip archive at `dest`.
+/// `on_progress` is called with (processed, total, current path).
+pub fn compress_to_zip(
+    paths: &[PathBuf],
+    dest: &Path,
+    mut on_progress: impl FnMut(usize, usize, &Path),
+) -> Result<PathBuf, String> {
+    let mut entries = Vec::new();
+    for path in paths {
+        if !path.exists() {
+            return Err(format!("Path not found: {}", path.display()));
+        }
+        let base = path.parent().unwrap_or_else(|| Path::new(""));
+        collect_entries(path, base, &mut entries);
+    }
+
+    let file = std::fs::File::create(dest)
+        .map_err(|e| format!("Failed to create archive: {}", e))?;
+    if let Err(e) = write_zip(file, &entries, &mut on_progress) {
+        // Don't leave a broken archive behind
+        let _ = std::fs::remove_file(dest);
+        return Err(e);
+    }
+    on_progress(entries.len(), entries.len(), dest);
+
+    Ok(dest.to_path_buf())
+}
+
+/// Write collected entries to a zip, streaming each file
+fn write_zip(
+    file: std::fs::File,
+    entries: &[(PathBuf, String)],
+    on_progress: &mut impl FnMut(usize, usize, &Path),
+) -> Result<(), String> {
+    let mut zip = zip::ZipWriter::new(file);
+    let options = zip::write::SimpleFileOptions::default()
+        .compression_method(zip::CompressionMethod::Deflated)
+        .large_file(true);
+
+    let total = entries.len();
+    for (idx, (path, name)) in entries.iter().enumerate() {
+        on_progress(idx, total, path);
+
+        if name.ends_with('/') {
+            zip.add_directory(name.as_str(), options)
+                .map_err(|e| format!("Failed to add {}: {}", name, e))?;
+            continue;
+        }
+
+        let mut source = std::fs::File::open(path)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        zip.start_file(name.as_str(), options)
+            .map_err(|e| format!("Failed to add {}: {}", name, e))?;
+        std::io::copy(&mut source, &mut zip)
+            .map_err(|e| format!("Failed to write {}: {}", name, e))?;
+    }
+
+    zip.finish()
+        .map_err(|e| format!("Failed to finalize archive: {}", e))?;
+    Ok(())
+}
+
+/// Largest total size a zip may extract to
+const MAX_EXTRACTED_BYTES: u64 = 16 * 1024 * 1024 * 1024;
+
+/// Most entries a zip may extract
+const MAX_EXTRACTED_ENTRIES: usize = 100_000;
+
+/// Extract an archive into `dest`, or into a new folder next to the archive
+/// when `dest` is `None`. Returns the folder the contents were written to.
+///
+/// Files already in `dest` are only replaced when `overwrite` is set. Without
+/// it a zip that would replace one is refused before anything is written, and
+/// tar is told to keep the existing files.
+pub fn extract_archive(
+    archive: &Path,
+    dest: Option<&Path>,
+    overwrite: bool,
+    mut on_progress: impl FnMut(usize, usize, &Path),
+) -> Result<PathBuf, String> {
+    let kind = ArchiveKind::from_path(archive)
+        .ok_or_else(|| format!("Unsupported archive: {}", archive.display()))?;
+
+    let dest = match dest {
+        Some(dest) => dest.to_path_buf(),
+        None => {
+            let parent = archive.parent().unwrap_or_else(|| Path::new("."));
+            unique_path(&parent.join(archive_stem(archive)))
+        }
+    };
+    std::fs::create_dir_all(&dest)
+        .map_err(|e| format!("Failed to create destination folder: {}", e))?;
+
+    match kind {
+        ArchiveKind::Zip => extract_zip(archive, &dest, overwrite, &mut on_progress)?,
+        ArchiveKind::Tar => {
+            on_progress(0, 1, archive);
+            // bsdtar/GNU tar ship with Linux, macOS and Windows 10+
+            let mut tar = std::process::Command::new("tar");
+            tar.arg("-xf").arg(archive);
+            if !overwrite {
+                tar.arg("-k");
+            }
+            let output = tar
+                .arg("-C")
+                .arg(&dest)
+                .output()
+                .map_err(|e| format!("Failed to run tar: {}", e))?;
+            if !output.status.success() {
+                return Err(format!(
+                    "Failed to extract archive: {}",
+                    String::from_utf8_lossy(&output.stderr).trim()
+                ));
+            }
+            on_progress(1, 1, &dest);
+        }
+    }
+
+    Ok(dest)
+}
+
+/// Extract a zip into `dest`. Every entry is checked before anything is
+/// written: the entry count and total size are capped and, unless
+/// `overwrite` is set, no existing file may be replaced. Sizes are checked
+/// again while writing, since the sizes in a zip's directory can lie.
+fn extract_zip(
+    archive: &Path,
+    dest: &Path,
+    overwrite: bool,
+    on_progress: &mut impl FnMut(usize, usize, &Path),
+) -> Result<(), String> {
+    let file =
+        std::fs::File::open(archive).map_err(|e| format!("Failed to open archive: {}", e))?;
+    let mut zip =
+        zip::ZipArchive::new(file).map_err(|e| format!("Failed to read archive: {}", e))?;
+
+    let total = zip.len();
+    if total > MAX_EXTRACTED_ENTRIES {
+        return Err(format!(
+            "Archive has more than {} files",
+            MAX_EXTRACTED_ENTRIES
+        ));
+    }
+
+    let mut size: u64 = 0;
+    for idx in 0..total {
+        let entry = zip
+            .by_index(idx)
+            .map_err(|e| format!("Failed to read archive entry: {}", e))?;
+        size = size.saturating_add(entry.size());
+        let Some(relative) = entry.enclosed_name() else {
+            continue;
+        };
+        let out_path = dest.join(relative);
+        if !overwrite && !entry.is_dir() && out_path.exists() {
+            return Err(format!("{} already exists", out_path.display()));
+        }
+    }
+    let too_large = || {
+        format!(
+            "Archive extracts to more than {} GB",
+            MAX_EXTRACTED_BYTES / 1024 / 1024 / 1024
+        )
+    };
+    if size > MAX_EXTRACTED_BYTES {
+        return Err(too_large());
+    }
+
+    let mut written: u64 = 0;
+    for idx in 0..total {
+        let entry = zip
+            .by_index(idx)
+            .map_err(|e| format!("Failed to read archive entry: {}", e))?;
+
+        // Skip entries that would escape the destination folder
+        let relative = match entry.enclosed_name() {
+            Some(name) => name,
+            None => continue,
+        };
+        let out_path = dest.join(relative);
+        on_progress(idx, total, &out_path);
+
+        if entry.is_dir() {
+            std::fs::create_dir_all(&out_path)
+                .map_err(|e| format!("Failed to create {}: {}", out_path.display(), e))?;
+            continue;
+        }
+
+        if let Some(parent) = out_path.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+        }
+        let mut out = std::fs::File::create(&out_path)
+            .map_err(|e| format!("Failed to create {}: {}", out_path.display(), e))?;
+        let budget = MAX_EXTRACTED_BYTES - written;
+        let copied = std::io::copy(&mut entry.take(budget + 1), &mut out)
+            .map_err(|e| format!("Failed to extract {}: {}", out_path.display(), e))?;
+        if copied > budget {
+            return Err(too_large());
+        }
+        written += copied;
+    }
+
+    on_progress(total, total, dest);
+    Ok(())
+}
+
+/// How to compute new names in a bulk rename
+///
+/// Parsed from the text after `rename:`:
+/// - `s/find/replace/` (or `s/find/replace/i`) applies a regex to the file name
+/// - anything else is a template using `{name}`, `{ext}` and `{n}`
+#[derive(Debug, Clone)]
+pub enum RenameRule {
+    Template(String),
+    Regex { find: regex::Regex, replace: String },
+}
+
+impl RenameRule {
+    pub fn parse(input: &str) -> Result<Self, String> {
+        let input = input.trim();
+        if input.is_empty() {
+            return Err("Rename pattern is empty".to_string());
+        }
+
+        if let Some(rest) = input.strip_prefix("s/") {
+            let parts: Vec<&str> = rest.splitn(3, '/').collect();
+            if parts.len() < 2 {
+                return Err("Expected s/find/replace/".to_string());
+            }
+            let flags = parts.get(2).copied().unwrap_or("");
+            let find = regex::RegexBuilder::new(parts[0])
+                .case_insensitive(flags.contains('i'))
+                .build()
+                .map_err(|e| format!("Invalid regex: {}", e))?;
+            return Ok(RenameRule::Regex {
+                find,
+                replace: parts[1].to_string(),
+            });
+        }
+
+        Ok(RenameRule::Template(input.to_string()))
+    }
+
+    /// New file name for the `index`-th (0-based) selected path
+    fn apply(&self, path: &Path, index: usize) -> String {
+        let file_name = path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+
+        match self {
+            RenameRule::Regex { find, replace } => {
+                find.replace_all(&file_name, replace.as_str()).to_string()
+            }
+            RenameRule::Template(template) => {
+                let stem = path
+                    .file_stem()
+                    .map(|s| s.to_string_lossy().to_string())
+                    .unwrap_or_default();
+                let ext = path
+                    .extension()
+                    .map(|e| e.to_string_lossy().to_string())
+                    .unwrap_or_default();
+
+                let name = template
+                    .replace("{name}", &stem)
+                    .replace("{ext}", &ext)
+                    .replace("{n}", &(index + 1).to_string());
+
+                // "{name}.{ext}" on a file without extension shouldn't leave a trailing dot
+                name.strip_suffix('.').map(str::to_string).unwrap_or(name)
+            }
+        }
+    }
+}
+
+/// One planned rename, returned to the UI as a dry-run preview
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RenamePreview {
+    pub from: String,
+    pub to: String,
+    /// Why this rename can't be applied, if it can't
+    pub conflict: Option<String>,
+}
+
+/// Whether `a` and `b` differ only in case, which on a case-insensitive disk
+/// makes `b` exist because `a` does
+fn same_name_ignoring_case(a: &Path, b: &Path) -> bool {
+    a.parent() == b.parent()
+        && a.to_string_lossy().to_lowercase() == b.to_string_lossy().to_lowercase()
+}
+
+/// Compute the new names for `paths` without touching the filesystem
+pub fn preview_rename(paths: &[PathBuf], rule: &RenameRule) -> Vec<RenamePreview> {
+    // Case-folded, as names differing only in case collide on case-insensitive disks
+    let mut targets = std::collections::HashSet::new();
+
+    paths
+        .iter()
+        .enumerate()
+        .map(|(idx, path)| {
+            let new_name = rule.apply(path, idx);
+            let target = path
+                .parent()
+                .unwrap_or_else(|| Path::new(""))
+                .join(&new_name);
+
+            let conflict = if !path.exists() {
+                Some("File no longer exists".to_string())
+            } else if new_name.is_empty() || new_name.contains(['/', '\\']) {
+                Some("Invalid file name".to_string())
+            } else if !targets.insert(target.to_string_lossy().to_lowercase()) {
+                Some("Another file is renamed to the same name".to_string())
+            } else if target != *path
+                && target.exists()
+                && !paths.contains(&target)
+                && !same_name_ignoring_case(path, &target)
+            {
+                Some("A file with this name already exists".to_string())
+            } else {
+                None
+            };
+
+            RenamePreview {
+                from: path.to_string_lossy().to_string(),
+                to: target.to_string_lossy().to_string(),
+                conflict,
+            }
+        })
+        .collect()
+}
+
+/// Apply a bulk rename. Nothing is renamed if any entry has a conflict.
+pub fn apply_rename(paths: &[PathBuf], rule: &RenameRule) -> Result<Vec<RenamePreview>, String> {
+    let plan = preview_rename(paths, rule);
+    if let Some(entry) = plan.iter().find(|p| p.conflict.is_some()) {
+        return Err(format!(
+            "Cannot rename {}: {}",
+            entry.from,
+            entry.conflict.as_deref().unwrap_or_default()
+        ));
+    }
+
+    let renames: Vec<(PathBuf, PathBuf)> = plan
+        .iter()
+        .filter(|p| p.from != p.to)
+        .map(|p| (PathBuf::from(&p.from), PathBuf::from(&p.to)))
+        .collect();
+
+    // Move through temporary names first so swaps like a->b, b->a work
+    let mut staged = Vec::with_capacity(renames.len());
+    for (idx, (from, to)) in renames.iter().enumerate() {
+        let temp = from.with_file_name(format!(".launcher-rename-{}-{}", std::process::id(), idx));
+        if let Err(e) = std::fs::rename(from, &temp) {
+            roll_back_rename(&staged, 0);
+            return Err(format!("Failed to rename {}: {}", from.display(), e));
+        }
+        staged.push((from, temp, to));
+    }
+    for (done, (_, temp, to)) in staged.iter().enumerate() {
+        if let Err(e) = std::fs::rename(temp, to) {
+            roll_back_rename(&staged, done);
+            return Err(format!("Failed to rename to {}: {}", to.display(), e));
+        }
+    }
+
+    Ok(plan)
+}
+
+/// Put every file of a failed bulk rename back under its old name; the
+/// first `done` had already reached their new names
+fn roll_back_rename(staged: &[(&PathBuf, PathBuf, &PathBuf)], done: usize) {
+    for (_, temp, to) in &staged[..done] {
+        let _ = std::fs::rename(to, temp);
+    }
+    for (from, temp, _) in staged {
+        if let Err(e) = std::fs::rename(temp, from) {
+            eprintln!("Failed to restore {}: {}", from.display(), e);
+        }
+    }
+}
+
+/// Give one file or folder a new name in the same folder, e.g. from an
+/// inline edit. Returns the new path.
+pub fn rename_in_place(path: &Path, new_name: &str) -> Result<PathBuf, String> {
+    let new_name = new_name.trim();
+    if new_name.is_empty() || new_name == "." || new_name == ".." || new_name.contains(['/', '\\'])
+    {
+        return Err("Invalid file name".to_string());
+    }
+    if !path.exists() {
+        return Err("File no longer exists".to_string());
+    }
+    let target = path.with_file_name(new_name);
+    // On a case-insensitive disk, a change of case finds the file itself
+    let case_only = path
+        .file_name()
+        .is_some_and(|name| name.to_string_lossy().to_lowercase() == new_name.to_lowercase());
+    if target.exists() && !case_only {
+        return Err("A file with this name already exists".to_string());
+    }
+    std::fs::rename(path, &target)
+        .map_err(|e| format!("Failed to rename {}: {}", path.display(), e))?;
+    Ok(target)
+}
+
+/// Moves `argv[0]` to the trash and prints where it ended up
+#[cfg(target_os = "macos")]
+const TRASH_SCRIPT: &str = r#"
+ObjC.import('Foundation');
+function run(argv) {
+  const trashed = Ref();
+  const error = Ref();
+  const url = $.NSURL.fileURLWithPath(argv[0]);
+  if (!$.NSFileManager.defaultManager.trashItemAtURLResultingItemURLError(url, trashed, error)) {
+    throw new Error(ObjC.unwrap(error[0].localizedDescription));
+  }
+  return ObjC.unwrap(trashed[0].path);
+}
+"#;
+
+/// Move a file or folder to the trash. Returns where it went, which
+/// `restore_from_trash` needs to put it back, since the trash can't be
+/// searched on macOS.
+#[cfg(target_os = "macos")]
+pub fn move_to_trash(path: &Path) -> Result<Option<PathBuf>, String> {
+    let output = std::process::Command::new("osascript")
+        .args(["-l", "JavaScript", "-e", TRASH_SCRIPT])
+        .arg(path)
+        .output()
+        .map_err(|e| format!("Failed to run osascript: {}", e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "Failed to move to trash: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    let trashed = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    Ok(Some(PathBuf::from(trashed)).filter(|p| !p.as_os_str().is_empty()))
+}
+
+/// Move a file or folder to the trash, where `restore_from_trash` can find it
+#[cfg(not(target_os = "macos"))]
+pub fn move_to_trash(path: &Path) -> Result<Option<PathBuf>, String> {
+    trash::delete(path)
+        .map(|()| None)
+        .map_err(|e| format!("Failed to move to trash: {}", e))
+}
+
+/// Put the most recently trashed item that came from `path` back
+#[cfg(any(target_os = "windows", target_os = "linux"))]
+pub fn restore_from_trash(path: &Path, _trashed: Option<&Path>) -> Result<(), String> {
+    if path.exists() {
+        return Err(format!("{} already exists", path.display()));
+    }
+    let items =
+        trash::os_limited::list().map_err(|e| format!("Failed to read the trash: {}", e))?;
+    let item = items
+        .into_iter()
+        .filter(|item| item.original_path() == path)
+        .max_by_key(|item| item.time_deleted)
+        .ok_or_else(|| format!("{} is no longer in the trash", path.display()))?;
+    trash::os_limited::restore_all([item]).map_err(|e| format!("Failed to restore: {}", e))
+}
+
+/// The trash can't be listed on macOS, so this needs the path
+/// `move_to_trash` reported
+#[cfg(target_os = "macos")]
+pub fn restore_from_trash(path: &Path, trashed: Option<&Path>) -> Result<(), String> {
+    if path.exists() {
+        return Err(format!("{} already exists", path.display()));
+    }
+    let trashed = trashed
+        .filter(|t| t.exists())
+        .ok_or_else(|| format!("{} is no longer in the trash", path.display()))?;
+    std::fs::rename(trashed, path).map_err(|e| format!("Failed to restore: {}", e))
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "linux", target_os = "macos")))]
+pub fn restore_from_trash(_path: &Path, _trashed: Option<&Path>) -> Result<(), String> {
+    Err("Restoring from the trash isn't supported on this platform".to_string())
+}
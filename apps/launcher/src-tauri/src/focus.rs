@@ -0,0 +1,331 @@
+//! Focus sessions: `focus 50m` silences notifications, pauses background
+//! work and sets a Slack status for a fixed time, then undoes all of it.
+//!
+//! Each step is independent; one failing (say, Slack isn't connected) is
+//! reported without stopping the others.
+
+use crate::power::{PowerMonitor, PowerSubsystem};
+use crate::providers::slack::SlackProvider;
+use crate::settings::SettingsStore;
+use chrono::{DateTime, Duration, Utc};
+use parking_lot::{Mutex, RwLock};
+use serde::{Deserialize, Serialize};
+use std::process::Command;
+use std::sync::Arc;
+
+/// Longest session `parse_minutes` accepts
+const MAX_MINUTES: u32 = 12 * 60;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FocusStep {
+    DoNotDisturb,
+    PauseBackground,
+    SlackStatus,
+}
+
+impl FocusStep {
+    pub fn label(&self) -> &'static str {
+        match self {
+            FocusStep::DoNotDisturb => "Do Not Disturb",
+            FocusStep::PauseBackground => "Pause background work",
+            FocusStep::SlackStatus => "Slack status",
+        }
+    }
+}
+
+/// How a step went when the session started
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StepOutcome {
+    pub step: FocusStep,
+    /// Why the step couldn't be applied
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FocusStatus {
+    pub active: bool,
+    pub started_at: Option<DateTime<Utc>>,
+    pub ends_at: Option<DateTime<Utc>>,
+    pub steps: Vec<StepOutcome>,
+}
+
+/// Parse a session length such as "50m", "50", "1h", "1h30m" or "90 min"
+pub fn parse_minutes(input: &str) -> Option<u32> {
+    let input: String = input
+        .to_lowercase()
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect();
+    if input.is_empty() {
+        return None;
+    }
+
+    let mut total = 0u32;
+    let mut number = String::new();
+    let mut rest = input.as_str();
+    while let Some(c) = rest.chars().next() {
+        if c.is_ascii_digit() {
+            number.push(c);
+            rest = &rest[1..];
+            continue;
+        }
+        let value: u32 = number.parse().ok()?;
+        number.clear();
+        let unit = [
+            "hours", "hour", "hrs", "hr", "h", "minutes", "minute", "mins", "min", "m",
+        ]
+        .into_iter()
+        .find(|u| rest.starts_with(u))?;
+        let minutes = if unit.starts_with('h') {
+            value.checked_mul(60)?
+        } else {
+            value
+        };
+        total = total.checked_add(minutes)?;
+        rest = &rest[unit.len()..];
+    }
+    // A bare number means minutes
+    if !number.is_empty() {
+        total = total.checked_add(number.parse().ok()?)?;
+    }
+
+    (1..=MAX_MINUTES).contains(&total).then_some(total)
+}
+
+#[cfg(target_os = "linux")]
+fn set_do_not_disturb(enabled: bool) -> Result<(), String> {
+    // GNOME; other desktops have no common switch
+    run(Command::new("gsettings").args([
+        "set",
+        "org.gnome.desktop.notifications",
+        "show-banners",
+        if enabled { "false" } else { "true" },
+    ]))
+}
+
+#[cfg(target_os = "macos")]
+fn set_do_not_disturb(enabled: bool) -> Result<(), String> {
+    // Focus has no public API; Shortcuts can toggle it
+    let shortcut = if enabled { "Focus On" } else { "Focus Off" };
+    run(Command::new("shortcuts").args(["run", shortcut])).map_err(|e| {
+        format!(
+            "Add \"Focus On\" and \"Focus Off\" shortcuts that set Do Not Disturb ({})",
+            e
+        )
+    })
+}
+
+#[cfg(target_os = "windows")]
+fn set_do_not_disturb(enabled: bool) -> Result<(), String> {
+    // Turns toast notifications off for all apps, like the quick setting
+    run(Command::new("reg").args([
+        "add",
+        r"HKCU\Software\Microsoft\Windows\CurrentVersion\Notifications\Settings",
+        "/v",
+        "NOC_GLOBAL_SETTING_TOASTS_ENABLED",
+        "/t",
+        "REG_DWORD",
+        "/d",
+        if enabled { "0" } else { "1" },
+        "/f",
+    ]))
+}
+
+fn run(command: &mut Command) -> Result<(), String> {
+    let program = command.get_program().to_string_lossy().to_string();
+    let output = command
+        .output()
+        .map_err(|e| format!("Failed to run {}: {}", program, e))?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(String::from_utf8_lossy(&output.stderr).trim().to_string())
+    }
+}
+
+struct Session {
+    started_at: DateTime<Utc>,
+    ends_at: DateTime<Utc>,
+    steps: Vec<StepOutcome>,
+}
+
+type FocusListener = Arc<dyn Fn(FocusStatus) + Send + Sync>;
+
+/// Runs focus sessions, one at a time
+pub struct FocusManager {
+    settings: Arc<SettingsStore>,
+    power_monitor: Arc<PowerMonitor>,
+    slack: Arc<SlackProvider>,
+    session: Mutex<Option<Session>>,
+    listener: RwLock<Option<FocusListener>>,
+}
+
+impl FocusManager {
+    pub fn new(
+        settings: Arc<SettingsStore>,
+        power_monitor: Arc<PowerMonitor>,
+        slack: Arc<SlackProvider>,
+    ) -> Self {
+        Self {
+            settings,
+            power_monitor,
+            slack,
+            session: Mutex::new(None),
+            listener: RwLock::new(None),
+        }
+    }
+
+    /// Called whenever a session starts or ends
+    pub fn set_listener(&self, listener: impl Fn(FocusStatus) + Send + Sync + 'static) {
+        *self.listener.write() = Some(Arc::new(listener));
+    }
+
+    fn notify(&self) {
+        let listener = self.listener.read().clone();
+        if let Some(listener) = listener {
+            listener(self.status());
+        }
+    }
+
+    pub fn status(&self) -> FocusStatus {
+        match self.session.lock().as_ref() {
+            Some(session) => FocusStatus {
+                active: true,
+                started_at: Some(session.started_at),
+                ends_at: Some(session.ends_at),
+                steps: session.steps.clone(),
+            },
+            None => FocusStatus::default(),
+        }
+    }
+
+    /// The steps a new session would run, per the focus settings
+    pub fn planned_steps(&self) -> Vec<FocusStep> {
+        let settings = self.settings.get().focus;
+        [
+            (settings.do_not_disturb, FocusStep::DoNotDisturb),
+            (settings.pause_background, FocusStep::PauseBackground),
+            (settings.slack_status, FocusStep::SlackStatus),
+        ]
+        .into_iter()
+        .filter_map(|(enabled, step)| enabled.then_some(step))
+        .collect()
+    }
+
+    pub fn default_minutes(&self) -> u32 {
+        self.settings.get().focus.default_minutes
+    }
+
+    fn apply(&self, step: FocusStep, ends_at: DateTime<Utc>) -> Result<(), String> {
+        match step {
+            FocusStep::DoNotDisturb => set_do_not_disturb(true),
+            FocusStep::PauseBackground => {
+                self.power_monitor.hold(&[
+                    PowerSubsystem::Indexing,
+                    PowerSubsystem::WidgetRefresh,
+                    PowerSubsystem::ProviderSync,
+                ]);
+                Ok(())
+            }
+            FocusStep::SlackStatus => {
+                let settings = self.settings.get().focus;
+                // Expires on Slack's side too, in case the launcher quits early
+                self.slack.set_status(
+                    &settings.slack_status_text,
+                    &settings.slack_status_emoji,
+                    ends_at.timestamp(),
+                )
+            }
+        }
+    }
+
+    fn undo(&self, step: FocusStep) -> Result<(), String> {
+        match step {
+            FocusStep::DoNotDisturb => set_do_not_disturb(false),
+            FocusStep::PauseBackground => {
+                self.power_monitor.release();
+                Ok(())
+            }
+            FocusStep::SlackStatus => self.slack.set_status("", "", 0),
+        }
+    }
+
+    /// Start a session, replacing a running one
+    pub fn start(&self, minutes: u32) -> FocusStatus {
+        self.end_session();
+
+        let started_at = Utc::now();
+        let ends_at = started_at + Duration::minutes(i64::from(minutes));
+        let steps = self
+            .planned_steps()
+            .into_iter()
+            .map(|step| StepOutcome {
+                step,
+                error: self.apply(step, ends_at).err(),
+            })
+            .collect();
+
+        *self.session.lock() = Some(Session {
+            started_at,
+            ends_at,
+            steps,
+        });
+        self.notify();
+        self.status()
+    }
+
+    /// End the running session early. Returns false if none was running.
+    pub fn stop(&self) -> bool {
+        let ended = self.end_session();
+        if ended {
+            self.notify();
+        }
+        ended
+    }
+
+    /// End the session if its time is up; called periodically
+    pub fn end_if_due(&self) -> bool {
+        let due = self
+            .session
+            .lock()
+            .as_ref()
+            .is_some_and(|s| s.ends_at <= Utc::now());
+        due && self.stop()
+    }
+
+    fn end_session(&self) -> bool {
+        let Some(session) = self.session.lock().take() else {
+            return false;
+        };
+        for outcome in session.steps.iter().filter(|o| o.error.is_none()) {
+            if let Err(e) = self.undo(outcome.step) {
+                eprintln!("Failed to undo {}: {}", outcome.step.label(), e);
+            }
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_session_lengths() {
+        assert_eq!(parse_minutes("50m"), Some(50));
+        assert_eq!(parse_minutes("50"), Some(50));
+        assert_eq!(parse_minutes("1h30m"), Some(90));
+        assert_eq!(parse_minutes("2 hours"), Some(120));
+        assert_eq!(parse_minutes("90 min"), Some(90));
+    }
+
+    #[test]
+    fn rejects_bad_lengths() {
+        assert_eq!(parse_minutes(""), None);
+        assert_eq!(parse_minutes("0m"), None);
+        assert_eq!(parse_minutes("soon"), None);
+        assert_eq!(parse_minutes("13h"), None);
+        assert_eq!(parse_minutes("m"), None);
+    }
+}
@@ -1,49 +1,262 @@
-use chrono::{DateTime, Utc};
-use parking_lot::RwLock;
+//! How often and how recently results are picked, used to boost them.
+//!
+//! Scoring follows Firefox's frecency: each entry keeps its last few visit
+//! times, each visit is worth points by age bucket, and the average is
+//! scaled by the total visit count. Old visits fade out in steps rather
+//! than continuously, so a result used daily last month still outranks one
+//! opened once today.
+//!
+//! Visits go to disk in batches: they're queued in memory, appended to
+//! frecency.log at most every [`FLUSH_INTERVAL`], and folded into the
+//! frecency.json snapshot once the log grows long and on exit. Each log line
+//! carries a checksum, so a line torn by a crash or otherwise damaged is
+//! skipped on load and the log is rewritten from what could be read.
+
+use chrono::{DateTime, Duration, Utc};
+use parking_lot::{Mutex, RwLock};
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::io::Write;
+use std::path::{Path, PathBuf};
 
-const HALF_LIFE_DAYS: f64 = 7.0;
 const MAX_ENTRIES: usize = 1000;
 
+/// Visit times kept per entry; older visits only count towards the total
+const MAX_SAMPLES: usize = 10;
+
+/// Points per visit, by the visit's age in days
+const AGE_BUCKETS: &[(i64, f64)] = &[(4, 100.0), (14, 70.0), (31, 50.0), (90, 30.0)];
+const OLDEST_BUCKET_POINTS: f64 = 10.0;
+
+/// How much picking a result counts, by result id prefix. Results that
+/// depend on exactly what was typed (calculations, web searches) count
+/// less; anything not listed counts fully.
+const DEFAULT_PROVIDER_WEIGHTS: &[(&str, f64)] = &[
+    ("calc", 0.3),
+    ("websearch", 0.5),
+    ("url", 0.5),
+    ("dict", 0.5),
+    ("clip", 0.5),
+    ("file", 0.8),
+    ("content", 0.8),
+];
+
+/// Entries shown in [`FrecencyStats::top`]
+const STATS_TOP: usize = 20;
+
+/// Queued visits are appended to the log at most this often, so moving
+/// quickly through results doesn't write to disk on every pick
+pub const FLUSH_INTERVAL: std::time::Duration = std::time::Duration::from_secs(2);
+
+/// Log records folded into the snapshot once there are this many
+const COMPACT_AFTER: usize = 200;
+
+fn bucket_points(age: Duration) -> f64 {
+    let days = age.num_days();
+    AGE_BUCKETS
+        .iter()
+        .find(|(max_days, _)| days <= *max_days)
+        .map(|(_, points)| *points)
+        .unwrap_or(OLDEST_BUCKET_POINTS)
+}
+
+/// The provider part of a result id, e.g. `app` for `app:firefox`
+fn provider_prefix(id: &str) -> &str {
+    id.split_once(':').map(|(prefix, _)| prefix).unwrap_or(id)
+}
+
+/// Weight for a result's provider; `overrides` come from the settings
+pub fn provider_weight(id: &str, overrides: &HashMap<String, f64>) -> f64 {
+    let prefix = provider_prefix(id);
+    overrides.get(prefix).copied().unwrap_or_else(|| {
+        DEFAULT_PROVIDER_WEIGHTS
+            .iter()
+            .find(|(p, _)| *p == prefix)
+            .map(|(_, weight)| *weight)
+            .unwrap_or(1.0)
+    })
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FrecencyEntry {
     pub id: String,
+    /// All visits, including ones no longer in `visits`
     pub access_count: u32,
     pub last_access: DateTime<Utc>,
+    /// The latest visit times, oldest first. Stores written before these
+    /// were kept only have `last_access`.
+    #[serde(default)]
+    pub visits: Vec<DateTime<Utc>>,
 }
 
 impl FrecencyEntry {
     pub fn new(id: String) -> Self {
+        Self::first_visit(id, Utc::now())
+    }
+
+    fn first_visit(id: String, at: DateTime<Utc>) -> Self {
         Self {
             id,
             access_count: 1,
-            last_access: Utc::now(),
+            last_access: at,
+            visits: vec![at],
         }
     }
 
     pub fn record_access(&mut self) {
-        self.access_count += 1;
-        self.last_access = Utc::now();
+        self.record_visit(Utc::now());
     }
 
+    fn record_visit(&mut self, at: DateTime<Utc>) {
+        self.access_count = self.access_count.saturating_add(1);
+        self.last_access = self.last_access.max(at);
+        self.visits.push(at);
+        self.trim_visits();
+    }
+
+    fn trim_visits(&mut self) {
+        self.visits.sort();
+        self.visits.dedup();
+        let excess = self.visits.len().saturating_sub(MAX_SAMPLES);
+        self.visits.drain(..excess);
+    }
+
+    /// Fold in the same result's history from another store
+    fn merge(&mut self, other: FrecencyEntry) {
+        self.access_count = self.access_count.saturating_add(other.access_count);
+        self.last_access = self.last_access.max(other.last_access);
+        self.visits.extend(other.visits);
+        self.trim_visits();
+    }
+
+    fn score_at(&self, now: DateTime<Utc>) -> f64 {
+        let samples = if self.visits.is_empty() {
+            std::slice::from_ref(&self.last_access)
+        } else {
+            &self.visits[..]
+        };
+        let points: f64 = samples.iter().map(|v| bucket_points(now - *v)).sum();
+        self.access_count as f64 * points / samples.len() as f64
+    }
+
+    /// Raw frecency: 100 for a single visit in the last few days
     pub fn score(&self) -> f64 {
-        let days_since = (Utc::now() - self.last_access).num_hours() as f64 / 24.0;
-        let recency_factor = (-days_since / HALF_LIFE_DAYS).exp();
-        let frequency_factor = (self.access_count as f64).ln() + 1.0;
-        recency_factor * frequency_factor * 10.0
+        self.score_at(Utc::now())
     }
 }
 
+/// Turn a raw frecency score into points added to a result's search score.
+/// Logarithmic so heavy use can't drown out how well the query matches.
+fn boost(score: f64) -> f64 {
+    (score / 100.0).ln_1p() * 10.0
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct FrecencyData {
     pub entries: HashMap<String, FrecencyEntry>,
+    /// The last log record folded into this snapshot. Records up to it that
+    /// are still in the log, because emptying it failed, are skipped on load.
+    #[serde(default)]
+    pub log_seq: u64,
+}
+
+fn apply_visit(data: &mut FrecencyData, id: &str, at: DateTime<Utc>) {
+    match data.entries.get_mut(id) {
+        Some(entry) => entry.record_visit(at),
+        None => {
+            data.entries.insert(
+                id.to_string(),
+                FrecencyEntry::first_visit(id.to_string(), at),
+            );
+        }
+    }
+}
+
+/// One visit in frecency.log
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct LogRecord {
+    seq: u64,
+    id: String,
+    at: DateTime<Utc>,
+}
+
+fn checksum(json: &str) -> String {
+    let digest = format!("{:x}", Sha256::digest(json.as_bytes()));
+    digest[..16].to_string()
+}
+
+impl LogRecord {
+    /// `<checksum> <json>`
+    fn to_line(&self) -> Option<String> {
+        let json = serde_json::to_string(self).ok()?;
+        Some(format!("{} {}\n", checksum(&json), json))
+    }
+
+    /// None for a damaged line
+    fn from_line(line: &str) -> Option<Self> {
+        let (sum, json) = line.split_once(' ')?;
+        if sum != checksum(json) {
+            return None;
+        }
+        serde_json::from_str(json).ok()
+    }
+}
+
+fn append_log(path: &Path, records: &[LogRecord]) -> Result<(), String> {
+    let lines: String = records.iter().filter_map(LogRecord::to_line).collect();
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    }
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .map_err(|e| e.to_string())?;
+    file.write_all(lines.as_bytes()).map_err(|e| e.to_string())
+}
+
+/// What [`FrecencyStore::replay`] found in the log
+struct Replay {
+    /// Intact records in the log
+    logged: usize,
+    last_seq: u64,
+    damaged: usize,
+}
+
+struct LogState {
+    /// Visits not yet appended to the log
+    pending: Vec<LogRecord>,
+    /// Records in the log since it was last emptied
+    logged: usize,
+    next_seq: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FrecencyStat {
+    pub id: String,
+    pub access_count: u32,
+    pub last_access: DateTime<Utc>,
+    pub score: f64,
+}
+
+/// Summary for the settings UI
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FrecencyStats {
+    pub entries: usize,
+    pub total_visits: u64,
+    /// Entry counts by result id prefix, e.g. `app` or `file`
+    pub by_provider: HashMap<String, usize>,
+    /// Highest scoring entries, best first
+    pub top: Vec<FrecencyStat>,
 }
 
 pub struct FrecencyStore {
     data: RwLock<FrecencyData>,
+    /// Always locked after `data` when both are needed
+    log: Mutex<LogState>,
     path: PathBuf,
+    log_path: PathBuf,
 }
 
 impl FrecencyStore {
@@ -52,72 +265,155 @@ impl FrecencyStore {
             .unwrap_or_else(|| PathBuf::from("."))
             .join("launcher")
             .join("frecency.json");
+        Self::open(path)
+    }
 
-        let data = Self::load_from_file(&path).unwrap_or_default();
+    fn open(path: PathBuf) -> Self {
+        let mut data: FrecencyData = crate::journal::load_json_store(&path).unwrap_or_default();
+        let log_path = path.with_extension("log");
+        let replay = Self::replay(&log_path, &mut data);
+        if data.entries.len() > MAX_ENTRIES {
+            Self::prune_old_entries(&mut data);
+        }
 
-        Self {
+        let store = Self {
             data: RwLock::new(data),
+            log: Mutex::new(LogState {
+                pending: Vec::new(),
+                logged: replay.logged,
+                next_seq: replay.last_seq + 1,
+            }),
             path,
+            log_path,
+        };
+        if replay.damaged > 0 {
+            eprintln!(
+                "Skipped {} damaged frecency log records, rewriting the log",
+                replay.damaged
+            );
+            store.compact();
         }
+        store
     }
 
-    fn load_from_file(path: &PathBuf) -> Option<FrecencyData> {
-        let content = std::fs::read_to_string(path).ok()?;
-        serde_json::from_str(&content).ok()
+    /// Fold the visits logged since `data` was saved into it
+    fn replay(log_path: &Path, data: &mut FrecencyData) -> Replay {
+        let content = std::fs::read_to_string(log_path).unwrap_or_default();
+        let mut replay = Replay {
+            logged: 0,
+            last_seq: data.log_seq,
+            damaged: 0,
+        };
+        for line in content.lines().filter(|l| !l.trim().is_empty()) {
+            let Some(record) = LogRecord::from_line(line) else {
+                replay.damaged += 1;
+                continue;
+            };
+            replay.logged += 1;
+            replay.last_seq = replay.last_seq.max(record.seq);
+            if record.seq > data.log_seq {
+                apply_visit(data, &record.id, record.at);
+            }
+        }
+        replay
     }
 
+    /// Count a visit now; it reaches disk with the next [`Self::flush`]
     pub fn record_access(&self, id: &str) {
+        let now = Utc::now();
         let mut data = self.data.write();
+        apply_visit(&mut data, id, now);
+        if data.entries.len() > MAX_ENTRIES {
+            Self::prune_old_entries(&mut data);
+        }
 
-        if let Some(entry) = data.entries.get_mut(id) {
-            entry.record_access();
-        } else {
-            data.entries
-                .insert(id.to_string(), FrecencyEntry::new(id.to_string()));
+        // Queued before letting go of the data, so a compaction can't save
+        // the visit in the snapshot and then log it again
+        let mut log = self.log.lock();
+        let seq = log.next_seq;
+        log.next_seq += 1;
+        log.pending.push(LogRecord {
+            seq,
+            id: id.to_string(),
+            at: now,
+        });
+    }
+
+    /// Append queued visits to the log, compacting it once it's long
+    pub fn flush(&self) {
+        let mut log = self.log.lock();
+        if log.pending.is_empty() {
+            return;
+        }
+        // Kept queued on failure, to be tried again next time
+        if let Err(e) = append_log(&self.log_path, &log.pending) {
+            eprintln!("Failed to write frecency log: {}", e);
+            return;
         }
+        log.logged += log.pending.len();
+        log.pending.clear();
+        let full = log.logged >= COMPACT_AFTER;
+        drop(log);
 
-        if data.entries.len() > MAX_ENTRIES {
-            self.prune_old_entries(&mut data);
+        if full {
+            self.compact();
         }
+    }
 
+    /// Save every visit to frecency.json and empty the log. Run on exit,
+    /// and after changes other than visits since the log can't replay them.
+    pub fn compact(&self) {
+        let mut data = self.data.write();
+        let mut log = self.log.lock();
+        data.log_seq = log.next_seq - 1;
+        let json = match serde_json::to_string_pretty(&*data) {
+            Ok(json) => json,
+            Err(e) => {
+                eprintln!("Failed to save frecency data: {}", e);
+                return;
+            }
+        };
         drop(data);
-        self.save();
+
+        if let Err(e) = crate::journal::write_json_store(&self.path, &json) {
+            eprintln!("Failed to save frecency data: {}", e);
+            return;
+        }
+        log.pending.clear();
+        log.logged = 0;
+        if let Err(e) = std::fs::write(&self.log_path, "") {
+            eprintln!("Failed to empty frecency log: {}", e);
+        }
     }
 
-    fn prune_old_entries(&self, data: &mut FrecencyData) {
+    fn prune_old_entries(data: &mut FrecencyData) {
+        let now = Utc::now();
         let mut entries: Vec<_> = data.entries.drain().collect();
         entries.sort_by(|a, b| {
-            b.1.score()
-                .partial_cmp(&a.1.score())
+            b.1.score_at(now)
+                .partial_cmp(&a.1.score_at(now))
                 .unwrap_or(std::cmp::Ordering::Equal)
         });
         entries.truncate(MAX_ENTRIES / 2);
         data.entries = entries.into_iter().collect();
     }
 
+    /// Points to add to a result's score, before provider weighting
     pub fn get_boost(&self, id: &str) -> f64 {
         let data = self.data.read();
-        data.entries.get(id).map(|e| e.score()).unwrap_or(0.0)
-    }
-
-    pub fn save(&self) {
-        let data = self.data.read();
-
-        if let Some(parent) = self.path.parent() {
-            let _ = std::fs::create_dir_all(parent);
-        }
-
-        if let Ok(json) = serde_json::to_string_pretty(&*data) {
-            let _ = std::fs::write(&self.path, json);
-        }
+        data.entries
+            .get(id)
+            .map(|e| boost(e.score()))
+            .unwrap_or(0.0)
     }
 
     pub fn get_top_results(&self, limit: usize) -> Vec<(String, f64)> {
         let data = self.data.read();
+        let now = Utc::now();
         let mut entries: Vec<_> = data
             .entries
             .iter()
-            .map(|(id, entry)| (id.clone(), entry.score()))
+            .map(|(id, entry)| (id.clone(), entry.score_at(now)))
             .collect();
 
         entries.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
@@ -132,4 +428,162 @@ impl FrecencyStore {
             .map(|(id, _)| id)
             .collect()
     }
+
+    /// Forget one result, e.g. an app that was uninstalled
+    pub fn remove(&self, id: &str) {
+        if self.data.write().entries.remove(id).is_some() {
+            self.compact();
+        }
+    }
+
+    /// Forget all history
+    pub fn clear(&self) {
+        self.data.write().entries.clear();
+        self.compact();
+    }
+
+    pub fn stats(&self) -> FrecencyStats {
+        // Before taking the lock below; parking_lot read locks aren't reentrant
+        let top = self.get_top_results(STATS_TOP);
+        let data = self.data.read();
+        let mut by_provider = HashMap::new();
+        for id in data.entries.keys() {
+            *by_provider
+                .entry(provider_prefix(id).to_string())
+                .or_insert(0) += 1;
+        }
+        let top = top
+            .into_iter()
+            .filter_map(|(id, score)| {
+                let entry = data.entries.get(&id)?;
+                Some(FrecencyStat {
+                    access_count: entry.access_count,
+                    last_access: entry.last_access,
+                    id,
+                    score,
+                })
+            })
+            .collect();
+
+        FrecencyStats {
+            entries: data.entries.len(),
+            total_visits: data.entries.values().map(|e| e.access_count as u64).sum(),
+            by_provider,
+            top,
+        }
+    }
+
+    /// Write the store to `path`, in the same format as frecency.json
+    pub fn export(&self, path: &Path) -> Result<(), String> {
+        let json = serde_json::to_string_pretty(&*self.data.read()).map_err(|e| e.to_string())?;
+        std::fs::write(path, json).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+    }
+
+    /// Load an exported store, either merging it into the current history
+    /// or replacing it. Returns how many entries were read.
+    pub fn import(&self, path: &Path, merge: bool) -> Result<usize, String> {
+        let json = std::fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        let imported: FrecencyData =
+            serde_json::from_str(&json).map_err(|e| format!("Not a frecency export: {}", e))?;
+        let count = imported.entries.len();
+
+        let mut data = self.data.write();
+        if !merge {
+            data.entries.clear();
+        }
+        for (id, mut entry) in imported.entries {
+            entry.id = id.clone();
+            entry.trim_visits();
+            match data.entries.get_mut(&id) {
+                Some(existing) => existing.merge(entry),
+                None => {
+                    data.entries.insert(id, entry);
+                }
+            }
+        }
+        if data.entries.len() > MAX_ENTRIES {
+            Self::prune_old_entries(&mut data);
+        }
+
+        drop(data);
+        self.compact();
+        Ok(count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry_with_visits(count: u32, days_ago: &[i64], now: DateTime<Utc>) -> FrecencyEntry {
+        let visits: Vec<_> = days_ago.iter().map(|d| now - Duration::days(*d)).collect();
+        FrecencyEntry {
+            id: "app:test".to_string(),
+            access_count: count,
+            last_access: *visits.iter().max().unwrap(),
+            visits,
+        }
+    }
+
+    #[test]
+    fn scores_visits_by_age_bucket() {
+        let now = Utc::now();
+        assert_eq!(entry_with_visits(1, &[1], now).score_at(now), 100.0);
+        assert_eq!(entry_with_visits(2, &[1, 20], now).score_at(now), 150.0);
+        // Regular use a month ago beats a single visit today
+        let regular = entry_with_visits(20, &[40, 41, 42, 43], now);
+        let once = entry_with_visits(1, &[0], now);
+        assert!(regular.score_at(now) > once.score_at(now));
+
+        // Entries saved before visit times were kept score by last access
+        let mut legacy = entry_with_visits(3, &[100], now);
+        legacy.visits.clear();
+        assert_eq!(legacy.score_at(now), 30.0);
+    }
+
+    #[test]
+    fn weights_by_provider_prefix() {
+        let mut overrides = HashMap::new();
+        assert_eq!(provider_weight("app:firefox", &overrides), 1.0);
+        assert_eq!(provider_weight("calc:2+2", &overrides), 0.3);
+        overrides.insert("app".to_string(), 2.0);
+        assert_eq!(provider_weight("app:firefox", &overrides), 2.0);
+    }
+
+    #[test]
+    fn replays_the_log_and_recovers_from_damage() {
+        let dir = std::env::temp_dir().join(format!("launcher-frecency-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let path = dir.join("frecency.json");
+        let log = path.with_extension("log");
+
+        let store = FrecencyStore::open(path.clone());
+        store.record_access("app:firefox");
+        store.record_access("app:firefox");
+        store.record_access("file:/tmp/notes.txt");
+        assert!(!log.exists());
+        store.flush();
+
+        // A crash mid-append leaves a torn line behind
+        let mut content = std::fs::read_to_string(&log).unwrap();
+        assert_eq!(content.lines().count(), 3);
+        content.push_str("0123456789abcdef {\"seq\":4,\"id\":\"app:");
+        std::fs::write(&log, content).unwrap();
+
+        let reopened = FrecencyStore::open(path.clone());
+        assert_eq!(reopened.stats().entries, 2);
+        assert_eq!(reopened.stats().total_visits, 3);
+        assert_eq!(std::fs::read_to_string(&log).unwrap(), "");
+
+        // Records already in the snapshot aren't counted twice if the log
+        // couldn't be emptied
+        reopened.record_access("app:firefox");
+        reopened.flush();
+        let content = std::fs::read_to_string(&log).unwrap();
+        reopened.compact();
+        std::fs::write(&log, content).unwrap();
+        assert_eq!(FrecencyStore::open(path).stats().total_visits, 4);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
 }
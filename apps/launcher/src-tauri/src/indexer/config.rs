@@ -61,6 +61,13 @@ impl Default for IndexConfig {
                 "sh".to_string(),
                 "bash".to_string(),
                 "zsh".to_string(),
+                "go".to_string(),
+                "java".to_string(),
+                "c".to_string(),
+                "cpp".to_string(),
+                "h".to_string(),
+                "csv".to_string(),
+                "pdf".to_string(),
             ],
         }
     }
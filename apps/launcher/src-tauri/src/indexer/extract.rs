@@ -0,0 +1,111 @@
+//! Reading the text of files for content search.
+//!
+//! Which files are read is decided by [`IndexConfig`](super::IndexConfig);
+//! the extractors here only know how to turn a file into text. PDF support
+//! needs the `pdf` feature.
+
+use std::path::Path;
+
+pub trait TextExtractor: Send + Sync {
+    /// Whether this extractor reads files with the given lowercase extension
+    fn handles(&self, extension: &str) -> bool;
+
+    fn extract(&self, path: &Path) -> Result<String, String>;
+}
+
+/// Anything stored as UTF-8: notes, code, config files
+struct PlainText;
+
+impl TextExtractor for PlainText {
+    fn handles(&self, _extension: &str) -> bool {
+        true
+    }
+
+    fn extract(&self, path: &Path) -> Result<String, String> {
+        std::fs::read_to_string(path).map_err(|e| e.to_string())
+    }
+}
+
+struct Pdf;
+
+impl TextExtractor for Pdf {
+    fn handles(&self, extension: &str) -> bool {
+        extension == "pdf"
+    }
+
+    #[cfg(feature = "pdf")]
+    fn extract(&self, path: &Path) -> Result<String, String> {
+        // The parser panics on some malformed files
+        std::panic::catch_unwind(|| pdf_extract::extract_text(path))
+            .map_err(|_| "PDF parser crashed".to_string())?
+            .map_err(|e| e.to_string())
+    }
+
+    #[cfg(not(feature = "pdf"))]
+    fn extract(&self, _path: &Path) -> Result<String, String> {
+        Err("PDF support is not included in this build".to_string())
+    }
+}
+
+/// Tried in order; plain text comes last as it takes any extension
+const EXTRACTORS: &[&dyn TextExtractor] = &[&Pdf, &PlainText];
+
+fn extension(path: &Path) -> String {
+    path.extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .unwrap_or_default()
+}
+
+/// The text of a file, using the first extractor that handles its type
+pub fn extract_text(path: &Path) -> Result<String, String> {
+    let extension = extension(path);
+    let extractor = EXTRACTORS
+        .iter()
+        .find(|e| e.handles(&extension))
+        .ok_or_else(|| format!("Can't read .{} files", extension))?;
+    extractor.extract(path)
+}
+
+/// Whether the file's text can be read back cheaply, e.g. to show where a
+/// search term appears
+pub fn is_plain_text(path: &Path) -> bool {
+    let extension = extension(path);
+    !EXTRACTORS
+        .iter()
+        .take(EXTRACTORS.len() - 1)
+        .any(|e| e.handles(&extension))
+}
+
+/// The first line containing one of `terms` (case-insensitive), with its
+/// 1-based line number
+pub fn matching_line(text: &str, terms: &[&str]) -> Option<(usize, String)> {
+    let terms: Vec<String> = terms.iter().map(|t| t.to_lowercase()).collect();
+    text.lines().enumerate().find_map(|(i, line)| {
+        let lower = line.to_lowercase();
+        terms
+            .iter()
+            .any(|t| lower.contains(t.as_str()))
+            .then(|| (i + 1, line.trim().to_string()))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_matching_line() {
+        let text = "# Notes\n\n  Renew the Passport in May\nother";
+        assert_eq!(
+            matching_line(text, &["passport"]),
+            Some((3, "Renew the Passport in May".to_string()))
+        );
+        assert_eq!(matching_line(text, &["visa"]), None);
+    }
+
+    #[test]
+    fn routes_by_extension() {
+        assert!(is_plain_text(Path::new("/notes/todo.md")));
+        assert!(!is_plain_text(Path::new("/docs/Report.PDF")));
+    }
+}
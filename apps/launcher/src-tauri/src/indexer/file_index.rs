@@ -1,15 +1,59 @@
 use super::config::IndexConfig;
 use chrono::{DateTime, Utc};
-use parking_lot::RwLock;
+use parking_lot::Mutex;
+use rusqlite::{params, Connection, OptionalExtension};
 use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicI64, Ordering};
 use std::sync::Arc;
-use tantivy::collector::TopDocs;
-use tantivy::query::QueryParser;
-use tantivy::schema::*;
-use tantivy::{Directory, Index, IndexReader, IndexWriter, ReloadPolicy};
+use std::time::{Duration, Instant};
 use walkdir::WalkDir;
 
+/// Entries written per transaction during a full scan
+const BATCH_SIZE: usize = 1000;
+
+/// Names scored by fuzzy search when the FTS match finds too few
+const FUZZY_SCAN_LIMIT: usize = 20_000;
+/// How long those names are reused across keystrokes
+const FUZZY_NAMES_TTL: Duration = Duration::from_secs(60);
+
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS files (
+        id INTEGER PRIMARY KEY,
+        path TEXT NOT NULL UNIQUE,
+        name TEXT NOT NULL,
+        extension TEXT,
+        size INTEGER NOT NULL,
+        modified INTEGER NOT NULL,
+        is_dir INTEGER NOT NULL,
+        -- Last full scan that saw the file
+        scan INTEGER NOT NULL
+    );
+    CREATE INDEX IF NOT EXISTS files_scan ON files (scan);
+
+    -- Substring matches on names and paths, kept in sync by the triggers
+    CREATE VIRTUAL TABLE IF NOT EXISTS names_fts USING fts5(
+        name, path, content = 'files', content_rowid = 'id', tokenize = 'trigram'
+    );
+    -- Words in text files; the text itself isn't stored
+    CREATE VIRTUAL TABLE IF NOT EXISTS content_fts USING fts5(
+        content, content = '', contentless_delete = 1
+    );
+
+    CREATE TRIGGER IF NOT EXISTS files_insert AFTER INSERT ON files BEGIN
+        INSERT INTO names_fts (rowid, name, path) VALUES (new.id, new.name, new.path);
+    END;
+    CREATE TRIGGER IF NOT EXISTS files_delete AFTER DELETE ON files BEGIN
+        INSERT INTO names_fts (names_fts, rowid, name, path)
+            VALUES ('delete', old.id, old.name, old.path);
+        DELETE FROM content_fts WHERE rowid = old.id;
+    END;
+";
+
+const COLUMNS: &str = "files.path, files.name, files.extension, files.size, files.modified, \
+                       files.is_dir";
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct IndexedFile {
     pub path: String,
@@ -20,82 +64,94 @@ pub struct IndexedFile {
     pub is_dir: bool,
 }
 
+fn indexed_file(row: &rusqlite::Row) -> rusqlite::Result<IndexedFile> {
+    let modified: i64 = row.get(4)?;
+    Ok(IndexedFile {
+        path: row.get(0)?,
+        name: row.get(1)?,
+        extension: row.get(2)?,
+        size: row.get::<_, i64>(3)? as u64,
+        modified: DateTime::from_timestamp(modified, 0).unwrap_or_else(Utc::now),
+        is_dir: row.get(5)?,
+    })
+}
+
+/// An FTS5 query requiring every term, each matched as a literal string.
+/// Terms shorter than `min_chars` are left out; `None` if nothing is left.
+fn fts_query(query: &str, min_chars: usize) -> Option<String> {
+    let terms: Vec<String> = query
+        .split_whitespace()
+        .filter(|t| t.chars().count() >= min_chars)
+        .map(|t| format!("\"{}\"", t.replace('"', "\"\"")))
+        .collect();
+    (!terms.is_empty()).then(|| terms.join(" AND "))
+}
+
+/// File index persisted in SQLite, so search works right after launch while
+/// a rescan runs. Names and paths are indexed with FTS5 trigrams for
+/// substring and fuzzy matching, text files by content.
 pub struct FileIndexer {
-    index: Index,
-    reader: IndexReader,
-    writer: Arc<RwLock<IndexWriter>>,
-    schema: Schema,
+    /// All writes go through this connection
+    writer: Mutex<Connection>,
+    /// Searches use their own connection so a long scan doesn't block them
+    reader: Mutex<Connection>,
     config: IndexConfig,
-    path_field: Field,
-    name_field: Field,
-    extension_field: Field,
-    content_field: Field,
-    size_field: Field,
-    modified_field: Field,
-    is_dir_field: Field,
+    /// Id of the latest full scan; rows with an older id weren't seen by it
+    scan: AtomicI64,
+    /// Ids and names of the most recently modified files, for fuzzy search
+    fuzzy_names: Mutex<Option<(Instant, Arc<Vec<(i64, String)>>)>>,
 }
 
 impl FileIndexer {
     pub fn new(index_dir: PathBuf, config: IndexConfig) -> Result<Self, String> {
-        let mut schema_builder = Schema::builder();
-
-        let text_options = TextOptions::default()
-            .set_indexing_options(
-                TextFieldIndexing::default()
-                    .set_tokenizer("default")
-                    .set_index_option(IndexRecordOption::WithFreqsAndPositions),
-            )
-            .set_stored();
-
-        let path_field = schema_builder.add_text_field("path", STRING | STORED);
-        let name_field = schema_builder.add_text_field("name", text_options.clone());
-        let extension_field = schema_builder.add_text_field("extension", STRING | STORED);
-        let content_field = schema_builder.add_text_field("content", TEXT);
-        let size_field = schema_builder.add_u64_field("size", INDEXED | STORED);
-        let modified_field = schema_builder.add_i64_field("modified", INDEXED | STORED);
-        let is_dir_field = schema_builder.add_u64_field("is_dir", INDEXED | STORED);
-
-        let schema = schema_builder.build();
-
+        // Indexes from before the SQLite one were tantivy directories
+        if index_dir.join("meta.json").exists() {
+            let _ = std::fs::remove_dir_all(&index_dir);
+        }
         std::fs::create_dir_all(&index_dir).map_err(|e| e.to_string())?;
 
-        let index = if index_dir.join("meta.json").exists() {
-            Index::open_in_dir(&index_dir).map_err(|e| e.to_string())?
-        } else {
-            Index::create_in_dir(&index_dir, schema.clone()).map_err(|e| e.to_string())?
+        let db_path = index_dir.join("files.db");
+        let writer = match Self::open(&db_path) {
+            Ok(conn) => conn,
+            Err(e) => {
+                // The index can always be rebuilt; start over rather than fail
+                eprintln!("File index unreadable, recreating it: {}", e);
+                for suffix in ["", "-wal", "-shm"] {
+                    let _ = std::fs::remove_file(format!("{}{}", db_path.display(), suffix));
+                }
+                Self::open(&db_path).map_err(|e| e.to_string())?
+            }
         };
-
-        let writer = index.writer(50_000_000).map_err(|e| e.to_string())?;
-
-        let reader = index
-            .reader_builder()
-            .reload_policy(ReloadPolicy::OnCommitWithDelay)
-            .try_into()
+        let reader = Connection::open(&db_path).map_err(|e| e.to_string())?;
+        let scan = writer
+            .query_row("SELECT COALESCE(MAX(scan), 0) FROM files", [], |row| {
+                row.get(0)
+            })
             .map_err(|e| e.to_string())?;
 
         Ok(Self {
-            index,
-            reader,
-            writer: Arc::new(RwLock::new(writer)),
-            schema,
+            writer: Mutex::new(writer),
+            reader: Mutex::new(reader),
             config,
-            path_field,
-            name_field,
-            extension_field,
-            content_field,
-            size_field,
-            modified_field,
-            is_dir_field,
+            scan: AtomicI64::new(scan),
+            fuzzy_names: Mutex::new(None),
         })
     }
 
+    fn open(db_path: &Path) -> rusqlite::Result<Connection> {
+        let conn = Connection::open(db_path)?;
+        conn.execute_batch("PRAGMA journal_mode = WAL; PRAGMA synchronous = NORMAL;")?;
+        conn.execute_batch(SCHEMA)?;
+        Ok(conn)
+    }
+
+    /// Walk the index roots, updating entries that changed since the last
+    /// scan, then drop entries that are gone. Unchanged files keep their
+    /// stored content, so a rescan only reads what was modified.
     pub fn index_all(&self) -> Result<usize, String> {
+        let scan = self.scan.fetch_add(1, Ordering::SeqCst) + 1;
         let mut count = 0;
-
-        {
-            let mut writer = self.writer.write();
-            writer.delete_all_documents().map_err(|e| e.to_string())?;
-        }
+        let mut batch = Vec::with_capacity(BATCH_SIZE);
 
         for base_path in &self.config.index_paths {
             if !base_path.exists() {
@@ -111,162 +167,202 @@ impl FileIndexer {
                     Ok(e) => e,
                     Err(_) => continue,
                 };
-
-                let path = entry.path();
                 let metadata = match entry.metadata() {
                     Ok(m) => m,
                     Err(_) => continue,
                 };
 
-                if metadata.len() > self.config.max_file_size_mb * 1024 * 1024 {
-                    continue;
+                batch.push((entry.into_path(), metadata));
+                if batch.len() >= BATCH_SIZE {
+                    count += self.write_batch(&batch, scan)?;
+                    batch.clear();
                 }
+            }
+        }
+        count += self.write_batch(&batch, scan)?;
 
-                if let Err(e) = self.index_file(path, &metadata) {
-                    eprintln!("Failed to index {}: {}", path.display(), e);
-                    continue;
-                }
+        // Deleted since the last scan, or excluded by a changed config
+        self.writer
+            .lock()
+            .execute("DELETE FROM files WHERE scan < ?1", [scan])
+            .map_err(|e| e.to_string())?;
 
-                count += 1;
+        Ok(count)
+    }
 
-                if count % 1000 == 0 {
-                    let mut writer = self.writer.write();
-                    let _ = writer.commit();
-                }
+    fn write_batch(
+        &self,
+        batch: &[(PathBuf, std::fs::Metadata)],
+        scan: i64,
+    ) -> Result<usize, String> {
+        let mut conn = self.writer.lock();
+        let tx = conn.transaction().map_err(|e| e.to_string())?;
+        let mut count = 0;
+        for (path, metadata) in batch {
+            match self.write_entry(&tx, path, metadata, scan, true) {
+                Ok(()) => count += 1,
+                Err(e) => eprintln!("Failed to index {}: {}", path.display(), e),
             }
         }
+        tx.commit().map_err(|e| e.to_string())?;
+        Ok(count)
+    }
 
-        {
-            let mut writer = self.writer.write();
-            writer.commit().map_err(|e| e.to_string())?;
+    fn content(&self, path: &Path, metadata: &std::fs::Metadata) -> String {
+        // Files over the size limit are still listed, just not searchable by content
+        let index_content = self.config.should_index_content(path)
+            && metadata.is_file()
+            && metadata.len() <= self.config.max_file_size_mb * 1024 * 1024;
+        if index_content {
+            super::extract::extract_text(path).unwrap_or_default()
+        } else if crate::ocr::is_image(path) {
+            // Text recognized by the OCR action, if it was asked to index it
+            crate::ocr::saved_text(path).unwrap_or_default()
+        } else {
+            String::new()
         }
-
-        Ok(count)
     }
 
-    pub fn index_file(&self, path: &Path, metadata: &std::fs::Metadata) -> Result<(), String> {
+    fn write_entry(
+        &self,
+        conn: &Connection,
+        path: &Path,
+        metadata: &std::fs::Metadata,
+        scan: i64,
+        skip_unchanged: bool,
+    ) -> rusqlite::Result<()> {
+        let path_str = path.to_string_lossy();
+        let size = metadata.len() as i64;
+        let modified = metadata
+            .modified()
+            .map(DateTime::<Utc>::from)
+            .unwrap_or_else(|_| Utc::now())
+            .timestamp();
+
+        let existing: Option<(i64, i64, i64)> = conn
+            .query_row(
+                "SELECT id, size, modified FROM files WHERE path = ?1",
+                [&path_str],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            )
+            .optional()?;
+        if let Some((id, old_size, old_modified)) = existing {
+            if skip_unchanged && old_size == size && old_modified == modified {
+                conn.execute(
+                    "UPDATE files SET scan = ?1 WHERE id = ?2",
+                    params![scan, id],
+                )?;
+                return Ok(());
+            }
+            conn.execute("DELETE FROM files WHERE id = ?1", [id])?;
+        }
+
         let name = path
             .file_name()
             .map(|n| n.to_string_lossy().to_string())
             .unwrap_or_default();
-
         let extension = path.extension().map(|e| e.to_string_lossy().to_string());
-
-        let modified = metadata
-            .modified()
-            .map(|t| DateTime::<Utc>::from(t))
-            .unwrap_or_else(|_| Utc::now());
-
-        let content = if self.config.should_index_content(path) && metadata.is_file() {
-            std::fs::read_to_string(path).unwrap_or_default()
-        } else {
-            String::new()
-        };
-
-        let mut doc = tantivy::TantivyDocument::new();
-        doc.add_text(self.path_field, path.to_string_lossy());
-        doc.add_text(self.name_field, &name);
-        doc.add_text(self.extension_field, extension.as_deref().unwrap_or(""));
-        doc.add_text(self.content_field, &content);
-        doc.add_u64(self.size_field, metadata.len());
-        doc.add_i64(self.modified_field, modified.timestamp());
-        doc.add_u64(self.is_dir_field, if metadata.is_dir() { 1 } else { 0 });
-
-        let mut writer = self.writer.write();
-        writer.add_document(doc).map_err(|e| e.to_string())?;
-
+        conn.execute(
+            "INSERT INTO files (path, name, extension, size, modified, is_dir, scan)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![
+                path_str,
+                name,
+                extension,
+                size,
+                modified,
+                metadata.is_dir(),
+                scan
+            ],
+        )?;
+
+        let content = self.content(path, metadata);
+        if !content.is_empty() {
+            conn.execute(
+                "INSERT INTO content_fts (rowid, content) VALUES (?1, ?2)",
+                params![conn.last_insert_rowid(), content],
+            )?;
+        }
         Ok(())
     }
 
+    pub fn index_file(&self, path: &Path, metadata: &std::fs::Metadata) -> Result<(), String> {
+        let conn = self.writer.lock();
+        let scan = self.scan.load(Ordering::SeqCst);
+        self.write_entry(&conn, path, metadata, scan, false)
+            .map_err(|e| e.to_string())
+    }
+
+    /// Remove a path, and everything below it if it was a folder
     pub fn remove_file(&self, path: &Path) -> Result<(), String> {
-        let term = tantivy::Term::from_field_text(self.path_field, &path.to_string_lossy());
-        let mut writer = self.writer.write();
-        writer.delete_term(term);
+        let path = path.to_string_lossy();
+        let below = format!("{}{}", path, std::path::MAIN_SEPARATOR);
+        self.writer
+            .lock()
+            .execute(
+                "DELETE FROM files WHERE path = ?1 OR substr(path, 1, length(?2)) = ?2",
+                params![path, below],
+            )
+            .map_err(|e| e.to_string())?;
         Ok(())
     }
 
+    /// Re-read a path reported by the watcher
     pub fn update_file(&self, path: &Path) -> Result<(), String> {
-        self.remove_file(path)?;
-
-        if path.exists() {
-            if let Ok(metadata) = std::fs::metadata(path) {
-                self.index_file(path, &metadata)?;
-            }
+        match std::fs::metadata(path) {
+            Ok(metadata) => self.index_file(path, &metadata),
+            Err(_) => self.remove_file(path),
         }
-
-        Ok(())
     }
 
-    pub fn commit(&self) -> Result<(), String> {
-        let mut writer = self.writer.write();
-        writer.commit().map_err(|e| e.to_string())?;
-        Ok(())
+    /// Files whose text contains all the words in `query_str`, best match
+    /// first. The last word may be incomplete.
+    pub fn content_search(
+        &self,
+        query_str: &str,
+        limit: usize,
+    ) -> Result<Vec<IndexedFile>, String> {
+        let Some(query) = fts_query(query_str, 1) else {
+            return Ok(Vec::new());
+        };
+        let query = format!("{}*", query);
+
+        let conn = self.reader.lock();
+        let sql = format!(
+            "SELECT {} FROM content_fts JOIN files ON files.id = content_fts.rowid
+             WHERE content_fts MATCH ?1 ORDER BY rank LIMIT ?2",
+            COLUMNS
+        );
+        let mut stmt = conn.prepare_cached(&sql).map_err(|e| e.to_string())?;
+        let rows = stmt
+            .query_map(params![query, limit as i64], indexed_file)
+            .map_err(|e| e.to_string())?;
+        Ok(rows.filter_map(|r| r.ok()).collect())
     }
 
+    /// Files whose name or content contains all the words in `query_str`
     pub fn search(&self, query_str: &str, limit: usize) -> Result<Vec<IndexedFile>, String> {
-        let searcher = self.reader.searcher();
-
-        let query_parser =
-            QueryParser::for_index(&self.index, vec![self.name_field, self.content_field]);
-
-        let query = query_parser
-            .parse_query(query_str)
-            .map_err(|e| e.to_string())?;
-
-        let top_docs = searcher
-            .search(&query, &TopDocs::with_limit(limit))
-            .map_err(|e| e.to_string())?;
-
-        let mut results = Vec::new();
-
-        for (_score, doc_address) in top_docs {
-            let doc: tantivy::TantivyDocument =
-                searcher.doc(doc_address).map_err(|e| e.to_string())?;
-
-            let path = doc
-                .get_first(self.path_field)
-                .and_then(|v| v.as_str())
-                .unwrap_or("")
-                .to_string();
-
-            let name = doc
-                .get_first(self.name_field)
-                .and_then(|v| v.as_str())
-                .unwrap_or("")
-                .to_string();
-
-            let extension = doc
-                .get_first(self.extension_field)
-                .and_then(|v| v.as_str())
-                .map(|s| s.to_string())
-                .filter(|s| !s.is_empty());
-
-            let size = doc
-                .get_first(self.size_field)
-                .and_then(|v| v.as_u64())
-                .unwrap_or(0);
-
-            let modified_ts = doc
-                .get_first(self.modified_field)
-                .and_then(|v| v.as_i64())
-                .unwrap_or(0);
-
-            let is_dir = doc
-                .get_first(self.is_dir_field)
-                .and_then(|v| v.as_u64())
-                .map(|v| v == 1)
-                .unwrap_or(false);
-
-            let modified = DateTime::from_timestamp(modified_ts, 0).unwrap_or_else(|| Utc::now());
-
-            results.push(IndexedFile {
-                path,
-                name,
-                extension,
-                size,
-                modified,
-                is_dir,
-            });
+        let mut results = self.content_search(query_str, limit)?;
+
+        // Trigrams need at least three characters per term
+        if results.len() < limit {
+            let conn = self.reader.lock();
+            if let Some(name_query) = fts_query(query_str, 3) {
+                let sql = format!(
+                    "SELECT {} FROM names_fts JOIN files ON files.id = names_fts.rowid
+                     WHERE names_fts MATCH ?1 ORDER BY rank LIMIT ?2",
+                    COLUMNS
+                );
+                let mut stmt = conn.prepare_cached(&sql).map_err(|e| e.to_string())?;
+                let rows = stmt
+                    .query_map(params![name_query, limit as i64], indexed_file)
+                    .map_err(|e| e.to_string())?;
+                for file in rows.filter_map(|r| r.ok()) {
+                    if results.len() < limit && !results.iter().any(|f| f.path == file.path) {
+                        results.push(file);
+                    }
+                }
+            }
         }
 
         Ok(results)
@@ -276,85 +372,126 @@ impl FileIndexer {
         use fuzzy_matcher::skim::SkimMatcherV2;
         use fuzzy_matcher::FuzzyMatcher;
 
-        let searcher = self.reader.searcher();
+        let conn = self.reader.lock();
         let matcher = SkimMatcherV2::default();
-
-        let all_docs = searcher
-            .search(&tantivy::query::AllQuery, &TopDocs::with_limit(10000))
-            .map_err(|e| e.to_string())?;
-
-        let mut scored_results: Vec<(i64, IndexedFile)> = Vec::new();
-
-        for (_score, doc_address) in all_docs {
-            let doc: tantivy::TantivyDocument =
-                searcher.doc(doc_address).map_err(|e| e.to_string())?;
-
-            let name = doc
-                .get_first(self.name_field)
-                .and_then(|v| v.as_str())
-                .unwrap_or("");
-
-            let path = doc
-                .get_first(self.path_field)
-                .and_then(|v| v.as_str())
-                .unwrap_or("");
-
-            let name_score = matcher.fuzzy_match(name, query_str).unwrap_or(0);
-            let path_score = matcher.fuzzy_match(path, query_str).unwrap_or(0) / 2;
-            let total_score = name_score.max(path_score);
-
-            if total_score > 0 {
-                let extension = doc
-                    .get_first(self.extension_field)
-                    .and_then(|v| v.as_str())
-                    .map(|s| s.to_string())
-                    .filter(|s| !s.is_empty());
-
-                let size = doc
-                    .get_first(self.size_field)
-                    .and_then(|v| v.as_u64())
-                    .unwrap_or(0);
-
-                let modified_ts = doc
-                    .get_first(self.modified_field)
-                    .and_then(|v| v.as_i64())
-                    .unwrap_or(0);
-
-                let is_dir = doc
-                    .get_first(self.is_dir_field)
-                    .and_then(|v| v.as_u64())
-                    .map(|v| v == 1)
-                    .unwrap_or(false);
-
-                let modified =
-                    DateTime::from_timestamp(modified_ts, 0).unwrap_or_else(|| Utc::now());
-
-                scored_results.push((
-                    total_score,
-                    IndexedFile {
-                        path: path.to_string(),
-                        name: name.to_string(),
-                        extension,
-                        size,
-                        modified,
-                        is_dir,
-                    },
-                ));
+        let mut candidates: Vec<IndexedFile> = Vec::new();
+
+        // Substring matches first; abbreviations like "prjrdm" won't be
+        // among them, so fall back to scoring names directly
+        if let Some(query) = fts_query(query_str, 3) {
+            let sql = format!(
+                "SELECT {} FROM names_fts JOIN files ON files.id = names_fts.rowid
+                 WHERE names_fts MATCH ?1 LIMIT ?2",
+                COLUMNS
+            );
+            let mut stmt = conn.prepare_cached(&sql).map_err(|e| e.to_string())?;
+            let rows = stmt
+                .query_map(params![query, FUZZY_SCAN_LIMIT as i64], indexed_file)
+                .map_err(|e| e.to_string())?;
+            candidates.extend(rows.filter_map(|r| r.ok()));
+        }
+        if candidates.len() < limit {
+            let seen: HashSet<String> = candidates.iter().map(|f| f.path.clone()).collect();
+            let names = self.fuzzy_names(&conn)?;
+            let mut best: Vec<(i64, i64)> = names
+                .iter()
+                .filter_map(|(id, name)| matcher.fuzzy_match(name, query_str).map(|s| (s, *id)))
+                .collect();
+            best.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)));
+
+            let sql = format!("SELECT {} FROM files WHERE id = ?1", COLUMNS);
+            let mut stmt = conn.prepare_cached(&sql).map_err(|e| e.to_string())?;
+            for (_, id) in best.into_iter().take(limit) {
+                // Files removed since the names were read are skipped
+                if let Ok(file) = stmt.query_row([id], indexed_file) {
+                    if !seen.contains(&file.path) {
+                        candidates.push(file);
+                    }
+                }
             }
         }
 
+        let mut scored_results: Vec<(i64, IndexedFile)> = candidates
+            .into_iter()
+            .filter_map(|file| {
+                let name_score = matcher.fuzzy_match(&file.name, query_str).unwrap_or(0);
+                let path_score = matcher.fuzzy_match(&file.path, query_str).unwrap_or(0) / 2;
+                let total_score = name_score.max(path_score);
+                (total_score > 0).then_some((total_score, file))
+            })
+            .collect();
+
         scored_results.sort_by(|a, b| b.0.cmp(&a.0));
         scored_results.truncate(limit);
 
         Ok(scored_results.into_iter().map(|(_, f)| f).collect())
     }
 
+    /// Ids and names of the most recently modified files, read at most once
+    /// a minute rather than on every keystroke
+    fn fuzzy_names(&self, conn: &Connection) -> Result<Arc<Vec<(i64, String)>>, String> {
+        let mut cached = self.fuzzy_names.lock();
+        if let Some((read_at, names)) = cached.as_ref() {
+            if read_at.elapsed() < FUZZY_NAMES_TTL {
+                return Ok(names.clone());
+            }
+        }
+
+        let mut stmt = conn
+            .prepare_cached("SELECT id, name FROM files ORDER BY modified DESC, id LIMIT ?1")
+            .map_err(|e| e.to_string())?;
+        let names: Vec<(i64, String)> = stmt
+            .query_map([FUZZY_SCAN_LIMIT as i64], |row| {
+                Ok((row.get(0)?, row.get(1)?))
+            })
+            .map_err(|e| e.to_string())?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        let names = Arc::new(names);
+        *cached = Some((Instant::now(), names.clone()));
+        Ok(names)
+    }
+
+    /// Every indexed file and folder. `progress` gets the number of entries
+    /// read so far and the total.
+    pub fn all_files(
+        &self,
+        mut progress: impl FnMut(usize, usize),
+    ) -> Result<Vec<IndexedFile>, String> {
+        let conn = self.reader.lock();
+        let total: i64 = conn
+            .query_row("SELECT COUNT(*) FROM files", [], |row| row.get(0))
+            .map_err(|e| e.to_string())?;
+        let total = total as usize;
+
+        let sql = format!("SELECT {} FROM files", COLUMNS);
+        let mut stmt = conn.prepare(&sql).map_err(|e| e.to_string())?;
+        let rows = stmt
+            .query_map([], indexed_file)
+            .map_err(|e| e.to_string())?;
+
+        let mut files = Vec::with_capacity(total);
+        for file in rows {
+            files.push(file.map_err(|e| e.to_string())?);
+            if files.len() % 10_000 == 0 {
+                progress(files.len(), total);
+            }
+        }
+        progress(files.len(), total);
+
+        Ok(files)
+    }
+
     pub fn get_stats(&self) -> Result<IndexStats, String> {
-        let searcher = self.reader.searcher();
-        let num_docs = searcher.num_docs() as usize;
+        let total: i64 = self
+            .reader
+            .lock()
+            .query_row("SELECT COUNT(*) FROM files", [], |row| row.get(0))
+            .map_err(|e| e.to_string())?;
 
         Ok(IndexStats {
-            total_files: num_docs,
+            total_files: total as usize,
             index_paths: self.config.index_paths.clone(),
         })
     }
@@ -365,3 +502,17 @@ pub struct IndexStats {
     pub total_files: usize,
     pub index_paths: Vec<PathBuf>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quotes_fts_terms() {
+        assert_eq!(
+            fts_query("read \"me\" of", 2).as_deref(),
+            Some("\"read\" AND \"\"\"me\"\"\" AND \"of\"")
+        );
+        assert_eq!(fts_query("a b", 3), None);
+    }
+}
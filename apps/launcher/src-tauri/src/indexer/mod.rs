@@ -1,4 +1,5 @@
 pub mod config;
+pub mod extract;
 pub mod file_index;
 pub mod watcher;
 
@@ -0,0 +1,316 @@
+use parking_lot::RwLock;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Where a recognized intent is sent
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum IntentTarget {
+    /// Run a single provider with a rewritten query, e.g. `{ "provider":
+    /// "calculator", "query": "{expr}" }`
+    Provider { provider: String, query: String },
+    /// Offer a fixed result, e.g. `{ "id": "system:lock" }`
+    Result {
+        id: String,
+        title: String,
+        #[serde(default)]
+        subtitle: Option<String>,
+        #[serde(default)]
+        icon: Option<String>,
+    },
+}
+
+/// A rule mapping natural-language patterns to a target.
+///
+/// Patterns are case-insensitive regexes matched against the whole query;
+/// named groups (`(?P<expr>...)`) can be used as `{expr}` in the target.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IntentRule {
+    pub id: String,
+    pub patterns: Vec<String>,
+    pub target: IntentTarget,
+    /// Where the rule came from: "builtin", "user", or a plugin id
+    #[serde(default)]
+    pub source: String,
+}
+
+/// A query matched against a rule, with its target filled in
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IntentMatch {
+    pub rule_id: String,
+    pub target: IntentTarget,
+    pub captures: HashMap<String, String>,
+}
+
+struct CompiledRule {
+    rule: IntentRule,
+    patterns: Vec<Regex>,
+}
+
+impl CompiledRule {
+    fn compile(rule: IntentRule) -> Option<Self> {
+        let patterns: Vec<Regex> = rule
+            .patterns
+            .iter()
+            .filter_map(|p| match Regex::new(&format!("(?i)^(?:{})$", p)) {
+                Ok(re) => Some(re),
+                Err(e) => {
+                    eprintln!("Invalid pattern in intent {}: {}", rule.id, e);
+                    None
+                }
+            })
+            .collect();
+
+        (!patterns.is_empty()).then_some(Self { rule, patterns })
+    }
+}
+
+/// Classifies free-form queries and routes them before generic search
+pub struct IntentRouter {
+    rules: RwLock<Vec<CompiledRule>>,
+}
+
+impl IntentRouter {
+    pub fn new() -> Self {
+        let router = Self {
+            rules: RwLock::new(Vec::new()),
+        };
+        router.reload();
+        router
+    }
+
+    fn rules_path() -> PathBuf {
+        dirs::data_dir()
+            .unwrap_or_else(|| PathBuf::from("."))
+            .join("launcher")
+            .join("intents.json")
+    }
+
+    fn load_rules_file(path: &Path, source: &str) -> Vec<IntentRule> {
+        let content = match std::fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(_) => return Vec::new(),
+        };
+
+        match serde_json::from_str::<Vec<IntentRule>>(&content) {
+            Ok(rules) => rules
+                .into_iter()
+                .map(|mut r| {
+                    r.source = source.to_string();
+                    r
+                })
+                .collect(),
+            Err(e) => {
+                eprintln!("Failed to parse intents from {}: {}", path.display(), e);
+                Vec::new()
+            }
+        }
+    }
+
+    /// Reload built-in and user rules, keeping plugin rules
+    pub fn reload(&self) {
+        let mut rules = self.rules.write();
+        rules.retain(|r| r.rule.source != "builtin" && r.rule.source != "user");
+
+        // User rules come first so they can override built-in phrasing
+        let user = Self::load_rules_file(&Self::rules_path(), "user");
+        rules.splice(
+            0..0,
+            user.into_iter()
+                .chain(builtin_rules())
+                .filter_map(CompiledRule::compile),
+        );
+    }
+
+    /// Load `intents.json` from a plugin directory, replacing earlier rules
+    /// from the same plugin
+    pub fn load_plugin_rules(&self, plugin_id: &str, plugin_dir: &Path) {
+        let plugin_rules = Self::load_rules_file(&plugin_dir.join("intents.json"), plugin_id);
+
+        let mut rules = self.rules.write();
+        rules.retain(|r| r.rule.source != plugin_id);
+        rules.extend(plugin_rules.into_iter().filter_map(CompiledRule::compile));
+    }
+
+    pub fn remove_plugin_rules(&self, plugin_id: &str) {
+        self.rules.write().retain(|r| r.rule.source != plugin_id);
+    }
+
+    pub fn list_rules(&self) -> Vec<IntentRule> {
+        self.rules.read().iter().map(|r| r.rule.clone()).collect()
+    }
+
+    /// Find the first rule matching the query
+    pub fn route(&self, query: &str) -> Option<IntentMatch> {
+        let query = query.trim();
+        // Single words are ordinary searches, not sentences
+        if !query.contains(' ') {
+            return None;
+        }
+
+        let rules = self.rules.read();
+        for compiled in rules.iter() {
+            for pattern in &compiled.patterns {
+                let Some(caps) = pattern.captures(query) else {
+                    continue;
+                };
+
+                let captures: HashMap<String, String> = pattern
+                    .capture_names()
+                    .flatten()
+                    .filter_map(|name| {
+                        caps.name(name)
+                            .map(|m| (name.to_string(), m.as_str().trim().to_string()))
+                    })
+                    .collect();
+
+                return Some(IntentMatch {
+                    rule_id: compiled.rule.id.clone(),
+                    target: fill_target(&compiled.rule.target, &captures),
+                    captures,
+                });
+            }
+        }
+        None
+    }
+}
+
+fn fill_template(template: &str, captures: &HashMap<String, String>) -> String {
+    captures
+        .iter()
+        .fold(template.to_string(), |acc, (name, value)| {
+            acc.replace(&format!("{{{}}}", name), value)
+        })
+}
+
+fn fill_target(target: &IntentTarget, captures: &HashMap<String, String>) -> IntentTarget {
+    match target {
+        IntentTarget::Provider { provider, query } => IntentTarget::Provider {
+            provider: provider.clone(),
+            query: fill_template(query, captures),
+        },
+        IntentTarget::Result {
+            id,
+            title,
+            subtitle,
+            icon,
+        } => IntentTarget::Result {
+            id: fill_template(id, captures),
+            title: fill_template(title, captures),
+            subtitle: subtitle.as_ref().map(|s| fill_template(s, captures)),
+            icon: icon.clone(),
+        },
+    }
+}
+
+fn builtin_rules() -> Vec<IntentRule> {
+    /// A time of day, optionally with its zone: `2pm UTC`, `15:30 tokyo`
+    const TIME: &str = r"(?:\d{1,2}(?::\d{2})?\s*(?:am|pm)?|noon|midnight)\b.*?";
+
+    fn system(id: &str, patterns: &[&str], title: &str, icon: &str) -> IntentRule {
+        IntentRule {
+            id: format!("builtin:{}", id),
+            patterns: patterns.iter().map(|p| p.to_string()).collect(),
+            target: IntentTarget::Result {
+                id: format!("system:{}", id),
+                title: title.to_string(),
+                subtitle: Some("System command".to_string()),
+                icon: Some(icon.to_string()),
+            },
+            source: "builtin".to_string(),
+        }
+    }
+
+    vec![
+        IntentRule {
+            id: "builtin:emptytrash".to_string(),
+            patterns: vec![
+                r"(?:please )?(?:empty|clear|clean) (?:the |my )?(?:trash|recycle bin|bin)"
+                    .to_string(),
+            ],
+            target: IntentTarget::Result {
+                id: "cleanup:empty-trash".to_string(),
+                title: "Empty Trash".to_string(),
+                subtitle: Some("Cleanup • asks before deleting".to_string()),
+                icon: Some("🗑️".to_string()),
+            },
+            source: "builtin".to_string(),
+        },
+        system(
+            "lock",
+            &[r"lock (?:the |my )?(?:screen|computer|pc|mac)"],
+            "Lock Screen",
+            "🔒",
+        ),
+        system(
+            "sleep",
+            &[r"(?:put (?:the |my )?(?:computer|pc|mac) to sleep|go to sleep)"],
+            "Sleep",
+            "😴",
+        ),
+        system(
+            "restart",
+            &[r"(?:restart|reboot) (?:the |my )?(?:computer|pc|mac|system)"],
+            "Restart",
+            "🔄",
+        ),
+        system(
+            "shutdown",
+            &[r"(?:shut ?down|turn off|power off) (?:the |my )?(?:computer|pc|mac|system)"],
+            "Shut Down",
+            "⏻",
+        ),
+        IntentRule {
+            id: "builtin:calculate".to_string(),
+            patterns: vec![
+                r"(?:what(?:'s| is)|calculate|compute) (?P<expr>[\d\s.+\-*/^()%]+)\??".to_string(),
+            ],
+            target: IntentTarget::Provider {
+                provider: "calculator".to_string(),
+                query: "{expr}".to_string(),
+            },
+            source: "builtin".to_string(),
+        },
+        IntentRule {
+            id: "builtin:localtime".to_string(),
+            patterns: vec![
+                format!(
+                    r"(?:what(?:'s| is) )?(?P<time>{}) (?:here|locally)\??",
+                    TIME
+                ),
+                format!(r"(?P<time>{}) in (?:my time ?zone|local time)\??", TIME),
+            ],
+            target: IntentTarget::Provider {
+                provider: "calculator".to_string(),
+                query: "{time} in local".to_string(),
+            },
+            source: "builtin".to_string(),
+        },
+        IntentRule {
+            id: "builtin:remind".to_string(),
+            patterns: vec![
+                r"(?:please )?remind me (?P<task>.+)".to_string(),
+                r"(?:set|add|create) (?:a )?reminder (?P<task>.+)".to_string(),
+            ],
+            target: IntentTarget::Provider {
+                provider: "reminders".to_string(),
+                query: "remind me {task}".to_string(),
+            },
+            source: "builtin".to_string(),
+        },
+        IntentRule {
+            id: "builtin:websearch".to_string(),
+            patterns: vec![
+                r"(?:search (?:the web|online|google) for|google|look up) (?P<terms>.+)"
+                    .to_string(),
+            ],
+            target: IntentTarget::Provider {
+                provider: "websearch".to_string(),
+                query: "{terms}".to_string(),
+            },
+            source: "builtin".to_string(),
+        },
+    ]
+}
@@ -0,0 +1,253 @@
+use parking_lot::Mutex;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+lazy_static::lazy_static! {
+    static ref JOURNAL: Journal = Journal::new();
+}
+
+/// A critical state transition that can be rolled back if it never completes
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum JournalOp {
+    /// Writing a JSON store such as settings.json or frecency.json
+    StoreWrite { path: PathBuf },
+    /// Downloading/extracting a plugin into its directory
+    PluginInstall { plugin_dir: PathBuf },
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "phase", rename_all = "snake_case")]
+enum JournalRecord {
+    Begin { txn: u64, op: JournalOp },
+    Commit { txn: u64 },
+}
+
+struct Journal {
+    path: PathBuf,
+    open: Mutex<HashMap<u64, JournalOp>>,
+    next_txn: AtomicU64,
+}
+
+impl Journal {
+    fn new() -> Self {
+        // Tests write stores too; keep them out of the user's journal
+        let dir = if cfg!(test) {
+            std::env::temp_dir().join(format!("launcher-test-{}", std::process::id()))
+        } else {
+            dirs::data_dir()
+                .unwrap_or_else(|| PathBuf::from("."))
+                .join("launcher")
+        };
+        Self {
+            path: dir.join("journal.jsonl"),
+            open: Mutex::new(HashMap::new()),
+            next_txn: AtomicU64::new(1),
+        }
+    }
+
+    fn append(&self, record: &JournalRecord) {
+        if let Some(parent) = self.path.parent() {
+            let _ = std::fs::create_dir_all(parent);
+        }
+        let Ok(line) = serde_json::to_string(record) else {
+            return;
+        };
+        if let Ok(mut file) = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+        {
+            let _ = writeln!(file, "{}", line);
+            let _ = file.sync_data();
+        }
+    }
+}
+
+/// An in-progress journaled operation.
+///
+/// Call [`Transaction::commit`] once the operation has finished. Dropping an
+/// uncommitted transaction rolls the operation back right away; if the
+/// process dies instead, [`recover`] rolls it back on the next start.
+pub struct Transaction {
+    txn: u64,
+    committed: bool,
+}
+
+impl Transaction {
+    pub fn commit(mut self) {
+        self.committed = true;
+        finish(self.txn);
+    }
+}
+
+impl Drop for Transaction {
+    fn drop(&mut self) {
+        if self.committed {
+            return;
+        }
+        let op = JOURNAL.open.lock().get(&self.txn).cloned();
+        if let Some(op) = op {
+            eprintln!("Rolling back incomplete operation: {:?}", op);
+            rollback(&op);
+        }
+        finish(self.txn);
+    }
+}
+
+/// Record the start of a critical operation
+pub fn begin(op: JournalOp) -> Transaction {
+    let txn = JOURNAL.next_txn.fetch_add(1, Ordering::SeqCst);
+
+    // Hold the lock while appending so a concurrent finish() can't truncate
+    // the journal between our Begin record and registering the transaction
+    let mut open = JOURNAL.open.lock();
+    JOURNAL.append(&JournalRecord::Begin {
+        txn,
+        op: op.clone(),
+    });
+    open.insert(txn, op);
+    drop(open);
+
+    Transaction {
+        txn,
+        committed: false,
+    }
+}
+
+fn finish(txn: u64) {
+    let mut open = JOURNAL.open.lock();
+    open.remove(&txn);
+
+    // Nothing in flight means nothing to recover; keep the file from growing
+    if open.is_empty() {
+        let _ = std::fs::write(&JOURNAL.path, "");
+    } else {
+        JOURNAL.append(&JournalRecord::Commit { txn });
+    }
+}
+
+/// Roll back operations left incomplete by an unclean shutdown. Must run
+/// once no other instance can be mid-operation, and before plugins are
+/// scanned; a store loaded earlier already fell back to its backup if it
+/// was torn. Returns what was rolled back.
+pub fn recover() -> Vec<JournalOp> {
+    let content = match std::fs::read_to_string(&JOURNAL.path) {
+        Ok(content) => content,
+        Err(_) => return Vec::new(),
+    };
+
+    let mut pending: Vec<(u64, JournalOp)> = Vec::new();
+    for line in content.lines().filter(|l| !l.trim().is_empty()) {
+        // A torn last line means we crashed mid-append; the op never started
+        match serde_json::from_str::<JournalRecord>(line) {
+            Ok(JournalRecord::Begin { txn, op }) => pending.push((txn, op)),
+            Ok(JournalRecord::Commit { txn }) => pending.retain(|(t, _)| *t != txn),
+            Err(_) => {}
+        }
+    }
+
+    let ops: Vec<JournalOp> = pending.into_iter().map(|(_, op)| op).collect();
+    for op in &ops {
+        eprintln!("Recovering from incomplete operation: {:?}", op);
+        rollback(op);
+    }
+
+    let _ = std::fs::write(&JOURNAL.path, "");
+    ops
+}
+
+fn rollback(op: &JournalOp) {
+    match op {
+        JournalOp::StoreWrite { path } => {
+            let _ = std::fs::remove_file(temp_path(path));
+            if std::fs::read_to_string(path)
+                .ok()
+                .and_then(|c| serde_json::from_str::<serde_json::Value>(&c).ok())
+                .is_none()
+            {
+                restore_backup(path);
+            }
+        }
+        JournalOp::PluginInstall { plugin_dir } => {
+            let _ = std::fs::remove_dir_all(plugin_dir);
+        }
+    }
+}
+
+fn backup_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".bak");
+    path.with_file_name(name)
+}
+
+fn temp_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".tmp");
+    path.with_file_name(name)
+}
+
+fn restore_backup(path: &Path) -> bool {
+    let backup = backup_path(path);
+    if backup.exists() && std::fs::copy(&backup, path).is_ok() {
+        eprintln!("Restored {} from backup", path.display());
+        true
+    } else {
+        false
+    }
+}
+
+/// Write a JSON store atomically, keeping the previous good version as `.bak`
+pub fn write_json_store(path: &Path, contents: &str) -> Result<(), String> {
+    let txn = begin(JournalOp::StoreWrite {
+        path: path.to_path_buf(),
+    });
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+
+    let temp = temp_path(path);
+    {
+        let mut file = std::fs::File::create(&temp).map_err(|e| e.to_string())?;
+        file.write_all(contents.as_bytes())
+            .map_err(|e| e.to_string())?;
+        file.sync_all().map_err(|e| e.to_string())?;
+    }
+
+    // Only back up a file that is itself intact, never overwrite a good
+    // backup with a corrupted one
+    let current_is_valid = std::fs::read_to_string(path)
+        .ok()
+        .and_then(|c| serde_json::from_str::<serde_json::Value>(&c).ok())
+        .is_some();
+    if current_is_valid {
+        let _ = std::fs::copy(path, backup_path(path));
+    }
+
+    std::fs::rename(&temp, path).map_err(|e| e.to_string())?;
+    txn.commit();
+    Ok(())
+}
+
+/// Load a JSON store, falling back to (and restoring) the last good backup
+/// if the file is corrupted
+pub fn load_json_store<T: DeserializeOwned>(path: &Path) -> Option<T> {
+    if let Ok(content) = std::fs::read_to_string(path) {
+        match serde_json::from_str(&content) {
+            Ok(value) => return Some(value),
+            Err(e) => eprintln!("{} failed integrity check: {}", path.display(), e),
+        }
+    } else if !backup_path(path).exists() {
+        return None;
+    }
+
+    let content = std::fs::read_to_string(backup_path(path)).ok()?;
+    let value = serde_json::from_str(&content).ok()?;
+    restore_backup(path);
+    Some(value)
+}
@@ -0,0 +1,263 @@
+//! How long searches take, overall and per provider. Each provider has a
+//! budget, by default the time `search` waits before answering with what it
+//! has; one that misses it `SLOW_AFTER` searches in a row is reported so the
+//! UI can suggest disabling or reconfiguring it. Aggregates are kept on disk
+//! for the diagnostics report.
+
+use crate::settings::SettingsStore;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Searches in a row over budget before a provider is reported as slow
+const SLOW_AFTER: usize = 3;
+/// Recent durations kept for percentiles
+const SAMPLES: usize = 200;
+
+/// How long one provider took for one search
+#[derive(Debug, Clone, Serialize)]
+pub struct ProviderTiming {
+    pub provider_id: String,
+    pub duration_ms: u64,
+    /// False when a newer query cancelled it first; the duration is then
+    /// how long it had been running
+    pub completed: bool,
+}
+
+/// Timing for one search, once every provider has answered
+#[derive(Debug, Clone, Serialize)]
+pub struct SearchTiming {
+    /// Characters in the query; the query itself isn't reported
+    pub query_len: usize,
+    pub total_ms: u64,
+    pub providers: Vec<ProviderTiming>,
+}
+
+/// A provider missed its budget `SLOW_AFTER` searches in a row
+#[derive(Debug, Clone, Serialize)]
+pub struct ProviderSlow {
+    pub provider_id: String,
+    pub budget_ms: u64,
+    /// The slowest of the searches that missed the budget
+    pub slowest_ms: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LatencyStats {
+    /// Provider id; None for whole searches
+    pub provider_id: Option<String>,
+    pub searches: u64,
+    pub over_budget: u64,
+    pub mean_ms: u64,
+    pub p50_ms: u64,
+    pub p95_ms: u64,
+    pub max_ms: u64,
+    pub budget_ms: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LatencyReport {
+    pub searches: LatencyStats,
+    /// Slowest first, by p95
+    pub providers: Vec<LatencyStats>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct Aggregate {
+    searches: u64,
+    over_budget: u64,
+    total_ms: u64,
+    max_ms: u64,
+    /// Newest last
+    recent: VecDeque<u64>,
+    /// Durations over budget since the last search within it
+    #[serde(skip)]
+    streak: Vec<u64>,
+}
+
+impl Aggregate {
+    fn add(&mut self, ms: u64, over_budget: bool) {
+        self.searches += 1;
+        self.total_ms += ms;
+        self.max_ms = self.max_ms.max(ms);
+        if over_budget {
+            self.over_budget += 1;
+        }
+        self.recent.push_back(ms);
+        if self.recent.len() > SAMPLES {
+            self.recent.pop_front();
+        }
+    }
+
+    fn stats(&self, provider_id: Option<String>, budget: Duration) -> LatencyStats {
+        let mut sorted: Vec<u64> = self.recent.iter().copied().collect();
+        sorted.sort_unstable();
+        let percentile = |p: usize| {
+            sorted
+                .get(sorted.len().saturating_sub(1) * p / 100)
+                .copied()
+                .unwrap_or(0)
+        };
+        LatencyStats {
+            provider_id,
+            searches: self.searches,
+            over_budget: self.over_budget,
+            mean_ms: self.total_ms.checked_div(self.searches).unwrap_or(0),
+            p50_ms: percentile(50),
+            p95_ms: percentile(95),
+            max_ms: self.max_ms,
+            budget_ms: budget.as_millis() as u64,
+        }
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct LatencyData {
+    searches: Aggregate,
+    providers: HashMap<String, Aggregate>,
+}
+
+impl LatencyData {
+    fn record_provider(
+        &mut self,
+        provider_id: &str,
+        elapsed: Duration,
+        completed: bool,
+        budget: Duration,
+    ) -> Option<ProviderSlow> {
+        let over_budget = elapsed > budget;
+        // Cancelled while still within budget says nothing about its speed
+        if !completed && !over_budget {
+            return None;
+        }
+        let ms = elapsed.as_millis() as u64;
+        let entry = self.providers.entry(provider_id.to_string()).or_default();
+        entry.add(ms, over_budget);
+
+        if !over_budget {
+            entry.streak.clear();
+            return None;
+        }
+        entry.streak.push(ms);
+        // Once per streak, not on every search after it
+        (entry.streak.len() == SLOW_AFTER).then(|| ProviderSlow {
+            provider_id: provider_id.to_string(),
+            budget_ms: budget.as_millis() as u64,
+            slowest_ms: entry.streak.iter().copied().max().unwrap_or(ms),
+        })
+    }
+}
+
+pub struct LatencyTracker {
+    data: Mutex<LatencyData>,
+    settings: Arc<SettingsStore>,
+    default_budget: Duration,
+    path: PathBuf,
+}
+
+impl LatencyTracker {
+    pub fn new(settings: Arc<SettingsStore>, default_budget: Duration) -> Self {
+        let path = dirs::data_dir()
+            .unwrap_or_else(|| PathBuf::from("."))
+            .join("launcher")
+            .join("search_latency.json");
+
+        Self {
+            data: Mutex::new(crate::journal::load_json_store(&path).unwrap_or_default()),
+            settings,
+            default_budget,
+            path,
+        }
+    }
+
+    fn budget(&self, provider_id: &str) -> Duration {
+        self.settings
+            .get()
+            .provider_budgets_ms
+            .get(provider_id)
+            .map_or(self.default_budget, |ms| Duration::from_millis(*ms))
+    }
+
+    /// Record how long a provider took; returns an event to raise if this
+    /// made it slow
+    pub fn record_provider(
+        &self,
+        provider_id: &str,
+        elapsed: Duration,
+        completed: bool,
+    ) -> Option<ProviderSlow> {
+        let budget = self.budget(provider_id);
+        self.data
+            .lock()
+            .record_provider(provider_id, elapsed, completed, budget)
+    }
+
+    /// Record how long a whole search took, until its last provider answered
+    pub fn record_search(&self, elapsed: Duration) {
+        let over_budget = elapsed > self.default_budget;
+        self.data
+            .lock()
+            .searches
+            .add(elapsed.as_millis() as u64, over_budget);
+    }
+
+    pub fn report(&self) -> LatencyReport {
+        let data = self.data.lock();
+        let mut providers: Vec<LatencyStats> = data
+            .providers
+            .iter()
+            .map(|(id, entry)| entry.stats(Some(id.clone()), self.budget(id)))
+            .collect();
+        providers.sort_by(|a, b| b.p95_ms.cmp(&a.p95_ms));
+        LatencyReport {
+            searches: data.searches.stats(None, self.default_budget),
+            providers,
+        }
+    }
+
+    pub fn save(&self) {
+        let json = serde_json::to_string_pretty(&*self.data.lock());
+        if let Ok(json) = json {
+            if let Err(e) = crate::journal::write_json_store(&self.path, &json) {
+                eprintln!("Failed to save search latency: {}", e);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reports_a_provider_once_per_slow_streak() {
+        let budget = Duration::from_millis(150);
+        let ms = Duration::from_millis;
+        let mut data = LatencyData::default();
+
+        assert!(data.record_provider("web", ms(400), true, budget).is_none());
+        assert!(data.record_provider("web", ms(900), true, budget).is_none());
+        // Cancelled early: not counted at all
+        assert!(data.record_provider("web", ms(20), false, budget).is_none());
+        let slow = data.record_provider("web", ms(300), false, budget).unwrap();
+        assert_eq!(slow.slowest_ms, 900);
+        assert_eq!(slow.budget_ms, 150);
+        assert!(data.record_provider("web", ms(500), true, budget).is_none());
+
+        // Back within budget starts a new streak
+        assert!(data.record_provider("web", ms(50), true, budget).is_none());
+        for _ in 0..2 {
+            assert!(data.record_provider("web", ms(200), true, budget).is_none());
+        }
+        assert!(data.record_provider("web", ms(200), true, budget).is_some());
+
+        let stats = data.providers["web"].stats(Some("web".to_string()), budget);
+        assert_eq!(stats.searches, 8);
+        assert_eq!(stats.over_budget, 7);
+        assert_eq!(stats.max_ms, 900);
+        assert_eq!(stats.p50_ms, 200);
+    }
+}
@@ -1,40 +1,160 @@
+mod aliases;
+mod audit;
 mod auth;
+mod ax;
+mod cache;
+mod capture;
+mod changes;
+mod cleanup;
+mod clipboard;
 mod codex;
 mod commands;
 mod config;
+mod context;
+mod currency;
+mod dates;
+mod devices;
+mod events;
+mod exec_policy;
+mod export;
+mod file_ops;
+mod focus;
 mod frecency;
 mod indexer;
+mod intent;
+mod journal;
+mod latency;
+mod locale;
+mod notifications;
 mod oauth;
+mod ocr;
 mod plugins;
+mod power;
+mod programmer;
 mod providers;
+mod ranking;
+mod reminders;
+mod scheduler;
+mod search_cache;
+mod secrets;
+mod sections;
 mod settings;
+mod startup;
 mod terminal;
 mod theme;
-
+mod thumbnails;
+mod trust;
+mod undo;
+mod uninstall;
+mod vault;
+mod weather;
+mod window_manager;
+mod workspaces;
+
+use audit::AuditEvent;
 use auth::{AuthState, WebAuth};
+use cache::{CacheCoordinator, CacheUsageReport};
+use capture::{CaptureMode, RecordingInfo};
+use changes::{Change, ChangeKind, ChangeLog};
+use cleanup::{CleanupPreview, CleanupProgress, CleanupReport, CleanupTask, LargestEntries};
 use codex::{
     BunInstallStatus, CodexAuthStatus, CodexManager, CodexStatus, DevServerInfo, PackageManager,
     PackageManagerInfo, SessionInfo, SessionMessage,
 };
 use commands::{Command, CommandRegistry};
-use frecency::FrecencyStore;
+use context::{ActiveContext, ContextTracker};
+use currency::ExchangeRates;
+use devices::ConnectedDevice;
+use events::{AppEvent, EventBus, IndexingStatus};
+use exec_policy::ExecPolicy;
+use export::{ExportFormat, ExportedResult};
+use file_ops::{FileOpProgress, RenamePreview, RenameRule};
+use focus::{FocusManager, FocusStatus};
+use frecency::{FrecencyStats, FrecencyStore};
+use intent::{IntentMatch, IntentRouter, IntentRule, IntentTarget};
+use journal::JournalOp;
+use latency::{LatencyReport, LatencyTracker, ProviderTiming, SearchTiming};
 use oauth::providers::{
     GitHubProvider as OAuthGitHubConfig, GoogleProvider as OAuthGoogleConfig,
     NotionProvider as OAuthNotionConfig, OAuthProvider, SlackProvider as OAuthSlackConfig,
 };
-use oauth::{CallbackServer, OAuthFlow, TokenStorage};
+use oauth::{CallbackServer, OAuthFlow, OAuthProviderConfig, TokenStorage};
+use ocr::OcrStatus;
+use plugins::health::PluginHealthReport;
+use plugins::host_api::{PluginHostApi, PluginStorageUsage, HOST_API};
+use plugins::inspect::PluginInspection;
+use plugins::registry::PluginSource;
+use plugins::runtime::PluginReloaded;
 use plugins::{
     MarketplaceResponse, PluginInfo, PluginLoader, PluginRegistry, PluginRuntime, RegistryPlugin,
 };
+use power::{BatteryReport, PowerMonitor, PowerStatus, PowerSubsystem};
 use providers::{
-    apps::AppProvider, calculator::CalculatorProvider, files::FileProvider, github::GitHubProvider,
-    google_calendar::GoogleCalendarProvider, google_drive::GoogleDriveProvider,
-    notion::NotionProvider, plugins::PluginProvider, slack::SlackProvider,
-    system::SystemProvider, url::UrlProvider, websearch::WebSearchProvider,
-    SearchProvider, SearchResult,
+    apps::AppProvider,
+    battery::BatteryProvider,
+    bluetooth::BluetoothProvider,
+    browse::{BrowseProvider, DirectoryPage},
+    calculator::CalculatorProvider,
+    capture::{CaptureProvider, CaptureSaved},
+    cleanup::CleanupProvider,
+    clipboard_history::{ClipboardEntry, ClipboardProvider},
+    content::ContentSearchProvider,
+    dictionary::{DictionaryEntry, DictionaryProvider, DictionaryStatus},
+    disk_usage::DiskUsageProvider,
+    docs::{DocsProvider, Docset},
+    fake_data::FakeDataProvider,
+    fallbacks::FallbackProvider,
+    feeds::{FeedItem, FeedProvider, FeedSubscription},
+    files::FileProvider,
+    focus::FocusProvider,
+    github::GitHubProvider,
+    google_calendar::GoogleCalendarProvider,
+    google_drive::GoogleDriveProvider,
+    home_assistant::{HaEntity, HomeAssistantProvider, HomeAssistantStatus},
+    http::{HttpProvider, HttpRequest, HttpResponse},
+    json::JsonProvider,
+    menu::MenuSearchProvider,
+    notion::NotionProvider,
+    ocr::OcrProvider,
+    packages::{PackageProvider, PackageTrackingStatus, TrackedPackage},
+    plugins::PluginProvider,
+    processes::ProcessProvider,
+    qr::QrProvider,
+    quicklinks::QuicklinkProvider,
+    regex_tester::RegexProvider,
+    registries::{Ecosystem, PackageInfo, RegistryProvider},
+    reminders::ReminderProvider,
+    remote::{RemoteEntry, RemoteHealth, RemoteLocation, RemoteProvider},
+    rename::{self, RenameProvider},
+    settings_panes::SettingsPaneProvider,
+    slack::SlackProvider,
+    stackoverflow::StackOverflowProvider,
+    system::SystemProvider,
+    text_tools::TextToolsProvider,
+    time_tracking::{RunningTimer, TimeTrackingProvider, TimeTrackingStatus},
+    totp::{TotpCode, TotpProvider},
+    transport::{TransportProvider, TransportSourceInfo},
+    url::UrlProvider,
+    vpn::{VpnProvider, VpnStatus},
+    weather::WeatherProvider,
+    websearch::WebSearchProvider,
+    window_manager::WindowManagerProvider,
+    windows::WindowsProvider,
+    workspaces::WorkspaceProvider,
+    ResultAction, ResultCategory, ResultContext, ResultIcon, ResultMutation, SearchContext,
+    SearchProvider, SearchResult, ASK_AI_ACTION, DEFAULT_ACTION,
 };
+use ranking::ProviderResults;
+use reminders::{Reminder, ReminderStore};
+use scheduler::{RunReason, ScheduleRun, Scheduler};
+use search_cache::{CachePolicy, SearchCache};
+use sections::{GroupedSearchResponse, ResultSection};
 use serde::{Deserialize, Serialize};
-use settings::{SettingsStore, UserSettings, WidgetPlacement};
+use settings::{
+    DeviceBus, Fallback, ProviderSettings, Quicklink, Sandbox, Schedule, ScheduleTarget,
+    SearchAlias, SettingsStore, UserSettings, WidgetPlacement,
+};
+use startup::StartupMode;
 use std::sync::Arc;
 use tauri::{
     image::Image,
@@ -43,65 +163,603 @@ use tauri::{
     AppHandle, Emitter, Manager,
 };
 use tauri_plugin_deep_link::DeepLinkExt;
+use tauri_plugin_dialog::{DialogExt, MessageDialogButtons, MessageDialogKind};
 use tauri_plugin_global_shortcut::{Code, GlobalShortcutExt, Modifiers, Shortcut, ShortcutState};
-use theme::SystemTheme;
+use theme::{AccessibilityPreferences, SystemTheme};
+use thumbnails::Thumbnails;
+use trust::{self, TrustedFolder, WorkspaceTrust};
+use undo::{UndoEntry, UndoStack, UndoableAction};
+use vault::SecretVault;
+use weather::{AirQualityReport, WeatherClient, WeatherReport};
+use window_manager::WindowAction;
 
 struct AppState {
     providers: Vec<Arc<dyn SearchProvider>>,
+    /// Cancels the in-flight search when the next keystroke arrives
+    search_cancel: parking_lot::Mutex<tokio_util::sync::CancellationToken>,
+    /// The last grouped search's query and ranked results, which
+    /// `search_section` pages through instead of searching again
+    grouped_results: parking_lot::Mutex<Option<(String, Vec<SearchResult>)>>,
     file_provider: Arc<FileProvider>,
+    remote_provider: Arc<RemoteProvider>,
+    rename_provider: Arc<RenameProvider>,
+    browse_provider: Arc<BrowseProvider>,
+    windows_provider: Arc<WindowsProvider>,
+    feed_provider: Arc<FeedProvider>,
+    clipboard_provider: Arc<ClipboardProvider>,
+    capture_provider: Arc<CaptureProvider>,
+    cleanup_provider: Arc<CleanupProvider>,
+    ocr_provider: Arc<OcrProvider>,
+    dictionary_provider: Arc<DictionaryProvider>,
+    docs_provider: Arc<DocsProvider>,
+    json_provider: Arc<JsonProvider>,
+    qr_provider: Arc<QrProvider>,
+    totp_provider: Arc<TotpProvider>,
+    time_tracking_provider: Arc<TimeTrackingProvider>,
+    home_assistant_provider: Arc<HomeAssistantProvider>,
+    weather_client: Arc<WeatherClient>,
+    transport_provider: Arc<TransportProvider>,
+    package_provider: Arc<PackageProvider>,
+    registry_provider: Arc<RegistryProvider>,
+    http_provider: Arc<HttpProvider>,
+    battery_provider: Arc<BatteryProvider>,
+    app_provider: Arc<AppProvider>,
+    vpn_provider: Arc<VpnProvider>,
+    bluetooth_provider: Arc<BluetoothProvider>,
+    plugin_provider: Arc<PluginProvider>,
+    focus_manager: Arc<FocusManager>,
+    reminders: Arc<ReminderStore>,
+    scheduler: Arc<Scheduler>,
     frecency: Arc<FrecencyStore>,
+    latency: Arc<LatencyTracker>,
     settings: Arc<SettingsStore>,
+    change_log: Arc<ChangeLog>,
+    undo_stack: Arc<UndoStack>,
+    exec_policy: Arc<ExecPolicy>,
     plugin_loader: Arc<PluginLoader>,
     plugin_runtime: Arc<PluginRuntime>,
     plugin_registry: Arc<PluginRegistry>,
     command_registry: Arc<CommandRegistry>,
+    intent_router: Arc<IntentRouter>,
+    context_tracker: Arc<ContextTracker>,
     oauth_flow: Arc<OAuthFlow>,
     callback_server: Arc<CallbackServer>,
     web_auth: Arc<WebAuth>,
     codex_manager: Arc<CodexManager>,
     terminal_manager: Arc<terminal::TerminalManager>,
+    workspace_trust: Arc<WorkspaceTrust>,
+    power_monitor: Arc<PowerMonitor>,
+    cache_coordinator: Arc<CacheCoordinator>,
+    search_cache: Arc<SearchCache>,
+    thumbnails: Arc<Thumbnails>,
+    event_bus: Arc<EventBus>,
+    startup_mode: StartupMode,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-struct IndexingStatus {
-    is_indexing: bool,
-    files_indexed: usize,
-    message: String,
+/// Results for a recognized natural-language intent; ranking puts them first
+async fn intent_results(
+    intent: &IntentMatch,
+    providers: &[Arc<dyn SearchProvider>],
+    ctx: &SearchContext,
+) -> Vec<SearchResult> {
+    match &intent.target {
+        IntentTarget::Provider { provider, query } => {
+            match providers.iter().find(|p| p.id() == provider) {
+                Some(p) => p.search(query, ctx).await,
+                None => Vec::new(),
+            }
+        }
+        IntentTarget::Result {
+            id,
+            title,
+            subtitle,
+            icon,
+        } => vec![SearchResult {
+            id: id.clone(),
+            title: title.clone(),
+            subtitle: subtitle.clone(),
+            icon: ResultIcon::Emoji(icon.clone().unwrap_or_else(|| "✨".to_string())),
+            category: ResultCategory::Command,
+            score: 0.0,
+            aria_label: None,
+        }],
+    }
 }
 
-#[tauri::command]
-fn search(query: &str, state: tauri::State<AppState>) -> Vec<SearchResult> {
-    let mut all_results: Vec<SearchResult> = Vec::new();
+/// How long `search` waits for slow providers before answering with what it
+/// has; the rest arrive through the `search-results-updated` event
+const SEARCH_BUDGET: std::time::Duration = std::time::Duration::from_millis(150);
+
+const SEARCH_SUPERSEDED: &str = "Search superseded by a newer query";
+
+/// Provider queries in flight for one keystroke
+struct ProviderSearch {
+    ctx: SearchContext,
+    /// Answers from the search cache, collected with the first batch
+    cached: Vec<ProviderResults>,
+    tasks: tokio::task::JoinSet<ProviderResults>,
+    started: std::time::Instant,
+    query_len: usize,
+    timings: Arc<parking_lot::Mutex<Vec<ProviderTiming>>>,
+}
+
+impl ProviderSearch {
+    /// Cancel the previous keystroke's search and query every provider on its
+    /// own task, so a slow one can't hold up the rest. Providers whose
+    /// results for the query are cached aren't asked.
+    fn start(query: &str, state: &AppState) -> Self {
+        let ctx = SearchContext::new();
+        let previous = std::mem::replace(&mut *state.search_cancel.lock(), ctx.cancel.clone());
+        previous.cancel();
+        Self::start_with(query, state, ctx)
+    }
+
+    /// A search apart from the keystroke search, like an export: it doesn't
+    /// cancel the search in flight, and the next keystroke doesn't cancel it
+    fn start_detached(query: &str, state: &AppState) -> Self {
+        Self::start_with(query, state, SearchContext::new())
+    }
+
+    fn start_with(query: &str, state: &AppState, ctx: SearchContext) -> Self {
+        // Providers turned off in settings aren't asked, even by an intent
+        let settings = state.settings.get();
+        let providers: Vec<Arc<dyn SearchProvider>> = state
+            .providers
+            .iter()
+            .filter(|provider| settings.provider(provider.id()).enabled)
+            .cloned()
+            .collect();
+
+        let mut tasks = tokio::task::JoinSet::new();
+        if let Some(intent) = state.intent_router.route(query) {
+            let providers = providers.clone();
+            let ctx = ctx.clone();
+            tasks.spawn(async move {
+                let results = tokio::select! {
+                    _ = ctx.cancel.cancelled() => Vec::new(),
+                    results = intent_results(&intent, &providers, &ctx) => results,
+                };
+                (None, results)
+            });
+        }
+        let timings = Arc::new(parking_lot::Mutex::new(Vec::new()));
+        let mut cached = Vec::new();
+        for provider in providers {
+            let policy = provider.cache_policy(query);
+            if policy != CachePolicy::Never {
+                if let Some(results) = state.search_cache.get(provider.id(), query) {
+                    cached.push((Some(provider.id().to_string()), results));
+                    continue;
+                }
+            }
+            // A shorter query's results stand in until the provider answers
+            if policy == CachePolicy::Remote {
+                if let Some(results) = state.search_cache.seed(provider.id(), query) {
+                    cached.push((Some(provider.id().to_string()), results));
+                }
+            }
+
+            let query = query.to_string();
+            let ctx = ctx.clone();
+            let latency = state.latency.clone();
+            let events = state.event_bus.clone();
+            let timings = timings.clone();
+            let search_cache = state.search_cache.clone();
+            tasks.spawn(async move {
+                // Wait for typing to pause; the next keystroke cancels this
+                let debounce = policy.debounce();
+                if !debounce.is_zero() {
+                    tokio::select! {
+                        _ = ctx.cancel.cancelled() => {
+                            return (Some(provider.id().to_string()), Vec::new());
+                        }
+                        _ = tokio::time::sleep(debounce) => {}
+                    }
+                }
+
+                let started = std::time::Instant::now();
+                let (results, completed) = tokio::select! {
+                    _ = ctx.cancel.cancelled() => (Vec::new(), false),
+                    results = provider.search(&query, &ctx) => (results, true),
+                };
+                let elapsed = started.elapsed();
+                if let Some(slow) = latency.record_provider(provider.id(), elapsed, completed) {
+                    events.publish(AppEvent::ProviderSlow(slow));
+                }
+                timings.lock().push(ProviderTiming {
+                    provider_id: provider.id().to_string(),
+                    duration_ms: elapsed.as_millis() as u64,
+                    completed,
+                });
+                if completed {
+                    search_cache.store(provider.id(), &query, &results, policy);
+                }
+                (Some(provider.id().to_string()), results)
+            });
+        }
+
+        Self {
+            ctx,
+            cached,
+            tasks,
+            started: std::time::Instant::now(),
+            query_len: query.chars().count(),
+            timings,
+        }
+    }
 
-    for provider in &state.providers {
-        let results = provider.search(query);
-        all_results.extend(results);
+    /// Wait for providers until they're all done, the budget runs out, or a
+    /// newer query cancels this one. A provider's answer replaces what stood
+    /// in for it.
+    async fn collect(&mut self, budget: Option<std::time::Duration>) -> Vec<ProviderResults> {
+        let deadline = async {
+            match budget {
+                Some(budget) => tokio::time::sleep(budget).await,
+                None => std::future::pending().await,
+            }
+        };
+        tokio::pin!(deadline);
+
+        let mut finished = std::mem::take(&mut self.cached);
+        loop {
+            let next = tokio::select! {
+                _ = self.ctx.cancel.cancelled() => break,
+                _ = &mut deadline => break,
+                next = self.tasks.join_next() => next,
+            };
+            match next {
+                Some(Ok(results)) => merge_results(&mut finished, results),
+                Some(Err(e)) => eprintln!("Search provider task failed: {}", e),
+                None => break,
+            }
+        }
+        finished
+    }
+
+    fn is_done(&self) -> bool {
+        self.tasks.is_empty()
+    }
+
+    /// Record the finished search's timing and send it out
+    fn report_timing(&self, state: &AppState) {
+        let total = self.started.elapsed();
+        state.latency.record_search(total);
+        let providers = std::mem::take(&mut *self.timings.lock());
+        state
+            .event_bus
+            .publish(AppEvent::SearchTiming(SearchTiming {
+                query_len: self.query_len,
+                total_ms: total.as_millis() as u64,
+                providers,
+            }));
+    }
+}
+
+/// Add a provider's results, replacing any that stood in for them
+fn merge_results(finished: &mut Vec<ProviderResults>, results: ProviderResults) {
+    if results.0.is_some() {
+        finished.retain(|(provider_id, _)| *provider_id != results.0);
+    }
+    finished.push(results);
+}
+
+/// When nothing but the configured fallbacks came back, search the plugins
+/// that only offer fallback suggestions, like "create a note with this text"
+fn add_plugin_fallbacks(
+    query: &str,
+    search: &ProviderSearch,
+    finished: &mut Vec<ProviderResults>,
+    state: &AppState,
+) {
+    let matched = finished.iter().any(|(provider_id, results)| {
+        provider_id.as_deref() != Some("fallback") && !results.is_empty()
+    });
+    if matched || !state.settings.get().provider("plugins").enabled {
+        return;
     }
+    let results = search
+        .ctx
+        .run_blocking(|| state.plugin_provider.search_fallbacks(query));
+    finished.push((Some("plugins".to_string()), results));
+}
 
+/// Rank provider results, put the result aliased to the query first, and
+/// fill in what screen readers say
+fn rank_results(
+    query: &str,
+    finished: Vec<ProviderResults>,
+    state: &AppState,
+) -> Vec<SearchResult> {
+    let profile = state.context_tracker.active().profile;
+    let settings = state.settings.get();
+    let mut all_results = ranking::rank(finished, &settings, profile.as_ref(), |id| {
+        state.frecency.get_boost(id)
+    });
+    aliases::pin(query, &mut all_results, &settings.aliases);
     for result in &mut all_results {
-        let frecency_boost = state.frecency.get_boost(&result.id);
-        result.score += frecency_boost as f32;
+        result.fill_aria_label();
+    }
+    all_results
+}
+
+/// All results of a started search, ranked and deduplicated but not
+/// truncated
+async fn collect_results(
+    mut search: ProviderSearch,
+    query: &str,
+    state: &AppState,
+) -> Result<Vec<SearchResult>, String> {
+    let mut finished = search.collect(None).await;
+    if search.ctx.is_cancelled() {
+        return Err(SEARCH_SUPERSEDED.to_string());
+    }
+    search.report_timing(state);
+    add_plugin_fallbacks(query, &search, &mut finished, state);
+    Ok(rank_results(query, finished, state))
+}
+
+/// Synchronous query of a single in-memory provider (e.g. listing all apps)
+fn search_provider_now(state: &AppState, provider_id: &str, query: &str) -> Vec<SearchResult> {
+    state
+        .providers
+        .iter()
+        .find(|p| p.id() == provider_id)
+        .map(|p| tauri::async_runtime::block_on(p.search(query, &SearchContext::new())))
+        .unwrap_or_default()
+}
+
+fn plugin_names(state: &AppState) -> std::collections::HashMap<String, String> {
+    state
+        .plugin_loader
+        .list_plugins()
+        .into_iter()
+        .map(|p| (p.id, p.name))
+        .collect()
+}
+
+/// Late results for a query whose fast answer was already returned by `search`
+#[derive(Debug, Clone, Serialize)]
+struct SearchUpdate {
+    query: String,
+    results: Vec<SearchResult>,
+}
+
+#[tauri::command]
+async fn search(
+    query: String,
+    app: AppHandle,
+    state: tauri::State<'_, AppState>,
+) -> Result<Vec<SearchResult>, String> {
+    let mut search = ProviderSearch::start(&query, &state);
+    let mut finished = search.collect(Some(SEARCH_BUDGET)).await;
+    if search.ctx.is_cancelled() {
+        return Err(SEARCH_SUPERSEDED.to_string());
     }
 
-    all_results.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap());
+    // Fallback plugins wait until every provider has had its say
+    if search.is_done() {
+        add_plugin_fallbacks(&query, &search, &mut finished, &state);
+    }
+    let mut all_results = rank_results(&query, finished.clone(), &state);
     all_results.truncate(20);
-    all_results
+    state.thumbnails.prewarm(&all_results);
+
+    // Slow providers keep going; re-rank and push the full list when they finish
+    if search.is_done() {
+        search.report_timing(&state);
+    } else {
+        tauri::async_runtime::spawn(async move {
+            for results in search.collect(None).await {
+                merge_results(&mut finished, results);
+            }
+            if search.ctx.is_cancelled() {
+                return;
+            }
+            let state = app.state::<AppState>();
+            search.report_timing(&state);
+            add_plugin_fallbacks(&query, &search, &mut finished, &state);
+            let mut results = rank_results(&query, finished, &state);
+            results.truncate(20);
+            state.thumbnails.prewarm(&results);
+            let _ = app.emit("search-results-updated", SearchUpdate { query, results });
+        });
+    }
+
+    Ok(all_results)
 }
 
 #[tauri::command]
-fn execute_result(result_id: &str, state: tauri::State<AppState>) -> Result<(), String> {
-    state.frecency.record_access(result_id);
+async fn search_grouped(
+    query: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<GroupedSearchResponse, String> {
+    let search = ProviderSearch::start(&query, &state);
+    let results = collect_results(search, &query, &state).await?;
+    *state.grouped_results.lock() = Some((query.clone(), results.clone()));
+    Ok(GroupedSearchResponse {
+        sections: sections::group_results(results, &plugin_names(&state)),
+        query,
+    })
+}
+
+#[tauri::command]
+async fn search_section(
+    query: String,
+    continuation: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<ResultSection, String> {
+    let grouped = state
+        .grouped_results
+        .lock()
+        .as_ref()
+        .filter(|(grouped_query, _)| *grouped_query == query)
+        .map(|(_, results)| results.clone());
+    let results = match grouped {
+        Some(results) => results,
+        // Searching again mustn't cancel a grouped search still in flight
+        None => {
+            let search = ProviderSearch::start_detached(&query, &state);
+            collect_results(search, &query, &state).await?
+        }
+    };
+    sections::section_page(results, &plugin_names(&state), &continuation)
+}
+
+/// Run the full search for `query` and write every result to `path`,
+/// returning how many were written
+#[tauri::command]
+async fn export_results(
+    query: String,
+    format: ExportFormat,
+    path: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<usize, String> {
+    let search = ProviderSearch::start_detached(&query, &state);
+    let results = collect_results(search, &query, &state).await?;
+    let exported: Vec<ExportedResult> = results
+        .iter()
+        .enumerate()
+        .map(|(i, result)| {
+            let provider = provider_for(&result.id, &state.providers);
+            let context = match provider {
+                Some(provider) => provider.context(result),
+                None => ResultContext::from_result(result),
+            };
+            ExportedResult {
+                rank: i + 1,
+                id: result.id.clone(),
+                title: result.title.clone(),
+                subtitle: result.subtitle.clone(),
+                kind: result.category.spoken_name().to_string(),
+                provider: provider.map(|p| p.id().to_string()),
+                path: context.path,
+                score: result.score,
+            }
+        })
+        .collect();
+
+    let contents = export::render(&query, &exported, format)?;
+    export::write(std::path::Path::new(&path), &contents)?;
+    Ok(exported.len())
+}
 
-    for provider in &state.providers {
-        if result_id.starts_with(&format!("{}:", provider.id()))
+/// The provider that produced a result, going by its id prefix
+fn provider_for<'a>(
+    result_id: &str,
+    providers: &'a [Arc<dyn SearchProvider>],
+) -> Option<&'a Arc<dyn SearchProvider>> {
+    providers.iter().find(|provider| {
+        result_id.starts_with(&format!("{}:", provider.id()))
             || (provider.id() == "apps" && result_id.starts_with("app:"))
             || (provider.id() == "calculator" && result_id.starts_with("calc:"))
             || (provider.id() == "files" && result_id.starts_with("file:"))
-        {
-            return provider.execute(result_id);
+    })
+}
+
+#[tauri::command]
+fn execute_result(result_id: &str, state: tauri::State<AppState>) -> Result<(), String> {
+    state.frecency.record_access(result_id);
+
+    provider_for(result_id, &state.providers)
+        .ok_or("No provider found for result")?
+        .execute(result_id)
+}
+
+/// Everything that can be done with a result: the default "Open" first, then
+/// the provider's own actions, then pinning for apps and asking AI about it
+#[tauri::command]
+fn get_actions(result_id: &str, state: tauri::State<AppState>) -> Vec<ResultAction> {
+    let mut actions = vec![ResultAction::new(DEFAULT_ACTION, "Open").with_shortcut("⏎")];
+    if let Some(provider) = provider_for(result_id, &state.providers) {
+        actions.extend(provider.actions(result_id));
+    }
+    if let Some(path) = result_id.strip_prefix("file:") {
+        let selected = state
+            .rename_provider
+            .is_selected(std::path::Path::new(path));
+        actions.push(if selected {
+            ResultAction::new(rename::SELECT_ACTION, "Deselect for bulk rename")
+        } else {
+            ResultAction::new(rename::SELECT_ACTION, "Select for bulk rename")
+        });
+    }
+    if result_id.starts_with("app:") {
+        let pinned = state.settings.get().pinned_apps;
+        actions.push(if pinned.iter().any(|id| id == result_id) {
+            ResultAction::new("unpin", "Unpin")
+        } else {
+            ResultAction::new("pin", "Pin")
+        });
+    }
+    actions.push(ResultAction::new(ASK_AI_ACTION, "Ask AI about this").with_shortcut("⌘J"));
+    actions
+}
+
+#[tauri::command]
+fn execute_action(
+    app: AppHandle,
+    result_id: &str,
+    action_id: &str,
+    state: tauri::State<AppState>,
+) -> Result<(), String> {
+    match action_id {
+        DEFAULT_ACTION => execute_result(result_id, state),
+        // Archive work can take a while; it reports back when it's done
+        rename::SELECT_ACTION if result_id.starts_with("file:") => {
+            let path = result_id.trim_start_matches("file:");
+            state.rename_provider.toggle(std::path::Path::new(path));
+            Ok(())
+        }
+        "compress" | "extract" if result_id.starts_with("file:") => {
+            let path = result_id.trim_start_matches("file:");
+            run_file_action(app, std::path::PathBuf::from(path), action_id);
+            Ok(())
+        }
+        "pin" if result_id.starts_with("app:") => {
+            state.settings.pin_app(result_id.to_string());
+            Ok(())
+        }
+        "unpin" if result_id.starts_with("app:") => {
+            unpin_app(result_id.to_string(), state);
+            Ok(())
         }
+        ASK_AI_ACTION => Err("Ask AI runs from the launcher window".to_string()),
+        _ => provider_for(result_id, &state.providers)
+            .ok_or("No provider found for result")?
+            .execute_action(result_id, action_id),
+    }
+}
+
+/// Inline edits a result offers, by `ResultMutation` type, e.g. `rename`
+#[tauri::command]
+fn get_result_mutations(result_id: &str, state: tauri::State<AppState>) -> Vec<&'static str> {
+    provider_for(result_id, &state.providers)
+        .map(|provider| provider.mutations(result_id))
+        .unwrap_or_default()
+}
+
+/// Edit a result in place, like renaming a file, and return it as it is
+/// now; its id may have changed. The edit goes on the undo stack.
+#[tauri::command]
+async fn mutate_result(
+    result_id: String,
+    mutation: ResultMutation,
+    state: tauri::State<'_, AppState>,
+) -> Result<SearchResult, String> {
+    let provider = provider_for(&result_id, &state.providers)
+        .ok_or("No provider found for result")?
+        .clone();
+    if !provider.mutations(&result_id).contains(&mutation.kind()) {
+        return Err("This result can't be edited that way".to_string());
     }
-    Err("No provider found for result".to_string())
+
+    let mutated = tokio::task::spawn_blocking(move || provider.mutate(&result_id, &mutation))
+        .await
+        .map_err(|e| e.to_string())??;
+    state.undo_stack.record(mutated.undo);
+    let mut result = mutated.result;
+    result.fill_aria_label();
+    Ok(result)
 }
 
 #[tauri::command]
@@ -109,6 +767,18 @@ fn get_system_theme() -> SystemTheme {
     theme::get_system_theme()
 }
 
+/// Reduced motion and high contrast as the OS has them, unless overridden
+/// in settings
+#[tauri::command]
+fn get_accessibility_preferences(state: tauri::State<AppState>) -> AccessibilityPreferences {
+    let overrides = state.settings.get().accessibility;
+    let system = theme::get_system_accessibility();
+    AccessibilityPreferences {
+        reduce_motion: overrides.reduce_motion.unwrap_or(system.reduce_motion),
+        high_contrast: overrides.high_contrast.unwrap_or(system.high_contrast),
+    }
+}
+
 #[tauri::command]
 fn hide_window(app: AppHandle) {
     if let Some(window) = app.get_webview_window("main") {
@@ -125,20 +795,16 @@ fn show_window(app: AppHandle) {
 }
 
 #[tauri::command]
-async fn start_indexing(
-    app: AppHandle,
-    state: tauri::State<'_, AppState>,
-) -> Result<usize, String> {
+async fn start_indexing(state: tauri::State<'_, AppState>) -> Result<usize, String> {
     let file_provider = state.file_provider.clone();
 
-    let _ = app.emit(
-        "indexing-status",
-        IndexingStatus {
+    state
+        .event_bus
+        .publish(AppEvent::IndexingStatus(IndexingStatus {
             is_indexing: true,
             files_indexed: 0,
             message: "Starting file indexing...".to_string(),
-        },
-    );
+        }));
 
     let result = tokio::task::spawn_blocking(move || file_provider.initialize())
         .await
@@ -146,14 +812,13 @@ async fn start_indexing(
 
     let count = result?;
 
-    let _ = app.emit(
-        "indexing-status",
-        IndexingStatus {
+    state
+        .event_bus
+        .publish(AppEvent::IndexingStatus(IndexingStatus {
             is_indexing: false,
             files_indexed: count,
             message: format!("Indexed {} files", count),
-        },
-    );
+        }));
 
     Ok(count)
 }
@@ -170,7 +835,14 @@ fn list_plugins(state: tauri::State<AppState>) -> Vec<PluginInfo> {
 
 #[tauri::command]
 fn enable_plugin(id: &str, state: tauri::State<AppState>) -> Result<(), String> {
+    enable_and_load_plugin(&state, id)
+}
+
+fn enable_and_load_plugin(state: &AppState, id: &str) -> Result<(), String> {
     state.plugin_loader.enable_plugin(id)?;
+    record_plugin_change(state, id, ChangeKind::PluginEnabled, None, None);
+    // Enabling a quarantined plugin gives it another chance
+    state.plugin_runtime.reset_health(id);
     if let Some(plugin) = state.plugin_loader.get_plugin(id) {
         if !state.plugin_runtime.is_loaded(id) {
             state.plugin_runtime.load_plugin(&plugin)?;
@@ -183,9 +855,119 @@ fn enable_plugin(id: &str, state: tauri::State<AppState>) -> Result<(), String>
 fn disable_plugin(id: &str, state: tauri::State<AppState>) -> Result<(), String> {
     state.plugin_loader.disable_plugin(id)?;
     state.plugin_runtime.unload_plugin(id)?;
+    record_plugin_change(&state, id, ChangeKind::PluginDisabled, None, None);
+    let name = state
+        .plugin_loader
+        .plugin_name(id)
+        .unwrap_or_else(|| id.to_string());
+    state.undo_stack.record(UndoableAction::DisablePlugin {
+        id: id.to_string(),
+        name,
+    });
     Ok(())
 }
 
+/// Add a plugin change to the history, naming the plugin if it's known
+fn record_plugin_change(
+    state: &AppState,
+    id: &str,
+    kind: ChangeKind,
+    from: Option<String>,
+    to: Option<String>,
+) {
+    let name = state
+        .plugin_loader
+        .plugin_name(id)
+        .unwrap_or_else(|| id.to_string());
+    state.change_log.record(kind, &name, from, to);
+}
+
+/// Recent errors of a plugin and whether it was disabled for them
+#[tauri::command]
+fn get_plugin_health(id: &str, state: tauri::State<AppState>) -> PluginHealthReport {
+    state.plugin_runtime.health(id)
+}
+
+/// A plugin's user-editable settings: the schema from its manifest and the
+/// current values
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct PluginSettingsValues {
+    schema: Option<serde_json::Value>,
+    values: serde_json::Map<String, serde_json::Value>,
+}
+
+#[tauri::command]
+fn get_plugin_config(
+    id: &str,
+    state: tauri::State<AppState>,
+) -> Result<PluginSettingsValues, String> {
+    let plugin = state
+        .plugin_loader
+        .get_plugin(id)
+        .ok_or_else(|| format!("Plugin not found: {}", id))?;
+    let schema = plugin.manifest.settings_schema;
+    let values = match &schema {
+        Some(schema) => plugins::config_schema::current_values(schema, &HOST_API.get_config(id)),
+        None => serde_json::Map::new(),
+    };
+    Ok(PluginSettingsValues { schema, values })
+}
+
+/// Save a plugin's settings after checking them against its schema
+#[tauri::command]
+fn set_plugin_config(
+    id: &str,
+    values: serde_json::Value,
+    state: tauri::State<AppState>,
+) -> Result<(), String> {
+    let plugin = state
+        .plugin_loader
+        .get_plugin(id)
+        .ok_or_else(|| format!("Plugin not found: {}", id))?;
+    let schema = plugin
+        .manifest
+        .settings_schema
+        .ok_or_else(|| format!("{} has no settings", plugin.manifest.name))?;
+    plugins::config_schema::validate(&schema, &values)?;
+    let serde_json::Value::Object(values) = values else {
+        return Err("Plugin settings must be an object".to_string());
+    };
+
+    let mut config = HOST_API.get_config(id);
+    plugins::config_schema::apply(&schema, &mut config, values);
+    HOST_API.set_config(id, config)
+}
+
+/// Disk space used by every installed plugin
+#[tauri::command]
+async fn get_plugin_storage_usage(
+    state: tauri::State<'_, AppState>,
+) -> Result<Vec<PluginStorageUsage>, String> {
+    let plugins = state.plugin_loader.list_plugins();
+    let plugins_dir = state.plugin_loader.plugins_dir().clone();
+    tokio::task::spawn_blocking(move || {
+        plugins
+            .iter()
+            .map(|p| HOST_API.storage_usage(&p.id, Some(&plugins_dir.join(&p.id))))
+            .collect()
+    })
+    .await
+    .map_err(|e| e.to_string())
+}
+
+/// Delete what a plugin has stored and restart it, so it starts over as if
+/// freshly installed
+#[tauri::command]
+fn clear_plugin_data(id: &str, state: tauri::State<AppState>) -> Result<(), String> {
+    // Stopped first, since shutting down may still write
+    state.plugin_runtime.unload_plugin(id)?;
+    HOST_API.clear_plugin_data(id)?;
+    match state.plugin_loader.get_plugin(id) {
+        Some(plugin) if plugin.enabled => state.plugin_runtime.load_plugin(&plugin),
+        _ => Ok(()),
+    }
+}
+
 #[tauri::command]
 fn get_plugins_dir(state: tauri::State<AppState>) -> String {
     state
@@ -195,6 +977,132 @@ fn get_plugins_dir(state: tauri::State<AppState>) -> String {
         .to_string()
 }
 
+/// Reload a plugin from disk after its files changed and re-register its
+/// intents and commands
+fn reload_plugin_from_disk(
+    state: &AppState,
+    plugin_dir: &std::path::Path,
+) -> Result<String, String> {
+    let plugin = state.plugin_loader.reload_plugin(plugin_dir)?;
+    let id = plugin.manifest.id.clone();
+    if plugin.enabled {
+        state.plugin_runtime.reload_plugin(&plugin)?;
+    }
+
+    state.intent_router.load_plugin_rules(&id, &plugin.path);
+    state.command_registry.unregister_plugin_commands(&id);
+    for cmd in &plugin.manifest.provides.commands {
+        state.command_registry.register_plugin_command(
+            &id,
+            &cmd.trigger,
+            &cmd.name,
+            &cmd.description,
+            cmd.icon.clone(),
+        );
+    }
+    Ok(id)
+}
+
+/// List plugins installed by `launcher-plugin dev` in the marketplace, or
+/// hide them when dev mode is off
+fn sync_dev_plugins(loader: &PluginLoader, registry: &PluginRegistry, dev_mode: bool) {
+    let entries = if dev_mode {
+        loader
+            .dev_plugins()
+            .iter()
+            .map(|(manifest, source_dir)| PluginRegistry::dev_entry(manifest, source_dir))
+            .collect()
+    } else {
+        Vec::new()
+    };
+    registry.set_dev_plugins(entries);
+}
+
+/// Dev mode: reload plugins as new builds are installed and tell the
+/// frontend with a `plugin-reloaded` event
+fn watch_plugins_for_dev(app: &AppHandle) -> Result<(), String> {
+    let state = app.state::<AppState>();
+    let handle = app.clone();
+    state
+        .plugin_runtime
+        .watch_plugins(state.plugin_loader.plugins_dir(), move |plugin_dir| {
+            let state = handle.state::<AppState>();
+            let event = match reload_plugin_from_disk(&state, plugin_dir) {
+                Ok(plugin_id) => PluginReloaded {
+                    plugin_id,
+                    error: None,
+                },
+                Err(e) => PluginReloaded {
+                    // The dev CLI installs into a directory named after the id
+                    plugin_id: plugin_dir
+                        .file_name()
+                        .map(|n| n.to_string_lossy().to_string())
+                        .unwrap_or_default(),
+                    error: Some(e),
+                },
+            };
+            // A first build only shows up in the marketplace once it's loaded
+            sync_dev_plugins(&state.plugin_loader, &state.plugin_registry, true);
+            state
+                .plugin_registry
+                .record_dev_reload(&event.plugin_id, event.error.clone());
+            state.event_bus.publish(AppEvent::PluginReloaded(event));
+            state.event_bus.publish(AppEvent::MarketplaceUpdated);
+        })
+}
+
+/// Turn automatic plugin reloading on or off
+#[tauri::command]
+fn set_plugin_dev_mode(
+    enabled: bool,
+    app: AppHandle,
+    state: tauri::State<AppState>,
+) -> Result<(), String> {
+    state.settings.update(|s| s.plugin_dev_mode = enabled);
+    sync_dev_plugins(&state.plugin_loader, &state.plugin_registry, enabled);
+    state.event_bus.publish(AppEvent::MarketplaceUpdated);
+    if !enabled {
+        state.plugin_runtime.stop_watching_plugins();
+    } else if !state.plugin_runtime.is_watching_plugins() {
+        watch_plugins_for_dev(&app)?;
+    }
+    Ok(())
+}
+
+/// Answer a plugin's request to contact a host its manifest doesn't list
+#[tauri::command]
+fn set_plugin_host_permission(plugin_id: String, host: String, allowed: bool) {
+    HOST_API.set_host_decision(&plugin_id, &host, allowed);
+}
+
+/// Hosts the user has allowed or denied for a plugin
+#[tauri::command]
+fn get_plugin_host_permissions(plugin_id: String) -> std::collections::HashMap<String, bool> {
+    HOST_API.host_decisions(&plugin_id)
+}
+
+#[tauri::command]
+fn clear_plugin_host_permission(plugin_id: String, host: String) {
+    HOST_API.clear_host_decision(&plugin_id, &host);
+}
+
+/// Answer a plugin's request for a connected account's token
+#[tauri::command]
+fn set_plugin_oauth_consent(plugin_id: String, provider: String, allowed: bool) {
+    HOST_API.set_oauth_consent(&plugin_id, &provider, allowed);
+}
+
+/// Account providers the user has allowed or denied for a plugin
+#[tauri::command]
+fn get_plugin_oauth_consents(plugin_id: String) -> std::collections::HashMap<String, bool> {
+    HOST_API.oauth_consents(&plugin_id)
+}
+
+#[tauri::command]
+fn clear_plugin_oauth_consent(plugin_id: String, provider: String) {
+    HOST_API.clear_oauth_consent(&plugin_id, &provider);
+}
+
 #[tauri::command]
 fn get_index_config(state: tauri::State<AppState>) -> indexer::IndexConfig {
     state.file_provider.get_config()
@@ -240,11 +1148,20 @@ fn get_command_by_trigger(trigger: &str, state: tauri::State<AppState>) -> Optio
 
 #[tauri::command]
 fn get_user_settings(state: tauri::State<AppState>) -> UserSettings {
-    state.settings.get()
+    let mut settings = state.settings.get();
+    // Don't restore widgets in safe mode, one of them may be what's crashing
+    if state.startup_mode.safe_mode {
+        settings.widget_layout.clear();
+    }
+    settings
 }
 
 #[tauri::command]
-fn set_user_settings(settings: UserSettings, state: tauri::State<AppState>) {
+fn set_user_settings(mut settings: UserSettings, state: tauri::State<AppState>) {
+    // The UI never saw the real layout in safe mode, keep the stored one
+    if state.startup_mode.safe_mode {
+        settings.widget_layout = state.settings.get().widget_layout;
+    }
     state.settings.set(settings);
 }
 
@@ -265,6 +1182,21 @@ fn set_window_size(width: u32, height: u32, state: tauri::State<AppState>) {
 
 #[tauri::command]
 fn update_widget_layout(layout: Vec<WidgetPlacement>, state: tauri::State<AppState>) {
+    if state.startup_mode.safe_mode {
+        return;
+    }
+    let removed: Vec<WidgetPlacement> = state
+        .settings
+        .get()
+        .widget_layout
+        .into_iter()
+        .filter(|w| !layout.iter().any(|p| p.instance_id == w.instance_id))
+        .collect();
+    if !removed.is_empty() {
+        state.undo_stack.record(UndoableAction::RemoveWidgets {
+            placements: removed,
+        });
+    }
     state.settings.update_widget_layout(layout);
 }
 
@@ -275,28 +1207,151 @@ fn pin_app(app_id: String, state: tauri::State<AppState>) {
 
 #[tauri::command]
 fn unpin_app(app_id: String, state: tauri::State<AppState>) {
-    state.settings.unpin_app(&app_id);
+    let pinned = state.settings.get().pinned_apps;
+    if let Some(position) = pinned.iter().position(|id| *id == app_id) {
+        state.settings.unpin_app(&app_id);
+        state
+            .undo_stack
+            .record(UndoableAction::UnpinApp { app_id, position });
+    }
 }
 
-/// Get suggested apps based on frecency and pinned apps
 #[tauri::command]
-fn get_suggested_apps(state: tauri::State<AppState>) -> Vec<SearchResult> {
-    let settings = state.settings.get();
-    let limit = settings.suggested_apps_count;
-
-    // Get pinned apps first
-    let pinned_ids: Vec<String> = settings.pinned_apps.clone();
+fn get_quicklinks(state: tauri::State<AppState>) -> Vec<Quicklink> {
+    state.settings.get().quicklinks
+}
 
-    // Get top frecency items that are apps
-    let frecency_items = state.frecency.get_top_items(limit * 2);
+/// Save a quicklink, replacing one with the same name. `url` may contain
+/// `{query}` for the text typed after the name.
+#[tauri::command]
+fn add_quicklink(name: String, url: String, state: tauri::State<AppState>) -> Result<(), String> {
+    let quicklink = Quicklink {
+        name: name.trim().to_string(),
+        url: url.trim().to_string(),
+    };
+    providers::quicklinks::validate(&quicklink)?;
+    state.settings.add_quicklink(quicklink);
+    Ok(())
+}
 
-    // Search all apps with empty query to get full list
-    let all_apps: Vec<SearchResult> = state
+#[tauri::command]
+fn remove_quicklink(name: String, state: tauri::State<AppState>) {
+    let quicklinks = state.settings.get().quicklinks;
+    if let Some(quicklink) = quicklinks
+        .into_iter()
+        .find(|q| q.name.eq_ignore_ascii_case(&name))
+    {
+        state.settings.remove_quicklink(&name);
+        state
+            .undo_stack
+            .record(UndoableAction::RemoveQuicklink { quicklink });
+    }
+}
+
+#[tauri::command]
+fn list_aliases(state: tauri::State<AppState>) -> Vec<SearchAlias> {
+    state.settings.get().aliases
+}
+
+/// Make `keyword` put `result` first in search, replacing any alias with
+/// the same keyword
+#[tauri::command]
+fn add_alias(
+    keyword: String,
+    result: SearchResult,
+    state: tauri::State<AppState>,
+) -> Result<(), String> {
+    let alias = SearchAlias {
+        keyword: keyword.trim().to_string(),
+        result,
+    };
+    aliases::validate(&alias)?;
+    state.settings.add_alias(alias);
+    Ok(())
+}
+
+#[tauri::command]
+fn remove_alias(keyword: String, state: tauri::State<AppState>) {
+    let aliases = state.settings.get().aliases;
+    if let Some(alias) = aliases
+        .into_iter()
+        .find(|a| a.keyword.eq_ignore_ascii_case(&keyword))
+    {
+        state.settings.remove_alias(&keyword);
+        state
+            .undo_stack
+            .record(UndoableAction::RemoveAlias { alias });
+    }
+}
+
+/// What's offered below the results when nothing else matches, in order
+#[tauri::command]
+fn get_fallbacks(state: tauri::State<AppState>) -> Vec<Fallback> {
+    state.settings.get().fallbacks
+}
+
+/// Replace the fallbacks, e.g. after reordering them
+#[tauri::command]
+fn set_fallbacks(fallbacks: Vec<Fallback>, state: tauri::State<AppState>) -> Result<(), String> {
+    for fallback in &fallbacks {
+        providers::fallbacks::validate(fallback)?;
+    }
+    state.settings.set_fallbacks(fallbacks);
+    Ok(())
+}
+
+/// A search provider and how search treats it
+#[derive(Debug, Clone, Serialize)]
+struct SearchProviderInfo {
+    id: String,
+    #[serde(flatten)]
+    settings: ProviderSettings,
+}
+
+#[tauri::command]
+fn list_search_providers(state: tauri::State<AppState>) -> Vec<SearchProviderInfo> {
+    let settings = state.settings.get();
+    state
         .providers
         .iter()
-        .find(|p| p.id() == "apps")
-        .map(|p| p.search(""))
-        .unwrap_or_default();
+        .map(|provider| SearchProviderInfo {
+            id: provider.id().to_string(),
+            settings: settings.provider(provider.id()),
+        })
+        .collect()
+}
+
+/// Turn a provider on or off, weigh its results, or limit how many it adds
+#[tauri::command]
+fn set_provider_settings(
+    provider_id: String,
+    settings: ProviderSettings,
+    state: tauri::State<AppState>,
+) -> Result<(), String> {
+    if !state.providers.iter().any(|p| p.id() == provider_id) {
+        return Err(format!("Unknown search provider: {}", provider_id));
+    }
+    if !settings.weight.is_finite() || settings.weight < 0.0 {
+        return Err("Provider weight can't be negative".to_string());
+    }
+    state.settings.set_provider_settings(&provider_id, settings);
+    Ok(())
+}
+
+/// Get suggested apps based on frecency and pinned apps
+#[tauri::command]
+fn get_suggested_apps(state: tauri::State<AppState>) -> Vec<SearchResult> {
+    let settings = state.settings.get();
+    let limit = settings.suggested_apps_count;
+
+    // Get pinned apps first
+    let pinned_ids: Vec<String> = settings.pinned_apps.clone();
+
+    // Get top frecency items that are apps
+    let frecency_items = state.frecency.get_top_items(limit * 2);
+
+    // Search all apps with empty query to get full list
+    let all_apps = search_provider_now(&state, "apps", "");
 
     let mut results: Vec<SearchResult> = Vec::new();
 
@@ -337,14 +1392,87 @@ fn get_suggested_apps(state: tauri::State<AppState>) -> Vec<SearchResult> {
     }
 
     results.truncate(limit);
+    results.iter_mut().for_each(SearchResult::fill_aria_label);
     results
 }
 
+/// Rescan installed apps now, returning how many were found
+#[tauri::command]
+async fn refresh_apps(state: tauri::State<'_, AppState>) -> Result<usize, String> {
+    let app_provider = state.app_provider.clone();
+    tokio::task::spawn_blocking(move || app_provider.refresh_apps())
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Carry out an uninstall plan, then forget the app in the app list,
+/// frecency and pinned apps. Blocks.
+fn perform_uninstall(state: &AppState, plan: &uninstall::UninstallPlan) -> Result<String, String> {
+    let message = uninstall::run(plan)?;
+
+    state.app_provider.remove_app(&plan.result_id);
+    state.frecency.remove(&plan.result_id);
+    state.settings.unpin_app(&plan.result_id);
+    Ok(message)
+}
+
+/// Uninstall an app the user confirmed, then forget it in the app list,
+/// frecency and pinned apps. Returns a summary to show.
+#[tauri::command]
+async fn uninstall_app(app: AppHandle, result_id: String) -> Result<String, String> {
+    tokio::task::spawn_blocking(move || {
+        let state = app.state::<AppState>();
+        let target = state
+            .app_provider
+            .uninstall_target(&result_id)
+            .ok_or("App not found")?;
+        let plan = uninstall::plan(&result_id, &target)?;
+        perform_uninstall(&state, &plan)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+// ============================================
+// Frecency Commands
+// ============================================
+
+/// Forget which results were picked and when
+#[tauri::command]
+fn clear_frecency(state: tauri::State<AppState>) {
+    state.frecency.clear();
+}
+
+#[tauri::command]
+fn get_frecency_stats(state: tauri::State<AppState>) -> FrecencyStats {
+    state.frecency.stats()
+}
+
+#[tauri::command]
+fn export_frecency(path: String, state: tauri::State<AppState>) -> Result<(), String> {
+    state.frecency.export(std::path::Path::new(&path))
+}
+
+/// Load an exported history, merging it into the current one unless
+/// `merge` is false. Returns how many entries the file had.
+#[tauri::command]
+fn import_frecency(
+    path: String,
+    merge: Option<bool>,
+    state: tauri::State<AppState>,
+) -> Result<usize, String> {
+    state
+        .frecency
+        .import(std::path::Path::new(&path), merge.unwrap_or(true))
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 struct OAuthProviderInfo {
     id: String,
     name: String,
     connected: bool,
+    /// Registered by the user rather than built in
+    custom: bool,
 }
 
 #[tauri::command]
@@ -357,10 +1485,27 @@ fn list_oauth_providers(state: tauri::State<AppState>) -> Vec<OAuthProviderInfo>
             id: p.id.clone(),
             name: p.name.clone(),
             connected: state.oauth_flow.is_connected(&p.id),
+            custom: state.oauth_flow.is_custom(&p.id),
         })
         .collect()
 }
 
+#[tauri::command]
+fn add_custom_oauth_provider(
+    provider: OAuthProviderConfig,
+    state: tauri::State<AppState>,
+) -> Result<(), String> {
+    state.oauth_flow.add_custom_provider(provider)
+}
+
+#[tauri::command]
+fn remove_custom_oauth_provider(
+    provider_id: &str,
+    state: tauri::State<AppState>,
+) -> Result<(), String> {
+    state.oauth_flow.remove_custom_provider(provider_id)
+}
+
 #[tauri::command]
 fn start_oauth(provider_id: &str, state: tauri::State<AppState>) -> Result<String, String> {
     let redirect_uri = "http://localhost:19284/oauth/callback";
@@ -382,6 +1527,15 @@ fn disconnect_oauth(provider_id: &str, state: tauri::State<AppState>) -> Result<
     state.oauth_flow.disconnect(provider_id)
 }
 
+#[tauri::command]
+async fn refresh_oauth_token(
+    provider_id: &str,
+    state: tauri::State<'_, AppState>,
+) -> Result<(), String> {
+    state.oauth_flow.refresh_token(provider_id).await?;
+    Ok(())
+}
+
 #[tauri::command]
 fn is_oauth_connected(provider_id: &str, state: tauri::State<AppState>) -> bool {
     state.oauth_flow.is_connected(provider_id)
@@ -465,13 +1619,33 @@ use config::CONFIG;
 async fn refresh_marketplace(
     state: tauri::State<'_, AppState>,
 ) -> Result<MarketplaceResponse, String> {
+    // Serve the cached catalog while in low-power mode
+    if state
+        .power_monitor
+        .should_pause(PowerSubsystem::MarketplaceRefresh)
+    {
+        eprintln!("Low-power mode: skipping marketplace refresh");
+        return Ok(state.plugin_registry.list_plugins_with_status());
+    }
+
     // Fetch from server API
     state.plugin_registry.fetch_from_server().await?;
+    cache_marketplace_icons(state.event_bus.clone(), state.plugin_registry.clone());
 
     // Return updated list with status
     Ok(state.plugin_registry.list_plugins_with_status())
 }
 
+/// Icons come after the list so it isn't held up; the UI reloads once
+/// they're cached
+fn cache_marketplace_icons(events: Arc<EventBus>, registry: Arc<PluginRegistry>) {
+    tauri::async_runtime::spawn(async move {
+        if registry.cache_icons().await > 0 {
+            events.publish(AppEvent::MarketplaceIconsUpdated);
+        }
+    });
+}
+
 #[tauri::command]
 fn list_marketplace_plugins(state: tauri::State<AppState>) -> MarketplaceResponse {
     state.plugin_registry.list_plugins_with_status()
@@ -516,6 +1690,15 @@ fn get_friendly_error_message(code: &str, error: &str) -> String {
     }
 }
 
+/// Dev builds come from their project folder, never from the store
+fn local_dev_error(plugin: &RegistryPlugin) -> String {
+    format!(
+        "{} is a local dev build. Rebuild it with `launcher-plugin dev`, or uninstall it \
+         to install the store version.",
+        plugin.name
+    )
+}
+
 #[tauri::command]
 async fn install_plugin(id: &str, state: tauri::State<'_, AppState>) -> Result<(), String> {
     // Try to get plugin from registry first
@@ -527,6 +1710,9 @@ async fn install_plugin(id: &str, state: tauri::State<'_, AppState>) -> Result<(
             state.plugin_registry.fetch_plugin_by_id(id).await?
         }
     };
+    if plugin.source == PluginSource::LocalDev {
+        return Err(local_dev_error(&plugin));
+    }
 
     let plugins_dir = state.plugin_loader.plugins_dir();
     let plugin_dir = plugins_dir.join(&plugin.id);
@@ -548,17 +1734,94 @@ async fn install_plugin(id: &str, state: tauri::State<'_, AppState>) -> Result<(
         }
 
         // Copy plugin directory
+        let install_txn = journal::begin(JournalOp::PluginInstall {
+            plugin_dir: plugin_dir.clone(),
+        });
         std::fs::create_dir_all(&plugin_dir).map_err(|e| e.to_string())?;
         copy_dir_recursive(&source_dir, &plugin_dir)?;
+        install_txn.commit();
 
         // Rescan plugins
         state.plugin_loader.scan_plugins()?;
+        let version = Some(plugin.version.clone());
+        record_plugin_change(&state, id, ChangeKind::PluginInstalled, None, version);
 
         return Ok(());
     }
 
-    // First, fetch the download info from the API to get the actual download URL
-    // This handles the new error codes properly
+    let (bytes, info) = download_package(&plugin).await?;
+
+    // Check if it's a WASM file (starts with WASM magic bytes: 0x00 0x61 0x73 0x6d)
+    let is_wasm = bytes.len() >= 4 && &bytes[0..4] == b"\x00asm";
+
+    // Check the signature before anything touches the disk
+    let require_signed = state.settings.get().require_signed_plugins;
+    let signer = if is_wasm {
+        state
+            .plugin_registry
+            .verify_unsigned(&plugin, require_signed)?;
+        None
+    } else {
+        let mut archive = zip::ZipArchive::new(std::io::Cursor::new(&bytes[..]))
+            .map_err(|e| format!("Failed to read archive: {}", e))?;
+        state
+            .plugin_registry
+            .verify_package(&plugin, &mut archive, require_signed)?
+    };
+
+    // Create plugin directory
+    let install_txn = journal::begin(JournalOp::PluginInstall {
+        plugin_dir: plugin_dir.clone(),
+    });
+    std::fs::create_dir_all(&plugin_dir).map_err(|e| e.to_string())?;
+
+    if is_wasm {
+        // It's a raw WASM file - save it directly
+        let wasm_path = plugin_dir.join("plugin.wasm");
+        std::fs::write(&wasm_path, &bytes)
+            .map_err(|e| format!("Failed to write WASM file: {}", e))?;
+
+        // Create a minimal manifest.json if it doesn't exist
+        let manifest_path = plugin_dir.join("manifest.json");
+        if !manifest_path.exists() {
+            let manifest = serde_json::json!({
+                "id": plugin.id,
+                "name": plugin.name,
+                "version": info.version.unwrap_or_else(|| plugin.version.clone()),
+                "entry": "plugin.wasm",
+                "permissions": plugin.permissions,
+            });
+            std::fs::write(
+                &manifest_path,
+                serde_json::to_string_pretty(&manifest).unwrap(),
+            )
+            .map_err(|e| format!("Failed to write manifest: {}", e))?;
+        }
+    } else {
+        // Try to handle it as a zip file
+        let cursor = std::io::Cursor::new(bytes);
+        let mut archive =
+            zip::ZipArchive::new(cursor).map_err(|e| format!("Failed to read archive: {}", e))?;
+
+        plugins::archive::extract(&mut archive, &plugin_dir)?;
+    }
+    install_txn.commit();
+    state
+        .plugin_registry
+        .pin_signer(&plugin.id, signer.as_deref());
+
+    // Rescan plugins
+    state.plugin_loader.scan_plugins()?;
+    let version = Some(plugin.version.clone());
+    record_plugin_change(&state, id, ChangeKind::PluginInstalled, None, version);
+
+    Ok(())
+}
+
+/// Download a marketplace plugin's package, checked against the published
+/// checksum. The download endpoint answers with where to fetch it from, or
+/// with an error code when it can't be had.
+async fn download_package(plugin: &RegistryPlugin) -> Result<(Vec<u8>, DownloadResponse), String> {
     let client = reqwest::Client::new();
     let download_response = client
         .get(&plugin.download_url)
@@ -568,104 +1831,117 @@ async fn install_plugin(id: &str, state: tauri::State<'_, AppState>) -> Result<(
         .map_err(|e| format!("Failed to connect to plugin server: {}", e))?;
 
     let status = download_response.status();
-    
+
     // Try to parse the response as JSON to get error details
     let response_text = download_response
         .text()
         .await
         .map_err(|e| format!("Failed to read server response: {}", e))?;
 
-    // Try to parse as JSON
-    let download_info: Result<DownloadResponse, _> = serde_json::from_str(&response_text);
-    
-    match download_info {
-        Ok(info) => {
-            // Check for error response
-            if let (Some(error), Some(code)) = (&info.error, &info.code) {
-                return Err(get_friendly_error_message(code, error));
-            }
-            
-            // Get the actual download URL
-            let actual_url = match info.url {
-                Some(url) => url,
-                None => {
-                    if !status.is_success() {
-                        return Err(format!("Server returned status {}: {}", status, response_text));
-                    }
-                    return Err("No download URL provided by server".to_string());
-                }
-            };
+    let info: DownloadResponse = match serde_json::from_str(&response_text) {
+        Ok(info) => info,
+        // Could not parse as JSON - return raw error
+        Err(_) if !status.is_success() => {
+            return Err(format!(
+                "Server returned status {}: {}",
+                status, response_text
+            ));
+        }
+        Err(_) => return Err(format!("Invalid response from server: {}", response_text)),
+    };
 
-            // Download the actual WASM file
-            let wasm_response = client
-                .get(&actual_url)
-                .timeout(std::time::Duration::from_secs(120))
-                .send()
-                .await
-                .map_err(|e| format!("Failed to download plugin file: {}", e))?;
+    // Check for error response
+    if let (Some(error), Some(code)) = (&info.error, &info.code) {
+        return Err(get_friendly_error_message(code, error));
+    }
 
-            if !wasm_response.status().is_success() {
+    // Get the actual download URL
+    let actual_url = match &info.url {
+        Some(url) => url,
+        None => {
+            if !status.is_success() {
                 return Err(format!(
-                    "Download failed with status: {}",
-                    wasm_response.status()
+                    "Server returned status {}: {}",
+                    status, response_text
                 ));
             }
+            return Err("No download URL provided by server".to_string());
+        }
+    };
 
-            let bytes = wasm_response.bytes().await.map_err(|e| e.to_string())?;
-
-            // Create plugin directory
-            std::fs::create_dir_all(&plugin_dir).map_err(|e| e.to_string())?;
-
-            // Check if it's a WASM file (starts with WASM magic bytes: 0x00 0x61 0x73 0x6d)
-            if bytes.len() >= 4 && &bytes[0..4] == b"\x00asm" {
-                // It's a raw WASM file - save it directly
-                let wasm_path = plugin_dir.join("plugin.wasm");
-                std::fs::write(&wasm_path, &bytes)
-                    .map_err(|e| format!("Failed to write WASM file: {}", e))?;
-
-                // Create a minimal manifest.json if it doesn't exist
-                let manifest_path = plugin_dir.join("manifest.json");
-                if !manifest_path.exists() {
-                    let manifest = serde_json::json!({
-                        "id": plugin.id,
-                        "name": plugin.name,
-                        "version": info.version.unwrap_or_else(|| plugin.version.clone()),
-                        "entry": "plugin.wasm",
-                        "permissions": plugin.permissions,
-                    });
-                    std::fs::write(&manifest_path, serde_json::to_string_pretty(&manifest).unwrap())
-                        .map_err(|e| format!("Failed to write manifest: {}", e))?;
-                }
-            } else {
-                // Try to handle it as a zip file
-                let cursor = std::io::Cursor::new(bytes);
-                let mut archive = zip::ZipArchive::new(cursor)
-                    .map_err(|e| format!("Failed to read archive: {}", e))?;
-
-                archive
-                    .extract(&plugin_dir)
-                    .map_err(|e| format!("Failed to extract plugin: {}", e))?;
-            }
+    // Download the actual WASM file
+    let wasm_response = client
+        .get(actual_url)
+        .timeout(std::time::Duration::from_secs(120))
+        .send()
+        .await
+        .map_err(|e| format!("Failed to download plugin file: {}", e))?;
 
-            // Rescan plugins
-            state.plugin_loader.scan_plugins()?;
+    if !wasm_response.status().is_success() {
+        return Err(format!(
+            "Download failed with status: {}",
+            wasm_response.status()
+        ));
+    }
 
-            Ok(())
-        }
-        Err(_) => {
-            // Could not parse as JSON - return raw error
-            if !status.is_success() {
-                Err(format!("Server returned status {}: {}", status, response_text))
-            } else {
-                Err(format!("Invalid response from server: {}", response_text))
-            }
-        }
+    let bytes = plugins::archive::download(wasm_response).await?;
+    let checksum = info.checksum.as_deref().or(plugin.checksum.as_deref());
+    plugins::archive::verify_checksum(&bytes, checksum)?;
+    Ok((bytes, info))
+}
+
+/// What installing a marketplace plugin would grant and put on disk, read
+/// from its package without installing anything
+#[tauri::command]
+async fn inspect_marketplace_plugin(
+    id: &str,
+    state: tauri::State<'_, AppState>,
+) -> Result<PluginInspection, String> {
+    let plugin = match state.plugin_registry.get_plugin(id) {
+        Some(plugin) => plugin,
+        None => state.plugin_registry.fetch_plugin_by_id(id).await?,
+    };
+    if plugin.source == PluginSource::LocalDev {
+        return Err(local_dev_error(&plugin));
+    }
+    if plugin.download_url.starts_with("local://") {
+        return Err(format!("{} is installed from a local folder", plugin.name));
     }
+
+    let (bytes, _) = download_package(&plugin).await?;
+    let require_signed = state.settings.get().require_signed_plugins;
+    let mut inspection =
+        plugins::inspect::inspect(plugin, &bytes, &state.plugin_registry, require_signed)?;
+    inspection.installed_version = state.plugin_loader.plugin_version(id);
+    Ok(inspection)
 }
 
 #[tauri::command]
 fn uninstall_plugin(id: &str, state: tauri::State<AppState>) -> Result<(), String> {
-    state.plugin_loader.uninstall_plugin(id)
+    // Named before it's gone from the loader
+    let name = state.plugin_loader.plugin_name(id);
+    let version = state.plugin_loader.plugin_version(id);
+    state.intent_router.remove_plugin_rules(id);
+    // Stop it before its files go, so it can't write them back
+    state.plugin_runtime.unload_plugin(id)?;
+    state.plugin_loader.uninstall_plugin(id)?;
+    HOST_API.clear_plugin_data(id)?;
+    let dev_mode = state.settings.get().plugin_dev_mode;
+    sync_dev_plugins(&state.plugin_loader, &state.plugin_registry, dev_mode);
+    state.change_log.record(
+        ChangeKind::PluginUninstalled,
+        &name.unwrap_or_else(|| id.to_string()),
+        version,
+        None,
+    );
+    Ok(())
+}
+
+/// Accept a different signing key on the plugin's next install or update,
+/// after its author rotated keys
+#[tauri::command]
+fn forget_plugin_signer(id: &str, state: tauri::State<AppState>) {
+    state.plugin_registry.forget_signer(id);
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -700,14 +1976,15 @@ fn check_plugin_updates(state: tauri::State<AppState>) -> Vec<PluginUpdate> {
 
 #[tauri::command]
 async fn update_plugin(id: &str, state: tauri::State<'_, AppState>) -> Result<(), String> {
-    // Uninstall current version
-    state.plugin_loader.uninstall_plugin(id)?;
-
     // Install latest from registry
     let plugin = state
         .plugin_registry
         .get_plugin(id)
         .ok_or_else(|| format!("Plugin not found in registry: {}", id))?;
+    if plugin.source == PluginSource::LocalDev {
+        return Err(local_dev_error(&plugin));
+    }
+    let previous_version = state.plugin_loader.plugin_version(id);
 
     let plugins_dir = state.plugin_loader.plugins_dir();
     let plugin_dir = plugins_dir.join(&plugin.id);
@@ -728,9 +2005,21 @@ async fn update_plugin(id: &str, state: tauri::State<'_, AppState>) -> Result<()
             ));
         }
 
+        state.plugin_loader.uninstall_plugin(id)?;
+        let install_txn = journal::begin(JournalOp::PluginInstall {
+            plugin_dir: plugin_dir.clone(),
+        });
         std::fs::create_dir_all(&plugin_dir).map_err(|e| e.to_string())?;
         copy_dir_recursive(&source_dir, &plugin_dir)?;
+        install_txn.commit();
         state.plugin_loader.scan_plugins()?;
+        record_plugin_change(
+            &state,
+            id,
+            ChangeKind::PluginUpdated,
+            previous_version,
+            Some(plugin.version.clone()),
+        );
         return Ok(());
     }
 
@@ -746,19 +2035,39 @@ async fn update_plugin(id: &str, state: tauri::State<'_, AppState>) -> Result<()
         ));
     }
 
-    let bytes = response.bytes().await.map_err(|e| e.to_string())?;
-
-    std::fs::create_dir_all(&plugin_dir).map_err(|e| e.to_string())?;
+    let bytes = plugins::archive::download(response).await?;
+    plugins::archive::verify_checksum(&bytes, plugin.checksum.as_deref())?;
 
     let cursor = std::io::Cursor::new(bytes);
     let mut archive =
         zip::ZipArchive::new(cursor).map_err(|e| format!("Failed to read zip archive: {}", e))?;
 
-    archive
-        .extract(&plugin_dir)
-        .map_err(|e| format!("Failed to extract plugin: {}", e))?;
+    // Keep the current version unless the new one checks out
+    let require_signed = state.settings.get().require_signed_plugins;
+    let signer = state
+        .plugin_registry
+        .verify_package(&plugin, &mut archive, require_signed)?;
+    state.plugin_loader.uninstall_plugin(id)?;
+
+    let install_txn = journal::begin(JournalOp::PluginInstall {
+        plugin_dir: plugin_dir.clone(),
+    });
+    std::fs::create_dir_all(&plugin_dir).map_err(|e| e.to_string())?;
+
+    plugins::archive::extract(&mut archive, &plugin_dir)?;
+    install_txn.commit();
+    state
+        .plugin_registry
+        .pin_signer(&plugin.id, signer.as_deref());
 
     state.plugin_loader.scan_plugins()?;
+    record_plugin_change(
+        &state,
+        id,
+        ChangeKind::PluginUpdated,
+        previous_version,
+        Some(plugin.version.clone()),
+    );
 
     Ok(())
 }
@@ -885,7 +2194,32 @@ pub struct WidgetData {
     pub title: Option<String>,
     pub value: Option<String>,
     pub subtitle: Option<String>,
-    pub html: Option<String>, // For custom widgets (sanitized)
+    pub html: Option<String>, // For custom widgets (sanitized by render_plugin_widget)
+    /// Read by screen readers; generated from the title, value and subtitle
+    /// when the plugin leaves it out
+    #[serde(default)]
+    pub aria_label: Option<String>,
+}
+
+impl WidgetData {
+    fn fill_aria_labels(&mut self) {
+        if self.aria_label.is_none() {
+            let parts: Vec<&str> = [&self.title, &self.value, &self.subtitle]
+                .into_iter()
+                .flatten()
+                .map(String::as_str)
+                .collect();
+            self.aria_label = (!parts.is_empty()).then(|| parts.join(", "));
+        }
+        for item in self.items.iter_mut().flatten() {
+            if item.aria_label.is_none() {
+                item.aria_label = Some(match &item.subtitle {
+                    Some(subtitle) => format!("{}, {}", item.title, subtitle),
+                    None => item.title.clone(),
+                });
+            }
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -895,6 +2229,8 @@ pub struct WidgetItem {
     pub subtitle: Option<String>,
     pub icon: Option<String>,
     pub action: Option<String>,
+    #[serde(default)]
+    pub aria_label: Option<String>,
 }
 
 /// Render a plugin widget and get its data
@@ -919,7 +2255,15 @@ fn render_plugin_widget(
         .call_render_widget(plugin_id, &request_str)?;
 
     // Parse the result
-    serde_json::from_str(&result).map_err(|e| format!("Failed to parse widget data: {}", e))
+    let mut data: WidgetData =
+        serde_json::from_str(&result).map_err(|e| format!("Failed to parse widget data: {}", e))?;
+
+    // The dashboard renders custom widget HTML as markup
+    if let Some(html) = data.html.as_mut() {
+        *html = plugins::sanitize::widget_html(html);
+    }
+    data.fill_aria_labels();
+    Ok(data)
 }
 
 /// Execute an AI tool via a plugin
@@ -976,12 +2320,7 @@ fn get_recent_files(limit: usize, state: tauri::State<AppState>) -> Vec<String>
 #[tauri::command]
 fn get_indexed_apps(limit: usize, state: tauri::State<AppState>) -> Vec<String> {
     // Search for apps with empty query to get all
-    let results = state
-        .providers
-        .iter()
-        .find(|p| p.id() == "apps")
-        .map(|p| p.search(""))
-        .unwrap_or_default();
+    let results = search_provider_now(&state, "apps", "");
 
     results.into_iter().take(limit).map(|r| r.title).collect()
 }
@@ -1126,6 +2465,11 @@ async fn codex_start_session(
     state: tauri::State<'_, AppState>,
 ) -> Result<SessionInfo, String> {
     let session_id = state.codex_manager.create_session(working_dir).await?;
+    // Ask about an untrusted folder now rather than at the first message
+    state
+        .workspace_trust
+        .sandbox_for(std::path::Path::new(working_dir), "codex")
+        .await;
 
     // Get session info
     let sessions = state.codex_manager.sessions.read().await;
@@ -1145,7 +2489,11 @@ async fn codex_send_message(
 ) -> Result<(), String> {
     let sessions = state.codex_manager.sessions.read().await;
     if let Some(session) = sessions.get(session_id) {
-        session.send_message(message).await
+        let sandbox = state
+            .workspace_trust
+            .sandbox_for(&session.working_dir, "codex")
+            .await;
+        session.send_message(message, sandbox).await
     } else {
         Err(format!("Session not found: {}", session_id))
     }
@@ -1198,6 +2546,42 @@ async fn codex_poll_output(
     }
 }
 
+/// Hand a search result to Codex: reuse the session running in the result's
+/// folder or start one, tell it about the result, and ask `question` if given
+#[tauri::command]
+async fn codex_ask_about_result(
+    result: SearchResult,
+    question: Option<String>,
+    state: tauri::State<'_, AppState>,
+) -> Result<SessionInfo, String> {
+    let context = match provider_for(&result.id, &state.providers) {
+        Some(provider) => provider.context(&result),
+        None => ResultContext::from_result(&result),
+    };
+    let working_dir = context
+        .folder()
+        .or_else(dirs::home_dir)
+        .ok_or("No folder to run Codex in")?;
+    let session_id = state
+        .codex_manager
+        .find_or_create_session(&working_dir.to_string_lossy())
+        .await?;
+
+    let sessions = state.codex_manager.sessions.read().await;
+    let session = sessions
+        .get(&session_id)
+        .ok_or("Failed to create session")?;
+    session.attach(context).await;
+    let sandbox = state
+        .workspace_trust
+        .sandbox_for(&session.working_dir, "codex")
+        .await;
+    if let Some(question) = question.filter(|q| !q.trim().is_empty()) {
+        session.send_message(&question, sandbox).await?;
+    }
+    Ok(session.info().await)
+}
+
 /// Start a dev server for a Codex session
 #[tauri::command]
 async fn codex_start_dev_server(
@@ -1214,10 +2598,14 @@ async fn codex_start_dev_server(
     drop(sessions);
 
     // Start the dev server
+    let sandbox = state
+        .workspace_trust
+        .sandbox_for(&working_dir, "dev-server")
+        .await;
     state
         .codex_manager
         .dev_servers
-        .start_server(session_id, &working_dir, command)
+        .start_server(session_id, &working_dir, command, sandbox)
         .await
 }
 
@@ -1371,6 +2759,55 @@ fn parse_shortcut(shortcut_str: &str) -> Result<Shortcut, String> {
         "," | "comma" => Code::Comma,
         "." | "period" => Code::Period,
         "/" | "slash" => Code::Slash,
+        "f13" => Code::F13,
+        "f14" => Code::F14,
+        "f15" => Code::F15,
+        "f16" => Code::F16,
+        "f17" => Code::F17,
+        "f18" => Code::F18,
+        "f19" => Code::F19,
+        "f20" => Code::F20,
+        "f21" => Code::F21,
+        "f22" => Code::F22,
+        "f23" => Code::F23,
+        "f24" => Code::F24,
+        "capslock" => Code::CapsLock,
+        "printscreen" | "prtsc" => Code::PrintScreen,
+        "scrolllock" => Code::ScrollLock,
+        "pause" | "break" => Code::Pause,
+        // Numpad
+        "num0" | "numpad0" => Code::Numpad0,
+        "num1" | "numpad1" => Code::Numpad1,
+        "num2" | "numpad2" => Code::Numpad2,
+        "num3" | "numpad3" => Code::Numpad3,
+        "num4" | "numpad4" => Code::Numpad4,
+        "num5" | "numpad5" => Code::Numpad5,
+        "num6" | "numpad6" => Code::Numpad6,
+        "num7" | "numpad7" => Code::Numpad7,
+        "num8" | "numpad8" => Code::Numpad8,
+        "num9" | "numpad9" => Code::Numpad9,
+        "numadd" | "numpadadd" | "numplus" => Code::NumpadAdd,
+        "numsubtract" | "numpadsubtract" | "numminus" => Code::NumpadSubtract,
+        "nummultiply" | "numpadmultiply" => Code::NumpadMultiply,
+        "numdivide" | "numpaddivide" => Code::NumpadDivide,
+        "numdecimal" | "numpaddecimal" => Code::NumpadDecimal,
+        "numenter" | "numpadenter" => Code::NumpadEnter,
+        "numequal" | "numpadequal" => Code::NumpadEqual,
+        "numlock" => Code::NumLock,
+        // Media keys
+        "mediaplaypause" | "playpause" => Code::MediaPlayPause,
+        "mediastop" => Code::MediaStop,
+        "medianexttrack" | "mediatracknext" | "nexttrack" => Code::MediaTrackNext,
+        "mediaprevioustrack" | "mediatrackprevious" | "prevtrack" => Code::MediaTrackPrevious,
+        "volumeup" | "audiovolumeup" => Code::AudioVolumeUp,
+        "volumedown" | "audiovolumedown" => Code::AudioVolumeDown,
+        "volumemute" | "audiovolumemute" | "mute" => Code::AudioVolumeMute,
+        // Keys that only exist on ISO/JIS layouts. What's printed on them (and on
+        // the punctuation keys above) depends on the layout, so they go by their
+        // physical key name rather than a character.
+        "intlbackslash" => Code::IntlBackslash,
+        "intlro" => Code::IntlRo,
+        "intlyen" => Code::IntlYen,
         _ => return Err(format!("Unknown key: {}", key_str)),
     };
     
@@ -1396,6 +2833,8 @@ fn set_global_shortcut(
     if let Err(e) = global_shortcut.unregister_all() {
         eprintln!("Warning: Failed to unregister existing shortcuts: {}", e);
     }
+    // That included the window command hotkeys; put them back
+    register_window_hotkeys(&app, &state.settings.get().window_hotkeys);
     
     // Save the setting
     state.settings.update(|s| {
@@ -1449,55 +2888,1616 @@ fn set_global_shortcut(
     }
 }
 
+/// Check whether a shortcut can be used without saving it.
+///
+/// `action` is the window command the shortcut is meant for, or None for the
+/// launcher shortcut. The shortcut is checked against the app's other bindings
+/// and briefly registered with the OS so that conflicts with other applications
+/// are reported before the user commits the change.
+#[tauri::command]
+fn validate_shortcut(
+    app: AppHandle,
+    shortcut: String,
+    action: Option<String>,
+    state: tauri::State<AppState>,
+) -> ShortcutResult {
+    let parsed = match parse_shortcut(&shortcut) {
+        Ok(s) => s,
+        Err(e) => {
+            return ShortcutResult {
+                success: false,
+                shortcut: Some(shortcut),
+                error: Some(format!("Invalid shortcut format: {}", e)),
+            };
+        }
+    };
+
+    if let Some(owner) = shortcut_owner(&parsed, &state.settings.get(), action.as_deref()) {
+        return ShortcutResult {
+            success: false,
+            shortcut: Some(shortcut),
+            error: Some(format!("This shortcut is already used for {}.", owner)),
+        };
+    }
+
+    let global_shortcut = app.global_shortcut();
+
+    // Already registered by us for this same binding
+    if global_shortcut.is_registered(parsed) {
+        return ShortcutResult {
+            success: true,
+            shortcut: Some(shortcut),
+            error: None,
+        };
+    }
+
+    match global_shortcut.register(parsed) {
+        Ok(_) => {
+            if let Err(e) = global_shortcut.unregister(parsed) {
+                eprintln!("Warning: Failed to unregister test shortcut: {}", e);
+            }
+            ShortcutResult {
+                success: true,
+                shortcut: Some(shortcut),
+                error: None,
+            }
+        }
+        Err(e) => {
+            let error_msg = if e.to_string().contains("already") || e.to_string().contains("use") {
+                "This shortcut is already in use by another application. Please choose a different combination.".to_string()
+            } else {
+                format!("Failed to register shortcut: {}", e)
+            };
+
+            ShortcutResult {
+                success: false,
+                shortcut: Some(shortcut),
+                error: Some(error_msg),
+            }
+        }
+    }
+}
+
+/// Which of the app's own bindings other than `action` (None being the launcher
+/// shortcut) already uses `shortcut`, if any
+fn shortcut_owner(
+    shortcut: &Shortcut,
+    settings: &UserSettings,
+    action: Option<&str>,
+) -> Option<String> {
+    let matches = |keys: &str| parse_shortcut(keys).is_ok_and(|s| s.id() == shortcut.id());
+    if action.is_some() && settings.custom_shortcut.as_deref().is_some_and(matches) {
+        return Some("opening the launcher".to_string());
+    }
+    settings
+        .window_hotkeys
+        .iter()
+        .filter(|(id, _)| Some(id.as_str()) != action)
+        .find(|(_, keys)| matches(keys.as_str()))
+        .map(|(id, _)| match WindowAction::from_id(id) {
+            Some(window_action) => format!("the \"{}\" window command", window_action.title()),
+            None => format!("the {} window command", id),
+        })
+}
+
+// ============================================
+// Window Management Commands
+// ============================================
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WindowCommandInfo {
+    pub id: String,
+    pub title: String,
+    pub hotkey: Option<String>,
+}
+
+/// Register the window command hotkeys from settings. Bad or taken
+/// shortcuts are skipped so one can't keep the others from working.
+fn register_window_hotkeys(app: &AppHandle, hotkeys: &std::collections::HashMap<String, String>) {
+    let global_shortcut = app.global_shortcut();
+    for (action, keys) in hotkeys {
+        let registered = parse_shortcut(keys).and_then(|shortcut| {
+            global_shortcut
+                .register(shortcut)
+                .map_err(|e| e.to_string())
+        });
+        if let Err(e) = registered {
+            eprintln!("Failed to register hotkey {} for {}: {}", keys, action, e);
+        }
+    }
+}
+
+/// The window command bound to a pressed global shortcut, if any
+fn window_action_for_shortcut(shortcut: &Shortcut, state: &AppState) -> Option<WindowAction> {
+    state
+        .settings
+        .get()
+        .window_hotkeys
+        .iter()
+        .find(|(_, keys)| parse_shortcut(keys).is_ok_and(|s| s.id() == shortcut.id()))
+        .and_then(|(action, _)| WindowAction::from_id(action))
+}
+
+#[tauri::command]
+fn get_window_commands(state: tauri::State<AppState>) -> Vec<WindowCommandInfo> {
+    let hotkeys = state.settings.get().window_hotkeys;
+    WindowAction::ALL
+        .iter()
+        .map(|action| WindowCommandInfo {
+            id: action.id().to_string(),
+            title: action.title().to_string(),
+            hotkey: hotkeys.get(action.id()).cloned(),
+        })
+        .collect()
+}
+
+/// Move or resize the window of the app the launcher was summoned over
+#[tauri::command]
+async fn run_window_command(
+    action: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<(), String> {
+    let action = WindowAction::from_id(&action).ok_or("Unknown window command")?;
+    let app = state.context_tracker.active().app.map(|app| app.name);
+    tokio::task::spawn_blocking(move || window_manager::apply(action, app.as_deref()))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// Bind a window command to a global hotkey, or unbind it with None
+#[tauri::command]
+fn set_window_hotkey(
+    app: AppHandle,
+    action: String,
+    shortcut: Option<String>,
+    state: tauri::State<AppState>,
+) -> Result<(), String> {
+    WindowAction::from_id(&action).ok_or("Unknown window command")?;
+    let global_shortcut = app.global_shortcut();
+
+    if let Some(previous) = state.settings.get().window_hotkeys.get(&action) {
+        if let Ok(previous) = parse_shortcut(previous) {
+            let _ = global_shortcut.unregister(previous);
+        }
+    }
+    if let Some(keys) = &shortcut {
+        let parsed = parse_shortcut(keys).map_err(|e| format!("Invalid shortcut format: {}", e))?;
+        global_shortcut
+            .register(parsed)
+            .map_err(|e| format!("Failed to register shortcut: {}", e))?;
+    }
+
+    state.settings.update(|s| match shortcut {
+        Some(keys) => {
+            s.window_hotkeys.insert(action, keys);
+        }
+        None => {
+            s.window_hotkeys.remove(&action);
+        }
+    });
+    Ok(())
+}
+
+// ============================================
+// Terminal Widget Commands
+// ============================================
+
+/// Spawn a new terminal session
+#[tauri::command]
+async fn terminal_spawn(
+    id: String,
+    cols: u16,
+    rows: u16,
+    cwd: Option<String>,
+    state: tauri::State<'_, AppState>,
+) -> Result<(), String> {
+    // The shell starts in the home folder without a `cwd`
+    let folder = cwd
+        .as_deref()
+        .map(std::path::PathBuf::from)
+        .or_else(dirs::home_dir);
+    let sandbox = match folder {
+        Some(folder) => state.workspace_trust.sandbox_for(&folder, "terminal").await,
+        None => None,
+    };
+    state
+        .terminal_manager
+        .spawn_terminal(id, cols, rows, cwd, sandbox)
+}
+
+/// Write input to a terminal session
+#[tauri::command]
+fn terminal_write(id: String, data: String, state: tauri::State<AppState>) -> Result<(), String> {
+    state.terminal_manager.write_to_terminal(&id, &data)
+}
+
+/// Resize a terminal session
+#[tauri::command]
+fn terminal_resize(
+    id: String,
+    cols: u16,
+    rows: u16,
+    state: tauri::State<AppState>,
+) -> Result<(), String> {
+    state.terminal_manager.resize_terminal(&id, cols, rows)
+}
+
+/// Close a terminal session
+#[tauri::command]
+fn terminal_close(id: String, state: tauri::State<AppState>) -> Result<(), String> {
+    state.terminal_manager.close_terminal(&id)
+}
+
+/// Check if a terminal session exists
+#[tauri::command]
+fn terminal_exists(id: String, state: tauri::State<AppState>) -> bool {
+    state.terminal_manager.has_terminal(&id)
+}
+
+/// List all active terminal sessions
+#[tauri::command]
+fn terminal_list(state: tauri::State<AppState>) -> Vec<String> {
+    state.terminal_manager.list_terminals()
+}
+
+// ============================================
+// Workspace Trust Commands
+// ============================================
+
+/// Folders Codex, dev servers and terminals run in without a sandbox
+#[tauri::command]
+fn get_trusted_folders(state: tauri::State<AppState>) -> Vec<TrustedFolder> {
+    state.workspace_trust.folders()
+}
+
+/// Trust a folder and everything under it
+#[tauri::command]
+fn trust_folder(path: &str, state: tauri::State<AppState>) -> Result<(), String> {
+    state.workspace_trust.trust(path)
+}
+
+#[tauri::command]
+fn untrust_folder(path: &str, state: tauri::State<AppState>) -> Result<(), String> {
+    state.workspace_trust.untrust(path)
+}
+
+// ============================================
+// File Operation Commands
+// ============================================
+
+fn emit_file_op_progress(
+    app: &AppHandle,
+    operation_id: &str,
+    operation: &str,
+    processed: usize,
+    total: usize,
+    current_path: &std::path::Path,
+) {
+    let _ = app.emit(
+        "file-op-progress",
+        FileOpProgress {
+            operation_id: operation_id.to_string(),
+            operation: operation.to_string(),
+            processed,
+            total,
+            current_path: Some(current_path.to_string_lossy().to_string()),
+            done: processed >= total,
+        },
+    );
+}
+
+/// Compress into a zip, emitting "file-op-progress", and index the
+/// archive. Blocks.
+fn compress_and_index(
+    app: &AppHandle,
+    paths: &[std::path::PathBuf],
+    destination: Option<std::path::PathBuf>,
+) -> Result<std::path::PathBuf, String> {
+    let operation_id = uuid::Uuid::new_v4().to_string();
+    let dest = match destination {
+        Some(dest) => dest,
+        None => file_ops::default_zip_path(paths)?,
+    };
+
+    let output = file_ops::compress_to_zip(paths, &dest, |processed, total, path| {
+        emit_file_op_progress(app, &operation_id, "compress", processed, total, path);
+    })?;
+
+    let _ = app.state::<AppState>().file_provider.update_file(&output);
+    Ok(output)
+}
+
+/// Extract an archive, emitting "file-op-progress", and index the output
+/// folder. Blocks.
+fn extract_and_index(
+    app: &AppHandle,
+    archive: &std::path::Path,
+    destination: Option<&std::path::Path>,
+    overwrite: bool,
+) -> Result<std::path::PathBuf, String> {
+    let operation_id = uuid::Uuid::new_v4().to_string();
+    let on_progress = |processed, total, path: &std::path::Path| {
+        emit_file_op_progress(app, &operation_id, "extract", processed, total, path);
+    };
+    let output = file_ops::extract_archive(archive, destination, overwrite, on_progress)?;
+
+    let _ = app.state::<AppState>().file_provider.update_file(&output);
+    Ok(output)
+}
+
+/// Compress or extract a file result from its actions, in the background,
+/// reporting the outcome as a notification
+fn run_file_action(app: AppHandle, path: std::path::PathBuf, action_id: &str) {
+    let compress = action_id == "compress";
+    tauri::async_runtime::spawn_blocking(move || {
+        let (done, failed, outcome) = if compress {
+            let outcome = compress_and_index(&app, &[path], None);
+            ("Compressed", "Couldn't compress", outcome)
+        } else {
+            let outcome = extract_and_index(&app, &path, None, false);
+            ("Extracted", "Couldn't extract", outcome)
+        };
+        let _ = match outcome {
+            Ok(output) => notifications::show(&app, done, &output.display().to_string()),
+            Err(e) => notifications::show(&app, failed, &e),
+        };
+    });
+}
+
+/// Compress files and folders into a zip archive.
+/// Returns the archive as a file result.
+#[tauri::command]
+async fn compress_files(
+    app: AppHandle,
+    paths: Vec<String>,
+    destination: Option<String>,
+) -> Result<SearchResult, String> {
+    tokio::task::spawn_blocking(move || {
+        let paths: Vec<std::path::PathBuf> = paths.iter().map(std::path::PathBuf::from).collect();
+        let output = compress_and_index(&app, &paths, destination.map(std::path::PathBuf::from))?;
+
+        let mut result = FileProvider::result_for_path(&output);
+        result.fill_aria_label();
+        Ok(result)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Extract an archive in place, or into `destination` if given. Files already
+/// in `destination` are only replaced when `overwrite` is set.
+/// Returns the output folder as a file result.
+#[tauri::command]
+async fn extract_archive(
+    app: AppHandle,
+    path: String,
+    destination: Option<String>,
+    overwrite: Option<bool>,
+) -> Result<SearchResult, String> {
+    tokio::task::spawn_blocking(move || {
+        let archive = std::path::PathBuf::from(path);
+        let dest = destination.map(std::path::PathBuf::from);
+        let overwrite = overwrite.unwrap_or(false);
+        let output = extract_and_index(&app, &archive, dest.as_deref(), overwrite)?;
+
+        let mut result = FileProvider::result_for_path(&output);
+        result.fill_aria_label();
+        Ok(result)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Dry-run a bulk rename and return the planned names for review
+#[tauri::command]
+fn preview_bulk_rename(paths: Vec<String>, pattern: String) -> Result<Vec<RenamePreview>, String> {
+    let rule = RenameRule::parse(&pattern)?;
+    let paths: Vec<std::path::PathBuf> = paths.iter().map(std::path::PathBuf::from).collect();
+    Ok(file_ops::preview_rename(&paths, &rule))
+}
+
+/// Apply a bulk rename and update the file index
+#[tauri::command]
+async fn bulk_rename(
+    app: AppHandle,
+    paths: Vec<String>,
+    pattern: String,
+) -> Result<Vec<RenamePreview>, String> {
+    let rule = RenameRule::parse(&pattern)?;
+    tokio::task::spawn_blocking(move || {
+        let paths: Vec<std::path::PathBuf> = paths.iter().map(std::path::PathBuf::from).collect();
+        let renamed = file_ops::apply_rename(&paths, &rule)?;
+
+        let state = app.state::<AppState>();
+        for entry in &renamed {
+            let _ = state
+                .file_provider
+                .update_file(std::path::Path::new(&entry.from));
+            let _ = state
+                .file_provider
+                .update_file(std::path::Path::new(&entry.to));
+        }
+
+        Ok(renamed)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+// ============================================
+// Remote Location Commands
+// ============================================
+
+#[tauri::command]
+fn list_remote_locations(state: tauri::State<AppState>) -> Vec<RemoteLocation> {
+    state.remote_provider.list_locations()
+}
+
+/// Add or update a remote location; the password is kept in the system keyring
+#[tauri::command]
+fn save_remote_location(
+    location: RemoteLocation,
+    password: Option<String>,
+    state: tauri::State<AppState>,
+) -> Result<(), String> {
+    state.remote_provider.save_location(location, password)
+}
+
+#[tauri::command]
+fn remove_remote_location(id: &str, state: tauri::State<AppState>) -> Result<(), String> {
+    state.remote_provider.remove_location(id)
+}
+
+#[tauri::command]
+async fn check_remote_location(
+    id: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<RemoteHealth, String> {
+    let remote_provider = state.remote_provider.clone();
+    tokio::task::spawn_blocking(move || remote_provider.check_health(&id))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// List one directory of a remote location
+#[tauri::command]
+async fn list_remote_directory(
+    id: String,
+    path: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<Vec<RemoteEntry>, String> {
+    let remote_provider = state.remote_provider.clone();
+    tokio::task::spawn_blocking(move || remote_provider.list(&id, &path))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+// ============================================
+// File Navigator Commands
+// ============================================
+
+/// A page of a local folder's listing for `browse:` mode, only names
+/// starting with `filter`
+#[tauri::command]
+async fn browse_directory(
+    path: String,
+    filter: Option<String>,
+    offset: usize,
+    limit: usize,
+    state: tauri::State<'_, AppState>,
+) -> Result<DirectoryPage, String> {
+    let browse_provider = state.browse_provider.clone();
+    tokio::task::spawn_blocking(move || {
+        browse_provider.page(&path, filter.as_deref().unwrap_or_default(), offset, limit)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Tab completion for a `browse:` or `~/` query
+#[tauri::command]
+async fn complete_path(
+    query: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<Option<String>, String> {
+    let browse_provider = state.browse_provider.clone();
+    tokio::task::spawn_blocking(move || browse_provider.complete(&query))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+// ============================================
+// Feed Commands
+// ============================================
+
+#[tauri::command]
+fn list_feed_subscriptions(state: tauri::State<AppState>) -> Vec<FeedSubscription> {
+    state.feed_provider.subscriptions()
+}
+
+#[tauri::command]
+async fn subscribe_feed(
+    url: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<FeedSubscription, String> {
+    let feed_provider = state.feed_provider.clone();
+    tokio::task::spawn_blocking(move || feed_provider.subscribe(&url))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+#[tauri::command]
+fn unsubscribe_feed(id: &str, state: tauri::State<AppState>) -> Result<(), String> {
+    state.feed_provider.unsubscribe(id)
+}
+
+/// Subscribe to all feeds in an OPML document, returning how many were added
+#[tauri::command]
+async fn import_opml(opml: String, state: tauri::State<'_, AppState>) -> Result<usize, String> {
+    let feed_provider = state.feed_provider.clone();
+    tokio::task::spawn_blocking(move || feed_provider.import_opml(&opml))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+#[tauri::command]
+fn export_opml(state: tauri::State<AppState>) -> String {
+    state.feed_provider.export_opml()
+}
+
+/// Unread items, newest first, for the read-later widget
+#[tauri::command]
+fn get_unread_feed_items(limit: Option<usize>, state: tauri::State<AppState>) -> Vec<FeedItem> {
+    state.feed_provider.unread_items(limit.unwrap_or(20))
+}
+
+#[tauri::command]
+fn mark_feed_item_read(id: &str, state: tauri::State<AppState>) -> Result<(), String> {
+    state.feed_provider.mark_read(id)
+}
+
+#[tauri::command]
+fn archive_feed_item(id: &str, state: tauri::State<AppState>) -> Result<(), String> {
+    state.feed_provider.archive(id)
+}
+
+/// Fetch all subscriptions now, returning the number of new items
+#[tauri::command]
+async fn refresh_feeds(state: tauri::State<'_, AppState>) -> Result<usize, String> {
+    let feed_provider = state.feed_provider.clone();
+    tokio::task::spawn_blocking(move || feed_provider.refresh_all())
+        .await
+        .map_err(|e| e.to_string())
+}
+
+// ============================================
+// Clipboard History Commands
+// ============================================
+
+/// Pinned entries first, then the rest newest first
+#[tauri::command]
+fn get_clipboard_history(
+    limit: Option<usize>,
+    state: tauri::State<AppState>,
+) -> Vec<ClipboardEntry> {
+    state.clipboard_provider.entries(limit.unwrap_or(100))
+}
+
+#[tauri::command]
+fn pin_clipboard_entry(
+    id: &str,
+    pinned: bool,
+    state: tauri::State<AppState>,
+) -> Result<(), String> {
+    state.clipboard_provider.set_pinned(id, pinned)
+}
+
+#[tauri::command]
+fn delete_clipboard_entry(id: &str, state: tauri::State<AppState>) -> Result<(), String> {
+    state.clipboard_provider.delete(id)
+}
+
+/// Forget everything except pinned entries
+#[tauri::command]
+fn clear_clipboard_history(state: tauri::State<AppState>) -> Result<(), String> {
+    state.clipboard_provider.clear()
+}
+
+// ============================================
+// Cleanup Commands
+// ============================================
+
+fn parse_cleanup_task(task: &str) -> Result<CleanupTask, String> {
+    CleanupTask::parse(task).ok_or_else(|| format!("Unknown cleanup task: {}", task))
+}
+
+/// What a cleanup task will do, for the confirmation dialog
+#[tauri::command]
+async fn preview_cleanup(task: String) -> Result<CleanupPreview, String> {
+    let task = parse_cleanup_task(&task)?;
+    tokio::task::spawn_blocking(move || cleanup::preview(task))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Run a cleanup task, emitting "cleanup-progress". Blocks.
+fn perform_cleanup(
+    app: &AppHandle,
+    task: CleanupTask,
+    cache_coordinator: &CacheCoordinator,
+) -> Result<CleanupReport, String> {
+    let progress = |progress: CleanupProgress| {
+        let _ = app.emit("cleanup-progress", progress);
+    };
+    match task {
+        CleanupTask::EmptyTrash => cleanup::empty_trash(&progress),
+        CleanupTask::ClearCaches => {
+            let disk = cleanup::clear_disk_caches(&progress)?;
+            let freed = disk + cache_coordinator.clear_all() as u64;
+            Ok(CleanupReport {
+                task,
+                freed_bytes: Some(freed),
+                message: format!("Caches cleared, {} freed", cleanup::format_bytes(freed)),
+            })
+        }
+        CleanupTask::FlushDns => cleanup::flush_dns(),
+    }
+}
+
+/// Run a cleanup task the user confirmed, emitting "cleanup-progress"
+#[tauri::command]
+async fn run_cleanup(
+    app: AppHandle,
+    task: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<CleanupReport, String> {
+    let task = parse_cleanup_task(&task)?;
+    let cache_coordinator = state.cache_coordinator.clone();
+
+    tokio::task::spawn_blocking(move || perform_cleanup(&app, task, &cache_coordinator))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// Largest files and folders under the indexed roots, from the index's
+/// size metadata. Emits "cleanup-progress" while reading the index.
+#[tauri::command]
+async fn get_largest_entries(
+    app: AppHandle,
+    limit: Option<usize>,
+    refresh: Option<bool>,
+    state: tauri::State<'_, AppState>,
+) -> Result<LargestEntries, String> {
+    let cleanup_provider = state.cleanup_provider.clone();
+    tokio::task::spawn_blocking(move || {
+        cleanup_provider.largest_entries(
+            limit.unwrap_or(50),
+            refresh.unwrap_or(false),
+            |done, total| {
+                let _ = app.emit(
+                    "cleanup-progress",
+                    CleanupProgress {
+                        task: "largest".to_string(),
+                        message: format!("Read {} of {} indexed files", done, total),
+                        done: done as u64,
+                        total: total as u64,
+                    },
+                );
+            },
+        )
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+// ============================================
+// Capture Commands
+// ============================================
+
+fn parse_capture_mode(mode: &str) -> Result<CaptureMode, String> {
+    CaptureMode::parse(mode).ok_or_else(|| format!("Unknown capture mode: {}", mode))
+}
+
+/// Take a screenshot ("full", "window" or "region") into the capture folder
+#[tauri::command]
+async fn take_screenshot(
+    mode: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<CaptureSaved, String> {
+    let mode = parse_capture_mode(&mode)?;
+    let capture_provider = state.capture_provider.clone();
+    tokio::task::spawn_blocking(move || capture_provider.screenshot(mode))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+#[tauri::command]
+async fn start_screen_recording(
+    mode: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<RecordingInfo, String> {
+    let mode = parse_capture_mode(&mode)?;
+    let capture_provider = state.capture_provider.clone();
+    tokio::task::spawn_blocking(move || capture_provider.start_recording(mode))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+#[tauri::command]
+async fn stop_screen_recording(state: tauri::State<'_, AppState>) -> Result<CaptureSaved, String> {
+    let capture_provider = state.capture_provider.clone();
+    tokio::task::spawn_blocking(move || capture_provider.stop_recording())
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+#[tauri::command]
+fn get_recording_status(state: tauri::State<AppState>) -> Option<RecordingInfo> {
+    state.capture_provider.recording()
+}
+
+/// Copy a captured image to the clipboard
+#[tauri::command]
+fn copy_image_to_clipboard(path: &str) -> Result<(), String> {
+    clipboard::copy_image(std::path::Path::new(path))
+}
+
+// ============================================
+// OCR Commands
+// ============================================
+
+#[tauri::command]
+fn get_ocr_status(state: tauri::State<AppState>) -> OcrStatus {
+    state.ocr_provider.status()
+}
+
+/// Download the OCR models (only available in builds with the `ocr` feature)
+#[tauri::command]
+async fn install_ocr_models(state: tauri::State<'_, AppState>) -> Result<OcrStatus, String> {
+    let ocr_provider = state.ocr_provider.clone();
+    tokio::task::spawn_blocking(move || ocr_provider.install_models())
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// Copy the text in an image file, optionally indexing it for content search
+#[tauri::command]
+async fn extract_text_from_image(
+    path: String,
+    index: bool,
+    state: tauri::State<'_, AppState>,
+) -> Result<String, String> {
+    let ocr_provider = state.ocr_provider.clone();
+    tokio::task::spawn_blocking(move || {
+        ocr_provider.extract_file(std::path::Path::new(&path), index)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+#[tauri::command]
+async fn extract_text_from_clipboard(state: tauri::State<'_, AppState>) -> Result<String, String> {
+    let ocr_provider = state.ocr_provider.clone();
+    tokio::task::spawn_blocking(move || ocr_provider.extract_clipboard())
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+// ============================================
+// Dictionary Commands
+// ============================================
+
+#[tauri::command]
+fn get_dictionary_status(state: tauri::State<AppState>) -> DictionaryStatus {
+    state.dictionary_provider.status()
+}
+
+/// Download the WordNet data pack used for offline definitions
+#[tauri::command]
+async fn install_dictionary(state: tauri::State<'_, AppState>) -> Result<DictionaryStatus, String> {
+    let dictionary_provider = state.dictionary_provider.clone();
+    tokio::task::spawn_blocking(move || dictionary_provider.install())
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// Full entry for a word, for the detail view
+#[tauri::command]
+async fn define_word(
+    word: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<Option<DictionaryEntry>, String> {
+    let dictionary_provider = state.dictionary_provider.clone();
+    tokio::task::spawn_blocking(move || dictionary_provider.lookup(&word))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+// ============================================
+// Documentation Commands
+// ============================================
+
+/// Every docset DevDocs offers, for the download picker
+#[tauri::command]
+async fn list_available_docsets(state: tauri::State<'_, AppState>) -> Result<Vec<Docset>, String> {
+    let docs_provider = state.docs_provider.clone();
+    tokio::task::spawn_blocking(move || docs_provider.available())
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+#[tauri::command]
+fn list_installed_docsets(state: tauri::State<AppState>) -> Vec<Docset> {
+    state.docs_provider.installed()
+}
+
+#[tauri::command]
+async fn install_docset(slug: String, state: tauri::State<'_, AppState>) -> Result<Docset, String> {
+    let docs_provider = state.docs_provider.clone();
+    tokio::task::spawn_blocking(move || docs_provider.install(&slug))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+#[tauri::command]
+fn remove_docset(slug: &str, state: tauri::State<AppState>) -> Result<(), String> {
+    state.docs_provider.remove(slug)
+}
+
+/// Reinstall docsets that changed upstream; returns the updated slugs
+#[tauri::command]
+async fn update_docsets(state: tauri::State<'_, AppState>) -> Result<Vec<String>, String> {
+    let docs_provider = state.docs_provider.clone();
+    tokio::task::spawn_blocking(move || docs_provider.update_all())
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// HTML of a page from an installed docset, for the in-app viewer
+#[tauri::command]
+fn get_doc_page(slug: &str, path: &str, state: tauri::State<AppState>) -> Result<String, String> {
+    state.docs_provider.page(slug, path)
+}
+
+// ============================================
+// JSON/YAML Commands
+// ============================================
+
+/// Full output behind a `json:` result; results only carry a short preview
+#[tauri::command]
+fn get_json_output(result_id: &str, state: tauri::State<AppState>) -> Option<String> {
+    state.json_provider.output(result_id)
+}
+
+// ============================================
+// QR Code Commands
+// ============================================
+
+/// Render a QR code into the icon cache for the preview pane, returning the
+/// PNG path
+#[tauri::command]
+async fn generate_qr_code(
+    text: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<String, String> {
+    let qr_provider = state.qr_provider.clone();
+    tokio::task::spawn_blocking(move || qr_provider.generate(&text))
+        .await
+        .map_err(|e| e.to_string())?
+        .map(|path| path.to_string_lossy().into_owned())
+}
+
+/// Decode a QR code from "clipboard", "screen" (interactive region) or the
+/// image at `path`, copying and returning its text
+#[tauri::command]
+async fn scan_qr_code(
+    source: String,
+    path: Option<String>,
+    state: tauri::State<'_, AppState>,
+) -> Result<String, String> {
+    let qr_provider = state.qr_provider.clone();
+    tokio::task::spawn_blocking(move || match (source.as_str(), path) {
+        ("clipboard", _) => qr_provider.scan_clipboard(),
+        ("screen", _) => qr_provider.scan_screen(),
+        ("file", Some(path)) => qr_provider.scan_file(std::path::Path::new(&path)),
+        _ => Err(format!("Unknown QR source: {}", source)),
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+// ============================================
+// TOTP Commands
+// ============================================
+
+/// Current codes for all TOTP accounts, for the live countdown view
+#[tauri::command]
+fn get_totp_codes(state: tauri::State<AppState>) -> Vec<TotpCode> {
+    state.totp_provider.codes()
+}
+
+/// Add a TOTP account from an otpauth:// URI
+#[tauri::command]
+fn import_totp_uri(uri: &str, state: tauri::State<AppState>) -> Result<TotpCode, String> {
+    state.totp_provider.import_uri(uri)
+}
+
+#[tauri::command]
+fn remove_totp_account(id: &str, state: tauri::State<AppState>) -> Result<(), String> {
+    state.totp_provider.remove(id)
+}
+
+/// Export all TOTP accounts, encrypted with the given passphrase
+#[tauri::command]
+fn export_totp(passphrase: &str, state: tauri::State<AppState>) -> Result<String, String> {
+    state.totp_provider.export_encrypted(passphrase)
+}
+
+/// Import TOTP accounts from an encrypted export, returning how many were added
+#[tauri::command]
+fn import_totp_export(
+    data: &str,
+    passphrase: &str,
+    state: tauri::State<AppState>,
+) -> Result<usize, String> {
+    state.totp_provider.import_encrypted(data, passphrase)
+}
+
+// ============================================
+// Time Tracking Commands
+// ============================================
+
+#[tauri::command]
+fn get_time_tracking_status(state: tauri::State<AppState>) -> TimeTrackingStatus {
+    state.time_tracking_provider.status()
+}
+
+/// Validate and store a Toggl API token
+#[tauri::command]
+async fn connect_time_tracking(
+    api_token: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<TimeTrackingStatus, String> {
+    let provider = state.time_tracking_provider.clone();
+    tokio::task::spawn_blocking(move || provider.connect(&api_token))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+#[tauri::command]
+fn disconnect_time_tracking(state: tauri::State<AppState>) -> Result<(), String> {
+    state.time_tracking_provider.disconnect()
+}
+
+/// The running timer, polled by the time tracking widget
+#[tauri::command]
+async fn get_running_timer(
+    state: tauri::State<'_, AppState>,
+) -> Result<Option<RunningTimer>, String> {
+    let provider = state.time_tracking_provider.clone();
+    tokio::task::spawn_blocking(move || provider.running_timer())
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn start_timer(
+    description: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<RunningTimer, String> {
+    let provider = state.time_tracking_provider.clone();
+    tokio::task::spawn_blocking(move || provider.start(&description, None))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+#[tauri::command]
+async fn stop_timer(state: tauri::State<'_, AppState>) -> Result<(), String> {
+    let provider = state.time_tracking_provider.clone();
+    tokio::task::spawn_blocking(move || provider.stop())
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+// ============================================
+// Home Assistant Commands
+// ============================================
+
+#[tauri::command]
+fn get_home_assistant_status(state: tauri::State<AppState>) -> HomeAssistantStatus {
+    state.home_assistant_provider.status()
+}
+
+/// Validate and store the instance URL and long-lived access token
+#[tauri::command]
+async fn connect_home_assistant(
+    url: String,
+    token: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<HomeAssistantStatus, String> {
+    let provider = state.home_assistant_provider.clone();
+    tokio::task::spawn_blocking(move || provider.configure(&url, &token))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+#[tauri::command]
+fn disconnect_home_assistant(state: tauri::State<AppState>) -> Result<(), String> {
+    state.home_assistant_provider.disconnect()
+}
+
+/// All cached entities with their current state
+#[tauri::command]
+fn list_home_assistant_entities(state: tauri::State<AppState>) -> Vec<HaEntity> {
+    state.home_assistant_provider.entities()
+}
+
+// ============================================
+// Weather Commands
+// ============================================
+
+/// Current conditions and the 7-day forecast for a place, for the detail view
+#[tauri::command]
+async fn get_weather_forecast(
+    place: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<WeatherReport, String> {
+    let client = state.weather_client.clone();
+    tokio::task::spawn_blocking(move || client.weather(&place))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+#[tauri::command]
+async fn get_air_quality(
+    place: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<AirQualityReport, String> {
+    let client = state.weather_client.clone();
+    tokio::task::spawn_blocking(move || client.air_quality(&place))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+// ============================================
+// Transport Commands
+// ============================================
+
+/// Flight and train data sources and whether their API keys are set
+#[tauri::command]
+fn get_transport_sources(state: tauri::State<AppState>) -> Vec<TransportSourceInfo> {
+    state.transport_provider.sources()
+}
+
+#[tauri::command]
+fn set_transport_api_key(
+    source: &str,
+    api_key: &str,
+    state: tauri::State<AppState>,
+) -> Result<(), String> {
+    state.transport_provider.set_api_key(source, api_key)
+}
+
+#[tauri::command]
+fn remove_transport_api_key(source: &str, state: tauri::State<AppState>) -> Result<(), String> {
+    state.transport_provider.remove_api_key(source)
+}
+
+/// Drop cached flight and train results so the next search is live
+#[tauri::command]
+fn refresh_transport(state: tauri::State<AppState>) {
+    state.transport_provider.refresh()
+}
+
+// ============================================
+// Package Tracking Commands
+// ============================================
+
+#[tauri::command]
+fn get_package_tracking_status(state: tauri::State<AppState>) -> PackageTrackingStatus {
+    state.package_provider.status()
+}
+
+/// Store the 17TRACK API key used to update package statuses
+#[tauri::command]
+fn connect_package_tracking(
+    api_key: &str,
+    state: tauri::State<AppState>,
+) -> Result<PackageTrackingStatus, String> {
+    state.package_provider.connect(api_key)
+}
+
+#[tauri::command]
+fn disconnect_package_tracking(state: tauri::State<AppState>) -> Result<(), String> {
+    state.package_provider.disconnect()
+}
+
+/// Tracked packages for the deliveries widget
+#[tauri::command]
+fn list_packages(state: tauri::State<AppState>) -> Vec<TrackedPackage> {
+    state.package_provider.packages()
+}
+
+#[tauri::command]
+async fn add_package(
+    tracking_number: String,
+    label: Option<String>,
+    state: tauri::State<'_, AppState>,
+) -> Result<TrackedPackage, String> {
+    let package_provider = state.package_provider.clone();
+    tokio::task::spawn_blocking(move || package_provider.add(&tracking_number, label.as_deref()))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+#[tauri::command]
+fn remove_package(id: &str, state: tauri::State<AppState>) -> Result<(), String> {
+    state.package_provider.remove(id)
+}
+
+/// Check all undelivered packages now and notify about status changes
+#[tauri::command]
+async fn refresh_packages(
+    state: tauri::State<'_, AppState>,
+) -> Result<Vec<TrackedPackage>, String> {
+    let package_provider = state.package_provider.clone();
+    let updates = tokio::task::spawn_blocking(move || package_provider.refresh())
+        .await
+        .map_err(|e| e.to_string())??;
+    if !updates.is_empty() {
+        state
+            .event_bus
+            .publish(AppEvent::PackageStatusChanged(updates));
+    }
+    Ok(state.package_provider.packages())
+}
+
+// ============================================
+// Package Registry Commands
+// ============================================
+
+/// Latest version and stats for a crates.io, npm or PyPI package
+#[tauri::command]
+async fn lookup_registry_package(
+    ecosystem: String,
+    name: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<Option<PackageInfo>, String> {
+    let ecosystem = Ecosystem::parse(&ecosystem)
+        .ok_or_else(|| format!("Unknown package registry: {}", ecosystem))?;
+    let registry_provider = state.registry_provider.clone();
+    tokio::task::spawn_blocking(move || registry_provider.lookup(ecosystem, &name))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+// ============================================
+// HTTP Request Commands
+// ============================================
+
+/// Send a request written in the `http:` syntax, e.g. `GET https://... @preset`
+#[tauri::command]
+async fn run_http_request(
+    request: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<HttpResponse, String> {
+    let request = HttpRequest::parse(&request).ok_or("Invalid HTTP request")?;
+    let http_provider = state.http_provider.clone();
+    tokio::task::spawn_blocking(move || http_provider.send(&request))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// Save the last response body to the downloads folder, returning its path
+#[tauri::command]
+fn save_http_response(state: tauri::State<AppState>) -> Result<String, String> {
+    state
+        .http_provider
+        .save_last_body()
+        .map(|path| path.to_string_lossy().into_owned())
+}
+
+#[tauri::command]
+fn list_http_presets(state: tauri::State<AppState>) -> Vec<String> {
+    state.http_provider.list_presets()
+}
+
+/// Store a header preset (one `Name: value` per line) in the vault
+#[tauri::command]
+fn set_http_preset(
+    name: String,
+    headers: String,
+    state: tauri::State<AppState>,
+) -> Result<(), String> {
+    state.http_provider.set_preset(&name, &headers)
+}
+
+#[tauri::command]
+fn remove_http_preset(name: String, state: tauri::State<AppState>) -> Result<(), String> {
+    state.http_provider.remove_preset(&name)
+}
+
+// ============================================
+// Startup Commands
+// ============================================
+
+/// Whether the launcher started in safe mode, for the UI banner
+#[tauri::command]
+fn get_startup_mode(state: tauri::State<AppState>) -> StartupMode {
+    state.startup_mode.clone()
+}
+
+/// Clear the crash history and restart normally
+#[tauri::command]
+fn exit_safe_mode(app: AppHandle) {
+    startup::reset();
+    // Restarting with the same arguments would keep `--safe-mode`
+    let mut env = app.env();
+    env.args_os.retain(|arg| arg != "--safe-mode");
+    app.cleanup_before_exit();
+    tauri::process::restart(&env);
+}
+
+// ============================================
+// Intent Commands
+// ============================================
+
+#[tauri::command]
+fn get_intent_rules(state: tauri::State<AppState>) -> Vec<IntentRule> {
+    state.intent_router.list_rules()
+}
+
+/// Reload built-in rules and the user's intents.json
+#[tauri::command]
+fn reload_intent_rules(state: tauri::State<AppState>) -> Vec<IntentRule> {
+    state.intent_router.reload();
+    state.intent_router.list_rules()
+}
+
+/// Show which intent, if any, a query would be routed to
+#[tauri::command]
+fn classify_query(query: &str, state: tauri::State<AppState>) -> Option<IntentMatch> {
+    state.intent_router.route(query)
+}
+
+// ============================================
+// Context Commands
+// ============================================
+
+/// The app the launcher was summoned over and its matching profile, if any
+#[tauri::command]
+fn get_active_context(state: tauri::State<AppState>) -> ActiveContext {
+    state.context_tracker.active()
+}
+
+// ============================================
+// Accessibility Commands
+// ============================================
+
+/// Whether the launcher may read and control other apps' menus and windows
+/// (macOS asks for this once; always true elsewhere)
+#[tauri::command]
+fn check_accessibility_permission() -> bool {
+    ax::is_trusted()
+}
+
+/// Open the System Settings pane where Accessibility access is granted
+#[tauri::command]
+fn open_accessibility_settings() -> Result<(), String> {
+    ax::open_settings()
+}
+
+// ============================================
+// Power Management Commands
+// ============================================
+
+/// Get the power state and which background subsystems are paused. The
+/// state is as of the last poll, which publishes `power-state-changed` when
+/// it changes.
+#[tauri::command]
+fn get_power_status(state: tauri::State<AppState>) -> PowerStatus {
+    state.power_monitor.status()
+}
+
+/// Battery charge and health plus device batteries, for the battery widget
+#[tauri::command]
+async fn get_battery_report(
+    refresh: Option<bool>,
+    state: tauri::State<'_, AppState>,
+) -> Result<BatteryReport, String> {
+    let battery_provider = state.battery_provider.clone();
+    tokio::task::spawn_blocking(move || battery_provider.report(refresh.unwrap_or(false)))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+// ============================================
+// Focus Commands
+// ============================================
+
+/// Start a focus session of `minutes`, or the configured default length
+#[tauri::command]
+async fn start_focus(
+    minutes: Option<u32>,
+    state: tauri::State<'_, AppState>,
+) -> Result<FocusStatus, String> {
+    let focus_manager = state.focus_manager.clone();
+    let minutes = minutes.unwrap_or_else(|| focus_manager.default_minutes());
+    tokio::task::spawn_blocking(move || focus_manager.start(minutes))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// End the running focus session and undo its changes
+#[tauri::command]
+async fn stop_focus(state: tauri::State<'_, AppState>) -> Result<bool, String> {
+    let focus_manager = state.focus_manager.clone();
+    tokio::task::spawn_blocking(move || focus_manager.stop())
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+fn get_focus_status(state: tauri::State<AppState>) -> FocusStatus {
+    state.focus_manager.status()
+}
+
+// ============================================
+// VPN Commands
+// ============================================
+
+/// Detected VPNs with their states and the Tailscale exit nodes
+#[tauri::command]
+async fn get_vpn_status(
+    refresh: Option<bool>,
+    state: tauri::State<'_, AppState>,
+) -> Result<VpnStatus, String> {
+    let vpn_provider = state.vpn_provider.clone();
+    tokio::task::spawn_blocking(move || vpn_provider.status(refresh.unwrap_or(false)))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+// ============================================
+// Reminder Commands
+// ============================================
+
+#[tauri::command]
+fn list_reminders(state: tauri::State<AppState>) -> Vec<Reminder> {
+    state.reminders.list()
+}
+
+/// Add a reminder from text such as "submit the report at 4pm"
+#[tauri::command]
+fn add_reminder(text: String, state: tauri::State<AppState>) -> Result<Reminder, String> {
+    state.reminders.add_text(&text)
+}
+
+#[tauri::command]
+fn snooze_reminder(
+    id: String,
+    minutes: i64,
+    state: tauri::State<AppState>,
+) -> Result<Reminder, String> {
+    state.reminders.snooze(&id, minutes)
+}
+
+#[tauri::command]
+fn complete_reminder(id: String, state: tauri::State<AppState>) -> Result<(), String> {
+    state.reminders.complete(&id)
+}
+
 // ============================================
-// Terminal Widget Commands
+// Schedule Commands
 // ============================================
 
-/// Spawn a new terminal session
+/// Run a schedule's target and record how it went, notifying if it failed
+fn run_schedule(app: &AppHandle, schedule: &Schedule, reason: RunReason) -> ScheduleRun {
+    let state = app.state::<AppState>();
+    let result = match &schedule.target {
+        ScheduleTarget::Result {
+            result_id,
+            action_id,
+        } => provider_for(result_id, &state.providers)
+            .ok_or_else(|| "No provider found for result".to_string())
+            .and_then(|provider| match action_id {
+                Some(action_id) if action_id != DEFAULT_ACTION => {
+                    provider.execute_action(result_id, action_id)
+                }
+                _ => provider.execute(result_id),
+            }),
+        ScheduleTarget::Quicklink { name, query } => state
+            .settings
+            .get()
+            .quicklinks
+            .iter()
+            .find(|q| q.name.eq_ignore_ascii_case(name))
+            .ok_or_else(|| format!("Quicklink '{}' not found", name))
+            .and_then(|q| providers::url::open_url(&providers::quicklinks::fill(&q.url, query))),
+    };
+
+    if let (Err(e), true) = (&result, schedule.notify_on_failure) {
+        let title = format!("Schedule failed: {}", schedule.name);
+        if let Err(e) = notifications::show(app, &title, e) {
+            eprintln!("{}", e);
+        }
+    }
+    let run = state.scheduler.record(schedule, reason, result);
+    state.event_bus.publish(AppEvent::ScheduleRan(run.clone()));
+    run
+}
+
 #[tauri::command]
-fn terminal_spawn(
-    id: String,
-    cols: u16,
-    rows: u16,
-    cwd: Option<String>,
+fn get_schedules(state: tauri::State<AppState>) -> Vec<Schedule> {
+    state.settings.get().schedules
+}
+
+/// Save a schedule, replacing the one with the same id; one without an id
+/// is added
+#[tauri::command]
+fn save_schedule(
+    mut schedule: Schedule,
     state: tauri::State<AppState>,
-) -> Result<(), String> {
-    state.terminal_manager.spawn_terminal(id, cols, rows, cwd)
+) -> Result<Schedule, String> {
+    schedule.name = schedule.name.trim().to_string();
+    if schedule.id.is_empty() {
+        schedule.id = uuid::Uuid::new_v4().to_string();
+    }
+    scheduler::validate(&schedule)?;
+    state.settings.save_schedule(schedule.clone());
+    Ok(schedule)
 }
 
-/// Write input to a terminal session
 #[tauri::command]
-fn terminal_write(id: String, data: String, state: tauri::State<AppState>) -> Result<(), String> {
-    state.terminal_manager.write_to_terminal(&id, &data)
+fn remove_schedule(id: String, state: tauri::State<AppState>) {
+    state.settings.remove_schedule(&id);
 }
 
-/// Resize a terminal session
+/// Past schedule runs, newest first
 #[tauri::command]
-fn terminal_resize(
-    id: String,
-    cols: u16,
-    rows: u16,
+fn get_schedule_history(state: tauri::State<AppState>) -> Vec<ScheduleRun> {
+    state.scheduler.history()
+}
+
+/// Devices connected now, to pick one for a device trigger
+#[tauri::command]
+async fn get_connected_devices(
+    state: tauri::State<'_, AppState>,
+) -> Result<Vec<ConnectedDevice>, String> {
+    let bluetooth_provider = state.bluetooth_provider.clone();
+    tokio::task::spawn_blocking(move || {
+        let buses = std::collections::HashSet::from([DeviceBus::Bluetooth, DeviceBus::Usb]);
+        let mut devices: Vec<ConnectedDevice> = devices::connected(&buses, &bluetooth_provider)
+            .into_values()
+            .flatten()
+            .collect();
+        devices.sort_by(|a, b| a.name.to_lowercase().cmp(&b.name.to_lowercase()));
+        devices
+    })
+    .await
+    .map_err(|e| e.to_string())
+}
+
+/// Changes to settings, shortcuts and plugins, newest first; only those
+/// since `since` if given
+#[tauri::command]
+fn get_change_history(
+    since: Option<chrono::DateTime<chrono::Utc>>,
     state: tauri::State<AppState>,
-) -> Result<(), String> {
-    state.terminal_manager.resize_terminal(&id, cols, rows)
+) -> Vec<Change> {
+    state.change_log.history(since)
 }
 
-/// Close a terminal session
+/// Recent security decisions, like plugin commands allowed or refused,
+/// newest first
 #[tauri::command]
-fn terminal_close(id: String, state: tauri::State<AppState>) -> Result<(), String> {
-    state.terminal_manager.close_terminal(&id)
+fn get_audit_log(limit: Option<usize>) -> Vec<AuditEvent> {
+    audit::recent(limit.unwrap_or(100))
 }
 
-/// Check if a terminal session exists
+/// Reverse the latest destructive action and return it, or None when
+/// there's nothing to undo. An action that can no longer be reversed, like
+/// a file emptied from the trash, is dropped with an error.
 #[tauri::command]
-fn terminal_exists(id: String, state: tauri::State<AppState>) -> bool {
-    state.terminal_manager.has_terminal(&id)
+async fn undo_last_action(state: tauri::State<'_, AppState>) -> Result<Option<UndoEntry>, String> {
+    let Some(entry) = state.undo_stack.pop() else {
+        return Ok(None);
+    };
+    match entry.action.clone() {
+        UndoableAction::UnpinApp { app_id, position } => {
+            state.settings.pin_app_at(app_id, position);
+        }
+        UndoableAction::RemoveWidgets { placements } => {
+            if state.startup_mode.safe_mode {
+                // Keep it for after safe mode
+                state.undo_stack.push(entry);
+                return Err("Widgets can't be restored in safe mode".to_string());
+            }
+            state.settings.restore_widgets(placements);
+        }
+        UndoableAction::RemoveQuicklink { quicklink } => {
+            state.settings.add_quicklink(quicklink);
+        }
+        UndoableAction::RemoveAlias { alias } => {
+            state.settings.add_alias(alias);
+        }
+        UndoableAction::TrashFile { path, trashed } => {
+            let file_provider = state.file_provider.clone();
+            tokio::task::spawn_blocking(move || {
+                file_ops::restore_from_trash(&path, trashed.as_deref())?;
+                file_provider.update_file(&path)
+            })
+            .await
+            .map_err(|e| e.to_string())??;
+        }
+        UndoableAction::DisablePlugin { id, .. } => {
+            enable_and_load_plugin(&state, &id)?;
+        }
+        UndoableAction::RenameFile { from, to } => {
+            let file_provider = state.file_provider.clone();
+            tokio::task::spawn_blocking(move || {
+                let name = from.file_name().ok_or("Invalid file name")?;
+                file_ops::rename_in_place(&to, &name.to_string_lossy())?;
+                let _ = file_provider.update_file(&to);
+                file_provider.update_file(&from)
+            })
+            .await
+            .map_err(|e| e.to_string())??;
+        }
+        UndoableAction::EditQuicklink { before, name } => {
+            // `name` is what the quicklink is called since the edit
+            state.settings.replace_quicklink(&name, before);
+        }
+    }
+    Ok(Some(entry))
 }
 
-/// List all active terminal sessions
+/// Actions `undo_last_action` can still reverse, newest first
 #[tauri::command]
-fn terminal_list(state: tauri::State<AppState>) -> Vec<String> {
-    state.terminal_manager.list_terminals()
+fn get_undo_history(state: tauri::State<AppState>) -> Vec<UndoEntry> {
+    state.undo_stack.history()
+}
+
+/// Run a schedule now, whatever its trigger
+#[tauri::command]
+async fn run_schedule_now(
+    id: String,
+    app: AppHandle,
+    state: tauri::State<'_, AppState>,
+) -> Result<ScheduleRun, String> {
+    let schedule = state
+        .settings
+        .get()
+        .schedules
+        .into_iter()
+        .find(|s| s.id == id)
+        .ok_or("Schedule not found")?;
+    tokio::task::spawn_blocking(move || run_schedule(&app, &schedule, RunReason::Manual))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+// ============================================
+// Diagnostics Commands
+// ============================================
+
+/// Get memory used by registered caches against the configured budget
+#[tauri::command]
+fn get_cache_usage(state: tauri::State<AppState>) -> CacheUsageReport {
+    state.cache_coordinator.usage()
+}
+
+/// Evict cache entries until memory usage fits the budget
+#[tauri::command]
+fn trim_caches(state: tauri::State<AppState>) -> CacheUsageReport {
+    state.cache_coordinator.enforce_budget();
+    state.cache_coordinator.usage()
+}
+
+/// Get search timing aggregates, overall and per provider
+#[tauri::command]
+fn get_latency_report(state: tauri::State<AppState>) -> LatencyReport {
+    state.latency.report()
 }
 
 fn toggle_window(app: &AppHandle) {
@@ -1507,6 +4507,15 @@ fn toggle_window(app: &AppHandle) {
         } else {
             let _ = window.show();
             let _ = window.set_focus();
+            // Find the app we were summoned over without holding up the window
+            if let Some(state) = app.try_state::<AppState>() {
+                let tracker = state.context_tracker.clone();
+                let app = app.clone();
+                tauri::async_runtime::spawn_blocking(move || {
+                    tracker.capture();
+                    let _ = app.emit("launcher-context", tracker.active());
+                });
+            }
         }
     }
 }
@@ -1527,6 +4536,15 @@ pub fn run() {
 
     eprintln!("Launcher starting...");
 
+    // Recorded once the single-instance check has passed, in setup
+    let startup_mode = startup::detect();
+    if startup_mode.safe_mode {
+        eprintln!(
+            "Starting in safe mode ({:?}): plugins, OAuth providers and widgets are disabled",
+            startup_mode.reason
+        );
+    }
+
     let file_provider = Arc::new(FileProvider::new());
     eprintln!("FileProvider initialized");
 
@@ -1536,10 +4554,20 @@ pub fn run() {
     let settings = Arc::new(SettingsStore::new());
     eprintln!("SettingsStore initialized");
 
+    let change_log = Arc::new(ChangeLog::new());
+    let settings_change_log = change_log.clone();
+    secrets::set_file_fallback(settings.get().secret_file_fallback);
+    settings.set_listener(move |before, after| {
+        settings_change_log.record_settings(before, after);
+        secrets::set_file_fallback(after.secret_file_fallback);
+    });
+    let undo_stack = Arc::new(UndoStack::new());
+    let exec_policy = Arc::new(ExecPolicy::new(settings.clone()));
+
     let plugin_loader = Arc::new(PluginLoader::new());
     eprintln!("PluginLoader initialized");
 
-    let plugin_runtime = match PluginRuntime::new() {
+    let plugin_runtime = match PluginRuntime::new(settings.clone()) {
         Ok(runtime) => Arc::new(runtime),
         Err(e) => {
             eprintln!(
@@ -1547,7 +4575,7 @@ pub fn run() {
                 e
             );
             // Create a dummy runtime or handle gracefully
-            Arc::new(PluginRuntime::new().expect("Plugin runtime failed twice"))
+            Arc::new(PluginRuntime::new(settings.clone()).expect("Plugin runtime failed twice"))
         }
     };
     eprintln!("PluginRuntime initialized");
@@ -1555,9 +4583,25 @@ pub fn run() {
     let plugin_provider = Arc::new(PluginProvider::new(
         plugin_loader.clone(),
         plugin_runtime.clone(),
+        exec_policy.clone(),
     ));
     eprintln!("PluginProvider initialized");
 
+    // Results from a plugin's previous build may not act the same any more
+    let event_bus = Arc::new(EventBus::new());
+    let stale_plugin_results = plugin_provider.clone();
+    event_bus.subscribe(move |event| match event {
+        AppEvent::PluginReloaded(reloaded) => {
+            stale_plugin_results.forget_plugin(&reloaded.plugin_id)
+        }
+        AppEvent::PluginQuarantined(report) => {
+            stale_plugin_results.forget_plugin(&report.plugin_id)
+        }
+        _ => {}
+    });
+
+    let latency = Arc::new(LatencyTracker::new(settings.clone(), SEARCH_BUDGET));
+
     let plugin_registry = Arc::new(PluginRegistry::new());
     // Load from cache first for fast startup
     let _ = plugin_registry.load_cache();
@@ -1566,8 +4610,12 @@ pub fn run() {
     let command_registry = Arc::new(CommandRegistry::new());
     eprintln!("CommandRegistry initialized with built-in commands");
 
+    let intent_router = Arc::new(IntentRouter::new());
+    eprintln!("IntentRouter initialized");
+
     let token_storage = Arc::new(TokenStorage::new());
     let oauth_flow = Arc::new(OAuthFlow::new(token_storage));
+    HOST_API.set_oauth_flow(oauth_flow.clone());
     let callback_server = Arc::new(CallbackServer::new());
     let web_auth = Arc::new(WebAuth::new(&CONFIG.web_app_url));
     eprintln!("OAuth components initialized");
@@ -1578,41 +4626,144 @@ pub fn run() {
     let terminal_manager = Arc::new(terminal::TerminalManager::new());
     eprintln!("TerminalManager initialized");
 
-    oauth_flow.register_provider(OAuthGitHubConfig::new(None, None).config().clone());
-    oauth_flow.register_provider(OAuthGoogleConfig::new(None, None).config().clone());
-    oauth_flow.register_provider(OAuthNotionConfig::new(None, None).config().clone());
-    oauth_flow.register_provider(OAuthSlackConfig::new(None, None).config().clone());
-    eprintln!("OAuth providers registered");
-
-    let github_provider = Arc::new(GitHubProvider::new(oauth_flow.clone()));
-    let notion_provider = Arc::new(NotionProvider::new(oauth_flow.clone()));
+    let workspace_trust = Arc::new(WorkspaceTrust::new(settings.clone()));
+
+    let power_monitor = Arc::new(PowerMonitor::new(settings.clone()));
+    eprintln!("PowerMonitor initialized");
+
+    let context_tracker = Arc::new(ContextTracker::new(settings.clone()));
+
+    let cache_coordinator = Arc::new(CacheCoordinator::new(settings.clone()));
+    cache_coordinator.register(plugin_loader.clone());
+    let search_cache = Arc::new(SearchCache::new());
+    cache_coordinator.register(search_cache.clone());
+    let thumbnails = Arc::new(Thumbnails::new());
+    thumbnails.start();
+    cache_coordinator.register(thumbnails.clone());
+    eprintln!("CacheCoordinator initialized");
+
+    let remote_provider = Arc::new(RemoteProvider::new());
+    let browse_provider = Arc::new(BrowseProvider::new());
+    let windows_provider = Arc::new(WindowsProvider::new());
+    let feed_provider = Arc::new(FeedProvider::new());
+    let clipboard_provider = Arc::new(ClipboardProvider::new());
+    let capture_provider = Arc::new(CaptureProvider::new(
+        settings.clone(),
+        file_provider.clone(),
+    ));
+    let cleanup_provider = Arc::new(CleanupProvider::new(file_provider.clone()));
+    let rename_provider = Arc::new(RenameProvider::new(file_provider.clone()));
+    let ocr_provider = Arc::new(OcrProvider::new(file_provider.clone()));
+    let dictionary_provider = Arc::new(DictionaryProvider::new());
+    let docs_provider = Arc::new(DocsProvider::new());
+    let json_provider = Arc::new(JsonProvider::new());
+    let qr_provider = Arc::new(QrProvider::new());
+
+    let vault = Arc::new(SecretVault::new());
+    let totp_provider = Arc::new(TotpProvider::new(vault.clone()));
+    let time_tracking_provider = Arc::new(TimeTrackingProvider::new(vault.clone()));
+    let home_assistant_provider = Arc::new(HomeAssistantProvider::new(vault.clone()));
+    let weather_client = Arc::new(WeatherClient::new());
+    let exchange_rates = Arc::new(ExchangeRates::new(settings.clone()));
+    let transport_provider = Arc::new(TransportProvider::new(vault.clone()));
+    let package_provider = Arc::new(PackageProvider::new(vault.clone()));
+    let registry_provider = Arc::new(RegistryProvider::new());
+    let http_provider = Arc::new(HttpProvider::new(vault.clone()));
+    let battery_provider = Arc::new(BatteryProvider::new());
+    let vpn_provider = Arc::new(VpnProvider::new());
+    let bluetooth_provider = Arc::new(BluetoothProvider::new(frecency.clone()));
     let slack_provider = Arc::new(SlackProvider::new(oauth_flow.clone()));
-    let google_drive_provider = Arc::new(GoogleDriveProvider::new(oauth_flow.clone()));
-    let google_calendar_provider = Arc::new(GoogleCalendarProvider::new(oauth_flow.clone()));
+    let focus_manager = Arc::new(FocusManager::new(
+        settings.clone(),
+        power_monitor.clone(),
+        slack_provider.clone(),
+    ));
+    let reminders = Arc::new(ReminderStore::new());
     eprintln!("Search providers created");
 
     eprintln!("Creating AppProvider...");
     let app_provider = Arc::new(AppProvider::new());
     eprintln!("AppProvider initialized");
 
-    let providers: Vec<Arc<dyn SearchProvider>> = vec![
-        Arc::new(CalculatorProvider::new()),
+    let mut providers: Vec<Arc<dyn SearchProvider>> = vec![
+        Arc::new(CalculatorProvider::new(exchange_rates, settings.clone())),
         Arc::new(UrlProvider::new()),
         Arc::new(SystemProvider::new()),
-        app_provider,
+        Arc::new(SettingsPaneProvider::new()),
+        Arc::new(WindowManagerProvider::new(
+            settings.clone(),
+            context_tracker.clone(),
+        )),
+        windows_provider.clone(),
+        Arc::new(ProcessProvider::new()),
+        Arc::new(WorkspaceProvider::new()),
+        battery_provider.clone(),
+        vpn_provider.clone(),
+        bluetooth_provider.clone(),
+        Arc::new(FocusProvider::new(focus_manager.clone())),
+        Arc::new(ReminderProvider::new(reminders.clone())),
+        Arc::new(MenuSearchProvider::new(context_tracker.clone())),
+        totp_provider.clone(),
+        app_provider.clone(),
         file_provider.clone(),
-        plugin_provider,
-        github_provider,
-        notion_provider,
-        slack_provider,
-        google_drive_provider,
-        google_calendar_provider,
-        Arc::new(WebSearchProvider::new()), // Low priority, shows as fallback
+        remote_provider.clone(),
+        rename_provider.clone(),
+        browse_provider.clone(),
+        feed_provider.clone(),
+        clipboard_provider.clone(),
+        capture_provider.clone(),
+        cleanup_provider.clone(),
+        Arc::new(DiskUsageProvider::new(
+            file_provider.clone(),
+            undo_stack.clone(),
+        )),
+        Arc::new(ContentSearchProvider::new(file_provider.clone())),
+        ocr_provider.clone(),
+        dictionary_provider.clone(),
+        docs_provider.clone(),
+        json_provider.clone(),
+        Arc::new(RegexProvider),
+        Arc::new(TextToolsProvider::new()),
+        Arc::new(FakeDataProvider::new()),
+        qr_provider.clone(),
+        Arc::new(QuicklinkProvider::new(settings.clone())),
+        plugin_provider.clone(),
     ];
+
+    if !startup_mode.safe_mode {
+        oauth_flow.register_provider(OAuthGitHubConfig::new(None, None).config().clone());
+        oauth_flow.register_provider(OAuthGoogleConfig::new(None, None).config().clone());
+        oauth_flow.register_provider(OAuthNotionConfig::new(None, None).config().clone());
+        oauth_flow.register_provider(OAuthSlackConfig::new(None, None).config().clone());
+        oauth_flow.load_custom_providers();
+        eprintln!("OAuth providers registered");
+
+        providers.push(Arc::new(GitHubProvider::new(oauth_flow.clone())));
+        providers.push(Arc::new(NotionProvider::new(oauth_flow.clone())));
+        providers.push(slack_provider);
+        providers.push(Arc::new(GoogleDriveProvider::new(oauth_flow.clone())));
+        providers.push(Arc::new(GoogleCalendarProvider::new(oauth_flow.clone())));
+        providers.push(time_tracking_provider.clone());
+        providers.push(home_assistant_provider.clone());
+        providers.push(Arc::new(WeatherProvider::new(weather_client.clone())));
+        providers.push(transport_provider.clone());
+        providers.push(package_provider.clone());
+        providers.push(registry_provider.clone());
+        providers.push(http_provider.clone());
+        providers.push(Arc::new(StackOverflowProvider::new(vault.clone())));
+    }
+
+    providers.push(Arc::new(WebSearchProvider::new()));
+    providers.push(Arc::new(FallbackProvider::new(settings.clone()))); // Low priority
     eprintln!("All providers ready, starting Tauri...");
 
     tauri::Builder::default()
+        .register_asynchronous_uri_scheme_protocol(thumbnails::SCHEME, |ctx, request, responder| {
+            let state = ctx.app_handle().state::<AppState>();
+            thumbnails::handle(&state.thumbnails, request, responder);
+        })
         .plugin(tauri_plugin_dialog::init())
+        .plugin(tauri_plugin_notification::init())
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_deep_link::init())
         .plugin(tauri_plugin_single_instance::init(|app, args, _cwd| {
@@ -1637,7 +4788,9 @@ pub fn run() {
                                                 "Emitting auth-callback with token: {}...",
                                                 &value[..8.min(value.len())]
                                             );
-                                            let _ = app.emit("auth-callback", value.to_string());
+                                            app.state::<AppState>()
+                                                .event_bus
+                                                .publish(AppEvent::AuthCallback(value.to_string()));
                                         }
                                     }
                                 }
@@ -1655,7 +4808,11 @@ pub fn run() {
                                                 "Received install request for plugin: {}",
                                                 value
                                             );
-                                            let _ = app.emit("install-plugin", value.to_string());
+                                            app.state::<AppState>()
+                                                .event_bus
+                                                .publish(AppEvent::InstallPlugin(
+                                                    value.to_string(),
+                                                ));
                                             // Show the window so user can see the installation
                                             if let Some(window) = app.get_webview_window("main") {
                                                 let _ = window.show();
@@ -1672,23 +4829,75 @@ pub fn run() {
         }))
         .manage(AppState {
             providers,
+            search_cancel: Default::default(),
+            grouped_results: Default::default(),
             file_provider,
+            remote_provider,
+            rename_provider,
+            browse_provider,
+            windows_provider,
+            feed_provider,
+            clipboard_provider,
+            capture_provider,
+            cleanup_provider,
+            ocr_provider,
+            dictionary_provider,
+            docs_provider,
+            json_provider,
+            qr_provider,
+            totp_provider,
+            time_tracking_provider,
+            home_assistant_provider,
+            weather_client,
+            transport_provider,
+            package_provider,
+            registry_provider,
+            http_provider,
+            battery_provider,
+            vpn_provider,
+            bluetooth_provider,
+            plugin_provider,
+            focus_manager,
+            reminders,
+            scheduler: Arc::new(Scheduler::new()),
+            app_provider,
             frecency,
+            latency,
             settings,
+            change_log,
+            undo_stack,
+            exec_policy,
             plugin_loader,
             plugin_runtime,
             plugin_registry,
             command_registry,
+            intent_router,
+            context_tracker,
             oauth_flow,
             callback_server,
             web_auth,
             codex_manager,
             terminal_manager,
+            workspace_trust,
+            power_monitor,
+            cache_coordinator,
+            search_cache,
+            thumbnails,
+            event_bus,
+            startup_mode,
         })
         .invoke_handler(tauri::generate_handler![
             search,
+            search_grouped,
+            search_section,
+            export_results,
             execute_result,
+            get_actions,
+            execute_action,
+            get_result_mutations,
+            mutate_result,
             get_system_theme,
+            get_accessibility_preferences,
             hide_window,
             show_window,
             start_indexing,
@@ -1696,7 +4905,19 @@ pub fn run() {
             list_plugins,
             enable_plugin,
             disable_plugin,
+            get_plugin_health,
+            get_plugin_config,
+            set_plugin_config,
+            get_plugin_storage_usage,
+            clear_plugin_data,
             get_plugins_dir,
+            set_plugin_dev_mode,
+            set_plugin_host_permission,
+            get_plugin_host_permissions,
+            clear_plugin_host_permission,
+            set_plugin_oauth_consent,
+            get_plugin_oauth_consents,
+            clear_plugin_oauth_consent,
             get_index_config,
             set_index_config,
             // Command registry commands
@@ -1713,11 +4934,31 @@ pub fn run() {
             update_widget_layout,
             pin_app,
             unpin_app,
+            get_quicklinks,
+            add_quicklink,
+            remove_quicklink,
+            list_aliases,
+            add_alias,
+            remove_alias,
+            get_fallbacks,
+            set_fallbacks,
+            list_search_providers,
+            set_provider_settings,
             get_suggested_apps,
+            refresh_apps,
+            uninstall_app,
+            // Frecency commands
+            clear_frecency,
+            get_frecency_stats,
+            export_frecency,
+            import_frecency,
             list_oauth_providers,
+            add_custom_oauth_provider,
+            remove_custom_oauth_provider,
             start_oauth,
             complete_oauth,
             disconnect_oauth,
+            refresh_oauth_token,
             is_oauth_connected,
             get_oauth_credentials,
             set_oauth_credentials,
@@ -1725,8 +4966,10 @@ pub fn run() {
             search_marketplace,
             get_marketplace_categories,
             get_marketplace_plugin,
+            inspect_marketplace_plugin,
             install_plugin,
             uninstall_plugin,
+            forget_plugin_signer,
             check_plugin_updates,
             update_plugin,
             refresh_marketplace,
@@ -1761,6 +5004,7 @@ pub fn run() {
             codex_stop_session,
             codex_get_session_info,
             codex_poll_output,
+            codex_ask_about_result,
             // Codex dev server commands
             codex_start_dev_server,
             codex_stop_dev_server,
@@ -1772,15 +5016,175 @@ pub fn run() {
             terminal_close,
             terminal_exists,
             terminal_list,
+            // Workspace trust commands
+            get_trusted_folders,
+            trust_folder,
+            untrust_folder,
             // Global shortcut commands
             get_default_shortcut,
             get_current_shortcut,
-            set_global_shortcut
+            set_global_shortcut,
+            validate_shortcut,
+            // File operation commands
+            compress_files,
+            extract_archive,
+            preview_bulk_rename,
+            bulk_rename,
+            // Remote location commands
+            list_remote_locations,
+            save_remote_location,
+            remove_remote_location,
+            check_remote_location,
+            list_remote_directory,
+            browse_directory,
+            complete_path,
+            // Feed commands
+            list_feed_subscriptions,
+            subscribe_feed,
+            unsubscribe_feed,
+            import_opml,
+            export_opml,
+            get_unread_feed_items,
+            mark_feed_item_read,
+            archive_feed_item,
+            refresh_feeds,
+            // Clipboard history commands
+            get_clipboard_history,
+            pin_clipboard_entry,
+            delete_clipboard_entry,
+            clear_clipboard_history,
+            // Cleanup commands
+            preview_cleanup,
+            run_cleanup,
+            get_largest_entries,
+            // Capture commands
+            take_screenshot,
+            start_screen_recording,
+            stop_screen_recording,
+            get_recording_status,
+            copy_image_to_clipboard,
+            // OCR
+            get_ocr_status,
+            install_ocr_models,
+            extract_text_from_image,
+            extract_text_from_clipboard,
+            // Dictionary
+            get_dictionary_status,
+            install_dictionary,
+            define_word,
+            // Documentation
+            list_available_docsets,
+            list_installed_docsets,
+            install_docset,
+            remove_docset,
+            update_docsets,
+            get_doc_page,
+            // JSON/YAML
+            get_json_output,
+            // QR code commands
+            generate_qr_code,
+            scan_qr_code,
+            // TOTP commands
+            get_totp_codes,
+            import_totp_uri,
+            remove_totp_account,
+            export_totp,
+            import_totp_export,
+            // Time tracking commands
+            get_time_tracking_status,
+            connect_time_tracking,
+            disconnect_time_tracking,
+            get_running_timer,
+            start_timer,
+            stop_timer,
+            // Home Assistant commands
+            get_home_assistant_status,
+            connect_home_assistant,
+            disconnect_home_assistant,
+            list_home_assistant_entities,
+            // Weather
+            get_weather_forecast,
+            get_air_quality,
+            // Transport
+            get_transport_sources,
+            set_transport_api_key,
+            remove_transport_api_key,
+            refresh_transport,
+            // Package tracking
+            get_package_tracking_status,
+            connect_package_tracking,
+            disconnect_package_tracking,
+            list_packages,
+            add_package,
+            remove_package,
+            refresh_packages,
+            // Package registries
+            lookup_registry_package,
+            // HTTP requests
+            run_http_request,
+            save_http_response,
+            list_http_presets,
+            set_http_preset,
+            remove_http_preset,
+            // Intent commands
+            get_intent_rules,
+            reload_intent_rules,
+            classify_query,
+            // Context commands
+            get_active_context,
+            // Accessibility commands
+            check_accessibility_permission,
+            open_accessibility_settings,
+            // Window management commands
+            get_window_commands,
+            run_window_command,
+            set_window_hotkey,
+            // Power management commands
+            get_power_status,
+            get_battery_report,
+            // Focus commands
+            start_focus,
+            stop_focus,
+            get_focus_status,
+            // VPN commands
+            get_vpn_status,
+            // Reminder commands
+            list_reminders,
+            add_reminder,
+            snooze_reminder,
+            complete_reminder,
+            get_schedules,
+            save_schedule,
+            remove_schedule,
+            get_schedule_history,
+            run_schedule_now,
+            get_connected_devices,
+            get_change_history,
+            undo_last_action,
+            get_undo_history,
+            get_audit_log,
+            // Startup commands
+            get_startup_mode,
+            exit_safe_mode,
+            // Diagnostics commands
+            get_cache_usage,
+            trim_caches,
+            get_latency_report
         ])
         .setup(|app| {
-            // Set up terminal manager with app handle for event emission
             let state = app.state::<AppState>();
+
+            // A second instance has exited by now, so it can't roll back
+            // this one's operations or count as a crash
+            let recovered = journal::recover();
+            if !recovered.is_empty() {
+                eprintln!("Rolled back {} incomplete operation(s)", recovered.len());
+            }
+            startup::begin(&state.startup_mode);
+
+            // Set up terminal manager with app handle for event emission
             state.terminal_manager.set_app_handle(app.handle().clone());
+            state.event_bus.attach(app.handle().clone());
 
             // Set up system tray
             let show_item = MenuItem::with_id(app, "show", "Show Launcher", true, None::<&str>)?;
@@ -1836,9 +5240,20 @@ pub fn run() {
 
             app.handle().plugin(
                 tauri_plugin_global_shortcut::Builder::new()
-                    .with_handler(move |_app, _shortcut, event| {
-                        if event.state() == ShortcutState::Pressed {
-                            toggle_window(&app_handle);
+                    .with_handler(move |_app, shortcut, event| {
+                        if event.state() != ShortcutState::Pressed {
+                            return;
+                        }
+                        let state = app_handle.state::<AppState>();
+                        match window_action_for_shortcut(shortcut, &state) {
+                            Some(action) => {
+                                std::thread::spawn(move || {
+                                    if let Err(e) = window_manager::apply(action, None) {
+                                        eprintln!("Window command failed: {}", e);
+                                    }
+                                });
+                            }
+                            None => toggle_window(&app_handle),
                         }
                     })
                     .build(),
@@ -1871,6 +5286,7 @@ pub fn run() {
             } else {
                 eprintln!("Global shortcut disabled by user settings");
             }
+            register_window_hotkeys(app.handle(), &settings.window_hotkeys);
 
             let state = app.state::<AppState>();
 
@@ -1908,16 +5324,58 @@ pub fn run() {
             let plugin_loader = state.plugin_loader.clone();
             let plugin_runtime = state.plugin_runtime.clone();
 
-            let callback_server = state.callback_server.clone();
-            let oauth_flow = state.oauth_flow.clone();
-            tauri::async_runtime::spawn(async move {
-                if let Err(e) = callback_server.start(oauth_flow).await {
-                    eprintln!("Failed to start OAuth callback server: {}", e);
-                }
+            let safe_mode = state.startup_mode.safe_mode;
+
+            // Secrets kept in the encrypted file while the keychain was
+            // unavailable move into it once it's reachable
+            std::thread::spawn(|| match secrets::migrate_to_keychain() {
+                Ok(0) => {}
+                Ok(moved) => eprintln!("Moved {} secrets into the keychain", moved),
+                Err(e) => eprintln!("Failed to move secrets into the keychain: {}", e),
+            });
+
+            if !safe_mode {
+                let callback_server = state.callback_server.clone();
+                let oauth_flow = state.oauth_flow.clone();
+                tauri::async_runtime::spawn(async move {
+                    if let Err(e) = callback_server.start(oauth_flow).await {
+                        eprintln!("Failed to start OAuth callback server: {}", e);
+                    }
+                });
+
+                // Refresh tokens shortly before they expire, so plugins and
+                // providers reading them never get an expired one
+                let oauth_events = state.event_bus.clone();
+                let oauth_flow = state.oauth_flow.clone();
+                tauri::async_runtime::spawn(async move {
+                    loop {
+                        for (provider_id, result) in oauth_flow.refresh_expiring().await {
+                            match result {
+                                Ok(()) => {
+                                    oauth_events
+                                        .publish(AppEvent::OauthTokenRefreshed(provider_id));
+                                }
+                                Err(e) => {
+                                    eprintln!("Failed to refresh {} token: {}", provider_id, e)
+                                }
+                            }
+                        }
+                        tokio::time::sleep(oauth::flow::REFRESH_CHECK_INTERVAL).await;
+                    }
+                });
+
+                state.home_assistant_provider.start_sync();
+            }
+
+            // Once the app has stayed up for a while, this startup didn't crash
+            let startup_mode = state.startup_mode.clone();
+            std::thread::spawn(move || {
+                std::thread::sleep(std::time::Duration::from_secs(startup::STABLE_AFTER_SECS));
+                startup::mark_stable(&startup_mode);
             });
 
             // Register deep link handler for launcher:// URLs
-            let deep_link_handle = app.handle().clone();
+            let deep_link_events = state.event_bus.clone();
             app.deep_link().on_open_url(move |event| {
                 let urls = event.urls();
                 for url in urls {
@@ -1929,8 +5387,8 @@ pub fn run() {
                                 let mut parts = pair.split('=');
                                 if let (Some(key), Some(value)) = (parts.next(), parts.next()) {
                                     if key == "token" {
-                                        let _ = deep_link_handle
-                                            .emit("auth-callback", value.to_string());
+                                        deep_link_events
+                                            .publish(AppEvent::AuthCallback(value.to_string()));
                                     }
                                 }
                             }
@@ -1943,8 +5401,17 @@ pub fn run() {
             let plugin_loader = state.plugin_loader.clone();
             let plugin_runtime = state.plugin_runtime.clone();
             let cmd_registry = state.command_registry.clone();
+            let intent_router = state.intent_router.clone();
+            let plugin_registry = state.plugin_registry.clone();
+            let plugin_dev_mode = state.settings.get().plugin_dev_mode;
+            let dev_handle = app.handle().clone();
 
             std::thread::spawn(move || {
+                if safe_mode {
+                    println!("Safe mode: skipping plugin loading");
+                    return;
+                }
+
                 match plugin_loader.scan_plugins() {
                     Ok(plugin_ids) => {
                         println!("Found {} plugins", plugin_ids.len());
@@ -1954,6 +5421,8 @@ pub fn run() {
                                     match plugin_runtime.load_plugin(&plugin) {
                                         Ok(_) => {
                                             println!("Loaded plugin: {}", id);
+                                            intent_router
+                                                .load_plugin_rules(&plugin.manifest.id, &plugin.path);
                                             // Register plugin commands
                                             for cmd in &plugin.manifest.provides.commands {
                                                 cmd_registry.register_plugin_command(
@@ -1974,33 +5443,492 @@ pub fn run() {
                     }
                     Err(e) => eprintln!("Failed to scan plugins: {}", e),
                 }
+                sync_dev_plugins(&plugin_loader, &plugin_registry, plugin_dev_mode);
+
+                if plugin_dev_mode {
+                    if let Err(e) = watch_plugins_for_dev(&dev_handle) {
+                        eprintln!("Failed to watch plugins for changes: {}", e);
+                    }
+                }
+            });
+
+            // Poll the power state and notify the frontend when it changes
+            let power_events = state.event_bus.clone();
+            let power_monitor = state.power_monitor.clone();
+
+            std::thread::spawn(move || loop {
+                std::thread::sleep(std::time::Duration::from_secs(60));
+                if power_monitor.refresh() {
+                    power_events.publish(AppEvent::PowerStateChanged(power_monitor.status()));
+                }
+            });
+
+            // Write recorded visits in batches rather than on every pick
+            let frecency = state.frecency.clone();
+
+            std::thread::spawn(move || loop {
+                std::thread::sleep(frecency::FLUSH_INTERVAL);
+                frecency.flush();
+            });
+
+            // Keep the window switcher's list live while it's on screen
+            let windows_handle = app.handle().clone();
+            let windows_events = state.event_bus.clone();
+            let windows_provider = state.windows_provider.clone();
+
+            std::thread::spawn(move || loop {
+                std::thread::sleep(providers::windows::REFRESH_INTERVAL);
+                let visible = windows_handle
+                    .get_webview_window("main")
+                    .is_some_and(|w| w.is_visible().unwrap_or(false));
+                if visible && windows_provider.refresh() {
+                    windows_events.publish(AppEvent::WindowsChanged);
+                }
+            });
+
+            // Rescan apps when their folders change, and now and then for
+            // installs that don't touch them
+            let apps_events = state.event_bus.clone();
+            let app_provider = state.app_provider.clone();
+
+            std::thread::spawn(move || {
+                let watcher = match indexer::FileWatcher::new(app_provider.app_dirs()) {
+                    Ok(watcher) => Some(watcher),
+                    Err(e) => {
+                        eprintln!("Failed to watch app folders: {}", e);
+                        None
+                    }
+                };
+                let mut refreshed_at = std::time::Instant::now();
+                loop {
+                    std::thread::sleep(providers::apps::WATCH_INTERVAL);
+                    let changed = watcher.as_ref().is_some_and(|w| !w.poll_events().is_empty());
+                    if changed || refreshed_at.elapsed() >= providers::apps::REFRESH_INTERVAL {
+                        let count = app_provider.refresh_apps();
+                        refreshed_at = std::time::Instant::now();
+                        apps_events.publish(AppEvent::AppsUpdated(count));
+                    }
+                }
+            });
+
+            // End focus sessions when their time is up
+            let focus_events = state.event_bus.clone();
+            state.focus_manager.set_listener(move |status| {
+                focus_events.publish(AppEvent::FocusChanged(status));
+            });
+            let focus_manager = state.focus_manager.clone();
+
+            std::thread::spawn(move || loop {
+                std::thread::sleep(std::time::Duration::from_secs(10));
+                focus_manager.end_if_due();
+            });
+
+            // Fire due reminders, including ones missed while the app was closed
+            let reminder_handle = app.handle().clone();
+            let reminder_events = state.event_bus.clone();
+            let reminders = state.reminders.clone();
+
+            std::thread::spawn(move || loop {
+                for reminder in reminders.take_due() {
+                    let shown = notifications::show(&reminder_handle, "Reminder", &reminder.text);
+                    if let Err(e) = shown {
+                        eprintln!("{}", e);
+                    }
+                    // The notification can't offer snooze; the UI does
+                    reminder_events.publish(AppEvent::ReminderDue(reminder));
+                }
+                std::thread::sleep(std::time::Duration::from_secs(15));
+            });
+
+            // Run schedules: startup ones now, the others as they come due
+            let schedule_handle = app.handle().clone();
+            let scheduler = state.scheduler.clone();
+            let settings = state.settings.clone();
+            let power_monitor = state.power_monitor.clone();
+            let bluetooth_provider = state.bluetooth_provider.clone();
+
+            std::thread::spawn(move || {
+                if safe_mode {
+                    println!("Safe mode: skipping schedules");
+                    return;
+                }
+                for schedule in scheduler.startup(&settings.get().schedules) {
+                    run_schedule(&schedule_handle, &schedule, RunReason::Startup);
+                }
+                loop {
+                    let schedules = settings.get().schedules;
+                    let on_battery = power_monitor.state().on_battery;
+                    let buses = scheduler::device_buses(&schedules);
+                    let devices = devices::connected(&buses, &bluetooth_provider);
+                    for (schedule, reason) in scheduler.tick(&schedules, on_battery, devices) {
+                        run_schedule(&schedule_handle, &schedule, reason);
+                    }
+                    std::thread::sleep(scheduler::TICK_INTERVAL);
+                }
+            });
+
+            // Poll VPN states so the UI notices connections made elsewhere
+            let vpn_events = state.event_bus.clone();
+            let vpn_provider = state.vpn_provider.clone();
+            let power_monitor = state.power_monitor.clone();
+
+            std::thread::spawn(move || loop {
+                std::thread::sleep(providers::vpn::POLL_INTERVAL);
+                if power_monitor.should_pause(PowerSubsystem::ProviderSync) {
+                    continue;
+                }
+                if let Some(status) = vpn_provider.poll() {
+                    vpn_events.publish(AppEvent::VpnStatusChanged(status));
+                }
+            });
+
+            // Plugins that keep failing are unloaded by the runtime; keep
+            // them disabled and tell the plugins UI
+            let quarantine_handle = app.handle().clone();
+            state.plugin_runtime.set_quarantine_listener(move |report| {
+                let state = quarantine_handle.state::<AppState>();
+                let _ = state.plugin_loader.disable_plugin(&report.plugin_id);
+                state.event_bus.publish(AppEvent::PluginQuarantined(report));
+            });
+
+            // Ask whether to trust folders Codex and terminals start in
+            let trust_prompt_handle = app.handle().clone();
+            state.workspace_trust.set_prompt_listener(move |prompt, reply| {
+                let what = match prompt.purpose.as_str() {
+                    "dev-server" => "A dev server",
+                    "terminal" => "A terminal",
+                    _ => "Codex",
+                };
+                let sandbox = match prompt.sandbox {
+                    Sandbox::ReadOnly => "read-only",
+                    Sandbox::WorkspaceWrite => "able to change only this folder",
+                };
+                // Codex sandboxes itself; the rest go through `codex sandbox`
+                let sandboxed = prompt.purpose == "codex" || trust::can_sandbox();
+                let otherwise = if sandboxed {
+                    format!("Otherwise it runs {}, without network access.", sandbox)
+                } else {
+                    "There's no sandbox to run it in here, so otherwise it won't start.".to_string()
+                };
+                let cancel = if sandboxed { "Run Sandboxed" } else { "Don't Run" };
+                trust_prompt_handle
+                    .dialog()
+                    .message(format!(
+                        "{} is starting in {}.\n\nTrust this folder to run it without a sandbox? \
+                         {}",
+                        what,
+                        prompt.path.display(),
+                        otherwise
+                    ))
+                    .title("Trust this folder?")
+                    .kind(MessageDialogKind::Warning)
+                    .buttons(MessageDialogButtons::OkCancelCustom(
+                        "Trust".to_string(),
+                        cancel.to_string(),
+                    ))
+                    .show(move |trusted| {
+                        let _ = reply.send(trusted);
+                    });
+            });
+
+            // Ask before plugins contact hosts they didn't declare
+            let host_prompt_handle = app.handle().clone();
+            HOST_API.set_host_prompt_listener(move |prompt| {
+                let state = host_prompt_handle.state::<AppState>();
+                let plugin_name = state
+                    .plugin_loader
+                    .plugin_name(&prompt.plugin_id)
+                    .unwrap_or_else(|| prompt.plugin_id.clone());
+                host_prompt_handle
+                    .dialog()
+                    .message(format!(
+                        "The plugin \"{}\" wants to connect to {}, which it didn't list \
+                         when it was installed.",
+                        plugin_name, prompt.host
+                    ))
+                    .title("Allow connection?")
+                    .kind(MessageDialogKind::Warning)
+                    .buttons(MessageDialogButtons::OkCancelCustom(
+                        "Allow".to_string(),
+                        "Don't Allow".to_string(),
+                    ))
+                    .show(move |allowed| {
+                        HOST_API.set_host_decision(&prompt.plugin_id, &prompt.host, allowed);
+                    });
+            });
+
+            // Plugins only get an account's token once the user allows it
+            let oauth_prompt_handle = app.handle().clone();
+            HOST_API.set_oauth_prompt_listener(move |prompt| {
+                let state = oauth_prompt_handle.state::<AppState>();
+                let plugin_name = state
+                    .plugin_loader
+                    .plugin_name(&prompt.plugin_id)
+                    .unwrap_or_else(|| prompt.plugin_id.clone());
+                oauth_prompt_handle
+                    .dialog()
+                    .message(format!(
+                        "The plugin \"{}\" wants to use your connected {} account. \
+                         It will be able to act as you on {}.",
+                        plugin_name, prompt.provider_name, prompt.provider_name
+                    ))
+                    .title("Allow account access?")
+                    .kind(MessageDialogKind::Warning)
+                    .buttons(MessageDialogButtons::OkCancelCustom(
+                        "Allow".to_string(),
+                        "Don't Allow".to_string(),
+                    ))
+                    .show(move |allowed| {
+                        HOST_API.set_oauth_consent(&prompt.plugin_id, &prompt.provider, allowed);
+                    });
+            });
+
+            // Commands from plugin results run only once the user confirms
+            // each one
+            let command_prompt_handle = app.handle().clone();
+            state.exec_policy.set_confirm_listener(move |request| {
+                let state = command_prompt_handle.state::<AppState>();
+                let plugin_name = state
+                    .plugin_loader
+                    .plugin_name(&request.plugin_id)
+                    .unwrap_or_else(|| request.plugin_id.clone());
+                let exec_policy = state.exec_policy.clone();
+                command_prompt_handle
+                    .dialog()
+                    .message(format!(
+                        "The plugin \"{}\" wants to run this command:\n\n{}",
+                        plugin_name, request.command
+                    ))
+                    .title("Run command?")
+                    .kind(MessageDialogKind::Warning)
+                    .buttons(MessageDialogButtons::OkCancelCustom(
+                        "Run".to_string(),
+                        "Don't Run".to_string(),
+                    ))
+                    .show(move |allowed| {
+                        if let Err(e) = exec_policy.answer(request, allowed) {
+                            eprintln!("Plugin command not run: {}", e);
+                        }
+                    });
+            });
+
+            // Let the UI offer copy/reveal for captures taken from search
+            let capture_events = state.event_bus.clone();
+            state.capture_provider.set_listener(move |saved| {
+                capture_events.publish(AppEvent::CaptureSaved(saved));
+            });
+
+            // Requests and actions sent from search report back as notifications
+            let http_handle = app.handle().clone();
+            state.http_provider.set_listener(move |request, result| {
+                let body = match result {
+                    Ok(response) => format!(
+                        "{} {} in {} ms",
+                        response.status, response.status_text, response.elapsed_ms
+                    ),
+                    Err(e) => e.to_string(),
+                };
+                let title = format!("{} {}", request.method, request.url);
+                let _ = notifications::show(&http_handle, &title, &body);
+            });
+            let ha_handle = app.handle().clone();
+            state.home_assistant_provider.set_error_listener(move |error| {
+                let _ = notifications::show(&ha_handle, "Home Assistant", &error);
+            });
+
+            // Plugin notifications go to the OS, titled with the plugin's name so
+            // they can't pass for Launcher's own
+            let notify_handle = app.handle().clone();
+            HOST_API.set_notification_listener(move |notification| {
+                let state = notify_handle.state::<AppState>();
+                let plugin_name = state
+                    .plugin_loader
+                    .plugin_name(&notification.plugin_id)
+                    .unwrap_or(notification.plugin_id);
+                let title = format!("{}: {}", plugin_name, notification.title);
+                notifications::show(&notify_handle, &title, &notification.body)
+            });
+
+            // App uninstalls only ask; the plan runs once the user confirms
+            let uninstall_handle = app.handle().clone();
+            state.app_provider.set_uninstall_listener(move |plan| {
+                let handle = uninstall_handle.clone();
+                let mut message = plan.message.clone();
+                if !plan.steps.is_empty() {
+                    message.push_str("\n\n");
+                    message.push_str(&plan.steps.join("\n"));
+                }
+                uninstall_handle
+                    .dialog()
+                    .message(message)
+                    .title(format!("Uninstall {}?", plan.name))
+                    .kind(MessageDialogKind::Warning)
+                    .buttons(MessageDialogButtons::OkCancelCustom(
+                        "Uninstall".to_string(),
+                        "Cancel".to_string(),
+                    ))
+                    .show(move |confirmed| {
+                        if !confirmed {
+                            return;
+                        }
+                        tauri::async_runtime::spawn_blocking(move || {
+                            let state = handle.state::<AppState>();
+                            let body = perform_uninstall(&state, &plan).unwrap_or_else(|e| e);
+                            let _ = notifications::show(&handle, &plan.name, &body);
+                        });
+                    });
+            });
+
+            // Cleanup results only ask; the task runs once the user confirms
+            let cleanup_handle = app.handle().clone();
+            state.cleanup_provider.set_listener(move |preview| {
+                let handle = cleanup_handle.clone();
+                let task = preview.task;
+                cleanup_handle
+                    .dialog()
+                    .message(preview.message)
+                    .title(preview.title)
+                    .kind(MessageDialogKind::Warning)
+                    .buttons(MessageDialogButtons::OkCancelCustom(
+                        "Continue".to_string(),
+                        "Cancel".to_string(),
+                    ))
+                    .show(move |confirmed| {
+                        if !confirmed {
+                            return;
+                        }
+                        tauri::async_runtime::spawn_blocking(move || {
+                            let state = handle.state::<AppState>();
+                            let body = perform_cleanup(&handle, task, &state.cache_coordinator)
+                                .map_or_else(|e| e, |report| report.message);
+                            let _ = notifications::show(&handle, "Cleanup", &body);
+                        });
+                    });
+            });
+
+            // Keep the marketplace catalog fresh, so it opens without waiting
+            // and still works offline
+            let marketplace_events = state.event_bus.clone();
+            let plugin_registry = state.plugin_registry.clone();
+            let power_monitor = state.power_monitor.clone();
+
+            tauri::async_runtime::spawn(async move {
+                tokio::time::sleep(std::time::Duration::from_secs(30)).await;
+                loop {
+                    if plugin_registry.is_cache_stale()
+                        && !power_monitor.should_pause(PowerSubsystem::MarketplaceRefresh)
+                    {
+                        match plugin_registry.fetch_from_server().await {
+                            Ok(()) => cache_marketplace_icons(
+                                marketplace_events.clone(),
+                                plugin_registry.clone(),
+                            ),
+                            Err(e) => eprintln!("Background marketplace refresh failed: {}", e),
+                        }
+                        marketplace_events.publish(AppEvent::MarketplaceUpdated);
+                    }
+                    tokio::time::sleep(plugins::registry::REFRESH_CHECK_INTERVAL).await;
+                }
+            });
+
+            // Periodically fetch RSS/Atom subscriptions
+            let feeds_events = state.event_bus.clone();
+            let feed_provider = state.feed_provider.clone();
+            let power_monitor = state.power_monitor.clone();
+
+            std::thread::spawn(move || {
+                std::thread::sleep(std::time::Duration::from_secs(10));
+                loop {
+                    if !power_monitor.should_pause(PowerSubsystem::ProviderSync) {
+                        let added = feed_provider.refresh_all();
+                        if added > 0 {
+                            feeds_events.publish(AppEvent::FeedsUpdated(added));
+                        }
+                    }
+                    std::thread::sleep(providers::feeds::REFRESH_INTERVAL);
+                }
+            });
+
+            // Record clipboard changes for the `clip:` history
+            let clipboard_events = state.event_bus.clone();
+            let clipboard_provider = state.clipboard_provider.clone();
+            let settings = state.settings.clone();
+
+            std::thread::spawn(move || loop {
+                std::thread::sleep(providers::clipboard_history::POLL_INTERVAL);
+                if settings.get().clipboard_history && clipboard_provider.poll() {
+                    clipboard_events.publish(AppEvent::ClipboardHistoryUpdated);
+                }
+            });
+
+            // Poll package statuses and notify when one changes
+            if !safe_mode {
+                let packages_events = state.event_bus.clone();
+                let package_provider = state.package_provider.clone();
+                let power_monitor = state.power_monitor.clone();
+
+                std::thread::spawn(move || {
+                    std::thread::sleep(std::time::Duration::from_secs(15));
+                    loop {
+                        if !power_monitor.should_pause(PowerSubsystem::ProviderSync) {
+                            match package_provider.refresh() {
+                                Ok(updates) if !updates.is_empty() => {
+                                    packages_events
+                                        .publish(AppEvent::PackageStatusChanged(updates));
+                                }
+                                Ok(_) => {}
+                                Err(e) => eprintln!("Package refresh skipped: {}", e),
+                            }
+                        }
+                        std::thread::sleep(providers::packages::POLL_INTERVAL);
+                    }
+                });
+            }
+
+            // Keep in-process caches within the configured memory budget
+            let cache_coordinator = state.cache_coordinator.clone();
+
+            std::thread::spawn(move || loop {
+                std::thread::sleep(std::time::Duration::from_secs(30));
+                let freed = cache_coordinator.enforce_budget();
+                if freed > 0 {
+                    eprintln!("Evicted {} bytes from caches", freed);
+                }
             });
 
-            let indexing_handle = app.handle().clone();
+            let indexing_events = state.event_bus.clone();
             let file_provider = state.file_provider.clone();
+            let power_monitor = state.power_monitor.clone();
 
             std::thread::spawn(move || {
+                if let Err(e) = file_provider.open() {
+                    eprintln!("Failed to open the file index: {}", e);
+                }
                 std::thread::sleep(std::time::Duration::from_secs(2));
 
-                let _ = indexing_handle.emit(
-                    "indexing-status",
-                    IndexingStatus {
-                        is_indexing: true,
+                // Hold off the initial index until we're out of low-power mode
+                while power_monitor.should_pause(PowerSubsystem::Indexing) {
+                    indexing_events.publish(AppEvent::IndexingStatus(IndexingStatus {
+                        is_indexing: false,
                         files_indexed: 0,
-                        message: "Starting background indexing...".to_string(),
-                    },
-                );
+                        message: "Indexing paused (low power)".to_string(),
+                    }));
+                    std::thread::sleep(std::time::Duration::from_secs(60));
+                }
+
+                indexing_events.publish(AppEvent::IndexingStatus(IndexingStatus {
+                    is_indexing: true,
+                    files_indexed: 0,
+                    message: "Starting background indexing...".to_string(),
+                }));
 
                 match file_provider.initialize() {
                     Ok(count) => {
-                        let _ = indexing_handle.emit(
-                            "indexing-status",
-                            IndexingStatus {
-                                is_indexing: false,
-                                files_indexed: count,
-                                message: format!("Indexed {} files", count),
-                            },
-                        );
+                        indexing_events.publish(AppEvent::IndexingStatus(IndexingStatus {
+                            is_indexing: false,
+                            files_indexed: count,
+                            message: format!("Indexed {} files", count),
+                        }));
                         println!("Background indexing complete: {} files", count);
 
                         if let Err(e) = file_provider.start_watcher() {
@@ -2010,19 +5938,20 @@ pub fn run() {
 
                             loop {
                                 std::thread::sleep(std::time::Duration::from_secs(1));
+                                // Watcher events queue up and are applied once we leave low-power mode
+                                if power_monitor.should_pause(PowerSubsystem::Indexing) {
+                                    continue;
+                                }
                                 let updated = file_provider.process_watcher_events();
                             }
                         }
                     }
                     Err(e) => {
-                        let _ = indexing_handle.emit(
-                            "indexing-status",
-                            IndexingStatus {
-                                is_indexing: false,
-                                files_indexed: 0,
-                                message: format!("Indexing failed: {}", e),
-                            },
-                        );
+                        indexing_events.publish(AppEvent::IndexingStatus(IndexingStatus {
+                            is_indexing: false,
+                            files_indexed: 0,
+                            message: format!("Indexing failed: {}", e),
+                        }));
                         eprintln!("Background indexing failed: {}", e);
                     }
                 }
@@ -2030,6 +5959,73 @@ pub fn run() {
 
             Ok(())
         })
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while running tauri application")
+        .run(|app, event| {
+            // Save visits still queued or in the log, so the next start
+            // has nothing to replay
+            if let tauri::RunEvent::Exit = event {
+                let state = app.state::<AppState>();
+                state.frecency.compact();
+                state.latency.save();
+                // Quitting isn't crashing, however soon it happens
+                startup::mark_stable(&state.startup_mode);
+            }
+        });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_extended_keys() {
+        let key = |s: &str| parse_shortcut(s).unwrap();
+        assert_eq!(key("F13"), Shortcut::new(None, Code::F13));
+        assert_eq!(
+            key("Ctrl+F24"),
+            Shortcut::new(Some(Modifiers::CONTROL), Code::F24)
+        );
+        assert_eq!(key("Num0"), Shortcut::new(None, Code::Numpad0));
+        assert_eq!(
+            key("Alt+NumpadAdd"),
+            Shortcut::new(Some(Modifiers::ALT), Code::NumpadAdd)
+        );
+        assert_eq!(key("NumEnter"), Shortcut::new(None, Code::NumpadEnter));
+        assert_eq!(
+            key("MediaPlayPause"),
+            Shortcut::new(None, Code::MediaPlayPause)
+        );
+        assert_eq!(key("NextTrack"), Shortcut::new(None, Code::MediaTrackNext));
+        assert_eq!(
+            key("VolumeMute"),
+            Shortcut::new(None, Code::AudioVolumeMute)
+        );
+        assert_eq!(
+            key("Super+IntlBackslash"),
+            Shortcut::new(Some(Modifiers::SUPER), Code::IntlBackslash)
+        );
+        // Printed characters vary by layout, so only key names are accepted
+        assert!(parse_shortcut("Ctrl+Ö").is_err());
+        assert!(parse_shortcut("Ctrl+<").is_err());
+    }
+
+    #[test]
+    fn reports_shortcuts_the_app_already_uses() {
+        let mut settings = UserSettings {
+            custom_shortcut: Some("Alt+Space".to_string()),
+            ..Default::default()
+        };
+        settings
+            .window_hotkeys
+            .insert("left-half".to_string(), "Ctrl+Alt+Left".to_string());
+        let owner =
+            |keys: &str, action| shortcut_owner(&parse_shortcut(keys).unwrap(), &settings, action);
+
+        assert_eq!(owner("alt+space", None), None);
+        assert!(owner("Alt+Space", Some("left-half")).is_some());
+        assert!(owner("Ctrl+Alt+Left", None).is_some());
+        assert_eq!(owner("Ctrl+Alt+Left", Some("left-half")), None);
+        assert_eq!(owner("Ctrl+Alt+Right", None), None);
+    }
 }
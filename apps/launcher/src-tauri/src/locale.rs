@@ -0,0 +1,319 @@
+//! How numbers and units are written in the launcher's locale, for the
+//! calculator: `3,5 km in Meilen` in German, `1 234,5` in French. Queries are
+//! rewritten to the `.` decimals the calculator parses, and answers shown
+//! with the locale's separators. What gets copied stays plain, `1234.5`.
+
+/// Unit names in other languages, to the calculator's canonical units
+const GERMAN_UNITS: &[(&str, &str)] = &[
+    ("metern", "m"),
+    ("kilometern", "km"),
+    ("zentimeter", "cm"),
+    ("zentimetern", "cm"),
+    ("millimetern", "mm"),
+    ("meile", "mi"),
+    ("meilen", "mi"),
+    ("fuß", "ft"),
+    ("fuss", "ft"),
+    ("zoll", "in"),
+    ("kilogramm", "kg"),
+    ("gramm", "g"),
+    ("milligramm", "mg"),
+    ("pfund", "lb"),
+    ("unze", "oz"),
+    ("unzen", "oz"),
+    ("gallone", "gal"),
+    ("gallonen", "gal"),
+    ("tasse", "cup"),
+    ("tassen", "cup"),
+    ("sekunde", "s"),
+    ("sekunden", "s"),
+    ("millisekunden", "ms"),
+    ("minuten", "min"),
+    ("stunde", "hr"),
+    ("stunden", "hr"),
+    ("std", "hr"),
+    ("tag", "day"),
+    ("tage", "day"),
+    ("tagen", "day"),
+    ("woche", "wk"),
+    ("wochen", "wk"),
+];
+
+const FRENCH_UNITS: &[(&str, &str)] = &[
+    ("mètre", "m"),
+    ("mètres", "m"),
+    ("kilomètre", "km"),
+    ("kilomètres", "km"),
+    ("centimètre", "cm"),
+    ("centimètres", "cm"),
+    ("millimètre", "mm"),
+    ("millimètres", "mm"),
+    ("mille", "mi"),
+    ("milles", "mi"),
+    ("pied", "ft"),
+    ("pieds", "ft"),
+    ("pouce", "in"),
+    ("pouces", "in"),
+    ("kilogramme", "kg"),
+    ("kilogrammes", "kg"),
+    ("gramme", "g"),
+    ("grammes", "g"),
+    ("livre", "lb"),
+    ("livres", "lb"),
+    ("once", "oz"),
+    ("onces", "oz"),
+    ("tasse", "cup"),
+    ("tasses", "cup"),
+    ("seconde", "s"),
+    ("secondes", "s"),
+    ("heure", "hr"),
+    ("heures", "hr"),
+    ("jour", "day"),
+    ("jours", "day"),
+    ("semaine", "wk"),
+    ("semaines", "wk"),
+    ("octet", "b"),
+    ("octets", "b"),
+    ("ko", "kb"),
+    ("mo", "mb"),
+    ("go", "gb"),
+];
+
+const SPANISH_UNITS: &[(&str, &str)] = &[
+    ("metro", "m"),
+    ("metros", "m"),
+    ("kilómetro", "km"),
+    ("kilómetros", "km"),
+    ("kilometro", "km"),
+    ("kilometros", "km"),
+    ("centímetro", "cm"),
+    ("centímetros", "cm"),
+    ("milímetro", "mm"),
+    ("milímetros", "mm"),
+    ("milla", "mi"),
+    ("millas", "mi"),
+    ("pie", "ft"),
+    ("pies", "ft"),
+    ("pulgada", "in"),
+    ("pulgadas", "in"),
+    ("kilogramo", "kg"),
+    ("kilogramos", "kg"),
+    ("gramo", "g"),
+    ("gramos", "g"),
+    ("libra", "lb"),
+    ("libras", "lb"),
+    ("onza", "oz"),
+    ("onzas", "oz"),
+    ("litro", "l"),
+    ("litros", "l"),
+    ("galón", "gal"),
+    ("galones", "gal"),
+    ("taza", "cup"),
+    ("tazas", "cup"),
+    ("segundo", "s"),
+    ("segundos", "s"),
+    ("minuto", "min"),
+    ("minutos", "min"),
+    ("hora", "hr"),
+    ("horas", "hr"),
+    ("día", "day"),
+    ("días", "day"),
+    ("semana", "wk"),
+    ("semanas", "wk"),
+];
+
+/// Separators and unit names for one locale
+#[derive(Debug, Clone, PartialEq)]
+pub struct NumberLocale {
+    /// Language subtag, e.g. `de`
+    language: String,
+    decimal: char,
+    group: char,
+}
+
+impl NumberLocale {
+    /// The locale for a tag like `de-DE` or `fr_CH.UTF-8`; languages it
+    /// doesn't know get English separators
+    pub fn new(tag: &str) -> Self {
+        let tag = tag.split(['.', '@']).next().unwrap_or_default();
+        let mut parts = tag.split(['-', '_']);
+        let language = parts.next().unwrap_or_default().to_lowercase();
+        let region = parts.next().unwrap_or_default().to_uppercase();
+
+        let (decimal, group) = match (language.as_str(), region.as_str()) {
+            ("de" | "it" | "fr" | "rm", "CH" | "LI") => ('.', '\''),
+            ("es", "MX" | "US") => ('.', ','),
+            (
+                "de" | "es" | "it" | "nl" | "pt" | "da" | "tr" | "id" | "el" | "ro" | "hr" | "sl"
+                | "sr",
+                _,
+            ) => (',', '.'),
+            (
+                "fr" | "ru" | "pl" | "sv" | "nb" | "nn" | "no" | "fi" | "cs" | "sk" | "uk" | "hu"
+                | "bg" | "lt" | "lv" | "et",
+                _,
+            ) => (',', '\u{a0}'),
+            _ => ('.', ','),
+        };
+
+        Self {
+            language,
+            decimal,
+            group,
+        }
+    }
+
+    /// `tag` if set, else the system's locale
+    pub fn resolve(tag: Option<&str>) -> Self {
+        match tag.map(str::trim).filter(|tag| !tag.is_empty()) {
+            Some(tag) => Self::new(tag),
+            None => Self::new(&sys_locale::get_locale().unwrap_or_else(|| "en-US".to_string())),
+        }
+    }
+
+    /// The canonical unit for a unit name in the locale's language
+    pub fn unit_alias(&self, name: &str) -> Option<&'static str> {
+        let units = match self.language.as_str() {
+            "de" => GERMAN_UNITS,
+            "fr" => FRENCH_UNITS,
+            "es" => SPANISH_UNITS,
+            _ => return None,
+        };
+        units
+            .iter()
+            .find(|(alias, _)| *alias == name)
+            .map(|(_, unit)| *unit)
+    }
+
+    /// Words between a value and the unit to convert it to, besides the
+    /// English ` to ` and ` in `
+    pub fn conversion_words(&self) -> &'static [&'static str] {
+        match self.language.as_str() {
+            "de" => &[" nach ", " zu "],
+            "fr" => &[" en "],
+            "es" => &[" en ", " a "],
+            _ => &[],
+        }
+    }
+
+    /// The query with its numbers written with `.` decimals and no grouping,
+    /// e.g. `1.234,5 + 1` to `1234.5 + 1` in German. Numbers that don't
+    /// read as the locale writes them, like `1.5` there, are left alone.
+    pub fn normalize(&self, query: &str) -> String {
+        let chars: Vec<char> = query.chars().collect();
+        let mut out = String::with_capacity(query.len());
+        let mut i = 0;
+        while i < chars.len() {
+            if !chars[i].is_ascii_digit() {
+                out.push(chars[i]);
+                i += 1;
+                continue;
+            }
+            // Digits, and separators between digits
+            let start = i;
+            while i < chars.len()
+                && (chars[i].is_ascii_digit()
+                    || (self.is_separator(chars[i])
+                        && chars.get(i + 1).is_some_and(char::is_ascii_digit)))
+            {
+                i += 1;
+            }
+            let number: String = chars[start..i].iter().collect();
+            out.push_str(&self.normalize_number(&number));
+        }
+        out
+    }
+
+    fn is_separator(&self, c: char) -> bool {
+        c == '.' || c == ',' || c == self.group
+    }
+
+    fn normalize_number(&self, number: &str) -> String {
+        let (integer, fraction) = match number.matches(self.decimal).count() {
+            0 => (number, None),
+            1 => match number.split_once(self.decimal) {
+                Some((integer, fraction)) => (integer, Some(fraction)),
+                None => (number, None),
+            },
+            // More like a list, e.g. `max(1,2,3)`
+            _ => return number.to_string(),
+        };
+        let Some(integer) = ungroup(integer, self.group) else {
+            return number.to_string();
+        };
+        match fraction {
+            Some(fraction) => format!("{}.{}", integer, fraction),
+            None => integer,
+        }
+    }
+
+    /// A plain number like `-1234.5` with the locale's separators
+    pub fn format(&self, plain: &str) -> String {
+        let (sign, digits) = match plain.strip_prefix('-') {
+            Some(digits) => ("-", digits),
+            None => ("", plain),
+        };
+        let (integer, fraction) = match digits.split_once('.') {
+            Some((integer, fraction)) => (integer, Some(fraction)),
+            None => (digits, None),
+        };
+        // `inf`, `NaN`
+        if !integer.chars().all(|c| c.is_ascii_digit()) {
+            return plain.to_string();
+        }
+
+        let mut out = sign.to_string();
+        for (i, digit) in integer.chars().enumerate() {
+            if i > 0 && (integer.len() - i) % 3 == 0 {
+                out.push(self.group);
+            }
+            out.push(digit);
+        }
+        if let Some(fraction) = fraction {
+            out.push(self.decimal);
+            out.push_str(fraction);
+        }
+        out
+    }
+}
+
+/// The integer part of a number without its group separators, if it's
+/// grouped in threes like `1.234.567`
+fn ungroup(integer: &str, group: char) -> Option<String> {
+    let all_digits = |part: &str| part.chars().all(|c| c.is_ascii_digit());
+    if !integer.contains(group) {
+        return all_digits(integer).then(|| integer.to_string());
+    }
+    let mut parts = integer.split(group);
+    let first = parts.next()?;
+    let grouped = (1..=3).contains(&first.len())
+        && all_digits(first)
+        && parts.all(|part| part.len() == 3 && all_digits(part));
+    grouped.then(|| integer.replace(group, ""))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_and_writes_locale_numbers() {
+        let german = NumberLocale::new("de_DE.UTF-8");
+        assert_eq!(german.normalize("1.234,5 + 2,25"), "1234.5 + 2.25");
+        assert_eq!(german.normalize("3,5 km nach Meilen"), "3.5 km nach Meilen");
+        // A point that can't be grouping is a decimal point
+        assert_eq!(german.normalize("1.5 * 2"), "1.5 * 2");
+        assert_eq!(german.format("-1234567.5"), "-1.234.567,5");
+        assert_eq!(german.unit_alias("meilen"), Some("mi"));
+
+        let english = NumberLocale::new("en-US");
+        assert_eq!(english.normalize("1,000.5 / 2"), "1000.5 / 2");
+        assert_eq!(english.normalize("max(1,2)"), "max(1,2)");
+        assert_eq!(english.format("1234.5"), "1,234.5");
+        assert_eq!(english.format("999"), "999");
+
+        let swiss = NumberLocale::new("de-CH");
+        assert_eq!(swiss.format("1234.5"), "1'234.5");
+        assert_eq!(NumberLocale::new("fr-FR").format("1234.5"), "1\u{a0}234,5");
+    }
+}
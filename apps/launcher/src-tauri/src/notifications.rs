@@ -0,0 +1,16 @@
+//! System notifications, shown through the OS notification center.
+//!
+//! Desktop notifications can't carry buttons, so anything the user should
+//! act on (like snoozing a reminder) is also sent to the UI as an event.
+
+use tauri::AppHandle;
+use tauri_plugin_notification::NotificationExt;
+
+pub fn show(app: &AppHandle, title: &str, body: &str) -> Result<(), String> {
+    app.notification()
+        .builder()
+        .title(title)
+        .body(body)
+        .show()
+        .map_err(|e| format!("Failed to show notification: {}", e))
+}
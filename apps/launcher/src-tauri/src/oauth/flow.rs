@@ -5,15 +5,26 @@ use parking_lot::RwLock;
 use rand::Rng;
 use serde::Deserialize;
 use sha2::{Digest, Sha256};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::Duration;
 use url::Url;
 
+/// Tokens are refreshed this many seconds before they expire, so whoever
+/// reads them without refreshing (plugins, search providers) gets a live one
+pub const REFRESH_AHEAD_SECS: i64 = 5 * 60;
+/// How often the background refresh looks for expiring tokens
+pub const REFRESH_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+/// A provider whose refresh failed isn't tried again for this many seconds
+const REFRESH_RETRY_SECS: i64 = 15 * 60;
+
 #[derive(Debug, Clone)]
 pub struct PendingAuth {
     pub provider: String,
     pub state: String,
-    pub code_verifier: String,
+    /// Only for providers that use PKCE
+    pub code_verifier: Option<String>,
     pub redirect_uri: String,
 }
 
@@ -21,6 +32,20 @@ pub struct OAuthFlow {
     storage: Arc<TokenStorage>,
     pending: RwLock<HashMap<String, PendingAuth>>,
     providers: RwLock<HashMap<String, OAuthProviderConfig>>,
+    /// Unix seconds of each provider's last failed background refresh
+    refresh_failures: RwLock<HashMap<String, i64>>,
+    /// Providers the user registered, saved to `custom_path` without their
+    /// client secrets, which live in the keyring
+    custom_ids: RwLock<HashSet<String>>,
+    custom_path: PathBuf,
+}
+
+/// Whether a token should be refreshed ahead of expiring at `now`
+fn needs_refresh(token: &OAuthToken, now: i64) -> bool {
+    token.refresh_token.is_some()
+        && token
+            .expires_at
+            .is_some_and(|expires_at| expires_at - now <= REFRESH_AHEAD_SECS)
 }
 
 impl OAuthFlow {
@@ -29,6 +54,12 @@ impl OAuthFlow {
             storage,
             pending: RwLock::new(HashMap::new()),
             providers: RwLock::new(HashMap::new()),
+            refresh_failures: RwLock::new(HashMap::new()),
+            custom_ids: RwLock::new(HashSet::new()),
+            custom_path: dirs::data_dir()
+                .unwrap_or_else(|| PathBuf::from("."))
+                .join("launcher")
+                .join("oauth_providers.json"),
         }
     }
 
@@ -58,11 +89,88 @@ impl OAuthFlow {
             .get_mut(provider_id)
             .ok_or_else(|| format!("Unknown provider: {}", provider_id))?;
 
+        let custom = self.is_custom(provider_id);
+        if custom {
+            self.storage
+                .store_client_secret(provider_id, client_secret.as_deref())?;
+        }
         provider.client_id = client_id;
         provider.client_secret = client_secret;
+        drop(providers);
+
+        if custom {
+            self.save_custom_providers();
+        }
         Ok(())
     }
 
+    /// Register the providers the user added in earlier sessions
+    pub fn load_custom_providers(&self) {
+        let saved: Vec<OAuthProviderConfig> =
+            crate::journal::load_json_store(&self.custom_path).unwrap_or_default();
+        for mut config in saved {
+            config.client_secret = self.storage.get_client_secret(&config.id);
+            self.custom_ids.write().insert(config.id.clone());
+            self.register_provider(config);
+        }
+    }
+
+    pub fn is_custom(&self, provider_id: &str) -> bool {
+        self.custom_ids.read().contains(provider_id)
+    }
+
+    /// Add or update a provider the user registered themselves. Built-in
+    /// providers can't be replaced this way.
+    pub fn add_custom_provider(&self, config: OAuthProviderConfig) -> Result<(), String> {
+        config.validate()?;
+        if self.get_provider(&config.id).is_some() && !self.is_custom(&config.id) {
+            return Err(format!("'{}' is a built-in provider", config.id));
+        }
+
+        self.storage
+            .store_client_secret(&config.id, config.client_secret.as_deref())?;
+        self.custom_ids.write().insert(config.id.clone());
+        self.register_provider(config);
+        self.save_custom_providers();
+        Ok(())
+    }
+
+    /// Remove a provider the user registered, disconnecting it
+    pub fn remove_custom_provider(&self, provider_id: &str) -> Result<(), String> {
+        if !self.custom_ids.write().remove(provider_id) {
+            return Err(format!("'{}' isn't a custom provider", provider_id));
+        }
+
+        self.providers.write().remove(provider_id);
+        self.storage.delete_token(provider_id)?;
+        self.storage.store_client_secret(provider_id, None)?;
+        self.save_custom_providers();
+        Ok(())
+    }
+
+    fn save_custom_providers(&self) {
+        let custom: Vec<OAuthProviderConfig> = {
+            let ids = self.custom_ids.read();
+            let providers = self.providers.read();
+            let mut custom: Vec<_> = ids
+                .iter()
+                .filter_map(|id| providers.get(id).cloned())
+                .map(|config| OAuthProviderConfig {
+                    client_secret: None,
+                    ..config
+                })
+                .collect();
+            custom.sort_by(|a, b| a.id.cmp(&b.id));
+            custom
+        };
+
+        if let Ok(json) = serde_json::to_string_pretty(&custom) {
+            if let Err(e) = crate::journal::write_json_store(&self.custom_path, &json) {
+                eprintln!("Failed to save OAuth providers: {}", e);
+            }
+        }
+    }
+
     fn generate_state() -> String {
         let bytes: [u8; 32] = rand::thread_rng().gen();
         URL_SAFE_NO_PAD.encode(bytes)
@@ -96,8 +204,7 @@ impl OAuthFlow {
             .ok_or("Provider not configured: missing client_id")?;
 
         let state = Self::generate_state();
-        let code_verifier = Self::generate_code_verifier();
-        let code_challenge = Self::generate_code_challenge(&code_verifier);
+        let code_verifier = provider.pkce.then(Self::generate_code_verifier);
 
         let scopes = scopes.unwrap_or(provider.scopes.clone());
         let scope_str = scopes.join(" ");
@@ -111,9 +218,14 @@ impl OAuthFlow {
             .append_pair("redirect_uri", redirect_uri)
             .append_pair("response_type", "code")
             .append_pair("state", &state)
-            .append_pair("scope", &scope_str)
-            .append_pair("code_challenge", &code_challenge)
-            .append_pair("code_challenge_method", "S256");
+            .append_pair("scope", &scope_str);
+
+        if let Some(ref verifier) = code_verifier {
+            auth_url
+                .query_pairs_mut()
+                .append_pair("code_challenge", &Self::generate_code_challenge(verifier))
+                .append_pair("code_challenge_method", "S256");
+        }
 
         let pending = PendingAuth {
             provider: provider_id.to_string(),
@@ -154,7 +266,10 @@ impl OAuthFlow {
         params.insert("code", code);
         params.insert("redirect_uri", &pending.redirect_uri);
         params.insert("grant_type", "authorization_code");
-        params.insert("code_verifier", &pending.code_verifier);
+
+        if let Some(ref verifier) = pending.code_verifier {
+            params.insert("code_verifier", verifier.as_str());
+        }
 
         if let Some(ref secret) = provider.client_secret {
             params.insert("client_secret", secret.as_str());
@@ -196,6 +311,7 @@ impl OAuthFlow {
         };
 
         self.storage.store_token(&pending.provider, &token)?;
+        self.refresh_failures.write().remove(&pending.provider);
 
         Ok(token)
     }
@@ -262,10 +378,46 @@ impl OAuthFlow {
         };
 
         self.storage.store_token(provider_id, &token)?;
+        self.refresh_failures.write().remove(provider_id);
 
         Ok(token)
     }
 
+    /// Refresh every connected provider's token that expires within
+    /// `REFRESH_AHEAD_SECS`. Returns the providers tried and how each went.
+    pub async fn refresh_expiring(&self) -> Vec<(String, Result<(), String>)> {
+        let now = chrono::Utc::now().timestamp();
+        let due: Vec<String> = {
+            let failures = self.refresh_failures.read();
+            self.list_providers()
+                .into_iter()
+                .map(|p| p.id)
+                .filter(|id| {
+                    !failures
+                        .get(id)
+                        .is_some_and(|failed_at| now - failed_at < REFRESH_RETRY_SECS)
+                })
+                .filter(|id| {
+                    self.storage
+                        .get_token(id)
+                        .is_some_and(|token| needs_refresh(&token, now))
+                })
+                .collect()
+        };
+
+        let mut results = Vec::new();
+        for provider_id in due {
+            let result = self.refresh_token(&provider_id).await.map(|_| ());
+            if result.is_err() {
+                self.refresh_failures
+                    .write()
+                    .insert(provider_id.clone(), now);
+            }
+            results.push((provider_id, result));
+        }
+        results
+    }
+
     pub async fn get_valid_token(&self, provider_id: &str) -> Result<String, String> {
         let token = self
             .storage
@@ -306,3 +458,64 @@ struct TokenResponse {
     expires_in: Option<u64>,
     scope: Option<String>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn provider(id: &str, pkce: bool) -> OAuthProviderConfig {
+        OAuthProviderConfig {
+            id: id.to_string(),
+            name: id.to_string(),
+            auth_url: "https://example.com/authorize".to_string(),
+            token_url: "https://example.com/token".to_string(),
+            scopes: vec![],
+            client_id: Some("client".to_string()),
+            client_secret: None,
+            pkce,
+        }
+    }
+
+    #[test]
+    fn sends_pkce_challenge_only_when_supported() {
+        let flow = OAuthFlow::new(Arc::new(TokenStorage::new()));
+        flow.register_provider(provider("pkce", true));
+        flow.register_provider(provider("plain", false));
+        let redirect_uri = "http://localhost/callback";
+
+        let url = Url::parse(&flow.start_auth("pkce", None, redirect_uri).unwrap()).unwrap();
+        let query: HashMap<_, _> = url.query_pairs().into_owned().collect();
+        let pending = flow.pending.read()[&query["state"]].clone();
+        let verifier = pending.code_verifier.unwrap();
+        assert_eq!(
+            query["code_challenge"],
+            OAuthFlow::generate_code_challenge(&verifier)
+        );
+        assert_eq!(query["code_challenge_method"], "S256");
+
+        let url = flow.start_auth("plain", None, redirect_uri).unwrap();
+        assert!(!url.contains("code_challenge"));
+        assert!(flow
+            .pending
+            .read()
+            .values()
+            .any(|p| p.code_verifier.is_none()));
+    }
+
+    #[test]
+    fn refreshes_ahead_of_expiry() {
+        let token = |expires_at, refresh_token: Option<&str>| OAuthToken {
+            access_token: "access".to_string(),
+            refresh_token: refresh_token.map(String::from),
+            token_type: "Bearer".to_string(),
+            expires_at,
+            scopes: vec![],
+        };
+        let now = 1_700_000_000;
+        assert!(needs_refresh(&token(Some(now + 60), Some("r")), now));
+        assert!(needs_refresh(&token(Some(now - 60), Some("r")), now));
+        assert!(!needs_refresh(&token(Some(now + 3600), Some("r")), now));
+        assert!(!needs_refresh(&token(Some(now + 60), None), now));
+        assert!(!needs_refresh(&token(None, Some("r")), now));
+    }
+}
@@ -9,6 +9,39 @@ pub struct OAuthProviderConfig {
     pub scopes: Vec<String>,
     pub client_id: Option<String>,
     pub client_secret: Option<String>,
+    /// Send a PKCE code challenge with the authorization request
+    #[serde(default)]
+    pub pkce: bool,
+}
+
+impl OAuthProviderConfig {
+    /// Check a provider the user registered themselves
+    pub fn validate(&self) -> Result<(), String> {
+        if self.id.is_empty()
+            || !self
+                .id
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_')
+        {
+            return Err(format!(
+                "Invalid provider id '{}': use lower-case letters, digits, '-' and '_'",
+                self.id
+            ));
+        }
+        if self.name.trim().is_empty() {
+            return Err("Provider name is required".to_string());
+        }
+        for (label, url) in [("auth", &self.auth_url), ("token", &self.token_url)] {
+            let parsed = url::Url::parse(url)
+                .map_err(|e| format!("Invalid {} URL '{}': {}", label, url, e))?;
+            // Codes and tokens only travel in the clear to this machine
+            let local = matches!(parsed.host_str(), Some("localhost" | "127.0.0.1" | "[::1]"));
+            if parsed.scheme() != "https" && !(parsed.scheme() == "http" && local) {
+                return Err(format!("The {} URL must use https: {}", label, url));
+            }
+        }
+        Ok(())
+    }
 }
 
 pub trait OAuthProvider: Send + Sync {
@@ -33,6 +66,7 @@ impl GitHubProvider {
                 scopes: vec!["repo".to_string(), "user".to_string()],
                 client_id,
                 client_secret,
+                pkce: false,
             },
         }
     }
@@ -74,6 +108,7 @@ impl GoogleProvider {
                 ],
                 client_id,
                 client_secret,
+                pkce: true,
             },
         }
     }
@@ -115,6 +150,7 @@ impl NotionProvider {
                 scopes: vec![], // Notion doesn't use scopes in the same way
                 client_id,
                 client_secret,
+                pkce: false,
             },
         }
     }
@@ -154,9 +190,11 @@ impl SlackProvider {
                     "channels:read".to_string(),
                     "search:read".to_string(),
                     "users:read".to_string(),
+                    "users.profile:write".to_string(),
                 ],
                 client_id,
                 client_secret,
+                pkce: true,
             },
         }
     }
@@ -180,6 +218,7 @@ impl OAuthProvider for SlackProvider {
             "channels:read".to_string(),
             "search:read".to_string(),
             "users:read".to_string(),
+            "users.profile:write".to_string(),
         ]
     }
 }
@@ -188,3 +227,41 @@ pub static GITHUB_PROVIDER: &str = "github";
 pub static GOOGLE_PROVIDER: &str = "google";
 pub static NOTION_PROVIDER: &str = "notion";
 pub static SLACK_PROVIDER: &str = "slack";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validates_custom_providers() {
+        let provider = OAuthProviderConfig {
+            id: "gitlab".to_string(),
+            name: "GitLab".to_string(),
+            auth_url: "https://gitlab.com/oauth/authorize".to_string(),
+            token_url: "https://gitlab.com/oauth/token".to_string(),
+            scopes: vec!["read_user".to_string()],
+            client_id: Some("client".to_string()),
+            client_secret: None,
+            pkce: true,
+        };
+        assert!(provider.validate().is_ok());
+
+        let local = OAuthProviderConfig {
+            token_url: "http://localhost:8080/token".to_string(),
+            ..provider.clone()
+        };
+        assert!(local.validate().is_ok());
+
+        let plain = OAuthProviderConfig {
+            token_url: "http://gitlab.com/oauth/token".to_string(),
+            ..provider.clone()
+        };
+        assert!(plain.validate().is_err());
+
+        let bad_id = OAuthProviderConfig {
+            id: "Git Lab".to_string(),
+            ..provider
+        };
+        assert!(bad_id.validate().is_err());
+    }
+}
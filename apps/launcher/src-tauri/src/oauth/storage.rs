@@ -1,4 +1,3 @@
-use keyring::Entry;
 use parking_lot::RwLock;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -45,12 +44,8 @@ impl TokenStorage {
         let json = serde_json::to_string(token)
             .map_err(|e| format!("Failed to serialize token: {}", e))?;
 
-        let entry = Entry::new(SERVICE_NAME, &key)
-            .map_err(|e| format!("Failed to create keyring entry: {}", e))?;
-
-        entry
-            .set_password(&json)
-            .map_err(|e| format!("Failed to store token in keyring: {}", e))?;
+        crate::secrets::set(SERVICE_NAME, &key, &json)
+            .map_err(|e| format!("Failed to store token: {}", e))?;
 
         let mut cache = self.cache.write();
         cache.insert(provider.to_string(), token.clone());
@@ -69,8 +64,7 @@ impl TokenStorage {
         }
 
         let key = Self::keyring_key(provider);
-        let entry = Entry::new(SERVICE_NAME, &key).ok()?;
-        let json = entry.get_password().ok()?;
+        let json = crate::secrets::get(SERVICE_NAME, &key)?;
         let token: OAuthToken = serde_json::from_str(&json).ok()?;
 
         if !token.is_expired() {
@@ -87,20 +81,34 @@ impl TokenStorage {
     pub fn delete_token(&self, provider: &str) -> Result<(), String> {
         let key = Self::keyring_key(provider);
 
-        if let Ok(entry) = Entry::new(SERVICE_NAME, &key) {
-            let _ = entry.delete_credential();
-        }
-
         let mut cache = self.cache.write();
         cache.remove(provider);
 
-        Ok(())
+        crate::secrets::delete(SERVICE_NAME, &key)
     }
 
     pub fn has_token(&self, provider: &str) -> bool {
         self.get_token(provider).is_some()
     }
 
+    fn client_secret_key(provider: &str) -> String {
+        format!("{}:{}:client-secret", SERVICE_NAME, provider)
+    }
+
+    /// Keep a custom provider's client secret with the tokens, or remove it
+    pub fn store_client_secret(&self, provider: &str, secret: Option<&str>) -> Result<(), String> {
+        let key = Self::client_secret_key(provider);
+        match secret {
+            Some(secret) => crate::secrets::set(SERVICE_NAME, &key, secret)
+                .map_err(|e| format!("Failed to store client secret: {}", e)),
+            None => crate::secrets::delete(SERVICE_NAME, &key),
+        }
+    }
+
+    pub fn get_client_secret(&self, provider: &str) -> Option<String> {
+        crate::secrets::get(SERVICE_NAME, &Self::client_secret_key(provider))
+    }
+
     pub fn list_connected_providers(&self) -> Vec<String> {
         let cache = self.cache.read();
         cache.keys().cloned().collect()
@@ -0,0 +1,196 @@
+//! Text recognition for screenshots and other images.
+//!
+//! The OCR engine (ocrs) is only compiled in with the `ocr` feature, and its
+//! models (~12 MB) are downloaded on demand rather than bundled. Recognized
+//! text can be saved next to the file index so image files become searchable
+//! by their contents.
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+
+const DETECTION_MODEL: &str = "text-detection.rten";
+const RECOGNITION_MODEL: &str = "text-recognition.rten";
+#[cfg(feature = "ocr")]
+const MODEL_BASE_URL: &str = "https://ocrs-models.s3-accelerate.amazonaws.com";
+/// Hex SHA-256 pinned for each model. The bucket isn't versioned, so a
+/// download that doesn't match is refused rather than installed, and so is
+/// any model without a pin; update these together with the ocrs version.
+#[cfg(feature = "ocr")]
+const MODEL_SHA256: [(&str, &str); 2] = [(DETECTION_MODEL, ""), (RECOGNITION_MODEL, "")];
+
+#[cfg(not(feature = "ocr"))]
+const NOT_SUPPORTED: &str = "OCR support is not included in this build";
+
+/// Image extensions the OCR action is offered for
+pub const IMAGE_EXTENSIONS: &[&str] = &["png", "jpg", "jpeg"];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OcrStatus {
+    /// Whether this build includes the OCR engine
+    pub supported: bool,
+    pub models_installed: bool,
+    pub models_dir: PathBuf,
+}
+
+fn ocr_dir() -> PathBuf {
+    dirs::data_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("launcher")
+        .join("ocr")
+}
+
+fn models_dir() -> PathBuf {
+    ocr_dir().join("models")
+}
+
+fn models_installed() -> bool {
+    let dir = models_dir();
+    dir.join(DETECTION_MODEL).exists() && dir.join(RECOGNITION_MODEL).exists()
+}
+
+pub fn status() -> OcrStatus {
+    OcrStatus {
+        supported: cfg!(feature = "ocr"),
+        models_installed: models_installed(),
+        models_dir: models_dir(),
+    }
+}
+
+pub fn is_image(path: &Path) -> bool {
+    path.extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .map_or(false, |e| IMAGE_EXTENSIONS.contains(&e.as_str()))
+}
+
+/// Download the detection and recognition models
+#[cfg(feature = "ocr")]
+pub fn install_models() -> Result<OcrStatus, String> {
+    let dir = models_dir();
+    std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+
+    let client = reqwest::blocking::Client::builder()
+        .timeout(std::time::Duration::from_secs(300))
+        .build()
+        .map_err(|e| e.to_string())?;
+
+    for (model, expected) in MODEL_SHA256 {
+        let target = dir.join(model);
+        if target.exists() {
+            continue;
+        }
+        let bytes = client
+            .get(format!("{}/{}", MODEL_BASE_URL, model))
+            .send()
+            .and_then(|r| r.error_for_status())
+            .and_then(|r| r.bytes())
+            .map_err(|e| format!("Failed to download {}: {}", model, e))?;
+
+        let actual = format!("{:x}", Sha256::digest(&bytes));
+        if expected.is_empty() || !actual.eq_ignore_ascii_case(expected) {
+            return Err(format!(
+                "Downloaded {} doesn't match the pinned checksum (got {})",
+                model, actual
+            ));
+        }
+
+        // Write to a temp file first so a failed download never looks installed
+        let partial = dir.join(format!("{}.part", model));
+        std::fs::write(&partial, &bytes).map_err(|e| e.to_string())?;
+        std::fs::rename(&partial, &target).map_err(|e| e.to_string())?;
+    }
+
+    Ok(status())
+}
+
+#[cfg(not(feature = "ocr"))]
+pub fn install_models() -> Result<OcrStatus, String> {
+    Err(NOT_SUPPORTED.to_string())
+}
+
+#[cfg(feature = "ocr")]
+mod engine {
+    use super::{models_dir, DETECTION_MODEL, RECOGNITION_MODEL};
+    use ocrs::{ImageSource, OcrEngine, OcrEngineParams};
+    use parking_lot::Mutex;
+    use rten::Model;
+    use std::sync::Arc;
+
+    lazy_static::lazy_static! {
+        // Loading the models takes a while, so keep the engine around
+        static ref ENGINE: Mutex<Option<Arc<OcrEngine>>> = Mutex::new(None);
+    }
+
+    fn engine() -> Result<Arc<OcrEngine>, String> {
+        let mut cached = ENGINE.lock();
+        if let Some(engine) = cached.as_ref() {
+            return Ok(engine.clone());
+        }
+        if !super::models_installed() {
+            return Err("OCR models are not installed".to_string());
+        }
+
+        let dir = models_dir();
+        let load = |name: &str| {
+            Model::load_file(dir.join(name)).map_err(|e| format!("Failed to load {}: {}", name, e))
+        };
+        let engine = OcrEngine::new(OcrEngineParams {
+            detection_model: Some(load(DETECTION_MODEL)?),
+            recognition_model: Some(load(RECOGNITION_MODEL)?),
+            ..Default::default()
+        })
+        .map_err(|e| e.to_string())?;
+
+        let engine = Arc::new(engine);
+        *cached = Some(engine.clone());
+        Ok(engine)
+    }
+
+    pub fn recognize(bytes: &[u8]) -> Result<String, String> {
+        let engine = engine()?;
+        let image = image::load_from_memory(bytes)
+            .map_err(|e| format!("Failed to decode image: {}", e))?
+            .into_rgb8();
+        let source = ImageSource::from_bytes(image.as_raw(), image.dimensions())
+            .map_err(|e| e.to_string())?;
+        let input = engine.prepare_input(source).map_err(|e| e.to_string())?;
+        engine.get_text(&input).map_err(|e| e.to_string())
+    }
+}
+
+/// Recognize the text in an encoded (PNG or JPEG) image
+#[cfg(feature = "ocr")]
+pub fn recognize_image(bytes: &[u8]) -> Result<String, String> {
+    engine::recognize(bytes).map(|text| text.trim().to_string())
+}
+
+#[cfg(not(feature = "ocr"))]
+pub fn recognize_image(_bytes: &[u8]) -> Result<String, String> {
+    Err(NOT_SUPPORTED.to_string())
+}
+
+pub fn recognize_file(path: &Path) -> Result<String, String> {
+    let bytes =
+        std::fs::read(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    recognize_image(&bytes)
+}
+
+fn text_path(path: &Path) -> PathBuf {
+    let digest = Sha256::digest(path.to_string_lossy().as_bytes());
+    let name: String = digest.iter().map(|b| format!("{:02x}", b)).collect();
+    ocr_dir().join("text").join(format!("{}.txt", name))
+}
+
+/// Remember the text recognized in `path` so the file indexer can search it
+pub fn save_text(path: &Path, text: &str) -> Result<(), String> {
+    let target = text_path(path);
+    if let Some(parent) = target.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    std::fs::write(target, text).map_err(|e| e.to_string())
+}
+
+/// Text previously recognized in `path`, if any
+pub fn saved_text(path: &Path) -> Option<String> {
+    std::fs::read_to_string(text_path(path)).ok()
+}
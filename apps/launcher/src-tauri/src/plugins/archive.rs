@@ -0,0 +1,190 @@
+//! Guards for installing downloaded plugin packages: a cap on the download
+//! size, a SHA-256 check against the checksum the marketplace published,
+//! and extraction that refuses entries escaping the plugin folder
+//! (zip-slip) or unpacking to more than a plugin could reasonably need.
+
+use super::loader::DEV_MARKER;
+use sha2::{Digest, Sha256};
+use std::io::{Read, Seek};
+use std::path::Path;
+
+/// Largest package download accepted
+pub const MAX_DOWNLOAD_BYTES: usize = 50 * 1024 * 1024;
+
+/// Largest total size a package may unpack to
+pub const MAX_UNPACKED_BYTES: u64 = 200 * 1024 * 1024;
+
+const MAX_ENTRIES: usize = 2_000;
+
+/// Read a download's body, giving up once it passes `MAX_DOWNLOAD_BYTES`
+pub async fn download(mut response: reqwest::Response) -> Result<Vec<u8>, String> {
+    let too_large = || {
+        format!(
+            "Plugin download is larger than {} MB",
+            MAX_DOWNLOAD_BYTES / 1024 / 1024
+        )
+    };
+    if response
+        .content_length()
+        .is_some_and(|len| len > MAX_DOWNLOAD_BYTES as u64)
+    {
+        return Err(too_large());
+    }
+
+    let mut bytes = Vec::new();
+    while let Some(chunk) = response
+        .chunk()
+        .await
+        .map_err(|e| format!("Failed to download plugin file: {}", e))?
+    {
+        if bytes.len() + chunk.len() > MAX_DOWNLOAD_BYTES {
+            return Err(too_large());
+        }
+        bytes.extend_from_slice(&chunk);
+    }
+    Ok(bytes)
+}
+
+/// Compare a download with the hex SHA-256 the marketplace published.
+/// Passes when there is no checksum to compare with.
+pub fn verify_checksum(bytes: &[u8], expected: Option<&str>) -> Result<(), String> {
+    let Some(expected) = expected.map(str::trim).filter(|c| !c.is_empty()) else {
+        return Ok(());
+    };
+    let actual = format!("{:x}", Sha256::digest(bytes));
+    if !actual.eq_ignore_ascii_case(expected) {
+        return Err(format!(
+            "Plugin download is corrupted or was tampered with: expected checksum {}, got {}",
+            expected, actual
+        ));
+    }
+    Ok(())
+}
+
+/// Unix file type bits of a symlink
+const S_IFLNK: u32 = 0o120000;
+
+/// Extract a package into `dest`. Every entry is checked before anything is
+/// written: paths must stay inside `dest`, symlinks are refused, and the
+/// total size is capped. Sizes are checked again while writing, since the
+/// sizes in a zip's directory can lie.
+pub fn extract<R: Read + Seek>(
+    archive: &mut zip::ZipArchive<R>,
+    dest: &Path,
+) -> Result<(), String> {
+    if archive.len() > MAX_ENTRIES {
+        return Err(format!(
+            "Plugin package has more than {} files",
+            MAX_ENTRIES
+        ));
+    }
+
+    let mut total: u64 = 0;
+    for i in 0..archive.len() {
+        let entry = archive
+            .by_index(i)
+            .map_err(|e| format!("Failed to read plugin package: {}", e))?;
+        if entry.enclosed_name().is_none() {
+            return Err(format!(
+                "Plugin package has an unsafe path: {}",
+                entry.name()
+            ));
+        }
+        if entry
+            .unix_mode()
+            .is_some_and(|mode| mode & 0o170000 == S_IFLNK)
+        {
+            return Err(format!("Plugin package has a symlink: {}", entry.name()));
+        }
+        total = total.saturating_add(entry.size());
+    }
+    let too_large = || {
+        format!(
+            "Plugin package unpacks to more than {} MB",
+            MAX_UNPACKED_BYTES / 1024 / 1024
+        )
+    };
+    if total > MAX_UNPACKED_BYTES {
+        return Err(too_large());
+    }
+
+    let mut written: u64 = 0;
+    for i in 0..archive.len() {
+        let entry = archive
+            .by_index(i)
+            .map_err(|e| format!("Failed to read plugin package: {}", e))?;
+        // Checked above
+        let Some(relative) = entry.enclosed_name() else {
+            continue;
+        };
+        // Only `launcher-plugin dev` may mark a plugin as a local build
+        if relative == Path::new(DEV_MARKER) {
+            continue;
+        }
+        let path = dest.join(relative);
+
+        if entry.is_dir() {
+            std::fs::create_dir_all(&path).map_err(|e| e.to_string())?;
+            continue;
+        }
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+        let mut file = std::fs::File::create(&path)
+            .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+        let budget = MAX_UNPACKED_BYTES - written;
+        let copied = std::io::copy(&mut entry.take(budget + 1), &mut file)
+            .map_err(|e| format!("Failed to extract {}: {}", path.display(), e))?;
+        if copied > budget {
+            return Err(too_large());
+        }
+        written += copied;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Cursor, Write};
+
+    fn package(files: &[(&str, &[u8])]) -> zip::ZipArchive<Cursor<Vec<u8>>> {
+        let mut zip = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        for (name, contents) in files {
+            zip.start_file(*name, zip::write::SimpleFileOptions::default())
+                .unwrap();
+            zip.write_all(contents).unwrap();
+        }
+        zip::ZipArchive::new(Cursor::new(zip.finish().unwrap().into_inner())).unwrap()
+    }
+
+    #[test]
+    fn refuses_paths_outside_the_plugin_folder() {
+        let dest = std::env::temp_dir().join("launcher-test-zip-slip");
+        let _ = std::fs::remove_dir_all(&dest);
+
+        let mut evil = package(&[("manifest.json", b"{}"), ("../../evil.sh", b"rm -rf ~")]);
+        assert!(extract(&mut evil, &dest).is_err());
+        // Nothing is written when any entry is unsafe
+        assert!(!dest.join("manifest.json").exists());
+
+        let mut good = package(&[
+            ("manifest.json", b"{}"),
+            ("assets/icon.svg", b"<svg/>"),
+            ("dev.json", b"{}"),
+        ]);
+        extract(&mut good, &dest).unwrap();
+        assert!(dest.join("assets").join("icon.svg").exists());
+        assert!(!dest.join("dev.json").exists());
+        let _ = std::fs::remove_dir_all(&dest);
+    }
+
+    #[test]
+    fn checks_checksums() {
+        let sha = "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824";
+        assert!(verify_checksum(b"hello", Some(sha)).is_ok());
+        assert!(verify_checksum(b"hello", Some(&sha.to_uppercase())).is_ok());
+        assert!(verify_checksum(b"hellO", Some(sha)).is_err());
+        assert!(verify_checksum(b"hello", None).is_ok());
+    }
+}
@@ -0,0 +1,213 @@
+//! On-disk copy of the marketplace catalog.
+//!
+//! One row per plugin, so a delta sync rewrites only what changed, plus a
+//! small key/value table for sync state: the ETag and Last-Modified of the
+//! last full download, the server time to ask for changes since, and when
+//! the server was last reached.
+
+use super::registry::RegistryPlugin;
+use parking_lot::Mutex;
+use rusqlite::{params, Connection, OptionalExtension};
+use std::path::Path;
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS plugins (
+    id TEXT PRIMARY KEY,
+    data TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS sync_state (
+    key TEXT PRIMARY KEY,
+    value TEXT NOT NULL
+);
+";
+
+/// Keys in `sync_state`
+pub const ETAG: &str = "etag";
+pub const LAST_MODIFIED: &str = "last_modified";
+/// Server time of the last sync, sent as `since` for the next delta
+pub const CURSOR: &str = "cursor";
+/// Unix seconds of the last full download
+pub const FULL_SYNC_AT: &str = "full_sync_at";
+/// Unix seconds the server last answered
+pub const CHECKED_AT: &str = "checked_at";
+/// "1" when the last attempt to reach the server failed
+pub const OFFLINE: &str = "offline";
+
+pub struct CatalogStore {
+    conn: Mutex<Connection>,
+}
+
+impl CatalogStore {
+    /// Open the catalog at `path`. The catalog can always be downloaded
+    /// again, so an unreadable one is recreated, and if even that fails the
+    /// catalog only lives in memory.
+    pub fn open(path: &Path) -> Self {
+        let conn = Self::open_file(path)
+            .or_else(|e| {
+                eprintln!("Marketplace catalog unreadable, recreating it: {}", e);
+                for suffix in ["", "-wal", "-shm"] {
+                    let _ = std::fs::remove_file(format!("{}{}", path.display(), suffix));
+                }
+                Self::open_file(path)
+            })
+            .or_else(|e| {
+                eprintln!("Keeping the marketplace catalog in memory: {}", e);
+                Connection::open_in_memory().and_then(|conn| {
+                    conn.execute_batch(SCHEMA)?;
+                    Ok(conn)
+                })
+            })
+            .expect("in-memory SQLite database");
+        Self {
+            conn: Mutex::new(conn),
+        }
+    }
+
+    fn open_file(path: &Path) -> rusqlite::Result<Connection> {
+        if let Some(parent) = path.parent() {
+            let _ = std::fs::create_dir_all(parent);
+        }
+        let conn = Connection::open(path)?;
+        conn.execute_batch("PRAGMA journal_mode = WAL; PRAGMA synchronous = NORMAL;")?;
+        conn.execute_batch(SCHEMA)?;
+        Ok(conn)
+    }
+
+    /// Every stored plugin; rows that no longer parse are skipped
+    pub fn load(&self) -> Result<Vec<RegistryPlugin>, String> {
+        let conn = self.conn.lock();
+        let mut stmt = conn
+            .prepare("SELECT data FROM plugins")
+            .map_err(|e| e.to_string())?;
+        let rows = stmt
+            .query_map([], |row| row.get::<_, String>(0))
+            .map_err(|e| e.to_string())?;
+        Ok(rows
+            .filter_map(|row| row.ok())
+            .filter_map(|data| serde_json::from_str(&data).ok())
+            .collect())
+    }
+
+    /// Store changed plugins and drop removed ones in one transaction. With
+    /// `replace`, plugins not in `upserts` are dropped too (a full sync).
+    pub fn apply(
+        &self,
+        upserts: &[RegistryPlugin],
+        removed: &[String],
+        replace: bool,
+    ) -> Result<(), String> {
+        let mut conn = self.conn.lock();
+        let tx = conn.transaction().map_err(|e| e.to_string())?;
+        if replace {
+            tx.execute("DELETE FROM plugins", [])
+                .map_err(|e| e.to_string())?;
+        }
+        for plugin in upserts {
+            let data = serde_json::to_string(plugin).map_err(|e| e.to_string())?;
+            tx.execute(
+                "INSERT OR REPLACE INTO plugins (id, data) VALUES (?1, ?2)",
+                params![plugin.id, data],
+            )
+            .map_err(|e| e.to_string())?;
+        }
+        for id in removed {
+            tx.execute("DELETE FROM plugins WHERE id = ?1", [id])
+                .map_err(|e| e.to_string())?;
+        }
+        tx.commit().map_err(|e| e.to_string())
+    }
+
+    pub fn get_state(&self, key: &str) -> Option<String> {
+        self.conn
+            .lock()
+            .query_row(
+                "SELECT value FROM sync_state WHERE key = ?1",
+                [key],
+                |row| row.get(0),
+            )
+            .optional()
+            .ok()
+            .flatten()
+    }
+
+    /// Set a sync state value, or clear it with None
+    pub fn set_state(&self, key: &str, value: Option<&str>) {
+        let conn = self.conn.lock();
+        let result = match value {
+            Some(value) => conn.execute(
+                "INSERT OR REPLACE INTO sync_state (key, value) VALUES (?1, ?2)",
+                params![key, value],
+            ),
+            None => conn.execute("DELETE FROM sync_state WHERE key = ?1", [key]),
+        };
+        if let Err(e) = result {
+            eprintln!("Failed to save marketplace sync state: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn plugin(id: &str, version: &str) -> RegistryPlugin {
+        serde_json::from_value(serde_json::json!({
+            "id": id,
+            "name": id,
+            "version": version,
+            "author": null,
+            "description": null,
+            "icon_url": null,
+            "homepage": null,
+            "repository": null,
+            "download_url": "",
+            "checksum": null,
+            "permissions": [],
+            "categories": [],
+            "downloads": 0,
+            "rating": null,
+            "verified": false,
+            "featured": false,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn applies_full_and_delta_syncs() {
+        let path = std::env::temp_dir().join("launcher-test-catalog.db");
+        for suffix in ["", "-wal", "-shm"] {
+            let _ = std::fs::remove_file(format!("{}{}", path.display(), suffix));
+        }
+        let store = CatalogStore::open(&path);
+
+        store
+            .apply(&[plugin("a", "1.0.0"), plugin("b", "1.0.0")], &[], true)
+            .unwrap();
+        store
+            .apply(
+                &[plugin("a", "1.1.0"), plugin("c", "1.0.0")],
+                &["b".to_string()],
+                false,
+            )
+            .unwrap();
+        let mut plugins: Vec<(String, String)> = store
+            .load()
+            .unwrap()
+            .into_iter()
+            .map(|p| (p.id, p.version))
+            .collect();
+        plugins.sort();
+        assert_eq!(
+            plugins,
+            vec![
+                ("a".to_string(), "1.1.0".to_string()),
+                ("c".to_string(), "1.0.0".to_string()),
+            ]
+        );
+
+        store.set_state(ETAG, Some("\"abc\""));
+        assert_eq!(store.get_state(ETAG).as_deref(), Some("\"abc\""));
+        store.set_state(ETAG, None);
+        assert_eq!(store.get_state(ETAG), None);
+    }
+}
@@ -0,0 +1,104 @@
+//! User-editable plugin settings.
+//!
+//! A manifest's `settings_schema` is a JSON Schema for an object whose
+//! properties are the settings the user may change. Their values live in the
+//! plugin's config storage next to whatever the plugin stores there itself;
+//! only keys the schema declares are shown to or changed by the user.
+
+use super::host_api::PluginConfig;
+use serde_json::{Map, Value};
+
+/// The schema's declared settings
+fn properties(schema: &Value) -> Option<&Map<String, Value>> {
+    schema.get("properties").and_then(Value::as_object)
+}
+
+/// Check values the user submitted against the schema
+pub fn validate(schema: &Value, values: &Value) -> Result<(), String> {
+    let validator = jsonschema::validator_for(schema)
+        .map_err(|e| format!("Plugin settings schema is invalid: {}", e))?;
+    let errors: Vec<String> = validator
+        .iter_errors(values)
+        .map(|e| {
+            let path = e.instance_path.to_string();
+            if path.is_empty() {
+                e.to_string()
+            } else {
+                format!("{}: {}", path.trim_start_matches('/'), e)
+            }
+        })
+        .collect();
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors.join("; "))
+    }
+}
+
+/// Current value of every declared setting, falling back to the schema's
+/// `default`. Settings with neither are left out.
+pub fn current_values(schema: &Value, config: &PluginConfig) -> Map<String, Value> {
+    let Some(properties) = properties(schema) else {
+        return Map::new();
+    };
+    properties
+        .iter()
+        .filter_map(|(key, property)| {
+            let value = config.values.get(key).or_else(|| property.get("default"))?;
+            Some((key.clone(), value.clone()))
+        })
+        .collect()
+}
+
+/// Store validated values. Declared settings missing from `values` are
+/// cleared so they fall back to their default; keys the plugin stored for
+/// itself are kept.
+pub fn apply(schema: &Value, config: &mut PluginConfig, values: Map<String, Value>) {
+    let Some(properties) = properties(schema) else {
+        return;
+    };
+    for key in properties.keys() {
+        config.values.remove(key);
+    }
+    config.values.extend(
+        values
+            .into_iter()
+            .filter(|(key, _)| properties.contains_key(key)),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn validates_and_keeps_plugin_keys() {
+        let schema = json!({
+            "type": "object",
+            "properties": {
+                "units": { "type": "string", "enum": ["metric", "imperial"], "default": "metric" },
+                "max_results": { "type": "integer", "minimum": 1 },
+            },
+            "additionalProperties": false,
+        });
+        assert!(validate(&schema, &json!({ "units": "imperial", "max_results": 5 })).is_ok());
+        assert!(validate(&schema, &json!({ "units": "kelvin" })).is_err());
+        assert!(validate(&schema, &json!({ "max_results": 0 })).is_err());
+        assert!(validate(&schema, &json!({ "token": "x" })).is_err());
+
+        let mut config = PluginConfig::default();
+        config.values.insert("token".into(), json!("secret"));
+        config.values.insert("max_results".into(), json!(3));
+        assert_eq!(
+            Value::Object(current_values(&schema, &config)),
+            json!({ "units": "metric", "max_results": 3 })
+        );
+
+        let values = json!({ "units": "imperial" }).as_object().unwrap().clone();
+        apply(&schema, &mut config, values);
+        assert_eq!(config.values.get("units"), Some(&json!("imperial")));
+        assert_eq!(config.values.get("max_results"), None);
+        assert_eq!(config.values.get("token"), Some(&json!("secret")));
+    }
+}
@@ -0,0 +1,174 @@
+//! Error tracking per plugin. A plugin whose calls keep failing is
+//! quarantined: the runtime unloads it and the loader marks it disabled, so
+//! one broken plugin can't slow down or clutter every search. It stays off
+//! until the user enables it again.
+
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::time::{Duration, Instant, SystemTime};
+
+/// Consecutive failed calls that quarantine a plugin...
+const QUARANTINE_AFTER: usize = 5;
+/// ...when they all happened within this long
+const QUARANTINE_WINDOW: Duration = Duration::from_secs(60);
+/// Errors kept per plugin for the UI
+const HISTORY_LEN: usize = 20;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PluginError {
+    /// Unix seconds
+    pub at: u64,
+    /// Plugin function that failed, e.g. `search`
+    pub call: String,
+    pub message: String,
+}
+
+/// What the plugins UI shows about a plugin's health
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PluginHealthReport {
+    pub plugin_id: String,
+    /// Failed calls since the last successful one
+    pub consecutive_errors: usize,
+    /// Failed calls since the launcher started
+    pub total_errors: u64,
+    /// Unix seconds the plugin was disabled for failing, if it was
+    pub quarantined_at: Option<u64>,
+    /// Newest first
+    pub recent_errors: Vec<PluginError>,
+}
+
+#[derive(Default)]
+struct HealthEntry {
+    /// When each failure since the last success happened
+    streak: VecDeque<Instant>,
+    total_errors: u64,
+    quarantined_at: Option<u64>,
+    history: VecDeque<PluginError>,
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+#[derive(Default)]
+pub struct HealthTracker {
+    entries: RwLock<HashMap<String, HealthEntry>>,
+}
+
+impl HealthTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record_success(&self, plugin_id: &str) {
+        if let Some(entry) = self.entries.write().get_mut(plugin_id) {
+            entry.streak.clear();
+        }
+    }
+
+    /// Record a failed call. Returns true if it got the plugin quarantined.
+    pub fn record_error(&self, plugin_id: &str, call: &str, message: &str) -> bool {
+        self.record_error_at(plugin_id, call, message, Instant::now())
+    }
+
+    fn record_error_at(&self, plugin_id: &str, call: &str, message: &str, now: Instant) -> bool {
+        let mut entries = self.entries.write();
+        let entry = entries.entry(plugin_id.to_string()).or_default();
+
+        entry.total_errors += 1;
+        entry.history.push_front(PluginError {
+            at: unix_now(),
+            call: call.to_string(),
+            message: message.to_string(),
+        });
+        entry.history.truncate(HISTORY_LEN);
+
+        entry.streak.push_back(now);
+        while entry.streak.len() > QUARANTINE_AFTER {
+            entry.streak.pop_front();
+        }
+        let within_window = entry
+            .streak
+            .front()
+            .is_some_and(|first| now.duration_since(*first) <= QUARANTINE_WINDOW);
+        if entry.quarantined_at.is_none() && entry.streak.len() >= QUARANTINE_AFTER && within_window
+        {
+            entry.quarantined_at = Some(unix_now());
+            return true;
+        }
+        false
+    }
+
+    pub fn is_quarantined(&self, plugin_id: &str) -> bool {
+        self.entries
+            .read()
+            .get(plugin_id)
+            .is_some_and(|e| e.quarantined_at.is_some())
+    }
+
+    /// Give a plugin a clean slate, keeping its error history
+    pub fn reset(&self, plugin_id: &str) {
+        if let Some(entry) = self.entries.write().get_mut(plugin_id) {
+            entry.streak.clear();
+            entry.quarantined_at = None;
+        }
+    }
+
+    pub fn report(&self, plugin_id: &str) -> PluginHealthReport {
+        let entries = self.entries.read();
+        let Some(entry) = entries.get(plugin_id) else {
+            return PluginHealthReport {
+                plugin_id: plugin_id.to_string(),
+                ..Default::default()
+            };
+        };
+        PluginHealthReport {
+            plugin_id: plugin_id.to_string(),
+            consecutive_errors: entry.streak.len(),
+            total_errors: entry.total_errors,
+            quarantined_at: entry.quarantined_at,
+            recent_errors: entry.history.iter().cloned().collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quarantines_after_repeated_errors() {
+        let health = HealthTracker::new();
+        let start = Instant::now();
+
+        // Spread out, the errors never add up to a quarantine
+        for i in 0..10 {
+            let at = start + Duration::from_secs(30 * i);
+            assert!(!health.record_error_at("slow", "search", "timeout", at));
+        }
+        assert_eq!(health.report("slow").total_errors, 10);
+
+        // A success breaks the streak
+        for _ in 0..4 {
+            health.record_error_at("flaky", "search", "trap", start);
+        }
+        health.record_success("flaky");
+        assert!(!health.record_error_at("flaky", "search", "trap", start));
+
+        for _ in 0..4 {
+            assert!(!health.record_error_at("broken", "search", "trap", start));
+        }
+        assert!(health.record_error_at("broken", "search", "trap", start));
+        assert!(health.is_quarantined("broken"));
+        // Reported once
+        assert!(!health.record_error_at("broken", "search", "trap", start));
+
+        health.reset("broken");
+        assert!(!health.is_quarantined("broken"));
+        assert_eq!(health.report("broken").recent_errors.len(), 6);
+    }
+}
@@ -1,6 +1,9 @@
+use crate::oauth::OAuthFlow;
 use serde::{Deserialize, Serialize};
-use std::collections::{HashMap, HashSet};
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 /// Filesystem permissions for a plugin
 #[derive(Debug, Clone, Default)]
@@ -10,6 +13,92 @@ pub struct PluginFsPermissions {
     pub data_dir: PathBuf,
 }
 
+/// Network permissions for a plugin
+#[derive(Debug, Clone, Default)]
+pub struct PluginNetworkPermissions {
+    pub enabled: bool,
+    /// Host patterns from the manifest, allowed without asking; None for a
+    /// manifest without a host list, which may contact any host
+    pub allowed_hosts: Option<Vec<String>>,
+}
+
+/// Asks the user whether a plugin may contact a host it didn't declare
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HostPrompt {
+    pub plugin_id: String,
+    pub host: String,
+    pub url: String,
+}
+
+type HostPromptListener = Arc<dyn Fn(HostPrompt) + Send + Sync>;
+
+/// Asks the user whether a plugin may use a connected account's token
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OAuthPrompt {
+    pub plugin_id: String,
+    /// Provider id, e.g. `github`
+    pub provider: String,
+    /// Provider name for display, e.g. `GitHub`
+    pub provider_name: String,
+}
+
+type OAuthPromptListener = Arc<dyn Fn(OAuthPrompt) + Send + Sync>;
+
+/// Disk space a plugin takes up
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PluginStorageUsage {
+    pub plugin_id: String,
+    /// The installed package: manifest, WASM and assets
+    pub package_bytes: u64,
+    /// Files the plugin wrote to its data directory
+    pub data_bytes: u64,
+    /// Its config, where plugins keep key/value data
+    pub config_bytes: u64,
+    /// Limit on data and config together, if any
+    pub quota_bytes: Option<u64>,
+}
+
+/// A notification a plugin asked to show
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PluginNotification {
+    pub plugin_id: String,
+    pub title: String,
+    pub body: String,
+}
+
+type NotificationListener = Arc<dyn Fn(PluginNotification) -> Result<(), String> + Send + Sync>;
+
+/// Notifications a plugin may show per `NOTIFICATION_WINDOW`
+const MAX_NOTIFICATIONS: usize = 5;
+const NOTIFICATION_WINDOW: Duration = Duration::from_secs(60);
+const MAX_TITLE_CHARS: usize = 100;
+const MAX_BODY_CHARS: usize = 500;
+/// Largest text a plugin may put on the clipboard
+const MAX_CLIPBOARD_BYTES: usize = 1024 * 1024;
+
+fn file_size(path: &Path) -> u64 {
+    std::fs::metadata(path).map(|m| m.len()).unwrap_or(0)
+}
+
+fn truncate_chars(text: &str, max: usize) -> String {
+    if text.chars().count() <= max {
+        return text.to_string();
+    }
+    let mut truncated: String = text.chars().take(max - 1).collect();
+    truncated.push('…');
+    truncated
+}
+
+/// Whether `host` matches a manifest pattern: an exact host name, or
+/// `*.example.com` for example.com and its subdomains
+fn host_matches(pattern: &str, host: &str) -> bool {
+    let pattern = pattern.trim().to_lowercase();
+    match pattern.strip_prefix("*.") {
+        Some(domain) => host == domain || host.ends_with(&format!(".{}", domain)),
+        None => host == pattern,
+    }
+}
+
 /// Search result returned by plugins
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PluginSearchResult {
@@ -20,6 +109,9 @@ pub struct PluginSearchResult {
     pub score: Option<f32>,
     pub category: Option<String>,
     pub action: Option<PluginAction>,
+    /// Read by screen readers instead of the title and subtitle
+    #[serde(default)]
+    pub aria_label: Option<String>,
 }
 
 /// Action that can be executed when a result is selected
@@ -87,6 +179,8 @@ pub trait PluginHostApi: Send + Sync {
     fn get_config(&self, plugin_id: &str) -> PluginConfig;
     fn set_config(&self, plugin_id: &str, config: PluginConfig) -> Result<(), String>;
     fn show_notification(&self, plugin_id: &str, title: &str, body: &str) -> Result<(), String>;
+    fn read_clipboard(&self, plugin_id: &str) -> Result<String, String>;
+    fn write_clipboard(&self, plugin_id: &str, text: &str) -> Result<(), String>;
     fn get_oauth_token(&self, plugin_id: &str, provider: &str) -> Result<String, String>;
 }
 
@@ -95,6 +189,31 @@ pub struct DefaultHostApi {
     config_dir: PathBuf,
     plugins_data_dir: PathBuf,
     plugin_permissions: parking_lot::RwLock<HashMap<String, PluginFsPermissions>>,
+    network_permissions: parking_lot::RwLock<HashMap<String, PluginNetworkPermissions>>,
+    /// The user's answers to host prompts, by plugin then host
+    host_decisions: parking_lot::RwLock<HashMap<String, HashMap<String, bool>>>,
+    host_decisions_path: PathBuf,
+    /// Prompts shown but not yet answered, so each is only shown once
+    pending_prompts: parking_lot::Mutex<HashSet<(String, String)>>,
+    host_prompt_listener: parking_lot::RwLock<Option<HostPromptListener>>,
+    /// Plugins with the `notifications` permission
+    notification_permissions: parking_lot::RwLock<HashSet<String>>,
+    /// When each plugin's recent notifications were shown
+    notification_times: parking_lot::Mutex<HashMap<String, VecDeque<Instant>>>,
+    notification_listener: parking_lot::RwLock<Option<NotificationListener>>,
+    /// Plugins with the `clipboard` permission
+    clipboard_permissions: parking_lot::RwLock<HashSet<String>>,
+    /// Bytes each plugin may keep in its data directory and config
+    storage_quotas: parking_lot::RwLock<HashMap<String, u64>>,
+    /// Providers each plugin declared an `oauth:` permission for
+    oauth_providers: parking_lot::RwLock<HashMap<String, HashSet<String>>>,
+    /// The user's answers to token requests, by plugin then provider
+    oauth_consents: parking_lot::RwLock<HashMap<String, HashMap<String, bool>>>,
+    oauth_consents_path: PathBuf,
+    pending_oauth_prompts: parking_lot::Mutex<HashSet<(String, String)>>,
+    oauth_prompt_listener: parking_lot::RwLock<Option<OAuthPromptListener>>,
+    /// Holds the tokens of accounts the user connected; set at startup
+    oauth_flow: parking_lot::RwLock<Option<Arc<OAuthFlow>>>,
 }
 
 impl DefaultHostApi {
@@ -109,10 +228,33 @@ impl DefaultHostApi {
         let _ = std::fs::create_dir_all(&config_dir);
         let _ = std::fs::create_dir_all(&plugins_data_dir);
 
+        let host_decisions_path = base_dir.join("plugin_hosts.json");
+        let host_decisions =
+            crate::journal::load_json_store(&host_decisions_path).unwrap_or_default();
+        let oauth_consents_path = base_dir.join("plugin_oauth.json");
+        let oauth_consents =
+            crate::journal::load_json_store(&oauth_consents_path).unwrap_or_default();
+
         Self {
             config_dir,
             plugins_data_dir,
             plugin_permissions: parking_lot::RwLock::new(HashMap::new()),
+            network_permissions: parking_lot::RwLock::new(HashMap::new()),
+            host_decisions: parking_lot::RwLock::new(host_decisions),
+            host_decisions_path,
+            pending_prompts: parking_lot::Mutex::new(HashSet::new()),
+            host_prompt_listener: parking_lot::RwLock::new(None),
+            notification_permissions: parking_lot::RwLock::new(HashSet::new()),
+            notification_times: parking_lot::Mutex::new(HashMap::new()),
+            notification_listener: parking_lot::RwLock::new(None),
+            clipboard_permissions: parking_lot::RwLock::new(HashSet::new()),
+            storage_quotas: parking_lot::RwLock::new(HashMap::new()),
+            oauth_providers: parking_lot::RwLock::new(HashMap::new()),
+            oauth_consents: parking_lot::RwLock::new(oauth_consents),
+            oauth_consents_path,
+            pending_oauth_prompts: parking_lot::Mutex::new(HashSet::new()),
+            oauth_prompt_listener: parking_lot::RwLock::new(None),
+            oauth_flow: parking_lot::RwLock::new(None),
         }
     }
 
@@ -132,10 +274,368 @@ impl DefaultHostApi {
         );
     }
 
+    /// Register whether a plugin may use the network, and which hosts it
+    /// declared
+    pub fn register_network(
+        &self,
+        plugin_id: &str,
+        enabled: bool,
+        allowed_hosts: Option<Vec<String>>,
+    ) {
+        self.network_permissions.write().insert(
+            plugin_id.to_string(),
+            PluginNetworkPermissions {
+                enabled,
+                allowed_hosts,
+            },
+        );
+    }
+
+    /// Register whether a plugin may show notifications
+    pub fn register_notifications(&self, plugin_id: &str, enabled: bool) {
+        let mut permissions = self.notification_permissions.write();
+        if enabled {
+            permissions.insert(plugin_id.to_string());
+        } else {
+            permissions.remove(plugin_id);
+        }
+    }
+
+    /// Register whether a plugin may read and change the clipboard
+    pub fn register_clipboard(&self, plugin_id: &str, enabled: bool) {
+        let mut permissions = self.clipboard_permissions.write();
+        if enabled {
+            permissions.insert(plugin_id.to_string());
+        } else {
+            permissions.remove(plugin_id);
+        }
+    }
+
+    fn check_clipboard(&self, plugin_id: &str) -> Result<(), String> {
+        if self.clipboard_permissions.read().contains(plugin_id) {
+            Ok(())
+        } else {
+            Err(format!(
+                "Plugin '{}' does not have clipboard permission",
+                plugin_id
+            ))
+        }
+    }
+
+    /// Register the providers whose tokens a plugin may ask for
+    pub fn register_oauth(&self, plugin_id: &str, providers: HashSet<String>) {
+        let mut registered = self.oauth_providers.write();
+        if providers.is_empty() {
+            registered.remove(plugin_id);
+        } else {
+            registered.insert(plugin_id.to_string(), providers);
+        }
+    }
+
     /// Unregister a plugin (when unloaded)
     pub fn unregister_plugin(&self, plugin_id: &str) {
         let mut permissions = self.plugin_permissions.write();
         permissions.remove(plugin_id);
+        self.network_permissions.write().remove(plugin_id);
+        self.notification_permissions.write().remove(plugin_id);
+        self.clipboard_permissions.write().remove(plugin_id);
+        self.storage_quotas.write().remove(plugin_id);
+        self.oauth_providers.write().remove(plugin_id);
+    }
+
+    /// Limit the data and config a plugin may store; None for no limit
+    pub fn register_storage_quota(&self, plugin_id: &str, quota_bytes: Option<u64>) {
+        let mut quotas = self.storage_quotas.write();
+        match quota_bytes {
+            Some(quota) => quotas.insert(plugin_id.to_string(), quota),
+            None => quotas.remove(plugin_id),
+        };
+    }
+
+    /// Measure what a plugin stores; `package_dir` is where it's installed
+    pub fn storage_usage(&self, plugin_id: &str, package_dir: Option<&Path>) -> PluginStorageUsage {
+        PluginStorageUsage {
+            plugin_id: plugin_id.to_string(),
+            package_bytes: package_dir.map(crate::cleanup::dir_size).unwrap_or(0),
+            data_bytes: crate::cleanup::dir_size(&self.get_plugin_data_dir(plugin_id)),
+            config_bytes: file_size(&self.get_config_path(plugin_id)),
+            quota_bytes: self.storage_quotas.read().get(plugin_id).copied(),
+        }
+    }
+
+    /// Delete a plugin's data directory and config. Unload the plugin
+    /// first; loading it creates a fresh data directory.
+    pub fn clear_plugin_data(&self, plugin_id: &str) -> Result<(), String> {
+        let data_dir = self.get_plugin_data_dir(plugin_id);
+        if data_dir.exists() {
+            std::fs::remove_dir_all(&data_dir)
+                .map_err(|e| format!("Failed to delete plugin data: {}", e))?;
+        }
+        let config_path = self.get_config_path(plugin_id);
+        if config_path.exists() {
+            std::fs::remove_file(&config_path)
+                .map_err(|e| format!("Failed to delete plugin config: {}", e))?;
+        }
+        Ok(())
+    }
+
+    /// Refuse a write that would take the plugin past its quota. `replacing`
+    /// is the file being overwritten, whose current size is freed.
+    fn check_quota(&self, plugin_id: &str, replacing: &Path, new_bytes: u64) -> Result<(), String> {
+        let Some(quota) = self.storage_quotas.read().get(plugin_id).copied() else {
+            return Ok(());
+        };
+        let usage = self.storage_usage(plugin_id, None);
+        let used = (usage.data_bytes + usage.config_bytes).saturating_sub(file_size(replacing));
+        if used + new_bytes > quota {
+            return Err(format!(
+                "Plugin storage quota of {} exceeded",
+                crate::cleanup::format_bytes(quota)
+            ));
+        }
+        Ok(())
+    }
+
+    /// Shows plugin notifications; without one they fail
+    pub fn set_notification_listener(
+        &self,
+        listener: impl Fn(PluginNotification) -> Result<(), String> + Send + Sync + 'static,
+    ) {
+        *self.notification_listener.write() = Some(Arc::new(listener));
+    }
+
+    /// Count a notification against the plugin's rate limit. False if it
+    /// has already shown `MAX_NOTIFICATIONS` within the window.
+    fn allow_notification_at(&self, plugin_id: &str, now: Instant) -> bool {
+        let mut times = self.notification_times.lock();
+        let recent = times.entry(plugin_id.to_string()).or_default();
+        while recent
+            .front()
+            .is_some_and(|shown| now.duration_since(*shown) >= NOTIFICATION_WINDOW)
+        {
+            recent.pop_front();
+        }
+        if recent.len() >= MAX_NOTIFICATIONS {
+            return false;
+        }
+        recent.push_back(now);
+        true
+    }
+
+    /// Called when a plugin contacts a host it didn't declare, to ask the
+    /// user
+    pub fn set_host_prompt_listener(&self, listener: impl Fn(HostPrompt) + Send + Sync + 'static) {
+        *self.host_prompt_listener.write() = Some(Arc::new(listener));
+    }
+
+    /// Record the user's answer for a host; an allowed host works from the
+    /// plugin's next request
+    pub fn set_host_decision(&self, plugin_id: &str, host: &str, allowed: bool) {
+        let host = host.to_lowercase();
+        self.pending_prompts
+            .lock()
+            .remove(&(plugin_id.to_string(), host.clone()));
+        self.host_decisions
+            .write()
+            .entry(plugin_id.to_string())
+            .or_default()
+            .insert(host, allowed);
+        self.save_host_decisions();
+    }
+
+    /// Forget an answer, so the plugin asks again next time
+    pub fn clear_host_decision(&self, plugin_id: &str, host: &str) {
+        if let Some(hosts) = self.host_decisions.write().get_mut(plugin_id) {
+            hosts.remove(&host.to_lowercase());
+        }
+        self.save_host_decisions();
+    }
+
+    /// Hosts the user has answered for, with whether each was allowed
+    pub fn host_decisions(&self, plugin_id: &str) -> HashMap<String, bool> {
+        self.host_decisions
+            .read()
+            .get(plugin_id)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    fn save_host_decisions(&self) {
+        let decisions = self.host_decisions.read();
+        if let Ok(json) = serde_json::to_string_pretty(&*decisions) {
+            if let Err(e) = crate::journal::write_json_store(&self.host_decisions_path, &json) {
+                eprintln!("Failed to save plugin host permissions: {}", e);
+            }
+        }
+    }
+
+    /// Broker tokens from the accounts the user connected
+    pub fn set_oauth_flow(&self, flow: Arc<OAuthFlow>) {
+        *self.oauth_flow.write() = Some(flow);
+    }
+
+    /// Called when a plugin first asks for a provider's token, so the UI
+    /// can ask the user
+    pub fn set_oauth_prompt_listener(
+        &self,
+        listener: impl Fn(OAuthPrompt) + Send + Sync + 'static,
+    ) {
+        *self.oauth_prompt_listener.write() = Some(Arc::new(listener));
+    }
+
+    /// Record the user's answer for a provider; an allowed provider's token
+    /// is handed out from the plugin's next request
+    pub fn set_oauth_consent(&self, plugin_id: &str, provider: &str, allowed: bool) {
+        let provider = provider.to_lowercase();
+        self.pending_oauth_prompts
+            .lock()
+            .remove(&(plugin_id.to_string(), provider.clone()));
+        self.oauth_consents
+            .write()
+            .entry(plugin_id.to_string())
+            .or_default()
+            .insert(provider, allowed);
+        self.save_oauth_consents();
+    }
+
+    /// Forget an answer, so the plugin asks again next time
+    pub fn clear_oauth_consent(&self, plugin_id: &str, provider: &str) {
+        if let Some(providers) = self.oauth_consents.write().get_mut(plugin_id) {
+            providers.remove(&provider.to_lowercase());
+        }
+        self.save_oauth_consents();
+    }
+
+    /// Providers the user has answered for, with whether each was allowed
+    pub fn oauth_consents(&self, plugin_id: &str) -> HashMap<String, bool> {
+        self.oauth_consents
+            .read()
+            .get(plugin_id)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    fn save_oauth_consents(&self) {
+        let consents = self.oauth_consents.read();
+        if let Ok(json) = serde_json::to_string_pretty(&*consents) {
+            if let Err(e) = crate::journal::write_json_store(&self.oauth_consents_path, &json) {
+                eprintln!("Failed to save plugin account permissions: {}", e);
+            }
+        }
+    }
+
+    /// Whether the plugin may use `provider`'s token: declared in its
+    /// manifest and allowed by the user. Asks the user the first time.
+    fn check_oauth_consent(
+        &self,
+        plugin_id: &str,
+        provider: &str,
+        name: &str,
+    ) -> Result<(), String> {
+        let declared = self
+            .oauth_providers
+            .read()
+            .get(plugin_id)
+            .is_some_and(|providers| providers.contains(provider));
+        if !declared {
+            return Err(format!(
+                "Plugin '{}' does not have oauth:{} permission",
+                plugin_id, provider
+            ));
+        }
+
+        let consent = self
+            .oauth_consents
+            .read()
+            .get(plugin_id)
+            .and_then(|providers| providers.get(provider).copied());
+        match consent {
+            Some(true) => Ok(()),
+            Some(false) => Err(format!("Access to your {} account was denied", name)),
+            None => {
+                let first_ask = self
+                    .pending_oauth_prompts
+                    .lock()
+                    .insert((plugin_id.to_string(), provider.to_string()));
+                let listener = self.oauth_prompt_listener.read().clone();
+                if let (true, Some(listener)) = (first_ask, listener) {
+                    listener(OAuthPrompt {
+                        plugin_id: plugin_id.to_string(),
+                        provider: provider.to_string(),
+                        provider_name: name.to_string(),
+                    });
+                }
+                Err(format!(
+                    "Waiting for permission to use your {} account; try again once allowed",
+                    name
+                ))
+            }
+        }
+    }
+
+    /// Whether the plugin may contact `host` without asking: declared in
+    /// its manifest or allowed by the user. `None` if the user hasn't been
+    /// asked yet. A manifest without a host list declares none, so every
+    /// host it reaches for is asked about.
+    fn host_allowed(&self, plugin_id: &str, host: &str) -> Option<bool> {
+        let declared = self
+            .network_permissions
+            .read()
+            .get(plugin_id)
+            .and_then(|n| n.allowed_hosts.as_ref())
+            .is_some_and(|hosts| hosts.iter().any(|p| host_matches(p, host)));
+        if declared {
+            return Some(true);
+        }
+        self.host_decisions
+            .read()
+            .get(plugin_id)
+            .and_then(|hosts| hosts.get(host).copied())
+    }
+
+    /// Check a request URL against the plugin's network permissions,
+    /// prompting the user the first time it reaches for an undeclared host
+    fn check_url(&self, plugin_id: &str, url: &str) -> Result<(), String> {
+        let enabled = self
+            .network_permissions
+            .read()
+            .get(plugin_id)
+            .is_some_and(|n| n.enabled);
+        if !enabled {
+            return Err(format!(
+                "Plugin '{}' does not have network permission",
+                plugin_id
+            ));
+        }
+
+        let parsed = url::Url::parse(url).map_err(|e| format!("Invalid URL: {}", e))?;
+        if !matches!(parsed.scheme(), "http" | "https") {
+            return Err(format!("Unsupported URL scheme: {}", parsed.scheme()));
+        }
+        let host = parsed.host_str().ok_or("URL has no host")?.to_lowercase();
+
+        match self.host_allowed(plugin_id, &host) {
+            Some(true) => Ok(()),
+            Some(false) => Err(format!("Access to {} was denied", host)),
+            None => {
+                let first_ask = self
+                    .pending_prompts
+                    .lock()
+                    .insert((plugin_id.to_string(), host.clone()));
+                let listener = self.host_prompt_listener.read().clone();
+                if let (true, Some(listener)) = (first_ask, listener) {
+                    listener(HostPrompt {
+                        plugin_id: plugin_id.to_string(),
+                        host: host.clone(),
+                        url: url.to_string(),
+                    });
+                }
+                Err(format!(
+                    "Waiting for permission to contact {}; try again once allowed",
+                    host
+                ))
+            }
+        }
     }
 
     fn get_config_path(&self, plugin_id: &str) -> PathBuf {
@@ -225,8 +725,24 @@ impl PluginHostApi for DefaultHostApi {
     }
 
     fn http_request(&self, plugin_id: &str, request: HttpRequest) -> Result<HttpResponse, String> {
+        self.check_url(plugin_id, &request.url)?;
+
+        // Redirects must stay on hosts the plugin may contact
+        let redirect_plugin_id = plugin_id.to_string();
+        let policy = reqwest::redirect::Policy::custom(move |attempt| {
+            if attempt.previous().len() >= 10 {
+                return attempt.error("Too many redirects");
+            }
+            match HOST_API.check_url(&redirect_plugin_id, attempt.url().as_str()) {
+                Ok(()) => attempt.follow(),
+                Err(e) => attempt.error(e),
+            }
+        });
         // Use blocking reqwest for simplicity in host functions
-        let client = reqwest::blocking::Client::new();
+        let client = reqwest::blocking::Client::builder()
+            .redirect(policy)
+            .build()
+            .map_err(|e| format!("HTTP client error: {}", e))?;
 
         let mut req_builder = match request.method.to_uppercase().as_str() {
             "GET" => client.get(&request.url),
@@ -317,6 +833,7 @@ impl PluginHostApi for DefaultHostApi {
 
         // Resolve and validate the path within the sandbox
         let resolved_path = self.resolve_sandboxed_path(plugin_id, path)?;
+        self.check_quota(plugin_id, &resolved_path, data.len() as u64)?;
 
         println!(
             "[Plugin:{}] Writing file: {} -> {}",
@@ -340,25 +857,93 @@ impl PluginHostApi for DefaultHostApi {
         let path = self.get_config_path(plugin_id);
         let json = serde_json::to_string_pretty(&config)
             .map_err(|e| format!("Failed to serialize config: {}", e))?;
+        self.check_quota(plugin_id, &path, json.len() as u64)?;
         std::fs::write(&path, json).map_err(|e| format!("Failed to write config: {}", e))
     }
 
     fn show_notification(&self, plugin_id: &str, title: &str, body: &str) -> Result<(), String> {
-        // TODO: Implement actual system notifications
-        println!("[Notification from {}] {}: {}", plugin_id, title, body);
-        Ok(())
+        if !self.notification_permissions.read().contains(plugin_id) {
+            return Err(format!(
+                "Plugin '{}' does not have notifications permission",
+                plugin_id
+            ));
+        }
+        let title = title.trim();
+        if title.is_empty() {
+            return Err("Notification title is empty".to_string());
+        }
+        if !self.allow_notification_at(plugin_id, Instant::now()) {
+            return Err(format!(
+                "Too many notifications; plugins may show {} per minute",
+                MAX_NOTIFICATIONS
+            ));
+        }
+
+        let listener = self
+            .notification_listener
+            .read()
+            .clone()
+            .ok_or("Notifications are not available")?;
+        listener(PluginNotification {
+            plugin_id: plugin_id.to_string(),
+            title: truncate_chars(title, MAX_TITLE_CHARS),
+            body: truncate_chars(body.trim(), MAX_BODY_CHARS),
+        })
     }
 
-    fn get_oauth_token(&self, plugin_id: &str, provider: &str) -> Result<String, String> {
-        // TODO: Integrate with OAuth flow
+    fn read_clipboard(&self, plugin_id: &str) -> Result<String, String> {
+        self.check_clipboard(plugin_id)?;
+        // Passwords copied from a password manager stay out of plugins' reach
+        if crate::clipboard::is_concealed() {
+            return Err("The clipboard holds a password or other secret".to_string());
+        }
+        crate::clipboard::read_text()
+    }
+
+    fn write_clipboard(&self, plugin_id: &str, text: &str) -> Result<(), String> {
+        self.check_clipboard(plugin_id)?;
+        if text.len() > MAX_CLIPBOARD_BYTES {
+            return Err(format!(
+                "Text is too large for the clipboard ({} bytes, limit {})",
+                text.len(),
+                MAX_CLIPBOARD_BYTES
+            ));
+        }
         println!(
-            "[Plugin:{}] Requesting OAuth token for: {}",
-            plugin_id, provider
+            "[Plugin:{}] Copying {} bytes to the clipboard",
+            plugin_id,
+            text.len()
         );
-        Err(format!(
-            "OAuth token retrieval not yet implemented for provider: {}",
-            provider
-        ))
+        crate::clipboard::copy_text(text)
+    }
+
+    fn get_oauth_token(&self, plugin_id: &str, provider: &str) -> Result<String, String> {
+        let provider = provider.to_lowercase();
+        let flow = self
+            .oauth_flow
+            .read()
+            .clone()
+            .ok_or("Connected accounts are not available")?;
+        let name = flow
+            .get_provider(&provider)
+            .map(|config| config.name)
+            .ok_or_else(|| format!("Unknown account provider: {}", provider))?;
+
+        self.check_oauth_consent(plugin_id, &provider, &name)?;
+        if !flow.is_connected(&provider) {
+            return Err(format!(
+                "No {} account is connected; connect one in Settings → Accounts",
+                name
+            ));
+        }
+        let token = flow.get_token_if_valid(&provider).ok_or_else(|| {
+            format!(
+                "The {} sign-in has expired; reconnect it in Settings → Accounts",
+                name
+            )
+        })?;
+        println!("[Plugin:{}] Using {} account token", plugin_id, name);
+        Ok(token)
     }
 }
 
@@ -366,3 +951,107 @@ impl PluginHostApi for DefaultHostApi {
 lazy_static::lazy_static! {
     pub static ref HOST_API: DefaultHostApi = DefaultHostApi::new();
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_declared_hosts() {
+        assert!(host_matches("api.github.com", "api.github.com"));
+        assert!(!host_matches("api.github.com", "github.com"));
+        assert!(host_matches("*.example.com", "example.com"));
+        assert!(host_matches("*.example.com", "cdn.example.com"));
+        assert!(!host_matches("*.example.com", "badexample.com"));
+    }
+
+    #[test]
+    fn asks_before_undeclared_hosts() {
+        let id = "hostless-test-plugin";
+        HOST_API.register_network(id, true, None);
+        let waiting = HOST_API.check_url(id, "https://example.com/data");
+        assert!(waiting.unwrap_err().starts_with("Waiting for permission"));
+        assert!(HOST_API
+            .pending_prompts
+            .lock()
+            .contains(&(id.to_string(), "example.com".to_string())));
+
+        HOST_API.register_network(id, true, Some(vec!["*.example.com".to_string()]));
+        assert!(HOST_API
+            .check_url(id, "https://api.example.com/data")
+            .is_ok());
+        assert!(HOST_API.check_url(id, "https://other.org/").is_err());
+        HOST_API.unregister_plugin(id);
+    }
+
+    #[test]
+    fn rate_limits_notifications() {
+        let start = Instant::now();
+        for _ in 0..MAX_NOTIFICATIONS {
+            assert!(HOST_API.allow_notification_at("noisy-test-plugin", start));
+        }
+        assert!(!HOST_API.allow_notification_at("noisy-test-plugin", start));
+        assert!(HOST_API.allow_notification_at("quiet-test-plugin", start));
+        assert!(HOST_API.allow_notification_at("noisy-test-plugin", start + NOTIFICATION_WINDOW));
+
+        assert_eq!(truncate_chars("short", 10), "short");
+        assert_eq!(truncate_chars("ünïcödé text", 6), "ünïcö…");
+    }
+
+    #[test]
+    fn asks_once_before_handing_out_tokens() {
+        let id = "oauth-test-plugin";
+        assert!(HOST_API
+            .check_oauth_consent(id, "github", "GitHub")
+            .is_err());
+
+        HOST_API.register_oauth(id, HashSet::from(["github".to_string()]));
+        let waiting = HOST_API.check_oauth_consent(id, "github", "GitHub");
+        assert!(waiting.unwrap_err().starts_with("Waiting for permission"));
+        assert!(HOST_API
+            .pending_oauth_prompts
+            .lock()
+            .contains(&(id.to_string(), "github".to_string())));
+
+        // Not saved, so the test leaves the user's answers alone
+        HOST_API.oauth_consents.write().insert(
+            id.to_string(),
+            HashMap::from([("github".to_string(), true)]),
+        );
+        assert!(HOST_API.check_oauth_consent(id, "github", "GitHub").is_ok());
+        assert!(HOST_API
+            .check_oauth_consent(id, "google", "Google")
+            .is_err());
+        HOST_API.oauth_consents.write().remove(id);
+        HOST_API.unregister_plugin(id);
+    }
+
+    #[test]
+    fn enforces_storage_quotas() {
+        let id = "quota-test-plugin";
+        let file = HOST_API.get_plugin_data_dir(id).join("state.json");
+        assert!(HOST_API.check_quota(id, &file, 1 << 30).is_ok());
+
+        HOST_API.register_storage_quota(id, Some(10));
+        assert!(HOST_API.check_quota(id, &file, 10).is_ok());
+        assert!(HOST_API.check_quota(id, &file, 11).is_err());
+        assert_eq!(HOST_API.storage_usage(id, None).quota_bytes, Some(10));
+        HOST_API.unregister_plugin(id);
+    }
+
+    #[test]
+    fn requires_clipboard_permission() {
+        let id = "clipboard-test-plugin";
+        assert!(HOST_API.read_clipboard(id).is_err());
+        assert!(HOST_API.write_clipboard(id, "hello").is_err());
+
+        HOST_API.register_clipboard(id, true);
+        let huge = "x".repeat(MAX_CLIPBOARD_BYTES + 1);
+        assert!(HOST_API
+            .write_clipboard(id, &huge)
+            .unwrap_err()
+            .contains("too large"));
+        HOST_API.unregister_plugin(id);
+        assert!(HOST_API.check_clipboard(id).is_err());
+    }
+}
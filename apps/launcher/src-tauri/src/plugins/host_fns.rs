@@ -0,0 +1,155 @@
+//! Extism host functions backing `launcher_plugin_sdk::host`.
+//!
+//! Each plugin instance gets its own set of functions with the plugin id as
+//! user data, so every call is attributed and sandboxed per plugin. Fallible
+//! calls answer with a JSON envelope (`{"ok": ...}` or `{"error": "..."}`)
+//! instead of returning an Extism error, which would abort the plugin call.
+
+use super::host_api::{HttpRequest, PluginConfig, PluginHostApi, HOST_API};
+use extism::{host_fn, Function, UserData, PTR};
+use serde::Serialize;
+
+fn envelope<T: Serialize>(result: Result<T, String>) -> String {
+    let value = match result {
+        Ok(value) => serde_json::json!({ "ok": value }),
+        Err(error) => serde_json::json!({ "error": error }),
+    };
+    value.to_string()
+}
+
+fn plugin_id(user_data: &UserData<String>) -> Result<String, extism::Error> {
+    let id = user_data.get()?;
+    let id = id
+        .lock()
+        .map_err(|_| extism::Error::msg("Plugin id lock poisoned"))?;
+    Ok(id.clone())
+}
+
+host_fn!(host_log(user_data: String; level: String, message: String) {
+    HOST_API.log(&plugin_id(&user_data)?, &level, &message);
+    Ok(())
+});
+
+host_fn!(host_http_request(user_data: String; request_json: String) -> String {
+    let plugin_id = plugin_id(&user_data)?;
+    let result = serde_json::from_str::<HttpRequest>(&request_json)
+        .map_err(|e| format!("Invalid request: {}", e))
+        .and_then(|request| HOST_API.http_request(&plugin_id, request));
+    Ok(envelope(result))
+});
+
+host_fn!(host_get_config(user_data: String;) -> String {
+    let config = HOST_API.get_config(&plugin_id(&user_data)?);
+    Ok(serde_json::to_string(&config)?)
+});
+
+host_fn!(host_set_config(user_data: String; config_json: String) -> String {
+    let plugin_id = plugin_id(&user_data)?;
+    let result = serde_json::from_str::<PluginConfig>(&config_json)
+        .map_err(|e| format!("Invalid config: {}", e))
+        .and_then(|config| HOST_API.set_config(&plugin_id, config));
+    Ok(envelope(result))
+});
+
+host_fn!(host_show_notification(user_data: String; title: String, body: String) -> String {
+    let result = HOST_API.show_notification(&plugin_id(&user_data)?, &title, &body);
+    Ok(envelope(result))
+});
+
+host_fn!(host_read_clipboard(user_data: String;) -> String {
+    let result = HOST_API.read_clipboard(&plugin_id(&user_data)?);
+    Ok(envelope(result))
+});
+
+host_fn!(host_write_clipboard(user_data: String; text: String) -> String {
+    let result = HOST_API.write_clipboard(&plugin_id(&user_data)?, &text);
+    Ok(envelope(result))
+});
+
+host_fn!(host_get_oauth_token(user_data: String; provider: String) -> String {
+    let result = HOST_API.get_oauth_token(&plugin_id(&user_data)?, &provider);
+    Ok(envelope(result))
+});
+
+host_fn!(host_read_file(user_data: String; path: String) -> String {
+    let result = HOST_API
+        .read_file(&plugin_id(&user_data)?, &path)
+        .map(|bytes| String::from_utf8_lossy(&bytes).into_owned());
+    Ok(envelope(result))
+});
+
+host_fn!(host_write_file(user_data: String; path: String, contents: String) -> String {
+    let result = HOST_API.write_file(&plugin_id(&user_data)?, &path, contents.as_bytes());
+    Ok(envelope(result))
+});
+
+/// Host functions for one plugin instance
+pub fn host_functions(plugin_id: &str) -> Vec<Function> {
+    let user_data = UserData::new(plugin_id.to_string());
+    vec![
+        Function::new("host_log", [PTR, PTR], [], user_data.clone(), host_log),
+        Function::new(
+            "host_http_request",
+            [PTR],
+            [PTR],
+            user_data.clone(),
+            host_http_request,
+        ),
+        Function::new(
+            "host_get_config",
+            [],
+            [PTR],
+            user_data.clone(),
+            host_get_config,
+        ),
+        Function::new(
+            "host_set_config",
+            [PTR],
+            [PTR],
+            user_data.clone(),
+            host_set_config,
+        ),
+        Function::new(
+            "host_show_notification",
+            [PTR, PTR],
+            [PTR],
+            user_data.clone(),
+            host_show_notification,
+        ),
+        Function::new(
+            "host_read_clipboard",
+            [],
+            [PTR],
+            user_data.clone(),
+            host_read_clipboard,
+        ),
+        Function::new(
+            "host_write_clipboard",
+            [PTR],
+            [PTR],
+            user_data.clone(),
+            host_write_clipboard,
+        ),
+        Function::new(
+            "host_get_oauth_token",
+            [PTR],
+            [PTR],
+            user_data.clone(),
+            host_get_oauth_token,
+        ),
+        Function::new(
+            "host_read_file",
+            [PTR],
+            [PTR],
+            user_data.clone(),
+            host_read_file,
+        ),
+        Function::new(
+            "host_write_file",
+            [PTR, PTR],
+            [PTR],
+            user_data,
+            host_write_file,
+        ),
+    ]
+}
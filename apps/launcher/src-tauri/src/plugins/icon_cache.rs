@@ -0,0 +1,174 @@
+//! Local copies of marketplace plugin icons.
+//!
+//! The webview used to fetch every `icon_url` on each render, which is slow
+//! and leaves blank icons offline. Icons are downloaded once, checked to
+//! really be images, shrunk to `ICON_SIZE` and kept under the local data
+//! dir, where the asset protocol can serve them. Copies older than
+//! `ICON_TTL` are fetched again, but a stale copy is still used until a
+//! fresh one arrives.
+
+use sha2::{Digest, Sha256};
+use std::collections::HashSet;
+use std::io::Cursor;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime};
+
+/// Icons are drawn at 40px; this leaves room for 2x displays
+const ICON_SIZE: u32 = 96;
+const ICON_TTL: Duration = Duration::from_secs(7 * 24 * 3600);
+const MAX_ICON_BYTES: usize = 2 * 1024 * 1024;
+
+pub struct IconCache {
+    dir: PathBuf,
+}
+
+impl IconCache {
+    pub fn new() -> Self {
+        // Inside $LOCALDATA, which the asset protocol is scoped to
+        let dir = dirs::data_local_dir()
+            .unwrap_or_else(|| PathBuf::from("."))
+            .join("launcher")
+            .join("marketplace-icons");
+        Self { dir }
+    }
+
+    fn stem(url: &str) -> String {
+        let hash = format!("{:x}", Sha256::digest(url.as_bytes()));
+        hash[..16].to_string()
+    }
+
+    /// The cached copy of `url`, fresh or not
+    pub fn cached(&self, url: &str) -> Option<PathBuf> {
+        let stem = Self::stem(url);
+        ["png", "svg"]
+            .iter()
+            .map(|ext| self.dir.join(format!("{}.{}", stem, ext)))
+            .find(|path| path.exists())
+    }
+
+    fn is_fresh(&self, url: &str) -> bool {
+        self.cached(url)
+            .and_then(|path| std::fs::metadata(path).ok())
+            .and_then(|meta| meta.modified().ok())
+            .and_then(|modified| SystemTime::now().duration_since(modified).ok())
+            .is_some_and(|age| age < ICON_TTL)
+    }
+
+    /// Download `url` unless a fresh copy exists. Returns the cached path.
+    pub async fn fetch(&self, client: &reqwest::Client, url: &str) -> Result<PathBuf, String> {
+        if self.is_fresh(url) {
+            if let Some(path) = self.cached(url) {
+                return Ok(path);
+            }
+        }
+        if !url.starts_with("https://") && !url.starts_with("http://") {
+            return Err(format!("Not a web URL: {}", url));
+        }
+
+        let mut response = client
+            .get(url)
+            .timeout(Duration::from_secs(10))
+            .send()
+            .await
+            .map_err(|e| format!("Failed to fetch icon: {}", e))?;
+        if !response.status().is_success() {
+            return Err(format!(
+                "Icon request returned status: {}",
+                response.status()
+            ));
+        }
+        if response
+            .content_length()
+            .is_some_and(|len| len > MAX_ICON_BYTES as u64)
+        {
+            return Err("Icon is too large".to_string());
+        }
+        let mut bytes = Vec::new();
+        while let Some(chunk) = response
+            .chunk()
+            .await
+            .map_err(|e| format!("Failed to fetch icon: {}", e))?
+        {
+            if bytes.len() + chunk.len() > MAX_ICON_BYTES {
+                return Err("Icon is too large".to_string());
+            }
+            bytes.extend_from_slice(&chunk);
+        }
+
+        let (contents, ext) = prepare_icon(&bytes)?;
+        std::fs::create_dir_all(&self.dir)
+            .map_err(|e| format!("Failed to create icon cache: {}", e))?;
+        let path = self.dir.join(format!("{}.{}", Self::stem(url), ext));
+        std::fs::write(&path, contents).map_err(|e| format!("Failed to cache icon: {}", e))?;
+        // An icon can change format between fetches
+        for old in ["png", "svg"].iter().filter(|old| **old != ext) {
+            let _ = std::fs::remove_file(path.with_extension(old));
+        }
+        Ok(path)
+    }
+
+    /// Delete icons of plugins that are no longer listed
+    pub fn prune(&self, urls: &[String]) {
+        let keep: HashSet<String> = urls.iter().map(|url| Self::stem(url)).collect();
+        let Ok(entries) = std::fs::read_dir(&self.dir) else {
+            return;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let stem = path
+                .file_stem()
+                .and_then(|s| s.to_str())
+                .unwrap_or_default();
+            if !keep.contains(stem) {
+                let _ = std::fs::remove_file(&path);
+            }
+        }
+    }
+}
+
+/// Check that a download is an image and shrink it to `ICON_SIZE`. Raster
+/// images are re-encoded as PNG; SVGs are kept as they are, since the
+/// webview draws them sharp at any size and never runs their scripts when
+/// they're loaded as images.
+fn prepare_icon(bytes: &[u8]) -> Result<(Vec<u8>, &'static str), String> {
+    let head = String::from_utf8_lossy(&bytes[..bytes.len().min(512)]).to_lowercase();
+    if head.trim_start().starts_with("<svg")
+        || (head.trim_start().starts_with("<?xml") && head.contains("<svg"))
+    {
+        return Ok((bytes.to_vec(), "svg"));
+    }
+
+    let image = image::load_from_memory(bytes).map_err(|e| format!("Not an image: {}", e))?;
+    let image = if image.width() > ICON_SIZE || image.height() > ICON_SIZE {
+        image.thumbnail(ICON_SIZE, ICON_SIZE)
+    } else {
+        image
+    };
+    let mut png = Vec::new();
+    image
+        .write_to(&mut Cursor::new(&mut png), image::ImageFormat::Png)
+        .map_err(|e| format!("Failed to encode icon: {}", e))?;
+    Ok((png, "png"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shrinks_images_and_rejects_other_files() {
+        let mut png = Vec::new();
+        image::DynamicImage::new_rgba8(512, 256)
+            .write_to(&mut Cursor::new(&mut png), image::ImageFormat::Png)
+            .unwrap();
+        let (icon, ext) = prepare_icon(&png).unwrap();
+        assert_eq!(ext, "png");
+        let icon = image::load_from_memory(&icon).unwrap();
+        assert_eq!((icon.width(), icon.height()), (96, 48));
+
+        let svg = br#"<?xml version="1.0"?><svg xmlns="http://www.w3.org/2000/svg"/>"#;
+        assert_eq!(prepare_icon(svg).unwrap().1, "svg");
+
+        assert!(prepare_icon(b"<html><body>Not found</body></html>").is_err());
+    }
+}
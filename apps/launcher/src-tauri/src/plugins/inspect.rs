@@ -0,0 +1,258 @@
+//! Dry run of a marketplace install: what a downloaded package would grant
+//! and put on disk, read without extracting it or pinning its signer, so
+//! users can review a plugin before installing it.
+
+use super::manifest::{
+    OAuthConfig, PluginCommand, PluginManifest, PluginPermission, WidgetDefinition,
+};
+use super::registry::{PluginRegistry, RegistryPlugin};
+use super::signing;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::io::{Cursor, Read};
+
+/// A permission and what it lets the plugin do
+#[derive(Debug, Clone, Serialize)]
+pub struct PermissionGrant {
+    /// As written in the manifest, e.g. `filesystem:read`
+    pub permission: String,
+    pub description: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AiToolInfo {
+    pub name: String,
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PluginInspection {
+    pub plugin: RegistryPlugin,
+    /// The package's manifest; None for a bare WASM download, whose
+    /// permissions come from the marketplace listing instead
+    pub manifest: Option<PluginManifest>,
+    pub permissions: Vec<PermissionGrant>,
+    /// Hosts the plugin may contact without asking; None for any host
+    pub allowed_hosts: Option<Vec<String>>,
+    pub commands: Vec<PluginCommand>,
+    pub widgets: Vec<WidgetDefinition>,
+    pub ai_tools: Vec<AiToolInfo>,
+    pub download_bytes: u64,
+    pub unpacked_bytes: u64,
+    pub files: usize,
+    /// Base64 public key the package is signed with
+    pub signer: Option<String>,
+    /// Why installing the package would be refused, if it would be
+    pub blocked: Option<String>,
+    /// Version already installed, if any
+    pub installed_version: Option<String>,
+}
+
+fn describe(
+    permission: &PluginPermission,
+    allowed_hosts: Option<&[String]>,
+    oauth: &HashMap<String, OAuthConfig>,
+) -> String {
+    match (permission, allowed_hosts) {
+        (PluginPermission::Network, None | Some([])) => {
+            "Connect to the internet, asking you before each new host".to_string()
+        }
+        (PluginPermission::Network, Some(allowed_hosts)) => format!(
+            "Connect to {} without asking, and to other hosts once you allow them",
+            allowed_hosts.join(", ")
+        ),
+        (PluginPermission::FilesystemRead, _) => "Read files on this computer".to_string(),
+        (PluginPermission::FilesystemWrite, _) => {
+            "Create, change and delete files on this computer".to_string()
+        }
+        (PluginPermission::Clipboard, _) => "Read and change the clipboard".to_string(),
+        (PluginPermission::Notifications, _) => "Show notifications".to_string(),
+        (PluginPermission::OAuth(provider), _) => {
+            match oauth
+                .get(provider)
+                .filter(|config| !config.scopes.is_empty())
+            {
+                Some(config) => format!(
+                    "Use your connected {} account ({})",
+                    provider,
+                    config.scopes.join(", ")
+                ),
+                None => format!("Use your connected {} account", provider),
+            }
+        }
+    }
+}
+
+fn grants(
+    permissions: &[PluginPermission],
+    allowed_hosts: Option<&[String]>,
+    oauth: &HashMap<String, OAuthConfig>,
+) -> Vec<PermissionGrant> {
+    permissions
+        .iter()
+        .map(|permission| PermissionGrant {
+            permission: permission.clone().into(),
+            description: describe(permission, allowed_hosts, oauth),
+        })
+        .collect()
+}
+
+/// A permission as the marketplace lists it, which may not be one this
+/// version of the launcher knows
+fn listed_grant(permission: &str) -> PermissionGrant {
+    let description = match PluginPermission::try_from(permission.to_string()) {
+        Ok(known) => describe(&known, Some(&[]), &HashMap::new()),
+        Err(e) => e,
+    };
+    PermissionGrant {
+        permission: permission.to_string(),
+        description,
+    }
+}
+
+/// What installing `plugin` from the package in `bytes` would do. Fails
+/// only when the package can't be read; a package that would be refused,
+/// say for its signature, is reported in `blocked`.
+pub fn inspect(
+    plugin: RegistryPlugin,
+    bytes: &[u8],
+    registry: &PluginRegistry,
+    require_signed: bool,
+) -> Result<PluginInspection, String> {
+    let download_bytes = bytes.len() as u64;
+
+    // A bare WASM module: no manifest, files or signature to look at
+    if bytes.starts_with(b"\x00asm") {
+        let permissions = plugin.permissions.iter().map(|p| listed_grant(p)).collect();
+        let blocked = registry.signer_verdict(&plugin, None, require_signed).err();
+        return Ok(PluginInspection {
+            plugin,
+            manifest: None,
+            permissions,
+            allowed_hosts: Some(Vec::new()),
+            commands: Vec::new(),
+            widgets: Vec::new(),
+            ai_tools: Vec::new(),
+            download_bytes,
+            unpacked_bytes: download_bytes,
+            files: 1,
+            signer: None,
+            blocked,
+            installed_version: None,
+        });
+    }
+
+    let mut archive = zip::ZipArchive::new(Cursor::new(bytes))
+        .map_err(|e| format!("Failed to read archive: {}", e))?;
+    let mut unpacked_bytes = 0;
+    let mut files = 0;
+    let mut manifest = None;
+    for i in 0..archive.len() {
+        let mut entry = archive
+            .by_index(i)
+            .map_err(|e| format!("Failed to read plugin package: {}", e))?;
+        if entry.is_dir() {
+            continue;
+        }
+        files += 1;
+        unpacked_bytes += entry.size();
+        if entry.name() == "manifest.json" {
+            let mut contents = String::new();
+            entry
+                .read_to_string(&mut contents)
+                .map_err(|e| format!("Failed to read manifest: {}", e))?;
+            let parsed: PluginManifest = serde_json::from_str(&contents)
+                .map_err(|e| format!("Failed to parse manifest: {}", e))?;
+            manifest = Some(parsed);
+        }
+    }
+    let manifest = manifest.ok_or("Plugin package has no manifest.json")?;
+
+    let (signer, blocked) = match signing::verify_archive(&mut archive) {
+        Ok(signer) => {
+            let blocked = registry
+                .signer_verdict(&plugin, signer.as_deref(), require_signed)
+                .err();
+            (signer, blocked)
+        }
+        Err(e) => (None, Some(e)),
+    };
+
+    let mut ai_tools: Vec<AiToolInfo> = manifest
+        .provides
+        .ai_tools
+        .iter()
+        .map(|name| AiToolInfo {
+            name: name.clone(),
+            description: manifest
+                .ai_tool_schemas
+                .get(name)
+                .map(|schema| schema.description.clone()),
+        })
+        .collect();
+    // Tools with a schema but left out of `provides` are callable too
+    let mut undeclared: Vec<_> = manifest
+        .ai_tool_schemas
+        .iter()
+        .filter(|(name, _)| !manifest.provides.ai_tools.contains(name))
+        .collect();
+    undeclared.sort_by_key(|(name, _)| name.as_str());
+    ai_tools.extend(undeclared.into_iter().map(|(name, schema)| AiToolInfo {
+        name: name.clone(),
+        description: Some(schema.description.clone()),
+    }));
+
+    Ok(PluginInspection {
+        permissions: grants(
+            &manifest.permissions,
+            manifest.allowed_hosts.as_deref(),
+            &manifest.oauth,
+        ),
+        allowed_hosts: manifest.allowed_hosts.clone(),
+        commands: manifest.provides.commands.clone(),
+        widgets: manifest.provides.widgets.clone(),
+        ai_tools,
+        manifest: Some(manifest),
+        plugin,
+        download_bytes,
+        unpacked_bytes,
+        files,
+        signer,
+        blocked,
+        installed_version: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn describes_permissions() {
+        let hosts = vec!["api.github.com".to_string()];
+        let oauth = HashMap::from([(
+            "github".to_string(),
+            OAuthConfig {
+                scopes: vec!["repo".to_string()],
+            },
+        )]);
+        let permissions = vec![
+            PluginPermission::Network,
+            PluginPermission::OAuth("github".to_string()),
+            PluginPermission::OAuth("slack".to_string()),
+        ];
+
+        let grants = grants(&permissions, &hosts, &oauth);
+        assert_eq!(grants[0].permission, "network");
+        assert!(grants[0]
+            .description
+            .contains("api.github.com without asking"));
+        assert_eq!(grants[1].permission, "oauth:github");
+        assert_eq!(
+            grants[1].description,
+            "Use your connected github account (repo)"
+        );
+        assert_eq!(grants[2].description, "Use your connected slack account");
+        assert!(describe(&PluginPermission::Network, &[], &oauth).contains("before each"));
+    }
+}
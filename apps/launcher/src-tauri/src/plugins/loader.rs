@@ -1,8 +1,9 @@
 use super::manifest::{LoadedPlugin, PluginManifest};
+use crate::cache::ManagedCache;
 use parking_lot::RwLock;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PluginInfo {
@@ -16,6 +17,16 @@ pub struct PluginInfo {
     pub enabled: bool,
 }
 
+/// Written into a plugin's folder by `launcher-plugin dev`; marks a local
+/// build rather than a store install
+pub const DEV_MARKER: &str = "dev.json";
+
+#[derive(Deserialize)]
+struct DevMarker {
+    /// The plugin project the build came from
+    source: PathBuf,
+}
+
 pub struct PluginLoader {
     plugins_dir: PathBuf,
     plugins: RwLock<HashMap<String, LoadedPlugin>>,
@@ -88,11 +99,17 @@ impl PluginLoader {
 
         let plugin_id = manifest.id.clone();
 
+        let dev_source = std::fs::read_to_string(plugin_dir.join(DEV_MARKER))
+            .ok()
+            .and_then(|content| serde_json::from_str::<DevMarker>(&content).ok())
+            .map(|marker| marker.source);
+
         let loaded = LoadedPlugin {
             manifest,
             path: plugin_dir.clone(),
             wasm_bytes,
             enabled: true,
+            dev_source,
         };
 
         let mut plugins = self.plugins.write();
@@ -101,9 +118,46 @@ impl PluginLoader {
         Ok(plugin_id)
     }
 
+    /// Read a plugin's manifest and WASM file from disk again, after a new
+    /// build was installed over it
+    pub fn reload_plugin(&self, plugin_dir: &Path) -> Result<LoadedPlugin, String> {
+        let was_enabled = self
+            .plugins
+            .read()
+            .values()
+            .find(|p| p.path == plugin_dir)
+            .map_or(true, |p| p.enabled);
+
+        let id = self.load_plugin(&plugin_dir.to_path_buf())?;
+        if !was_enabled {
+            self.disable_plugin(&id)?;
+        }
+        self.get_plugin(&id)
+            .ok_or_else(|| format!("Plugin not found: {}", id))
+    }
+
     pub fn get_plugin(&self, id: &str) -> Option<LoadedPlugin> {
-        let plugins = self.plugins.read();
-        plugins.get(id).cloned()
+        let plugin = self.plugins.read().get(id).cloned()?;
+        if !plugin.wasm_bytes.is_empty() {
+            return Some(plugin);
+        }
+
+        // Evicted while disabled; read it back once and keep it
+        let wasm_bytes = std::fs::read(plugin.path.join(&plugin.manifest.entry)).ok()?;
+        let mut plugins = self.plugins.write();
+        let plugin = plugins.get_mut(id)?;
+        plugin.wasm_bytes = wasm_bytes;
+        Some(plugin.clone())
+    }
+
+    /// Manifest of an enabled plugin, without copying its WASM module like
+    /// [`Self::get_plugin`] does
+    pub fn enabled_manifest(&self, id: &str) -> Option<PluginManifest> {
+        self.plugins
+            .read()
+            .get(id)
+            .filter(|p| p.enabled)
+            .map(|p| p.manifest.clone())
     }
 
     pub fn list_plugins(&self) -> Vec<PluginInfo> {
@@ -128,6 +182,28 @@ impl PluginLoader {
             .collect()
     }
 
+    /// Display name of an installed plugin
+    pub fn plugin_name(&self, id: &str) -> Option<String> {
+        self.plugins.read().get(id).map(|p| p.manifest.name.clone())
+    }
+
+    pub fn plugin_version(&self, id: &str) -> Option<String> {
+        self.plugins
+            .read()
+            .get(id)
+            .map(|p| p.manifest.version.clone())
+    }
+
+    /// Manifests and project folders of plugins installed by
+    /// `launcher-plugin dev`
+    pub fn dev_plugins(&self) -> Vec<(PluginManifest, PathBuf)> {
+        self.plugins
+            .read()
+            .values()
+            .filter_map(|p| Some((p.manifest.clone(), p.dev_source.clone()?)))
+            .collect()
+    }
+
     pub fn enable_plugin(&self, id: &str) -> Result<(), String> {
         let mut plugins = self.plugins.write();
         if let Some(plugin) = plugins.get_mut(id) {
@@ -163,3 +239,41 @@ impl PluginLoader {
         &self.plugins_dir
     }
 }
+
+impl ManagedCache for PluginLoader {
+    fn cache_name(&self) -> &str {
+        "plugin_modules"
+    }
+
+    fn cost(&self) -> usize {
+        self.plugins.read().values().map(|p| p.wasm_bytes.len()).sum()
+    }
+
+    fn evict(&self, target_bytes: usize) -> usize {
+        let mut plugins = self.plugins.write();
+        let mut total: usize = plugins.values().map(|p| p.wasm_bytes.len()).sum();
+        let mut freed = 0;
+
+        // Only disabled plugins: enabled ones are searched on every keystroke,
+        // and reading them back from disk each time costs more than it saves
+        let ids: Vec<String> = plugins
+            .iter()
+            .filter(|(_, p)| !p.enabled)
+            .map(|(id, _)| id.clone())
+            .collect();
+
+        for id in ids {
+            if total <= target_bytes {
+                break;
+            }
+            if let Some(plugin) = plugins.get_mut(&id) {
+                let size = plugin.wasm_bytes.len();
+                plugin.wasm_bytes = Vec::new();
+                total -= size;
+                freed += size;
+            }
+        }
+
+        freed
+    }
+}
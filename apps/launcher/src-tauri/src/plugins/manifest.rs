@@ -10,6 +10,12 @@ pub struct PluginManifest {
     pub author: Option<String>,
     pub description: Option<String>,
     pub permissions: Vec<PluginPermission>,
+    /// Hosts the `network` permission covers without asking, e.g.
+    /// `api.github.com` or `*.example.com`. Other hosts need the user's
+    /// approval the first time the plugin contacts them. Manifests from
+    /// before host lists leave it out, and may contact any host.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub allowed_hosts: Option<Vec<String>>,
     pub entry: String,
     pub provides: PluginProvides,
     #[serde(default)]
@@ -17,6 +23,24 @@ pub struct PluginManifest {
     /// AI tool schemas - maps tool name to schema definition
     #[serde(default)]
     pub ai_tool_schemas: HashMap<String, AIToolSchema>,
+    /// Runtime limits this plugin needs beyond the defaults in settings
+    #[serde(default)]
+    pub limits: PluginLimitOverrides,
+    /// JSON Schema of an object whose properties are the settings users can
+    /// edit; their values are kept in the plugin's config
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub settings_schema: Option<serde_json::Value>,
+}
+
+/// Per-plugin overrides of the runtime limits, capped by the runtime
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PluginLimitOverrides {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memory_mb: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timeout_ms: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fuel: Option<u64>,
 }
 
 /// Command trigger that plugins can register (e.g., "git:", "docker:", "jira:")
@@ -47,6 +71,10 @@ pub struct PluginProvides {
     /// Command triggers that this plugin provides
     #[serde(default)]
     pub commands: Vec<PluginCommand>,
+    /// Search only when no other provider found anything, to offer things
+    /// like "create a note with this text"
+    #[serde(default)]
+    pub fallback: bool,
 }
 
 /// Widget definition for dashboard widgets provided by plugins
@@ -105,20 +133,52 @@ pub struct AIToolProperty {
     pub default: Option<serde_json::Value>,
 }
 
+/// A manifest permission, written as a string such as `network` or
+/// `oauth:github`
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
-#[serde(rename_all = "snake_case")]
+#[serde(try_from = "String", into = "String")]
 pub enum PluginPermission {
     Network,
-    #[serde(rename = "filesystem:read")]
     FilesystemRead,
-    #[serde(rename = "filesystem:write")]
     FilesystemWrite,
     Clipboard,
     Notifications,
-    #[serde(rename = "oauth")]
+    /// Use the token of an account the user connected, by provider id
     OAuth(String),
 }
 
+impl TryFrom<String> for PluginPermission {
+    type Error = String;
+
+    fn try_from(permission: String) -> Result<Self, String> {
+        match permission.as_str() {
+            "network" => Ok(Self::Network),
+            "filesystem:read" => Ok(Self::FilesystemRead),
+            "filesystem:write" => Ok(Self::FilesystemWrite),
+            "clipboard" => Ok(Self::Clipboard),
+            "notifications" => Ok(Self::Notifications),
+            other => other
+                .strip_prefix("oauth:")
+                .filter(|provider| !provider.is_empty())
+                .map(|provider| Self::OAuth(provider.to_lowercase()))
+                .ok_or_else(|| format!("Unknown permission: {}", other)),
+        }
+    }
+}
+
+impl From<PluginPermission> for String {
+    fn from(permission: PluginPermission) -> Self {
+        match permission {
+            PluginPermission::Network => "network".to_string(),
+            PluginPermission::FilesystemRead => "filesystem:read".to_string(),
+            PluginPermission::FilesystemWrite => "filesystem:write".to_string(),
+            PluginPermission::Clipboard => "clipboard".to_string(),
+            PluginPermission::Notifications => "notifications".to_string(),
+            PluginPermission::OAuth(provider) => format!("oauth:{}", provider),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct OAuthConfig {
     pub scopes: Vec<String>,
@@ -130,6 +190,8 @@ pub struct LoadedPlugin {
     pub path: PathBuf,
     pub wasm_bytes: Vec<u8>,
     pub enabled: bool,
+    /// Project folder, for builds installed by `launcher-plugin dev`
+    pub dev_source: Option<PathBuf>,
 }
 
 impl PluginManifest {
@@ -153,6 +215,7 @@ impl Default for PluginProvides {
             ai_tools: Vec::new(),
             widgets: Vec::new(),
             commands: Vec::new(),
+            fallback: false,
         }
     }
 }
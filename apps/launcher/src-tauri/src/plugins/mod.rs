@@ -1,8 +1,17 @@
+pub mod archive;
+pub mod catalog;
+pub mod config_schema;
+pub mod health;
 pub mod host_api;
+pub mod host_fns;
+pub mod icon_cache;
+pub mod inspect;
 pub mod loader;
 pub mod manifest;
 pub mod registry;
 pub mod runtime;
+pub mod sanitize;
+pub mod signing;
 
 pub use loader::{PluginInfo, PluginLoader};
 pub use registry::{MarketplaceResponse, PluginRegistry, RegistryPlugin};
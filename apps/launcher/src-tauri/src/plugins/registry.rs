@@ -1,10 +1,25 @@
+use super::catalog::{self, CatalogStore};
+use super::icon_cache::IconCache;
+use super::manifest::PluginManifest;
+use super::signing::{self, SignerStore};
 use crate::config::CONFIG;
 use parking_lot::RwLock;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::io::{Read, Seek};
+use std::path::{Path, PathBuf};
 use std::time::{Duration, SystemTime};
 
+/// How often the whole catalog is downloaded even when deltas would do
+const FULL_SYNC_INTERVAL: Duration = Duration::from_secs(24 * 3600);
+
+/// How old the catalog may get before a background refresh
+const STALE_AFTER: Duration = Duration::from_secs(3600);
+
+/// How often the background refresh checks for a stale catalog; also how
+/// soon it retries while offline
+pub const REFRESH_CHECK_INTERVAL: Duration = Duration::from_secs(10 * 60);
+
 /// A plugin entry in the marketplace registry
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RegistryPlugin {
@@ -14,6 +29,9 @@ pub struct RegistryPlugin {
     pub author: Option<String>,
     pub description: Option<String>,
     pub icon_url: Option<String>,
+    /// Local copy of the icon, served through the asset protocol
+    #[serde(default)]
+    pub icon_path: Option<String>,
     pub homepage: Option<String>,
     pub repository: Option<String>,
     pub download_url: String,
@@ -24,6 +42,32 @@ pub struct RegistryPlugin {
     pub rating: Option<f32>,
     pub verified: bool,
     pub featured: bool,
+    #[serde(default)]
+    pub source: PluginSource,
+    /// Build status, for `LocalDev` entries
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dev: Option<DevStatus>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PluginSource {
+    #[default]
+    Marketplace,
+    /// Installed by `launcher-plugin dev` straight from a local build. Never
+    /// signed, never installed or updated from the store, and only listed
+    /// while plugin dev mode is on.
+    LocalDev,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DevStatus {
+    /// The plugin project the build came from
+    pub source_dir: String,
+    /// Unix seconds a new build was last picked up
+    pub reloaded_at: Option<u64>,
+    /// Why the last build couldn't be loaded
+    pub error: Option<String>,
 }
 
 /// Response from the marketplace API list endpoint
@@ -90,12 +134,40 @@ struct ServerApiResponse {
     has_more: bool,
 }
 
+/// Response from the changes endpoint
+#[derive(Debug, Clone, Deserialize)]
+struct ServerChangesResponse {
+    plugins: Vec<ServerPluginResponse>,
+    removed: Vec<String>,
+    #[serde(rename = "serverTime")]
+    server_time: String,
+    #[serde(rename = "hasMore", default)]
+    has_more: bool,
+}
+
+/// The `since` to send with the next delta request, from a response's Date
+/// header. A minute early, so changes made while the catalog was being
+/// listed are fetched again rather than missed.
+fn server_time(date_header: Option<&str>) -> String {
+    let now = date_header
+        .and_then(|date| chrono::DateTime::parse_from_rfc2822(date).ok())
+        .map(|date| date.with_timezone(&chrono::Utc))
+        .unwrap_or_else(chrono::Utc::now);
+    (now - chrono::Duration::minutes(1)).to_rfc3339_opts(chrono::SecondsFormat::Millis, true)
+}
+
 /// Plugin registry for marketplace
 pub struct PluginRegistry {
     cache_dir: PathBuf,
+    catalog: CatalogStore,
     plugins: RwLock<HashMap<String, RegistryPlugin>>,
+    /// Local dev builds; kept out of the catalog and listed over store
+    /// entries with the same id
+    dev_plugins: RwLock<HashMap<String, RegistryPlugin>>,
     last_updated: RwLock<Option<SystemTime>>,
     is_offline: RwLock<bool>,
+    signers: SignerStore,
+    icons: IconCache,
 }
 
 impl PluginRegistry {
@@ -106,105 +178,259 @@ impl PluginRegistry {
             .join("registry");
 
         Self {
+            catalog: CatalogStore::open(&cache_dir.join("catalog.db")),
             cache_dir,
             plugins: RwLock::new(HashMap::new()),
+            dev_plugins: RwLock::new(HashMap::new()),
             last_updated: RwLock::new(None),
             is_offline: RwLock::new(false),
+            signers: SignerStore::new(),
+            icons: IconCache::new(),
         }
     }
 
-    /// Load registry from local cache
-    pub fn load_cache(&self) -> Result<(), String> {
-        let cache_file = self.cache_dir.join("plugins.json");
-        if !cache_file.exists() {
-            return Ok(());
-        }
+    /// Check a downloaded package before it's extracted: a signature must
+    /// match the contents and the key pinned for the plugin. Unsigned
+    /// packages pass unless `require_signed` is set and the marketplace
+    /// hasn't verified the plugin. Returns the signer, to `pin_signer` once
+    /// the install went through.
+    pub fn verify_package<R: Read + Seek>(
+        &self,
+        plugin: &RegistryPlugin,
+        archive: &mut zip::ZipArchive<R>,
+        require_signed: bool,
+    ) -> Result<Option<String>, String> {
+        let signer = signing::verify_archive(archive)?;
+        self.signer_verdict(plugin, signer.as_deref(), require_signed)?;
+        Ok(signer)
+    }
+
+    /// Check a bare WASM download, which can't carry a signature
+    pub fn verify_unsigned(
+        &self,
+        plugin: &RegistryPlugin,
+        require_signed: bool,
+    ) -> Result<(), String> {
+        self.signer_verdict(plugin, None, require_signed)
+    }
+
+    /// Trust the key an installed package was signed with from now on, if
+    /// it's the plugin's first signed install
+    pub fn pin_signer(&self, plugin_id: &str, signer: Option<&str>) {
+        self.signers.pin(plugin_id, signer);
+    }
 
-        let contents = std::fs::read_to_string(&cache_file)
-            .map_err(|e| format!("Failed to read registry cache: {}", e))?;
+    /// Whether a package signed by `signer` would pass `verify_package`
+    pub fn signer_verdict(
+        &self,
+        plugin: &RegistryPlugin,
+        signer: Option<&str>,
+        require_signed: bool,
+    ) -> Result<(), String> {
+        self.signers
+            .verdict(&plugin.id, signer, require_signed && !plugin.verified)
+    }
 
-        let plugins: Vec<RegistryPlugin> = serde_json::from_str(&contents)
-            .map_err(|e| format!("Failed to parse registry cache: {}", e))?;
+    /// Accept a new signing key for a plugin on its next install
+    pub fn forget_signer(&self, plugin_id: &str) {
+        self.signers.forget(plugin_id);
+    }
 
+    /// Load the catalog saved by earlier syncs, moving over the JSON file
+    /// older versions kept it in
+    pub fn load_cache(&self) -> Result<(), String> {
+        let legacy_file = self.cache_dir.join("plugins.json");
+        if legacy_file.exists() {
+            let plugins: Vec<RegistryPlugin> = std::fs::read_to_string(&legacy_file)
+                .ok()
+                .and_then(|contents| serde_json::from_str(&contents).ok())
+                .unwrap_or_default();
+            self.catalog.apply(&plugins, &[], true)?;
+            let _ = std::fs::remove_file(&legacy_file);
+        }
+
+        let plugins = self.catalog.load()?;
         let mut registry = self.plugins.write();
-        for plugin in plugins {
+        for mut plugin in plugins {
+            // The icon cache may have been cleared since
+            plugin.icon_path = self.cached_icon(plugin.icon_url.as_deref());
             registry.insert(plugin.id.clone(), plugin);
         }
+        drop(registry);
 
-        if let Ok(metadata) = std::fs::metadata(&cache_file) {
-            if let Ok(modified) = metadata.modified() {
-                *self.last_updated.write() = Some(modified);
-            }
-        }
+        *self.last_updated.write() = self
+            .catalog
+            .get_state(catalog::CHECKED_AT)
+            .and_then(|secs| secs.parse().ok())
+            .map(|secs| SystemTime::UNIX_EPOCH + Duration::from_secs(secs));
+        *self.is_offline.write() = self.catalog.get_state(catalog::OFFLINE).is_some();
 
         Ok(())
     }
 
-    /// Save registry to local cache
-    pub fn save_cache(&self) -> Result<(), String> {
-        std::fs::create_dir_all(&self.cache_dir)
-            .map_err(|e| format!("Failed to create cache directory: {}", e))?;
-
-        let plugins: Vec<RegistryPlugin> = self.plugins.read().values().cloned().collect();
-        let contents = serde_json::to_string_pretty(&plugins)
-            .map_err(|e| format!("Failed to serialize registry: {}", e))?;
-
-        let cache_file = self.cache_dir.join("plugins.json");
-        std::fs::write(&cache_file, contents)
-            .map_err(|e| format!("Failed to write registry cache: {}", e))?;
-
-        *self.last_updated.write() = Some(SystemTime::now());
-
-        Ok(())
+    /// Record whether the server could be reached
+    fn mark_checked(&self, reached: bool) {
+        *self.is_offline.write() = !reached;
+        self.catalog
+            .set_state(catalog::OFFLINE, (!reached).then_some("1"));
+        if reached {
+            let now = SystemTime::now();
+            *self.last_updated.write() = Some(now);
+            let secs = now.duration_since(SystemTime::UNIX_EPOCH).unwrap_or_default();
+            self.catalog
+                .set_state(catalog::CHECKED_AT, Some(&secs.as_secs().to_string()));
+        }
     }
 
-    /// Fetch plugins from the server API
+    /// Bring the catalog up to date: just the changes since the last sync
+    /// when possible, otherwise the whole catalog (which the server skips
+    /// sending if it hasn't changed). Full syncs still happen daily, since
+    /// only they notice plugins deleted outright.
     pub async fn fetch_from_server(&self) -> Result<(), String> {
-        let api_url = CONFIG.plugins_api_url();
-        // Fetch all plugins with a high limit
-        let url = format!("{}?limit=100", api_url);
-
-        eprintln!("Fetching plugins from: {}", url);
-
         let client = reqwest::Client::new();
+        let since_full_sync = self
+            .catalog
+            .get_state(catalog::FULL_SYNC_AT)
+            .and_then(|secs| secs.parse::<u64>().ok())
+            .map(|secs| SystemTime::UNIX_EPOCH + Duration::from_secs(secs))
+            .and_then(|at| SystemTime::now().duration_since(at).ok());
+        let full_sync_due = match since_full_sync {
+            Some(age) => age > FULL_SYNC_INTERVAL,
+            None => true,
+        };
+
+        let result = match self.catalog.get_state(catalog::CURSOR) {
+            Some(cursor) if !full_sync_due => match self.fetch_changes(&client, &cursor).await {
+                Ok(true) => Ok(()),
+                Ok(false) => self.fetch_full(&client).await,
+                Err(e) => Err(e),
+            },
+            _ => self.fetch_full(&client).await,
+        };
+        self.mark_checked(result.is_ok());
+        result
+    }
+
+    /// Apply the changes since `cursor`. Returns false when they can't be
+    /// had as a delta and a full sync is needed instead.
+    async fn fetch_changes(&self, client: &reqwest::Client, cursor: &str) -> Result<bool, String> {
+        let url = format!(
+            "{}/changes?since={}",
+            CONFIG.plugins_api_url(),
+            urlencoding::encode(cursor)
+        );
         let response = client
             .get(&url)
             .timeout(Duration::from_secs(10))
             .send()
             .await
-            .map_err(|e| {
-                *self.is_offline.write() = true;
-                format!("Failed to fetch plugins: {}", e)
-            })?;
+            .map_err(|e| format!("Failed to fetch plugins: {}", e))?;
 
+        // Servers without the changes endpoint
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(false);
+        }
         if !response.status().is_success() {
-            *self.is_offline.write() = true;
             return Err(format!("Plugin API returned status: {}", response.status()));
         }
-
-        let api_response: ServerApiResponse = response
+        let changes: ServerChangesResponse = response
             .json()
             .await
-            .map_err(|e| format!("Failed to parse plugin response: {}", e))?;
+            .map_err(|e| format!("Failed to parse plugin changes: {}", e))?;
+        if changes.has_more {
+            return Ok(false);
+        }
 
-        // Convert server plugins to registry format
-        let mut registry = self.plugins.write();
-        registry.clear();
+        let upserts: Vec<RegistryPlugin> = changes
+            .plugins
+            .into_iter()
+            .map(|p| self.convert_server_plugin(p))
+            .collect();
+        self.catalog.apply(&upserts, &changes.removed, false)?;
 
-        for server_plugin in api_response.plugins {
-            let plugin = self.convert_server_plugin(server_plugin);
+        let mut registry = self.plugins.write();
+        for id in &changes.removed {
+            registry.remove(id);
+        }
+        for plugin in upserts {
             registry.insert(plugin.id.clone(), plugin);
         }
         drop(registry);
 
-        // Mark as online and update timestamp
-        *self.is_offline.write() = false;
-        *self.last_updated.write() = Some(SystemTime::now());
+        self.catalog
+            .set_state(catalog::CURSOR, Some(&changes.server_time));
+        Ok(true)
+    }
 
-        // Save to cache
-        self.save_cache()?;
+    /// Download the whole catalog, unless it matches the one we have
+    async fn fetch_full(&self, client: &reqwest::Client) -> Result<(), String> {
+        // Fetch all plugins with a high limit
+        let url = format!("{}?limit=100", CONFIG.plugins_api_url());
+        eprintln!("Fetching plugins from: {}", url);
 
-        eprintln!("Successfully fetched {} plugins", api_response.total);
+        let mut request = client.get(&url).timeout(Duration::from_secs(10));
+        // Only worth sending if we still have the catalog they describe
+        if !self.plugins.read().is_empty() {
+            if let Some(etag) = self.catalog.get_state(catalog::ETAG) {
+                request = request.header(reqwest::header::IF_NONE_MATCH, etag);
+            }
+            if let Some(modified) = self.catalog.get_state(catalog::LAST_MODIFIED) {
+                request = request.header(reqwest::header::IF_MODIFIED_SINCE, modified);
+            }
+        }
+        let response = request
+            .send()
+            .await
+            .map_err(|e| format!("Failed to fetch plugins: {}", e))?;
+
+        let header = |name| {
+            response
+                .headers()
+                .get(name)
+                .and_then(|v| v.to_str().ok())
+                .map(str::to_string)
+        };
+        let etag = header(reqwest::header::ETAG);
+        let last_modified = header(reqwest::header::LAST_MODIFIED);
+        let cursor = server_time(header(reqwest::header::DATE).as_deref());
+
+        if response.status() == reqwest::StatusCode::NOT_MODIFIED {
+            eprintln!("Plugin catalog unchanged");
+        } else {
+            if !response.status().is_success() {
+                return Err(format!("Plugin API returned status: {}", response.status()));
+            }
+            let api_response: ServerApiResponse = response
+                .json()
+                .await
+                .map_err(|e| format!("Failed to parse plugin response: {}", e))?;
+
+            let plugins: Vec<RegistryPlugin> = api_response
+                .plugins
+                .into_iter()
+                .map(|p| self.convert_server_plugin(p))
+                .collect();
+            self.catalog.apply(&plugins, &[], true)?;
+
+            let mut registry = self.plugins.write();
+            registry.clear();
+            for plugin in plugins {
+                registry.insert(plugin.id.clone(), plugin);
+            }
+            drop(registry);
+
+            self.catalog.set_state(catalog::ETAG, etag.as_deref());
+            self.catalog
+                .set_state(catalog::LAST_MODIFIED, last_modified.as_deref());
+            eprintln!("Successfully fetched {} plugins", api_response.total);
+        }
+
+        self.catalog.set_state(catalog::CURSOR, Some(&cursor));
+        let now = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap_or_default();
+        self.catalog
+            .set_state(catalog::FULL_SYNC_AT, Some(&now.as_secs().to_string()));
         Ok(())
     }
 
@@ -238,11 +464,9 @@ impl PluginRegistry {
         let plugin = self.convert_server_plugin(server_plugin);
 
         // Add to registry cache
+        self.catalog.apply(std::slice::from_ref(&plugin), &[], false)?;
         self.plugins.write().insert(plugin.id.clone(), plugin.clone());
 
-        // Save to cache
-        self.save_cache()?;
-
         Ok(plugin)
     }
 
@@ -250,6 +474,7 @@ impl PluginRegistry {
     fn convert_server_plugin(&self, server: ServerPluginResponse) -> RegistryPlugin {
         // Build download URL from API
         let download_url = format!("{}/{}/download", CONFIG.plugins_api_url(), server.id);
+        let icon_path = self.cached_icon(server.icon_url.as_deref());
 
         RegistryPlugin {
             id: server.id,
@@ -260,6 +485,7 @@ impl PluginRegistry {
             author: server.author_name,
             description: server.description,
             icon_url: server.icon_url,
+            icon_path,
             homepage: server.homepage,
             repository: server.repository,
             download_url,
@@ -270,12 +496,57 @@ impl PluginRegistry {
             rating: server.rating,
             verified: server.verified.unwrap_or(false),
             featured: server.featured.unwrap_or(false),
+            source: PluginSource::Marketplace,
+            dev: None,
         }
     }
 
+    fn cached_icon(&self, icon_url: Option<&str>) -> Option<String> {
+        icon_url
+            .and_then(|url| self.icons.cached(url))
+            .map(|path| path.to_string_lossy().into_owned())
+    }
+
+    /// Download icons that aren't cached yet or have gone stale, and drop
+    /// those of plugins that left the marketplace. Returns how many plugins
+    /// got a new icon path.
+    pub async fn cache_icons(&self) -> usize {
+        let urls: Vec<(String, String)> = self
+            .plugins
+            .read()
+            .values()
+            .filter_map(|p| Some((p.id.clone(), p.icon_url.clone()?)))
+            .collect();
+
+        let client = reqwest::Client::new();
+        let mut updated = Vec::new();
+        for (id, url) in &urls {
+            let path = match self.icons.fetch(&client, url).await {
+                Ok(path) => path.to_string_lossy().into_owned(),
+                Err(e) => {
+                    eprintln!("Failed to cache icon for {}: {}", id, e);
+                    continue;
+                }
+            };
+            if let Some(plugin) = self.plugins.write().get_mut(id) {
+                if plugin.icon_path.as_deref() != Some(path.as_str()) {
+                    plugin.icon_path = Some(path);
+                    updated.push(plugin.clone());
+                }
+            }
+        }
+        self.icons
+            .prune(&urls.into_iter().map(|(_, url)| url).collect::<Vec<_>>());
+
+        if let Err(e) = self.catalog.apply(&updated, &[], false) {
+            eprintln!("Failed to save icon paths: {}", e);
+        }
+        updated.len()
+    }
+
     /// List all plugins in registry with status
     pub fn list_plugins_with_status(&self) -> MarketplaceResponse {
-        let plugins: Vec<RegistryPlugin> = self.plugins.read().values().cloned().collect();
+        let plugins = self.all_plugins();
         let total = plugins.len() as u64;
         let is_offline = *self.is_offline.read();
         let last_updated = self.last_updated.read().map(|t| {
@@ -295,15 +566,26 @@ impl PluginRegistry {
 
     /// List all plugins in registry (legacy method for compatibility)
     pub fn list_plugins(&self) -> Vec<RegistryPlugin> {
-        self.plugins.read().values().cloned().collect()
+        self.all_plugins()
     }
 
-    /// Search plugins by query
-    pub fn search(&self, query: &str) -> Vec<RegistryPlugin> {
-        let query_lower = query.to_lowercase();
+    /// Store plugins with local dev builds in place of those they shadow
+    fn all_plugins(&self) -> Vec<RegistryPlugin> {
+        let dev_plugins = self.dev_plugins.read();
         self.plugins
             .read()
             .values()
+            .filter(|p| !dev_plugins.contains_key(&p.id))
+            .chain(dev_plugins.values())
+            .cloned()
+            .collect()
+    }
+
+    /// Search plugins by query
+    pub fn search(&self, query: &str) -> Vec<RegistryPlugin> {
+        let query_lower = query.to_lowercase();
+        self.all_plugins()
+            .into_iter()
             .filter(|p| {
                 p.name.to_lowercase().contains(&query_lower)
                     || p.description
@@ -314,36 +596,35 @@ impl PluginRegistry {
                         .iter()
                         .any(|c| c.to_lowercase().contains(&query_lower))
             })
-            .cloned()
             .collect()
     }
 
     /// Get a specific plugin by ID
     pub fn get_plugin(&self, id: &str) -> Option<RegistryPlugin> {
+        if let Some(plugin) = self.dev_plugins.read().get(id) {
+            return Some(plugin.clone());
+        }
         self.plugins.read().get(id).cloned()
     }
 
     /// Filter plugins by category
     pub fn by_category(&self, category: &str) -> Vec<RegistryPlugin> {
         let category_lower = category.to_lowercase();
-        self.plugins
-            .read()
-            .values()
+        self.all_plugins()
+            .into_iter()
             .filter(|p| {
                 p.categories
                     .iter()
                     .any(|c| c.to_lowercase() == category_lower)
             })
-            .cloned()
             .collect()
     }
 
     /// Get all unique categories
     pub fn categories(&self) -> Vec<String> {
         let mut categories: Vec<String> = self
-            .plugins
-            .read()
-            .values()
+            .all_plugins()
+            .iter()
             .flat_map(|p| p.categories.clone())
             .collect();
         categories.sort();
@@ -356,6 +637,67 @@ impl PluginRegistry {
         self.plugins.write().insert(plugin.id.clone(), plugin);
     }
 
+    /// Marketplace entry for a plugin installed by `launcher-plugin dev`
+    pub fn dev_entry(manifest: &PluginManifest, source_dir: &Path) -> RegistryPlugin {
+        RegistryPlugin {
+            id: manifest.id.clone(),
+            name: manifest.name.clone(),
+            version: manifest.version.clone(),
+            author: manifest.author.clone(),
+            description: manifest.description.clone(),
+            icon_url: None,
+            icon_path: None,
+            homepage: None,
+            repository: None,
+            download_url: String::new(),
+            checksum: None,
+            permissions: manifest
+                .permissions
+                .iter()
+                .cloned()
+                .map(String::from)
+                .collect(),
+            categories: Vec::new(),
+            downloads: 0,
+            rating: None,
+            verified: false,
+            featured: false,
+            source: PluginSource::LocalDev,
+            dev: Some(DevStatus {
+                source_dir: source_dir.to_string_lossy().into_owned(),
+                ..Default::default()
+            }),
+        }
+    }
+
+    /// Replace the listed dev builds, keeping the reload status of those
+    /// still installed. An empty list hides them all.
+    pub fn set_dev_plugins(&self, plugins: Vec<RegistryPlugin>) {
+        let mut dev_plugins = self.dev_plugins.write();
+        let previous = std::mem::take(&mut *dev_plugins);
+        for mut plugin in plugins {
+            let old_status = previous.get(&plugin.id).and_then(|p| p.dev.clone());
+            if let (Some(status), Some(old_status)) = (plugin.dev.as_mut(), old_status) {
+                status.reloaded_at = old_status.reloaded_at;
+                status.error = old_status.error;
+            }
+            dev_plugins.insert(plugin.id.clone(), plugin);
+        }
+    }
+
+    /// Note that a dev build was reloaded, or why it failed to load
+    pub fn record_dev_reload(&self, plugin_id: &str, error: Option<String>) {
+        let mut dev_plugins = self.dev_plugins.write();
+        let Some(status) = dev_plugins.get_mut(plugin_id).and_then(|p| p.dev.as_mut()) else {
+            return;
+        };
+        status.reloaded_at = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .ok();
+        status.error = error;
+    }
+
     /// Check if the registry is offline
     pub fn is_offline(&self) -> bool {
         *self.is_offline.read()
@@ -366,12 +708,12 @@ impl PluginRegistry {
         *self.last_updated.read()
     }
 
-    /// Check if cache is stale (older than 1 hour)
+    /// Check if the server hasn't been reached for `STALE_AFTER`
     pub fn is_cache_stale(&self) -> bool {
         match *self.last_updated.read() {
             Some(time) => {
                 let elapsed = SystemTime::now().duration_since(time).unwrap_or_default();
-                elapsed > Duration::from_secs(3600) // 1 hour
+                elapsed > STALE_AFTER
             }
             None => true,
         }
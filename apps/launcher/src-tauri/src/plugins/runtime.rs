@@ -1,13 +1,38 @@
+use super::health::{HealthTracker, PluginHealthReport};
 use super::host_api::{PluginHostApi, PluginSearchResult, HOST_API};
-use super::manifest::{LoadedPlugin, PluginPermission};
-use extism::{Manifest, Plugin, Wasm};
-use parking_lot::RwLock;
+use super::host_fns::host_functions;
+use super::manifest::{LoadedPlugin, PluginLimitOverrides, PluginPermission};
+use crate::settings::{PluginLimits, SettingsStore};
+use extism::{Manifest, Plugin, PluginBuilder, Wasm};
+use notify::{RecommendedWatcher, RecursiveMode};
+use notify_debouncer_mini::{new_debouncer, DebounceEventResult, Debouncer};
+use parking_lot::{Mutex, RwLock};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Builds write the WASM file in several chunks; wait for them to settle
+const RELOAD_DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// The most a manifest can raise each limit to, unless the user's own
+/// default is higher
+const MAX_MEMORY_MB: u64 = 1024;
+const MAX_TIMEOUT_MS: u64 = 30_000;
+const MAX_FUEL: u64 = 50_000_000_000;
+
+type QuarantineListener = Arc<dyn Fn(PluginHealthReport) + Send + Sync>;
 
 /// Plugin runtime using Extism for multi-language WASM support
 pub struct PluginRuntime {
     instances: RwLock<HashMap<String, PluginInstance>>,
+    /// Watches installed plugins in dev mode
+    dev_watcher: Mutex<Option<Debouncer<RecommendedWatcher>>>,
+    settings: Arc<SettingsStore>,
+    health: HealthTracker,
+    /// Told when a plugin is unloaded for failing repeatedly
+    quarantine_listener: RwLock<Option<QuarantineListener>>,
 }
 
 struct PluginInstance {
@@ -27,13 +52,84 @@ pub struct SearchOutput {
     pub results: Vec<PluginSearchResult>,
 }
 
+/// Payload of the `plugin-reloaded` event
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PluginReloaded {
+    pub plugin_id: String,
+    /// Why the new build couldn't be loaded; the plugin stays unloaded
+    pub error: Option<String>,
+}
+
+/// Plugin directories under `plugins_dir` whose manifest or WASM file is
+/// among `paths`
+/// One limit after a manifest's override; 0 means no limit, both in
+/// settings and in the manifest
+fn pick_limit(default: u64, requested: Option<u64>, ceiling: u64) -> u64 {
+    match requested {
+        _ if default == 0 => 0,
+        Some(requested) => {
+            let requested = if requested == 0 { u64::MAX } else { requested };
+            requested.min(ceiling.max(default))
+        }
+        None => default,
+    }
+}
+
+/// The limits a plugin runs with: the defaults from settings, raised or
+/// lowered by its manifest within the ceilings above
+fn effective_limits(defaults: &PluginLimits, overrides: &PluginLimitOverrides) -> PluginLimits {
+    PluginLimits {
+        memory_mb: pick_limit(
+            defaults.memory_mb as u64,
+            overrides.memory_mb.map(u64::from),
+            MAX_MEMORY_MB,
+        ) as u32,
+        timeout_ms: pick_limit(defaults.timeout_ms, overrides.timeout_ms, MAX_TIMEOUT_MS),
+        fuel: pick_limit(defaults.fuel, overrides.fuel, MAX_FUEL),
+    }
+}
+
+fn changed_plugin_dirs(plugins_dir: &Path, paths: &[PathBuf]) -> BTreeSet<PathBuf> {
+    paths
+        .iter()
+        .filter(|path| {
+            path.file_name().and_then(|n| n.to_str()) == Some("manifest.json")
+                || path.extension().and_then(|e| e.to_str()) == Some("wasm")
+        })
+        .filter_map(|path| {
+            let dir = path.strip_prefix(plugins_dir).ok()?.components().next()?;
+            Some(plugins_dir.join(dir))
+        })
+        .collect()
+}
+
 impl PluginRuntime {
-    pub fn new() -> Result<Self, String> {
+    pub fn new(settings: Arc<SettingsStore>) -> Result<Self, String> {
         Ok(Self {
             instances: RwLock::new(HashMap::new()),
+            dev_watcher: Mutex::new(None),
+            settings,
+            health: HealthTracker::new(),
+            quarantine_listener: RwLock::new(None),
         })
     }
 
+    pub fn set_quarantine_listener(
+        &self,
+        listener: impl Fn(PluginHealthReport) + Send + Sync + 'static,
+    ) {
+        *self.quarantine_listener.write() = Some(Arc::new(listener));
+    }
+
+    pub fn health(&self, plugin_id: &str) -> PluginHealthReport {
+        self.health.report(plugin_id)
+    }
+
+    /// Let a quarantined plugin load again, e.g. when the user re-enables it
+    pub fn reset_health(&self, plugin_id: &str) {
+        self.health.reset(plugin_id);
+    }
+
     pub fn load_plugin(&self, plugin: &LoadedPlugin) -> Result<(), String> {
         // Register plugin permissions with the host API for sandboxing
         let can_read = plugin
@@ -43,15 +139,68 @@ impl PluginRuntime {
             .manifest
             .has_permission(&PluginPermission::FilesystemWrite);
         HOST_API.register_plugin(&plugin.manifest.id, can_read, can_write);
+        HOST_API.register_network(
+            &plugin.manifest.id,
+            plugin.manifest.has_permission(&PluginPermission::Network),
+            plugin.manifest.allowed_hosts.clone(),
+        );
+        HOST_API.register_notifications(
+            &plugin.manifest.id,
+            plugin
+                .manifest
+                .has_permission(&PluginPermission::Notifications),
+        );
+        HOST_API.register_clipboard(
+            &plugin.manifest.id,
+            plugin.manifest.has_permission(&PluginPermission::Clipboard),
+        );
+        HOST_API.register_oauth(
+            &plugin.manifest.id,
+            plugin
+                .manifest
+                .permissions
+                .iter()
+                .filter_map(|permission| match permission {
+                    PluginPermission::OAuth(provider) => Some(provider.clone()),
+                    _ => None,
+                })
+                .collect(),
+        );
+
+        let settings = self.settings.get();
+        let quota_mb = settings
+            .plugin_storage_quotas
+            .get(&plugin.manifest.id)
+            .copied()
+            .unwrap_or(settings.plugin_storage_quota_mb);
+        HOST_API.register_storage_quota(
+            &plugin.manifest.id,
+            (quota_mb > 0).then_some(quota_mb.saturating_mul(1024 * 1024)),
+        );
 
         // Create Extism manifest from WASM bytes
         let wasm = Wasm::data(plugin.wasm_bytes.clone());
-        let manifest = Manifest::new([wasm]);
+        let mut manifest = Manifest::new([wasm]);
 
-        // Create plugin instance
-        // Note: Host functions will be added in a future update when Extism's
-        // host function API is properly integrated
-        let mut extism_plugin = Plugin::new(&manifest, [], true).map_err(|e| {
+        let limits = effective_limits(&settings.plugin_limits, &plugin.manifest.limits);
+        if limits.memory_mb > 0 {
+            // WASM memory grows in 64 KiB pages
+            manifest = manifest.with_memory_max(limits.memory_mb.saturating_mul(16));
+        }
+        if limits.timeout_ms > 0 {
+            manifest = manifest.with_timeout(Duration::from_millis(limits.timeout_ms));
+        }
+
+        // Create plugin instance with the host functions the SDK imports
+        let functions = host_functions(&plugin.manifest.id);
+        let mut builder = PluginBuilder::new(manifest)
+            .with_wasi(true)
+            .with_functions(functions);
+        if limits.fuel > 0 {
+            // Fuel is refilled before every call
+            builder = builder.with_fuel_limit(limits.fuel);
+        }
+        let mut extism_plugin = builder.build().map_err(|e| {
             // Unregister on failure
             HOST_API.unregister_plugin(&plugin.manifest.id);
             format!("Failed to create Extism plugin: {}", e)
@@ -88,37 +237,67 @@ impl PluginRuntime {
         Ok(())
     }
 
+    /// Track a call's outcome, quarantining the plugin if it keeps failing.
+    /// Must not be called while holding the instances lock.
+    fn track(&self, plugin_id: &str, call: &str, error: Option<&str>) {
+        let Some(error) = error else {
+            self.health.record_success(plugin_id);
+            return;
+        };
+        if !self.health.record_error(plugin_id, call, error) {
+            return;
+        }
+
+        HOST_API.log(plugin_id, "error", "Disabled after failing repeatedly");
+        let _ = self.unload_plugin(plugin_id);
+        let listener = self.quarantine_listener.read().clone();
+        if let Some(listener) = listener {
+            listener(self.health.report(plugin_id));
+        }
+    }
+
     pub fn call_search(
         &self,
         plugin_id: &str,
         query: &str,
     ) -> Result<Vec<PluginSearchResult>, String> {
-        let mut instances = self.instances.write();
-        let instance = instances
-            .get_mut(plugin_id)
-            .ok_or_else(|| format!("Plugin not loaded: {}", plugin_id))?;
+        let output = {
+            let mut instances = self.instances.write();
+            let instance = instances
+                .get_mut(plugin_id)
+                .ok_or_else(|| format!("Plugin not loaded: {}", plugin_id))?;
 
-        // Check if search function exists
-        if !instance.plugin.function_exists("search") {
-            return Ok(vec![]);
-        }
+            // Check if search function exists
+            if !instance.plugin.function_exists("search") {
+                return Ok(vec![]);
+            }
 
-        // Call the search function with JSON input
-        let input = SearchInput {
-            query: query.to_string(),
-        };
+            // Call the search function with JSON input
+            let input = SearchInput {
+                query: query.to_string(),
+            };
 
-        let input_json = serde_json::to_string(&input)
-            .map_err(|e| format!("Failed to serialize search input: {}", e))?;
+            let input_json = serde_json::to_string(&input)
+                .map_err(|e| format!("Failed to serialize search input: {}", e))?;
 
-        match instance.plugin.call::<&str, &str>("search", &input_json) {
-            Ok(output_json) => {
-                let output: SearchOutput = serde_json::from_str(output_json)
-                    .map_err(|e| format!("Failed to parse search output: {}", e))?;
+            instance
+                .plugin
+                .call::<&str, &str>("search", &input_json)
+                .map_err(|e| e.to_string())
+                .and_then(|output_json| {
+                    serde_json::from_str::<SearchOutput>(output_json)
+                        .map_err(|e| format!("Failed to parse search output: {}", e))
+                })
+        };
+
+        match output {
+            Ok(output) => {
+                self.track(plugin_id, "search", None);
                 Ok(output.results)
             }
             Err(e) => {
                 HOST_API.log(plugin_id, "error", &format!("Search error: {}", e));
+                self.track(plugin_id, "search", Some(&e));
                 Ok(vec![])
             }
         }
@@ -126,30 +305,37 @@ impl PluginRuntime {
 
     /// Call an AI tool function on a plugin
     pub fn call_ai_tool(&self, plugin_id: &str, tool_input_json: &str) -> Result<String, String> {
-        let mut instances = self.instances.write();
-        let instance = instances
-            .get_mut(plugin_id)
-            .ok_or_else(|| format!("Plugin not loaded: {}", plugin_id))?;
-
-        // Check if execute_ai_tool function exists
-        if !instance.plugin.function_exists("execute_ai_tool") {
-            return Err(format!(
-                "Plugin {} does not support AI tools (no execute_ai_tool function)",
-                plugin_id
-            ));
-        }
+        let output = {
+            let mut instances = self.instances.write();
+            let instance = instances
+                .get_mut(plugin_id)
+                .ok_or_else(|| format!("Plugin not loaded: {}", plugin_id))?;
 
-        // Call the AI tool execution function
-        match instance
-            .plugin
-            .call::<&str, &str>("execute_ai_tool", tool_input_json)
-        {
+            // Check if execute_ai_tool function exists
+            if !instance.plugin.function_exists("execute_ai_tool") {
+                return Err(format!(
+                    "Plugin {} does not support AI tools (no execute_ai_tool function)",
+                    plugin_id
+                ));
+            }
+
+            // Call the AI tool execution function
+            instance
+                .plugin
+                .call::<&str, &str>("execute_ai_tool", tool_input_json)
+                .map(|output_json| output_json.to_string())
+                .map_err(|e| e.to_string())
+        };
+
+        match output {
             Ok(output_json) => {
-                HOST_API.log(plugin_id, "info", &format!("AI tool executed successfully"));
-                Ok(output_json.to_string())
+                HOST_API.log(plugin_id, "info", "AI tool executed successfully");
+                self.track(plugin_id, "execute_ai_tool", None);
+                Ok(output_json)
             }
             Err(e) => {
                 HOST_API.log(plugin_id, "error", &format!("AI tool error: {}", e));
+                self.track(plugin_id, "execute_ai_tool", Some(&e));
                 Err(format!("AI tool execution failed: {}", e))
             }
         }
@@ -161,30 +347,37 @@ impl PluginRuntime {
         plugin_id: &str,
         render_request_json: &str,
     ) -> Result<String, String> {
-        let mut instances = self.instances.write();
-        let instance = instances
-            .get_mut(plugin_id)
-            .ok_or_else(|| format!("Plugin not loaded: {}", plugin_id))?;
-
-        // Check if render_widget function exists
-        if !instance.plugin.function_exists("render_widget") {
-            return Err(format!(
-                "Plugin {} does not support widgets (no render_widget function)",
-                plugin_id
-            ));
-        }
+        let output = {
+            let mut instances = self.instances.write();
+            let instance = instances
+                .get_mut(plugin_id)
+                .ok_or_else(|| format!("Plugin not loaded: {}", plugin_id))?;
+
+            // Check if render_widget function exists
+            if !instance.plugin.function_exists("render_widget") {
+                return Err(format!(
+                    "Plugin {} does not support widgets (no render_widget function)",
+                    plugin_id
+                ));
+            }
+
+            // Call the render_widget function
+            instance
+                .plugin
+                .call::<&str, &str>("render_widget", render_request_json)
+                .map(|output_json| output_json.to_string())
+                .map_err(|e| e.to_string())
+        };
 
-        // Call the render_widget function
-        match instance
-            .plugin
-            .call::<&str, &str>("render_widget", render_request_json)
-        {
+        match output {
             Ok(output_json) => {
                 HOST_API.log(plugin_id, "info", "Widget rendered successfully");
-                Ok(output_json.to_string())
+                self.track(plugin_id, "render_widget", None);
+                Ok(output_json)
             }
             Err(e) => {
                 HOST_API.log(plugin_id, "error", &format!("Widget render error: {}", e));
+                self.track(plugin_id, "render_widget", Some(&e));
                 Err(format!("Widget render failed: {}", e))
             }
         }
@@ -206,6 +399,55 @@ impl PluginRuntime {
         Ok(())
     }
 
+    /// Swap a loaded plugin for a fresh instance of `plugin`, e.g. a new build
+    pub fn reload_plugin(&self, plugin: &LoadedPlugin) -> Result<(), String> {
+        self.unload_plugin(&plugin.manifest.id)?;
+        self.load_plugin(plugin)?;
+        HOST_API.log(&plugin.manifest.id, "info", "Plugin reloaded");
+        Ok(())
+    }
+
+    /// Dev mode: watch the installed plugins and call `on_change` with a
+    /// plugin's directory whenever its manifest or WASM file is rewritten,
+    /// e.g. by `launcher-plugin dev --watch`
+    pub fn watch_plugins(
+        &self,
+        plugins_dir: &Path,
+        on_change: impl Fn(&Path) + Send + 'static,
+    ) -> Result<(), String> {
+        let dir = plugins_dir.to_path_buf();
+        let mut debouncer =
+            new_debouncer(
+                RELOAD_DEBOUNCE,
+                move |result: DebounceEventResult| match result {
+                    Ok(events) => {
+                        let paths: Vec<PathBuf> = events.into_iter().map(|e| e.path).collect();
+                        for plugin_dir in changed_plugin_dirs(&dir, &paths) {
+                            on_change(&plugin_dir);
+                        }
+                    }
+                    Err(e) => eprintln!("Plugin watcher error: {}", e),
+                },
+            )
+            .map_err(|e| e.to_string())?;
+
+        debouncer
+            .watcher()
+            .watch(plugins_dir, RecursiveMode::Recursive)
+            .map_err(|e| format!("Failed to watch {}: {}", plugins_dir.display(), e))?;
+
+        *self.dev_watcher.lock() = Some(debouncer);
+        Ok(())
+    }
+
+    pub fn stop_watching_plugins(&self) {
+        *self.dev_watcher.lock() = None;
+    }
+
+    pub fn is_watching_plugins(&self) -> bool {
+        self.dev_watcher.lock().is_some()
+    }
+
     pub fn is_loaded(&self, plugin_id: &str) -> bool {
         let instances = self.instances.read();
         instances.contains_key(plugin_id)
@@ -216,3 +458,49 @@ impl PluginRuntime {
         instances.keys().cloned().collect()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn applies_manifest_limit_overrides() {
+        let defaults = PluginLimits {
+            memory_mb: 64,
+            timeout_ms: 3000,
+            fuel: 0,
+        };
+        let overrides = PluginLimitOverrides {
+            memory_mb: Some(256),
+            timeout_ms: Some(600_000),
+            fuel: Some(1),
+        };
+        let limits = effective_limits(&defaults, &overrides);
+        assert_eq!(limits.memory_mb, 256);
+        // Capped, and a limit the user turned off stays off
+        assert_eq!(limits.timeout_ms, MAX_TIMEOUT_MS);
+        assert_eq!(limits.fuel, 0);
+
+        let unlimited = PluginLimitOverrides {
+            memory_mb: Some(0),
+            ..Default::default()
+        };
+        let limits = effective_limits(&defaults, &unlimited);
+        assert_eq!(limits.memory_mb as u64, MAX_MEMORY_MB);
+        assert_eq!(limits.timeout_ms, 3000);
+    }
+
+    #[test]
+    fn finds_changed_plugin_dirs() {
+        let plugins = PathBuf::from("/data/launcher/plugins");
+        let paths = vec![
+            plugins.join("weather/plugin.wasm"),
+            plugins.join("weather/manifest.json"),
+            plugins.join("notes/src/lib.rs"),
+            plugins.join("todo/manifest.json"),
+            PathBuf::from("/elsewhere/x.wasm"),
+        ];
+        let dirs: Vec<PathBuf> = changed_plugin_dirs(&plugins, &paths).into_iter().collect();
+        assert_eq!(dirs, vec![plugins.join("todo"), plugins.join("weather")]);
+    }
+}
@@ -0,0 +1,89 @@
+//! Cleaning of the HTML custom widgets hand to the dashboard.
+//!
+//! The webview renders `WidgetData.html` as markup, so whatever a plugin
+//! returns is cut down to an allowlist first: layout and text tags, no
+//! scripts, forms, frames or styles. Event handler and `style` attributes
+//! are dropped, so nothing in a widget needs inline script or style
+//! permissions from a content security policy. Links may only point at
+//! web or mail addresses, and images at https or inline image data.
+
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
+
+/// Space-separated, like a `class` attribute
+const TAGS: &str = "a abbr b blockquote br code del div em h1 h2 h3 h4 hr i img kbd li mark \
+    meter ol p pre progress s small span strong sub sup table tbody td tfoot th thead time tr u ul";
+
+/// Allowed on every tag; classes can use the app's own stylesheet
+const GENERIC_ATTRIBUTES: &[&str] = &["class", "title", "aria-label"];
+
+const TAG_ATTRIBUTES: &[(&str, &[&str])] = &[
+    ("a", &["href"]),
+    ("img", &["src", "alt", "width", "height"]),
+    ("meter", &["value", "min", "max", "low", "high", "optimum"]),
+    ("ol", &["start"]),
+    ("progress", &["value", "max"]),
+    ("td", &["colspan", "rowspan"]),
+    ("th", &["colspan", "rowspan", "scope"]),
+    ("time", &["datetime"]),
+];
+
+/// Schemes a URL attribute may use; `data` is narrowed to images below
+const URL_SCHEMES: &[&str] = &["http", "https", "mailto", "data"];
+
+/// Clean a custom widget's HTML before it reaches the webview
+pub fn widget_html(html: &str) -> String {
+    let tag_attributes: HashMap<&str, HashSet<&str>> = TAG_ATTRIBUTES
+        .iter()
+        .map(|(tag, attributes)| (*tag, attributes.iter().copied().collect()))
+        .collect();
+
+    ammonia::Builder::empty()
+        .tags(TAGS.split_whitespace().collect())
+        .generic_attributes(GENERIC_ATTRIBUTES.iter().copied().collect())
+        .tag_attributes(tag_attributes)
+        .url_schemes(URL_SCHEMES.iter().copied().collect())
+        .url_relative(ammonia::UrlRelative::Deny)
+        .link_rel(Some("noopener noreferrer"))
+        .clean_content_tags(["script", "style"].into_iter().collect())
+        .strip_comments(true)
+        .attribute_filter(|element, attribute, value| {
+            // Schemes are case-insensitive and may be padded with spaces
+            let url = value.trim().to_ascii_lowercase();
+            match (element, attribute) {
+                // Plain http images would load mixed content
+                ("img", "src") if url.starts_with("https://") || url.starts_with("data:image/") => {
+                    Some(Cow::Borrowed(value))
+                }
+                ("img", "src") => None,
+                (_, "href") if url.starts_with("data:") => None,
+                _ => Some(Cow::Borrowed(value)),
+            }
+        })
+        .clean(html)
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strips_scripts_handlers_and_unsafe_urls() {
+        let html = widget_html(concat!(
+            r#"<div class="stat" style="color:red" onclick="steal()">"#,
+            r#"<script>steal()</script><b>42</b>"#,
+            r#"<a href="javascript:steal()">x</a><a href="data:text/html,hi">y</a>"#,
+            r#"<a href=" DATA:text/html,hi">w</a>"#,
+            r#"<a href="https://example.com">z</a>"#,
+            r#"<img src="http://example.com/a.png"><img src="data:image/png;base64,AA==">"#,
+            r#"<iframe src="https://example.com"></iframe></div>"#,
+        ));
+        assert!(html.starts_with(r#"<div class="stat"><b>42</b>"#));
+        for unsafe_part in ["style", "onclick", "steal", "text/html", "http:", "iframe"] {
+            assert!(!html.contains(unsafe_part), "{}", html);
+        }
+        assert!(html.contains(r#"<a href="https://example.com" rel="noopener noreferrer">z</a>"#));
+        assert!(html.contains(r#"<img src="data:image/png;base64,AA==">"#));
+    }
+}
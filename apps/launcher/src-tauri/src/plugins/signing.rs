@@ -0,0 +1,275 @@
+//! Plugin package signatures.
+//!
+//! `launcher-plugin sign` adds a `signature.json` to a package zip: an
+//! ed25519 signature over the SHA-256 of every other file in it. Installs
+//! check the signature before extracting anything, and once installed pin
+//! the first key a plugin id is signed with, so a later update signed by
+//! someone else (or not signed at all) is refused.
+
+use super::archive::MAX_UNPACKED_BYTES;
+use base64::{engine::general_purpose::STANDARD, Engine};
+use ed25519_dalek::{Signature, VerifyingKey};
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap};
+use std::io::{Read, Seek};
+use std::path::PathBuf;
+
+pub const SIGNATURE_FILE: &str = "signature.json";
+
+/// First line of the signed payload; bump it if the payload format changes
+const PAYLOAD_HEADER: &str = "launcher-plugin-signature-v1";
+
+/// More than any real `signature.json`
+const MAX_SIGNATURE_BYTES: u64 = 64 * 1024;
+
+/// Contents of `signature.json`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PackageSignature {
+    /// Base64 ed25519 public key
+    pub public_key: String,
+    /// Base64 signature over `signed_payload`
+    pub signature: String,
+}
+
+/// What gets signed: a header, then `<sha256> <path>` for every file,
+/// sorted by path. Must match the CLI.
+pub fn signed_payload(file_hashes: &BTreeMap<String, String>) -> Vec<u8> {
+    let mut payload = format!("{}\n", PAYLOAD_HEADER);
+    for (name, hash) in file_hashes {
+        payload.push_str(&format!("{} {}\n", hash, name));
+    }
+    payload.into_bytes()
+}
+
+fn verify_signature(
+    file_hashes: &BTreeMap<String, String>,
+    signature: &PackageSignature,
+) -> Result<(), String> {
+    let key: [u8; 32] = STANDARD
+        .decode(&signature.public_key)
+        .ok()
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or("Plugin signature has an invalid public key")?;
+    let key =
+        VerifyingKey::from_bytes(&key).map_err(|_| "Plugin signature has an invalid public key")?;
+    let sig: [u8; 64] = STANDARD
+        .decode(&signature.signature)
+        .ok()
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or("Plugin signature is malformed")?;
+
+    key.verify_strict(&signed_payload(file_hashes), &Signature::from_bytes(&sig))
+        .map_err(|_| "Plugin signature doesn't match its contents".to_string())
+}
+
+/// Check a package's signature without extracting it. Returns the signer's
+/// public key, or None if the package isn't signed. Files are hashed as
+/// they're read, and reading stops past `MAX_UNPACKED_BYTES` in all, so a
+/// zip bomb fails here rather than filling memory.
+pub fn verify_archive<R: Read + Seek>(
+    archive: &mut zip::ZipArchive<R>,
+) -> Result<Option<String>, String> {
+    let mut file_hashes = BTreeMap::new();
+    let mut signature = None;
+    let mut total = 0;
+
+    for i in 0..archive.len() {
+        let file = archive
+            .by_index(i)
+            .map_err(|e| format!("Failed to read package: {}", e))?;
+        if file.is_dir() {
+            continue;
+        }
+        let name = file.name().to_string();
+        let read_error = |e: std::io::Error| format!("Failed to read {} from package: {}", name, e);
+        // One byte over the budget tells a file that's too big from one that fits
+        let mut file = file.take(MAX_UNPACKED_BYTES - total + 1);
+
+        if name == SIGNATURE_FILE {
+            let mut contents = Vec::new();
+            file.by_ref()
+                .take(MAX_SIGNATURE_BYTES)
+                .read_to_end(&mut contents)
+                .map_err(read_error)?;
+            total += contents.len() as u64;
+            let parsed: PackageSignature = serde_json::from_slice(&contents)
+                .map_err(|e| format!("Plugin signature is malformed: {}", e))?;
+            signature = Some(parsed);
+        } else {
+            let mut hasher = Sha256::new();
+            total += std::io::copy(&mut file, &mut hasher).map_err(read_error)?;
+            file_hashes.insert(name, format!("{:x}", hasher.finalize()));
+        }
+
+        if total > MAX_UNPACKED_BYTES {
+            return Err(format!(
+                "Package unpacks to more than {} MB",
+                MAX_UNPACKED_BYTES / 1024 / 1024
+            ));
+        }
+    }
+
+    let Some(signature) = signature else {
+        return Ok(None);
+    };
+    verify_signature(&file_hashes, &signature)?;
+    Ok(Some(signature.public_key))
+}
+
+/// Public keys pinned per plugin id, trusted on first install
+pub struct SignerStore {
+    path: PathBuf,
+    keys: RwLock<HashMap<String, String>>,
+}
+
+impl SignerStore {
+    pub fn new() -> Self {
+        let path = dirs::data_dir()
+            .unwrap_or_else(|| PathBuf::from("."))
+            .join("launcher")
+            .join("plugin_signers.json");
+        let keys = crate::journal::load_json_store(&path).unwrap_or_default();
+        Self {
+            path,
+            keys: RwLock::new(keys),
+        }
+    }
+
+    fn save(&self) {
+        let keys = self.keys.read();
+        if let Ok(json) = serde_json::to_string_pretty(&*keys) {
+            if let Err(e) = crate::journal::write_json_store(&self.path, &json) {
+                eprintln!("Failed to save plugin signers: {}", e);
+            }
+        }
+    }
+
+    pub fn pinned(&self, plugin_id: &str) -> Option<String> {
+        self.keys.read().get(plugin_id).cloned()
+    }
+
+    /// Whether a package signed by `signer` may be installed.
+    /// `require_signed` rejects unsigned packages outright.
+    pub fn verdict(
+        &self,
+        plugin_id: &str,
+        signer: Option<&str>,
+        require_signed: bool,
+    ) -> Result<(), String> {
+        match (signer, self.pinned(plugin_id)) {
+            (Some(signer), Some(pinned)) if signer != pinned => Err(format!(
+                "{} is signed by a different key than the version installed before. \
+                 Refusing to install it.",
+                plugin_id
+            )),
+            (Some(_), _) => Ok(()),
+            (None, Some(_)) => Err(format!(
+                "{} was signed before, but this package isn't. Refusing to install it.",
+                plugin_id
+            )),
+            (None, None) if require_signed => Err(format!(
+                "{} isn't signed, and unsigned third-party plugins are turned off in \
+                 settings.",
+                plugin_id
+            )),
+            (None, None) => Ok(()),
+        }
+    }
+
+    /// Pin `signer` if this is the first signed install of `plugin_id`;
+    /// called once the install went through
+    pub fn pin(&self, plugin_id: &str, signer: Option<&str>) {
+        let Some(signer) = signer else {
+            return;
+        };
+        if self.pinned(plugin_id).is_none() {
+            self.keys
+                .write()
+                .insert(plugin_id.to_string(), signer.to_string());
+            self.save();
+        }
+    }
+
+    /// Trust whatever key `plugin_id` is signed with next, e.g. after its
+    /// author rotated keys
+    pub fn forget(&self, plugin_id: &str) {
+        if self.keys.write().remove(plugin_id).is_some() {
+            self.save();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ed25519_dalek::{Signer, SigningKey};
+    use std::io::{Cursor, Write};
+
+    fn sign(files: &[(&str, &[u8])], key: &SigningKey) -> PackageSignature {
+        let hashes = files
+            .iter()
+            .map(|(name, contents)| (name.to_string(), format!("{:x}", Sha256::digest(contents))))
+            .collect();
+        PackageSignature {
+            public_key: STANDARD.encode(key.verifying_key().to_bytes()),
+            signature: STANDARD.encode(key.sign(&signed_payload(&hashes)).to_bytes()),
+        }
+    }
+
+    fn verify(
+        files: &[(&str, &[u8])],
+        signature: Option<&PackageSignature>,
+    ) -> Result<Option<String>, String> {
+        let mut zip = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        let options = zip::write::SimpleFileOptions::default();
+        for (name, contents) in files {
+            zip.start_file(*name, options).unwrap();
+            zip.write_all(contents).unwrap();
+        }
+        if let Some(signature) = signature {
+            zip.start_file(SIGNATURE_FILE, options).unwrap();
+            zip.write_all(&serde_json::to_vec(signature).unwrap())
+                .unwrap();
+        }
+        let bytes = zip.finish().unwrap().into_inner();
+        verify_archive(&mut zip::ZipArchive::new(Cursor::new(bytes)).unwrap())
+    }
+
+    #[test]
+    fn verifies_signed_packages() {
+        let key = SigningKey::from_bytes(&[7; 32]);
+        let files: &[(&str, &[u8])] = &[("manifest.json", b"{}"), ("plugin.wasm", b"\0asm")];
+        let signature = sign(files, &key);
+
+        assert_eq!(
+            verify(files, Some(&signature)).unwrap(),
+            Some(signature.public_key.clone())
+        );
+        assert_eq!(verify(files, None).unwrap(), None);
+
+        // Signed over different contents than the package holds
+        let tampered: &[(&str, &[u8])] = &[("manifest.json", b"{}"), ("plugin.wasm", b"\0bsm")];
+        assert!(verify(tampered, Some(&signature)).is_err());
+    }
+
+    #[test]
+    fn pins_the_first_signer() {
+        let store = SignerStore {
+            path: std::env::temp_dir().join("launcher-test-plugin-signers.json"),
+            keys: RwLock::new(HashMap::new()),
+        };
+        assert!(store.verdict("a", None, false).is_ok());
+        assert!(store.verdict("a", None, true).is_err());
+        assert!(store.verdict("a", Some("key1"), true).is_ok());
+        // Nothing is pinned until an install goes through
+        assert!(store.verdict("a", Some("key2"), false).is_ok());
+        store.pin("a", Some("key1"));
+        assert!(store.verdict("a", Some("key1"), true).is_ok());
+        assert!(store.verdict("a", Some("key2"), false).is_err());
+        assert!(store.verdict("a", None, false).is_err());
+        store.forget("a");
+        assert!(store.verdict("a", Some("key2"), false).is_ok());
+    }
+}
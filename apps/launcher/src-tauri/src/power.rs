@@ -0,0 +1,536 @@
+use crate::settings::SettingsStore;
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+/// Snapshot of the machine's power situation
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PowerState {
+    /// Running on battery (not plugged in)
+    pub on_battery: bool,
+    /// Remaining battery charge (0-100), if the machine has a battery
+    pub battery_percent: Option<u8>,
+    /// The OS reports a power-saver / low power mode
+    pub power_saver: bool,
+}
+
+/// Background subsystems that can be paused while in low-power mode
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PowerSubsystem {
+    Indexing,
+    WidgetRefresh,
+    MarketplaceRefresh,
+    ProviderSync,
+}
+
+impl PowerSubsystem {
+    pub const ALL: [PowerSubsystem; 4] = [
+        PowerSubsystem::Indexing,
+        PowerSubsystem::WidgetRefresh,
+        PowerSubsystem::MarketplaceRefresh,
+        PowerSubsystem::ProviderSync,
+    ];
+}
+
+/// Power status as reported to the frontend
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PowerStatus {
+    pub state: PowerState,
+    pub low_power: bool,
+    pub paused: Vec<PowerSubsystem>,
+}
+
+/// Tracks the power state and decides which background work should pause
+pub struct PowerMonitor {
+    state: RwLock<PowerState>,
+    settings: Arc<SettingsStore>,
+    /// Paused regardless of power, e.g. during a focus session
+    held: RwLock<Vec<PowerSubsystem>>,
+}
+
+impl PowerMonitor {
+    pub fn new(settings: Arc<SettingsStore>) -> Self {
+        Self {
+            state: RwLock::new(read_power_state()),
+            settings,
+            held: RwLock::new(Vec::new()),
+        }
+    }
+
+    /// Pause these subsystems until [`release`](Self::release) is called
+    pub fn hold(&self, subsystems: &[PowerSubsystem]) {
+        *self.held.write() = subsystems.to_vec();
+    }
+
+    pub fn release(&self) {
+        self.held.write().clear();
+    }
+
+    /// Re-read the power state from the OS. Returns true if it changed.
+    pub fn refresh(&self) -> bool {
+        let new_state = read_power_state();
+        let mut state = self.state.write();
+        if *state != new_state {
+            *state = new_state;
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn state(&self) -> PowerState {
+        self.state.read().clone()
+    }
+
+    /// Whether the launcher should currently behave as in low-power mode
+    pub fn is_low_power(&self) -> bool {
+        let settings = self.settings.get().power;
+        if !settings.enabled {
+            return false;
+        }
+
+        let state = self.state.read();
+        if settings.pause_on_power_saver && state.power_saver {
+            return true;
+        }
+
+        state.on_battery
+            && state
+                .battery_percent
+                .map(|p| p <= settings.battery_threshold)
+                .unwrap_or(false)
+    }
+
+    /// Whether the given subsystem should hold off on background work
+    pub fn should_pause(&self, subsystem: PowerSubsystem) -> bool {
+        if self.held.read().contains(&subsystem) {
+            return true;
+        }
+        if !self.is_low_power() {
+            return false;
+        }
+
+        let overrides = self.settings.get().power.pause;
+        match subsystem {
+            PowerSubsystem::Indexing => overrides.indexing,
+            PowerSubsystem::WidgetRefresh => overrides.widget_refresh,
+            PowerSubsystem::MarketplaceRefresh => overrides.marketplace_refresh,
+            PowerSubsystem::ProviderSync => overrides.provider_sync,
+        }
+    }
+
+    pub fn status(&self) -> PowerStatus {
+        PowerStatus {
+            state: self.state(),
+            low_power: self.is_low_power(),
+            paused: PowerSubsystem::ALL
+                .into_iter()
+                .filter(|s| self.should_pause(*s))
+                .collect(),
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn read_power_state() -> PowerState {
+    fn read_trimmed(path: std::path::PathBuf) -> Option<String> {
+        std::fs::read_to_string(path).ok().map(|s| s.trim().to_string())
+    }
+
+    let mut state = PowerState::default();
+
+    if let Ok(entries) = std::fs::read_dir("/sys/class/power_supply") {
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if read_trimmed(path.join("type")).as_deref() != Some("Battery") {
+                continue;
+            }
+            // Peripheral batteries (mice, headsets) report scope=Device
+            if read_trimmed(path.join("scope")).as_deref() == Some("Device") {
+                continue;
+            }
+
+            if let Some(capacity) = read_trimmed(path.join("capacity")).and_then(|c| c.parse().ok())
+            {
+                state.battery_percent = Some(capacity);
+            }
+            if read_trimmed(path.join("status")).as_deref() == Some("Discharging") {
+                state.on_battery = true;
+            }
+        }
+    }
+
+    if let Ok(output) = std::process::Command::new("powerprofilesctl")
+        .arg("get")
+        .output()
+    {
+        state.power_saver = String::from_utf8_lossy(&output.stdout).trim() == "power-saver";
+    }
+
+    state
+}
+
+#[cfg(target_os = "macos")]
+fn read_power_state() -> PowerState {
+    let mut state = PowerState::default();
+
+    // Example output:
+    // Now drawing from 'Battery Power'
+    //  -InternalBattery-0 (id=1234)	85%; discharging; 4:12 remaining present: true
+    if let Ok(output) = std::process::Command::new("pmset")
+        .args(["-g", "batt"])
+        .output()
+    {
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        state.on_battery = stdout.contains("'Battery Power'");
+        state.battery_percent = stdout
+            .split(|c: char| c.is_whitespace() || c == ';')
+            .find_map(|part| part.strip_suffix('%').and_then(|p| p.parse().ok()));
+    }
+
+    if let Ok(output) = std::process::Command::new("pmset").arg("-g").output() {
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        state.power_saver = stdout.lines().any(|line| {
+            let mut parts = line.split_whitespace();
+            parts.next() == Some("lowpowermode") && parts.next() == Some("1")
+        });
+    }
+
+    state
+}
+
+#[cfg(target_os = "windows")]
+fn read_power_state() -> PowerState {
+    use ::windows::Win32::System::Power::{GetSystemPowerStatus, SYSTEM_POWER_STATUS};
+
+    let mut status = SYSTEM_POWER_STATUS::default();
+    if unsafe { GetSystemPowerStatus(&mut status) }.is_err() {
+        return PowerState::default();
+    }
+
+    PowerState {
+        // 0 = offline, 1 = online, 255 = unknown
+        on_battery: status.ACLineStatus == 0,
+        // 255 = unknown
+        battery_percent: (status.BatteryLifePercent != 255).then_some(status.BatteryLifePercent),
+        // 1 = battery saver is on
+        power_saver: status.SystemStatusFlag == 1,
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "windows", target_os = "macos")))]
+fn read_power_state() -> PowerState {
+    PowerState::default()
+}
+
+/// Whether the built-in battery is charging
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChargeState {
+    Charging,
+    Discharging,
+    Full,
+    /// Plugged in but held below full, e.g. by charge limiting
+    NotCharging,
+    #[default]
+    Unknown,
+}
+
+/// The built-in battery in more detail than `PowerState`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct BatteryInfo {
+    pub percent: Option<u8>,
+    pub state: ChargeState,
+    /// Full-charge capacity as a share of the design capacity
+    pub health_percent: Option<u8>,
+    pub cycle_count: Option<u32>,
+    /// Time until empty when discharging, or until full when charging
+    pub minutes_remaining: Option<u32>,
+}
+
+/// A peripheral reporting its battery, e.g. a Bluetooth mouse or headset
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DeviceBattery {
+    pub name: String,
+    pub percent: u8,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct BatteryReport {
+    /// `None` on machines without a battery
+    pub battery: Option<BatteryInfo>,
+    pub devices: Vec<DeviceBattery>,
+}
+
+fn health_percent(full: f64, design: f64) -> Option<u8> {
+    (full > 0.0 && design > 0.0).then(|| (full / design * 100.0).round().min(100.0) as u8)
+}
+
+/// Read the built-in battery and peripheral batteries the OS exposes. This
+/// runs external tools on some platforms, so call it off the main thread.
+#[cfg(target_os = "linux")]
+pub fn read_battery_report() -> BatteryReport {
+    fn read_trimmed(path: std::path::PathBuf) -> Option<String> {
+        std::fs::read_to_string(path)
+            .ok()
+            .map(|s| s.trim().to_string())
+    }
+    fn read_number(path: std::path::PathBuf) -> Option<f64> {
+        read_trimmed(path).and_then(|s| s.parse().ok())
+    }
+
+    let mut report = BatteryReport::default();
+
+    if let Ok(entries) = std::fs::read_dir("/sys/class/power_supply") {
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if read_trimmed(path.join("type")).as_deref() != Some("Battery") {
+                continue;
+            }
+            let percent = read_number(path.join("capacity")).map(|c| c as u8);
+
+            if read_trimmed(path.join("scope")).as_deref() == Some("Device") {
+                if let Some(percent) = percent {
+                    let name = read_trimmed(path.join("model_name"))
+                        .filter(|n| !n.is_empty())
+                        .unwrap_or_else(|| entry.file_name().to_string_lossy().into_owned());
+                    report.devices.push(DeviceBattery { name, percent });
+                }
+                continue;
+            }
+
+            let state = match read_trimmed(path.join("status")).as_deref() {
+                Some("Charging") => ChargeState::Charging,
+                Some("Discharging") => ChargeState::Discharging,
+                Some("Full") => ChargeState::Full,
+                Some("Not charging") => ChargeState::NotCharging,
+                _ => ChargeState::Unknown,
+            };
+            // Batteries report either energy (µWh, µW) or charge (µAh, µA)
+            let [now, full, design, rate] = if path.join("energy_now").exists() {
+                [
+                    "energy_now",
+                    "energy_full",
+                    "energy_full_design",
+                    "power_now",
+                ]
+            } else {
+                [
+                    "charge_now",
+                    "charge_full",
+                    "charge_full_design",
+                    "current_now",
+                ]
+            }
+            .map(|file| read_number(path.join(file)).unwrap_or(0.0));
+
+            let hours = match state {
+                ChargeState::Discharging if rate > 0.0 => Some(now / rate),
+                ChargeState::Charging if rate > 0.0 => Some((full - now).max(0.0) / rate),
+                _ => None,
+            };
+
+            report.battery = Some(BatteryInfo {
+                percent,
+                state,
+                health_percent: health_percent(full, design),
+                cycle_count: read_number(path.join("cycle_count"))
+                    .map(|c| c as u32)
+                    .filter(|c| *c > 0),
+                minutes_remaining: hours.map(|h| (h * 60.0).round() as u32),
+            });
+        }
+    }
+
+    // Bluetooth devices with a battery service show up in UPower, not sysfs
+    if let Ok(output) = std::process::Command::new("upower").arg("--dump").output() {
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        for block in stdout.split("Device: ").skip(1) {
+            let field = |name: &str| {
+                block.lines().find_map(|line| {
+                    let (key, value) = line.trim().split_once(':')?;
+                    (key.trim() == name).then(|| value.trim().to_string())
+                })
+            };
+            if field("power supply").as_deref() != Some("no") {
+                continue;
+            }
+            let (Some(name), Some(percent)) = (
+                field("model"),
+                field("percentage").and_then(|p| p.trim_end_matches('%').parse::<f64>().ok()),
+            ) else {
+                continue;
+            };
+            if !report.devices.iter().any(|d| d.name == name) {
+                report.devices.push(DeviceBattery {
+                    name,
+                    percent: percent as u8,
+                });
+            }
+        }
+    }
+
+    report
+}
+
+#[cfg(target_os = "macos")]
+pub fn read_battery_report() -> BatteryReport {
+    /// `"Key" = value` pairs from `ioreg` output
+    fn ioreg_value(block: &str, key: &str) -> Option<String> {
+        let needle = format!("\"{}\" = ", key);
+        block.lines().find_map(|line| {
+            let value = line.split_once(&needle)?.1;
+            Some(value.trim().trim_matches('"').to_string())
+        })
+    }
+
+    let mut report = BatteryReport::default();
+
+    // Example output:
+    //  -InternalBattery-0 (id=1234)	85%; discharging; 4:12 remaining present: true
+    if let Ok(output) = std::process::Command::new("pmset")
+        .args(["-g", "batt"])
+        .output()
+    {
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        if let Some(line) = stdout.lines().find(|l| l.contains("InternalBattery")) {
+            let fields: Vec<&str> = line.split(';').map(str::trim).collect();
+            let percent = fields.first().and_then(|f| {
+                f.split_whitespace()
+                    .find_map(|part| part.strip_suffix('%').and_then(|p| p.parse().ok()))
+            });
+            let state = match fields.get(1).copied() {
+                Some("charging") | Some("finishing charge") => ChargeState::Charging,
+                Some("discharging") => ChargeState::Discharging,
+                Some("charged") => ChargeState::Full,
+                Some("AC attached") => ChargeState::NotCharging,
+                _ => ChargeState::Unknown,
+            };
+            let minutes_remaining = fields.get(2).and_then(|f| {
+                let (hours, minutes) = f.split_whitespace().next()?.split_once(':')?;
+                Some(hours.parse::<u32>().ok()? * 60 + minutes.parse::<u32>().ok()?)
+            });
+            report.battery = Some(BatteryInfo {
+                percent,
+                state,
+                minutes_remaining,
+                ..Default::default()
+            });
+        }
+    }
+
+    if let Some(battery) = report.battery.as_mut() {
+        if let Ok(output) = std::process::Command::new("ioreg")
+            .args(["-r", "-c", "AppleSmartBattery"])
+            .output()
+        {
+            let stdout = String::from_utf8_lossy(&output.stdout);
+            let number = |key| ioreg_value(&stdout, key).and_then(|v| v.parse::<f64>().ok());
+            // MaxCapacity is a percentage on Apple silicon, the raw value is in mAh
+            let full = number("AppleRawMaxCapacity").or_else(|| number("MaxCapacity"));
+            battery.health_percent =
+                health_percent(full.unwrap_or(0.0), number("DesignCapacity").unwrap_or(0.0));
+            battery.cycle_count = number("CycleCount").map(|c| c as u32);
+        }
+    }
+
+    // Apple keyboards, mice and trackpads publish BatteryPercent in the registry
+    if let Ok(output) = std::process::Command::new("ioreg")
+        .args(["-r", "-l", "-k", "BatteryPercent"])
+        .output()
+    {
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        for block in stdout.split("+-o ").skip(1) {
+            let name = ioreg_value(block, "Product");
+            let percent = ioreg_value(block, "BatteryPercent").and_then(|p| p.parse().ok());
+            if let (Some(name), Some(percent)) = (name, percent) {
+                report.devices.push(DeviceBattery { name, percent });
+            }
+        }
+    }
+
+    report
+}
+
+#[cfg(target_os = "windows")]
+pub fn read_battery_report() -> BatteryReport {
+    // Bluetooth devices expose their level as a PnP property
+    const SCRIPT: &str = r#"
+$b = Get-CimInstance Win32_Battery | Select-Object -First 1
+$wmi = @{ Namespace = 'root\wmi'; ErrorAction = 'SilentlyContinue' }
+$full = Get-CimInstance @wmi -ClassName BatteryFullChargedCapacity | Select-Object -First 1
+$static = Get-CimInstance @wmi -ClassName BatteryStaticData | Select-Object -First 1
+$cycles = Get-CimInstance @wmi -ClassName BatteryCycleCount | Select-Object -First 1
+$bluetooth = Get-PnpDevice -Class Bluetooth -Status OK -ErrorAction SilentlyContinue
+$devices = $bluetooth | ForEach-Object {
+    $p = Get-PnpDeviceProperty -InstanceId $_.InstanceId -ErrorAction SilentlyContinue `
+        -KeyName '{104EA319-6EE2-4701-BD47-8DDBF425BBE5} 2'
+    if ($p.Data -ne $null) { @{ name = $_.FriendlyName; percent = [int]$p.Data } }
+}
+@{
+    present = $b -ne $null
+    percent = $b.EstimatedChargeRemaining
+    status = $b.BatteryStatus
+    runtime = $b.EstimatedRunTime
+    full = $full.FullChargedCapacity
+    design = $static.DesignedCapacity
+    cycles = $cycles.CycleCount
+    devices = @($devices)
+} | ConvertTo-Json -Compress -Depth 3
+"#;
+
+    #[derive(Deserialize)]
+    struct Output {
+        present: bool,
+        percent: Option<u8>,
+        status: Option<u16>,
+        runtime: Option<u32>,
+        full: Option<f64>,
+        design: Option<f64>,
+        cycles: Option<u32>,
+        #[serde(default)]
+        devices: Vec<DeviceBattery>,
+    }
+
+    let output = match std::process::Command::new("powershell")
+        .args(["-NoProfile", "-Command", SCRIPT])
+        .output()
+    {
+        Ok(output) => output,
+        Err(_) => return BatteryReport::default(),
+    };
+    let Ok(parsed) = serde_json::from_slice::<Output>(&output.stdout) else {
+        return BatteryReport::default();
+    };
+
+    let state = match parsed.status {
+        Some(1) => ChargeState::Discharging,
+        Some(2) => ChargeState::NotCharging,
+        Some(3) => ChargeState::Full,
+        Some(6..=9) => ChargeState::Charging,
+        _ => ChargeState::Unknown,
+    };
+    BatteryReport {
+        battery: parsed.present.then(|| BatteryInfo {
+            percent: parsed.percent,
+            state,
+            health_percent: health_percent(
+                parsed.full.unwrap_or(0.0),
+                parsed.design.unwrap_or(0.0),
+            ),
+            cycle_count: parsed.cycles.filter(|c| *c > 0),
+            // 71582788 means "unknown", e.g. while on AC power
+            minutes_remaining: parsed
+                .runtime
+                .filter(|_| state == ChargeState::Discharging)
+                .filter(|m| *m < 71_582_788),
+        }),
+        devices: parsed.devices,
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "windows", target_os = "macos")))]
+pub fn read_battery_report() -> BatteryReport {
+    BatteryReport::default()
+}
@@ -0,0 +1,327 @@
+//! Programmer mode for the calculator, for the integer math meval can't
+//! do: hex, binary and octal literals (`0b1010 + 5`), bitwise operators
+//! (`<<`, `>>`, `&`, `|`, `xor`, `~`), converting between bases
+//! (`0xff to dec`, `255 to hex`) and character lookups (`chr 65`,
+//! `ord A`, `U+1F600`). Plain decimal math without any of these is left to
+//! meval, so `10 / 4` is still 2.5.
+
+/// A result worked out from a query
+#[derive(Debug, Clone)]
+pub struct ProgrammerAnswer {
+    /// What gets copied
+    pub value: String,
+    /// The conversion or other bases, for the subtitle
+    pub detail: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Token {
+    Number(i128),
+    Op(&'static str),
+    Open,
+    Close,
+}
+
+const OPERATORS: &[&str] = &["<<", ">>", "&", "|", "+", "-", "*", "/", "%", "~"];
+/// Operators that only make sense on integers
+const BITWISE: &[&str] = &["<<", ">>", "&", "|", "xor", "~"];
+
+fn parse_digits(digits: &str, radix: u32) -> Option<i128> {
+    let digits = digits.replace('_', "");
+    if digits.is_empty() {
+        return None;
+    }
+    i128::from_str_radix(&digits, radix).ok()
+}
+
+/// Split an expression into tokens; also returns whether it uses anything
+/// that needs programmer mode
+fn tokenize(input: &str) -> Option<(Vec<Token>, bool)> {
+    let mut tokens = Vec::new();
+    let mut programmer = false;
+    let mut rest = input.trim();
+
+    while !rest.is_empty() {
+        let lower = rest.to_lowercase();
+        if let Some(op) = OPERATORS.iter().find(|op| rest.starts_with(**op)) {
+            programmer |= BITWISE.contains(op);
+            tokens.push(Token::Op(*op));
+            rest = &rest[op.len()..];
+        } else if lower.starts_with("xor") && !lower[3..].starts_with(char::is_alphanumeric) {
+            programmer = true;
+            tokens.push(Token::Op("xor"));
+            rest = &rest[3..];
+        } else if let Some(after) = rest.strip_prefix('(') {
+            tokens.push(Token::Open);
+            rest = after;
+        } else if let Some(after) = rest.strip_prefix(')') {
+            tokens.push(Token::Close);
+            rest = after;
+        } else if let Some(after) = rest.strip_prefix('\'') {
+            // 'A' is its code point
+            let mut chars = after.chars();
+            let c = chars.next()?;
+            rest = chars.as_str().strip_prefix('\'')?;
+            programmer = true;
+            tokens.push(Token::Number(c as i128));
+        } else if lower.starts_with("u+") {
+            // A code point, `U+1F600`
+            let end = rest[2..]
+                .find(|c: char| !c.is_ascii_hexdigit())
+                .map_or(rest.len(), |i| i + 2);
+            programmer = true;
+            tokens.push(Token::Number(parse_digits(&rest[2..end], 16)?));
+            rest = &rest[end..];
+        } else if rest.starts_with(|c: char| c.is_ascii_digit()) {
+            let end = rest
+                .find(|c: char| !c.is_ascii_alphanumeric() && c != '_')
+                .unwrap_or(rest.len());
+            let literal = &lower[..end];
+            let (digits, radix) = match literal.get(..2) {
+                Some("0x") => (&literal[2..], 16),
+                Some("0b") => (&literal[2..], 2),
+                Some("0o") => (&literal[2..], 8),
+                _ => (literal, 10),
+            };
+            programmer |= radix != 10;
+            tokens.push(Token::Number(parse_digits(digits, radix)?));
+            rest = &rest[end..];
+        } else if rest.starts_with(char::is_whitespace) {
+            rest = rest.trim_start();
+        } else {
+            return None;
+        }
+    }
+    Some((tokens, programmer))
+}
+
+/// How tightly a binary operator binds, C-style
+fn precedence(op: &str) -> Option<u8> {
+    match op {
+        "|" => Some(1),
+        "xor" => Some(2),
+        "&" => Some(3),
+        "<<" | ">>" => Some(4),
+        "+" | "-" => Some(5),
+        "*" | "/" | "%" => Some(6),
+        _ => None,
+    }
+}
+
+fn apply(op: &str, lhs: i128, rhs: i128) -> Option<i128> {
+    let shift = || u32::try_from(rhs).ok().filter(|bits| *bits < 128);
+    match op {
+        "|" => Some(lhs | rhs),
+        "xor" => Some(lhs ^ rhs),
+        "&" => Some(lhs & rhs),
+        "<<" => lhs.checked_shl(shift()?),
+        ">>" => lhs.checked_shr(shift()?),
+        "+" => lhs.checked_add(rhs),
+        "-" => lhs.checked_sub(rhs),
+        "*" => lhs.checked_mul(rhs),
+        "/" => lhs.checked_div(rhs),
+        "%" => lhs.checked_rem(rhs),
+        _ => None,
+    }
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).copied();
+        self.pos += 1;
+        token
+    }
+
+    fn expression(&mut self, min_precedence: u8) -> Option<i128> {
+        let mut lhs = self.unary()?;
+        while let Some(Token::Op(op)) = self.tokens.get(self.pos).copied() {
+            let precedence = precedence(op)?;
+            if precedence < min_precedence {
+                break;
+            }
+            self.pos += 1;
+            let rhs = self.expression(precedence + 1)?;
+            lhs = apply(op, lhs, rhs)?;
+        }
+        Some(lhs)
+    }
+
+    fn unary(&mut self) -> Option<i128> {
+        match self.next()? {
+            Token::Number(n) => Some(n),
+            Token::Op("-") => self.unary()?.checked_neg(),
+            Token::Op("~") => Some(!self.unary()?),
+            Token::Open => {
+                let value = self.expression(1)?;
+                (self.next()? == Token::Close).then_some(value)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// The integer `input` works out to, and whether it needed programmer mode
+fn evaluate_integer(input: &str) -> Option<(i128, bool)> {
+    let (tokens, programmer) = tokenize(input)?;
+    let mut parser = Parser { tokens, pos: 0 };
+    let value = parser.expression(1)?;
+    (parser.pos == parser.tokens.len()).then_some((value, programmer))
+}
+
+fn signed(n: i128, prefix: &str, digits: String) -> String {
+    let sign = if n < 0 { "-" } else { "" };
+    format!("{}{}{}", sign, prefix, digits)
+}
+
+fn hex(n: i128) -> String {
+    signed(n, "0x", format!("{:X}", n.unsigned_abs()))
+}
+
+fn binary(n: i128) -> String {
+    signed(n, "0b", format!("{:b}", n.unsigned_abs()))
+}
+
+fn octal(n: i128) -> String {
+    signed(n, "0o", format!("{:o}", n.unsigned_abs()))
+}
+
+fn code_point(n: i128) -> String {
+    format!("U+{:04X}", n)
+}
+
+/// `n` in hex, binary and octal
+fn other_bases(n: i128) -> String {
+    format!("{} • {} • {}", hex(n), binary(n), octal(n))
+}
+
+fn character_answer(n: i128) -> Option<ProgrammerAnswer> {
+    let c = char::from_u32(u32::try_from(n).ok()?)?;
+    // Control characters don't show up, so give their code point instead
+    let value = if c.is_control() {
+        code_point(n)
+    } else {
+        c.to_string()
+    };
+    let kind = if c.is_control() {
+        "Control character • "
+    } else {
+        ""
+    };
+    Some(ProgrammerAnswer {
+        value,
+        detail: format!("{}{} • {} • {}", kind, code_point(n), n, hex(n)),
+    })
+}
+
+fn code_answer(c: char) -> ProgrammerAnswer {
+    let n = c as i128;
+    ProgrammerAnswer {
+        value: n.to_string(),
+        detail: format!("'{}' = {} • {}", c, code_point(n), other_bases(n)),
+    }
+}
+
+fn single_char(input: &str) -> Option<char> {
+    let mut chars = input.chars();
+    let c = chars.next()?;
+    chars.next().is_none().then_some(c)
+}
+
+/// `chr 65`, `ord A`, `ascii A`, `ascii 65`, `unicode 😀` or `U+1F600`
+fn lookup(query: &str) -> Option<ProgrammerAnswer> {
+    let (command, argument) = query.split_once(' ').unwrap_or(("", query));
+    let argument = argument.trim();
+    match command.to_lowercase().as_str() {
+        "chr" | "char" => character_answer(evaluate_integer(argument)?.0),
+        "ord" | "unicode" => Some(code_answer(single_char(argument)?)),
+        "ascii" => match single_char(argument).filter(|c| !c.is_ascii_digit()) {
+            Some(c) => Some(code_answer(c)),
+            None => character_answer(evaluate_integer(argument)?.0),
+        },
+        "" if argument.to_lowercase().starts_with("u+") => {
+            character_answer(evaluate_integer(argument)?.0)
+        }
+        _ => None,
+    }
+}
+
+/// `0xff to dec`, `255 in binary`, `0x41 to char`
+fn convert(query: &str) -> Option<ProgrammerAnswer> {
+    let (left, right) = [" to ", " in "]
+        .iter()
+        .find_map(|separator| query.rsplit_once(separator))?;
+    let (n, _) = evaluate_integer(left)?;
+    let value = match right.trim().to_lowercase().as_str() {
+        "dec" | "decimal" => n.to_string(),
+        "hex" | "hexadecimal" => hex(n),
+        "bin" | "binary" => binary(n),
+        "oct" | "octal" => octal(n),
+        "char" | "ascii" => return character_answer(n),
+        "unicode" => code_point(n),
+        _ => return None,
+    };
+    Some(ProgrammerAnswer {
+        detail: format!("{} = {}", left.trim(), value),
+        value,
+    })
+}
+
+/// `0b1010 + 5`, `1 << 4`, `0xff & 0x0f`; None for plain decimal math
+fn calculate(query: &str) -> Option<ProgrammerAnswer> {
+    let (n, programmer) = evaluate_integer(query)?;
+    if !programmer {
+        return None;
+    }
+    Some(ProgrammerAnswer {
+        value: n.to_string(),
+        detail: format!("= {} • {}", query.trim(), other_bases(n)),
+    })
+}
+
+/// Answer a programmer query, if it is one
+pub fn evaluate(query: &str) -> Option<ProgrammerAnswer> {
+    lookup(query)
+        .or_else(|| convert(query))
+        .or_else(|| calculate(query))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn answers_programmer_queries() {
+        assert_eq!(evaluate("0xff to dec").unwrap().value, "255");
+        let answer = evaluate("255 to hex").unwrap();
+        assert_eq!(answer.value, "0xFF");
+        assert_eq!(answer.detail, "255 = 0xFF");
+        assert_eq!(evaluate("-10 in binary").unwrap().value, "-0b1010");
+
+        assert_eq!(evaluate("0b1010 + 5").unwrap().value, "15");
+        assert_eq!(evaluate("1 << 4 | 1").unwrap().value, "17");
+        assert_eq!(evaluate("0xff & 0x0f xor 0b11").unwrap().value, "12");
+        assert_eq!(evaluate("~0").unwrap().value, "-1");
+        assert_eq!(evaluate("'a' + 1 to char").unwrap().value, "b");
+        assert!(evaluate("1 << 200").is_none());
+        assert!(evaluate("0x10 / 0").is_none());
+        // Left to meval
+        assert!(evaluate("10 / 4").is_none());
+        assert!(evaluate("2.5 * 2").is_none());
+        assert!(evaluate("100 km to miles").is_none());
+
+        assert_eq!(evaluate("chr 65").unwrap().value, "A");
+        assert_eq!(evaluate("ord A").unwrap().value, "65");
+        assert_eq!(evaluate("ascii 65").unwrap().value, "A");
+        assert_eq!(evaluate("U+1F600").unwrap().value, "😀");
+        assert_eq!(
+            evaluate("unicode 😀").unwrap().detail,
+            "'😀' = U+1F600 • 0x1F600 • 0b11111011000000000 • 0o373000"
+        );
+        assert_eq!(evaluate("chr 10").unwrap().value, "U+000A");
+    }
+}
@@ -1,4 +1,43 @@
-use super::{ResultCategory, ResultIcon, SearchProvider, SearchResult};
+use super::{
+    execute_path_action, path_actions, ResultAction, ResultCategory, ResultIcon, SearchContext,
+    SearchProvider, SearchResult,
+};
+use crate::uninstall::{self, UninstallPlan, UninstallTarget};
+use async_trait::async_trait;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// How often the app folders' watcher is checked for changes
+pub const WATCH_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Full rescans catch installs the watcher can't see, such as Store apps
+/// or app folders created after startup
+pub const REFRESH_INTERVAL: Duration = Duration::from_secs(30 * 60);
+
+type UninstallListener = Arc<dyn Fn(UninstallPlan) + Send + Sync>;
+
+/// The "uninstall" action only asks: it hands a plan to the listener, which
+/// runs it once the user agrees
+#[derive(Default)]
+struct UninstallConfirm(parking_lot::RwLock<Option<UninstallListener>>);
+
+impl UninstallConfirm {
+    fn set(&self, listener: UninstallListener) {
+        *self.0.write() = Some(listener);
+    }
+
+    fn ask(&self, result_id: &str, target: Option<UninstallTarget>) -> Result<(), String> {
+        let target = target.ok_or("App not found")?;
+        let listener = self.0.read().clone();
+        let listener = listener.ok_or("Uninstall confirmation is not available")?;
+        listener(uninstall::plan(result_id, &target)?);
+        Ok(())
+    }
+}
+
+fn uninstall_action() -> ResultAction {
+    ResultAction::new("uninstall", "Uninstall…")
+}
 
 // Linux implementation using freedesktop desktop entries
 #[cfg(target_os = "linux")]
@@ -6,10 +45,12 @@ mod linux {
     use super::*;
     use freedesktop_desktop_entry::{default_paths, DesktopEntry, Iter};
     use std::collections::HashMap;
+    use std::path::PathBuf;
     use std::sync::RwLock;
 
     pub struct AppProvider {
         apps: RwLock<Vec<AppEntry>>,
+        uninstall: UninstallConfirm,
     }
 
     #[derive(Debug, Clone)]
@@ -27,12 +68,21 @@ mod linux {
         pub fn new() -> Self {
             let provider = Self {
                 apps: RwLock::new(Vec::new()),
+                uninstall: UninstallConfirm::default(),
             };
             provider.refresh_apps();
             provider
         }
 
-        pub fn refresh_apps(&self) {
+        /// The XDG applications folders desktop entries are read from
+        pub fn app_dirs(&self) -> Vec<PathBuf> {
+            let mut dirs = Vec::new();
+            dirs.extend(default_paths());
+            dirs
+        }
+
+        /// Rescan the app folders, returning the number of apps found
+        pub fn refresh_apps(&self) -> usize {
             let mut apps = Vec::new();
             let mut seen: HashMap<String, bool> = HashMap::new();
             let locales = &["en"];
@@ -82,9 +132,39 @@ mod linux {
                 }
             }
 
+            let count = apps.len();
             if let Ok(mut lock) = self.apps.write() {
                 *lock = apps;
             }
+            count
+        }
+
+        /// Called with a plan when an app's "uninstall" action runs
+        pub fn set_uninstall_listener(
+            &self,
+            listener: impl Fn(UninstallPlan) + Send + Sync + 'static,
+        ) {
+            self.uninstall.set(Arc::new(listener));
+        }
+
+        pub fn uninstall_target(&self, result_id: &str) -> Option<UninstallTarget> {
+            let id = result_id.strip_prefix("app:")?;
+            let apps = self.apps.read().ok()?;
+            let app = apps.iter().find(|a| a.id == id)?;
+            Some(UninstallTarget {
+                name: app.name.clone(),
+                path: app.id.clone(),
+            })
+        }
+
+        /// Drop an uninstalled app until the next rescan
+        pub fn remove_app(&self, result_id: &str) {
+            let Some(id) = result_id.strip_prefix("app:") else {
+                return;
+            };
+            if let Ok(mut apps) = self.apps.write() {
+                apps.retain(|a| a.id != id);
+            }
         }
 
         fn score_match(query: &str, app: &AppEntry) -> f32 {
@@ -150,12 +230,13 @@ mod linux {
         }
     }
 
+    #[async_trait]
     impl SearchProvider for AppProvider {
         fn id(&self) -> &str {
             "apps"
         }
 
-        fn search(&self, query: &str) -> Vec<SearchResult> {
+        async fn search(&self, query: &str, _ctx: &SearchContext) -> Vec<SearchResult> {
             if query.trim().is_empty() {
                 return vec![];
             }
@@ -181,6 +262,7 @@ mod linux {
                                 .unwrap_or(ResultIcon::Emoji("📦".to_string())),
                             category: ResultCategory::Application,
                             score,
+                            aria_label: None,
                         })
                     } else {
                         None
@@ -210,10 +292,27 @@ mod linux {
                 Err("Invalid app result".to_string())
             }
         }
+
+        fn actions(&self, _result_id: &str) -> Vec<ResultAction> {
+            let mut actions = path_actions();
+            actions.push(uninstall_action());
+            actions
+        }
+
+        fn execute_action(&self, result_id: &str, action_id: &str) -> Result<(), String> {
+            if action_id == "uninstall" {
+                return self
+                    .uninstall
+                    .ask(result_id, self.uninstall_target(result_id));
+            }
+            let path = result_id.strip_prefix("app:").ok_or("Invalid app result")?;
+            execute_path_action(path, action_id)
+        }
     }
 }
 
-// Windows implementation - scans Start Menu shortcuts with icon extraction
+// Windows implementation - scans Start Menu shortcuts with icon extraction, plus
+// packaged (UWP / Microsoft Store) apps, which have no shortcuts
 #[cfg(target_os = "windows")]
 mod windows_impl {
     use super::*;
@@ -230,16 +329,44 @@ mod windows_impl {
     use lnk::ShellLink;
     use pelite::pe64::{Pe, PeFile};
     use pelite::resources::version_info::VersionInfo;
+    use serde::Deserialize;
     use sha2::{Digest, Sha256};
     use std::collections::HashMap;
     use std::ffi::OsStr;
     use std::os::windows::ffi::OsStrExt;
-    use std::path::PathBuf;
+    use std::path::{Path, PathBuf};
     use std::sync::RwLock;
 
+    /// Packaged apps are launched through the shell by their AUMID
+    /// (`<package family>!<app id>`)
+    const APPS_FOLDER: &str = "shell:AppsFolder\\";
+
+    /// Start menu entries backed by an app package, with the package's
+    /// install folder for the logo
+    const PACKAGED_APPS_SCRIPT: &str = r#"
+$locations = @{}
+Get-AppxPackage | ForEach-Object { $locations[$_.PackageFamilyName] = $_.InstallLocation }
+$apps = Get-StartApps | Where-Object { $_.AppID -like '*!*' } | ForEach-Object {
+    [pscustomobject]@{
+        name = $_.Name
+        aumid = $_.AppID
+        location = $locations[$_.AppID.Split('!')[0]]
+    }
+}
+ConvertTo-Json -InputObject @($apps) -Compress
+"#;
+
+    #[derive(Deserialize)]
+    struct PackagedApp {
+        name: String,
+        aumid: String,
+        location: Option<PathBuf>,
+    }
+
     pub struct AppProvider {
         apps: RwLock<Vec<AppEntry>>,
         icon_cache_dir: PathBuf,
+        uninstall: UninstallConfirm,
     }
 
     #[derive(Debug, Clone)]
@@ -265,12 +392,19 @@ mod windows_impl {
             let provider = Self {
                 apps: RwLock::new(Vec::new()),
                 icon_cache_dir,
+                uninstall: UninstallConfirm::default(),
             };
             provider.refresh_apps();
             provider
         }
 
-        pub fn refresh_apps(&self) {
+        /// The Start Menu folders shortcuts are read from
+        pub fn app_dirs(&self) -> Vec<PathBuf> {
+            Self::get_start_menu_paths()
+        }
+
+        /// Rescan shortcuts and packaged apps, returning the number of apps found
+        pub fn refresh_apps(&self) -> usize {
             let mut apps = Vec::new();
             let mut seen: HashMap<String, bool> = HashMap::new();
 
@@ -283,9 +417,32 @@ mod windows_impl {
                 }
             }
 
+            // Store apps that also install a shortcut were found above
+            for app in Self::packaged_apps() {
+                if seen.contains_key(&app.name) {
+                    continue;
+                }
+                seen.insert(app.name.clone(), true);
+
+                let icon_path = app
+                    .location
+                    .as_deref()
+                    .and_then(|location| Self::packaged_logo(location, &app.aumid));
+                apps.push(AppEntry {
+                    id: format!("{}{}", APPS_FOLDER, app.aumid),
+                    name: app.name,
+                    description: Some("Windows app".to_string()),
+                    target_path: None,
+                    icon_path,
+                    shortcut_path: app.location.unwrap_or_default(),
+                });
+            }
+
+            let count = apps.len();
             if let Ok(mut lock) = self.apps.write() {
                 *lock = apps;
             }
+            count
         }
 
         fn get_start_menu_paths() -> Vec<PathBuf> {
@@ -367,6 +524,59 @@ mod windows_impl {
             }
         }
 
+        fn packaged_apps() -> Vec<PackagedApp> {
+            let output = match std::process::Command::new("powershell")
+                .args(["-NoProfile", "-Command", PACKAGED_APPS_SCRIPT])
+                .output()
+            {
+                Ok(output) => output,
+                Err(_) => return Vec::new(),
+            };
+            serde_json::from_slice(&output.stdout).unwrap_or_default()
+        }
+
+        /// The app's small tile logo from its package manifest. Logos ship in
+        /// scaled variants (`Logo.scale-200.png`), so the manifest's file name
+        /// is matched by stem.
+        fn packaged_logo(location: &Path, aumid: &str) -> Option<String> {
+            let app_id = aumid.split_once('!')?.1;
+            let manifest = std::fs::read_to_string(location.join("AppxManifest.xml")).ok()?;
+            let pattern = format!(
+                r#"(?s)<Application\s[^>]*Id="{}".*?Square44x44Logo="([^"]+)""#,
+                regex::escape(app_id)
+            );
+            let logo = regex::Regex::new(&pattern)
+                .ok()?
+                .captures(&manifest)?
+                .get(1)?
+                .as_str()
+                .replace('\\', "/");
+
+            let logo = location.join(logo);
+            if logo.exists() {
+                return Some(logo.to_string_lossy().to_string());
+            }
+            let dir = logo.parent()?;
+            let stem = logo.file_stem()?.to_string_lossy().to_string();
+            let mut variants: Vec<PathBuf> = std::fs::read_dir(dir)
+                .ok()?
+                .flatten()
+                .map(|entry| entry.path())
+                .filter(|path| {
+                    let name = path.file_name().unwrap_or_default().to_string_lossy();
+                    name.starts_with(&format!("{}.", stem)) && name.ends_with(".png")
+                })
+                .collect();
+            // Prefer the variant sized for the launcher's icons
+            variants.sort_by_key(|path| {
+                let name = path.to_string_lossy();
+                !(name.contains("targetsize-48") || name.contains("scale-200"))
+            });
+            variants
+                .first()
+                .map(|path| path.to_string_lossy().to_string())
+        }
+
         fn parse_lnk(path: &PathBuf) -> (Option<String>, Option<String>) {
             match ShellLink::open(path) {
                 Ok(lnk) => {
@@ -635,6 +845,34 @@ mod windows_impl {
             img.save(path).is_ok()
         }
 
+        /// Called with a plan when an app's "uninstall" action runs
+        pub fn set_uninstall_listener(
+            &self,
+            listener: impl Fn(UninstallPlan) + Send + Sync + 'static,
+        ) {
+            self.uninstall.set(Arc::new(listener));
+        }
+
+        pub fn uninstall_target(&self, result_id: &str) -> Option<UninstallTarget> {
+            let id = result_id.strip_prefix("app:")?;
+            let apps = self.apps.read().ok()?;
+            let app = apps.iter().find(|a| a.id == id)?;
+            Some(UninstallTarget {
+                name: app.name.clone(),
+                path: app.id.clone(),
+            })
+        }
+
+        /// Drop an uninstalled app until the next rescan
+        pub fn remove_app(&self, result_id: &str) {
+            let Some(id) = result_id.strip_prefix("app:") else {
+                return;
+            };
+            if let Ok(mut apps) = self.apps.write() {
+                apps.retain(|a| a.id != id);
+            }
+        }
+
         fn score_match(query: &str, app: &AppEntry) -> f32 {
             use strsim::jaro_winkler;
 
@@ -700,12 +938,13 @@ mod windows_impl {
         rgba: Vec<u8>,
     }
 
+    #[async_trait]
     impl SearchProvider for AppProvider {
         fn id(&self) -> &str {
             "apps"
         }
 
-        fn search(&self, query: &str) -> Vec<SearchResult> {
+        async fn search(&self, query: &str, _ctx: &SearchContext) -> Vec<SearchResult> {
             if query.trim().is_empty() {
                 return vec![];
             }
@@ -734,6 +973,7 @@ mod windows_impl {
                             icon,
                             category: ResultCategory::Application,
                             score,
+                            aria_label: None,
                         })
                     } else {
                         None
@@ -757,6 +997,26 @@ mod windows_impl {
                 Err("Invalid app result".to_string())
             }
         }
+
+        fn actions(&self, result_id: &str) -> Vec<ResultAction> {
+            // Packaged apps have no file to open or reveal
+            if result_id.starts_with(&format!("app:{}", APPS_FOLDER)) {
+                return vec![uninstall_action()];
+            }
+            let mut actions = path_actions();
+            actions.push(uninstall_action());
+            actions
+        }
+
+        fn execute_action(&self, result_id: &str, action_id: &str) -> Result<(), String> {
+            if action_id == "uninstall" {
+                return self
+                    .uninstall
+                    .ask(result_id, self.uninstall_target(result_id));
+            }
+            let path = result_id.strip_prefix("app:").ok_or("Invalid app result")?;
+            execute_path_action(path, action_id)
+        }
     }
 }
 
@@ -770,6 +1030,7 @@ mod macos {
 
     pub struct AppProvider {
         apps: RwLock<Vec<AppEntry>>,
+        uninstall: UninstallConfirm,
     }
 
     #[derive(Debug, Clone)]
@@ -783,33 +1044,39 @@ mod macos {
         pub fn new() -> Self {
             let provider = Self {
                 apps: RwLock::new(Vec::new()),
+                uninstall: UninstallConfirm::default(),
             };
             provider.refresh_apps();
             provider
         }
 
-        pub fn refresh_apps(&self) {
-            let mut apps = Vec::new();
-            let mut seen: HashMap<String, bool> = HashMap::new();
-
-            // Standard Applications directories
-            let app_dirs = vec![
+        /// Standard Applications directories
+        pub fn app_dirs(&self) -> Vec<PathBuf> {
+            vec![
                 PathBuf::from("/Applications"),
                 PathBuf::from("/System/Applications"),
                 dirs::home_dir()
                     .map(|h| h.join("Applications"))
                     .unwrap_or_default(),
-            ];
+            ]
+        }
+
+        /// Rescan the Applications folders, returning the number of apps found
+        pub fn refresh_apps(&self) -> usize {
+            let mut apps = Vec::new();
+            let mut seen: HashMap<String, bool> = HashMap::new();
 
-            for dir in app_dirs {
+            for dir in self.app_dirs() {
                 if dir.exists() {
                     Self::scan_directory(&dir, &mut apps, &mut seen, 0);
                 }
             }
 
+            let count = apps.len();
             if let Ok(mut lock) = self.apps.write() {
                 *lock = apps;
             }
+            count
         }
 
         fn scan_directory(
@@ -856,6 +1123,34 @@ mod macos {
             }
         }
 
+        /// Called with a plan when an app's "uninstall" action runs
+        pub fn set_uninstall_listener(
+            &self,
+            listener: impl Fn(UninstallPlan) + Send + Sync + 'static,
+        ) {
+            self.uninstall.set(Arc::new(listener));
+        }
+
+        pub fn uninstall_target(&self, result_id: &str) -> Option<UninstallTarget> {
+            let id = result_id.strip_prefix("app:")?;
+            let apps = self.apps.read().ok()?;
+            let app = apps.iter().find(|a| a.id == id)?;
+            Some(UninstallTarget {
+                name: app.name.clone(),
+                path: app.id.clone(),
+            })
+        }
+
+        /// Drop an uninstalled app until the next rescan
+        pub fn remove_app(&self, result_id: &str) {
+            let Some(id) = result_id.strip_prefix("app:") else {
+                return;
+            };
+            if let Ok(mut apps) = self.apps.write() {
+                apps.retain(|a| a.id != id);
+            }
+        }
+
         fn score_match(query: &str, app: &AppEntry) -> f32 {
             use strsim::jaro_winkler;
 
@@ -902,12 +1197,13 @@ mod macos {
         }
     }
 
+    #[async_trait]
     impl SearchProvider for AppProvider {
         fn id(&self) -> &str {
             "apps"
         }
 
-        fn search(&self, query: &str) -> Vec<SearchResult> {
+        async fn search(&self, query: &str, _ctx: &SearchContext) -> Vec<SearchResult> {
             if query.trim().is_empty() {
                 return vec![];
             }
@@ -929,6 +1225,7 @@ mod macos {
                             icon: ResultIcon::Emoji("📦".to_string()),
                             category: ResultCategory::Application,
                             score,
+                            aria_label: None,
                         })
                     } else {
                         None
@@ -952,6 +1249,22 @@ mod macos {
                 Err("Invalid app result".to_string())
             }
         }
+
+        fn actions(&self, _result_id: &str) -> Vec<ResultAction> {
+            let mut actions = path_actions();
+            actions.push(uninstall_action());
+            actions
+        }
+
+        fn execute_action(&self, result_id: &str, action_id: &str) -> Result<(), String> {
+            if action_id == "uninstall" {
+                return self
+                    .uninstall
+                    .ask(result_id, self.uninstall_target(result_id));
+            }
+            let path = result_id.strip_prefix("app:").ok_or("Invalid app result")?;
+            execute_path_action(path, action_id)
+        }
     }
 }
 
@@ -0,0 +1,236 @@
+use super::{ResultCategory, ResultIcon, SearchContext, SearchProvider, SearchResult};
+use crate::power::{self, BatteryInfo, BatteryReport, ChargeState};
+use async_trait::async_trait;
+use parking_lot::Mutex;
+use std::time::{Duration, Instant};
+
+/// Reading the battery spawns tools on macOS and Windows; keep a recent
+/// report around for the next keystroke
+const CACHE_TTL: Duration = Duration::from_secs(30);
+
+const KEYWORDS: &[&str] = &["battery", "charge", "bluetooth battery"];
+
+fn matches(query: &str) -> bool {
+    query.len() >= 3
+        && KEYWORDS
+            .iter()
+            .any(|k| k.starts_with(query) || query.starts_with(k))
+}
+
+fn duration(minutes: u32) -> String {
+    match (minutes / 60, minutes % 60) {
+        (0, m) => format!("{} min", m),
+        (h, 0) => format!("{} h", h),
+        (h, m) => format!("{} h {} min", h, m),
+    }
+}
+
+fn state_label(state: ChargeState) -> &'static str {
+    match state {
+        ChargeState::Charging => "Charging",
+        ChargeState::Discharging => "On battery",
+        ChargeState::Full => "Fully charged",
+        ChargeState::NotCharging => "Plugged in, not charging",
+        ChargeState::Unknown => "Unknown state",
+    }
+}
+
+/// Subtitle line for the built-in battery, e.g.
+/// "On battery • 3 h 20 min left • Health 92% • 412 cycles"
+fn describe(info: &BatteryInfo) -> String {
+    let mut parts = vec![state_label(info.state).to_string()];
+    if let Some(minutes) = info.minutes_remaining {
+        parts.push(match info.state {
+            ChargeState::Charging => format!("{} until full", duration(minutes)),
+            _ => format!("{} left", duration(minutes)),
+        });
+    }
+    if let Some(health) = info.health_percent {
+        parts.push(format!("Health {}%", health));
+    }
+    if let Some(cycles) = info.cycle_count {
+        parts.push(format!("{} cycles", cycles));
+    }
+    parts.join(" • ")
+}
+
+fn battery_icon(percent: Option<u8>) -> &'static str {
+    match percent {
+        Some(p) if p <= 20 => "🪫",
+        _ => "🔋",
+    }
+}
+
+/// Answers "battery" with the built-in battery's charge and health and the
+/// battery levels of connected devices the OS exposes.
+pub struct BatteryProvider {
+    cached: Mutex<Option<(Instant, BatteryReport)>>,
+}
+
+impl BatteryProvider {
+    pub fn new() -> Self {
+        Self {
+            cached: Mutex::new(None),
+        }
+    }
+
+    /// The current report, re-read from the OS when the cached one is stale
+    /// or `refresh` is set
+    pub fn report(&self, refresh: bool) -> BatteryReport {
+        if let Some((read_at, report)) = self.cached.lock().as_ref() {
+            if !refresh && read_at.elapsed() < CACHE_TTL {
+                return report.clone();
+            }
+        }
+        let report = power::read_battery_report();
+        *self.cached.lock() = Some((Instant::now(), report.clone()));
+        report
+    }
+
+    fn results(report: &BatteryReport) -> Vec<SearchResult> {
+        let mut results = Vec::new();
+
+        if let Some(info) = &report.battery {
+            let title = match info.percent {
+                Some(percent) => format!("Battery {}%", percent),
+                None => "Battery".to_string(),
+            };
+            results.push(SearchResult {
+                id: "battery:internal".to_string(),
+                title,
+                subtitle: Some(describe(info)),
+                icon: ResultIcon::Emoji(battery_icon(info.percent).to_string()),
+                category: ResultCategory::System,
+                score: 90.0,
+                aria_label: None,
+            });
+        }
+
+        for (i, device) in report.devices.iter().enumerate() {
+            results.push(SearchResult {
+                id: format!("battery:device:{}", device.name),
+                title: format!("{} {}%", device.name, device.percent),
+                subtitle: Some("Device battery".to_string()),
+                icon: ResultIcon::Emoji(battery_icon(Some(device.percent)).to_string()),
+                category: ResultCategory::System,
+                score: 85.0 - i as f32 * 0.1,
+                aria_label: None,
+            });
+        }
+
+        if results.is_empty() {
+            results.push(SearchResult {
+                id: "battery:none".to_string(),
+                title: "No battery found".to_string(),
+                subtitle: Some("This machine reports no battery or battery devices".to_string()),
+                icon: ResultIcon::Emoji("🔌".to_string()),
+                category: ResultCategory::System,
+                score: 70.0,
+                aria_label: None,
+            });
+        }
+        results
+    }
+}
+
+impl Default for BatteryProvider {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Open the OS battery / power settings
+fn open_power_settings() -> Result<(), String> {
+    #[cfg(target_os = "linux")]
+    {
+        std::process::Command::new("gnome-control-center")
+            .arg("power")
+            .spawn()
+            .map_err(|e| format!("Failed to open power settings: {}", e))?;
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        std::process::Command::new("open")
+            .arg("x-apple.systempreferences:com.apple.Battery-Settings.extension")
+            .spawn()
+            .map_err(|e| format!("Failed to open battery settings: {}", e))?;
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        std::process::Command::new("cmd")
+            .args(["/C", "start", "", "ms-settings:batterysaver"])
+            .spawn()
+            .map_err(|e| format!("Failed to open battery settings: {}", e))?;
+    }
+
+    Ok(())
+}
+
+#[async_trait]
+impl SearchProvider for BatteryProvider {
+    fn id(&self) -> &str {
+        "battery"
+    }
+
+    async fn search(&self, query: &str, ctx: &SearchContext) -> Vec<SearchResult> {
+        if !matches(&query.trim().to_lowercase()) {
+            return vec![];
+        }
+        match ctx.run_blocking(|| Some(self.report(false))) {
+            Some(report) => Self::results(&report),
+            None => vec![],
+        }
+    }
+
+    fn execute(&self, result_id: &str) -> Result<(), String> {
+        match result_id {
+            "battery:internal" | "battery:none" => open_power_settings(),
+            id if id.starts_with("battery:device:") => Ok(()),
+            _ => Err("Invalid battery result".to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn describes_battery_state() {
+        let info = BatteryInfo {
+            percent: Some(64),
+            state: ChargeState::Discharging,
+            health_percent: Some(92),
+            cycle_count: Some(412),
+            minutes_remaining: Some(200),
+        };
+        assert_eq!(
+            describe(&info),
+            "On battery • 3 h 20 min left • Health 92% • 412 cycles"
+        );
+
+        let charging = BatteryInfo {
+            state: ChargeState::Charging,
+            minutes_remaining: Some(45),
+            ..Default::default()
+        };
+        assert_eq!(describe(&charging), "Charging • 45 min until full");
+    }
+
+    #[test]
+    fn lists_devices_after_the_battery() {
+        let report = BatteryReport {
+            battery: None,
+            devices: vec![power::DeviceBattery {
+                name: "Magic Mouse".to_string(),
+                percent: 15,
+            }],
+        };
+        let results = BatteryProvider::results(&report);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].title, "Magic Mouse 15%");
+        assert!(matches("batt") && !matches("bat man"));
+    }
+}
@@ -0,0 +1,462 @@
+use super::{
+    ResultAction, ResultCategory, ResultIcon, SearchContext, SearchProvider, SearchResult,
+};
+use crate::frecency::FrecencyStore;
+use async_trait::async_trait;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::process::Command;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Listing devices spawns a CLI (and one per device on Linux); reuse the
+/// list while typing
+const CACHE_TTL: Duration = Duration::from_secs(5);
+
+const KEYWORDS: &[&str] = &["bluetooth", "bt"];
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BluetoothDevice {
+    /// MAC address, upper case with colons
+    pub address: String,
+    pub name: String,
+    pub connected: bool,
+    /// Percent, for devices that report it (mostly headsets and mice)
+    pub battery: Option<u8>,
+    /// Kind of device as the OS names it, e.g. "audio-headset" or "Headphones"
+    pub kind: Option<String>,
+}
+
+/// Stdout of a command that ran successfully
+fn output(command: &mut Command) -> Option<String> {
+    let output = command.output().ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+#[cfg_attr(target_os = "windows", allow(dead_code))]
+fn spawn(command: &mut Command) -> Result<(), String> {
+    command
+        .spawn()
+        .map(|_| ())
+        .map_err(|e| format!("Failed to run {:?}: {}", command.get_program(), e))
+}
+
+/// Paired devices from `bluetoothctl devices Paired`, with their details
+/// from `bluetoothctl info <address>`:
+/// `Connected: yes`, `Battery Percentage: 0x5a (90)`, `Icon: audio-headset`
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_bluetoothctl(devices: &str, info: impl Fn(&str) -> String) -> Vec<BluetoothDevice> {
+    devices
+        .lines()
+        .filter_map(|line| {
+            let rest = line.trim().strip_prefix("Device ")?;
+            let (address, name) = rest.split_once(' ')?;
+            let info = info(address);
+            let field = |key: &str| {
+                info.lines()
+                    .find_map(|l| l.trim().strip_prefix(key))
+                    .map(str::trim)
+            };
+            let battery = field("Battery Percentage:")
+                .and_then(|b| b.split_once('(')?.1.strip_suffix(')')?.parse().ok());
+
+            Some(BluetoothDevice {
+                address: address.to_uppercase(),
+                name: name.trim().to_string(),
+                connected: field("Connected:") == Some("yes"),
+                battery,
+                kind: field("Icon:").map(String::from),
+            })
+        })
+        .collect()
+}
+
+/// Devices in `system_profiler SPBluetoothDataType -json` output, which
+/// groups them into `device_connected` and `device_not_connected` lists of
+/// `{ name: { device_address, device_batteryLevelMain, ... } }`
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+fn parse_system_profiler(json: &str) -> Vec<BluetoothDevice> {
+    let Ok(report) = serde_json::from_str::<serde_json::Value>(json) else {
+        return Vec::new();
+    };
+    let percent = |value: &serde_json::Value| -> Option<u8> {
+        value.as_str()?.trim_end_matches('%').trim().parse().ok()
+    };
+
+    let mut devices = Vec::new();
+    for controller in report["SPBluetoothDataType"]
+        .as_array()
+        .into_iter()
+        .flatten()
+    {
+        for (key, connected) in [("device_connected", true), ("device_not_connected", false)] {
+            let entries = controller[key].as_array().into_iter().flatten();
+            for (name, details) in entries.filter_map(|e| e.as_object()).flatten() {
+                let Some(address) = details["device_address"].as_str() else {
+                    continue;
+                };
+                // AirPods report each bud; the lower one is what matters
+                let buds = ["device_batteryLevelLeft", "device_batteryLevelRight"]
+                    .iter()
+                    .filter_map(|k| percent(&details[*k]))
+                    .min();
+                devices.push(BluetoothDevice {
+                    address: address.replace('-', ":").to_uppercase(),
+                    name: name.clone(),
+                    connected,
+                    battery: percent(&details["device_batteryLevelMain"])
+                        .or(buds)
+                        .or_else(|| percent(&details["device_batteryLevel"])),
+                    kind: details["device_minorType"].as_str().map(String::from),
+                });
+            }
+        }
+    }
+    devices
+}
+
+#[cfg(target_os = "linux")]
+fn paired_devices() -> Result<Vec<BluetoothDevice>, String> {
+    // BlueZ before 5.65 only knows `paired-devices`
+    let devices = output(Command::new("bluetoothctl").args(["devices", "Paired"]))
+        .filter(|list| !list.trim().is_empty())
+        .or_else(|| output(Command::new("bluetoothctl").arg("paired-devices")))
+        .ok_or("bluetoothctl isn't available; is BlueZ installed?")?;
+    Ok(parse_bluetoothctl(&devices, |address| {
+        output(Command::new("bluetoothctl").args(["info", address])).unwrap_or_default()
+    }))
+}
+
+#[cfg(target_os = "macos")]
+fn paired_devices() -> Result<Vec<BluetoothDevice>, String> {
+    output(Command::new("system_profiler").args(["SPBluetoothDataType", "-json"]))
+        .map(|json| parse_system_profiler(&json))
+        .ok_or_else(|| "Couldn't read Bluetooth devices".to_string())
+}
+
+/// Paired devices through WinRT's `BluetoothDevice`; battery levels come
+/// from the hands-free profile's device property, where there is one
+#[cfg(target_os = "windows")]
+const PAIRED_DEVICES_SCRIPT: &str = r#"
+Add-Type -AssemblyName System.Runtime.WindowsRuntime
+$asTask = [System.WindowsRuntimeSystemExtensions].GetMethods() | Where-Object {
+    $_.Name -eq 'AsTask' -and $_.GetParameters().Count -eq 1 -and
+    $_.GetParameters()[0].ParameterType.Name -eq 'IAsyncOperation`1'
+} | Select-Object -First 1
+function Await($operation, $type) {
+    $task = $asTask.MakeGenericMethod($type).Invoke($null, @($operation))
+    $task.Wait(-1) | Out-Null
+    $task.Result
+}
+$DeviceType = [Windows.Devices.Bluetooth.BluetoothDevice, Windows.Devices.Bluetooth, ContentType = WindowsRuntime]
+$InfoType = [Windows.Devices.Enumeration.DeviceInformation, Windows.Devices.Enumeration, ContentType = WindowsRuntime]
+$CollectionType = [Windows.Devices.Enumeration.DeviceInformationCollection]
+$infos = Await ($InfoType::FindAllAsync($DeviceType::GetDeviceSelectorFromPairingState($true))) $CollectionType
+$batteryKey = '{104EA319-6EE2-4701-BD47-8DDBF425BBE5} 2'
+$pnp = Get-PnpDevice -Class Bluetooth, System -ErrorAction SilentlyContinue
+$devices = foreach ($info in $infos) {
+    $device = Await ($DeviceType::FromIdAsync($info.Id)) $DeviceType
+    if (-not $device) { continue }
+    $hex = '{0:X12}' -f $device.BluetoothAddress
+    $battery = $pnp | Where-Object { $_.InstanceId -like "*$hex*" } | ForEach-Object {
+        (Get-PnpDeviceProperty -InstanceId $_.InstanceId -KeyName $batteryKey -ErrorAction Ignore).Data
+    } | Where-Object { $_ -ne $null } | Select-Object -First 1
+    [pscustomobject]@{
+        address = ($hex -split '(..)' -ne '') -join ':'
+        name = $device.Name
+        connected = $device.ConnectionStatus -eq 'Connected'
+        battery = $battery
+        kind = [string]$device.ClassOfDevice.MajorClass
+    }
+}
+ConvertTo-Json -InputObject @($devices) -Compress
+"#;
+
+#[cfg(target_os = "windows")]
+fn paired_devices() -> Result<Vec<BluetoothDevice>, String> {
+    let json =
+        output(Command::new("powershell").args(["-NoProfile", "-Command", PAIRED_DEVICES_SCRIPT]))
+            .ok_or("Couldn't read Bluetooth devices")?;
+    serde_json::from_str(&json).map_err(|e| format!("Couldn't read Bluetooth devices: {}", e))
+}
+
+#[cfg(target_os = "linux")]
+fn set_connected(device: &BluetoothDevice, connect: bool) -> Result<(), String> {
+    let verb = if connect { "connect" } else { "disconnect" };
+    spawn(Command::new("bluetoothctl").args([verb, &device.address]))
+}
+
+#[cfg(target_os = "macos")]
+fn set_connected(device: &BluetoothDevice, connect: bool) -> Result<(), String> {
+    // IOBluetooth through the JavaScript for Automation bridge, so no helper
+    // tool needs installing
+    let method = if connect {
+        "openConnection"
+    } else {
+        "closeConnection"
+    };
+    let script = format!(
+        "ObjC.import('IOBluetooth'); $.IOBluetoothDevice.deviceWithAddressString('{}').{}",
+        device.address.replace(':', "-"),
+        method
+    );
+    spawn(Command::new("osascript").args(["-l", "JavaScript", "-e", &script]))
+}
+
+#[cfg(target_os = "windows")]
+fn set_connected(_device: &BluetoothDevice, _connect: bool) -> Result<(), String> {
+    // WinRT can't connect classic devices on an app's behalf; open the
+    // Settings page that can
+    Command::new("explorer")
+        .arg("ms-settings:bluetooth")
+        .spawn()
+        .map(|_| ())
+        .map_err(|e| format!("Failed to open Bluetooth settings: {}", e))
+}
+
+fn icon(device: &BluetoothDevice) -> ResultIcon {
+    let kind = device.kind.as_deref().unwrap_or_default().to_lowercase();
+    let emoji = if ["audio", "head", "phone", "speaker"]
+        .iter()
+        .any(|k| kind.contains(k))
+    {
+        "🎧"
+    } else if kind.contains("keyboard") {
+        "⌨️"
+    } else if kind.contains("mouse") || kind.contains("input") || kind.contains("peripheral") {
+        "🖱️"
+    } else {
+        "🔵"
+    };
+    ResultIcon::Emoji(emoji.to_string())
+}
+
+/// `bluetooth [name]` (or `bt`) lists paired devices with their battery
+/// levels and connects or disconnects them. Devices used often rank first.
+pub struct BluetoothProvider {
+    cached: Mutex<Option<(Instant, Vec<BluetoothDevice>)>>,
+    frecency: Arc<FrecencyStore>,
+}
+
+impl BluetoothProvider {
+    pub fn new(frecency: Arc<FrecencyStore>) -> Self {
+        Self {
+            cached: Mutex::new(None),
+            frecency,
+        }
+    }
+
+    /// Paired devices, re-read when the cached list is stale or `refresh`
+    /// is set
+    pub fn devices(&self, refresh: bool) -> Result<Vec<BluetoothDevice>, String> {
+        if let Some((read_at, devices)) = self.cached.lock().as_ref() {
+            if !refresh && read_at.elapsed() < CACHE_TTL {
+                return Ok(devices.clone());
+            }
+        }
+        let devices = paired_devices()?;
+        *self.cached.lock() = Some((Instant::now(), devices.clone()));
+        Ok(devices)
+    }
+
+    fn result_id(device: &BluetoothDevice) -> String {
+        format!("bluetooth:{}", device.address)
+    }
+
+    fn device_result(device: &BluetoothDevice, score: f32) -> SearchResult {
+        let mut parts = vec![if device.connected {
+            "Connected • Enter to disconnect"
+        } else {
+            "Enter to connect"
+        }
+        .to_string()];
+        if let Some(battery) = device.battery {
+            parts.push(format!("🔋 {}%", battery));
+        }
+
+        SearchResult {
+            id: Self::result_id(device),
+            title: device.name.clone(),
+            subtitle: Some(parts.join(" • ")),
+            icon: icon(device),
+            category: ResultCategory::System,
+            score,
+            aria_label: None,
+        }
+    }
+
+    fn results(
+        &self,
+        devices: Result<Vec<BluetoothDevice>, String>,
+        filter: &str,
+    ) -> Vec<SearchResult> {
+        let mut devices: Vec<BluetoothDevice> = match devices {
+            Ok(devices) => devices
+                .into_iter()
+                .filter(|d| d.name.to_lowercase().contains(filter))
+                .collect(),
+            Err(e) => {
+                return vec![SearchResult {
+                    id: "bluetooth:none".to_string(),
+                    title: "Bluetooth unavailable".to_string(),
+                    subtitle: Some(e),
+                    icon: ResultIcon::Emoji("🔵".to_string()),
+                    category: ResultCategory::System,
+                    score: 70.0,
+                    aria_label: None,
+                }];
+            }
+        };
+        if devices.is_empty() {
+            if !filter.is_empty() {
+                return vec![];
+            }
+            return vec![SearchResult {
+                id: "bluetooth:none".to_string(),
+                title: "No paired Bluetooth devices".to_string(),
+                subtitle: Some("Pair devices in the system settings first".to_string()),
+                icon: ResultIcon::Emoji("🔵".to_string()),
+                category: ResultCategory::System,
+                score: 70.0,
+                aria_label: None,
+            }];
+        }
+
+        // Connected devices first, then the ones connected to most often
+        let boost = |d: &BluetoothDevice| self.frecency.get_boost(&Self::result_id(d));
+        devices.sort_by(|a, b| {
+            b.connected
+                .cmp(&a.connected)
+                .then_with(|| boost(b).total_cmp(&boost(a)))
+                .then_with(|| a.name.cmp(&b.name))
+        });
+        devices
+            .iter()
+            .enumerate()
+            .map(|(i, d)| Self::device_result(d, 90.0 - i as f32 * 0.1))
+            .collect()
+    }
+
+    fn device(&self, result_id: &str) -> Result<BluetoothDevice, String> {
+        let address = result_id
+            .strip_prefix("bluetooth:")
+            .filter(|a| *a != "none")
+            .ok_or("Invalid Bluetooth result")?;
+        self.devices(false)?
+            .into_iter()
+            .find(|d| d.address == address)
+            .ok_or_else(|| "That device is no longer paired".to_string())
+    }
+
+    fn toggle(&self, result_id: &str, connect: Option<bool>) -> Result<(), String> {
+        let device = self.device(result_id)?;
+        let connect = connect.unwrap_or(!device.connected);
+        set_connected(&device, connect)?;
+        // Pick up the new state on the next search
+        *self.cached.lock() = None;
+        Ok(())
+    }
+}
+
+fn parse_query(query: &str) -> Option<&str> {
+    let (keyword, filter) = query.split_once(' ').unwrap_or((query, ""));
+    if KEYWORDS.contains(&keyword) {
+        return Some(filter.trim());
+    }
+    // A partial keyword, while nothing follows it
+    (filter.is_empty() && query.len() >= 4 && "bluetooth".starts_with(query)).then_some("")
+}
+
+#[async_trait]
+impl SearchProvider for BluetoothProvider {
+    fn id(&self) -> &str {
+        "bluetooth"
+    }
+
+    async fn search(&self, query: &str, ctx: &SearchContext) -> Vec<SearchResult> {
+        let query = query.trim().to_lowercase();
+        let Some(filter) = parse_query(&query) else {
+            return vec![];
+        };
+
+        match ctx.run_blocking(|| Some(self.devices(false))) {
+            Some(devices) => self.results(devices, filter),
+            None => vec![],
+        }
+    }
+
+    fn execute(&self, result_id: &str) -> Result<(), String> {
+        if result_id == "bluetooth:none" {
+            return Ok(());
+        }
+        self.toggle(result_id, None)
+    }
+
+    fn actions(&self, result_id: &str) -> Vec<ResultAction> {
+        if result_id == "bluetooth:none" {
+            return Vec::new();
+        }
+        vec![
+            ResultAction::new("connect", "Connect"),
+            ResultAction::new("disconnect", "Disconnect"),
+        ]
+    }
+
+    fn execute_action(&self, result_id: &str, action_id: &str) -> Result<(), String> {
+        match action_id {
+            "connect" => {
+                // Enter is recorded by execute_result; count explicit
+                // connects the same way
+                self.frecency.record_access(result_id);
+                self.toggle(result_id, Some(true))
+            }
+            "disconnect" => self.toggle(result_id, Some(false)),
+            _ => Err(format!("Unknown action: {}", action_id)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_bluetoothctl_output() {
+        let devices = "Device aa:bb:cc:dd:ee:ff WH-1000XM4\nDevice 11:22:33:44:55:66 MX Keys\n";
+        let devices = parse_bluetoothctl(devices, |address| {
+            if address == "aa:bb:cc:dd:ee:ff" {
+                "\tConnected: yes\n\tIcon: audio-headset\n\tBattery Percentage: 0x5a (90)\n".into()
+            } else {
+                "\tConnected: no\n\tIcon: input-keyboard\n".into()
+            }
+        });
+        assert_eq!(devices.len(), 2);
+        assert_eq!(devices[0].address, "AA:BB:CC:DD:EE:FF");
+        assert!(devices[0].connected);
+        assert_eq!(devices[0].battery, Some(90));
+        assert_eq!(devices[1].name, "MX Keys");
+        assert_eq!(devices[1].battery, None);
+    }
+
+    #[test]
+    fn parses_system_profiler_output() {
+        let json = r#"{"SPBluetoothDataType": [{
+            "device_connected": [{"AirPods Pro": {
+                "device_address": "AA:BB:CC:DD:EE:FF",
+                "device_batteryLevelLeft": "80%",
+                "device_batteryLevelRight": "75%",
+                "device_minorType": "Headphones"
+            }}],
+            "device_not_connected": [{"Magic Mouse": {"device_address": "11:22:33:44:55:66"}}]
+        }]}"#;
+        let devices = parse_system_profiler(json);
+        assert_eq!(devices.len(), 2);
+        assert_eq!(devices[0].battery, Some(75));
+        assert!(devices[0].connected);
+        assert!(!devices[1].connected);
+        assert_eq!(devices[1].name, "Magic Mouse");
+    }
+}
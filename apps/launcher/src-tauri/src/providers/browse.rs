@@ -0,0 +1,506 @@
+use super::files::FileProvider;
+use super::{
+    ResultAction, ResultCategory, ResultContext, ResultIcon, SearchContext, SearchProvider,
+    SearchResult,
+};
+use crate::cleanup::format_bytes;
+use async_trait::async_trait;
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+const PREFIX: &str = "browse:";
+/// Long enough for paging and completing in one folder to share a listing,
+/// short enough that files created meanwhile show up
+const LISTING_TTL: Duration = Duration::from_secs(5);
+const MAX_RESULTS: usize = 50;
+
+/// A file's state in the git repository it's in. Folders take the state
+/// `Modified` when anything below them changed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GitStatus {
+    Modified,
+    Added,
+    Deleted,
+    Renamed,
+    Untracked,
+    Conflicted,
+}
+
+impl GitStatus {
+    fn label(&self) -> &'static str {
+        match self {
+            Self::Modified => "Modified",
+            Self::Added => "Added",
+            Self::Deleted => "Deleted",
+            Self::Renamed => "Renamed",
+            Self::Untracked => "Untracked",
+            Self::Conflicted => "Conflict",
+        }
+    }
+}
+
+/// One file or folder in a listing
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BrowseEntry {
+    pub path: String,
+    pub name: String,
+    pub is_dir: bool,
+    /// Files only
+    pub size: Option<u64>,
+    pub icon: ResultIcon,
+    pub git_status: Option<GitStatus>,
+}
+
+/// A slice of a folder's listing, folders first and then by name
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DirectoryPage {
+    pub path: String,
+    pub entries: Vec<BrowseEntry>,
+    /// Entries matching the filter, across all pages
+    pub total: usize,
+    /// Where the next page starts, if there is one
+    pub next_offset: Option<usize>,
+}
+
+/// A file navigator behind the search bar.
+///
+/// `browse:` followed by a path, or a path starting with `~/`, lists that
+/// folder; anything after the last `/` filters it by name. Tab completion
+/// extends the query the way a shell would, so folders can be walked
+/// without leaving the keyboard.
+pub struct BrowseProvider {
+    listings: RwLock<HashMap<PathBuf, (Instant, Arc<Vec<BrowseEntry>>)>>,
+}
+
+/// `~` and `~/…` in the home folder, relative paths too
+fn expand(path: &str, home: &Path) -> PathBuf {
+    match path.strip_prefix('~') {
+        Some(rest) => home.join(rest.trim_start_matches(['/', '\\'])),
+        None => home.join(path),
+    }
+}
+
+/// The folder to list and the partial name after its last `/` to filter it
+/// by, or None if the query isn't for browsing
+fn parse_query(query: &str, home: &Path) -> Option<(PathBuf, String)> {
+    let path = match query.strip_prefix(PREFIX) {
+        Some(rest) => rest.trim_start(),
+        None if query == "~" || query.starts_with("~/") => query,
+        None => return None,
+    };
+    if path == "~" {
+        return Some((home.to_path_buf(), String::new()));
+    }
+    // Split after the last separator, so the name is the query's own text
+    let (dir, name) = match path.rfind(['/', '\\']) {
+        Some(i) => path.split_at(i + 1),
+        None => ("", path),
+    };
+    Some((expand(dir, home), name.to_string()))
+}
+
+fn matches_filter(name: &str, filter: &str) -> bool {
+    // Dotfiles only once the filter asks for them
+    if name.starts_with('.') && !filter.starts_with('.') {
+        return false;
+    }
+    name.to_lowercase().starts_with(&filter.to_lowercase())
+}
+
+/// Changes in `git status --porcelain -z` output, by path relative to the
+/// repository root
+fn parse_porcelain(output: &str) -> HashMap<String, GitStatus> {
+    let mut statuses = HashMap::new();
+    let mut records = output.split('\0');
+    while let Some(record) = records.next() {
+        let (Some(code), Some(path)) = (record.get(..2), record.get(3..)) else {
+            continue;
+        };
+        let status = match code {
+            "??" => GitStatus::Untracked,
+            "AA" | "DD" => GitStatus::Conflicted,
+            _ if code.contains('U') => GitStatus::Conflicted,
+            _ if code.starts_with('A') => GitStatus::Added,
+            _ if code.contains('D') => GitStatus::Deleted,
+            _ if code.starts_with(['R', 'C']) => {
+                // Followed by the path it was renamed or copied from
+                records.next();
+                GitStatus::Renamed
+            }
+            _ => GitStatus::Modified,
+        };
+        statuses.insert(path.trim_end_matches('/').to_string(), status);
+    }
+    statuses
+}
+
+/// Changes in the repository `dir` is in, with the repository's root
+fn git_statuses(dir: &Path) -> Option<(PathBuf, HashMap<String, GitStatus>)> {
+    let root = dir.ancestors().find(|a| a.join(".git").exists())?;
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(root)
+        .args(["status", "--porcelain=v1", "-z"])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let statuses = parse_porcelain(&String::from_utf8_lossy(&output.stdout));
+    Some((root.to_path_buf(), statuses))
+}
+
+#[cfg(target_os = "macos")]
+fn open_terminal(dir: &Path) -> Result<(), String> {
+    Command::new("open")
+        .args(["-a", "Terminal"])
+        .arg(dir)
+        .spawn()
+        .map_err(|e| format!("Failed to open terminal: {}", e))?;
+    Ok(())
+}
+
+#[cfg(target_os = "windows")]
+fn open_terminal(dir: &Path) -> Result<(), String> {
+    // Windows Terminal if it's installed, a console window otherwise
+    if Command::new("wt").arg("-d").arg(dir).spawn().is_ok() {
+        return Ok(());
+    }
+    Command::new("cmd")
+        .args(["/C", "start", "cmd", "/K"])
+        .current_dir(dir)
+        .spawn()
+        .map_err(|e| format!("Failed to open terminal: {}", e))?;
+    Ok(())
+}
+
+#[cfg(target_os = "linux")]
+fn open_terminal(dir: &Path) -> Result<(), String> {
+    const TERMINALS: &[&str] = &[
+        "x-terminal-emulator",
+        "gnome-terminal",
+        "konsole",
+        "xfce4-terminal",
+        "alacritty",
+        "kitty",
+        "xterm",
+    ];
+    let preferred = std::env::var("TERMINAL").ok().filter(|t| !t.is_empty());
+    let candidates = preferred
+        .iter()
+        .map(String::as_str)
+        .chain(TERMINALS.iter().copied());
+    for terminal in candidates {
+        if Command::new(terminal).current_dir(dir).spawn().is_ok() {
+            return Ok(());
+        }
+    }
+    Err("No terminal emulator found; set $TERMINAL to pick one".to_string())
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "linux")))]
+fn open_terminal(_dir: &Path) -> Result<(), String> {
+    Err("Opening a terminal isn't supported on this platform".to_string())
+}
+
+impl BrowseProvider {
+    pub fn new() -> Self {
+        Self {
+            listings: RwLock::new(HashMap::new()),
+        }
+    }
+
+    fn home() -> PathBuf {
+        dirs::home_dir().unwrap_or_else(|| PathBuf::from("/"))
+    }
+
+    /// Everything in `dir`, folders first, kept for a few seconds
+    fn entries(&self, dir: &Path) -> Result<Arc<Vec<BrowseEntry>>, String> {
+        if let Some((listed_at, entries)) = self.listings.read().get(dir) {
+            if listed_at.elapsed() < LISTING_TTL {
+                return Ok(entries.clone());
+            }
+        }
+
+        let read = std::fs::read_dir(dir)
+            .map_err(|e| format!("Couldn't open {}: {}", dir.display(), e))?;
+        let git = git_statuses(dir);
+        let git_status = |path: &Path, is_dir: bool| {
+            let (root, statuses) = git.as_ref()?;
+            let relative = path
+                .strip_prefix(root)
+                .ok()?
+                .to_string_lossy()
+                .replace('\\', "/");
+            if let Some(status) = statuses.get(&relative) {
+                return Some(*status);
+            }
+            let below = format!("{}/", relative);
+            (is_dir && statuses.keys().any(|p| p.starts_with(&below)))
+                .then_some(GitStatus::Modified)
+        };
+
+        let mut entries: Vec<BrowseEntry> = read
+            .flatten()
+            .map(|entry| {
+                let path = entry.path();
+                // Follows symlinks, so a link to a folder browses like one
+                let metadata = std::fs::metadata(&path).ok();
+                let is_dir = metadata.as_ref().is_some_and(|m| m.is_dir());
+                let extension = path.extension().map(|e| e.to_string_lossy().to_lowercase());
+                let path_str = path.to_string_lossy().to_string();
+                BrowseEntry {
+                    name: entry.file_name().to_string_lossy().to_string(),
+                    is_dir,
+                    size: metadata.filter(|_| !is_dir).map(|m| m.len()),
+                    icon: FileProvider::get_file_icon(&path_str, &extension, is_dir),
+                    git_status: git_status(&path, is_dir),
+                    path: path_str,
+                }
+            })
+            .collect();
+        entries.sort_by(|a, b| {
+            b.is_dir
+                .cmp(&a.is_dir)
+                .then_with(|| a.name.to_lowercase().cmp(&b.name.to_lowercase()))
+        });
+
+        let entries = Arc::new(entries);
+        let mut listings = self.listings.write();
+        listings.retain(|_, (listed_at, _)| listed_at.elapsed() < LISTING_TTL);
+        listings.insert(dir.to_path_buf(), (Instant::now(), entries.clone()));
+        Ok(entries)
+    }
+
+    /// Up to `limit` entries of `path` whose names start with `filter`,
+    /// from `offset` on, for listings too long to send at once
+    pub fn page(
+        &self,
+        path: &str,
+        filter: &str,
+        offset: usize,
+        limit: usize,
+    ) -> Result<DirectoryPage, String> {
+        let dir = expand(path, &Self::home());
+        let entries = self.entries(&dir)?;
+        let matching: Vec<&BrowseEntry> = entries
+            .iter()
+            .filter(|e| matches_filter(&e.name, filter))
+            .collect();
+        let end = offset.saturating_add(limit).min(matching.len());
+
+        Ok(DirectoryPage {
+            path: dir.to_string_lossy().to_string(),
+            entries: matching
+                .get(offset..end)
+                .unwrap_or_default()
+                .iter()
+                .map(|e| (*e).clone())
+                .collect(),
+            total: matching.len(),
+            next_offset: (end < matching.len()).then_some(end),
+        })
+    }
+
+    /// Tab completion: the query extended as far as the names it matches
+    /// agree, with a `/` after a single folder. None if there's nothing to add.
+    pub fn complete(&self, query: &str) -> Option<String> {
+        let (dir, partial) = parse_query(query, &Self::home())?;
+        let entries = self.entries(&dir).ok()?;
+        let matches: Vec<&BrowseEntry> = entries
+            .iter()
+            .filter(|e| matches_filter(&e.name, &partial))
+            .collect();
+        let first = matches.first()?;
+
+        // Matching ignores case, so the completion takes the names' own
+        let common = first
+            .name
+            .char_indices()
+            .map(|(i, c)| i + c.len_utf8())
+            .take_while(|&end| {
+                let prefix = first.name[..end].to_lowercase();
+                matches
+                    .iter()
+                    .all(|m| m.name.to_lowercase().starts_with(&prefix))
+            })
+            .last()
+            .unwrap_or(0);
+
+        if common < partial.len() {
+            return None;
+        }
+
+        let mut completed = query[..query.len() - partial.len()].to_string();
+        completed.push_str(&first.name[..common]);
+        if matches.len() == 1 && first.is_dir {
+            completed.push('/');
+        }
+        (completed != query).then_some(completed)
+    }
+
+    fn entry_result(entry: &BrowseEntry, score: f32) -> SearchResult {
+        let mut details = vec![match entry.size {
+            Some(size) => format_bytes(size),
+            None => "Folder".to_string(),
+        }];
+        details.extend(entry.git_status.map(|s| s.label().to_string()));
+
+        SearchResult {
+            id: format!("{}{}", PREFIX, entry.path),
+            title: entry.name.clone(),
+            subtitle: Some(details.join(" • ")),
+            icon: entry.icon.clone(),
+            category: ResultCategory::File,
+            score,
+            aria_label: None,
+        }
+    }
+
+    fn search_blocking(&self, query: &str) -> Vec<SearchResult> {
+        let Some((dir, filter)) = parse_query(query, &Self::home()) else {
+            return vec![];
+        };
+
+        match self.entries(&dir) {
+            Ok(entries) => entries
+                .iter()
+                .filter(|e| matches_filter(&e.name, &filter))
+                .take(MAX_RESULTS)
+                .enumerate()
+                .map(|(idx, e)| Self::entry_result(e, 95.0 - idx as f32 * 0.1))
+                .collect(),
+            Err(e) => vec![SearchResult {
+                id: format!("{}{}", PREFIX, dir.to_string_lossy()),
+                title: format!("Can't open {}", dir.display()),
+                subtitle: Some(e),
+                icon: ResultIcon::Emoji("⚠️".to_string()),
+                category: ResultCategory::File,
+                score: 95.0,
+                aria_label: None,
+            }],
+        }
+    }
+}
+
+impl Default for BrowseProvider {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl SearchProvider for BrowseProvider {
+    fn id(&self) -> &str {
+        "browse"
+    }
+
+    async fn search(&self, query: &str, ctx: &SearchContext) -> Vec<SearchResult> {
+        ctx.run_blocking(|| self.search_blocking(query))
+    }
+
+    fn execute(&self, result_id: &str) -> Result<(), String> {
+        let path = result_id
+            .strip_prefix(PREFIX)
+            .ok_or("Invalid browse result")?;
+        crate::open_file(path)
+    }
+
+    fn actions(&self, _result_id: &str) -> Vec<ResultAction> {
+        let mut actions = super::path_actions();
+        actions.push(ResultAction::new("terminal", "Open terminal here").with_shortcut("⌘T"));
+        actions
+    }
+
+    fn execute_action(&self, result_id: &str, action_id: &str) -> Result<(), String> {
+        let path = result_id
+            .strip_prefix(PREFIX)
+            .ok_or("Invalid browse result")?;
+        if action_id == "terminal" {
+            let path = Path::new(path);
+            let dir = if path.is_dir() {
+                path
+            } else {
+                path.parent().unwrap_or(path)
+            };
+            return open_terminal(dir);
+        }
+        super::execute_path_action(path, action_id)
+    }
+
+    fn context(&self, result: &SearchResult) -> ResultContext {
+        let context = ResultContext::from_result(result);
+        let Some(path) = result.id.strip_prefix(PREFIX) else {
+            return context;
+        };
+        let git_status = Path::new(path).parent().and_then(|dir| {
+            let entries = self.entries(dir).ok()?;
+            entries.iter().find(|e| e.path == path)?.git_status
+        });
+        match git_status {
+            Some(status) => context
+                .with_path(path)
+                .with_detail("Git status", status.label()),
+            None => context.with_path(path),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_queries_and_git_status() {
+        let home = Path::new("/home/ada");
+        assert_eq!(
+            parse_query("~", home),
+            Some((home.to_path_buf(), String::new()))
+        );
+        assert_eq!(
+            parse_query("~/Doc", home),
+            Some((home.to_path_buf(), "Doc".to_string()))
+        );
+        assert_eq!(
+            parse_query("browse: /etc/", home),
+            Some((PathBuf::from("/etc"), String::new()))
+        );
+        assert_eq!(parse_query("docs", home), None);
+
+        let statuses = parse_porcelain(" M src/lib.rs\0?? notes/\0R  new.rs\0old.rs\0UU a.rs\0");
+        assert_eq!(statuses.get("src/lib.rs"), Some(&GitStatus::Modified));
+        assert_eq!(statuses.get("notes"), Some(&GitStatus::Untracked));
+        assert_eq!(statuses.get("new.rs"), Some(&GitStatus::Renamed));
+        assert_eq!(statuses.get("old.rs"), None);
+        assert_eq!(statuses.get("a.rs"), Some(&GitStatus::Conflicted));
+    }
+
+    #[test]
+    fn completes_like_a_shell() {
+        let dir =
+            std::env::temp_dir().join(format!("launcher-test-browse-{}", rand::random::<u32>()));
+        std::fs::create_dir_all(dir.join("Documents")).unwrap();
+        std::fs::create_dir_all(dir.join("Downloads")).unwrap();
+        std::fs::write(dir.join("notes.txt"), "hi").unwrap();
+        let query = |rest: &str| format!("browse:{}/{}", dir.display(), rest);
+
+        let browse = BrowseProvider::new();
+        assert_eq!(browse.complete(&query("do")), Some(query("Do")));
+        assert_eq!(browse.complete(&query("Doc")), Some(query("Documents/")));
+        assert_eq!(browse.complete(&query("n")), Some(query("notes.txt")));
+        assert_eq!(browse.complete(&query("x")), None);
+
+        let page = browse.page(&dir.to_string_lossy(), "", 1, 1).unwrap();
+        assert_eq!(page.total, 3);
+        assert_eq!(page.entries[0].name, "Downloads");
+        assert_eq!(page.next_offset, Some(2));
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
@@ -1,5 +1,12 @@
-use super::{ResultCategory, ResultIcon, SearchProvider, SearchResult};
+use super::{ResultCategory, ResultIcon, SearchContext, SearchProvider, SearchResult};
+use crate::currency::{Conversion, ExchangeRates};
+use crate::dates::{self, DateAnswer};
+use crate::locale::NumberLocale;
+use crate::programmer::{self, ProgrammerAnswer};
+use crate::settings::SettingsStore;
+use async_trait::async_trait;
 use std::collections::HashMap;
+use std::sync::Arc;
 
 /// Unit conversion definitions
 struct UnitConverter {
@@ -209,7 +216,7 @@ impl UnitConverter {
         }
     }
 
-    fn normalize_unit(&self, unit: &str) -> Option<&'static str> {
+    fn normalize_unit(&self, unit: &str, locale: &NumberLocale) -> Option<&'static str> {
         let lower = unit.to_lowercase();
         let lower_ref: &str = &lower;
 
@@ -228,7 +235,8 @@ impl UnitConverter {
             return Some(canonical);
         }
 
-        None
+        // Then names in the locale's language
+        locale.unit_alias(lower_ref)
     }
 
     fn get_unit_name<'a>(&self, unit: &'a str) -> &'a str
@@ -274,22 +282,27 @@ impl UnitConverter {
     }
 
     /// Try to parse a unit conversion query like "100 km to miles" or "50kg in lb"
-    fn parse_conversion(&self, query: &str) -> Option<(f64, &'static str, &'static str)> {
+    fn parse_conversion(
+        &self,
+        query: &str,
+        locale: &NumberLocale,
+    ) -> Option<(f64, &'static str, &'static str)> {
         let query_lower = query.to_lowercase();
         let query_ref: &str = &query_lower;
 
-        // Patterns: "X unit to unit", "X unit in unit", "X unit -> unit", "X unit = unit"
+        // Patterns: "X unit to unit", "X unit in unit", "X unit -> unit", "X unit = unit",
+        // and the locale's own words, e.g. "X unit nach unit"
         let patterns = [" to ", " in ", " -> ", " => ", " = "];
 
-        for pattern in patterns {
+        for pattern in patterns.iter().chain(locale.conversion_words()) {
             if let Some(pos) = query_ref.find(pattern) {
                 let left = query_ref[..pos].trim();
                 let right = query_ref[pos + pattern.len()..].trim();
 
                 // Parse left side: "100 km" or "100km"
                 if let Some((value, from_unit)) = self.parse_value_unit(left) {
-                    if let Some(from) = self.normalize_unit(from_unit) {
-                        if let Some(to) = self.normalize_unit(right) {
+                    if let Some(from) = self.normalize_unit(from_unit, locale) {
+                        if let Some(to) = self.normalize_unit(right, locale) {
                             return Some((value, from, to));
                         }
                     }
@@ -336,12 +349,16 @@ impl UnitConverter {
 
 pub struct CalculatorProvider {
     converter: UnitConverter,
+    rates: Arc<ExchangeRates>,
+    settings: Arc<SettingsStore>,
 }
 
 impl CalculatorProvider {
-    pub fn new() -> Self {
+    pub fn new(rates: Arc<ExchangeRates>, settings: Arc<SettingsStore>) -> Self {
         Self {
             converter: UnitConverter::new(),
+            rates,
+            settings,
         }
     }
 
@@ -372,6 +389,7 @@ impl CalculatorProvider {
         has_operator || has_math_func
     }
 
+    /// The plain form that gets copied; shown through `NumberLocale::format`
     fn format_number(num: f64) -> String {
         if num.fract() == 0.0 && num.abs() < 1e15 {
             format!("{}", num as i64)
@@ -382,48 +400,137 @@ impl CalculatorProvider {
                 .to_string()
         }
     }
+
+    fn currency_result(conversion: &Conversion, locale: &NumberLocale) -> SearchResult {
+        let value = format!("{:.2}", conversion.value);
+        let converted = format!("{} {}", locale.format(&value), conversion.to);
+        let mut subtitle = format!(
+            "{} {} = {} • 1 {} = {} {}",
+            locale.format(&Self::format_number(conversion.amount)),
+            conversion.from,
+            converted,
+            conversion.from,
+            locale.format(&Self::format_number(conversion.rate)),
+            conversion.to
+        );
+        if conversion.offline {
+            let fetched_at = conversion.fetched_at.with_timezone(&chrono::Local);
+            subtitle = format!(
+                "{} • Offline, rates from {}",
+                subtitle,
+                fetched_at.format("%b %-d %H:%M")
+            );
+        }
+
+        SearchResult {
+            id: format!("calc:{} {}", value, conversion.to),
+            title: converted,
+            subtitle: Some(subtitle),
+            icon: ResultIcon::Emoji("💱".to_string()),
+            category: ResultCategory::Calculator,
+            score: 1000.0,
+            aria_label: None,
+        }
+    }
+
+    fn date_result(answer: &DateAnswer) -> SearchResult {
+        SearchResult {
+            id: format!("calc:{}", answer.value),
+            title: answer.value.clone(),
+            subtitle: Some(answer.detail.clone()),
+            icon: ResultIcon::Emoji("🕒".to_string()),
+            category: ResultCategory::Calculator,
+            score: 1000.0,
+            aria_label: None,
+        }
+    }
+
+    fn programmer_result(answer: &ProgrammerAnswer) -> SearchResult {
+        SearchResult {
+            id: format!("calc:{}", answer.value),
+            title: answer.value.clone(),
+            subtitle: Some(answer.detail.clone()),
+            icon: ResultIcon::Emoji("🧮".to_string()),
+            category: ResultCategory::Calculator,
+            score: 1000.0,
+            aria_label: None,
+        }
+    }
 }
 
+#[async_trait]
 impl SearchProvider for CalculatorProvider {
     fn id(&self) -> &str {
         "calculator"
     }
 
-    fn search(&self, query: &str) -> Vec<SearchResult> {
+    async fn search(&self, query: &str, ctx: &SearchContext) -> Vec<SearchResult> {
         let mut results = Vec::new();
+        let locale = NumberLocale::resolve(self.settings.get().calculator_locale.as_deref());
+        // `3,5 km` in German; dates and programmer math read the query as typed
+        let normalized = locale.normalize(query);
 
         // Try unit conversion first
-        if let Some((value, from, to)) = self.converter.parse_conversion(query) {
+        if let Some((value, from, to)) = self.converter.parse_conversion(&normalized, &locale) {
             if let Some(converted) = self.converter.convert(value, from, to) {
                 let from_name = self.converter.get_unit_name(from);
                 let to_name = self.converter.get_unit_name(to);
-                let formatted = Self::format_number(converted);
+                let plain = Self::format_number(converted);
+                let formatted = locale.format(&plain);
 
                 results.push(SearchResult {
-                    id: format!("calc:{} {}", formatted, to_name),
+                    id: format!("calc:{} {}", plain, to_name),
                     title: format!("{} {}", formatted, to_name),
-                    subtitle: Some(format!("{} {} = {} {}", Self::format_number(value), from_name, formatted, to_name)),
+                    subtitle: Some(format!(
+                        "{} {} = {} {}",
+                        locale.format(&Self::format_number(value)),
+                        from_name,
+                        formatted,
+                        to_name
+                    )),
                     icon: ResultIcon::Emoji("📐".to_string()),
                     category: ResultCategory::Calculator,
                     score: 1000.0,
+                    aria_label: None,
                 });
 
                 return results;
             }
         }
 
+        // Dates and times before currencies, which `3pm est in cet` would
+        // otherwise look like
+        if let Some(answer) = dates::evaluate(query) {
+            results.push(Self::date_result(&answer));
+            return results;
+        }
+
+        // Integer math meval can't do: other bases, bitwise operators and
+        // characters. `0xff to dec` would look like a currency too.
+        if let Some(answer) = programmer::evaluate(query) {
+            results.push(Self::programmer_result(&answer));
+            return results;
+        }
+
+        // Then currencies, which may have to fetch rates first
+        if let Some(conversion) = ctx.run_blocking(|| self.rates.convert(&normalized)) {
+            results.push(Self::currency_result(&conversion, &locale));
+            return results;
+        }
+
         // Try math expression
-        if Self::is_math_expression(query) {
-            if let Ok(result) = meval::eval_str(query) {
-                let formatted = Self::format_number(result);
+        if Self::is_math_expression(&normalized) {
+            if let Ok(result) = meval::eval_str(&normalized) {
+                let plain = Self::format_number(result);
 
                 results.push(SearchResult {
-                    id: format!("calc:{}", formatted),
-                    title: formatted,
+                    id: format!("calc:{}", plain),
+                    title: locale.format(&plain),
                     subtitle: Some(format!("= {}", query)),
                     icon: ResultIcon::Emoji("🔢".to_string()),
                     category: ResultCategory::Calculator,
                     score: 1000.0,
+                    aria_label: None,
                 });
             }
         }
@@ -433,9 +540,7 @@ impl SearchProvider for CalculatorProvider {
 
     fn execute(&self, result_id: &str) -> Result<(), String> {
         if let Some(value) = result_id.strip_prefix("calc:") {
-            // In a real implementation, this would copy to clipboard
-            println!("Copy to clipboard: {}", value);
-            Ok(())
+            crate::clipboard::copy_text(value)
         } else {
             Err("Invalid calculator result".to_string())
         }
@@ -0,0 +1,310 @@
+use super::files::FileProvider;
+use super::{ResultCategory, ResultIcon, SearchContext, SearchProvider, SearchResult};
+use crate::capture::{self, CaptureMode, RecordingInfo, ScreenRecorder};
+use crate::settings::SettingsStore;
+use async_trait::async_trait;
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Time for the launcher window to hide before the screen is captured
+const HIDE_DELAY: Duration = Duration::from_millis(400);
+
+/// A screenshot or recording that was just saved
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CaptureSaved {
+    pub path: PathBuf,
+    pub recording: bool,
+    /// File result for the capture, so the UI can show it right away
+    pub result: SearchResult,
+}
+
+type CaptureListener = Arc<dyn Fn(CaptureSaved) + Send + Sync>;
+
+struct CaptureAction {
+    id: &'static str,
+    name: &'static str,
+    description: &'static str,
+    icon: &'static str,
+    keywords: &'static [&'static str],
+}
+
+const ACTIONS: &[CaptureAction] = &[
+    CaptureAction {
+        id: "screenshot:full",
+        name: "Screenshot",
+        description: "Capture the whole screen",
+        icon: "📸",
+        keywords: &[
+            "screenshot",
+            "screen shot",
+            "capture screen",
+            "print screen",
+        ],
+    },
+    CaptureAction {
+        id: "screenshot:window",
+        name: "Screenshot Window",
+        description: "Capture a single window",
+        icon: "🪟",
+        keywords: &["screenshot window", "capture window"],
+    },
+    CaptureAction {
+        id: "screenshot:region",
+        name: "Screenshot Region",
+        description: "Select an area to capture",
+        icon: "✂️",
+        keywords: &[
+            "screenshot region",
+            "screenshot area",
+            "capture region",
+            "snip",
+        ],
+    },
+    CaptureAction {
+        id: "record:full",
+        name: "Record Screen",
+        description: "Start recording the whole screen",
+        icon: "🎥",
+        keywords: &["record screen", "screen recording", "record"],
+    },
+    CaptureAction {
+        id: "record:region",
+        name: "Record Screen Region",
+        description: "Select an area and start recording",
+        icon: "🎬",
+        keywords: &["record region", "record area", "screen recording region"],
+    },
+];
+
+struct CaptureState {
+    settings: Arc<SettingsStore>,
+    file_provider: Arc<FileProvider>,
+    recorder: ScreenRecorder,
+    last_capture: RwLock<Option<PathBuf>>,
+    listener: RwLock<Option<CaptureListener>>,
+}
+
+impl CaptureState {
+    fn capture_dir(&self) -> PathBuf {
+        capture::capture_dir(self.settings.get().capture_folder.as_deref())
+    }
+
+    /// Index a new capture right away and let listeners know about it
+    fn saved(&self, path: PathBuf, recording: bool) -> CaptureSaved {
+        if let Err(e) = self.file_provider.update_file(&path) {
+            eprintln!("Failed to index {}: {}", path.display(), e);
+        }
+        *self.last_capture.write() = Some(path.clone());
+
+        let event = CaptureSaved {
+            result: FileProvider::result_for_path(&path),
+            path,
+            recording,
+        };
+        let listener = self.listener.read().clone();
+        if let Some(listener) = listener {
+            listener(event.clone());
+        }
+        event
+    }
+
+    fn screenshot(&self, mode: CaptureMode) -> Result<CaptureSaved, String> {
+        let path = capture::take_screenshot(mode, &self.capture_dir())?;
+        Ok(self.saved(path, false))
+    }
+
+    fn start_recording(&self, mode: CaptureMode) -> Result<RecordingInfo, String> {
+        self.recorder.start(mode, &self.capture_dir())
+    }
+
+    fn stop_recording(&self) -> Result<CaptureSaved, String> {
+        let path = self.recorder.stop()?;
+        Ok(self.saved(path, true))
+    }
+
+    fn last_capture(&self) -> Option<PathBuf> {
+        self.last_capture.read().clone()
+    }
+}
+
+/// Screenshot and screen recording commands
+pub struct CaptureProvider {
+    state: Arc<CaptureState>,
+}
+
+impl CaptureProvider {
+    pub fn new(settings: Arc<SettingsStore>, file_provider: Arc<FileProvider>) -> Self {
+        Self {
+            state: Arc::new(CaptureState {
+                settings,
+                file_provider,
+                recorder: ScreenRecorder::new(),
+                last_capture: RwLock::new(None),
+                listener: RwLock::new(None),
+            }),
+        }
+    }
+
+    /// Called whenever a capture is saved, e.g. to notify the frontend
+    pub fn set_listener(&self, listener: impl Fn(CaptureSaved) + Send + Sync + 'static) {
+        *self.state.listener.write() = Some(Arc::new(listener));
+    }
+
+    pub fn screenshot(&self, mode: CaptureMode) -> Result<CaptureSaved, String> {
+        self.state.screenshot(mode)
+    }
+
+    pub fn start_recording(&self, mode: CaptureMode) -> Result<RecordingInfo, String> {
+        self.state.start_recording(mode)
+    }
+
+    pub fn stop_recording(&self) -> Result<CaptureSaved, String> {
+        self.state.stop_recording()
+    }
+
+    pub fn recording(&self) -> Option<RecordingInfo> {
+        self.state.recorder.current()
+    }
+
+    pub fn last_capture(&self) -> Option<PathBuf> {
+        self.state.last_capture()
+    }
+
+    fn matches(query: &str, action: &CaptureAction) -> Option<f32> {
+        let name = action.name.to_lowercase();
+        if name == query {
+            return Some(95.0);
+        }
+        if name.starts_with(query) {
+            return Some(85.0);
+        }
+        action
+            .keywords
+            .iter()
+            .any(|k| k.starts_with(query) || query.starts_with(k))
+            .then_some(70.0)
+    }
+}
+
+#[async_trait]
+impl SearchProvider for CaptureProvider {
+    fn id(&self) -> &str {
+        "capture"
+    }
+
+    async fn search(&self, query: &str, _ctx: &SearchContext) -> Vec<SearchResult> {
+        let query = query.trim().to_lowercase();
+        if query.len() < 3 {
+            return vec![];
+        }
+
+        let mut results: Vec<SearchResult> = Vec::new();
+
+        let recording = self.recording();
+        if let Some(recording) = &recording {
+            if "stop recording".starts_with(&query) || query.starts_with("record") {
+                results.push(SearchResult {
+                    id: "capture:stop".to_string(),
+                    title: "Stop Recording".to_string(),
+                    subtitle: Some(format!("Save to {}", recording.path.display())),
+                    icon: ResultIcon::Emoji("⏹️".to_string()),
+                    category: ResultCategory::Command,
+                    score: 100.0,
+                    aria_label: None,
+                });
+            }
+        }
+
+        for action in ACTIONS {
+            // Only one recording at a time
+            if action.id.starts_with("record:") && recording.is_some() {
+                continue;
+            }
+            if let Some(score) = Self::matches(&query, action) {
+                results.push(SearchResult {
+                    id: format!("capture:{}", action.id),
+                    title: action.name.to_string(),
+                    subtitle: Some(action.description.to_string()),
+                    icon: ResultIcon::Emoji(action.icon.to_string()),
+                    category: ResultCategory::Command,
+                    score,
+                    aria_label: None,
+                });
+            }
+        }
+
+        if let Some(last) = self.last_capture() {
+            if "screenshot".starts_with(&query) || query.starts_with("screenshot") {
+                let name = last
+                    .file_name()
+                    .map(|n| n.to_string_lossy().to_string())
+                    .unwrap_or_default();
+                results.push(SearchResult {
+                    id: "capture:copy".to_string(),
+                    title: "Copy Last Capture".to_string(),
+                    subtitle: Some(name.clone()),
+                    icon: ResultIcon::Emoji("📋".to_string()),
+                    category: ResultCategory::Command,
+                    score: 60.0,
+                    aria_label: None,
+                });
+                results.push(SearchResult {
+                    id: "capture:reveal".to_string(),
+                    title: "Reveal Last Capture".to_string(),
+                    subtitle: Some(name),
+                    icon: ResultIcon::Emoji("📂".to_string()),
+                    category: ResultCategory::Command,
+                    score: 59.0,
+                    aria_label: None,
+                });
+            }
+        }
+
+        results
+    }
+
+    fn execute(&self, result_id: &str) -> Result<(), String> {
+        let action = result_id
+            .strip_prefix("capture:")
+            .ok_or("Invalid capture result")?;
+
+        match action {
+            "stop" => self.stop_recording().map(|_| ()),
+            "copy" | "reveal" => {
+                let path = self.last_capture().ok_or("Nothing captured yet")?;
+                if action == "copy" {
+                    crate::clipboard::copy_image(&path)
+                } else {
+                    crate::reveal_in_folder(&path.to_string_lossy())
+                }
+            }
+            _ => {
+                let (kind, mode) = action.split_once(':').ok_or("Invalid capture result")?;
+                let mode = CaptureMode::parse(mode).ok_or("Invalid capture mode")?;
+                let recording = match kind {
+                    "screenshot" => false,
+                    "record" => true,
+                    _ => return Err(format!("Unknown capture action: {}", kind)),
+                };
+
+                // The launcher hides once this returns; capture after it's gone
+                let state = self.state.clone();
+                std::thread::spawn(move || {
+                    std::thread::sleep(HIDE_DELAY);
+                    let result = if recording {
+                        state.start_recording(mode).map(|_| ())
+                    } else {
+                        state.screenshot(mode).map(|_| ())
+                    };
+                    if let Err(e) = result {
+                        eprintln!("Capture failed: {}", e);
+                    }
+                });
+                Ok(())
+            }
+        }
+    }
+}
@@ -0,0 +1,235 @@
+use super::files::FileProvider;
+use super::{ResultCategory, ResultIcon, SearchContext, SearchProvider, SearchResult};
+use crate::cleanup::{self, CleanupPreview, CleanupTask, LargestEntries, SizedEntry};
+use async_trait::async_trait;
+use parking_lot::{Mutex, RwLock};
+use std::path::Path;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Scanning the whole index is too slow to repeat on every keystroke
+const LARGEST_CACHE_TTL: Duration = Duration::from_secs(300);
+
+const LARGEST_LIMIT: usize = 10;
+
+type ConfirmListener = Arc<dyn Fn(CleanupPreview) + Send + Sync>;
+
+struct CleanupAction {
+    id: &'static str,
+    task: CleanupTask,
+    name: &'static str,
+    icon: &'static str,
+    keywords: &'static [&'static str],
+}
+
+const ACTIONS: &[CleanupAction] = &[
+    CleanupAction {
+        id: "empty-trash",
+        task: CleanupTask::EmptyTrash,
+        name: "Empty Trash",
+        icon: "🗑️",
+        keywords: &["empty trash", "trash", "recycle bin", "clean"],
+    },
+    CleanupAction {
+        id: "clear-caches",
+        task: CleanupTask::ClearCaches,
+        name: "Clear Launcher Caches",
+        icon: "🧹",
+        keywords: &["clear cache", "cache", "clean"],
+    },
+    CleanupAction {
+        id: "flush-dns",
+        task: CleanupTask::FlushDns,
+        name: "Flush DNS Cache",
+        icon: "🌐",
+        keywords: &["flush dns", "dns", "clear dns"],
+    },
+];
+
+const LARGEST_FILES_KEYWORDS: &[&str] = &["largest files", "biggest files", "big files"];
+const LARGEST_FOLDERS_KEYWORDS: &[&str] = &["largest folders", "biggest folders", "disk usage"];
+
+fn keyword_score(query: &str, name: &str, keywords: &[&str]) -> Option<f32> {
+    let name = name.to_lowercase();
+    if name == query {
+        return Some(95.0);
+    }
+    if name.starts_with(query) {
+        return Some(85.0);
+    }
+    keywords
+        .iter()
+        .any(|k| k.starts_with(query) || query.starts_with(k))
+        .then_some(70.0)
+}
+
+/// Cleanup tasks (empty trash, clear caches, flush DNS) and the largest
+/// files and folders under the indexed roots. Running a task only asks for
+/// confirmation through the listener, which runs it once the user agrees.
+pub struct CleanupProvider {
+    file_provider: Arc<FileProvider>,
+    /// Last scan with the time it ran and the limit it was made for
+    largest: Mutex<Option<(Instant, usize, LargestEntries)>>,
+    listener: RwLock<Option<ConfirmListener>>,
+}
+
+impl CleanupProvider {
+    pub fn new(file_provider: Arc<FileProvider>) -> Self {
+        Self {
+            file_provider,
+            largest: Mutex::new(None),
+            listener: RwLock::new(None),
+        }
+    }
+
+    /// Called with a preview when a task result is run, to ask the user
+    pub fn set_listener(&self, listener: impl Fn(CleanupPreview) + Send + Sync + 'static) {
+        *self.listener.write() = Some(Arc::new(listener));
+    }
+
+    /// Largest files and folders from the index. Reuses a recent scan unless
+    /// `refresh` is set.
+    pub fn largest_entries(
+        &self,
+        limit: usize,
+        refresh: bool,
+        progress: impl FnMut(usize, usize),
+    ) -> Result<LargestEntries, String> {
+        let mut cached = self.largest.lock();
+        if let Some((scanned_at, scanned_limit, entries)) = cached.as_ref() {
+            let fresh = scanned_at.elapsed() < LARGEST_CACHE_TTL;
+            if fresh && !refresh && *scanned_limit >= limit {
+                let mut entries = entries.clone();
+                entries.files.truncate(limit);
+                entries.folders.truncate(limit);
+                return Ok(entries);
+            }
+        }
+
+        let files = self.file_provider.all_files(progress)?;
+        let roots = self.file_provider.get_config().index_paths;
+        let entries = cleanup::largest_entries(files, &roots, limit);
+        *cached = Some((Instant::now(), limit, entries.clone()));
+        Ok(entries)
+    }
+
+    fn entry_result(entry: &SizedEntry, score: f32) -> SearchResult {
+        let mut result = FileProvider::result_for_path(Path::new(&entry.path));
+        let what = if entry.is_dir {
+            "indexed files"
+        } else {
+            "file"
+        };
+        result.subtitle = Some(format!(
+            "{} {} • {}",
+            cleanup::format_bytes(entry.size),
+            what,
+            entry.path
+        ));
+        result.score = score;
+        result
+    }
+}
+
+#[async_trait]
+impl SearchProvider for CleanupProvider {
+    fn id(&self) -> &str {
+        "cleanup"
+    }
+
+    async fn search(&self, query: &str, ctx: &SearchContext) -> Vec<SearchResult> {
+        let query = query.trim().to_lowercase();
+        let query = query
+            .strip_prefix("cleanup:")
+            .map(str::trim)
+            .unwrap_or(&query);
+        let listing = query.is_empty();
+        if query.len() < 3 && !listing {
+            return vec![];
+        }
+
+        let mut results: Vec<SearchResult> = ACTIONS
+            .iter()
+            .filter_map(|action| {
+                let score = if listing {
+                    80.0
+                } else {
+                    keyword_score(query, action.name, action.keywords)?
+                };
+                Some(SearchResult {
+                    id: format!("cleanup:{}", action.id),
+                    title: action.name.to_string(),
+                    subtitle: Some("Cleanup • asks before running".to_string()),
+                    icon: ResultIcon::Emoji(action.icon.to_string()),
+                    category: ResultCategory::System,
+                    score,
+                    aria_label: None,
+                })
+            })
+            .collect();
+
+        let files = query.len() >= 5 && LARGEST_FILES_KEYWORDS.iter().any(|k| k.starts_with(query));
+        let folders = query.len() >= 5
+            && LARGEST_FOLDERS_KEYWORDS
+                .iter()
+                .any(|k| k.starts_with(query));
+        if (files || folders) && self.file_provider.is_initialized() {
+            let largest = ctx.run_blocking(|| {
+                self.largest_entries(LARGEST_LIMIT, false, |_, _| {})
+                    .unwrap_or_default()
+            });
+            let entries = if files {
+                &largest.files
+            } else {
+                &largest.folders
+            };
+            results.extend(
+                entries
+                    .iter()
+                    .enumerate()
+                    .map(|(i, entry)| Self::entry_result(entry, 90.0 - i as f32 * 0.1)),
+            );
+        }
+
+        results
+    }
+
+    fn execute(&self, result_id: &str) -> Result<(), String> {
+        let id = result_id
+            .strip_prefix("cleanup:")
+            .ok_or("Invalid cleanup result")?;
+        let action = ACTIONS
+            .iter()
+            .find(|a| a.id == id)
+            .ok_or_else(|| format!("Unknown cleanup task: {}", id))?;
+
+        let listener = self.listener.read().clone();
+        let listener = listener.ok_or("Cleanup confirmation is not available")?;
+        listener(cleanup::preview(action.task));
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_task_keywords() {
+        assert_eq!(keyword_score("empty trash", "Empty Trash", &[]), Some(95.0));
+        assert_eq!(keyword_score("flush", "Flush DNS Cache", &[]), Some(85.0));
+        assert_eq!(
+            keyword_score("dns", "Flush DNS Cache", &["dns"]),
+            Some(70.0)
+        );
+        assert_eq!(keyword_score("lock", "Empty Trash", &["trash"]), None);
+    }
+
+    #[test]
+    fn every_action_maps_to_a_task() {
+        for action in ACTIONS {
+            assert!(CleanupTask::parse(action.task.id()).is_some());
+            assert!(!action.keywords.is_empty());
+        }
+    }
+}
@@ -0,0 +1,316 @@
+use super::{
+    ResultAction, ResultCategory, ResultIcon, SearchContext, SearchProvider, SearchResult,
+};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use fuzzy_matcher::skim::SkimMatcherV2;
+use fuzzy_matcher::FuzzyMatcher;
+use parking_lot::{Mutex, RwLock};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// Unpinned entries kept; the oldest are dropped first
+const MAX_ENTRIES: usize = 500;
+
+/// Larger copies (whole files, logs) aren't worth keeping around
+const MAX_ENTRY_CHARS: usize = 100_000;
+
+const MAX_RESULTS: usize = 50;
+
+/// How often the background watcher checks the clipboard
+pub const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClipboardEntry {
+    pub id: String,
+    pub text: String,
+    pub copied_at: DateTime<Utc>,
+    #[serde(default)]
+    pub pinned: bool,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct ClipboardStore {
+    /// Newest first
+    entries: Vec<ClipboardEntry>,
+}
+
+fn preview(text: &str) -> String {
+    let flat = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if flat.chars().count() > 80 {
+        format!("{}…", flat.chars().take(80).collect::<String>())
+    } else {
+        flat
+    }
+}
+
+fn age(copied_at: DateTime<Utc>, now: DateTime<Utc>) -> String {
+    let minutes = (now - copied_at).num_minutes().max(0);
+    match minutes {
+        0 => "just now".to_string(),
+        1..=59 => format!("{} min ago", minutes),
+        60..=1439 => format!("{} h ago", minutes / 60),
+        _ => copied_at.format("%b %-d").to_string(),
+    }
+}
+
+/// Put `text` at the top of the history, reusing the entry if it was copied
+/// before, and trim unpinned entries beyond `MAX_ENTRIES`
+fn record(entries: &mut Vec<ClipboardEntry>, text: &str, now: DateTime<Utc>) {
+    let entry = match entries.iter().position(|e| e.text == text) {
+        Some(index) => {
+            let mut entry = entries.remove(index);
+            entry.copied_at = now;
+            entry
+        }
+        None => ClipboardEntry {
+            id: uuid::Uuid::new_v4().to_string(),
+            text: text.to_string(),
+            copied_at: now,
+            pinned: false,
+        },
+    };
+    entries.insert(0, entry);
+
+    let mut unpinned = 0;
+    entries.retain(|e| {
+        if e.pinned {
+            return true;
+        }
+        unpinned += 1;
+        unpinned <= MAX_ENTRIES
+    });
+}
+
+/// Text clipboard history, searched with `clip:`. A background watcher
+/// records copies; anything marked as a secret by a password manager is
+/// skipped. Pinned entries are never trimmed.
+pub struct ClipboardProvider {
+    store: RwLock<ClipboardStore>,
+    path: PathBuf,
+    /// Last clipboard text seen by `poll`, recorded or not
+    last_seen: Mutex<Option<String>>,
+    matcher: SkimMatcherV2,
+}
+
+impl ClipboardProvider {
+    pub fn new() -> Self {
+        let path = dirs::data_dir()
+            .unwrap_or_else(|| PathBuf::from("."))
+            .join("launcher")
+            .join("clipboard_history.json");
+        let store = crate::journal::load_json_store(&path).unwrap_or_default();
+
+        Self {
+            store: RwLock::new(store),
+            path,
+            last_seen: Mutex::new(None),
+            matcher: SkimMatcherV2::default(),
+        }
+    }
+
+    fn save(&self, store: &ClipboardStore) -> Result<(), String> {
+        let json = serde_json::to_string(store).map_err(|e| e.to_string())?;
+        crate::journal::write_json_store(&self.path, &json)
+    }
+
+    /// Record the clipboard if it changed since the last poll. Returns
+    /// whether the history changed.
+    pub fn poll(&self) -> bool {
+        let Ok(text) = crate::clipboard::read_text() else {
+            return false;
+        };
+        {
+            let mut last_seen = self.last_seen.lock();
+            if last_seen.as_deref() == Some(text.as_str()) {
+                return false;
+            }
+            *last_seen = Some(text.clone());
+        }
+
+        if text.trim().is_empty()
+            || text.chars().count() > MAX_ENTRY_CHARS
+            || crate::clipboard::is_concealed()
+        {
+            return false;
+        }
+
+        let mut store = self.store.write();
+        record(&mut store.entries, &text, Utc::now());
+        if let Err(e) = self.save(&store) {
+            eprintln!("Failed to save clipboard history: {}", e);
+        }
+        true
+    }
+
+    /// Entries newest first, pinned ones on top
+    pub fn entries(&self, limit: usize) -> Vec<ClipboardEntry> {
+        let store = self.store.read();
+        let (mut entries, unpinned): (Vec<_>, Vec<_>) =
+            store.entries.iter().cloned().partition(|e| e.pinned);
+        entries.extend(unpinned);
+        entries.truncate(limit);
+        entries
+    }
+
+    pub fn set_pinned(&self, id: &str, pinned: bool) -> Result<(), String> {
+        let mut store = self.store.write();
+        let entry = store
+            .entries
+            .iter_mut()
+            .find(|e| e.id == id)
+            .ok_or("Clipboard entry not found")?;
+        entry.pinned = pinned;
+        self.save(&store)
+    }
+
+    pub fn delete(&self, id: &str) -> Result<(), String> {
+        let mut store = self.store.write();
+        store.entries.retain(|e| e.id != id);
+        self.save(&store)
+    }
+
+    /// Forget all unpinned entries
+    pub fn clear(&self) -> Result<(), String> {
+        let mut store = self.store.write();
+        store.entries.retain(|e| e.pinned);
+        self.save(&store)
+    }
+
+    fn entry(&self, result_id: &str) -> Result<ClipboardEntry, String> {
+        let id = result_id
+            .strip_prefix("clip:")
+            .ok_or("Invalid clipboard result")?;
+        self.store
+            .read()
+            .entries
+            .iter()
+            .find(|e| e.id == id)
+            .cloned()
+            .ok_or_else(|| "Clipboard entry not found".to_string())
+    }
+
+    fn entry_result(&self, entry: &ClipboardEntry, score: f32, now: DateTime<Utc>) -> SearchResult {
+        let chars = entry.text.chars().count();
+        let mut subtitle = format!("{} • {} chars", age(entry.copied_at, now), chars);
+        if entry.pinned {
+            subtitle = format!("Pinned • {}", subtitle);
+        }
+        SearchResult {
+            id: format!("clip:{}", entry.id),
+            title: preview(&entry.text),
+            subtitle: Some(subtitle),
+            icon: ResultIcon::Emoji(if entry.pinned { "📌" } else { "📋" }.to_string()),
+            category: ResultCategory::Command,
+            score,
+            aria_label: None,
+        }
+    }
+}
+
+impl Default for ClipboardProvider {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl SearchProvider for ClipboardProvider {
+    fn id(&self) -> &str {
+        "clip"
+    }
+
+    async fn search(&self, query: &str, _ctx: &SearchContext) -> Vec<SearchResult> {
+        let Some(filter) = query.trim_start().strip_prefix("clip:") else {
+            return vec![];
+        };
+        let filter = filter.trim();
+        let now = Utc::now();
+
+        let entries = self.entries(usize::MAX);
+        if filter.is_empty() {
+            return entries
+                .iter()
+                .take(MAX_RESULTS)
+                .enumerate()
+                .map(|(i, entry)| self.entry_result(entry, 90.0 - i as f32 * 0.1, now))
+                .collect();
+        }
+
+        let mut matches: Vec<(i64, &ClipboardEntry)> = entries
+            .iter()
+            .filter_map(|entry| {
+                let score = self.matcher.fuzzy_match(&entry.text, filter)?;
+                // Pinned entries win ties against equally good matches
+                Some((score + if entry.pinned { 10 } else { 0 }, entry))
+            })
+            .collect();
+        matches.sort_by(|a, b| b.0.cmp(&a.0));
+        matches
+            .into_iter()
+            .take(MAX_RESULTS)
+            .enumerate()
+            .map(|(i, (_, entry))| self.entry_result(entry, 90.0 - i as f32 * 0.1, now))
+            .collect()
+    }
+
+    fn execute(&self, result_id: &str) -> Result<(), String> {
+        let entry = self.entry(result_id)?;
+        crate::clipboard::copy_text(&entry.text)
+    }
+
+    fn actions(&self, result_id: &str) -> Vec<ResultAction> {
+        let pin = match self.entry(result_id) {
+            Ok(entry) if entry.pinned => ResultAction::new("unpin", "Unpin"),
+            Ok(_) => ResultAction::new("pin", "Pin"),
+            Err(_) => return Vec::new(),
+        };
+        vec![
+            pin.with_shortcut("⌘P"),
+            ResultAction::new("delete", "Delete from history").with_shortcut("⌘⌫"),
+        ]
+    }
+
+    fn execute_action(&self, result_id: &str, action_id: &str) -> Result<(), String> {
+        let entry = self.entry(result_id)?;
+        match action_id {
+            "pin" => self.set_pinned(&entry.id, true),
+            "unpin" => self.set_pinned(&entry.id, false),
+            "delete" => self.delete(&entry.id),
+            _ => Err(format!("Unknown action: {}", action_id)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn records_newest_first_without_duplicates() {
+        let now = Utc::now();
+        let mut entries = Vec::new();
+        record(&mut entries, "a", now);
+        record(&mut entries, "b", now);
+        record(&mut entries, "a", now);
+
+        let texts: Vec<&str> = entries.iter().map(|e| e.text.as_str()).collect();
+        assert_eq!(texts, ["a", "b"]);
+    }
+
+    #[test]
+    fn trims_only_unpinned_entries() {
+        let now = Utc::now();
+        let mut entries = Vec::new();
+        record(&mut entries, "keep", now);
+        entries[0].pinned = true;
+        for i in 0..MAX_ENTRIES + 5 {
+            record(&mut entries, &i.to_string(), now);
+        }
+
+        assert_eq!(entries.len(), MAX_ENTRIES + 1);
+        assert!(entries.iter().any(|e| e.text == "keep"));
+        assert!(!entries.iter().any(|e| e.text == "0"));
+    }
+}
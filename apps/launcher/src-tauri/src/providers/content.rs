@@ -0,0 +1,163 @@
+use super::files::FileProvider;
+use super::{
+    ResultAction, ResultCategory, ResultContext, ResultIcon, SearchContext, SearchProvider,
+    SearchResult,
+};
+use crate::indexer::{extract, IndexedFile};
+use async_trait::async_trait;
+use std::path::Path;
+use std::sync::Arc;
+
+const MAX_RESULTS: usize = 20;
+
+/// Longest line excerpt shown under a result
+const SNIPPET_CHARS: usize = 80;
+
+fn snippet(line: &str) -> String {
+    if line.chars().count() <= SNIPPET_CHARS {
+        return line.to_string();
+    }
+    let cut: String = line.chars().take(SNIPPET_CHARS).collect();
+    format!("{}…", cut.trim_end())
+}
+
+/// `content: <words>` searches inside indexed files rather than by name.
+/// Text files show the first line the words appear on.
+pub struct ContentSearchProvider {
+    file_provider: Arc<FileProvider>,
+}
+
+impl ContentSearchProvider {
+    pub fn new(file_provider: Arc<FileProvider>) -> Self {
+        Self { file_provider }
+    }
+
+    fn result(file: IndexedFile, terms: &[&str], score: f32) -> SearchResult {
+        let path = Path::new(&file.path);
+        let line = if extract::is_plain_text(path) {
+            std::fs::read_to_string(path)
+                .ok()
+                .and_then(|text| extract::matching_line(&text, terms))
+        } else {
+            None
+        };
+        let subtitle = match line {
+            Some((number, line)) => format!("{}: {} • {}", number, snippet(&line), file.path),
+            None => file.path.clone(),
+        };
+        let extension = file.extension.as_deref().map(str::to_lowercase);
+
+        SearchResult {
+            id: format!("content:{}", file.path),
+            title: file.name,
+            subtitle: Some(subtitle),
+            icon: FileProvider::get_file_icon(&file.path, &extension, file.is_dir),
+            category: ResultCategory::File,
+            score,
+            aria_label: None,
+        }
+    }
+
+    fn results(&self, query: &str) -> Vec<SearchResult> {
+        let files = match self.file_provider.content_search(query, MAX_RESULTS) {
+            Ok(files) => files,
+            Err(e) => {
+                eprintln!("Content search failed: {}", e);
+                return vec![];
+            }
+        };
+        if files.is_empty() {
+            return vec![SearchResult {
+                id: "content:none".to_string(),
+                title: format!("No files contain \"{}\"", query),
+                subtitle: Some("Only indexed text files and PDFs are searched".to_string()),
+                icon: ResultIcon::Emoji("🔎".to_string()),
+                category: ResultCategory::File,
+                score: 80.0,
+                aria_label: None,
+            }];
+        }
+
+        let terms: Vec<&str> = query.split_whitespace().collect();
+        files
+            .into_iter()
+            .enumerate()
+            .map(|(i, file)| Self::result(file, &terms, 90.0 - i as f32 * 0.1))
+            .collect()
+    }
+
+    fn path(result_id: &str) -> Result<&str, String> {
+        result_id
+            .strip_prefix("content:")
+            .filter(|p| *p != "none")
+            .ok_or_else(|| "Invalid content search result".to_string())
+    }
+}
+
+#[async_trait]
+impl SearchProvider for ContentSearchProvider {
+    fn id(&self) -> &str {
+        "content"
+    }
+
+    async fn search(&self, query: &str, ctx: &SearchContext) -> Vec<SearchResult> {
+        let Some(input) = query.trim_start().strip_prefix("content:") else {
+            return vec![];
+        };
+        let input = input.trim();
+        if input.is_empty() || !self.file_provider.is_initialized() {
+            return vec![];
+        }
+
+        ctx.run_blocking(|| self.results(input))
+    }
+
+    fn execute(&self, result_id: &str) -> Result<(), String> {
+        if result_id == "content:none" {
+            return Ok(());
+        }
+        crate::open_file(Self::path(result_id)?)
+    }
+
+    fn actions(&self, result_id: &str) -> Vec<ResultAction> {
+        if Self::path(result_id).is_err() {
+            return Vec::new();
+        }
+        super::path_actions()
+    }
+
+    fn execute_action(&self, result_id: &str, action_id: &str) -> Result<(), String> {
+        super::execute_path_action(Self::path(result_id)?, action_id)
+    }
+
+    fn context(&self, result: &SearchResult) -> ResultContext {
+        let context = ResultContext::from_result(result);
+        match Self::path(&result.id) {
+            Ok(path) => context.with_path(path),
+            Err(_) => context,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shortens_long_lines() {
+        assert_eq!(snippet("short line"), "short line");
+        let long = "word ".repeat(30);
+        let cut = snippet(&long);
+        assert!(cut.ends_with('…'));
+        assert_eq!(cut.chars().count(), SNIPPET_CHARS);
+    }
+
+    #[test]
+    fn rejects_placeholder_result() {
+        assert_eq!(
+            ContentSearchProvider::path("content:/a/b.md"),
+            Ok("/a/b.md")
+        );
+        assert!(ContentSearchProvider::path("content:none").is_err());
+    }
+}
@@ -0,0 +1,534 @@
+use super::{ResultCategory, ResultIcon, SearchContext, SearchProvider, SearchResult};
+use crate::search_cache::CachePolicy;
+use async_trait::async_trait;
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+const WORDNET_URL: &str = "https://wordnetcode.princeton.edu/wn3.1.dict.tar.gz";
+/// cmudict commit the pronunciations are downloaded from, and the hex
+/// SHA-256 of its `cmudict.dict`. Both move together; a file that doesn't
+/// match (or has no pin) is skipped, since pronunciations are optional.
+const CMUDICT_COMMIT: &str = "";
+const CMUDICT_SHA256: &str = "";
+const PRONUNCIATION_FILE: &str = "cmudict.dict";
+const MAX_SENSES: usize = 8;
+
+/// WordNet file suffix and display name for each part of speech
+const PARTS_OF_SPEECH: &[(&str, &str)] = &[
+    ("noun", "noun"),
+    ("verb", "verb"),
+    ("adj", "adjective"),
+    ("adv", "adverb"),
+];
+
+/// Where an installed WordNet database may already live
+const SYSTEM_WORDNET_DIRS: &[&str] = &[
+    "/usr/share/wordnet",
+    "/usr/local/share/wordnet",
+    "/usr/share/wordnet/dict",
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DictionaryStatus {
+    pub installed: bool,
+    /// Folder the WordNet database is read from
+    pub source: Option<PathBuf>,
+    pub pronunciations: bool,
+}
+
+/// One meaning of a word
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WordSense {
+    pub part_of_speech: String,
+    pub definition: String,
+    pub examples: Vec<String>,
+    pub synonyms: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DictionaryEntry {
+    pub word: String,
+    pub pronunciation: Option<String>,
+    pub senses: Vec<WordSense>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Synset {
+    words: Vec<String>,
+    definition: String,
+    examples: Vec<String>,
+}
+
+/// Parse a line of a WordNet `data.*` file:
+/// `offset lex_filenum ss_type w_cnt word lex_id [word lex_id...] ... | gloss`
+fn parse_synset(line: &str) -> Option<Synset> {
+    let (fields, gloss) = line.split_once(" | ").unwrap_or((line, ""));
+    let parts: Vec<&str> = fields.split_whitespace().collect();
+    let count = usize::from_str_radix(parts.get(3)?, 16).ok()?;
+
+    let words = (0..count)
+        .filter_map(|i| parts.get(4 + i * 2))
+        .map(|w| {
+            // Adjectives may carry a syntactic marker like "(p)"
+            let w = w.split('(').next().unwrap_or(w);
+            w.replace('_', " ")
+        })
+        .collect();
+
+    let mut pieces = gloss.trim().split("; \"");
+    let definition = pieces.next().unwrap_or("").trim().to_string();
+    let examples = pieces
+        .map(|e| e.trim().trim_end_matches(';').trim_matches('"').to_string())
+        .filter(|e| !e.is_empty())
+        .collect();
+
+    Some(Synset {
+        words,
+        definition,
+        examples,
+    })
+}
+
+/// Parse a line of a WordNet `index.*` file into its lemma and synset offsets
+fn parse_index_line(line: &str) -> Option<(String, Vec<u64>)> {
+    let parts: Vec<&str> = line.split_whitespace().collect();
+    let lemma = parts.first()?.to_string();
+    let synset_count: usize = parts.get(2)?.parse().ok()?;
+    let offsets = parts
+        .get(parts.len().checked_sub(synset_count)?..)?
+        .iter()
+        .filter_map(|o| o.parse().ok())
+        .collect();
+    Some((lemma, offsets))
+}
+
+/// ARPAbet pronunciation from the CMU dictionary, without stress markers
+fn format_pronunciation(phones: &str) -> String {
+    let phones: Vec<String> = phones
+        .split_whitespace()
+        .map(|p| p.trim_end_matches(|c: char| c.is_ascii_digit()).to_string())
+        .collect();
+    format!("/{}/", phones.join(" ").to_lowercase())
+}
+
+/// An opened WordNet database. The index is kept in memory; synsets are read
+/// from the data files by offset as needed.
+struct WordNet {
+    dir: PathBuf,
+    /// Lemma -> (part of speech index, synset offsets)
+    index: HashMap<String, Vec<(usize, Vec<u64>)>>,
+    pronunciations: HashMap<String, String>,
+}
+
+impl WordNet {
+    fn open(dir: &Path, pronunciation_file: Option<PathBuf>) -> Result<Self, String> {
+        let mut index: HashMap<String, Vec<(usize, Vec<u64>)>> = HashMap::new();
+        for (pos, (suffix, _)) in PARTS_OF_SPEECH.iter().enumerate() {
+            let path = dir.join(format!("index.{}", suffix));
+            let file = std::fs::File::open(&path)
+                .map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+            // License header lines start with a space
+            for line in BufReader::new(file).lines().map_while(Result::ok) {
+                if line.starts_with(' ') {
+                    continue;
+                }
+                if let Some((lemma, offsets)) = parse_index_line(&line) {
+                    index.entry(lemma).or_default().push((pos, offsets));
+                }
+            }
+        }
+
+        let mut pronunciations = HashMap::new();
+        if let Some(file) = pronunciation_file.and_then(|p| std::fs::File::open(p).ok()) {
+            for line in BufReader::new(file).lines().map_while(Result::ok) {
+                let Some((word, phones)) = line.split_once(' ') else {
+                    continue;
+                };
+                // Alternate pronunciations look like "word(2)"; keep the first
+                if !word.contains('(') {
+                    let phones = phones.split('#').next().unwrap_or(phones);
+                    pronunciations.insert(word.to_lowercase(), format_pronunciation(phones));
+                }
+            }
+        }
+
+        Ok(Self {
+            dir: dir.to_path_buf(),
+            index,
+            pronunciations,
+        })
+    }
+
+    fn read_synset(&self, pos: usize, offset: u64) -> Option<Synset> {
+        let path = self
+            .dir
+            .join(format!("data.{}", PARTS_OF_SPEECH.get(pos)?.0));
+        let mut file = std::fs::File::open(path).ok()?;
+        file.seek(SeekFrom::Start(offset)).ok()?;
+        let mut line = String::new();
+        BufReader::new(file).read_line(&mut line).ok()?;
+        parse_synset(line.trim_end())
+    }
+
+    /// Find the lemma for a word, trying a few common inflections
+    fn lemma_for(&self, word: &str) -> Option<String> {
+        let word = word.trim().to_lowercase().replace(' ', "_");
+        if self.index.contains_key(&word) {
+            return Some(word);
+        }
+        let candidates = [
+            word.strip_suffix("ies").map(|w| format!("{}y", w)),
+            word.strip_suffix("es").map(str::to_string),
+            word.strip_suffix('s').map(str::to_string),
+            word.strip_suffix("ing").map(str::to_string),
+            word.strip_suffix("ing").map(|w| format!("{}e", w)),
+            word.strip_suffix("ed").map(str::to_string),
+            word.strip_suffix('d').map(str::to_string),
+        ];
+        candidates
+            .into_iter()
+            .flatten()
+            .find(|w| self.index.contains_key(w))
+    }
+
+    fn lookup(&self, word: &str) -> Option<DictionaryEntry> {
+        let lemma = self.lemma_for(word)?;
+        let display = lemma.replace('_', " ");
+
+        let mut senses = Vec::new();
+        for (pos, offsets) in self.index.get(&lemma)? {
+            for offset in offsets {
+                let Some(synset) = self.read_synset(*pos, *offset) else {
+                    continue;
+                };
+                senses.push(WordSense {
+                    part_of_speech: PARTS_OF_SPEECH[*pos].1.to_string(),
+                    definition: synset.definition,
+                    examples: synset.examples,
+                    synonyms: synset
+                        .words
+                        .into_iter()
+                        .filter(|w| !w.eq_ignore_ascii_case(&display))
+                        .collect(),
+                });
+            }
+        }
+
+        Some(DictionaryEntry {
+            pronunciation: self.pronunciations.get(&lemma).cloned(),
+            word: display,
+            senses,
+        })
+    }
+}
+
+/// Download the WordNet database and CMU pronunciations into `dir`
+fn install_pack(dir: &Path) -> Result<(), String> {
+    std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    let client = reqwest::blocking::Client::builder()
+        .timeout(std::time::Duration::from_secs(300))
+        .build()
+        .map_err(|e| e.to_string())?;
+
+    let archive = client
+        .get(WORDNET_URL)
+        .send()
+        .and_then(|r| r.error_for_status())
+        .and_then(|r| r.bytes())
+        .map_err(|e| format!("Failed to download WordNet: {}", e))?;
+
+    let mut tar = tar::Archive::new(flate2::read::GzDecoder::new(&archive[..]));
+    for entry in tar.entries().map_err(|e| e.to_string())? {
+        let mut entry = entry.map_err(|e| e.to_string())?;
+        let name = entry
+            .path()
+            .ok()
+            .and_then(|p| p.file_name().map(|n| n.to_string_lossy().to_string()))
+            .unwrap_or_default();
+        // Only the index and data files are needed; never trust archive paths
+        let wanted = PARTS_OF_SPEECH.iter().any(|(suffix, _)| {
+            name == format!("index.{}", suffix) || name == format!("data.{}", suffix)
+        });
+        if wanted {
+            let mut bytes = Vec::new();
+            entry.read_to_end(&mut bytes).map_err(|e| e.to_string())?;
+            std::fs::write(dir.join(&name), bytes).map_err(|e| e.to_string())?;
+        }
+    }
+
+    // Pronunciations are optional
+    let url = format!(
+        "https://raw.githubusercontent.com/cmusphinx/cmudict/{}/{}",
+        CMUDICT_COMMIT, PRONUNCIATION_FILE
+    );
+    match client
+        .get(url)
+        .send()
+        .and_then(|r| r.error_for_status())
+        .and_then(|r| r.bytes())
+    {
+        Ok(bytes) => {
+            let actual = format!("{:x}", Sha256::digest(&bytes));
+            if !CMUDICT_SHA256.is_empty() && actual.eq_ignore_ascii_case(CMUDICT_SHA256) {
+                let _ = std::fs::write(dir.join(PRONUNCIATION_FILE), bytes);
+            } else {
+                eprintln!(
+                    "Pronunciations don't match the pinned checksum (got {})",
+                    actual
+                );
+            }
+        }
+        Err(e) => eprintln!("Failed to download pronunciations: {}", e),
+    }
+    Ok(())
+}
+
+/// Offline dictionary and thesaurus backed by WordNet, triggered with `define:`
+pub struct DictionaryProvider {
+    data_dir: PathBuf,
+    wordnet: RwLock<Option<Arc<WordNet>>>,
+}
+
+impl DictionaryProvider {
+    pub fn new() -> Self {
+        let data_dir = dirs::data_dir()
+            .unwrap_or_else(|| PathBuf::from("."))
+            .join("launcher")
+            .join("dictionary");
+        Self {
+            data_dir,
+            wordnet: RwLock::new(None),
+        }
+    }
+
+    fn has_database(dir: &Path) -> bool {
+        PARTS_OF_SPEECH.iter().all(|(suffix, _)| {
+            dir.join(format!("index.{}", suffix)).exists()
+                && dir.join(format!("data.{}", suffix)).exists()
+        })
+    }
+
+    /// The downloaded data pack, or a system WordNet install
+    fn database_dir(&self) -> Option<PathBuf> {
+        let env_dir = std::env::var_os("WNSEARCHDIR").map(PathBuf::from);
+        std::iter::once(self.data_dir.clone())
+            .chain(env_dir)
+            .chain(SYSTEM_WORDNET_DIRS.iter().map(PathBuf::from))
+            .find(|dir| Self::has_database(dir))
+    }
+
+    pub fn status(&self) -> DictionaryStatus {
+        DictionaryStatus {
+            source: self.database_dir(),
+            installed: self.database_dir().is_some(),
+            pronunciations: self.data_dir.join(PRONUNCIATION_FILE).exists(),
+        }
+    }
+
+    fn wordnet(&self) -> Option<Arc<WordNet>> {
+        if let Some(wordnet) = self.wordnet.read().as_ref() {
+            return Some(wordnet.clone());
+        }
+
+        let dir = self.database_dir()?;
+        let pronunciations = Some(self.data_dir.join(PRONUNCIATION_FILE)).filter(|p| p.exists());
+        match WordNet::open(&dir, pronunciations) {
+            Ok(wordnet) => {
+                let wordnet = Arc::new(wordnet);
+                *self.wordnet.write() = Some(wordnet.clone());
+                Some(wordnet)
+            }
+            Err(e) => {
+                eprintln!("Failed to load dictionary: {}", e);
+                None
+            }
+        }
+    }
+
+    pub fn lookup(&self, word: &str) -> Option<DictionaryEntry> {
+        self.wordnet()?.lookup(word)
+    }
+
+    /// Download the WordNet data pack into the data dir
+    pub fn install(&self) -> Result<DictionaryStatus, String> {
+        install_pack(&self.data_dir)?;
+        *self.wordnet.write() = None;
+        let status = self.status();
+        if status.installed {
+            Ok(status)
+        } else {
+            Err("The downloaded dictionary is incomplete".to_string())
+        }
+    }
+
+    fn search_blocking(&self, query: &str) -> Vec<SearchResult> {
+        let query = query.trim();
+        let Some(word) = query
+            .strip_prefix("define:")
+            .or_else(|| query.strip_prefix("define "))
+            .map(str::trim)
+        else {
+            return vec![];
+        };
+        if word.is_empty() {
+            return vec![];
+        }
+
+        let Some(wordnet) = self.wordnet() else {
+            return vec![SearchResult {
+                id: "dict:install".to_string(),
+                title: "Download Dictionary".to_string(),
+                subtitle: Some(
+                    "WordNet data pack (about 12 MB) for offline definitions".to_string(),
+                ),
+                icon: ResultIcon::Emoji("⬇️".to_string()),
+                category: ResultCategory::Command,
+                score: 60.0,
+                aria_label: None,
+            }];
+        };
+
+        let Some(entry) = wordnet.lookup(word) else {
+            return vec![SearchResult {
+                id: "dict:none".to_string(),
+                title: format!("No definition for \"{}\"", word),
+                subtitle: None,
+                icon: ResultIcon::Emoji("📖".to_string()),
+                category: ResultCategory::Command,
+                score: 50.0,
+                aria_label: None,
+            }];
+        };
+
+        let mut results = Vec::new();
+        for (i, sense) in entry.senses.iter().take(MAX_SENSES).enumerate() {
+            let mut title = format!("{} ({})", entry.word, sense.part_of_speech);
+            if i == 0 {
+                if let Some(pronunciation) = &entry.pronunciation {
+                    title = format!("{} {}", title, pronunciation);
+                }
+            }
+            results.push(SearchResult {
+                id: format!("dict:copy:{}", urlencoding::encode(&sense.definition)),
+                title,
+                subtitle: Some(sense.definition.clone()),
+                icon: ResultIcon::Emoji("📖".to_string()),
+                category: ResultCategory::Command,
+                score: 95.0 - i as f32,
+                aria_label: None,
+            });
+        }
+
+        // Thesaurus: synonyms across all senses, in sense order
+        let mut seen = std::collections::HashSet::new();
+        let synonyms: Vec<&str> = entry
+            .senses
+            .iter()
+            .flat_map(|s| s.synonyms.iter())
+            .filter(|s| seen.insert(s.to_lowercase()))
+            .map(String::as_str)
+            .take(12)
+            .collect();
+        if !synonyms.is_empty() {
+            let synonyms = synonyms.join(", ");
+            results.push(SearchResult {
+                id: format!("dict:copy:{}", urlencoding::encode(&synonyms)),
+                title: format!("Synonyms for {}", entry.word),
+                subtitle: Some(synonyms),
+                icon: ResultIcon::Emoji("🔁".to_string()),
+                category: ResultCategory::Command,
+                score: 80.0,
+                aria_label: None,
+            });
+        }
+
+        results
+    }
+}
+
+impl Default for DictionaryProvider {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl SearchProvider for DictionaryProvider {
+    fn id(&self) -> &str {
+        "dict"
+    }
+
+    async fn search(&self, query: &str, ctx: &SearchContext) -> Vec<SearchResult> {
+        ctx.run_blocking(|| self.search_blocking(query))
+    }
+
+    fn cache_policy(&self, query: &str) -> CachePolicy {
+        // Lookups read the data files; a definition doesn't change
+        if query.trim_start().starts_with("define") {
+            CachePolicy::For(std::time::Duration::from_secs(10))
+        } else {
+            CachePolicy::Never
+        }
+    }
+
+    fn execute(&self, result_id: &str) -> Result<(), String> {
+        let action = result_id
+            .strip_prefix("dict:")
+            .ok_or("Invalid dictionary result")?;
+
+        if let Some(text) = action.strip_prefix("copy:") {
+            let text = urlencoding::decode(text).map_err(|e| e.to_string())?;
+            return crate::clipboard::copy_text(&text);
+        }
+        match action {
+            "install" => {
+                // Large download; don't hold the window open for it
+                let data_dir = self.data_dir.clone();
+                std::thread::spawn(move || {
+                    if let Err(e) = install_pack(&data_dir) {
+                        eprintln!("Failed to install dictionary: {}", e);
+                    }
+                });
+                Ok(())
+            }
+            "none" => Ok(()),
+            _ => Err(format!("Unknown dictionary action: {}", action)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_wordnet_data_line() {
+        let line = "01784295 00 s 02 frightened 0 scared 0 001 & 01783158 a 0000 | made afraid; \"the frightened child cowered\"; \"a scared puppy\"";
+
+        assert_eq!(
+            parse_synset(line),
+            Some(Synset {
+                words: vec!["frightened".to_string(), "scared".to_string()],
+                definition: "made afraid".to_string(),
+                examples: vec![
+                    "the frightened child cowered".to_string(),
+                    "a scared puppy".to_string()
+                ],
+            })
+        );
+    }
+
+    #[test]
+    fn parses_wordnet_index_line() {
+        let line = "scare n 2 3 @ ~ + 2 0 07534700 07533577";
+        assert_eq!(
+            parse_index_line(line),
+            Some(("scare".to_string(), vec![7534700, 7533577]))
+        );
+    }
+}
@@ -0,0 +1,411 @@
+use super::files::FileProvider;
+use super::{
+    ResultAction, ResultCategory, ResultContext, ResultIcon, SearchContext, SearchProvider,
+    SearchResult,
+};
+use crate::cleanup::{format_bytes, SizedEntry};
+use crate::indexer::IndexedFile;
+use crate::undo::{UndoStack, UndoableAction};
+use async_trait::async_trait;
+use parking_lot::RwLock;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+const MAX_RESULTS: usize = 30;
+
+/// Sizes of indexed files and the folders above them, up to the index
+/// roots. Built once from the index and then kept current from the
+/// index's change notifications.
+#[derive(Default)]
+struct UsageTree {
+    /// Index generation the tree was built from
+    generation: Option<u64>,
+    roots: Vec<PathBuf>,
+    /// `PathBuf` orders by component, so everything below a path follows
+    /// it directly
+    files: BTreeMap<PathBuf, u64>,
+    dirs: BTreeMap<PathBuf, u64>,
+}
+
+impl UsageTree {
+    fn build(generation: u64, roots: Vec<PathBuf>, files: Vec<IndexedFile>) -> Self {
+        let mut tree = Self {
+            generation: Some(generation),
+            roots,
+            ..Default::default()
+        };
+        for file in files.into_iter().filter(|f| !f.is_dir) {
+            tree.add(PathBuf::from(file.path), file.size);
+        }
+        tree
+    }
+
+    fn root_of(&self, path: &Path) -> Option<PathBuf> {
+        self.roots.iter().find(|r| path.starts_with(r)).cloned()
+    }
+
+    fn adjust_ancestors(&mut self, path: &Path, delta: i64) {
+        let Some(root) = self.root_of(path) else {
+            return;
+        };
+        for ancestor in path.ancestors().skip(1) {
+            if !ancestor.starts_with(&root) {
+                break;
+            }
+            let size = self.dirs.entry(ancestor.to_path_buf()).or_default();
+            *size = size.saturating_add_signed(delta);
+        }
+    }
+
+    fn add(&mut self, path: PathBuf, size: u64) {
+        if self.root_of(&path).is_none() {
+            return;
+        }
+        let old = self.files.insert(path.clone(), size).unwrap_or(0);
+        self.adjust_ancestors(&path, size as i64 - old as i64);
+    }
+
+    /// Forget a file, or a folder with everything below it
+    fn remove(&mut self, path: &Path) {
+        if let Some(size) = self.files.remove(path) {
+            self.adjust_ancestors(path, -(size as i64));
+            return;
+        }
+
+        let below: Vec<(PathBuf, u64)> = self
+            .files
+            .range(path.to_path_buf()..)
+            .take_while(|(p, _)| p.starts_with(path))
+            .map(|(p, size)| (p.clone(), *size))
+            .collect();
+        let dirs: Vec<PathBuf> = self
+            .dirs
+            .range(path.to_path_buf()..)
+            .take_while(|(p, _)| p.starts_with(path))
+            .map(|(p, _)| p.clone())
+            .collect();
+        for dir in dirs {
+            self.dirs.remove(&dir);
+        }
+
+        let total: u64 = below.iter().map(|(_, size)| size).sum();
+        for (file, _) in below {
+            self.files.remove(&file);
+        }
+        self.adjust_ancestors(path, -(total as i64));
+    }
+
+    /// Re-read a path the index reported as changed
+    fn refresh(&mut self, path: &Path) {
+        match std::fs::symlink_metadata(path) {
+            Ok(metadata) if metadata.is_file() => self.add(path.to_path_buf(), metadata.len()),
+            // A folder has no size of its own; its files are reported on their own
+            Ok(metadata) if metadata.is_dir() => {}
+            _ => self.remove(path),
+        }
+    }
+
+    fn size_of(&self, path: &Path) -> Option<u64> {
+        self.dirs
+            .get(path)
+            .or_else(|| self.files.get(path))
+            .copied()
+    }
+
+    /// Files and folders directly inside `dir`, largest first
+    fn children(&self, dir: &Path) -> Vec<SizedEntry> {
+        let entries = |map: &BTreeMap<PathBuf, u64>, is_dir: bool| {
+            map.range(dir.to_path_buf()..)
+                .take_while(|(p, _)| p.starts_with(dir))
+                .filter(|(p, _)| p.parent() == Some(dir))
+                .map(|(p, size)| SizedEntry {
+                    path: p.to_string_lossy().into_owned(),
+                    size: *size,
+                    is_dir,
+                })
+                .collect::<Vec<_>>()
+        };
+        let mut children = entries(&self.dirs, true);
+        children.extend(entries(&self.files, false));
+        children.sort_by(|a, b| b.size.cmp(&a.size).then_with(|| a.path.cmp(&b.path)));
+        children
+    }
+}
+
+fn expand_home(input: &str) -> PathBuf {
+    match input.strip_prefix('~') {
+        Some(rest) => dirs::home_dir()
+            .unwrap_or_default()
+            .join(rest.trim_start_matches(['/', '\\'])),
+        None => PathBuf::from(input),
+    }
+}
+
+/// `du: <path>` ranks the folders and files inside a path by size, using the
+/// sizes stored in the file index. Partial names filter the parent folder,
+/// so `du: ~/Doc` lists matches in the home folder.
+pub struct DiskUsageProvider {
+    file_provider: Arc<FileProvider>,
+    tree: Arc<RwLock<UsageTree>>,
+    undo: Arc<UndoStack>,
+}
+
+impl DiskUsageProvider {
+    pub fn new(file_provider: Arc<FileProvider>, undo: Arc<UndoStack>) -> Self {
+        let tree: Arc<RwLock<UsageTree>> = Arc::default();
+
+        let changes = tree.clone();
+        file_provider.on_change(move |paths| {
+            let mut tree = changes.write();
+            // Not built yet, the first search reads the index anyway
+            if tree.generation.is_none() {
+                return;
+            }
+            for path in paths {
+                tree.refresh(path);
+            }
+        });
+
+        Self {
+            file_provider,
+            tree,
+            undo,
+        }
+    }
+
+    /// Build the tree from the index unless it is current. Returns whether
+    /// a tree is available.
+    fn ensure_built(&self) -> bool {
+        if !self.file_provider.is_initialized() {
+            return false;
+        }
+        let generation = self.file_provider.generation();
+        if self.tree.read().generation == Some(generation) {
+            return true;
+        }
+
+        match self.file_provider.all_files(|_, _| {}) {
+            Ok(files) => {
+                let roots = self.file_provider.get_config().index_paths;
+                *self.tree.write() = UsageTree::build(generation, roots, files);
+                true
+            }
+            Err(e) => {
+                eprintln!("Failed to read the index for du: {}", e);
+                false
+            }
+        }
+    }
+
+    fn entry_result(entry: &SizedEntry, parent_size: Option<u64>, score: f32) -> SearchResult {
+        let path = Path::new(&entry.path);
+        let mut subtitle = format_bytes(entry.size);
+        if let Some(parent_size) = parent_size.filter(|s| *s > 0) {
+            let share = entry.size as f64 / parent_size as f64 * 100.0;
+            subtitle = format!("{} • {:.0}%", subtitle, share);
+        }
+
+        SearchResult {
+            id: format!("du:{}", entry.path),
+            title: path
+                .file_name()
+                .map(|n| n.to_string_lossy().into_owned())
+                .unwrap_or_else(|| entry.path.clone()),
+            subtitle: Some(format!("{} • {}", subtitle, entry.path)),
+            icon: ResultIcon::Emoji(if entry.is_dir { "📁" } else { "📄" }.to_string()),
+            category: ResultCategory::File,
+            score,
+            aria_label: None,
+        }
+    }
+
+    fn results(&self, input: &str) -> Vec<SearchResult> {
+        let tree = self.tree.read();
+        let rank = |entries: Vec<SizedEntry>, parent_size: Option<u64>| {
+            entries
+                .iter()
+                .take(MAX_RESULTS)
+                .enumerate()
+                .map(|(i, entry)| Self::entry_result(entry, parent_size, 90.0 - i as f32 * 0.1))
+                .collect::<Vec<_>>()
+        };
+
+        if input.is_empty() {
+            let mut roots: Vec<SizedEntry> = tree
+                .roots
+                .iter()
+                .map(|root| SizedEntry {
+                    path: root.to_string_lossy().into_owned(),
+                    size: tree.size_of(root).unwrap_or(0),
+                    is_dir: true,
+                })
+                .collect();
+            roots.sort_by(|a, b| b.size.cmp(&a.size));
+            return rank(roots, None);
+        }
+
+        let path = expand_home(input);
+        if tree.dirs.contains_key(&path) {
+            return rank(tree.children(&path), tree.size_of(&path));
+        }
+
+        // A partial name: filter the folder it's typed in
+        let (Some(parent), Some(prefix)) = (path.parent(), path.file_name()) else {
+            return vec![];
+        };
+        let prefix = prefix.to_string_lossy().to_lowercase();
+        let matches: Vec<SizedEntry> = tree
+            .children(parent)
+            .into_iter()
+            .filter(|entry| {
+                Path::new(&entry.path)
+                    .file_name()
+                    .is_some_and(|n| n.to_string_lossy().to_lowercase().starts_with(&prefix))
+            })
+            .collect();
+        if !matches.is_empty() {
+            return rank(matches, tree.size_of(parent));
+        }
+
+        if tree.root_of(&path).is_none() {
+            return vec![SearchResult {
+                id: "du:none".to_string(),
+                title: "Not in the file index".to_string(),
+                subtitle: Some("du: only covers indexed folders".to_string()),
+                icon: ResultIcon::Emoji("📁".to_string()),
+                category: ResultCategory::File,
+                score: 80.0,
+                aria_label: None,
+            }];
+        }
+        vec![]
+    }
+
+    fn path(result_id: &str) -> Result<&str, String> {
+        result_id
+            .strip_prefix("du:")
+            .filter(|p| *p != "none")
+            .ok_or_else(|| "Invalid disk usage result".to_string())
+    }
+
+    /// Move a file or folder to the trash and drop it from the index
+    fn trash(&self, path: &str) -> Result<(), String> {
+        let trashed = crate::file_ops::move_to_trash(Path::new(path))?;
+        self.undo.record(UndoableAction::TrashFile {
+            path: PathBuf::from(path),
+            trashed,
+        });
+        self.tree.write().remove(Path::new(path));
+        self.file_provider.update_file(Path::new(path))
+    }
+}
+
+#[async_trait]
+impl SearchProvider for DiskUsageProvider {
+    fn id(&self) -> &str {
+        "du"
+    }
+
+    async fn search(&self, query: &str, ctx: &SearchContext) -> Vec<SearchResult> {
+        let Some(input) = query.trim_start().strip_prefix("du:") else {
+            return vec![];
+        };
+        let input = input.trim();
+
+        if !ctx.run_blocking(|| self.ensure_built()) {
+            return vec![];
+        }
+        self.results(input)
+    }
+
+    fn execute(&self, result_id: &str) -> Result<(), String> {
+        if result_id == "du:none" {
+            return Ok(());
+        }
+        crate::open_file(Self::path(result_id)?)
+    }
+
+    fn actions(&self, result_id: &str) -> Vec<ResultAction> {
+        if Self::path(result_id).is_err() {
+            return Vec::new();
+        }
+        let mut actions = super::path_actions();
+        actions.push(ResultAction::new("delete", "Move to Trash").with_shortcut("⌘⌫"));
+        actions
+    }
+
+    fn execute_action(&self, result_id: &str, action_id: &str) -> Result<(), String> {
+        let path = Self::path(result_id)?;
+        match action_id {
+            "delete" => self.trash(path),
+            _ => super::execute_path_action(path, action_id),
+        }
+    }
+
+    fn context(&self, result: &SearchResult) -> ResultContext {
+        let context = ResultContext::from_result(result);
+        match Self::path(&result.id) {
+            Ok(path) => context.with_path(path),
+            Err(_) => context,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn tree() -> UsageTree {
+        let file = |path: &str, size| IndexedFile {
+            path: path.to_string(),
+            name: String::new(),
+            extension: None,
+            size,
+            modified: Utc::now(),
+            is_dir: false,
+        };
+        UsageTree::build(
+            1,
+            vec![PathBuf::from("/r")],
+            vec![
+                file("/r/a/big.iso", 500),
+                file("/r/a/b/small.txt", 10),
+                file("/r/notes.md", 40),
+                file("/r/a-side.txt", 5),
+            ],
+        )
+    }
+
+    #[test]
+    fn ranks_children_by_size() {
+        let tree = tree();
+        let children: Vec<(String, u64)> = tree
+            .children(Path::new("/r"))
+            .into_iter()
+            .map(|e| (e.path, e.size))
+            .collect();
+        assert_eq!(
+            children,
+            [
+                ("/r/a".to_string(), 510),
+                ("/r/notes.md".to_string(), 40),
+                ("/r/a-side.txt".to_string(), 5),
+            ]
+        );
+    }
+
+    #[test]
+    fn updates_sizes_incrementally() {
+        let mut tree = tree();
+        tree.add(PathBuf::from("/r/a/b/new.bin"), 90);
+        assert_eq!(tree.size_of(Path::new("/r/a/b")), Some(100));
+        assert_eq!(tree.size_of(Path::new("/r")), Some(645));
+
+        tree.remove(Path::new("/r/a"));
+        assert_eq!(tree.size_of(Path::new("/r")), Some(45));
+        assert_eq!(tree.size_of(Path::new("/r/a/b")), None);
+        assert_eq!(tree.size_of(Path::new("/r/a-side.txt")), Some(5));
+    }
+}
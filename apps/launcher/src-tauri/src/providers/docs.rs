@@ -0,0 +1,439 @@
+use super::{ResultCategory, ResultIcon, SearchContext, SearchProvider, SearchResult};
+use crate::search_cache::CachePolicy;
+use async_trait::async_trait;
+use fuzzy_matcher::skim::SkimMatcherV2;
+use fuzzy_matcher::FuzzyMatcher;
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+const CATALOG_URL: &str = "https://devdocs.io/docs.json";
+const DOCUMENTS_URL: &str = "https://documents.devdocs.io";
+const WEBSITE_URL: &str = "https://devdocs.io";
+const MAX_RESULTS: usize = 8;
+
+/// A documentation set available from DevDocs
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Docset {
+    pub name: String,
+    pub slug: String,
+    #[serde(default)]
+    pub version: Option<String>,
+    #[serde(default)]
+    pub release: Option<String>,
+    /// Last modified, used to detect updates
+    pub mtime: u64,
+    #[serde(default)]
+    pub db_size: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DocEntry {
+    pub name: String,
+    /// Page path, possibly with a `#fragment`
+    pub path: String,
+    #[serde(rename = "type", default)]
+    pub kind: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct DocsetIndex {
+    entries: Vec<DocEntry>,
+}
+
+/// An installed docset with its index in memory. Page contents stay on disk.
+struct InstalledDocset {
+    docset: Docset,
+    entries: Vec<DocEntry>,
+}
+
+/// Offline DevDocs documentation, triggered with `docs:`
+pub struct DocsProvider {
+    dir: PathBuf,
+    installed: RwLock<HashMap<String, InstalledDocset>>,
+    catalog: RwLock<Option<Vec<Docset>>>,
+    matcher: SkimMatcherV2,
+}
+
+fn http_client() -> reqwest::blocking::Client {
+    reqwest::blocking::Client::builder()
+        .timeout(Duration::from_secs(120))
+        .build()
+        .unwrap_or_default()
+}
+
+/// Docset slugs become folder names, so only allow the DevDocs charset
+fn valid_slug(slug: &str) -> bool {
+    !slug.is_empty()
+        && !slug.starts_with('.')
+        && slug
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '~' | '.' | '_' | '-'))
+}
+
+/// File name for a page path, safe to create on any platform
+fn page_file_name(path: &str) -> String {
+    let name: String = path
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    format!("{}.html", name)
+}
+
+impl DocsProvider {
+    pub fn new() -> Self {
+        let dir = dirs::data_dir()
+            .unwrap_or_else(|| PathBuf::from("."))
+            .join("launcher")
+            .join("docs");
+
+        let provider = Self {
+            dir,
+            installed: RwLock::new(HashMap::new()),
+            catalog: RwLock::new(None),
+            matcher: SkimMatcherV2::default(),
+        };
+        provider.load_installed();
+        provider
+    }
+
+    fn docset_dir(&self, slug: &str) -> Result<PathBuf, String> {
+        if !valid_slug(slug) {
+            return Err(format!("Invalid docset: {}", slug));
+        }
+        Ok(self.dir.join(slug))
+    }
+
+    fn load_installed(&self) {
+        let Ok(dirs) = std::fs::read_dir(&self.dir) else {
+            return;
+        };
+        let mut installed = self.installed.write();
+        for dir in dirs.flatten() {
+            match Self::load_docset(&dir.path()) {
+                Ok(docset) => {
+                    installed.insert(docset.docset.slug.clone(), docset);
+                }
+                Err(e) => eprintln!("Skipping docset {}: {}", dir.path().display(), e),
+            }
+        }
+    }
+
+    fn load_docset(dir: &Path) -> Result<InstalledDocset, String> {
+        let read = |name: &str| {
+            std::fs::read_to_string(dir.join(name)).map_err(|e| format!("{}: {}", name, e))
+        };
+        let docset: Docset =
+            serde_json::from_str(&read("meta.json")?).map_err(|e| e.to_string())?;
+        let index: DocsetIndex =
+            serde_json::from_str(&read("index.json")?).map_err(|e| e.to_string())?;
+        Ok(InstalledDocset {
+            docset,
+            entries: index.entries,
+        })
+    }
+
+    /// All docsets DevDocs offers
+    pub fn available(&self) -> Result<Vec<Docset>, String> {
+        if let Some(catalog) = self.catalog.read().as_ref() {
+            return Ok(catalog.clone());
+        }
+        let catalog: Vec<Docset> = http_client()
+            .get(CATALOG_URL)
+            .send()
+            .and_then(|r| r.error_for_status())
+            .and_then(|r| r.json())
+            .map_err(|e| format!("Failed to fetch docset list: {}", e))?;
+        *self.catalog.write() = Some(catalog.clone());
+        Ok(catalog)
+    }
+
+    pub fn installed(&self) -> Vec<Docset> {
+        let mut docsets: Vec<Docset> = self
+            .installed
+            .read()
+            .values()
+            .map(|d| d.docset.clone())
+            .collect();
+        docsets.sort_by(|a, b| a.name.cmp(&b.name));
+        docsets
+    }
+
+    /// Download a docset's index and pages
+    pub fn install(&self, slug: &str) -> Result<Docset, String> {
+        let docset = self
+            .available()?
+            .into_iter()
+            .find(|d| d.slug == slug)
+            .ok_or_else(|| format!("Unknown docset: {}", slug))?;
+
+        let client = http_client();
+        let fetch = |file: &str| {
+            client
+                .get(format!(
+                    "{}/{}/{}?{}",
+                    DOCUMENTS_URL, docset.slug, file, docset.mtime
+                ))
+                .send()
+                .and_then(|r| r.error_for_status())
+                .and_then(|r| r.bytes())
+                .map_err(|e| format!("Failed to download {}: {}", file, e))
+        };
+        let index = fetch("index.json")?;
+        let db = fetch("db.json")?;
+
+        // Replace any older version, including its rendered pages
+        let dir = self.docset_dir(&docset.slug)?;
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+        std::fs::write(dir.join("index.json"), &index).map_err(|e| e.to_string())?;
+        std::fs::write(dir.join("db.json"), &db).map_err(|e| e.to_string())?;
+        let meta = serde_json::to_string_pretty(&docset).map_err(|e| e.to_string())?;
+        std::fs::write(dir.join("meta.json"), meta).map_err(|e| e.to_string())?;
+
+        let installed = Self::load_docset(&dir)?;
+        self.installed
+            .write()
+            .insert(docset.slug.clone(), installed);
+        Ok(docset)
+    }
+
+    pub fn remove(&self, slug: &str) -> Result<(), String> {
+        self.installed.write().remove(slug);
+        let dir = self.docset_dir(slug)?;
+        if dir.exists() {
+            std::fs::remove_dir_all(dir).map_err(|e| e.to_string())?;
+        }
+        Ok(())
+    }
+
+    /// Reinstall docsets that have a newer version. Returns the updated slugs.
+    pub fn update_all(&self) -> Result<Vec<String>, String> {
+        *self.catalog.write() = None;
+        let catalog = self.available()?;
+        let outdated: Vec<String> = self
+            .installed()
+            .into_iter()
+            .filter(|installed| {
+                catalog
+                    .iter()
+                    .any(|d| d.slug == installed.slug && d.mtime > installed.mtime)
+            })
+            .map(|d| d.slug)
+            .collect();
+
+        for slug in &outdated {
+            self.install(slug)?;
+        }
+        Ok(outdated)
+    }
+
+    /// HTML for a page of an installed docset
+    pub fn page(&self, slug: &str, path: &str) -> Result<String, String> {
+        let page = path.split('#').next().unwrap_or(path);
+        let db = std::fs::read_to_string(self.docset_dir(slug)?.join("db.json"))
+            .map_err(|_| format!("Docset {} is not installed", slug))?;
+        let mut pages: HashMap<String, String> =
+            serde_json::from_str(&db).map_err(|e| e.to_string())?;
+        pages
+            .remove(page)
+            .ok_or_else(|| format!("Page not found: {}", page))
+    }
+
+    /// Write a page to a standalone HTML file so the browser can show it offline
+    fn render_page(&self, slug: &str, path: &str) -> Result<PathBuf, String> {
+        let page = path.split('#').next().unwrap_or(path);
+        let file = self
+            .docset_dir(slug)?
+            .join("pages")
+            .join(page_file_name(page));
+        if file.exists() {
+            return Ok(file);
+        }
+
+        let body = self.page(slug, page)?;
+        let title = self
+            .installed
+            .read()
+            .get(slug)
+            .map(|d| d.docset.name.clone())
+            .unwrap_or_else(|| slug.to_string());
+        let html = format!(
+            "<!DOCTYPE html><html><head><meta charset=\"utf-8\"><title>{}</title>\
+             <style>body{{font-family:system-ui,sans-serif;max-width:60rem;margin:2rem auto;padding:0 1rem;line-height:1.5}}\
+             pre{{background:#f4f4f4;padding:.75rem;overflow:auto}}</style></head><body>{}</body></html>",
+            title, body
+        );
+        if let Some(parent) = file.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+        std::fs::write(&file, html).map_err(|e| e.to_string())?;
+        Ok(file)
+    }
+
+    /// Open a doc entry from the local copy, falling back to devdocs.io
+    pub fn open(&self, slug: &str, path: &str) -> Result<(), String> {
+        let fragment = path
+            .split_once('#')
+            .map(|(_, f)| format!("#{}", f))
+            .unwrap_or_default();
+        match self.render_page(slug, path) {
+            Ok(file) => {
+                let url =
+                    url::Url::from_file_path(&file).map_err(|_| "Invalid docs path".to_string())?;
+                super::url::open_url(&format!("{}{}", url, fragment))
+            }
+            Err(e) => {
+                eprintln!("Opening {} online: {}", path, e);
+                super::url::open_url(&format!("{}/{}/{}", WEBSITE_URL, slug, path))
+            }
+        }
+    }
+
+    fn search_blocking(&self, query: &str) -> Vec<SearchResult> {
+        let query = query.trim();
+        let Some(rest) = query
+            .strip_prefix("docs:")
+            .or_else(|| query.strip_prefix("docs "))
+            .map(str::trim)
+        else {
+            return vec![];
+        };
+
+        let installed = self.installed.read();
+        if installed.is_empty() {
+            return vec![SearchResult {
+                id: "docs:manage".to_string(),
+                title: "No documentation installed".to_string(),
+                subtitle: Some("Download docsets in Settings → Documentation".to_string()),
+                icon: ResultIcon::Emoji("📚".to_string()),
+                category: ResultCategory::Command,
+                score: 50.0,
+                aria_label: None,
+            }];
+        }
+        if rest.is_empty() {
+            return vec![];
+        }
+
+        // "docs rust: vec" narrows to one docset
+        let (docsets, term): (Vec<&InstalledDocset>, &str) = match rest.split_once(':') {
+            Some((prefix, term)) => {
+                let prefix = prefix.trim().to_lowercase();
+                let matching: Vec<&InstalledDocset> = installed
+                    .values()
+                    .filter(|d| {
+                        d.docset.slug.starts_with(&prefix)
+                            || d.docset.name.to_lowercase().starts_with(&prefix)
+                    })
+                    .collect();
+                if matching.is_empty() {
+                    (installed.values().collect(), rest)
+                } else {
+                    (matching, term.trim())
+                }
+            }
+            None => (installed.values().collect(), rest),
+        };
+        if term.is_empty() {
+            return vec![];
+        }
+
+        let mut scored: Vec<(i64, &InstalledDocset, &DocEntry)> = docsets
+            .iter()
+            .flat_map(|docset| docset.entries.iter().map(move |entry| (*docset, entry)))
+            .filter_map(|(docset, entry)| {
+                let score = self.matcher.fuzzy_match(&entry.name, term)?;
+                // Prefer exact and prefix matches over scattered fuzzy ones
+                let bonus = if entry.name.eq_ignore_ascii_case(term) {
+                    1000
+                } else if entry.name.to_lowercase().starts_with(&term.to_lowercase()) {
+                    200
+                } else {
+                    0
+                };
+                Some((score + bonus, docset, entry))
+            })
+            .collect();
+        scored.sort_by(|a, b| b.0.cmp(&a.0));
+
+        scored
+            .into_iter()
+            .take(MAX_RESULTS)
+            .enumerate()
+            .map(|(i, (_, docset, entry))| {
+                let mut subtitle = docset.docset.name.clone();
+                if let Some(version) = docset.docset.version.as_deref().filter(|v| !v.is_empty()) {
+                    subtitle = format!("{} {}", subtitle, version);
+                }
+                if let Some(kind) = &entry.kind {
+                    subtitle = format!("{} • {}", subtitle, kind);
+                }
+                SearchResult {
+                    id: format!(
+                        "docs:open:{}:{}",
+                        docset.docset.slug,
+                        urlencoding::encode(&entry.path)
+                    ),
+                    title: entry.name.clone(),
+                    subtitle: Some(subtitle),
+                    icon: ResultIcon::Emoji("📘".to_string()),
+                    category: ResultCategory::Plugin,
+                    score: 90.0 - i as f32,
+                    aria_label: None,
+                }
+            })
+            .collect()
+    }
+}
+
+impl Default for DocsProvider {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl SearchProvider for DocsProvider {
+    fn id(&self) -> &str {
+        "docs"
+    }
+
+    async fn search(&self, query: &str, ctx: &SearchContext) -> Vec<SearchResult> {
+        ctx.run_blocking(|| self.search_blocking(query))
+    }
+
+    fn cache_policy(&self, query: &str) -> CachePolicy {
+        // Fuzzy matching every entry of a large docset takes a while
+        if query.trim_start().starts_with("docs") {
+            CachePolicy::For(Duration::from_secs(10))
+        } else {
+            CachePolicy::Never
+        }
+    }
+
+    fn execute(&self, result_id: &str) -> Result<(), String> {
+        let action = result_id
+            .strip_prefix("docs:")
+            .ok_or("Invalid docs result")?;
+
+        if let Some(rest) = action.strip_prefix("open:") {
+            let (slug, path) = rest.split_once(':').ok_or("Invalid docs result")?;
+            let path = urlencoding::decode(path).map_err(|e| e.to_string())?;
+            return self.open(slug, &path);
+        }
+        if action == "manage" {
+            return Err("Download docsets in Settings → Documentation".to_string());
+        }
+
+        Err(format!("Unknown docs action: {}", action))
+    }
+}
@@ -0,0 +1,511 @@
+use super::{ResultCategory, ResultIcon, SearchContext, SearchProvider, SearchResult};
+use async_trait::async_trait;
+use parking_lot::RwLock;
+use rand::seq::SliceRandom;
+use rand::Rng;
+use serde_json::json;
+use std::collections::HashMap;
+
+const MAX_COUNT: usize = 1000;
+const PREVIEW_CHARS: usize = 80;
+
+const LOREM: &str = "lorem ipsum dolor sit amet consectetur adipiscing elit sed do eiusmod tempor \
+     incididunt ut labore et dolore magna aliqua enim ad minim veniam quis nostrud \
+     exercitation ullamco laboris nisi aliquip ex ea commodo consequat duis aute irure in \
+     reprehenderit voluptate velit esse cillum fugiat nulla pariatur excepteur sint \
+     occaecat cupidatat non proident sunt culpa qui officia deserunt mollit anim id est \
+     laborum";
+
+const FIRST_NAMES: &[&str] = &[
+    "Ada", "Alan", "Amara", "Ben", "Carla", "Chen", "Dmitri", "Elena", "Farah", "Grace", "Hiro",
+    "Ines", "Jonas", "Kemi", "Lars", "Maya", "Noah", "Olga", "Priya", "Quinn", "Rosa", "Sam",
+    "Tariq", "Uma", "Victor", "Wen", "Yusuf", "Zoe",
+];
+
+const LAST_NAMES: &[&str] = &[
+    "Andersen", "Baker", "Costa", "Dubois", "Eriksen", "Fischer", "Garcia", "Hansen", "Ito",
+    "Johnson", "Kowalski", "Lopez", "Müller", "Nakamura", "Okafor", "Petrov", "Rossi", "Singh",
+    "Tanaka", "Usman", "Virtanen", "Williams", "Xu", "Yilmaz", "Zhang",
+];
+
+const STREETS: &[&str] = &[
+    "Maple Street",
+    "Oak Avenue",
+    "Harbour Road",
+    "Mill Lane",
+    "Station Road",
+    "Park Avenue",
+    "Church Street",
+    "River Walk",
+    "Elm Court",
+    "High Street",
+];
+
+const CITIES: &[(&str, &str)] = &[
+    ("Oslo", "Norway"),
+    ("Berlin", "Germany"),
+    ("Lyon", "France"),
+    ("Porto", "Portugal"),
+    ("Austin", "United States"),
+    ("Toronto", "Canada"),
+    ("Osaka", "Japan"),
+    ("Melbourne", "Australia"),
+    ("Nairobi", "Kenya"),
+    ("Bergen", "Norway"),
+];
+
+const EMAIL_DOMAINS: &[&str] = &["example.com", "example.org", "example.net"];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Kind {
+    Paragraphs,
+    Sentences,
+    Words,
+    Names,
+    Emails,
+    Addresses,
+    Users,
+    Uuids,
+}
+
+impl Kind {
+    const ALL: [Kind; 8] = [
+        Kind::Paragraphs,
+        Kind::Sentences,
+        Kind::Words,
+        Kind::Names,
+        Kind::Emails,
+        Kind::Addresses,
+        Kind::Users,
+        Kind::Uuids,
+    ];
+
+    fn keyword(&self) -> &'static str {
+        match self {
+            Kind::Paragraphs => "paragraphs",
+            Kind::Sentences => "sentences",
+            Kind::Words => "words",
+            Kind::Names => "names",
+            Kind::Emails => "emails",
+            Kind::Addresses => "addresses",
+            Kind::Users => "users",
+            Kind::Uuids => "uuids",
+        }
+    }
+
+    fn parse(word: &str) -> Option<Self> {
+        match word {
+            "lorem" | "ipsum" => Some(Kind::Paragraphs),
+            _ => Kind::ALL
+                .into_iter()
+                .find(|k| k.keyword().starts_with(word)),
+        }
+    }
+
+    fn default_count(&self) -> usize {
+        match self {
+            Kind::Paragraphs => 3,
+            Kind::Sentences => 5,
+            Kind::Words => 50,
+            Kind::Uuids | Kind::Names | Kind::Emails => 5,
+            Kind::Addresses | Kind::Users => 3,
+        }
+    }
+
+    /// Kinds that are records and can be rendered as JSON or CSV
+    fn is_record(&self) -> bool {
+        matches!(
+            self,
+            Kind::Names | Kind::Emails | Kind::Addresses | Kind::Users
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum OutputFormat {
+    Text,
+    Json,
+    Csv,
+}
+
+/// `fake: 5 users json` in any word order; everything is optional
+#[derive(Debug, PartialEq)]
+struct FakeQuery {
+    count: Option<usize>,
+    kinds: Vec<Kind>,
+    format: OutputFormat,
+}
+
+fn parse_query(input: &str) -> FakeQuery {
+    let mut query = FakeQuery {
+        count: None,
+        kinds: Vec::new(),
+        format: OutputFormat::Text,
+    };
+    for word in input.split_whitespace().map(str::to_lowercase) {
+        if let Ok(count) = word.parse::<usize>() {
+            query.count = Some(count.clamp(1, MAX_COUNT));
+        } else if word == "json" {
+            query.format = OutputFormat::Json;
+        } else if word == "csv" {
+            query.format = OutputFormat::Csv;
+        } else if let Some(kind) = Kind::parse(&word) {
+            query.kinds = vec![kind];
+        }
+    }
+    if query.kinds.is_empty() {
+        query.kinds = Kind::ALL.to_vec();
+    }
+    query
+}
+
+fn pick<'a, R: Rng>(rng: &mut R, items: &[&'a str]) -> &'a str {
+    items.choose(rng).copied().unwrap_or_default()
+}
+
+fn sentence<R: Rng>(rng: &mut R) -> String {
+    let len = rng.gen_range(6..14);
+    let lorem: Vec<&str> = LOREM.split_whitespace().collect();
+    let words: Vec<&str> = (0..len).map(|_| pick(rng, &lorem)).collect();
+    let mut sentence = words.join(" ");
+    if let Some(first) = sentence.get(..1) {
+        sentence = format!("{}{}.", first.to_uppercase(), &sentence[1..]);
+    }
+    sentence
+}
+
+fn paragraph<R: Rng>(rng: &mut R) -> String {
+    let len = rng.gen_range(4..8);
+    (0..len)
+        .map(|_| sentence(rng))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+struct Person {
+    first: &'static str,
+    last: &'static str,
+    email: String,
+    phone: String,
+    street: String,
+    city: &'static str,
+    postal_code: String,
+    country: &'static str,
+}
+
+fn person<R: Rng>(rng: &mut R) -> Person {
+    let first = pick(rng, FIRST_NAMES);
+    let last = pick(rng, LAST_NAMES);
+    let (city, country) = *CITIES.choose(rng).unwrap_or(&CITIES[0]);
+    let email_name: String = format!("{}.{}", first, last)
+        .to_lowercase()
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric() || *c == '.')
+        .collect();
+    Person {
+        first,
+        last,
+        email: format!(
+            "{}{}@{}",
+            email_name,
+            rng.gen_range(1..100),
+            pick(rng, EMAIL_DOMAINS)
+        ),
+        // 555-01xx numbers are reserved for fiction
+        phone: format!("+1 555-01{:02}", rng.gen_range(0..100)),
+        street: format!("{} {}", rng.gen_range(1..250), pick(rng, STREETS)),
+        city,
+        postal_code: format!("{:05}", rng.gen_range(1000..99999)),
+        country,
+    }
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn generate<R: Rng>(rng: &mut R, kind: Kind, count: usize, format: OutputFormat) -> String {
+    match kind {
+        Kind::Paragraphs => (0..count)
+            .map(|_| paragraph(rng))
+            .collect::<Vec<_>>()
+            .join("\n\n"),
+        Kind::Sentences => (0..count)
+            .map(|_| sentence(rng))
+            .collect::<Vec<_>>()
+            .join(" "),
+        Kind::Words => {
+            let lorem: Vec<&str> = LOREM.split_whitespace().collect();
+            (0..count)
+                .map(|_| pick(rng, &lorem))
+                .collect::<Vec<_>>()
+                .join(" ")
+        }
+        Kind::Uuids => (0..count)
+            .map(|_| uuid::Uuid::new_v4().to_string())
+            .collect::<Vec<_>>()
+            .join("\n"),
+        Kind::Names | Kind::Emails | Kind::Addresses | Kind::Users => {
+            let people: Vec<Person> = (0..count).map(|_| person(rng)).collect();
+            render_people(&people, kind, format)
+        }
+    }
+}
+
+fn render_people(people: &[Person], kind: Kind, format: OutputFormat) -> String {
+    let (headers, rows): (Vec<&str>, Vec<Vec<String>>) = match kind {
+        Kind::Names => (
+            vec!["first_name", "last_name"],
+            people
+                .iter()
+                .map(|p| vec![p.first.to_string(), p.last.to_string()])
+                .collect(),
+        ),
+        Kind::Emails => (
+            vec!["email"],
+            people.iter().map(|p| vec![p.email.clone()]).collect(),
+        ),
+        Kind::Addresses => (
+            vec!["street", "city", "postal_code", "country"],
+            people
+                .iter()
+                .map(|p| {
+                    vec![
+                        p.street.clone(),
+                        p.city.to_string(),
+                        p.postal_code.clone(),
+                        p.country.to_string(),
+                    ]
+                })
+                .collect(),
+        ),
+        _ => (
+            vec![
+                "id",
+                "first_name",
+                "last_name",
+                "email",
+                "phone",
+                "street",
+                "city",
+                "country",
+            ],
+            people
+                .iter()
+                .enumerate()
+                .map(|(i, p)| {
+                    vec![
+                        (i + 1).to_string(),
+                        p.first.to_string(),
+                        p.last.to_string(),
+                        p.email.clone(),
+                        p.phone.clone(),
+                        p.street.clone(),
+                        p.city.to_string(),
+                        p.country.to_string(),
+                    ]
+                })
+                .collect(),
+        ),
+    };
+
+    match format {
+        OutputFormat::Json => {
+            let records: Vec<serde_json::Value> = if kind == Kind::Users {
+                people
+                    .iter()
+                    .enumerate()
+                    .map(|(i, p)| {
+                        json!({
+                            "id": i + 1,
+                            "name": format!("{} {}", p.first, p.last),
+                            "email": p.email,
+                            "phone": p.phone,
+                            "address": {
+                                "street": p.street,
+                                "city": p.city,
+                                "postal_code": p.postal_code,
+                                "country": p.country,
+                            },
+                        })
+                    })
+                    .collect()
+            } else {
+                rows.iter()
+                    .map(|row| {
+                        let record: serde_json::Map<String, serde_json::Value> = headers
+                            .iter()
+                            .zip(row)
+                            .map(|(h, v)| (h.to_string(), json!(v)))
+                            .collect();
+                        serde_json::Value::Object(record)
+                    })
+                    .collect()
+            };
+            serde_json::to_string_pretty(&records).unwrap_or_default()
+        }
+        OutputFormat::Csv => std::iter::once(headers.join(","))
+            .chain(rows.iter().map(|row| {
+                row.iter()
+                    .map(|v| csv_field(v))
+                    .collect::<Vec<_>>()
+                    .join(",")
+            }))
+            .collect::<Vec<_>>()
+            .join("\n"),
+        OutputFormat::Text => match kind {
+            Kind::Names => people
+                .iter()
+                .map(|p| format!("{} {}", p.first, p.last))
+                .collect::<Vec<_>>()
+                .join("\n"),
+            Kind::Emails => people
+                .iter()
+                .map(|p| p.email.clone())
+                .collect::<Vec<_>>()
+                .join("\n"),
+            Kind::Addresses => people
+                .iter()
+                .map(|p| format!("{}, {} {}, {}", p.street, p.postal_code, p.city, p.country))
+                .collect::<Vec<_>>()
+                .join("\n"),
+            _ => people
+                .iter()
+                .map(|p| format!("{} {} <{}>, {}", p.first, p.last, p.email, p.phone))
+                .collect::<Vec<_>>()
+                .join("\n"),
+        },
+    }
+}
+
+fn preview(text: &str) -> String {
+    let flat = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if flat.chars().count() > PREVIEW_CHARS {
+        format!("{}…", flat.chars().take(PREVIEW_CHARS).collect::<String>())
+    } else {
+        flat
+    }
+}
+
+/// Placeholder content for mockups and fixtures: `fake: 3 paragraphs`,
+/// `fake: 5 users json`, `fake: emails csv`. Execute copies the output.
+pub struct FakeDataProvider {
+    /// Generated text by result id, so execute copies exactly what was previewed
+    outputs: RwLock<HashMap<String, String>>,
+}
+
+impl FakeDataProvider {
+    pub fn new() -> Self {
+        Self {
+            outputs: RwLock::new(HashMap::new()),
+        }
+    }
+}
+
+impl Default for FakeDataProvider {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl SearchProvider for FakeDataProvider {
+    fn id(&self) -> &str {
+        "fake"
+    }
+
+    async fn search(&self, query: &str, _ctx: &SearchContext) -> Vec<SearchResult> {
+        let Some(input) = query.trim_start().strip_prefix("fake:") else {
+            return vec![];
+        };
+        let query = parse_query(input);
+        let mut rng = rand::thread_rng();
+
+        let mut outputs = HashMap::new();
+        let results = query
+            .kinds
+            .iter()
+            .enumerate()
+            .map(|(i, kind)| {
+                let count = query.count.unwrap_or_else(|| kind.default_count());
+                let format = if kind.is_record() {
+                    query.format
+                } else {
+                    OutputFormat::Text
+                };
+                let output = generate(&mut rng, *kind, count, format);
+                let format_name = match format {
+                    OutputFormat::Text => "",
+                    OutputFormat::Json => " as JSON",
+                    OutputFormat::Csv => " as CSV",
+                };
+
+                let id = format!("fake:{}", kind.keyword());
+                let result = SearchResult {
+                    id: id.clone(),
+                    title: format!("{} {}{}", count, kind.keyword(), format_name),
+                    subtitle: Some(preview(&output)),
+                    icon: ResultIcon::Emoji("🎲".to_string()),
+                    category: ResultCategory::Command,
+                    score: 90.0 - i as f32 * 0.5,
+                    aria_label: None,
+                };
+                outputs.insert(id, output);
+                result
+            })
+            .collect();
+
+        *self.outputs.write() = outputs;
+        results
+    }
+
+    fn execute(&self, result_id: &str) -> Result<(), String> {
+        let output = self
+            .outputs
+            .read()
+            .get(result_id)
+            .cloned()
+            .ok_or("This result has expired, search again")?;
+        crate::clipboard::copy_text(&output)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_queries() {
+        assert_eq!(
+            parse_query(" 5 users json"),
+            FakeQuery {
+                count: Some(5),
+                kinds: vec![Kind::Users],
+                format: OutputFormat::Json,
+            }
+        );
+        assert_eq!(parse_query("lorem 2").kinds, vec![Kind::Paragraphs]);
+        assert_eq!(parse_query("email").kinds, vec![Kind::Emails]);
+        assert_eq!(parse_query("99999 words").count, Some(MAX_COUNT));
+        assert_eq!(parse_query("").kinds.len(), Kind::ALL.len());
+    }
+
+    #[test]
+    fn generates_requested_counts() {
+        let mut rng = rand::thread_rng();
+        let users = generate(&mut rng, Kind::Users, 4, OutputFormat::Json);
+        let parsed: Vec<serde_json::Value> = serde_json::from_str(&users).unwrap();
+        assert_eq!(parsed.len(), 4);
+        assert!(parsed[0]["email"].as_str().unwrap().contains('@'));
+
+        let csv = generate(&mut rng, Kind::Emails, 3, OutputFormat::Csv);
+        assert_eq!(csv.lines().count(), 4);
+
+        let words = generate(&mut rng, Kind::Words, 7, OutputFormat::Text);
+        assert_eq!(words.split(' ').count(), 7);
+    }
+}
@@ -0,0 +1,164 @@
+use super::quicklinks;
+use super::websearch::{SearchEngine, WebSearchProvider};
+use super::{ResultCategory, ResultIcon, SearchContext, SearchProvider, SearchResult};
+use crate::settings::{Fallback, SettingsStore};
+use async_trait::async_trait;
+use std::sync::Arc;
+
+/// Score of the first fallback; each next one scores a point less, so they
+/// keep their order and stay below anything that matched
+const FIRST_SCORE: f32 = 15.0;
+
+/// Check a fallback before it is saved
+pub fn validate(fallback: &Fallback) -> Result<(), String> {
+    match fallback {
+        Fallback::WebSearch { engine } => {
+            SearchEngine::from_name(engine)
+                .ok_or_else(|| format!("Unknown search engine: {}", engine))?;
+        }
+        Fallback::Url { name, url } => quicklinks::validate(&crate::settings::Quicklink {
+            name: name.clone(),
+            url: url.clone(),
+        })?,
+        Fallback::AskAi | Fallback::FileContents => {}
+    }
+    Ok(())
+}
+
+fn result(fallback: &Fallback, query: &str, score: f32) -> Option<SearchResult> {
+    let (id, title, subtitle, icon, category) = match fallback {
+        Fallback::WebSearch { engine } => {
+            let engine = SearchEngine::from_name(engine)?;
+            (
+                // Opened by WebSearchProvider
+                format!("websearch:{}:{}", engine.name().to_lowercase(), query),
+                format!("Search {} for \"{}\"", engine.name(), query),
+                "Web search".to_string(),
+                engine.icon(),
+                ResultCategory::WebSearch,
+            )
+        }
+        Fallback::AskAi => (
+            format!("fallback:ask-ai:{}", query),
+            format!("Ask AI \"{}\"", query),
+            "AI assistant".to_string(),
+            "✨",
+            ResultCategory::Command,
+        ),
+        Fallback::FileContents => (
+            format!("fallback:file-contents:{}", query),
+            format!("Search file contents for \"{}\"", query),
+            "Text inside indexed files".to_string(),
+            "📄",
+            ResultCategory::File,
+        ),
+        Fallback::Url { name, url } => {
+            let url = quicklinks::fill(url, query);
+            (
+                // Opened by QuicklinkProvider
+                format!("quicklink:{}", url),
+                format!("Search {} for \"{}\"", name, query),
+                url,
+                "🔗",
+                ResultCategory::URL,
+            )
+        }
+    };
+    Some(SearchResult {
+        id,
+        title,
+        subtitle: Some(subtitle),
+        icon: ResultIcon::Emoji(icon.to_string()),
+        category,
+        score,
+        aria_label: None,
+    })
+}
+
+/// What to do with a query nothing else matched: search the web, ask AI,
+/// look inside files or open a URL, in the order set in `fallbacks`
+pub struct FallbackProvider {
+    settings: Arc<SettingsStore>,
+}
+
+impl FallbackProvider {
+    pub fn new(settings: Arc<SettingsStore>) -> Self {
+        Self { settings }
+    }
+}
+
+#[async_trait]
+impl SearchProvider for FallbackProvider {
+    fn id(&self) -> &str {
+        "fallback"
+    }
+
+    async fn search(&self, query: &str, _ctx: &SearchContext) -> Vec<SearchResult> {
+        let trimmed = query.trim();
+        // Engine shortcuts like `g:` are searches of their own
+        if trimmed.len() < 3 || WebSearchProvider::detect_engine_shortcut(trimmed).is_some() {
+            return vec![];
+        }
+
+        self.settings
+            .get()
+            .fallbacks
+            .iter()
+            .enumerate()
+            .filter_map(|(index, fallback)| {
+                result(fallback, trimmed, (FIRST_SCORE - index as f32).max(0.0))
+            })
+            .collect()
+    }
+
+    fn execute(&self, result_id: &str) -> Result<(), String> {
+        let rest = result_id
+            .strip_prefix("fallback:")
+            .ok_or("Invalid fallback result")?;
+        // Both switch the launcher window to another mode with the query
+        if rest.starts_with("ask-ai:") {
+            Err("Ask AI runs from the launcher window".to_string())
+        } else if rest.starts_with("file-contents:") {
+            Err("File contents search runs from the launcher window".to_string())
+        } else {
+            Err("Invalid fallback result".to_string())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builds_results_in_order() {
+        let fallbacks = [
+            Fallback::Url {
+                name: "MDN".to_string(),
+                url: "https://developer.mozilla.org/search?q={query}".to_string(),
+            },
+            Fallback::WebSearch {
+                engine: "stackoverflow".to_string(),
+            },
+            Fallback::AskAi,
+        ];
+        let results: Vec<SearchResult> = fallbacks
+            .iter()
+            .enumerate()
+            .filter_map(|(index, fallback)| result(fallback, "flex box", 15.0 - index as f32))
+            .collect();
+
+        assert_eq!(
+            results[0].id,
+            "quicklink:https://developer.mozilla.org/search?q=flex%20box"
+        );
+        assert_eq!(results[1].id, "websearch:stack overflow:flex box");
+        assert_eq!(results[2].id, "fallback:ask-ai:flex box");
+        assert!(results[0].score > results[1].score);
+
+        assert!(validate(&Fallback::WebSearch {
+            engine: "altavista".to_string()
+        })
+        .is_err());
+    }
+}
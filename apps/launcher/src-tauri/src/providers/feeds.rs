@@ -0,0 +1,416 @@
+use super::{ResultCategory, ResultIcon, SearchContext, SearchProvider, SearchResult};
+use async_trait::async_trait;
+use fuzzy_matcher::skim::SkimMatcherV2;
+use fuzzy_matcher::FuzzyMatcher;
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// Items kept per feed; older ones are dropped on refresh
+const MAX_ITEMS_PER_FEED: usize = 100;
+
+/// How often the background fetcher refreshes subscriptions
+pub const REFRESH_INTERVAL: Duration = Duration::from_secs(30 * 60);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FeedSubscription {
+    pub id: String,
+    pub url: String,
+    pub title: String,
+    #[serde(default)]
+    pub last_fetched: Option<String>,
+    #[serde(default)]
+    pub last_error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FeedItem {
+    pub id: String,
+    pub feed_id: String,
+    pub title: String,
+    pub link: String,
+    #[serde(default)]
+    pub published: Option<String>,
+    #[serde(default)]
+    pub read: bool,
+    #[serde(default)]
+    pub archived: bool,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct FeedStore {
+    subscriptions: Vec<FeedSubscription>,
+    items: Vec<FeedItem>,
+}
+
+/// Local RSS/Atom subscriptions, triggered with `rss:`. Unread items also
+/// show up in regular search when their title matches.
+pub struct FeedProvider {
+    store: RwLock<FeedStore>,
+    path: PathBuf,
+    matcher: SkimMatcherV2,
+}
+
+impl FeedProvider {
+    pub fn new() -> Self {
+        let path = dirs::data_dir()
+            .unwrap_or_else(|| PathBuf::from("."))
+            .join("launcher")
+            .join("feeds.json");
+        let store = crate::journal::load_json_store(&path).unwrap_or_default();
+
+        Self {
+            store: RwLock::new(store),
+            path,
+            matcher: SkimMatcherV2::default(),
+        }
+    }
+
+    fn save(&self, store: &FeedStore) -> Result<(), String> {
+        let json = serde_json::to_string_pretty(store).map_err(|e| e.to_string())?;
+        crate::journal::write_json_store(&self.path, &json)
+    }
+
+    pub fn subscriptions(&self) -> Vec<FeedSubscription> {
+        self.store.read().subscriptions.clone()
+    }
+
+    /// Unread, non-archived items, newest first
+    pub fn unread_items(&self, limit: usize) -> Vec<FeedItem> {
+        let store = self.store.read();
+        let mut items: Vec<FeedItem> = store
+            .items
+            .iter()
+            .filter(|i| !i.read && !i.archived)
+            .cloned()
+            .collect();
+        items.sort_by(|a, b| b.published.cmp(&a.published));
+        items.truncate(limit);
+        items
+    }
+
+    /// Subscribe to a feed, fetching it once to validate the URL
+    pub fn subscribe(&self, url: &str) -> Result<FeedSubscription, String> {
+        let url = url.trim();
+        if self.store.read().subscriptions.iter().any(|s| s.url == url) {
+            return Err("Already subscribed to this feed".to_string());
+        }
+
+        let feed = fetch_feed(url)?;
+        let subscription = FeedSubscription {
+            id: uuid::Uuid::new_v4().to_string(),
+            url: url.to_string(),
+            title: feed
+                .title
+                .as_ref()
+                .map(|t| t.content.clone())
+                .unwrap_or_else(|| url.to_string()),
+            last_fetched: Some(chrono::Utc::now().to_rfc3339()),
+            last_error: None,
+        };
+
+        let mut store = self.store.write();
+        merge_items(&mut store.items, &subscription.id, feed);
+        store.subscriptions.push(subscription.clone());
+        self.save(&store)?;
+        Ok(subscription)
+    }
+
+    pub fn unsubscribe(&self, id: &str) -> Result<(), String> {
+        let mut store = self.store.write();
+        store.subscriptions.retain(|s| s.id != id);
+        store.items.retain(|i| i.feed_id != id);
+        self.save(&store)
+    }
+
+    /// Subscribe to every feed in an OPML document. Returns how many were added.
+    pub fn import_opml(&self, opml: &str) -> Result<usize, String> {
+        let urls = parse_opml(opml);
+        if urls.is_empty() {
+            return Err("No feeds found in OPML".to_string());
+        }
+
+        let mut added = 0;
+        for url in urls {
+            match self.subscribe(&url) {
+                Ok(_) => added += 1,
+                Err(e) => eprintln!("Skipping feed {}: {}", url, e),
+            }
+        }
+        Ok(added)
+    }
+
+    pub fn export_opml(&self) -> String {
+        let outlines: String = self
+            .store
+            .read()
+            .subscriptions
+            .iter()
+            .map(|s| {
+                format!(
+                    "    <outline type=\"rss\" text=\"{}\" title=\"{}\" xmlUrl=\"{}\"/>\n",
+                    escape_xml(&s.title),
+                    escape_xml(&s.title),
+                    escape_xml(&s.url)
+                )
+            })
+            .collect();
+
+        format!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<opml version=\"2.0\">\n  <head><title>Launcher subscriptions</title></head>\n  <body>\n{}  </body>\n</opml>\n",
+            outlines
+        )
+    }
+
+    /// Fetch all subscriptions. Network requests run without holding the
+    /// store lock. Returns the number of new items.
+    pub fn refresh_all(&self) -> usize {
+        let subscriptions = self.subscriptions();
+        let fetched: Vec<(String, Result<feed_rs::model::Feed, String>)> = subscriptions
+            .iter()
+            .map(|s| (s.id.clone(), fetch_feed(&s.url)))
+            .collect();
+
+        let mut store = self.store.write();
+        let before = store.items.len();
+        let now = chrono::Utc::now().to_rfc3339();
+
+        for (feed_id, result) in fetched {
+            let error = match result {
+                Ok(feed) => {
+                    merge_items(&mut store.items, &feed_id, feed);
+                    None
+                }
+                Err(e) => Some(e),
+            };
+            if let Some(sub) = store.subscriptions.iter_mut().find(|s| s.id == feed_id) {
+                sub.last_fetched = Some(now.clone());
+                sub.last_error = error;
+            }
+        }
+
+        let added = store.items.len().saturating_sub(before);
+        if let Err(e) = self.save(&store) {
+            eprintln!("Failed to save feeds: {}", e);
+        }
+        added
+    }
+
+    fn update_item(&self, id: &str, update: impl FnOnce(&mut FeedItem)) -> Result<(), String> {
+        let mut store = self.store.write();
+        let item = store
+            .items
+            .iter_mut()
+            .find(|i| i.id == id)
+            .ok_or("Feed item not found")?;
+        update(item);
+        self.save(&store)
+    }
+
+    pub fn mark_read(&self, id: &str) -> Result<(), String> {
+        self.update_item(id, |i| i.read = true)
+    }
+
+    pub fn archive(&self, id: &str) -> Result<(), String> {
+        self.update_item(id, |i| {
+            i.read = true;
+            i.archived = true;
+        })
+    }
+
+    fn feed_title(&self, feed_id: &str) -> String {
+        self.store
+            .read()
+            .subscriptions
+            .iter()
+            .find(|s| s.id == feed_id)
+            .map(|s| s.title.clone())
+            .unwrap_or_default()
+    }
+
+    fn item_result(&self, item: &FeedItem, score: f32) -> SearchResult {
+        SearchResult {
+            id: format!("feeds:{}", item.id),
+            title: item.title.clone(),
+            subtitle: Some(self.feed_title(&item.feed_id)),
+            icon: ResultIcon::Emoji("📰".to_string()),
+            category: ResultCategory::URL,
+            score,
+            aria_label: None,
+        }
+    }
+}
+
+impl Default for FeedProvider {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn fetch_feed(url: &str) -> Result<feed_rs::model::Feed, String> {
+    let response = reqwest::blocking::Client::new()
+        .get(url)
+        .header("User-Agent", "Launcher feed reader")
+        .timeout(Duration::from_secs(10))
+        .send()
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| format!("Failed to fetch feed: {}", e))?;
+    let body = response.bytes().map_err(|e| e.to_string())?;
+    feed_rs::parser::parse(body.as_ref()).map_err(|e| format!("Invalid feed: {}", e))
+}
+
+/// Add entries from a fetched feed that we haven't seen yet, keeping the
+/// read/archived state of known ones
+fn merge_items(items: &mut Vec<FeedItem>, feed_id: &str, feed: feed_rs::model::Feed) {
+    for entry in feed.entries {
+        let id = format!("{}:{}", feed_id, entry.id);
+        if items.iter().any(|i| i.id == id) {
+            continue;
+        }
+        let Some(link) = entry.links.first().map(|l| l.href.clone()) else {
+            continue;
+        };
+        items.push(FeedItem {
+            id,
+            feed_id: feed_id.to_string(),
+            title: entry
+                .title
+                .map(|t| t.content)
+                .unwrap_or_else(|| link.clone()),
+            link,
+            published: entry.published.or(entry.updated).map(|d| d.to_rfc3339()),
+            read: false,
+            archived: false,
+        });
+    }
+
+    // Trim the oldest items of this feed
+    let mut feed_items: Vec<(Option<String>, String)> = items
+        .iter()
+        .filter(|i| i.feed_id == feed_id)
+        .map(|i| (i.published.clone(), i.id.clone()))
+        .collect();
+    if feed_items.len() > MAX_ITEMS_PER_FEED {
+        feed_items.sort_by(|a, b| b.0.cmp(&a.0));
+        let dropped: std::collections::HashSet<String> = feed_items
+            .into_iter()
+            .skip(MAX_ITEMS_PER_FEED)
+            .map(|(_, id)| id)
+            .collect();
+        items.retain(|i| !dropped.contains(&i.id));
+    }
+}
+
+/// Feed URLs from the `xmlUrl` attributes of an OPML document
+fn parse_opml(opml: &str) -> Vec<String> {
+    let re = regex::Regex::new(r#"(?i)xmlUrl\s*=\s*["']([^"']+)["']"#).unwrap();
+    re.captures_iter(opml)
+        .map(|c| unescape_xml(&c[1]))
+        .collect()
+}
+
+fn escape_xml(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn unescape_xml(s: &str) -> String {
+    s.replace("&quot;", "\"")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&amp;", "&")
+}
+
+#[async_trait]
+impl SearchProvider for FeedProvider {
+    fn id(&self) -> &str {
+        "feeds"
+    }
+
+    async fn search(&self, query: &str, _ctx: &SearchContext) -> Vec<SearchResult> {
+        let query = query.trim();
+        let (filter, triggered) = match query
+            .strip_prefix("rss:")
+            .or_else(|| query.strip_prefix("rss "))
+        {
+            Some(rest) => (rest.trim(), true),
+            None if query == "rss" => ("", true),
+            None => (query, false),
+        };
+
+        if triggered && filter.is_empty() {
+            return self
+                .unread_items(10)
+                .iter()
+                .enumerate()
+                .map(|(i, item)| self.item_result(item, 90.0 - i as f32))
+                .collect();
+        }
+        if !triggered && filter.len() < 3 {
+            return vec![];
+        }
+
+        let mut results: Vec<SearchResult> = self
+            .unread_items(usize::MAX)
+            .iter()
+            .filter_map(|item| {
+                self.matcher
+                    .fuzzy_match(&item.title, filter)
+                    .map(|score| (item, score))
+            })
+            .map(|(item, score)| {
+                // Feed items shouldn't crowd out apps and files in plain search
+                let base = if triggered { 60.0 } else { 20.0 };
+                self.item_result(item, base + (score as f32 / 10.0).min(30.0))
+            })
+            .collect();
+
+        results.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap());
+        results.truncate(if triggered { 10 } else { 3 });
+        results
+    }
+
+    fn execute(&self, result_id: &str) -> Result<(), String> {
+        let id = result_id
+            .strip_prefix("feeds:")
+            .ok_or("Invalid feed result")?;
+
+        let link = self
+            .store
+            .read()
+            .items
+            .iter()
+            .find(|i| i.id == id)
+            .map(|i| i.link.clone())
+            .ok_or("Feed item not found")?;
+
+        super::url::open_web_url(&link)?;
+        self.mark_read(id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_opml_feed_urls() {
+        let opml = r#"<opml version="2.0"><body>
+            <outline text="Tech">
+                <outline type="rss" text="A" xmlUrl="https://a.example/feed.xml"/>
+                <outline type="rss" text="B" xmlUrl='https://b.example/rss?x=1&amp;y=2'/>
+            </outline>
+        </body></opml>"#;
+
+        assert_eq!(
+            parse_opml(opml),
+            vec![
+                "https://a.example/feed.xml".to_string(),
+                "https://b.example/rss?x=1&y=2".to_string()
+            ]
+        );
+    }
+}
@@ -1,15 +1,25 @@
-use super::{ResultCategory, ResultIcon, SearchProvider, SearchResult};
+use super::{
+    Mutated, ResultAction, ResultCategory, ResultContext, ResultIcon, ResultMutation,
+    SearchContext, SearchProvider, SearchResult,
+};
 use crate::indexer::{FileIndexer, FileWatcher, IndexConfig};
+use crate::undo::UndoableAction;
+use async_trait::async_trait;
 use parking_lot::{Mutex, RwLock};
 use std::path::PathBuf;
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::Arc;
 
+type ChangeListener = Arc<dyn Fn(&[PathBuf]) + Send + Sync>;
+
 pub struct FileProvider {
     indexer: Arc<RwLock<Option<FileIndexer>>>,
     watcher: Arc<Mutex<Option<FileWatcher>>>,
     config: Arc<RwLock<Option<IndexConfig>>>,
     watcher_running: Arc<AtomicBool>,
+    /// Bumped on every full (re)index, so derived data knows to rebuild
+    generation: AtomicU64,
+    change_listeners: RwLock<Vec<ChangeListener>>,
 }
 
 impl FileProvider {
@@ -19,6 +29,25 @@ impl FileProvider {
             watcher: Arc::new(Mutex::new(None)),
             config: Arc::new(RwLock::new(None)),
             watcher_running: Arc::new(AtomicBool::new(false)),
+            generation: AtomicU64::new(0),
+            change_listeners: RwLock::new(Vec::new()),
+        }
+    }
+
+    /// Called with the paths updated in the index between full reindexes
+    pub fn on_change(&self, listener: impl Fn(&[PathBuf]) + Send + Sync + 'static) {
+        self.change_listeners.write().push(Arc::new(listener));
+    }
+
+    /// Changes whenever the index is rebuilt from scratch
+    pub fn generation(&self) -> u64 {
+        self.generation.load(Ordering::SeqCst)
+    }
+
+    fn notify_changed(&self, paths: &[PathBuf]) {
+        let listeners = self.change_listeners.read().clone();
+        for listener in listeners {
+            listener(paths);
         }
     }
 
@@ -31,7 +60,9 @@ impl FileProvider {
         *lock = Some(config);
     }
 
-    pub fn initialize(&self) -> Result<usize, String> {
+    /// Open the index saved by the last run, so files are searchable before
+    /// the first scan
+    pub fn open(&self) -> Result<(), String> {
         let config = IndexConfig::load();
 
         let index_dir = dirs::data_dir()
@@ -40,7 +71,6 @@ impl FileProvider {
             .join("index");
 
         let indexer = FileIndexer::new(index_dir, config.clone())?;
-        let count = indexer.index_all()?;
 
         {
             let mut lock = self.indexer.write();
@@ -51,8 +81,17 @@ impl FileProvider {
             let mut lock = self.config.write();
             *lock = Some(config);
         }
+        self.generation.fetch_add(1, Ordering::SeqCst);
 
-        Ok(count)
+        Ok(())
+    }
+
+    /// Open the index if needed, then scan the index roots for changes
+    pub fn initialize(&self) -> Result<usize, String> {
+        if !self.is_initialized() {
+            self.open()?;
+        }
+        self.reindex()
     }
 
     pub fn start_watcher(&self) -> Result<(), String> {
@@ -99,7 +138,7 @@ impl FileProvider {
             None => return 0,
         };
 
-        let mut updated = 0;
+        let mut updated = Vec::new();
         for path in &changed_paths {
             if config.should_exclude(path) {
                 continue;
@@ -108,15 +147,17 @@ impl FileProvider {
             if let Err(e) = indexer.update_file(path) {
                 eprintln!("Failed to update index for {}: {}", path.display(), e);
             } else {
-                updated += 1;
+                updated.push(path.clone());
             }
         }
 
-        if updated > 0 {
-            let _ = indexer.commit();
-        }
+        drop(indexer_lock);
+        drop(config_lock);
 
-        updated
+        if !updated.is_empty() {
+            self.notify_changed(&updated);
+        }
+        updated.len()
     }
 
     pub fn is_initialized(&self) -> bool {
@@ -126,22 +167,84 @@ impl FileProvider {
     pub fn reindex(&self) -> Result<usize, String> {
         let lock = self.indexer.read();
         if let Some(indexer) = lock.as_ref() {
-            indexer.index_all()
+            let count = indexer.index_all()?;
+            self.generation.fetch_add(1, Ordering::SeqCst);
+            Ok(count)
         } else {
             Err("Indexer not initialized".to_string())
         }
     }
 
     pub fn update_file(&self, path: &std::path::Path) -> Result<(), String> {
-        let lock = self.indexer.read();
-        if let Some(indexer) = lock.as_ref() {
+        {
+            let lock = self.indexer.read();
+            let Some(indexer) = lock.as_ref() else {
+                return Ok(());
+            };
             indexer.update_file(path)?;
-            indexer.commit()?;
         }
+        self.notify_changed(&[path.to_path_buf()]);
         Ok(())
     }
 
-    fn get_file_icon(extension: &Option<String>, is_dir: bool) -> ResultIcon {
+    /// Files whose indexed text matches `query`
+    pub fn content_search(
+        &self,
+        query: &str,
+        limit: usize,
+    ) -> Result<Vec<crate::indexer::IndexedFile>, String> {
+        let lock = self.indexer.read();
+        match lock.as_ref() {
+            Some(indexer) => indexer.content_search(query, limit),
+            None => Err("Indexer not initialized".to_string()),
+        }
+    }
+
+    /// Every indexed entry with its size, for disk usage views
+    pub fn all_files(
+        &self,
+        progress: impl FnMut(usize, usize),
+    ) -> Result<Vec<crate::indexer::IndexedFile>, String> {
+        let lock = self.indexer.read();
+        match lock.as_ref() {
+            Some(indexer) => indexer.all_files(progress),
+            None => Err("Indexer not initialized".to_string()),
+        }
+    }
+
+    /// Build a result for a path that may not be indexed yet, e.g. the
+    /// output of a file operation
+    pub fn result_for_path(path: &std::path::Path) -> SearchResult {
+        let is_dir = path.is_dir();
+        let extension = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(|e| e.to_lowercase());
+        let path_str = path.to_string_lossy().to_string();
+
+        let subtitle = if is_dir {
+            path_str.clone()
+        } else {
+            let size = std::fs::metadata(path).map(|m| m.len()).unwrap_or(0);
+            format!("{} • {}", Self::format_size(size), path_str)
+        };
+
+        SearchResult {
+            id: format!("file:{}", path_str),
+            title: path
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_else(|| path_str.clone()),
+            subtitle: Some(subtitle),
+            icon: Self::get_file_icon(&path_str, &extension, is_dir),
+            category: ResultCategory::File,
+            score: 100.0,
+            aria_label: None,
+        }
+    }
+
+    /// Images get a thumbnail of `path`, with their emoji as placeholder
+    pub fn get_file_icon(path: &str, extension: &Option<String>, is_dir: bool) -> ResultIcon {
         if is_dir {
             return ResultIcon::Emoji("📁".to_string());
         }
@@ -169,7 +272,15 @@ impl FileProvider {
             _ => "📄",
         };
 
-        ResultIcon::Emoji(emoji.to_string())
+        match extension.as_deref() {
+            Some(extension) if crate::thumbnails::has_thumbnail(extension) => {
+                ResultIcon::Thumbnail {
+                    path: path.to_string(),
+                    placeholder: emoji.to_string(),
+                }
+            }
+            _ => ResultIcon::Emoji(emoji.to_string()),
+        }
     }
 
     fn format_size(size: u64) -> String {
@@ -187,14 +298,8 @@ impl FileProvider {
             format!("{} B", size)
         }
     }
-}
-
-impl SearchProvider for FileProvider {
-    fn id(&self) -> &str {
-        "files"
-    }
 
-    fn search(&self, query: &str) -> Vec<SearchResult> {
+    fn search_blocking(&self, query: &str) -> Vec<SearchResult> {
         if query.trim().len() < 2 {
             return vec![];
         }
@@ -224,13 +329,25 @@ impl SearchProvider for FileProvider {
                     id: format!("file:{}", file.path),
                     title: file.name,
                     subtitle: Some(subtitle),
-                    icon: Self::get_file_icon(&file.extension, file.is_dir),
+                    icon: Self::get_file_icon(&file.path, &file.extension, file.is_dir),
                     category: ResultCategory::File,
                     score: 50.0 - (idx as f32 * 0.5),
+                    aria_label: None,
                 }
             })
             .collect()
     }
+}
+
+#[async_trait]
+impl SearchProvider for FileProvider {
+    fn id(&self) -> &str {
+        "files"
+    }
+
+    async fn search(&self, query: &str, ctx: &SearchContext) -> Vec<SearchResult> {
+        ctx.run_blocking(|| self.search_blocking(query))
+    }
 
     fn execute(&self, result_id: &str) -> Result<(), String> {
         if let Some(path) = result_id.strip_prefix("file:") {
@@ -291,4 +408,66 @@ impl SearchProvider for FileProvider {
             Err("Invalid file result".to_string())
         }
     }
+
+    fn actions(&self, result_id: &str) -> Vec<ResultAction> {
+        let mut actions = super::path_actions();
+        actions.push(ResultAction::new("copy-name", "Copy name"));
+        // Carried out by the app, which reports progress and the outcome
+        actions.push(ResultAction::new("compress", "Compress to zip"));
+        let path = result_id.strip_prefix("file:").unwrap_or_default();
+        if crate::file_ops::ArchiveKind::from_path(std::path::Path::new(path)).is_some() {
+            actions.push(ResultAction::new("extract", "Extract here"));
+        }
+        actions
+    }
+
+    fn execute_action(&self, result_id: &str, action_id: &str) -> Result<(), String> {
+        let path = result_id
+            .strip_prefix("file:")
+            .ok_or("Invalid file result")?;
+        if action_id == "copy-name" {
+            let name = std::path::Path::new(path)
+                .file_name()
+                .map(|n| n.to_string_lossy().into_owned())
+                .unwrap_or_else(|| path.to_string());
+            return crate::clipboard::copy_text(&name);
+        }
+        if matches!(action_id, "compress" | "extract") {
+            return Err("Compressing and extracting run from the launcher window".to_string());
+        }
+        super::execute_path_action(path, action_id)
+    }
+
+    fn mutations(&self, _result_id: &str) -> Vec<&'static str> {
+        vec!["rename"]
+    }
+
+    fn mutate(&self, result_id: &str, mutation: &ResultMutation) -> Result<Mutated, String> {
+        let path = result_id
+            .strip_prefix("file:")
+            .ok_or("Invalid file result")?;
+        let ResultMutation::Rename { name } = mutation else {
+            return Err("Files can only be renamed".to_string());
+        };
+        let from = std::path::Path::new(path);
+        let to = crate::file_ops::rename_in_place(from, name)?;
+        let _ = self.update_file(from);
+        let _ = self.update_file(&to);
+
+        Ok(Mutated {
+            result: Self::result_for_path(&to),
+            undo: UndoableAction::RenameFile {
+                from: from.to_path_buf(),
+                to,
+            },
+        })
+    }
+
+    fn context(&self, result: &SearchResult) -> ResultContext {
+        let context = ResultContext::from_result(result);
+        match result.id.strip_prefix("file:") {
+            Some(path) => context.with_path(path),
+            None => context,
+        }
+    }
 }
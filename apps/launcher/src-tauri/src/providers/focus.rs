@@ -0,0 +1,143 @@
+use super::{ResultCategory, ResultIcon, SearchContext, SearchProvider, SearchResult};
+use crate::focus::{self, FocusManager};
+use async_trait::async_trait;
+use chrono::Utc;
+use std::sync::Arc;
+
+fn duration(minutes: u32) -> String {
+    match (minutes / 60, minutes % 60) {
+        (0, m) => format!("{} min", m),
+        (h, 0) => format!("{} h", h),
+        (h, m) => format!("{} h {} min", h, m),
+    }
+}
+
+/// `focus 50m` starts a focus session (Do Not Disturb, paused background
+/// work, Slack status); `focus` alone shows or ends the running one.
+pub struct FocusProvider {
+    manager: Arc<FocusManager>,
+}
+
+impl FocusProvider {
+    pub fn new(manager: Arc<FocusManager>) -> Self {
+        Self { manager }
+    }
+
+    fn start_result(&self, minutes: u32, restart: bool) -> SearchResult {
+        let steps: Vec<&str> = self
+            .manager
+            .planned_steps()
+            .iter()
+            .map(|s| s.label())
+            .collect();
+        let verb = if restart { "Restart" } else { "Start" };
+
+        SearchResult {
+            id: format!("focus:start:{}", minutes),
+            title: format!("{} focus for {}", verb, duration(minutes)),
+            subtitle: Some(if steps.is_empty() {
+                "Timer only • turn on steps in Settings".to_string()
+            } else {
+                steps.join(" • ")
+            }),
+            icon: ResultIcon::Emoji("🎧".to_string()),
+            category: ResultCategory::System,
+            score: 95.0,
+            aria_label: None,
+        }
+    }
+
+    fn results(&self, argument: &str) -> Vec<SearchResult> {
+        let status = self.manager.status();
+        let mut results = Vec::new();
+
+        if let Some(ends_at) = status.ends_at.filter(|_| status.active) {
+            let left = (ends_at - Utc::now()).num_minutes().max(0) as u32 + 1;
+            let failed: Vec<&str> = status
+                .steps
+                .iter()
+                .filter(|o| o.error.is_some())
+                .map(|o| o.step.label())
+                .collect();
+            let subtitle = if failed.is_empty() {
+                format!(
+                    "Until {}",
+                    ends_at.with_timezone(&chrono::Local).format("%H:%M")
+                )
+            } else {
+                format!("Couldn't set {}", failed.join(", "))
+            };
+            results.push(SearchResult {
+                id: "focus:stop".to_string(),
+                title: format!("End focus ({} left)", duration(left)),
+                subtitle: Some(subtitle),
+                icon: ResultIcon::Emoji("⏹️".to_string()),
+                category: ResultCategory::System,
+                score: if argument.is_empty() { 95.0 } else { 90.0 },
+                aria_label: None,
+            });
+        }
+
+        let minutes = if argument.is_empty() {
+            (!status.active).then(|| self.manager.default_minutes())
+        } else {
+            focus::parse_minutes(argument)
+        };
+        if let Some(minutes) = minutes {
+            results.push(self.start_result(minutes, status.active));
+        }
+        results
+    }
+}
+
+#[async_trait]
+impl SearchProvider for FocusProvider {
+    fn id(&self) -> &str {
+        "focus"
+    }
+
+    async fn search(&self, query: &str, _ctx: &SearchContext) -> Vec<SearchResult> {
+        let query = query.trim().to_lowercase();
+        let argument = match query.strip_prefix("focus") {
+            Some(rest) if rest.is_empty() || rest.starts_with(' ') => rest.trim(),
+            _ => return vec![],
+        };
+        self.results(argument)
+    }
+
+    fn execute(&self, result_id: &str) -> Result<(), String> {
+        let minutes: Option<u32> = if result_id == "focus:stop" {
+            None
+        } else {
+            let minutes = result_id
+                .strip_prefix("focus:start:")
+                .and_then(|m| m.parse().ok())
+                .ok_or("Invalid focus result")?;
+            Some(minutes)
+        };
+
+        let manager = self.manager.clone();
+        // Setting the Slack status is a network call; don't hold up the UI
+        std::thread::spawn(move || match minutes {
+            Some(minutes) => {
+                manager.start(minutes);
+            }
+            None => {
+                manager.stop();
+            }
+        });
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_durations() {
+        assert_eq!(duration(25), "25 min");
+        assert_eq!(duration(120), "2 h");
+        assert_eq!(duration(90), "1 h 30 min");
+    }
+}
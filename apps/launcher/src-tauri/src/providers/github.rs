@@ -1,35 +1,14 @@
-use super::{ResultCategory, ResultIcon, SearchProvider, SearchResult};
+use super::{ResultCategory, ResultIcon, SearchContext, SearchProvider, SearchResult};
 use crate::oauth::OAuthFlow;
-use parking_lot::RwLock;
+use crate::search_cache::{CachePolicy, ResultUrls};
+use async_trait::async_trait;
 use serde::Deserialize;
 use std::collections::HashMap;
 use std::sync::Arc;
 
 pub struct GitHubProvider {
     oauth_flow: Arc<OAuthFlow>,
-    cache: RwLock<SearchCache>,
-}
-
-struct SearchCache {
-    query: String,
-    results: Vec<SearchResult>,
-    urls: HashMap<String, String>,
-    timestamp: std::time::Instant,
-}
-
-impl SearchCache {
-    fn new() -> Self {
-        Self {
-            query: String::new(),
-            results: Vec::new(),
-            urls: HashMap::new(),
-            timestamp: std::time::Instant::now(),
-        }
-    }
-
-    fn is_valid(&self, query: &str) -> bool {
-        self.query == query && self.timestamp.elapsed().as_secs() < 60
-    }
+    urls: ResultUrls,
 }
 
 #[derive(Debug, Deserialize)]
@@ -51,7 +30,7 @@ impl GitHubProvider {
     pub fn new(oauth_flow: Arc<OAuthFlow>) -> Self {
         Self {
             oauth_flow,
-            cache: RwLock::new(SearchCache::new()),
+            urls: ResultUrls::new(),
         }
     }
 
@@ -64,14 +43,6 @@ impl GitHubProvider {
             return Vec::new();
         }
 
-        // Check cache first
-        {
-            let cache = self.cache.read();
-            if cache.is_valid(query) {
-                return cache.results.clone();
-            }
-        }
-
         // Check if connected to GitHub
         if !self.oauth_flow.is_connected("github") {
             return vec![SearchResult {
@@ -81,6 +52,7 @@ impl GitHubProvider {
                 icon: ResultIcon::Emoji("🔗".to_string()),
                 category: ResultCategory::GitHub,
                 score: 50.0,
+                aria_label: None,
             }];
         }
 
@@ -126,6 +98,7 @@ impl GitHubProvider {
                                 icon: ResultIcon::Emoji("📦".to_string()),
                                 category: ResultCategory::GitHub,
                                 score: 100.0 - (i as f32 * 5.0),
+                                aria_label: None,
                             }
                         })
                         .collect();
@@ -143,30 +116,36 @@ impl GitHubProvider {
             }
         };
 
-        // Update cache
-        {
-            let mut cache = self.cache.write();
-            cache.query = query.to_string();
-            cache.results = results.clone();
-            cache.urls = urls;
-            cache.timestamp = std::time::Instant::now();
-        }
+        self.urls.remember(urls);
 
         results
     }
+
+    fn search_blocking(&self, query: &str) -> Vec<SearchResult> {
+        // Only search GitHub if query starts with "gh " prefix
+        if let Some(gh_query) = query.strip_prefix("gh ") {
+            self.search_github(gh_query.trim())
+        } else {
+            Vec::new()
+        }
+    }
 }
 
+#[async_trait]
 impl SearchProvider for GitHubProvider {
     fn id(&self) -> &str {
         "github"
     }
 
-    fn search(&self, query: &str) -> Vec<SearchResult> {
-        // Only search GitHub if query starts with "gh " prefix
-        if let Some(gh_query) = query.strip_prefix("gh ") {
-            self.search_github(gh_query.trim())
+    async fn search(&self, query: &str, ctx: &SearchContext) -> Vec<SearchResult> {
+        ctx.run_blocking(|| self.search_blocking(query))
+    }
+
+    fn cache_policy(&self, query: &str) -> CachePolicy {
+        if query.starts_with("gh ") && self.oauth_flow.is_connected("github") {
+            CachePolicy::Remote
         } else {
-            Vec::new()
+            CachePolicy::Never
         }
     }
 
@@ -176,8 +155,7 @@ impl SearchProvider for GitHubProvider {
         }
 
         if result_id.starts_with("github:repo:") {
-            let cache = self.cache.read();
-            if let Some(url) = cache.urls.get(result_id) {
+            if let Some(url) = self.urls.get(result_id).as_deref() {
                 std::process::Command::new("xdg-open")
                     .arg(url)
                     .spawn()
@@ -1,36 +1,15 @@
-use super::{ResultCategory, ResultIcon, SearchProvider, SearchResult};
+use super::{ResultCategory, ResultIcon, SearchContext, SearchProvider, SearchResult};
 use crate::oauth::OAuthFlow;
+use crate::search_cache::{CachePolicy, ResultUrls};
+use async_trait::async_trait;
 use chrono::{DateTime, Local, Utc};
-use parking_lot::RwLock;
 use serde::Deserialize;
 use std::collections::HashMap;
 use std::sync::Arc;
 
 pub struct GoogleCalendarProvider {
     oauth_flow: Arc<OAuthFlow>,
-    cache: RwLock<SearchCache>,
-}
-
-struct SearchCache {
-    query: String,
-    results: Vec<SearchResult>,
-    urls: HashMap<String, String>,
-    timestamp: std::time::Instant,
-}
-
-impl SearchCache {
-    fn new() -> Self {
-        Self {
-            query: String::new(),
-            results: Vec::new(),
-            urls: HashMap::new(),
-            timestamp: std::time::Instant::now(),
-        }
-    }
-
-    fn is_valid(&self, query: &str) -> bool {
-        self.query == query && self.timestamp.elapsed().as_secs() < 60
-    }
+    urls: ResultUrls,
 }
 
 #[derive(Debug, Deserialize)]
@@ -69,7 +48,7 @@ impl GoogleCalendarProvider {
     pub fn new(oauth_flow: Arc<OAuthFlow>) -> Self {
         Self {
             oauth_flow,
-            cache: RwLock::new(SearchCache::new()),
+            urls: ResultUrls::new(),
         }
     }
 
@@ -97,14 +76,6 @@ impl GoogleCalendarProvider {
             return Vec::new();
         }
 
-        // Check cache first
-        {
-            let cache = self.cache.read();
-            if cache.is_valid(query) {
-                return cache.results.clone();
-            }
-        }
-
         // Check if connected to Google
         if !self.oauth_flow.is_connected("google") {
             return vec![SearchResult {
@@ -114,6 +85,7 @@ impl GoogleCalendarProvider {
                 icon: ResultIcon::Emoji("🔗".to_string()),
                 category: ResultCategory::Plugin,
                 score: 50.0,
+                aria_label: None,
             }];
         }
 
@@ -178,6 +150,7 @@ impl GoogleCalendarProvider {
                                 icon: ResultIcon::Emoji("📅".to_string()),
                                 category: ResultCategory::Plugin,
                                 score: 100.0 - (i as f32 * 5.0),
+                                aria_label: None,
                             }
                         })
                         .collect();
@@ -198,30 +171,36 @@ impl GoogleCalendarProvider {
             }
         };
 
-        // Update cache
-        {
-            let mut cache = self.cache.write();
-            cache.query = query.to_string();
-            cache.results = results.clone();
-            cache.urls = urls;
-            cache.timestamp = std::time::Instant::now();
-        }
+        self.urls.remember(urls);
 
         results
     }
+
+    fn search_blocking(&self, query: &str) -> Vec<SearchResult> {
+        // Only search Google Calendar if query starts with "gc " prefix
+        if let Some(cal_query) = query.strip_prefix("gc ") {
+            self.search_calendar(cal_query.trim())
+        } else {
+            Vec::new()
+        }
+    }
 }
 
+#[async_trait]
 impl SearchProvider for GoogleCalendarProvider {
     fn id(&self) -> &str {
         "google_calendar"
     }
 
-    fn search(&self, query: &str) -> Vec<SearchResult> {
-        // Only search Google Calendar if query starts with "gc " prefix
-        if let Some(cal_query) = query.strip_prefix("gc ") {
-            self.search_calendar(cal_query.trim())
+    async fn search(&self, query: &str, ctx: &SearchContext) -> Vec<SearchResult> {
+        ctx.run_blocking(|| self.search_blocking(query))
+    }
+
+    fn cache_policy(&self, query: &str) -> CachePolicy {
+        if query.starts_with("gc ") && self.oauth_flow.is_connected("google") {
+            CachePolicy::Remote
         } else {
-            Vec::new()
+            CachePolicy::Never
         }
     }
 
@@ -231,8 +210,7 @@ impl SearchProvider for GoogleCalendarProvider {
         }
 
         if result_id.starts_with("gcal:event:") {
-            let cache = self.cache.read();
-            if let Some(url) = cache.urls.get(result_id) {
+            if let Some(url) = self.urls.get(result_id).as_deref() {
                 #[cfg(target_os = "linux")]
                 {
                     std::process::Command::new("xdg-open")
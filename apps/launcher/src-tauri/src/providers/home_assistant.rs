@@ -0,0 +1,471 @@
+use super::{ResultCategory, ResultIcon, SearchContext, SearchProvider, SearchResult};
+use crate::vault::SecretVault;
+use async_trait::async_trait;
+use fuzzy_matcher::skim::SkimMatcherV2;
+use fuzzy_matcher::FuzzyMatcher;
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::net::{TcpStream, ToSocketAddrs};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+const VAULT_KEY: &str = "home_assistant_token";
+/// Delay before reconnecting a dropped websocket
+const RECONNECT_DELAY: Duration = Duration::from_secs(30);
+/// Limit on connecting and the websocket handshake
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+type Socket = tungstenite::WebSocket<tungstenite::stream::MaybeTlsStream<TcpStream>>;
+type ErrorListener = Arc<dyn Fn(String) + Send + Sync>;
+
+/// Domains that can be acted on from search
+const CONTROLLABLE_DOMAINS: &[&str] = &[
+    "light",
+    "switch",
+    "fan",
+    "input_boolean",
+    "scene",
+    "script",
+    "automation",
+    "lock",
+    "cover",
+    "media_player",
+];
+
+/// Domains whose actions open doors or windows, only offered behind `ha:`
+const SENSITIVE_DOMAINS: &[&str] = &["lock", "cover"];
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct HomeAssistantConfig {
+    pub url: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HomeAssistantStatus {
+    pub url: Option<String>,
+    pub configured: bool,
+    /// The websocket is connected and entity states are live
+    pub connected: bool,
+    pub entity_count: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HaEntity {
+    pub entity_id: String,
+    pub state: String,
+    pub name: String,
+}
+
+impl HaEntity {
+    fn from_state(value: &serde_json::Value) -> Option<Self> {
+        let entity_id = value.get("entity_id")?.as_str()?.to_string();
+        let state = value
+            .get("state")?
+            .as_str()
+            .unwrap_or("unknown")
+            .to_string();
+        let name = value
+            .get("attributes")
+            .and_then(|a| a.get("friendly_name"))
+            .and_then(|n| n.as_str())
+            .unwrap_or(&entity_id)
+            .to_string();
+        Some(Self {
+            entity_id,
+            state,
+            name,
+        })
+    }
+
+    pub fn domain(&self) -> &str {
+        self.entity_id.split('.').next().unwrap_or("")
+    }
+
+    /// The service that toggles or activates this entity
+    fn action(&self) -> Option<(&'static str, &'static str)> {
+        Some(match self.domain() {
+            "light" | "switch" | "fan" | "input_boolean" | "cover" | "media_player" => {
+                ("Toggle", "toggle")
+            }
+            "scene" | "script" => ("Activate", "turn_on"),
+            "automation" => ("Trigger", "trigger"),
+            "lock" if self.state == "locked" => ("Unlock", "unlock"),
+            "lock" => ("Lock", "lock"),
+            _ => return None,
+        })
+    }
+
+    fn icon(&self) -> &'static str {
+        match self.domain() {
+            "light" => "💡",
+            "switch" | "input_boolean" => "🔌",
+            "fan" => "🌀",
+            "scene" => "🎬",
+            "script" | "automation" => "⚙️",
+            "lock" => "🔒",
+            "cover" => "🪟",
+            "media_player" => "🔊",
+            _ => "🏠",
+        }
+    }
+}
+
+/// Home Assistant entities and scenes, triggered with `ha:`.
+///
+/// Entity states are kept current over the websocket API; actions go through
+/// the REST API.
+pub struct HomeAssistantProvider {
+    vault: Arc<SecretVault>,
+    config: RwLock<HomeAssistantConfig>,
+    config_path: PathBuf,
+    entities: RwLock<HashMap<String, HaEntity>>,
+    connected: AtomicBool,
+    /// Bumped on reconfiguration so a running sync loop knows to exit
+    generation: AtomicU64,
+    matcher: SkimMatcherV2,
+    error_listener: RwLock<Option<ErrorListener>>,
+}
+
+impl HomeAssistantProvider {
+    pub fn new(vault: Arc<SecretVault>) -> Self {
+        let config_path = dirs::data_dir()
+            .unwrap_or_else(|| PathBuf::from("."))
+            .join("launcher")
+            .join("home_assistant.json");
+        let config = std::fs::read_to_string(&config_path)
+            .ok()
+            .and_then(|c| serde_json::from_str(&c).ok())
+            .unwrap_or_default();
+
+        Self {
+            vault,
+            config: RwLock::new(config),
+            config_path,
+            entities: RwLock::new(HashMap::new()),
+            connected: AtomicBool::new(false),
+            generation: AtomicU64::new(0),
+            matcher: SkimMatcherV2::default(),
+            error_listener: RwLock::new(None),
+        }
+    }
+
+    /// Called when an action run from search fails; successes show up as
+    /// state changes
+    pub fn set_error_listener(&self, listener: impl Fn(String) + Send + Sync + 'static) {
+        *self.error_listener.write() = Some(Arc::new(listener));
+    }
+
+    fn credentials(&self) -> Option<(String, String)> {
+        let url = self.config.read().url.clone()?;
+        let token = self.vault.get(VAULT_KEY)?;
+        Some((url, token))
+    }
+
+    pub fn status(&self) -> HomeAssistantStatus {
+        HomeAssistantStatus {
+            url: self.config.read().url.clone(),
+            configured: self.credentials().is_some(),
+            connected: self.connected.load(Ordering::SeqCst),
+            entity_count: self.entities.read().len(),
+        }
+    }
+
+    pub fn entities(&self) -> Vec<HaEntity> {
+        let mut entities: Vec<HaEntity> = self.entities.read().values().cloned().collect();
+        entities.sort_by(|a, b| a.name.cmp(&b.name));
+        entities
+    }
+
+    /// Validate and store the instance URL and long-lived access token, then
+    /// (re)start the websocket sync
+    pub fn configure(
+        self: &Arc<Self>,
+        url: &str,
+        token: &str,
+    ) -> Result<HomeAssistantStatus, String> {
+        let url = url.trim().trim_end_matches('/').to_string();
+        let token = token.trim();
+
+        let response = reqwest::blocking::Client::new()
+            .get(format!("{}/api/", url))
+            .bearer_auth(token)
+            .timeout(Duration::from_secs(5))
+            .send()
+            .map_err(|e| format!("Failed to reach Home Assistant: {}", e))?;
+        if response.status() == reqwest::StatusCode::UNAUTHORIZED {
+            return Err("Home Assistant rejected the access token".to_string());
+        }
+        if !response.status().is_success() {
+            return Err(format!(
+                "Home Assistant returned status {}",
+                response.status()
+            ));
+        }
+
+        self.vault.set(VAULT_KEY, token)?;
+        *self.config.write() = HomeAssistantConfig { url: Some(url) };
+        self.save_config()?;
+
+        self.start_sync();
+        Ok(self.status())
+    }
+
+    pub fn disconnect(&self) -> Result<(), String> {
+        self.generation.fetch_add(1, Ordering::SeqCst);
+        *self.config.write() = HomeAssistantConfig::default();
+        self.entities.write().clear();
+        self.connected.store(false, Ordering::SeqCst);
+        self.save_config()?;
+        self.vault.delete(VAULT_KEY)
+    }
+
+    fn save_config(&self) -> Result<(), String> {
+        if let Some(parent) = self.config_path.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+        let json = serde_json::to_string_pretty(&*self.config.read()).map_err(|e| e.to_string())?;
+        std::fs::write(&self.config_path, json).map_err(|e| e.to_string())
+    }
+
+    /// Start the websocket sync loop on a background thread, replacing any
+    /// loop that is already running
+    pub fn start_sync(self: &Arc<Self>) {
+        if self.credentials().is_none() {
+            return;
+        }
+
+        let generation = self.generation.fetch_add(1, Ordering::SeqCst) + 1;
+        let provider = self.clone();
+        std::thread::spawn(move || {
+            while provider.generation.load(Ordering::SeqCst) == generation {
+                let Some((url, token)) = provider.credentials() else {
+                    break;
+                };
+                if let Err(e) = provider.sync(&url, &token, generation) {
+                    eprintln!("Home Assistant websocket: {}", e);
+                }
+                // A newer loop owns the connection state once this one is
+                // replaced
+                if provider.generation.load(Ordering::SeqCst) == generation {
+                    provider.connected.store(false, Ordering::SeqCst);
+                }
+                std::thread::sleep(RECONNECT_DELAY);
+            }
+        });
+    }
+
+    /// Connect, load all states and apply `state_changed` events until the
+    /// connection drops or the provider is reconfigured
+    fn sync(&self, url: &str, token: &str, generation: u64) -> Result<(), String> {
+        use tungstenite::Message;
+
+        let ws_url = format!(
+            "{}/api/websocket",
+            url.replacen("https://", "wss://", 1)
+                .replacen("http://", "ws://", 1)
+        );
+        let mut socket = Self::connect(&ws_url)?;
+
+        loop {
+            if self.generation.load(Ordering::SeqCst) != generation {
+                let _ = socket.close(None);
+                return Ok(());
+            }
+
+            let text = match socket.read().map_err(|e| e.to_string())? {
+                Message::Text(text) => text.to_string(),
+                Message::Close(_) => return Err("connection closed".to_string()),
+                _ => continue,
+            };
+            let Ok(msg) = serde_json::from_str::<serde_json::Value>(&text) else {
+                continue;
+            };
+
+            match msg.get("type").and_then(|t| t.as_str()) {
+                Some("auth_required") => {
+                    let auth = serde_json::json!({ "type": "auth", "access_token": token });
+                    socket
+                        .send(Message::text(auth.to_string()))
+                        .map_err(|e| e.to_string())?;
+                }
+                Some("auth_invalid") => return Err("authentication failed".to_string()),
+                Some("auth_ok") if self.generation.load(Ordering::SeqCst) == generation => {
+                    self.connected.store(true, Ordering::SeqCst);
+                    let requests = [
+                        serde_json::json!({ "id": 1, "type": "get_states" }),
+                        serde_json::json!({
+                            "id": 2,
+                            "type": "subscribe_events",
+                            "event_type": "state_changed"
+                        }),
+                    ];
+                    for request in requests {
+                        socket
+                            .send(Message::text(request.to_string()))
+                            .map_err(|e| e.to_string())?;
+                    }
+                }
+                Some("result") if msg.get("id").and_then(|id| id.as_u64()) == Some(1) => {
+                    if let Some(states) = msg.get("result").and_then(|r| r.as_array()) {
+                        let entities = states
+                            .iter()
+                            .filter_map(HaEntity::from_state)
+                            .map(|e| (e.entity_id.clone(), e))
+                            .collect();
+                        *self.entities.write() = entities;
+                    }
+                }
+                Some("event") => {
+                    let Some(data) = msg.get("event").and_then(|e| e.get("data")) else {
+                        continue;
+                    };
+                    let entity_id = data.get("entity_id").and_then(|id| id.as_str());
+                    match (entity_id, data.get("new_state")) {
+                        (Some(id), Some(state)) if !state.is_null() => {
+                            if let Some(entity) = HaEntity::from_state(state) {
+                                self.entities.write().insert(id.to_string(), entity);
+                            }
+                        }
+                        (Some(id), _) => {
+                            self.entities.write().remove(id);
+                        }
+                        _ => {}
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Open the websocket, giving up if the host doesn't answer in time
+    fn connect(ws_url: &str) -> Result<Socket, String> {
+        let parsed = url::Url::parse(ws_url).map_err(|e| e.to_string())?;
+        let host = parsed.host_str().ok_or("Home Assistant URL has no host")?;
+        let port = parsed.port_or_known_default().unwrap_or(80);
+        let addr = (host, port)
+            .to_socket_addrs()
+            .map_err(|e| e.to_string())?
+            .next()
+            .ok_or_else(|| format!("Could not resolve {}", host))?;
+
+        let stream =
+            TcpStream::connect_timeout(&addr, CONNECT_TIMEOUT).map_err(|e| e.to_string())?;
+        stream
+            .set_read_timeout(Some(CONNECT_TIMEOUT))
+            .map_err(|e| e.to_string())?;
+        let tcp = stream.try_clone().map_err(|e| e.to_string())?;
+        let (socket, _) = tungstenite::client_tls(ws_url, stream).map_err(|e| e.to_string())?;
+        // Once connected, reads wait for the next state change
+        tcp.set_read_timeout(None).map_err(|e| e.to_string())?;
+        Ok(socket)
+    }
+
+    /// Call a service in the background, for search, which mustn't wait on
+    /// the network
+    fn run_service(&self, entity: HaEntity, service: &'static str) -> Result<(), String> {
+        let (url, token) = self
+            .credentials()
+            .ok_or("Home Assistant is not connected")?;
+        let listener = self.error_listener.read().clone();
+        tauri::async_runtime::spawn_blocking(move || {
+            if let Err(e) = call_service(&url, &token, &entity, service) {
+                match listener {
+                    Some(listener) => listener(e),
+                    None => eprintln!("Home Assistant: {}", e),
+                }
+            }
+        });
+        Ok(())
+    }
+
+    fn search_blocking(&self, query: &str) -> Vec<SearchResult> {
+        let query = query.trim();
+        let (filter, triggered) = match query
+            .strip_prefix("ha:")
+            .or_else(|| query.strip_prefix("ha "))
+        {
+            Some(rest) => (rest.trim(), true),
+            None => (query, false),
+        };
+        if !triggered && filter.len() < 2 {
+            return vec![];
+        }
+
+        let entities = self.entities.read();
+        let mut results: Vec<SearchResult> = entities
+            .values()
+            .filter(|e| CONTROLLABLE_DOMAINS.contains(&e.domain()))
+            .filter(|e| triggered || !SENSITIVE_DOMAINS.contains(&e.domain()))
+            .filter_map(|e| {
+                let score = if filter.is_empty() {
+                    0
+                } else {
+                    self.matcher.fuzzy_match(&e.name, filter)?
+                };
+                let (verb, _) = e.action()?;
+                Some(SearchResult {
+                    id: format!("ha:{}", e.entity_id),
+                    title: format!("{} {}", verb, e.name),
+                    subtitle: Some(format!("{} • {}", e.entity_id, e.state)),
+                    icon: ResultIcon::Emoji(e.icon().to_string()),
+                    category: ResultCategory::Command,
+                    // Outside the trigger, rank below apps with similar names
+                    score: if triggered { 70.0 } else { 30.0 } + (score as f32 / 10.0).min(25.0),
+                    aria_label: None,
+                })
+            })
+            .collect();
+
+        results.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap());
+        results.truncate(if triggered { 15 } else { 3 });
+        results
+    }
+}
+
+/// Call a service on an entity. Blocks for up to 5 seconds.
+fn call_service(url: &str, token: &str, entity: &HaEntity, service: &str) -> Result<(), String> {
+    reqwest::blocking::Client::new()
+        .post(format!(
+            "{}/api/services/{}/{}",
+            url,
+            entity.domain(),
+            service
+        ))
+        .bearer_auth(token)
+        .json(&serde_json::json!({ "entity_id": entity.entity_id }))
+        .timeout(Duration::from_secs(5))
+        .send()
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| format!("Failed to call {}.{}: {}", entity.domain(), service, e))?;
+    Ok(())
+}
+
+#[async_trait]
+impl SearchProvider for HomeAssistantProvider {
+    fn id(&self) -> &str {
+        "ha"
+    }
+
+    async fn search(&self, query: &str, ctx: &SearchContext) -> Vec<SearchResult> {
+        ctx.run_blocking(|| self.search_blocking(query))
+    }
+
+    fn execute(&self, result_id: &str) -> Result<(), String> {
+        let entity_id = result_id
+            .strip_prefix("ha:")
+            .ok_or("Invalid Home Assistant result")?;
+
+        let entity = self
+            .entities
+            .read()
+            .get(entity_id)
+            .cloned()
+            .ok_or("Entity not found")?;
+        let (_, service) = entity.action().ok_or("Entity has no action")?;
+
+        self.run_service(entity, service)
+    }
+}
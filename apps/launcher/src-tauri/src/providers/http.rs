@@ -0,0 +1,499 @@
+use super::{ResultCategory, ResultIcon, SearchContext, SearchProvider, SearchResult};
+use crate::vault::SecretVault;
+use async_trait::async_trait;
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(15);
+const USER_AGENT: &str = concat!("launcher/", env!("CARGO_PKG_VERSION"));
+const PREVIEW_CHARS: usize = 200;
+/// Bodies above this are cut off; the launcher isn't a download manager
+const MAX_BODY_BYTES: usize = 5 * 1024 * 1024;
+const METHODS: &[&str] = &["GET", "POST", "PUT", "PATCH", "DELETE", "HEAD", "OPTIONS"];
+
+type ResponseListener = Arc<dyn Fn(&HttpRequest, Result<&HttpResponse, &str>) + Send + Sync>;
+
+fn preset_vault_key(name: &str) -> String {
+    format!("http_preset:{}", name)
+}
+
+/// A parsed `http:` line: `[METHOD] URL [@preset...] [-d BODY]`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HttpRequest {
+    pub method: String,
+    pub url: String,
+    pub presets: Vec<String>,
+    pub body: Option<String>,
+}
+
+impl HttpRequest {
+    pub fn parse(line: &str) -> Option<Self> {
+        let (line, body) = match line.split_once(" -d ") {
+            Some((head, body)) => (head, Some(body.trim().to_string())),
+            None => (line, None),
+        };
+
+        let mut words = line.split_whitespace().peekable();
+        let method = match words.peek() {
+            Some(word) if METHODS.contains(&word.to_uppercase().as_str()) => {
+                words.next().map(|m| m.to_uppercase())?
+            }
+            _ => "GET".to_string(),
+        };
+        let mut url = words.next()?.to_string();
+        if !url.contains("://") {
+            url = format!("https://{}", url);
+        }
+        let url = reqwest::Url::parse(&url).ok()?;
+        if !matches!(url.scheme(), "http" | "https") || url.host_str().is_none() {
+            return None;
+        }
+
+        let presets = words
+            .filter_map(|w| w.strip_prefix('@'))
+            .filter(|p| !p.is_empty())
+            .map(String::from)
+            .collect();
+
+        Some(Self {
+            method,
+            url: url.to_string(),
+            presets,
+            body: body.filter(|b| !b.is_empty()),
+        })
+    }
+
+    fn summary(&self) -> String {
+        format!("{} {}", self.method, self.url)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HttpResponse {
+    pub status: u16,
+    pub status_text: String,
+    pub elapsed_ms: u64,
+    pub content_type: Option<String>,
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+    pub truncated: bool,
+}
+
+/// Lightweight REST client: `http: GET https://api.example.com/users @github`
+///
+/// Typing never sends anything; the request goes out in the background when
+/// the "Send" result is executed, the listener hears how it went, and the
+/// response then shows up as results for the same line.
+/// Header presets are `Name: value` lines stored in the vault.
+pub struct HttpProvider {
+    vault: Arc<SecretVault>,
+    client: reqwest::blocking::Client,
+    presets_path: PathBuf,
+    preset_names: RwLock<Vec<String>>,
+    last: Arc<RwLock<Option<(HttpRequest, HttpResponse)>>>,
+    listener: RwLock<Option<ResponseListener>>,
+}
+
+impl HttpProvider {
+    pub fn new(vault: Arc<SecretVault>) -> Self {
+        let presets_path = dirs::data_dir()
+            .unwrap_or_else(|| PathBuf::from("."))
+            .join("launcher")
+            .join("http_presets.json");
+        let preset_names = crate::journal::load_json_store(&presets_path).unwrap_or_default();
+
+        Self {
+            vault,
+            client: reqwest::blocking::Client::builder()
+                .timeout(REQUEST_TIMEOUT)
+                .user_agent(USER_AGENT)
+                .build()
+                .unwrap_or_default(),
+            presets_path,
+            preset_names: RwLock::new(preset_names),
+            last: Arc::new(RwLock::new(None)),
+            listener: RwLock::new(None),
+        }
+    }
+
+    pub fn list_presets(&self) -> Vec<String> {
+        self.preset_names.read().clone()
+    }
+
+    /// Store a header preset; `headers` is one `Name: value` per line
+    pub fn set_preset(&self, name: &str, headers: &str) -> Result<(), String> {
+        let name = name.trim();
+        if name.is_empty() || name.contains(char::is_whitespace) {
+            return Err("Preset names can't be empty or contain spaces".to_string());
+        }
+        parse_headers(headers)?;
+        self.vault.set(&preset_vault_key(name), headers)?;
+
+        let mut names = self.preset_names.write();
+        if !names.iter().any(|n| n == name) {
+            names.push(name.to_string());
+            names.sort();
+            self.save_presets(&names)?;
+        }
+        Ok(())
+    }
+
+    pub fn remove_preset(&self, name: &str) -> Result<(), String> {
+        self.vault.delete(&preset_vault_key(name))?;
+        let mut names = self.preset_names.write();
+        names.retain(|n| n != name);
+        self.save_presets(&names)
+    }
+
+    fn save_presets(&self, names: &[String]) -> Result<(), String> {
+        let json = serde_json::to_string_pretty(names).map_err(|e| e.to_string())?;
+        crate::journal::write_json_store(&self.presets_path, &json)
+    }
+
+    /// Called when a request sent from search gets a response or fails
+    pub fn set_listener(
+        &self,
+        listener: impl Fn(&HttpRequest, Result<&HttpResponse, &str>) + Send + Sync + 'static,
+    ) {
+        *self.listener.write() = Some(Arc::new(listener));
+    }
+
+    pub fn last_response(&self) -> Option<(HttpRequest, HttpResponse)> {
+        self.last.read().clone()
+    }
+
+    pub fn send(&self, request: &HttpRequest) -> Result<HttpResponse, String> {
+        let result = receive(self.build(request)?)?;
+        *self.last.write() = Some((request.clone(), result.clone()));
+        Ok(result)
+    }
+
+    /// Send in the background, for search, which mustn't wait on the network
+    fn send_in_background(&self, request: HttpRequest) -> Result<(), String> {
+        let builder = self.build(&request)?;
+        let last = self.last.clone();
+        let listener = self.listener.read().clone();
+        tauri::async_runtime::spawn_blocking(move || {
+            let result = receive(builder);
+            if let Ok(response) = &result {
+                *last.write() = Some((request.clone(), response.clone()));
+            }
+            if let Some(listener) = listener {
+                listener(&request, result.as_ref().map_err(String::as_str));
+            }
+        });
+        Ok(())
+    }
+
+    /// The request with its presets' headers, ready to send
+    fn build(&self, request: &HttpRequest) -> Result<reqwest::blocking::RequestBuilder, String> {
+        let method =
+            reqwest::Method::from_bytes(request.method.as_bytes()).map_err(|e| e.to_string())?;
+        let mut builder = self.client.request(method, &request.url);
+        for preset in &request.presets {
+            let headers = self
+                .vault
+                .get(&preset_vault_key(preset))
+                .ok_or_else(|| format!("Unknown header preset: @{}", preset))?;
+            for (name, value) in parse_headers(&headers)? {
+                builder = builder.header(name, value);
+            }
+        }
+        if let Some(body) = &request.body {
+            if serde_json::from_str::<serde_json::Value>(body).is_ok() {
+                builder = builder.header("Content-Type", "application/json");
+            }
+            builder = builder.body(body.clone());
+        }
+        Ok(builder)
+    }
+
+    /// Write the last response body to the downloads folder
+    pub fn save_last_body(&self) -> Result<PathBuf, String> {
+        let (request, response) = self.last_response().ok_or("No response to save")?;
+        let dir = dirs::download_dir()
+            .or_else(dirs::home_dir)
+            .ok_or("No downloads folder")?;
+        let path = dir.join(response_file_name(&request, &response));
+        std::fs::write(&path, &response.body).map_err(|e| e.to_string())?;
+        Ok(path)
+    }
+}
+
+/// Send a request and read its response. Blocks for up to `REQUEST_TIMEOUT`.
+fn receive(builder: reqwest::blocking::RequestBuilder) -> Result<HttpResponse, String> {
+    let started = Instant::now();
+    let response = builder
+        .send()
+        .map_err(|e| format!("Request failed: {}", e))?;
+    let status = response.status();
+    let headers: Vec<(String, String)> = response
+        .headers()
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.to_str().unwrap_or("").to_string()))
+        .collect();
+    let content_type = response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .map(String::from);
+    let bytes = response.bytes().map_err(|e| e.to_string())?;
+    let elapsed_ms = started.elapsed().as_millis() as u64;
+
+    let truncated = bytes.len() > MAX_BODY_BYTES;
+    let bytes = &bytes[..bytes.len().min(MAX_BODY_BYTES)];
+    let mut body = String::from_utf8_lossy(bytes).into_owned();
+    if content_type.as_deref().is_some_and(|t| t.contains("json")) {
+        if let Ok(value) = serde_json::from_str::<serde_json::Value>(&body) {
+            body = serde_json::to_string_pretty(&value).unwrap_or(body);
+        }
+    }
+
+    Ok(HttpResponse {
+        status: status.as_u16(),
+        status_text: status.canonical_reason().unwrap_or("").to_string(),
+        elapsed_ms,
+        content_type,
+        headers,
+        body,
+        truncated,
+    })
+}
+
+fn parse_headers(text: &str) -> Result<Vec<(String, String)>, String> {
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(|line| {
+            let (name, value) = line
+                .split_once(':')
+                .ok_or_else(|| format!("Invalid header line: {}", line))?;
+            let name = name.trim();
+            reqwest::header::HeaderName::from_bytes(name.as_bytes())
+                .map_err(|_| format!("Invalid header name: {}", name))?;
+            Ok((name.to_string(), value.trim().to_string()))
+        })
+        .collect()
+}
+
+fn response_file_name(request: &HttpRequest, response: &HttpResponse) -> String {
+    let extension = match response.content_type.as_deref() {
+        Some(t) if t.contains("json") => "json",
+        Some(t) if t.contains("html") => "html",
+        Some(t) if t.contains("xml") => "xml",
+        _ => "txt",
+    };
+    let stem = reqwest::Url::parse(&request.url)
+        .ok()
+        .and_then(|u| {
+            u.path_segments()
+                .and_then(|mut s| s.next_back().map(String::from))
+        })
+        .map(|s| s.split('.').next().unwrap_or("").to_string())
+        .filter(|s| {
+            !s.is_empty()
+                && s.chars()
+                    .all(|c| c.is_alphanumeric() || c == '-' || c == '_')
+        })
+        .unwrap_or_else(|| "response".to_string());
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    format!("{}-{}.{}", stem, timestamp, extension)
+}
+
+fn preview(body: &str) -> String {
+    let flat: String = body.split_whitespace().collect::<Vec<_>>().join(" ");
+    if flat.chars().count() > PREVIEW_CHARS {
+        format!("{}…", flat.chars().take(PREVIEW_CHARS).collect::<String>())
+    } else if flat.is_empty() {
+        "(empty body)".to_string()
+    } else {
+        flat
+    }
+}
+
+#[async_trait]
+impl SearchProvider for HttpProvider {
+    fn id(&self) -> &str {
+        "http"
+    }
+
+    async fn search(&self, query: &str, _ctx: &SearchContext) -> Vec<SearchResult> {
+        let Some(line) = query.trim().strip_prefix("http:") else {
+            return vec![];
+        };
+        let line = line.trim();
+
+        // Suggest presets while one is being typed
+        if let Some(partial) = line
+            .split_whitespace()
+            .last()
+            .and_then(|w| w.strip_prefix('@'))
+        {
+            let names = self.preset_names.read();
+            if !names.iter().any(|n| n == partial) {
+                return names
+                    .iter()
+                    .filter(|n| n.starts_with(partial))
+                    .map(|n| SearchResult {
+                        id: format!("http:preset:{}", n),
+                        title: format!("@{}", n),
+                        subtitle: Some("Header preset".to_string()),
+                        icon: ResultIcon::Emoji("🔑".to_string()),
+                        category: ResultCategory::Command,
+                        score: 90.0,
+                        aria_label: None,
+                    })
+                    .collect();
+            }
+        }
+
+        let Some(request) = HttpRequest::parse(line) else {
+            return vec![SearchResult {
+                id: "http:help".to_string(),
+                title: "HTTP request".to_string(),
+                subtitle: Some(
+                    "http: GET https://api.example.com/users @preset -d {body}".to_string(),
+                ),
+                icon: ResultIcon::Emoji("🌐".to_string()),
+                category: ResultCategory::Command,
+                score: 80.0,
+                aria_label: None,
+            }];
+        };
+
+        let encoded = urlencoding::encode(line);
+        let mut results = vec![SearchResult {
+            id: format!("http:send:{}", encoded),
+            title: format!("Send {}", request.summary()),
+            subtitle: Some(if request.presets.is_empty() {
+                "Run the request".to_string()
+            } else {
+                format!("With headers from @{}", request.presets.join(", @"))
+            }),
+            icon: ResultIcon::Emoji("🌐".to_string()),
+            category: ResultCategory::Command,
+            score: 95.0,
+            aria_label: None,
+        }];
+
+        if let Some((last_request, response)) = self.last_response() {
+            if last_request == request {
+                let truncated = if response.truncated {
+                    " (truncated)"
+                } else {
+                    ""
+                };
+                results.push(SearchResult {
+                    id: "http:copy-headers".to_string(),
+                    title: format!(
+                        "{} {} • {} ms",
+                        response.status, response.status_text, response.elapsed_ms
+                    ),
+                    subtitle: Some(format!("{}{}", preview(&response.body), truncated)),
+                    icon: ResultIcon::Emoji(
+                        if response.status < 400 {
+                            "✅"
+                        } else {
+                            "⚠️"
+                        }
+                        .to_string(),
+                    ),
+                    category: ResultCategory::Command,
+                    score: 99.0,
+                    aria_label: None,
+                });
+                results.push(SearchResult {
+                    id: "http:copy-body".to_string(),
+                    title: "Copy response body".to_string(),
+                    subtitle: Some(format!("{} bytes", response.body.len())),
+                    icon: ResultIcon::Emoji("📋".to_string()),
+                    category: ResultCategory::Command,
+                    score: 94.0,
+                    aria_label: None,
+                });
+                results.push(SearchResult {
+                    id: "http:save-body".to_string(),
+                    title: "Save response body".to_string(),
+                    subtitle: Some("Write to the downloads folder".to_string()),
+                    icon: ResultIcon::Emoji("💾".to_string()),
+                    category: ResultCategory::Command,
+                    score: 93.0,
+                    aria_label: None,
+                });
+            }
+        }
+
+        results
+    }
+
+    fn execute(&self, result_id: &str) -> Result<(), String> {
+        let action = result_id
+            .strip_prefix("http:")
+            .ok_or("Invalid HTTP result")?;
+
+        if let Some(line) = action.strip_prefix("send:") {
+            let line = urlencoding::decode(line).map_err(|e| e.to_string())?;
+            let request = HttpRequest::parse(&line).ok_or("Invalid HTTP request")?;
+            return self.send_in_background(request);
+        }
+        match action {
+            "copy-body" => {
+                let (_, response) = self.last_response().ok_or("No response yet")?;
+                crate::clipboard::copy_text(&response.body)
+            }
+            "copy-headers" => {
+                let (_, response) = self.last_response().ok_or("No response yet")?;
+                let mut text = format!("HTTP {} {}", response.status, response.status_text);
+                for (name, value) in &response.headers {
+                    text.push_str(&format!("\n{}: {}", name, value));
+                }
+                crate::clipboard::copy_text(&text)
+            }
+            "save-body" => self.save_last_body().map(|_| ()),
+            "help" => Ok(()),
+            _ if action.starts_with("preset:") => Ok(()),
+            _ => Err(format!("Unknown HTTP action: {}", action)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_request_lines() {
+        let request =
+            HttpRequest::parse("post api.example.com/users @github -d {\"name\": \"a b\"}")
+                .unwrap();
+        assert_eq!(request.method, "POST");
+        assert_eq!(request.url, "https://api.example.com/users");
+        assert_eq!(request.presets, vec!["github".to_string()]);
+        assert_eq!(request.body.as_deref(), Some("{\"name\": \"a b\"}"));
+
+        let request = HttpRequest::parse("https://example.com").unwrap();
+        assert_eq!(request.method, "GET");
+        assert!(request.body.is_none());
+
+        assert!(HttpRequest::parse("GET").is_none());
+        assert!(HttpRequest::parse("ftp://example.com").is_none());
+    }
+
+    #[test]
+    fn parses_header_presets() {
+        let headers =
+            parse_headers("Authorization: Bearer abc\n\nAccept: application/json").unwrap();
+        assert_eq!(headers.len(), 2);
+        assert_eq!(
+            headers[0],
+            ("Authorization".to_string(), "Bearer abc".to_string())
+        );
+        assert!(parse_headers("no colon here").is_err());
+    }
+}
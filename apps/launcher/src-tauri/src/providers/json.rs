@@ -0,0 +1,417 @@
+use super::{ResultCategory, ResultIcon, SearchContext, SearchProvider, SearchResult};
+use async_trait::async_trait;
+use parking_lot::RwLock;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Larger clipboards are refused rather than parsed on every keystroke
+const MAX_INPUT_BYTES: usize = 10 * 1024 * 1024;
+const PREVIEW_CHARS: usize = 120;
+const MAX_QUERY_RESULTS: usize = 20;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Format {
+    Json,
+    Yaml,
+}
+
+impl Format {
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Json => "JSON",
+            Self::Yaml => "YAML",
+        }
+    }
+}
+
+/// Parse clipboard text as JSON, falling back to YAML. Plain YAML scalars are
+/// rejected since almost any text is a valid YAML string.
+fn parse_document(text: &str) -> Result<(Format, Value), String> {
+    if let Ok(value) = serde_json::from_str::<Value>(text) {
+        return Ok((Format::Json, value));
+    }
+    match serde_yaml::from_str::<Value>(text) {
+        Ok(value @ (Value::Object(_) | Value::Array(_))) => Ok((Format::Yaml, value)),
+        Ok(_) => Err("Clipboard doesn't contain JSON or YAML".to_string()),
+        Err(e) => Err(format!("Invalid JSON or YAML: {}", e)),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum PathSegment {
+    Key(String),
+    Index(i64),
+    /// `[]`, every element of an array or value of an object
+    Iterate,
+}
+
+/// Parse a jq-style path: `.items[0].name`, `.users[].email`, `.["a b"]`, `.[-1]`
+fn parse_path(path: &str) -> Result<Vec<PathSegment>, String> {
+    let path = path.trim();
+    if !path.starts_with('.') {
+        return Err("Paths start with '.'".to_string());
+    }
+
+    let chars: Vec<char> = path.chars().collect();
+    let mut segments = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        match chars[i] {
+            '.' => {
+                i += 1;
+                let start = i;
+                while i < chars.len()
+                    && (chars[i].is_alphanumeric() || chars[i] == '_' || chars[i] == '-')
+                {
+                    i += 1;
+                }
+                if i > start {
+                    segments.push(PathSegment::Key(chars[start..i].iter().collect()));
+                }
+            }
+            '[' => {
+                let end = chars[i..]
+                    .iter()
+                    .position(|&c| c == ']')
+                    .map(|p| i + p)
+                    .ok_or("Unclosed '['")?;
+                let inner: String = chars[i + 1..end].iter().collect();
+                let inner = inner.trim();
+                let segment = if inner.is_empty() {
+                    PathSegment::Iterate
+                } else if let Some(key) = inner.strip_prefix('"').and_then(|s| s.strip_suffix('"'))
+                {
+                    PathSegment::Key(key.to_string())
+                } else {
+                    PathSegment::Index(
+                        inner
+                            .parse()
+                            .map_err(|_| format!("Invalid index: {}", inner))?,
+                    )
+                };
+                segments.push(segment);
+                i = end + 1;
+            }
+            c => return Err(format!("Unexpected '{}' in path", c)),
+        }
+    }
+    Ok(segments)
+}
+
+/// Every value the path selects; missing keys and indexes select nothing
+fn query_path<'a>(value: &'a Value, path: &[PathSegment]) -> Vec<&'a Value> {
+    let Some((segment, rest)) = path.split_first() else {
+        return vec![value];
+    };
+
+    let next: Vec<&Value> = match (segment, value) {
+        (PathSegment::Key(key), Value::Object(map)) => map.get(key).into_iter().collect(),
+        (PathSegment::Index(index), Value::Array(items)) => {
+            let index = if *index < 0 {
+                items.len() as i64 + index
+            } else {
+                *index
+            };
+            usize::try_from(index)
+                .ok()
+                .and_then(|i| items.get(i))
+                .into_iter()
+                .collect()
+        }
+        (PathSegment::Iterate, Value::Array(items)) => items.iter().collect(),
+        (PathSegment::Iterate, Value::Object(map)) => map.values().collect(),
+        _ => vec![],
+    };
+
+    next.into_iter().flat_map(|v| query_path(v, rest)).collect()
+}
+
+/// Strings print bare, like `jq -r`; everything else prints as JSON
+fn render_value(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        other => serde_json::to_string_pretty(other).unwrap_or_default(),
+    }
+}
+
+fn render(value: &Value, format: Format, minify: bool) -> Result<String, String> {
+    match (format, minify) {
+        (Format::Json, true) => serde_json::to_string(value).map_err(|e| e.to_string()),
+        (Format::Json, false) => serde_json::to_string_pretty(value).map_err(|e| e.to_string()),
+        (Format::Yaml, _) => serde_yaml::to_string(value).map_err(|e| e.to_string()),
+    }
+}
+
+fn preview(text: &str) -> String {
+    let flat = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if flat.chars().count() > PREVIEW_CHARS {
+        format!("{}…", flat.chars().take(PREVIEW_CHARS).collect::<String>())
+    } else {
+        flat
+    }
+}
+
+fn format_size(bytes: usize) -> String {
+    if bytes < 1024 {
+        format!("{} B", bytes)
+    } else if bytes < 1024 * 1024 {
+        format!("{:.1} KB", bytes as f64 / 1024.0)
+    } else {
+        format!("{:.1} MB", bytes as f64 / (1024.0 * 1024.0))
+    }
+}
+
+/// Format, minify, convert and query JSON/YAML on the clipboard
+/// (`json:`, `yaml:`, `json: .items[0].name`)
+///
+/// Outputs can be large, so results only carry a preview; the full text is
+/// kept here until the next search and handed out by `output`.
+pub struct JsonProvider {
+    /// Clipboard text and its parse, reused while the clipboard is unchanged
+    parsed: RwLock<Option<(String, Arc<(Format, Value)>)>>,
+    outputs: RwLock<HashMap<String, String>>,
+}
+
+impl JsonProvider {
+    pub fn new() -> Self {
+        Self {
+            parsed: RwLock::new(None),
+            outputs: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Full output behind a result, for copying or a preview pane
+    pub fn output(&self, result_id: &str) -> Option<String> {
+        self.outputs.read().get(result_id).cloned()
+    }
+
+    fn clipboard_document(&self) -> Result<Arc<(Format, Value)>, String> {
+        let text = crate::clipboard::read_text()?;
+        if text.trim().is_empty() {
+            return Err("The clipboard is empty".to_string());
+        }
+        if text.len() > MAX_INPUT_BYTES {
+            return Err(format!(
+                "Clipboard is too large ({}, limit {})",
+                format_size(text.len()),
+                format_size(MAX_INPUT_BYTES)
+            ));
+        }
+
+        if let Some((cached_text, document)) = &*self.parsed.read() {
+            if *cached_text == text {
+                return Ok(document.clone());
+            }
+        }
+        let document = Arc::new(parse_document(&text)?);
+        *self.parsed.write() = Some((text, document.clone()));
+        Ok(document)
+    }
+
+    fn output_result(
+        &self,
+        outputs: &mut HashMap<String, String>,
+        key: &str,
+        title: String,
+        output: String,
+        score: f32,
+    ) -> SearchResult {
+        let id = format!("json:copy:{}", key);
+        let subtitle = format!("{} • {}", format_size(output.len()), preview(&output));
+        outputs.insert(id.clone(), output);
+        SearchResult {
+            id,
+            title,
+            subtitle: Some(subtitle),
+            icon: ResultIcon::Emoji("🧾".to_string()),
+            category: ResultCategory::Command,
+            score,
+            aria_label: None,
+        }
+    }
+
+    fn search_blocking(&self, query: &str) -> Vec<SearchResult> {
+        let query = query.trim();
+        let (preferred, args) = if let Some(rest) = query.strip_prefix("json:") {
+            (Format::Json, rest.trim())
+        } else if let Some(rest) = query.strip_prefix("yaml:") {
+            (Format::Yaml, rest.trim())
+        } else {
+            return vec![];
+        };
+
+        let document = match self.clipboard_document() {
+            Ok(document) => document,
+            Err(e) => {
+                return vec![SearchResult {
+                    id: "json:none".to_string(),
+                    title: format!("No {} on the clipboard", preferred.name()),
+                    subtitle: Some(e),
+                    icon: ResultIcon::Emoji("🧾".to_string()),
+                    category: ResultCategory::Command,
+                    score: 80.0,
+                    aria_label: None,
+                }];
+            }
+        };
+        let (format, value) = (document.0, &document.1);
+
+        let mut outputs = HashMap::new();
+        let mut results = Vec::new();
+
+        if args.starts_with('.') {
+            let path = match parse_path(args) {
+                Ok(path) => path,
+                Err(e) => {
+                    return vec![SearchResult {
+                        id: "json:none".to_string(),
+                        title: "Invalid path".to_string(),
+                        subtitle: Some(e),
+                        icon: ResultIcon::Emoji("⚠️".to_string()),
+                        category: ResultCategory::Command,
+                        score: 80.0,
+                        aria_label: None,
+                    }];
+                }
+            };
+            let matches = query_path(value, &path);
+            if matches.is_empty() {
+                return vec![SearchResult {
+                    id: "json:none".to_string(),
+                    title: "No matches".to_string(),
+                    subtitle: Some(format!("{} selects nothing in the clipboard", args)),
+                    icon: ResultIcon::Emoji("🧾".to_string()),
+                    category: ResultCategory::Command,
+                    score: 80.0,
+                    aria_label: None,
+                }];
+            }
+
+            if matches.len() > 1 {
+                let all = matches
+                    .iter()
+                    .map(|v| render_value(v))
+                    .collect::<Vec<_>>()
+                    .join("\n");
+                results.push(self.output_result(
+                    &mut outputs,
+                    "all",
+                    format!("Copy all {} matches", matches.len()),
+                    all,
+                    96.0,
+                ));
+            }
+            for (i, matched) in matches.iter().take(MAX_QUERY_RESULTS).enumerate() {
+                let output = render_value(matched);
+                let title = preview(&output);
+                results.push(self.output_result(
+                    &mut outputs,
+                    &format!("match:{}", i),
+                    title,
+                    output,
+                    95.0 - i as f32 * 0.1,
+                ));
+            }
+        } else {
+            let other = match format {
+                Format::Json => Format::Yaml,
+                Format::Yaml => Format::Json,
+            };
+            let mut actions = vec![
+                ("format", format!("Format {}", format.name()), format, false),
+                (
+                    "convert",
+                    format!("Convert to {}", other.name()),
+                    other,
+                    false,
+                ),
+            ];
+            actions.push(("minify", "Minify as JSON".to_string(), Format::Json, true));
+            // `yaml:` on a JSON clipboard leads with the conversion
+            if format != preferred {
+                actions.swap(0, 1);
+            }
+
+            for (i, (key, title, target, minify)) in actions.into_iter().enumerate() {
+                match render(value, target, minify) {
+                    Ok(output) => results.push(self.output_result(
+                        &mut outputs,
+                        key,
+                        title,
+                        output,
+                        95.0 - i as f32,
+                    )),
+                    Err(e) => eprintln!("Failed to render {}: {}", target.name(), e),
+                }
+            }
+        }
+
+        *self.outputs.write() = outputs;
+        results
+    }
+}
+
+impl Default for JsonProvider {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl SearchProvider for JsonProvider {
+    fn id(&self) -> &str {
+        "json"
+    }
+
+    async fn search(&self, query: &str, ctx: &SearchContext) -> Vec<SearchResult> {
+        ctx.run_blocking(|| self.search_blocking(query))
+    }
+
+    fn execute(&self, result_id: &str) -> Result<(), String> {
+        if result_id == "json:none" {
+            return Ok(());
+        }
+        let output = self
+            .output(result_id)
+            .ok_or("This result has expired, search again")?;
+        crate::clipboard::copy_text(&output)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn queries_paths() {
+        let value: Value =
+            serde_json::from_str(r#"{"items": [{"name": "a"}, {"name": "b"}], "a b": 1}"#).unwrap();
+
+        let path = parse_path(".items[0].name").unwrap();
+        assert_eq!(query_path(&value, &path), vec![&Value::from("a")]);
+
+        let path = parse_path(".items[].name").unwrap();
+        assert_eq!(query_path(&value, &path).len(), 2);
+
+        let path = parse_path(".items[-1].name").unwrap();
+        assert_eq!(query_path(&value, &path), vec![&Value::from("b")]);
+
+        let path = parse_path(".[\"a b\"]").unwrap();
+        assert_eq!(query_path(&value, &path), vec![&Value::from(1)]);
+
+        assert!(query_path(&value, &parse_path(".missing.key").unwrap()).is_empty());
+        assert!(parse_path("items").is_err());
+        assert!(parse_path(".items[0").is_err());
+    }
+
+    #[test]
+    fn detects_yaml_but_not_plain_text() {
+        let (format, value) = parse_document("name: launcher\ntags:\n  - rust\n").unwrap();
+        assert_eq!(format, Format::Yaml);
+        assert_eq!(value["tags"][0], "rust");
+
+        assert_eq!(parse_document("[1, 2]").unwrap().0, Format::Json);
+        assert!(parse_document("just some text").is_err());
+    }
+}
@@ -0,0 +1,315 @@
+use super::{ResultCategory, ResultIcon, SearchContext, SearchProvider, SearchResult};
+use crate::ax;
+use crate::context::ContextTracker;
+use async_trait::async_trait;
+use fuzzy_matcher::skim::SkimMatcherV2;
+use fuzzy_matcher::FuzzyMatcher;
+use parking_lot::Mutex;
+use serde::Deserialize;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Walking a menu bar through System Events takes a second or more for
+/// big apps; reuse it while typing
+const CACHE_TTL: Duration = Duration::from_secs(30);
+
+const MAX_RESULTS: usize = 30;
+
+/// Separates the app and menu titles in result ids; titles can contain
+/// anything printable, including colons
+const ID_SEPARATOR: char = '\u{1f}';
+
+const PERMISSION_ID: &str = "menu:permission";
+
+/// A command in the frontmost app's menu bar
+#[derive(Debug, Clone, Deserialize)]
+pub struct MenuItem {
+    /// Menu titles from the menu bar down, e.g. ["File", "Export", "PDF…"]
+    pub path: Vec<String>,
+    pub enabled: bool,
+    /// Key equivalent, from `AXMenuItemCmdChar`
+    #[serde(default)]
+    pub key: Option<String>,
+    /// `AXMenuItemCmdModifiers`: 1 shift, 2 option, 4 control, 8 no command
+    #[serde(default)]
+    pub modifiers: u32,
+}
+
+impl MenuItem {
+    fn shortcut(&self) -> Option<String> {
+        let key = self.key.as_deref().filter(|k| !k.is_empty())?;
+        Some(format_shortcut(key, self.modifiers))
+    }
+}
+
+/// A key equivalent as macOS menus show it, e.g. "⌥⇧⌘S"
+fn format_shortcut(key: &str, modifiers: u32) -> String {
+    let mut shortcut = String::new();
+    if modifiers & 4 != 0 {
+        shortcut.push('⌃');
+    }
+    if modifiers & 2 != 0 {
+        shortcut.push('⌥');
+    }
+    if modifiers & 1 != 0 {
+        shortcut.push('⇧');
+    }
+    if modifiers & 8 == 0 {
+        shortcut.push('⌘');
+    }
+    shortcut.push_str(&key.to_uppercase());
+    shortcut
+}
+
+fn result_id(app: &str, path: &[String]) -> String {
+    let mut parts = vec![app.to_string()];
+    parts.extend(path.iter().cloned());
+    format!("menu:{}", parts.join(&ID_SEPARATOR.to_string()))
+}
+
+/// App name and menu path from a result id
+fn parse_id(result_id: &str) -> Option<(String, Vec<String>)> {
+    let mut parts = result_id
+        .strip_prefix("menu:")?
+        .split(ID_SEPARATOR)
+        .map(String::from);
+    let app = parts.next()?;
+    let path: Vec<String> = parts.collect();
+    (!path.is_empty()).then_some((app, path))
+}
+
+/// Every menu item of a process as JSON, skipping the Apple menu, which is
+/// the same in every app
+#[cfg(target_os = "macos")]
+const LIST_SCRIPT: &str = r#"
+function run(argv) {
+  const proc = Application('System Events').processes.byName(argv[0]);
+  const items = [];
+  function walk(menu, path) {
+    menu.menuItems().forEach(item => {
+      const name = item.name();
+      if (!name) return;
+      const submenus = item.menus();
+      if (submenus.length) return walk(submenus[0], path.concat(name));
+      let key = null, modifiers = 0;
+      try {
+        key = item.attributes.byName('AXMenuItemCmdChar').value();
+        modifiers = item.attributes.byName('AXMenuItemCmdModifiers').value();
+      } catch (e) {}
+      items.push({ path: path.concat(name), enabled: item.enabled(), key, modifiers });
+    });
+  }
+  proc.menuBars[0].menuBarItems().slice(1).forEach(bar => {
+    const menus = bar.menus();
+    if (menus.length) walk(menus[0], [bar.name()]);
+  });
+  return JSON.stringify(items);
+}
+"#;
+
+/// Bring a process forward and click the menu item at argv[1..]
+#[cfg(target_os = "macos")]
+const CLICK_SCRIPT: &str = r#"
+function run(argv) {
+  const proc = Application('System Events').processes.byName(argv[0]);
+  proc.frontmost = true;
+  let item = proc.menuBars[0].menuBarItems.byName(argv[1]);
+  argv.slice(2).forEach(name => { item = item.menus[0].menuItems.byName(name); });
+  item.click();
+}
+"#;
+
+#[cfg(target_os = "macos")]
+fn list_items(app: &str) -> Result<Vec<MenuItem>, String> {
+    let output = std::process::Command::new("osascript")
+        .args(["-l", "JavaScript", "-e", LIST_SCRIPT, app])
+        .output()
+        .map_err(|e| format!("Failed to run osascript: {}", e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "Couldn't read the menus of {}: {}",
+            app,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    serde_json::from_slice(&output.stdout)
+        .map_err(|e| format!("Couldn't read the menus of {}: {}", app, e))
+}
+
+#[cfg(not(target_os = "macos"))]
+fn list_items(_app: &str) -> Result<Vec<MenuItem>, String> {
+    Err("Menu search is only available on macOS".to_string())
+}
+
+#[cfg(target_os = "macos")]
+fn click(app: &str, path: &[String]) -> Result<(), String> {
+    let output = std::process::Command::new("osascript")
+        .args(["-l", "JavaScript", "-e", CLICK_SCRIPT, app])
+        .args(path)
+        .output()
+        .map_err(|e| format!("Failed to run osascript: {}", e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "Couldn't choose {}: {}",
+            path.join(" › "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(not(target_os = "macos"))]
+fn click(_app: &str, _path: &[String]) -> Result<(), String> {
+    Err("Menu search is only available on macOS".to_string())
+}
+
+/// `menu <words>` fuzzy-searches the menu bar of the app the launcher was
+/// summoned over and chooses the picked command (macOS). Needs the
+/// Accessibility permission; until it's granted the only result asks for it.
+pub struct MenuSearchProvider {
+    context_tracker: Arc<ContextTracker>,
+    matcher: SkimMatcherV2,
+    /// App name, when its menus were read, and its items
+    cache: Mutex<Option<(String, Instant, Vec<MenuItem>)>>,
+}
+
+impl MenuSearchProvider {
+    pub fn new(context_tracker: Arc<ContextTracker>) -> Self {
+        Self {
+            context_tracker,
+            matcher: SkimMatcherV2::default(),
+            cache: Mutex::new(None),
+        }
+    }
+
+    fn items(&self, app: &str) -> Vec<MenuItem> {
+        if let Some((cached_app, read_at, items)) = &*self.cache.lock() {
+            if cached_app == app && read_at.elapsed() < CACHE_TTL {
+                return items.clone();
+            }
+        }
+        match list_items(app) {
+            Ok(items) => {
+                *self.cache.lock() = Some((app.to_string(), Instant::now(), items.clone()));
+                items
+            }
+            Err(e) => {
+                eprintln!("{}", e);
+                Vec::new()
+            }
+        }
+    }
+
+    fn permission_result() -> SearchResult {
+        SearchResult {
+            id: PERMISSION_ID.to_string(),
+            title: "Allow Accessibility access to search menus".to_string(),
+            subtitle: Some(
+                "Opens System Settings › Privacy & Security › Accessibility".to_string(),
+            ),
+            icon: ResultIcon::Emoji("🔐".to_string()),
+            category: ResultCategory::System,
+            score: 90.0,
+            aria_label: None,
+        }
+    }
+
+    fn item_result(app: &str, item: &MenuItem, score: f32) -> SearchResult {
+        let parents = item.path[..item.path.len() - 1].join(" › ");
+        let subtitle = match item.shortcut() {
+            Some(shortcut) => format!("{} › {} • {}", app, parents, shortcut),
+            None => format!("{} › {}", app, parents),
+        };
+        SearchResult {
+            id: result_id(app, &item.path),
+            title: item.path.last().cloned().unwrap_or_default(),
+            subtitle: Some(subtitle),
+            icon: ResultIcon::Emoji("☰".to_string()),
+            category: ResultCategory::Command,
+            score,
+            aria_label: None,
+        }
+    }
+
+    fn results(&self, app: &str, filter: &str) -> Vec<SearchResult> {
+        let items: Vec<MenuItem> = self
+            .items(app)
+            .into_iter()
+            .filter(|item| item.enabled && !item.path.is_empty())
+            .collect();
+
+        let mut matches: Vec<(i64, &MenuItem)> = if filter.is_empty() {
+            items.iter().map(|item| (0, item)).collect()
+        } else {
+            items
+                .iter()
+                .filter_map(|item| {
+                    let score = self.matcher.fuzzy_match(&item.path.join(" "), filter)?;
+                    Some((score, item))
+                })
+                .collect()
+        };
+        // Stable, so equal scores keep menu order
+        matches.sort_by(|a, b| b.0.cmp(&a.0));
+        matches
+            .into_iter()
+            .take(MAX_RESULTS)
+            .enumerate()
+            .map(|(i, (_, item))| Self::item_result(app, item, 90.0 - i as f32 * 0.1))
+            .collect()
+    }
+}
+
+#[async_trait]
+impl SearchProvider for MenuSearchProvider {
+    fn id(&self) -> &str {
+        "menu"
+    }
+
+    async fn search(&self, query: &str, ctx: &SearchContext) -> Vec<SearchResult> {
+        if !cfg!(target_os = "macos") {
+            return vec![];
+        }
+        let query = query.trim();
+        let filter = match query.to_lowercase().strip_prefix("menu") {
+            Some(rest) if rest.is_empty() || rest.starts_with(' ') => rest.trim().to_string(),
+            _ => return vec![],
+        };
+        if !ax::is_trusted() {
+            return vec![Self::permission_result()];
+        }
+        let Some(app) = self.context_tracker.active().app else {
+            return vec![];
+        };
+
+        ctx.run_blocking(|| self.results(&app.name, &filter))
+    }
+
+    fn execute(&self, result_id: &str) -> Result<(), String> {
+        if result_id == PERMISSION_ID {
+            return ax::open_settings();
+        }
+        let (app, path) = parse_id(result_id).ok_or("Invalid menu result")?;
+        click(&app, &path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_key_equivalents() {
+        assert_eq!(format_shortcut("s", 0), "⌘S");
+        assert_eq!(format_shortcut("S", 3), "⌥⇧⌘S");
+        assert_eq!(format_shortcut("f", 4 | 8), "⌃F");
+    }
+
+    #[test]
+    fn round_trips_ids() {
+        let path = vec!["File".to_string(), "Export as: PDF…".to_string()];
+        let id = result_id("Preview", &path);
+        assert_eq!(parse_id(&id), Some(("Preview".to_string(), path)));
+        assert_eq!(parse_id(PERMISSION_ID), None);
+    }
+}
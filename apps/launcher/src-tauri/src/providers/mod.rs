@@ -1,17 +1,61 @@
 pub mod apps;
+pub mod battery;
+pub mod bluetooth;
+pub mod browse;
 pub mod calculator;
+pub mod capture;
+pub mod cleanup;
+pub mod clipboard_history;
+pub mod content;
+pub mod dictionary;
+pub mod disk_usage;
+pub mod docs;
+pub mod fake_data;
+pub mod fallbacks;
+pub mod feeds;
 pub mod files;
+pub mod focus;
 pub mod github;
 pub mod google_calendar;
 pub mod google_drive;
+pub mod home_assistant;
+pub mod http;
+pub mod json;
+pub mod menu;
 pub mod notion;
+pub mod ocr;
+pub mod packages;
 pub mod plugins;
+pub mod processes;
+pub mod qr;
+pub mod quicklinks;
+pub mod regex_tester;
+pub mod registries;
+pub mod reminders;
+pub mod remote;
+pub mod rename;
+pub mod settings_panes;
 pub mod slack;
+pub mod stackoverflow;
 pub mod system;
+pub mod text_tools;
+pub mod time_tracking;
+pub mod totp;
+pub mod transport;
 pub mod url;
+pub mod vpn;
+pub mod weather;
 pub mod websearch;
+pub mod window_manager;
+pub mod windows;
+pub mod workspaces;
 
+use crate::search_cache::CachePolicy;
+use crate::undo::UndoableAction;
+use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use tokio_util::sync::CancellationToken;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SearchResult {
@@ -21,6 +65,22 @@ pub struct SearchResult {
     pub icon: ResultIcon,
     pub category: ResultCategory,
     pub score: f32,
+    /// What screen readers announce; filled in from the title, subtitle
+    /// and category when a provider leaves it out
+    #[serde(default)]
+    pub aria_label: Option<String>,
+}
+
+impl SearchResult {
+    pub fn fill_aria_label(&mut self) {
+        if self.aria_label.is_some() {
+            return;
+        }
+        let mut parts = vec![self.title.as_str()];
+        parts.extend(self.subtitle.as_deref().filter(|s| !s.is_empty()));
+        parts.push(self.category.spoken_name());
+        self.aria_label = Some(parts.join(", "));
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -29,6 +89,12 @@ pub enum ResultIcon {
     Text(String),
     Path(String),
     Emoji(String),
+    /// An image file, drawn small through the `thumb://` protocol with
+    /// `placeholder` shown until it's ready
+    Thumbnail {
+        path: String,
+        placeholder: String,
+    },
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -44,8 +110,218 @@ pub enum ResultCategory {
     System,
 }
 
+impl ResultCategory {
+    /// Read out after a result's title, e.g. "Safari, Application"
+    pub fn spoken_name(&self) -> &'static str {
+        match self {
+            Self::Calculator => "Calculation",
+            Self::Application => "Application",
+            Self::File => "File",
+            Self::Command => "Command",
+            Self::Plugin => "Plugin result",
+            Self::GitHub => "GitHub",
+            Self::URL => "Link",
+            Self::WebSearch => "Web search",
+            Self::System => "System",
+        }
+    }
+}
+
+/// Id of the action every result has: running `SearchProvider::execute`
+pub const DEFAULT_ACTION: &str = "open";
+
+/// Id of the action that hands a result to a Codex session. The window runs
+/// it with `codex_ask_about_result`, which needs the whole result.
+pub const ASK_AI_ACTION: &str = "ask-ai";
+
+/// A secondary action offered on a result, e.g. "Reveal in folder"
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResultAction {
+    pub id: String,
+    pub title: String,
+    /// Display hint for the action panel, e.g. "⌘C"
+    pub shortcut: Option<String>,
+}
+
+impl ResultAction {
+    pub fn new(id: &str, title: &str) -> Self {
+        Self {
+            id: id.to_string(),
+            title: title.to_string(),
+            shortcut: None,
+        }
+    }
+
+    pub fn with_shortcut(mut self, shortcut: &str) -> Self {
+        self.shortcut = Some(shortcut.to_string());
+        self
+    }
+}
+
+/// Actions for results that point at something on disk
+pub fn path_actions() -> Vec<ResultAction> {
+    vec![
+        ResultAction::new("reveal", "Reveal in folder").with_shortcut("⌘⏎"),
+        ResultAction::new("copy-path", "Copy path").with_shortcut("⌘C"),
+    ]
+}
+
+pub fn execute_path_action(path: &str, action_id: &str) -> Result<(), String> {
+    match action_id {
+        "reveal" => crate::reveal_in_folder(path),
+        "copy-path" => crate::clipboard::copy_text(path),
+        _ => Err(format!("Unknown action: {}", action_id)),
+    }
+}
+
+/// An inline edit to a result, from `mutate_result`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ResultMutation {
+    /// A new name, like a file's or a quicklink's
+    Rename { name: String },
+    /// A new address, like a quicklink's URL
+    SetUrl { url: String },
+}
+
+impl ResultMutation {
+    /// The edit's `type`, as `SearchProvider::mutations` lists it
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::Rename { .. } => "rename",
+            Self::SetUrl { .. } => "set_url",
+        }
+    }
+}
+
+/// A result after an edit, and how to take the edit back
+pub struct Mutated {
+    pub result: SearchResult,
+    pub undo: UndoableAction,
+}
+
+/// What an agent is told about a result handed to it from search
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResultContext {
+    pub title: String,
+    pub subtitle: Option<String>,
+    /// Spoken category name, e.g. "File"
+    pub kind: String,
+    /// The file or folder on disk the result stands for
+    pub path: Option<String>,
+    /// Further facts from the provider, as label and value
+    pub details: Vec<(String, String)>,
+}
+
+impl ResultContext {
+    pub fn from_result(result: &SearchResult) -> Self {
+        Self {
+            title: result.title.clone(),
+            subtitle: result.subtitle.clone().filter(|s| !s.is_empty()),
+            kind: result.category.spoken_name().to_string(),
+            path: None,
+            details: Vec::new(),
+        }
+    }
+
+    pub fn with_path(mut self, path: &str) -> Self {
+        self.path = Some(path.to_string());
+        self
+    }
+
+    pub fn with_detail(mut self, label: &str, value: impl Into<String>) -> Self {
+        self.details.push((label.to_string(), value.into()));
+        self
+    }
+
+    /// Where a session about the result should run: the folder itself, or
+    /// the folder a file is in
+    pub fn folder(&self) -> Option<PathBuf> {
+        let path = Path::new(self.path.as_ref()?);
+        if path.is_dir() {
+            Some(path.to_path_buf())
+        } else {
+            path.parent().filter(|p| p.is_dir()).map(Path::to_path_buf)
+        }
+    }
+
+    /// The result as a few lines of prompt
+    pub fn describe(&self) -> String {
+        let mut lines = vec![format!("{}: {}", self.kind, self.title)];
+        lines.extend(self.subtitle.as_ref().map(|s| format!("  {}", s)));
+        lines.extend(self.path.as_ref().map(|p| format!("  Path: {}", p)));
+        lines.extend(
+            self.details
+                .iter()
+                .map(|(label, value)| format!("  {}: {}", label, value)),
+        );
+        lines.join("\n")
+    }
+}
+
+/// Per-query state handed to every provider. A newer keystroke cancels the
+/// token, so providers should bail out early once `is_cancelled` is true.
+#[derive(Debug, Clone, Default)]
+pub struct SearchContext {
+    pub cancel: CancellationToken,
+}
+
+impl SearchContext {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancel.is_cancelled()
+    }
+
+    /// Run blocking work (disk, blocking HTTP) without stalling the async
+    /// runtime. Skipped entirely when the query was already superseded.
+    pub fn run_blocking<T: Default>(&self, f: impl FnOnce() -> T) -> T {
+        if self.is_cancelled() {
+            return T::default();
+        }
+        tokio::task::block_in_place(f)
+    }
+}
+
+#[async_trait]
 pub trait SearchProvider: Send + Sync {
     fn id(&self) -> &str;
-    fn search(&self, query: &str) -> Vec<SearchResult>;
+    async fn search(&self, query: &str, ctx: &SearchContext) -> Vec<SearchResult>;
     fn execute(&self, result_id: &str) -> Result<(), String>;
+
+    /// Secondary actions for one of this provider's results, besides the
+    /// default `execute`
+    fn actions(&self, _result_id: &str) -> Vec<ResultAction> {
+        Vec::new()
+    }
+
+    fn execute_action(&self, _result_id: &str, action_id: &str) -> Result<(), String> {
+        Err(format!("Unknown action: {}", action_id))
+    }
+
+    /// Whether results for `query` may be reused while the user types,
+    /// see `search_cache`. A provider that prompts to sign in should only
+    /// opt in once signed in, or the prompt outlives signing in.
+    fn cache_policy(&self, _query: &str) -> CachePolicy {
+        CachePolicy::Never
+    }
+
+    /// Inline edits this provider can make to one of its results, by
+    /// `ResultMutation` type, e.g. `rename`
+    fn mutations(&self, _result_id: &str) -> Vec<&'static str> {
+        Vec::new()
+    }
+
+    /// Apply an inline edit, one of those `mutations` lists
+    fn mutate(&self, _result_id: &str, _mutation: &ResultMutation) -> Result<Mutated, String> {
+        Err("This result can't be edited".to_string())
+    }
+
+    /// What to tell an agent about one of this provider's results, for
+    /// "Ask AI about this"
+    fn context(&self, result: &SearchResult) -> ResultContext {
+        ResultContext::from_result(result)
+    }
 }
@@ -1,35 +1,14 @@
-use super::{ResultCategory, ResultIcon, SearchProvider, SearchResult};
+use super::{ResultCategory, ResultIcon, SearchContext, SearchProvider, SearchResult};
 use crate::oauth::OAuthFlow;
-use parking_lot::RwLock;
+use crate::search_cache::{CachePolicy, ResultUrls};
+use async_trait::async_trait;
 use serde::Deserialize;
 use std::collections::HashMap;
 use std::sync::Arc;
 
 pub struct NotionProvider {
     oauth_flow: Arc<OAuthFlow>,
-    cache: RwLock<SearchCache>,
-}
-
-struct SearchCache {
-    query: String,
-    results: Vec<SearchResult>,
-    urls: HashMap<String, String>,
-    timestamp: std::time::Instant,
-}
-
-impl SearchCache {
-    fn new() -> Self {
-        Self {
-            query: String::new(),
-            results: Vec::new(),
-            urls: HashMap::new(),
-            timestamp: std::time::Instant::now(),
-        }
-    }
-
-    fn is_valid(&self, query: &str) -> bool {
-        self.query == query && self.timestamp.elapsed().as_secs() < 60
-    }
+    urls: ResultUrls,
 }
 
 #[derive(Debug, Deserialize)]
@@ -90,7 +69,7 @@ impl NotionProvider {
     pub fn new(oauth_flow: Arc<OAuthFlow>) -> Self {
         Self {
             oauth_flow,
-            cache: RwLock::new(SearchCache::new()),
+            urls: ResultUrls::new(),
         }
     }
 
@@ -125,14 +104,6 @@ impl NotionProvider {
             return Vec::new();
         }
 
-        // Check cache first
-        {
-            let cache = self.cache.read();
-            if cache.is_valid(query) {
-                return cache.results.clone();
-            }
-        }
-
         // Check if connected to Notion
         if !self.oauth_flow.is_connected("notion") {
             return vec![SearchResult {
@@ -142,6 +113,7 @@ impl NotionProvider {
                 icon: ResultIcon::Emoji("🔗".to_string()),
                 category: ResultCategory::Plugin, // Using Plugin as generic category
                 score: 50.0,
+                aria_label: None,
             }];
         }
 
@@ -190,6 +162,7 @@ impl NotionProvider {
                                 icon,
                                 category: ResultCategory::Plugin,
                                 score: 100.0 - (i as f32 * 5.0),
+                                aria_label: None,
                             }
                         })
                         .collect();
@@ -210,30 +183,36 @@ impl NotionProvider {
             }
         };
 
-        // Update cache
-        {
-            let mut cache = self.cache.write();
-            cache.query = query.to_string();
-            cache.results = results.clone();
-            cache.urls = urls;
-            cache.timestamp = std::time::Instant::now();
-        }
+        self.urls.remember(urls);
 
         results
     }
+
+    fn search_blocking(&self, query: &str) -> Vec<SearchResult> {
+        // Only search Notion if query starts with "nt " prefix
+        if let Some(notion_query) = query.strip_prefix("nt ") {
+            self.search_notion(notion_query.trim())
+        } else {
+            Vec::new()
+        }
+    }
 }
 
+#[async_trait]
 impl SearchProvider for NotionProvider {
     fn id(&self) -> &str {
         "notion"
     }
 
-    fn search(&self, query: &str) -> Vec<SearchResult> {
-        // Only search Notion if query starts with "nt " prefix
-        if let Some(notion_query) = query.strip_prefix("nt ") {
-            self.search_notion(notion_query.trim())
+    async fn search(&self, query: &str, ctx: &SearchContext) -> Vec<SearchResult> {
+        ctx.run_blocking(|| self.search_blocking(query))
+    }
+
+    fn cache_policy(&self, query: &str) -> CachePolicy {
+        if query.starts_with("nt ") && self.oauth_flow.is_connected("notion") {
+            CachePolicy::Remote
         } else {
-            Vec::new()
+            CachePolicy::Never
         }
     }
 
@@ -243,8 +222,7 @@ impl SearchProvider for NotionProvider {
         }
 
         if result_id.starts_with("notion:page:") {
-            let cache = self.cache.read();
-            if let Some(url) = cache.urls.get(result_id) {
+            if let Some(url) = self.urls.get(result_id).as_deref() {
                 #[cfg(target_os = "linux")]
                 {
                     std::process::Command::new("xdg-open")
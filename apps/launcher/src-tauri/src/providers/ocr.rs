@@ -0,0 +1,118 @@
+use super::files::FileProvider;
+use super::{ResultCategory, ResultIcon, SearchContext, SearchProvider, SearchResult};
+use crate::ocr::{self, OcrStatus};
+use async_trait::async_trait;
+use std::path::Path;
+use std::sync::Arc;
+
+const KEYWORDS: &[&str] = &[
+    "ocr",
+    "extract text",
+    "copy text from image",
+    "text from image",
+];
+
+/// Extracts text from images with the optional OCR engine
+pub struct OcrProvider {
+    file_provider: Arc<FileProvider>,
+}
+
+impl OcrProvider {
+    pub fn new(file_provider: Arc<FileProvider>) -> Self {
+        Self { file_provider }
+    }
+
+    pub fn status(&self) -> OcrStatus {
+        ocr::status()
+    }
+
+    pub fn install_models(&self) -> Result<OcrStatus, String> {
+        ocr::install_models()
+    }
+
+    /// Recognize the text in an image file and copy it. With `index`, the text
+    /// is also saved so the file shows up in content search.
+    pub fn extract_file(&self, path: &Path, index: bool) -> Result<String, String> {
+        if !ocr::is_image(path) {
+            return Err("Only PNG and JPEG images are supported".to_string());
+        }
+        let text = ocr::recognize_file(path)?;
+        if text.is_empty() {
+            return Err("No text found in the image".to_string());
+        }
+
+        if index {
+            ocr::save_text(path, &text)?;
+            self.file_provider.update_file(path)?;
+        }
+        crate::clipboard::copy_text(&text)?;
+        Ok(text)
+    }
+
+    /// Recognize the text in the image on the clipboard and copy it back as text
+    pub fn extract_clipboard(&self) -> Result<String, String> {
+        let image = crate::clipboard::read_image()?;
+        let text = ocr::recognize_image(&image)?;
+        if text.is_empty() {
+            return Err("No text found in the image".to_string());
+        }
+        crate::clipboard::copy_text(&text)?;
+        Ok(text)
+    }
+}
+
+#[async_trait]
+impl SearchProvider for OcrProvider {
+    fn id(&self) -> &str {
+        "ocr"
+    }
+
+    async fn search(&self, query: &str, _ctx: &SearchContext) -> Vec<SearchResult> {
+        let query = query.trim().to_lowercase();
+        if query.len() < 3 || !KEYWORDS.iter().any(|k| k.starts_with(&query)) {
+            return vec![];
+        }
+
+        let status = self.status();
+        if !status.supported {
+            return vec![];
+        }
+        if !status.models_installed {
+            return vec![SearchResult {
+                id: "ocr:install".to_string(),
+                title: "Download OCR Models".to_string(),
+                subtitle: Some("About 12 MB, needed to extract text from images".to_string()),
+                icon: ResultIcon::Emoji("⬇️".to_string()),
+                category: ResultCategory::Command,
+                score: 70.0,
+                aria_label: None,
+            }];
+        }
+
+        vec![SearchResult {
+            id: "ocr:clipboard".to_string(),
+            title: "Extract Text from Clipboard Image".to_string(),
+            subtitle: Some("Copy the text in the copied image".to_string()),
+            icon: ResultIcon::Emoji("🔤".to_string()),
+            category: ResultCategory::Command,
+            score: 80.0,
+            aria_label: None,
+        }]
+    }
+
+    fn execute(&self, result_id: &str) -> Result<(), String> {
+        match result_id {
+            "ocr:clipboard" => self.extract_clipboard().map(|_| ()),
+            "ocr:install" => {
+                // Large download; don't hold the window open for it
+                std::thread::spawn(|| {
+                    if let Err(e) = ocr::install_models() {
+                        eprintln!("Failed to install OCR models: {}", e);
+                    }
+                });
+                Ok(())
+            }
+            _ => Err(format!("Unknown OCR action: {}", result_id)),
+        }
+    }
+}
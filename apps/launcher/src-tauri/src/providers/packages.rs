@@ -0,0 +1,529 @@
+use super::{ResultCategory, ResultIcon, SearchContext, SearchProvider, SearchResult};
+use crate::vault::SecretVault;
+use async_trait::async_trait;
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+const API_BASE: &str = "https://api.17track.net/track/v2.2";
+const VAULT_KEY: &str = "17track_api_key";
+
+/// How often the background poller refreshes undelivered packages
+pub const POLL_INTERVAL: Duration = Duration::from_secs(30 * 60);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Carrier {
+    Ups,
+    Fedex,
+    Usps,
+    Dhl,
+    RoyalMail,
+    Unknown,
+}
+
+impl Carrier {
+    /// Guess the carrier from the shape of a tracking number
+    pub fn detect(number: &str) -> Self {
+        let n = number.to_uppercase();
+        let digits = n.chars().all(|c| c.is_ascii_digit());
+        let alnum = n.chars().all(|c| c.is_ascii_alphanumeric());
+
+        if n.starts_with("1Z") && n.len() == 18 && alnum {
+            Self::Ups
+        } else if alnum
+            && n.len() == 13
+            && n.ends_with("GB")
+            && n[..2].chars().all(|c| c.is_ascii_alphabetic())
+            && n[2..11].chars().all(|c| c.is_ascii_digit())
+        {
+            Self::RoyalMail
+        } else if (n.starts_with("JJD") || n.starts_with("JVGL")) && alnum {
+            Self::Dhl
+        } else if digits && (20..=22).contains(&n.len()) {
+            Self::Usps
+        } else if digits && matches!(n.len(), 12 | 15) {
+            Self::Fedex
+        } else if digits && n.len() == 10 {
+            Self::Dhl
+        } else {
+            Self::Unknown
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Ups => "UPS",
+            Self::Fedex => "FedEx",
+            Self::Usps => "USPS",
+            Self::Dhl => "DHL",
+            Self::RoyalMail => "Royal Mail",
+            Self::Unknown => "Carrier",
+        }
+    }
+
+    /// The carrier's own tracking page
+    pub fn tracking_url(&self, number: &str) -> String {
+        let number = urlencoding::encode(number);
+        match self {
+            Self::Ups => format!("https://www.ups.com/track?tracknum={}", number),
+            Self::Fedex => format!("https://www.fedex.com/fedextrack/?trknbr={}", number),
+            Self::Usps => format!(
+                "https://tools.usps.com/go/TrackConfirmAction?tLabels={}",
+                number
+            ),
+            Self::Dhl => format!(
+                "https://www.dhl.com/global-en/home/tracking.html?tracking-id={}",
+                number
+            ),
+            Self::RoyalMail => format!(
+                "https://www.royalmail.com/track-your-item#/tracking-results/{}",
+                number
+            ),
+            Self::Unknown => format!("https://t.17track.net/en#nums={}", number),
+        }
+    }
+}
+
+/// Whether a string looks like a tracking number we can register
+pub fn looks_like_tracking_number(text: &str) -> bool {
+    let text = text.trim();
+    text.len() >= 8
+        && text.len() <= 34
+        && text.chars().all(|c| c.is_ascii_alphanumeric())
+        && text.chars().any(|c| c.is_ascii_digit())
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrackedPackage {
+    pub id: String,
+    pub tracking_number: String,
+    pub carrier: Carrier,
+    #[serde(default)]
+    pub label: Option<String>,
+    /// e.g. "InTransit" or "Delivered", as reported by the tracking service
+    #[serde(default)]
+    pub status: Option<String>,
+    /// Latest checkpoint, e.g. "Arrived at facility, Leipzig"
+    #[serde(default)]
+    pub last_event: Option<String>,
+    #[serde(default)]
+    pub last_event_time: Option<String>,
+    #[serde(default)]
+    pub delivered: bool,
+    #[serde(default)]
+    pub last_checked: Option<String>,
+    pub added_at: String,
+}
+
+impl TrackedPackage {
+    fn title(&self) -> String {
+        self.label
+            .clone()
+            .unwrap_or_else(|| format!("{} {}", self.carrier.name(), self.tracking_number))
+    }
+}
+
+/// A package whose status changed during a refresh
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PackageUpdate {
+    pub package: TrackedPackage,
+    pub previous_status: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PackageTrackingStatus {
+    pub connected: bool,
+    pub service: String,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct PackageStore {
+    packages: Vec<TrackedPackage>,
+}
+
+#[derive(Deserialize)]
+struct TrackResponse {
+    code: i64,
+    #[serde(default)]
+    data: Option<TrackData>,
+}
+
+#[derive(Deserialize)]
+struct TrackData {
+    #[serde(default)]
+    accepted: Vec<TrackAccepted>,
+}
+
+#[derive(Deserialize)]
+struct TrackAccepted {
+    number: String,
+    #[serde(default)]
+    track_info: Option<TrackInfo>,
+}
+
+#[derive(Deserialize)]
+struct TrackInfo {
+    latest_status: Option<TrackLatestStatus>,
+    latest_event: Option<TrackEvent>,
+}
+
+#[derive(Deserialize)]
+struct TrackLatestStatus {
+    status: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct TrackEvent {
+    time_iso: Option<String>,
+    description: Option<String>,
+    location: Option<String>,
+}
+
+/// Registered tracking numbers, triggered with `track:`. Statuses come from
+/// 17TRACK, which covers most carriers with a single API key.
+pub struct PackageProvider {
+    vault: Arc<SecretVault>,
+    store: RwLock<PackageStore>,
+    path: PathBuf,
+}
+
+impl PackageProvider {
+    pub fn new(vault: Arc<SecretVault>) -> Self {
+        let path = dirs::data_dir()
+            .unwrap_or_else(|| PathBuf::from("."))
+            .join("launcher")
+            .join("packages.json");
+        let store = crate::journal::load_json_store(&path).unwrap_or_default();
+
+        Self {
+            vault,
+            store: RwLock::new(store),
+            path,
+        }
+    }
+
+    fn save(&self, store: &PackageStore) -> Result<(), String> {
+        let json = serde_json::to_string_pretty(store).map_err(|e| e.to_string())?;
+        crate::journal::write_json_store(&self.path, &json)
+    }
+
+    pub fn status(&self) -> PackageTrackingStatus {
+        PackageTrackingStatus {
+            connected: self.vault.get(VAULT_KEY).is_some(),
+            service: "17track".to_string(),
+        }
+    }
+
+    pub fn connect(&self, api_key: &str) -> Result<PackageTrackingStatus, String> {
+        self.vault.set(VAULT_KEY, api_key.trim())?;
+        Ok(self.status())
+    }
+
+    pub fn disconnect(&self) -> Result<(), String> {
+        self.vault.delete(VAULT_KEY)
+    }
+
+    /// Packages not yet delivered first, most recently added first
+    pub fn packages(&self) -> Vec<TrackedPackage> {
+        let mut packages = self.store.read().packages.clone();
+        packages.sort_by(|a, b| {
+            a.delivered
+                .cmp(&b.delivered)
+                .then_with(|| b.added_at.cmp(&a.added_at))
+        });
+        packages
+    }
+
+    pub fn add(
+        &self,
+        tracking_number: &str,
+        label: Option<&str>,
+    ) -> Result<TrackedPackage, String> {
+        let number = tracking_number.trim().to_uppercase();
+        if !looks_like_tracking_number(&number) {
+            return Err("That doesn't look like a tracking number".to_string());
+        }
+        if self
+            .store
+            .read()
+            .packages
+            .iter()
+            .any(|p| p.tracking_number == number)
+        {
+            return Err("Already tracking this package".to_string());
+        }
+
+        let package = TrackedPackage {
+            id: uuid::Uuid::new_v4().to_string(),
+            carrier: Carrier::detect(&number),
+            tracking_number: number,
+            label: label
+                .map(str::trim)
+                .filter(|l| !l.is_empty())
+                .map(str::to_string),
+            status: None,
+            last_event: None,
+            last_event_time: None,
+            delivered: false,
+            last_checked: None,
+            added_at: chrono::Utc::now().to_rfc3339(),
+        };
+
+        {
+            let mut store = self.store.write();
+            store.packages.push(package.clone());
+            self.save(&store)?;
+        }
+
+        if let Some(api_key) = self.vault.get(VAULT_KEY) {
+            if let Err(e) = self.call(&api_key, "register", &[&package.tracking_number]) {
+                eprintln!("Failed to register {}: {}", package.tracking_number, e);
+            }
+        }
+        Ok(package)
+    }
+
+    pub fn remove(&self, id: &str) -> Result<(), String> {
+        let mut store = self.store.write();
+        store.packages.retain(|p| p.id != id);
+        self.save(&store)
+    }
+
+    fn call(
+        &self,
+        api_key: &str,
+        endpoint: &str,
+        numbers: &[&str],
+    ) -> Result<TrackResponse, String> {
+        let body: Vec<_> = numbers
+            .iter()
+            .map(|n| serde_json::json!({ "number": n }))
+            .collect();
+        let response: TrackResponse = reqwest::blocking::Client::new()
+            .post(format!("{}/{}", API_BASE, endpoint))
+            .header("17token", api_key)
+            .json(&body)
+            .timeout(Duration::from_secs(15))
+            .send()
+            .and_then(|r| r.error_for_status())
+            .and_then(|r| r.json())
+            .map_err(|e| format!("Failed to reach 17TRACK: {}", e))?;
+        if response.code != 0 {
+            return Err(format!("17TRACK returned code {}", response.code));
+        }
+        Ok(response)
+    }
+
+    /// Fetch the latest status of every undelivered package and return the
+    /// ones whose status changed
+    pub fn refresh(&self) -> Result<Vec<PackageUpdate>, String> {
+        let api_key = self
+            .vault
+            .get(VAULT_KEY)
+            .ok_or("Add a 17TRACK API key to update package statuses")?;
+        let pending: Vec<String> = self
+            .store
+            .read()
+            .packages
+            .iter()
+            .filter(|p| !p.delivered)
+            .map(|p| p.tracking_number.clone())
+            .collect();
+        if pending.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let numbers: Vec<&str> = pending.iter().map(String::as_str).collect();
+        // The API takes at most 40 numbers per request
+        let mut accepted = Vec::new();
+        for chunk in numbers.chunks(40) {
+            let response = self.call(&api_key, "gettrackinfo", chunk)?;
+            accepted.extend(response.data.map(|d| d.accepted).unwrap_or_default());
+        }
+
+        let now = chrono::Utc::now().to_rfc3339();
+        let mut updates = Vec::new();
+        let mut store = self.store.write();
+        for result in accepted {
+            let Some(package) = store
+                .packages
+                .iter_mut()
+                .find(|p| p.tracking_number == result.number)
+            else {
+                continue;
+            };
+            package.last_checked = Some(now.clone());
+            let Some(info) = result.track_info else {
+                continue;
+            };
+
+            let status = info.latest_status.and_then(|s| s.status);
+            if let Some(event) = info.latest_event {
+                package.last_event = match (event.description, event.location) {
+                    (Some(description), Some(location)) if !location.is_empty() => {
+                        Some(format!("{}, {}", description, location))
+                    }
+                    (description, _) => description,
+                };
+                package.last_event_time = event.time_iso;
+            }
+            if status.is_some() && status != package.status {
+                let previous_status = package.status.clone();
+                package.delivered = status.as_deref() == Some("Delivered");
+                package.status = status;
+                updates.push(PackageUpdate {
+                    package: package.clone(),
+                    previous_status,
+                });
+            }
+        }
+        self.save(&store)?;
+        Ok(updates)
+    }
+
+    fn package_result(package: &TrackedPackage, score: f32) -> SearchResult {
+        let status = package.status.as_deref().unwrap_or("Not checked yet");
+        let subtitle = match &package.last_event {
+            Some(event) => format!("{} • {}", status, event),
+            None => format!("{} • {}", status, package.tracking_number),
+        };
+        SearchResult {
+            id: format!("packages:open:{}", package.id),
+            title: package.title(),
+            subtitle: Some(subtitle),
+            icon: ResultIcon::Emoji(if package.delivered { "✅" } else { "📦" }.to_string()),
+            category: ResultCategory::Plugin,
+            score,
+            aria_label: None,
+        }
+    }
+
+    fn search_blocking(&self, query: &str) -> Vec<SearchResult> {
+        let query = query.trim();
+        let lower = query.to_lowercase();
+
+        let Some(rest) = query
+            .strip_prefix("track:")
+            .or_else(|| query.strip_prefix("track "))
+            .map(str::trim)
+        else {
+            // Outside the trigger, "package"/"delivery" lists what's on the way
+            if lower.len() >= 3
+                && ("packages".starts_with(&lower) || "deliveries".starts_with(&lower))
+            {
+                return self
+                    .packages()
+                    .iter()
+                    .filter(|p| !p.delivered)
+                    .enumerate()
+                    .map(|(i, p)| Self::package_result(p, 75.0 - i as f32))
+                    .collect();
+            }
+            return vec![];
+        };
+
+        let mut results = Vec::new();
+
+        // `track: <number> [label]` registers a new package
+        let (number, label) = rest.split_once(' ').unwrap_or((rest, ""));
+        let tracked = self
+            .store
+            .read()
+            .packages
+            .iter()
+            .any(|p| p.tracking_number.eq_ignore_ascii_case(number));
+        if looks_like_tracking_number(number) && !tracked {
+            let carrier = Carrier::detect(number);
+            let mut id = format!("packages:add:{}", urlencoding::encode(number));
+            if !label.trim().is_empty() {
+                id = format!("{}:{}", id, urlencoding::encode(label.trim()));
+            }
+            results.push(SearchResult {
+                id,
+                title: format!("Track {} package {}", carrier.name(), number.to_uppercase()),
+                subtitle: Some(if label.trim().is_empty() {
+                    "Add to tracked packages".to_string()
+                } else {
+                    format!("Add \"{}\" to tracked packages", label.trim())
+                }),
+                icon: ResultIcon::Emoji("📦".to_string()),
+                category: ResultCategory::Command,
+                score: 96.0,
+                aria_label: None,
+            });
+        }
+
+        let filter = rest.to_lowercase();
+        for (i, package) in self
+            .packages()
+            .iter()
+            .filter(|p| {
+                filter.is_empty()
+                    || p.title().to_lowercase().contains(&filter)
+                    || p.tracking_number.to_lowercase().contains(&filter)
+            })
+            .enumerate()
+        {
+            results.push(Self::package_result(package, 90.0 - i as f32));
+        }
+
+        results
+    }
+}
+
+#[async_trait]
+impl SearchProvider for PackageProvider {
+    fn id(&self) -> &str {
+        "packages"
+    }
+
+    async fn search(&self, query: &str, ctx: &SearchContext) -> Vec<SearchResult> {
+        ctx.run_blocking(|| self.search_blocking(query))
+    }
+
+    fn execute(&self, result_id: &str) -> Result<(), String> {
+        let action = result_id
+            .strip_prefix("packages:")
+            .ok_or("Invalid package result")?;
+
+        if let Some(rest) = action.strip_prefix("add:") {
+            let (number, label) = rest.split_once(':').unwrap_or((rest, ""));
+            let number = urlencoding::decode(number).map_err(|e| e.to_string())?;
+            let label = urlencoding::decode(label).map_err(|e| e.to_string())?;
+            return self
+                .add(&number, Some(label.as_ref()).filter(|l| !l.is_empty()))
+                .map(|_| ());
+        }
+        if let Some(id) = action.strip_prefix("open:") {
+            let package = self
+                .store
+                .read()
+                .packages
+                .iter()
+                .find(|p| p.id == id)
+                .cloned()
+                .ok_or("Package not found")?;
+            return super::url::open_url(&package.carrier.tracking_url(&package.tracking_number));
+        }
+
+        Err(format!("Unknown package action: {}", action))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_carrier_from_tracking_number() {
+        assert_eq!(Carrier::detect("1Z999AA10123456784"), Carrier::Ups);
+        assert_eq!(Carrier::detect("9400111899223100000000"), Carrier::Usps);
+        assert_eq!(Carrier::detect("123456789012"), Carrier::Fedex);
+        assert_eq!(Carrier::detect("JJD000390007827"), Carrier::Dhl);
+        assert_eq!(Carrier::detect("AB123456789GB"), Carrier::RoyalMail);
+        assert_eq!(Carrier::detect("XYZ"), Carrier::Unknown);
+    }
+}
@@ -1,42 +1,63 @@
-use super::{ResultCategory, ResultIcon, SearchProvider, SearchResult};
+use super::{ResultCategory, ResultIcon, SearchContext, SearchProvider, SearchResult};
+use crate::exec_policy::ExecPolicy;
+use crate::plugins::host_api::PluginAction;
 use crate::plugins::{PluginLoader, PluginRuntime};
+use async_trait::async_trait;
+use parking_lot::RwLock;
+use std::collections::{HashMap, VecDeque};
 use std::sync::Arc;
 
+/// How many recent searches keep their results' actions, so a result that
+/// is still on screen runs its own action after newer searches finish
+const ACTION_HISTORY: usize = 8;
+
 pub struct PluginProvider {
     loader: Arc<PluginLoader>,
     runtime: Arc<PluginRuntime>,
+    exec_policy: Arc<ExecPolicy>,
+    /// Actions of recent searches' results by result id, newest first
+    actions: RwLock<VecDeque<HashMap<String, PluginAction>>>,
 }
 
 impl PluginProvider {
-    pub fn new(loader: Arc<PluginLoader>, runtime: Arc<PluginRuntime>) -> Self {
-        Self { loader, runtime }
-    }
-}
-
-impl SearchProvider for PluginProvider {
-    fn id(&self) -> &str {
-        "plugins"
+    pub fn new(
+        loader: Arc<PluginLoader>,
+        runtime: Arc<PluginRuntime>,
+        exec_policy: Arc<ExecPolicy>,
+    ) -> Self {
+        Self {
+            loader,
+            runtime,
+            exec_policy,
+            actions: RwLock::new(VecDeque::new()),
+        }
     }
 
-    fn search(&self, query: &str) -> Vec<SearchResult> {
+    /// Search the plugins marked `fallback` in their manifest, or the rest
+    fn search_blocking(&self, query: &str, fallback: bool) -> Vec<SearchResult> {
         if query.is_empty() {
             return vec![];
         }
 
         let mut results = Vec::new();
+        let mut actions = HashMap::new();
         let plugin_ids = self.runtime.loaded_plugin_ids();
 
         for plugin_id in plugin_ids {
-            if let Some(plugin) = self.loader.get_plugin(&plugin_id) {
-                if !plugin.enabled {
+            if let Some(manifest) = self.loader.enabled_manifest(&plugin_id) {
+                if manifest.provides.fallback != fallback {
                     continue;
                 }
 
                 match self.runtime.call_search(&plugin_id, query) {
                     Ok(plugin_results) => {
                         for pr in plugin_results {
+                            let id = format!("plugin:{}:{}", plugin_id, pr.id);
+                            if let Some(action) = pr.action {
+                                actions.insert(id.clone(), action);
+                            }
                             results.push(SearchResult {
-                                id: format!("plugin:{}:{}", plugin_id, pr.id),
+                                id,
                                 title: pr.title,
                                 subtitle: pr.subtitle,
                                 icon: pr
@@ -45,6 +66,7 @@ impl SearchProvider for PluginProvider {
                                     .unwrap_or(ResultIcon::Emoji("🔌".to_string())),
                                 category: ResultCategory::Plugin,
                                 score: 50.0,
+                                aria_label: pr.aria_label,
                             });
                         }
                     }
@@ -55,18 +77,67 @@ impl SearchProvider for PluginProvider {
             }
         }
 
+        if !actions.is_empty() {
+            let mut history = self.actions.write();
+            history.push_front(actions);
+            history.truncate(ACTION_HISTORY);
+        }
         results
     }
 
+    /// Suggestions from fallback plugins, for a query no other provider
+    /// found anything for
+    pub fn search_fallbacks(&self, query: &str) -> Vec<SearchResult> {
+        self.search_blocking(query, true)
+    }
+
+    /// Drop the actions of a plugin's results, e.g. once it's been reloaded
+    pub fn forget_plugin(&self, plugin_id: &str) {
+        let prefix = format!("plugin:{}:", plugin_id);
+        for actions in self.actions.write().iter_mut() {
+            actions.retain(|id, _| !id.starts_with(&prefix));
+        }
+    }
+}
+
+#[async_trait]
+impl SearchProvider for PluginProvider {
+    fn id(&self) -> &str {
+        "plugins"
+    }
+
+    async fn search(&self, query: &str, ctx: &SearchContext) -> Vec<SearchResult> {
+        ctx.run_blocking(|| self.search_blocking(query, false))
+    }
+
     fn execute(&self, result_id: &str) -> Result<(), String> {
         let parts: Vec<&str> = result_id.splitn(3, ':').collect();
         if parts.len() < 3 || parts[0] != "plugin" {
             return Err("Invalid plugin result ID".to_string());
         }
 
-        let _plugin_id = parts[1];
-        let _action_id = parts[2];
+        let plugin_id = parts[1];
+        let action = self
+            .actions
+            .read()
+            .iter()
+            .find_map(|actions| actions.get(result_id))
+            .cloned();
 
-        Ok(())
+        match action {
+            // Only web links; a plugin shouldn't open local files or apps
+            Some(PluginAction::OpenUrl(url)) => {
+                if !url.starts_with("https://") && !url.starts_with("http://") {
+                    return Err(format!("Plugins can only open web links: {}", url));
+                }
+                tauri_plugin_opener::open_url(&url, None::<&str>)
+                    .map_err(|e| format!("Failed to open link: {}", e))
+            }
+            Some(PluginAction::Copy(text)) => crate::clipboard::copy_text(&text),
+            Some(PluginAction::RunCommand(command)) => {
+                self.exec_policy.request(plugin_id, &command)
+            }
+            Some(PluginAction::Custom(_)) | None => Ok(()),
+        }
     }
 }
@@ -0,0 +1,263 @@
+use super::{
+    ResultAction, ResultCategory, ResultIcon, SearchContext, SearchProvider, SearchResult,
+};
+use crate::cleanup::format_bytes;
+use async_trait::async_trait;
+use parking_lot::RwLock;
+use serde::Deserialize;
+use std::process::Command;
+use std::time::{Duration, Instant};
+
+const PREFIX: &str = "kill:";
+/// A search reuses a process list this recent, so typing doesn't re-run ps
+/// on every keystroke
+const LIST_TTL: Duration = Duration::from_secs(2);
+const MAX_RESULTS: usize = 30;
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+struct Process {
+    pid: u32,
+    name: String,
+    /// Percent of one core
+    cpu: f32,
+    /// Resident memory in bytes
+    memory: u64,
+}
+
+/// `ps -axo pid=,pcpu=,rss=,comm=` lines; `comm` is the executable's path
+/// on macOS and its (truncated) name on Linux
+#[cfg_attr(not(unix), allow(dead_code))]
+fn parse_ps(output: &str) -> Vec<Process> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let pid = fields.next()?.parse().ok()?;
+            let cpu = fields.next()?.parse().ok()?;
+            let rss: u64 = fields.next()?.parse().ok()?;
+            let command = fields.collect::<Vec<_>>().join(" ");
+            let name = command.rsplit('/').next().unwrap_or(&command).to_string();
+            (!name.is_empty()).then_some(Process {
+                pid,
+                name,
+                cpu,
+                memory: rss * 1024,
+            })
+        })
+        .collect()
+}
+
+#[cfg(unix)]
+fn list_processes() -> Result<Vec<Process>, String> {
+    let output = Command::new("ps")
+        .args(["-axo", "pid=,pcpu=,rss=,comm="])
+        .output()
+        .map_err(|e| format!("Failed to list processes: {}", e))?;
+    Ok(parse_ps(&String::from_utf8_lossy(&output.stdout)))
+}
+
+#[cfg(windows)]
+fn list_processes() -> Result<Vec<Process>, String> {
+    // The performance counters' CPU time is summed over cores like ps's
+    const SCRIPT: &str = r#"
+Get-CimInstance Win32_PerfFormattedData_PerfProc_Process |
+    Where-Object { $_.IDProcess -ne 0 -and $_.Name -ne '_Total' } |
+    ForEach-Object { [pscustomobject]@{
+        pid = [uint32]$_.IDProcess; name = ($_.Name -replace '#\d+$', '');
+        cpu = [single]$_.PercentProcessorTime; memory = [uint64]$_.WorkingSetPrivate } } |
+    ConvertTo-Json -Compress
+"#;
+    let output = Command::new("powershell")
+        .args(["-NoProfile", "-Command", SCRIPT])
+        .output()
+        .map_err(|e| format!("Failed to list processes: {}", e))?;
+    let json = String::from_utf8_lossy(&output.stdout);
+    serde_json::from_str(&json).map_err(|e| format!("Couldn't read the process list: {}", e))
+}
+
+#[cfg(unix)]
+fn signal(pid: u32, force: bool) -> Result<(), String> {
+    let signal = if force { libc::SIGKILL } else { libc::SIGTERM };
+    let result = unsafe { libc::kill(pid as libc::pid_t, signal) };
+    if result != 0 {
+        return Err(format!(
+            "Couldn't quit process {}: {}",
+            pid,
+            std::io::Error::last_os_error()
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(windows)]
+fn signal(pid: u32, force: bool) -> Result<(), String> {
+    // Without /F, taskkill asks the process's windows to close
+    let mut command = Command::new("taskkill");
+    if force {
+        command.arg("/F");
+    }
+    let output = command
+        .args(["/PID", &pid.to_string()])
+        .output()
+        .map_err(|e| format!("Failed to run taskkill: {}", e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "Couldn't quit process {}: {}",
+            pid,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(())
+}
+
+fn score(name: &str, filter: &str) -> Option<f32> {
+    let name = name.to_lowercase();
+    if filter.is_empty() || name.starts_with(filter) {
+        Some(90.0)
+    } else if name.contains(filter) {
+        Some(80.0)
+    } else {
+        None
+    }
+}
+
+/// Find running processes with `kill: name` and quit them: Enter asks the
+/// process to quit, the force action kills it outright
+pub struct ProcessProvider {
+    processes: RwLock<Option<(Instant, Vec<Process>)>>,
+}
+
+impl ProcessProvider {
+    pub fn new() -> Self {
+        Self {
+            processes: RwLock::new(None),
+        }
+    }
+
+    /// Other processes than the launcher, busiest first
+    fn processes(&self) -> Vec<Process> {
+        let cached = self
+            .processes
+            .read()
+            .as_ref()
+            .filter(|(listed_at, _)| listed_at.elapsed() < LIST_TTL)
+            .map(|(_, processes)| processes.clone());
+        if let Some(processes) = cached {
+            return processes;
+        }
+
+        let own = std::process::id();
+        let mut processes = match list_processes() {
+            Ok(processes) => processes,
+            Err(e) => {
+                eprintln!("{}", e);
+                return Vec::new();
+            }
+        };
+        processes.retain(|p| p.pid != own && p.pid > 1);
+        processes.sort_by(|a, b| b.cpu.total_cmp(&a.cpu));
+        *self.processes.write() = Some((Instant::now(), processes.clone()));
+        processes
+    }
+
+    fn pid(result_id: &str) -> Result<u32, String> {
+        result_id
+            .strip_prefix("process:")
+            .and_then(|pid| pid.parse().ok())
+            .ok_or_else(|| "Invalid process result".to_string())
+    }
+
+    /// Quit a process and drop the cached list so it disappears from results
+    fn quit(&self, result_id: &str, force: bool) -> Result<(), String> {
+        signal(Self::pid(result_id)?, force)?;
+        *self.processes.write() = None;
+        Ok(())
+    }
+}
+
+impl Default for ProcessProvider {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl SearchProvider for ProcessProvider {
+    fn id(&self) -> &str {
+        "process"
+    }
+
+    async fn search(&self, query: &str, ctx: &SearchContext) -> Vec<SearchResult> {
+        let Some(filter) = query.trim_start().strip_prefix(PREFIX) else {
+            return vec![];
+        };
+        let filter = filter.trim().to_lowercase();
+
+        let processes = ctx.run_blocking(|| self.processes());
+        let mut matches: Vec<(f32, Process)> = processes
+            .into_iter()
+            .filter_map(|p| Some((score(&p.name, &filter)?, p)))
+            .collect();
+        // Stable, so equally good matches stay busiest first
+        matches.sort_by(|a, b| b.0.total_cmp(&a.0));
+
+        matches
+            .into_iter()
+            .take(MAX_RESULTS)
+            .enumerate()
+            .map(|(i, (score, process))| SearchResult {
+                id: format!("process:{}", process.pid),
+                title: process.name,
+                subtitle: Some(format!(
+                    "PID {} • {:.1}% CPU • {}",
+                    process.pid,
+                    process.cpu,
+                    format_bytes(process.memory)
+                )),
+                icon: ResultIcon::Emoji("⚙️".to_string()),
+                category: ResultCategory::System,
+                score: score - i as f32 * 0.1,
+                aria_label: None,
+            })
+            .collect()
+    }
+
+    fn execute(&self, result_id: &str) -> Result<(), String> {
+        self.quit(result_id, false)
+    }
+
+    fn actions(&self, result_id: &str) -> Vec<ResultAction> {
+        if Self::pid(result_id).is_err() {
+            return Vec::new();
+        }
+        vec![ResultAction::new("force-quit", "Force Quit").with_shortcut("⌘⌫")]
+    }
+
+    fn execute_action(&self, result_id: &str, action_id: &str) -> Result<(), String> {
+        match action_id {
+            "force-quit" => self.quit(result_id, true),
+            _ => Err(format!("Unknown action: {}", action_id)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_ps_output() {
+        let output = "    1   0.0  12345 /sbin/launchd\n\
+                      4242  12.5 204800 /Applications/Zed.app/Contents/MacOS/zed\n\
+                      77    0.3   2048 Google Chrome Helper\n";
+        let processes = parse_ps(output);
+        assert_eq!(processes.len(), 3);
+        assert_eq!(processes[1].name, "zed");
+        assert_eq!(processes[1].memory, 204800 * 1024);
+        assert_eq!(processes[2].name, "Google Chrome Helper");
+
+        assert_eq!(score("zed", "ze"), Some(90.0));
+        assert_eq!(score("Google Chrome Helper", "chrome"), Some(80.0));
+        assert_eq!(score("launchd", "chrome"), None);
+    }
+}
@@ -0,0 +1,275 @@
+use super::{
+    ResultAction, ResultCategory, ResultIcon, SearchContext, SearchProvider, SearchResult,
+};
+use crate::capture::{self, CaptureMode};
+use async_trait::async_trait;
+use image::{GrayImage, Luma};
+use qrcode::{Color, QrCode};
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+
+/// Pixels per QR module in generated images
+const MODULE_PIXELS: u32 = 8;
+
+/// Light border around the code, in modules; scanners need at least 4
+const QUIET_ZONE: u32 = 4;
+
+/// Byte capacity of the largest QR code at the lowest error correction
+const MAX_QR_BYTES: usize = 2953;
+
+const KEYWORDS: &[&str] = &["qr", "qr code", "scan qr", "scan qr code"];
+
+/// Render `text` as a black-on-white QR code
+pub fn render(text: &str) -> Result<GrayImage, String> {
+    let code = QrCode::new(text.as_bytes()).map_err(|e| format!("Can't encode as QR: {}", e))?;
+    let width = code.width() as u32;
+    let colors = code.to_colors();
+    let size = (width + QUIET_ZONE * 2) * MODULE_PIXELS;
+
+    Ok(GrayImage::from_fn(size, size, |x, y| {
+        let module_x = (x / MODULE_PIXELS).checked_sub(QUIET_ZONE);
+        let module_y = (y / MODULE_PIXELS).checked_sub(QUIET_ZONE);
+        let dark = match (module_x, module_y) {
+            (Some(mx), Some(my)) if mx < width && my < width => {
+                colors[(my * width + mx) as usize] == Color::Dark
+            }
+            _ => false,
+        };
+        Luma([if dark { 0 } else { 255 }])
+    }))
+}
+
+/// Text of every QR code found in a PNG
+pub fn decode_png(png: &[u8]) -> Result<Vec<String>, String> {
+    let image = image::load_from_memory(png)
+        .map_err(|e| format!("Invalid image: {}", e))?
+        .to_luma8();
+    let mut prepared = rqrr::PreparedImage::prepare_from_greyscale(
+        image.width() as usize,
+        image.height() as usize,
+        |x, y| image.get_pixel(x as u32, y as u32).0[0],
+    );
+    let texts: Vec<String> = prepared
+        .detect_grids()
+        .into_iter()
+        .filter_map(|grid| grid.decode().ok().map(|(_, text)| text))
+        .collect();
+    if texts.is_empty() {
+        return Err("No QR code found".to_string());
+    }
+    Ok(texts)
+}
+
+fn preview(text: &str) -> String {
+    let flat = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if flat.chars().count() > 60 {
+        format!("{}…", flat.chars().take(60).collect::<String>())
+    } else {
+        flat
+    }
+}
+
+/// `qr: <text>` offers a QR code for the text, or for the clipboard when no
+/// text is given. The PNG is only rendered when the result is used or
+/// previewed, not on every keystroke. Scanning reads codes from a clipboard
+/// image or a screen region and copies what they contain.
+pub struct QrProvider {
+    /// Generated codes live next to the app icons, named by content hash
+    cache_dir: PathBuf,
+}
+
+impl QrProvider {
+    pub fn new() -> Self {
+        let cache_dir = dirs::data_local_dir()
+            .unwrap_or_else(|| PathBuf::from("."))
+            .join("launcher")
+            .join("icons");
+
+        Self { cache_dir }
+    }
+
+    /// Render `text` into the icon cache, reusing an earlier PNG of the same
+    /// text. Returns the PNG path.
+    pub fn generate(&self, text: &str) -> Result<PathBuf, String> {
+        let hash = format!("{:x}", Sha256::digest(text.as_bytes()));
+        let path = self.cache_dir.join(format!("qr-{}.png", &hash[..16]));
+        if path.exists() {
+            return Ok(path);
+        }
+
+        std::fs::create_dir_all(&self.cache_dir).map_err(|e| e.to_string())?;
+        render(text)?
+            .save(&path)
+            .map_err(|e| format!("Failed to save QR code: {}", e))?;
+        Ok(path)
+    }
+
+    /// Decode the QR code in the image on the clipboard and copy its text
+    pub fn scan_clipboard(&self) -> Result<String, String> {
+        let png = crate::clipboard::read_image()?;
+        copy_decoded(decode_png(&png)?)
+    }
+
+    /// Let the user select a screen region and decode the QR code in it
+    pub fn scan_screen(&self) -> Result<String, String> {
+        let dir = std::env::temp_dir().join("launcher-qr");
+        let path = capture::take_screenshot(CaptureMode::Region, &dir)?;
+        let png = std::fs::read(&path).map_err(|e| e.to_string());
+        let _ = std::fs::remove_file(&path);
+        copy_decoded(decode_png(&png?)?)
+    }
+
+    /// Decode the QR code in a saved image, e.g. an earlier screenshot
+    pub fn scan_file(&self, path: &Path) -> Result<String, String> {
+        let png = std::fs::read(path).map_err(|e| e.to_string())?;
+        copy_decoded(decode_png(&png)?)
+    }
+
+    fn code_path(&self, result_id: &str) -> Result<PathBuf, String> {
+        let text = result_id
+            .strip_prefix("qr:code:")
+            .ok_or("Invalid QR result")?;
+        let text = urlencoding::decode(text).map_err(|e| e.to_string())?;
+        self.generate(&text)
+    }
+}
+
+impl Default for QrProvider {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn code_result(text: &str, source: &str) -> SearchResult {
+    if text.len() > MAX_QR_BYTES {
+        return SearchResult {
+            id: "qr:too-long".to_string(),
+            title: "Too long for a QR code".to_string(),
+            subtitle: Some(format!(
+                "That's {} bytes, a QR code fits {}",
+                text.len(),
+                MAX_QR_BYTES
+            )),
+            icon: ResultIcon::Emoji("🔳".to_string()),
+            category: ResultCategory::Command,
+            score: 90.0,
+            aria_label: None,
+        };
+    }
+    SearchResult {
+        id: format!("qr:code:{}", urlencoding::encode(text)),
+        title: format!("QR code for {}", preview(text)),
+        subtitle: Some(format!("From {} • copy image", source)),
+        icon: ResultIcon::Emoji("🔳".to_string()),
+        category: ResultCategory::Command,
+        score: 90.0,
+        aria_label: None,
+    }
+}
+
+/// Copy the decoded text, one code per line when the image held several
+fn copy_decoded(texts: Vec<String>) -> Result<String, String> {
+    let text = texts.join("\n");
+    crate::clipboard::copy_text(&text)?;
+    Ok(text)
+}
+
+fn scan_results(score: f32) -> Vec<SearchResult> {
+    vec![
+        SearchResult {
+            id: "qr:scan-clipboard".to_string(),
+            title: "Scan QR Code from Clipboard Image".to_string(),
+            subtitle: Some("Copy the text of the QR code in the copied image".to_string()),
+            icon: ResultIcon::Emoji("📷".to_string()),
+            category: ResultCategory::Command,
+            score,
+            aria_label: None,
+        },
+        SearchResult {
+            id: "qr:scan-screen".to_string(),
+            title: "Scan QR Code on Screen".to_string(),
+            subtitle: Some("Select the area with the code".to_string()),
+            icon: ResultIcon::Emoji("📷".to_string()),
+            category: ResultCategory::Command,
+            score: score - 1.0,
+            aria_label: None,
+        },
+    ]
+}
+
+#[async_trait]
+impl SearchProvider for QrProvider {
+    fn id(&self) -> &str {
+        "qr"
+    }
+
+    async fn search(&self, query: &str, ctx: &SearchContext) -> Vec<SearchResult> {
+        let Some(input) = query.trim_start().strip_prefix("qr:") else {
+            let query = query.trim().to_lowercase();
+            if query.len() >= 2 && KEYWORDS.iter().any(|k| k.starts_with(&query)) {
+                return scan_results(75.0);
+            }
+            return vec![];
+        };
+
+        let input = input.trim();
+        let mut results = Vec::new();
+        if !input.is_empty() {
+            results.push(code_result(input, "the text"));
+        } else if let Some(text) = ctx.run_blocking(|| crate::clipboard::read_text().ok()) {
+            if !text.trim().is_empty() {
+                results.push(code_result(text.trim(), "the clipboard"));
+            }
+        }
+        results.extend(scan_results(85.0));
+        results
+    }
+
+    fn execute(&self, result_id: &str) -> Result<(), String> {
+        match result_id {
+            "qr:too-long" => Ok(()),
+            "qr:scan-clipboard" => self.scan_clipboard().map(|_| ()),
+            "qr:scan-screen" => self.scan_screen().map(|_| ()),
+            _ => crate::clipboard::copy_image(&self.code_path(result_id)?),
+        }
+    }
+
+    fn actions(&self, result_id: &str) -> Vec<ResultAction> {
+        if result_id.starts_with("qr:code:") {
+            super::path_actions()
+        } else {
+            Vec::new()
+        }
+    }
+
+    fn execute_action(&self, result_id: &str, action_id: &str) -> Result<(), String> {
+        let path = self.code_path(result_id)?;
+        super::execute_path_action(&path.to_string_lossy(), action_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_with_quiet_zone() {
+        let image = render("https://example.com").unwrap();
+        assert_eq!(image.width(), image.height());
+        // Corners are in the quiet zone, the finder pattern starts right after
+        assert_eq!(image.get_pixel(0, 0).0[0], 255);
+        let start = QUIET_ZONE * MODULE_PIXELS;
+        assert_eq!(image.get_pixel(start, start).0[0], 0);
+    }
+
+    #[test]
+    fn decodes_what_it_renders() {
+        let image = render("hello qr").unwrap();
+        let mut png = Vec::new();
+        image
+            .write_to(&mut std::io::Cursor::new(&mut png), image::ImageFormat::Png)
+            .unwrap();
+        assert_eq!(decode_png(&png).unwrap(), vec!["hello qr".to_string()]);
+        assert!(decode_png(&[]).is_err());
+    }
+}
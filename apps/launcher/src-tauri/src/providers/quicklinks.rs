@@ -0,0 +1,240 @@
+use super::{
+    Mutated, ResultAction, ResultCategory, ResultIcon, ResultMutation, SearchContext,
+    SearchProvider, SearchResult,
+};
+use crate::settings::{Quicklink, SettingsStore};
+use crate::undo::UndoableAction;
+use async_trait::async_trait;
+use std::sync::Arc;
+
+const PLACEHOLDER: &str = "{query}";
+
+/// Fill the template's `{query}` placeholders with the URL-encoded argument
+pub fn fill(template: &str, argument: &str) -> String {
+    template.replace(PLACEHOLDER, &urlencoding::encode(argument))
+}
+
+/// Check a quicklink before it is saved
+pub fn validate(quicklink: &Quicklink) -> Result<(), String> {
+    if quicklink.name.trim().is_empty() {
+        return Err("Quicklink name can't be empty".to_string());
+    }
+    url::Url::parse(&fill(&quicklink.url, "test"))
+        .map_err(|e| format!("Invalid quicklink URL: {}", e))?;
+    Ok(())
+}
+
+/// The saved quicklink a result's URL was filled in from. When several
+/// could have made it, the one with the most fixed text wins.
+fn quicklink_for_url<'a>(quicklinks: &'a [Quicklink], url: &str) -> Option<&'a Quicklink> {
+    quicklinks
+        .iter()
+        .filter(|quicklink| {
+            let template = quicklink.url.as_str();
+            match (template.find(PLACEHOLDER), template.rfind(PLACEHOLDER)) {
+                (Some(first), Some(last)) => {
+                    let before = &template[..first];
+                    let after = &template[last + PLACEHOLDER.len()..];
+                    url.len() >= before.len() + after.len()
+                        && url.starts_with(before)
+                        && url.ends_with(after)
+                }
+                _ => template == url,
+            }
+        })
+        .max_by_key(|quicklink| quicklink.url.replace(PLACEHOLDER, "").len())
+}
+
+/// The text typed after the quicklink's name, if the query starts with it
+fn argument<'a>(query: &'a str, name: &str) -> Option<&'a str> {
+    let head = query.get(..name.len())?;
+    if !head.eq_ignore_ascii_case(name) {
+        return None;
+    }
+    let rest = &query[name.len()..];
+    if rest.is_empty() {
+        Some("")
+    } else if rest.starts_with(char::is_whitespace) {
+        Some(rest.trim())
+    } else {
+        None
+    }
+}
+
+/// User-defined quicklinks: typing a quicklink's name followed by text opens
+/// its URL with the text in place of `{query}`.
+pub struct QuicklinkProvider {
+    settings: Arc<SettingsStore>,
+}
+
+impl QuicklinkProvider {
+    pub fn new(settings: Arc<SettingsStore>) -> Self {
+        Self { settings }
+    }
+
+    fn result(quicklink: &Quicklink, argument: &str, score: f32) -> SearchResult {
+        let url = fill(&quicklink.url, argument);
+        let takes_argument = quicklink.url.contains(PLACEHOLDER);
+        let title = if argument.is_empty() || !takes_argument {
+            format!("Open {}", quicklink.name)
+        } else {
+            format!("Open {} for \"{}\"", quicklink.name, argument)
+        };
+        let subtitle = if argument.is_empty() && takes_argument {
+            format!("Type {} <text> • {}", quicklink.name, quicklink.url)
+        } else {
+            url.clone()
+        };
+
+        SearchResult {
+            id: format!("quicklink:{}", url),
+            title,
+            subtitle: Some(subtitle),
+            icon: ResultIcon::Emoji("🔗".to_string()),
+            category: ResultCategory::URL,
+            score,
+            aria_label: None,
+        }
+    }
+}
+
+#[async_trait]
+impl SearchProvider for QuicklinkProvider {
+    fn id(&self) -> &str {
+        "quicklink"
+    }
+
+    async fn search(&self, query: &str, _ctx: &SearchContext) -> Vec<SearchResult> {
+        let query = query.trim_start();
+        if query.trim().len() < 2 {
+            return vec![];
+        }
+        let lowercase = query.trim().to_lowercase();
+
+        let mut results = Vec::new();
+        for quicklink in &self.settings.get().quicklinks {
+            if let Some(argument) = argument(query, &quicklink.name) {
+                results.push(Self::result(quicklink, argument, 95.0));
+            } else if quicklink.name.to_lowercase().starts_with(&lowercase) {
+                results.push(Self::result(quicklink, "", 75.0));
+            }
+        }
+        results
+    }
+
+    fn execute(&self, result_id: &str) -> Result<(), String> {
+        let url = result_id
+            .strip_prefix("quicklink:")
+            .ok_or("Invalid quicklink result")?;
+        super::url::open_url(url)
+    }
+
+    fn actions(&self, _result_id: &str) -> Vec<ResultAction> {
+        vec![ResultAction::new("copy-url", "Copy URL").with_shortcut("⌘C")]
+    }
+
+    fn execute_action(&self, result_id: &str, action_id: &str) -> Result<(), String> {
+        let url = result_id
+            .strip_prefix("quicklink:")
+            .ok_or("Invalid quicklink result")?;
+        match action_id {
+            "copy-url" => crate::clipboard::copy_text(url),
+            _ => Err(format!("Unknown action: {}", action_id)),
+        }
+    }
+
+    fn mutations(&self, result_id: &str) -> Vec<&'static str> {
+        let url = result_id.strip_prefix("quicklink:").unwrap_or_default();
+        // Fallback URLs share the id scheme but aren't saved quicklinks
+        match quicklink_for_url(&self.settings.get().quicklinks, url) {
+            Some(_) => vec!["rename", "set_url"],
+            None => Vec::new(),
+        }
+    }
+
+    fn mutate(&self, result_id: &str, mutation: &ResultMutation) -> Result<Mutated, String> {
+        let url = result_id
+            .strip_prefix("quicklink:")
+            .ok_or("Invalid quicklink result")?;
+        let quicklinks = self.settings.get().quicklinks;
+        let before = quicklink_for_url(&quicklinks, url)
+            .ok_or("Not a saved quicklink")?
+            .clone();
+
+        let mut after = before.clone();
+        match mutation {
+            ResultMutation::Rename { name } => after.name = name.trim().to_string(),
+            ResultMutation::SetUrl { url } => after.url = url.trim().to_string(),
+        }
+        validate(&after)?;
+        if !after.name.eq_ignore_ascii_case(&before.name)
+            && quicklinks
+                .iter()
+                .any(|q| q.name.eq_ignore_ascii_case(&after.name))
+        {
+            return Err(format!("A quicklink named {} already exists", after.name));
+        }
+        self.settings.replace_quicklink(&before.name, after.clone());
+
+        Ok(Mutated {
+            result: Self::result(&after, "", 75.0),
+            undo: UndoableAction::EditQuicklink {
+                name: after.name,
+                before,
+            },
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fills_encoded_argument() {
+        assert_eq!(
+            fill("https://crates.io/search?q={query}", "serde json"),
+            "https://crates.io/search?q=serde%20json"
+        );
+        let quicklink = Quicklink {
+            name: "bad".to_string(),
+            url: "not a url {query}".to_string(),
+        };
+        assert!(validate(&quicklink).is_err());
+    }
+
+    #[test]
+    fn finds_quicklink_for_filled_url() {
+        let quicklinks = vec![
+            Quicklink {
+                name: "g".to_string(),
+                url: "https://google.com/search?q={query}".to_string(),
+            },
+            Quicklink {
+                name: "gi".to_string(),
+                url: "https://google.com/search?q={query}&tbm=isch".to_string(),
+            },
+            Quicklink {
+                name: "home".to_string(),
+                url: "https://example.com".to_string(),
+            },
+        ];
+        let name = |url: &str| quicklink_for_url(&quicklinks, url).map(|q| q.name.as_str());
+
+        assert_eq!(name("https://google.com/search?q=cats"), Some("g"));
+        assert_eq!(
+            name("https://google.com/search?q=cats&tbm=isch"),
+            Some("gi")
+        );
+        assert_eq!(name("https://example.com"), Some("home"));
+        assert_eq!(name("https://developer.mozilla.org/search?q=flex"), None);
+    }
+
+    #[test]
+    fn splits_argument_after_name() {
+        assert_eq!(argument("GH tokio", "gh"), Some("tokio"));
+        assert_eq!(argument("gh", "gh"), Some(""));
+        assert_eq!(argument("ghost", "gh"), None);
+        assert_eq!(argument("docs rs serde", "docs rs"), Some("serde"));
+    }
+}
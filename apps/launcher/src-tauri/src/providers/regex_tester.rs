@@ -0,0 +1,255 @@
+use super::{ResultCategory, ResultIcon, SearchContext, SearchProvider, SearchResult};
+use async_trait::async_trait;
+use regex::{Regex, RegexBuilder};
+
+const MAX_MATCHES: usize = 20;
+/// Keeps the compiled program small enough to build on every keystroke
+const SIZE_LIMIT: usize = 1 << 20;
+
+/// A `/pattern/flags text` line; an empty `text` means "use the clipboard"
+#[derive(Debug, PartialEq)]
+struct RegexQuery<'a> {
+    pattern: String,
+    flags: &'a str,
+    text: &'a str,
+}
+
+fn parse_query(input: &str) -> Result<RegexQuery<'_>, String> {
+    let rest = input
+        .strip_prefix('/')
+        .ok_or("Write the pattern between slashes: re:/\\d+/ text")?;
+
+    // Find the closing slash, skipping escaped ones (`\/`)
+    let mut pattern = String::new();
+    let mut chars = rest.char_indices();
+    let mut end = None;
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '\\' => match chars.next() {
+                Some((_, '/')) => pattern.push('/'),
+                Some((_, next)) => {
+                    pattern.push('\\');
+                    pattern.push(next);
+                }
+                None => pattern.push('\\'),
+            },
+            '/' => {
+                end = Some(i);
+                break;
+            }
+            c => pattern.push(c),
+        }
+    }
+    let end = end.ok_or("Missing closing '/'")?;
+
+    let after = &rest[end + 1..];
+    let flags_len = after
+        .find(|c: char| !c.is_ascii_alphabetic())
+        .unwrap_or(after.len());
+    let (flags, text) = after.split_at(flags_len);
+    if let Some(bad) = flags.chars().find(|c| !"imsxU".contains(*c)) {
+        return Err(format!("Unknown flag '{}' (use i, m, s, x or U)", bad));
+    }
+
+    Ok(RegexQuery {
+        pattern,
+        flags,
+        text: text.strip_prefix(' ').unwrap_or(text),
+    })
+}
+
+fn build_regex(pattern: &str, flags: &str) -> Result<Regex, String> {
+    RegexBuilder::new(pattern)
+        .case_insensitive(flags.contains('i'))
+        .multi_line(flags.contains('m'))
+        .dot_matches_new_line(flags.contains('s'))
+        .ignore_whitespace(flags.contains('x'))
+        .swap_greed(flags.contains('U'))
+        .size_limit(SIZE_LIMIT)
+        .build()
+        .map_err(|e| e.to_string())
+}
+
+fn truncate(text: &str, max: usize) -> String {
+    let flat = text.replace('\n', "↵");
+    if flat.chars().count() > max {
+        format!("{}…", flat.chars().take(max).collect::<String>())
+    } else {
+        flat
+    }
+}
+
+fn copy_result(id_text: &str, title: String, subtitle: String, score: f32) -> SearchResult {
+    SearchResult {
+        id: format!("re:copy:{}", urlencoding::encode(id_text)),
+        title,
+        subtitle: Some(subtitle),
+        icon: ResultIcon::Emoji("🔍".to_string()),
+        category: ResultCategory::Command,
+        score,
+        aria_label: None,
+    }
+}
+
+fn info_result(title: &str, subtitle: String) -> SearchResult {
+    SearchResult {
+        id: "re:info".to_string(),
+        title: title.to_string(),
+        subtitle: Some(subtitle),
+        icon: ResultIcon::Emoji("🔍".to_string()),
+        category: ResultCategory::Command,
+        score: 90.0,
+        aria_label: None,
+    }
+}
+
+/// Offline regex tester: `re:/(\w+)@(\w+)/i mail me at a@b` lists matches and
+/// capture groups. Without text after the pattern, the clipboard is tested.
+pub struct RegexProvider;
+
+impl RegexProvider {
+    fn test(&self, query: &RegexQuery, text: &str, source: &str) -> Vec<SearchResult> {
+        let regex = match build_regex(&query.pattern, query.flags) {
+            Ok(regex) => regex,
+            Err(e) => return vec![info_result("Invalid pattern", e)],
+        };
+
+        let names: Vec<Option<&str>> = regex.capture_names().collect();
+        let captures: Vec<regex::Captures> = regex.captures_iter(text).collect();
+        if captures.is_empty() {
+            return vec![info_result(
+                "No matches",
+                format!("/{}/ doesn't match {}", query.pattern, source),
+            )];
+        }
+
+        let mut results = Vec::new();
+        let all: Vec<&str> = captures
+            .iter()
+            .filter_map(|c| c.get(0).map(|m| m.as_str()))
+            .collect();
+        results.push(copy_result(
+            &all.join("\n"),
+            format!(
+                "{} match{} in {}",
+                all.len(),
+                if all.len() == 1 { "" } else { "es" },
+                source
+            ),
+            "Copy all matches, one per line".to_string(),
+            96.0,
+        ));
+
+        for (i, caps) in captures.iter().take(MAX_MATCHES).enumerate() {
+            let Some(whole) = caps.get(0) else {
+                continue;
+            };
+            let groups: Vec<String> = names
+                .iter()
+                .enumerate()
+                .skip(1)
+                .map(|(index, name)| {
+                    let label = name.map(String::from).unwrap_or_else(|| index.to_string());
+                    let value = caps
+                        .get(index)
+                        .map(|m| format!("\"{}\"", truncate(m.as_str(), 40)))
+                        .unwrap_or_else(|| "–".to_string());
+                    format!("{}: {}", label, value)
+                })
+                .collect();
+            let position = format!("{}..{}", whole.start(), whole.end());
+            let subtitle = if groups.is_empty() {
+                format!("at {}", position)
+            } else {
+                format!("at {} • {}", position, groups.join(" • "))
+            };
+
+            results.push(copy_result(
+                whole.as_str(),
+                format!("{}. {}", i + 1, truncate(whole.as_str(), 80)),
+                subtitle,
+                95.0 - i as f32 * 0.1,
+            ));
+        }
+        results
+    }
+}
+
+#[async_trait]
+impl SearchProvider for RegexProvider {
+    fn id(&self) -> &str {
+        "re"
+    }
+
+    async fn search(&self, query: &str, ctx: &SearchContext) -> Vec<SearchResult> {
+        let Some(input) = query.trim_start().strip_prefix("re:") else {
+            return vec![];
+        };
+        let input = input.trim_start();
+        if input.is_empty() {
+            return vec![info_result(
+                "Regex tester",
+                "re:/pattern/flags text — leave out the text to test the clipboard".to_string(),
+            )];
+        }
+
+        let parsed = match parse_query(input) {
+            Ok(parsed) => parsed,
+            Err(e) => return vec![info_result("Invalid regex query", e)],
+        };
+
+        if !parsed.text.is_empty() {
+            return self.test(&parsed, parsed.text, "the text");
+        }
+        // `None` when the search was superseded before the clipboard was read
+        match ctx.run_blocking(|| Some(crate::clipboard::read_text())) {
+            None => vec![],
+            Some(Ok(text)) if !text.is_empty() => self.test(&parsed, &text, "the clipboard"),
+            Some(Ok(_)) => vec![info_result(
+                "The clipboard is empty",
+                "Add text after the pattern".to_string(),
+            )],
+            Some(Err(e)) => vec![info_result("Couldn't read the clipboard", e)],
+        }
+    }
+
+    fn execute(&self, result_id: &str) -> Result<(), String> {
+        if result_id == "re:info" {
+            return Ok(());
+        }
+        let text = result_id
+            .strip_prefix("re:copy:")
+            .ok_or("Invalid regex result")?;
+        let text = urlencoding::decode(text).map_err(|e| e.to_string())?;
+        crate::clipboard::copy_text(&text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_pattern_flags_and_text() {
+        let query = parse_query(r"/\d+/ some text 123").unwrap();
+        assert_eq!(query.pattern, r"\d+");
+        assert_eq!(query.flags, "");
+        assert_eq!(query.text, "some text 123");
+
+        let query = parse_query(r"/a\/b/im").unwrap();
+        assert_eq!(query.pattern, "a/b");
+        assert_eq!(query.flags, "im");
+        assert_eq!(query.text, "");
+
+        assert!(parse_query("no slashes").is_err());
+        assert!(parse_query("/unclosed").is_err());
+        assert!(parse_query("/a/q text").is_err());
+    }
+
+    #[test]
+    fn applies_flags() {
+        assert!(build_regex("hello", "i").unwrap().is_match("HELLO"));
+        assert!(!build_regex("hello", "").unwrap().is_match("HELLO"));
+        assert!(build_regex("(", "").is_err());
+    }
+}
@@ -0,0 +1,358 @@
+use super::{ResultCategory, ResultIcon, SearchContext, SearchProvider, SearchResult};
+use async_trait::async_trait;
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// Package metadata rarely changes within a session
+const CACHE_TTL: Duration = Duration::from_secs(60 * 60);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+const USER_AGENT: &str = concat!("launcher/", env!("CARGO_PKG_VERSION"));
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Ecosystem {
+    Crates,
+    Npm,
+    PyPi,
+}
+
+impl Ecosystem {
+    /// Query keyword for each registry, e.g. `crate serde`
+    pub fn parse(keyword: &str) -> Option<Self> {
+        match keyword {
+            "crate" | "crates" | "cargo" => Some(Self::Crates),
+            "npm" => Some(Self::Npm),
+            "pip" | "pypi" => Some(Self::PyPi),
+            _ => None,
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Crates => "crates.io",
+            Self::Npm => "npm",
+            Self::PyPi => "PyPI",
+        }
+    }
+
+    fn icon(&self) -> &'static str {
+        match self {
+            Self::Crates => "🦀",
+            Self::Npm => "📦",
+            Self::PyPi => "🐍",
+        }
+    }
+
+    fn page_url(&self, name: &str) -> String {
+        match self {
+            Self::Crates => format!("https://crates.io/crates/{}", name),
+            Self::Npm => format!("https://www.npmjs.com/package/{}", name),
+            Self::PyPi => format!("https://pypi.org/project/{}/", name),
+        }
+    }
+
+    /// The line to paste into Cargo.toml, package.json or requirements.txt
+    fn dependency_line(&self, name: &str, version: &str) -> String {
+        match self {
+            Self::Crates => format!("{} = \"{}\"", name, version),
+            Self::Npm => format!("\"{}\": \"^{}\"", name, version),
+            Self::PyPi => format!("{}=={}", name, version),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PackageInfo {
+    pub ecosystem: Ecosystem,
+    pub name: String,
+    pub version: String,
+    pub description: Option<String>,
+    /// Recent downloads, over whatever window the registry reports
+    pub downloads: Option<u64>,
+    pub downloads_period: Option<String>,
+    pub url: String,
+    pub dependency_line: String,
+}
+
+#[derive(Deserialize)]
+struct CratesResponse {
+    #[serde(rename = "crate")]
+    krate: CrateInfo,
+}
+
+#[derive(Deserialize)]
+struct CrateInfo {
+    name: String,
+    max_stable_version: Option<String>,
+    max_version: String,
+    description: Option<String>,
+    recent_downloads: Option<u64>,
+}
+
+#[derive(Deserialize)]
+struct NpmLatest {
+    name: String,
+    version: String,
+    description: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct NpmDownloads {
+    downloads: u64,
+}
+
+#[derive(Deserialize)]
+struct PyPiResponse {
+    info: PyPiInfo,
+}
+
+#[derive(Deserialize)]
+struct PyPiInfo {
+    name: String,
+    version: String,
+    summary: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct PyPiStats {
+    data: PyPiStatsData,
+}
+
+#[derive(Deserialize)]
+struct PyPiStatsData {
+    last_week: u64,
+}
+
+fn format_count(n: u64) -> String {
+    match n {
+        0..=999 => n.to_string(),
+        1_000..=999_999 => format!("{:.1}k", n as f64 / 1_000.0),
+        _ => format!("{:.1}M", n as f64 / 1_000_000.0),
+    }
+}
+
+/// Latest version lookups for crates.io, npm and PyPI
+/// (`crate serde`, `npm react`, `pip requests`)
+pub struct RegistryProvider {
+    client: reqwest::blocking::Client,
+    cache: RwLock<HashMap<(Ecosystem, String), (Instant, Option<PackageInfo>)>>,
+}
+
+impl RegistryProvider {
+    pub fn new() -> Self {
+        Self {
+            client: reqwest::blocking::Client::builder()
+                .timeout(REQUEST_TIMEOUT)
+                .user_agent(USER_AGENT)
+                .build()
+                .unwrap_or_default(),
+            cache: RwLock::new(HashMap::new()),
+        }
+    }
+
+    fn get_json<T: serde::de::DeserializeOwned>(&self, url: &str) -> Result<Option<T>, String> {
+        let response = self
+            .client
+            .get(url)
+            .send()
+            .map_err(|e| format!("Failed to reach registry: {}", e))?;
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        response
+            .error_for_status()
+            .and_then(|r| r.json())
+            .map(Some)
+            .map_err(|e| e.to_string())
+    }
+
+    fn fetch(&self, ecosystem: Ecosystem, name: &str) -> Result<Option<PackageInfo>, String> {
+        let encoded = urlencoding::encode(name);
+        let (name, version, description, downloads, period) = match ecosystem {
+            Ecosystem::Crates => {
+                let Some(response) = self.get_json::<CratesResponse>(&format!(
+                    "https://crates.io/api/v1/crates/{}",
+                    encoded
+                ))?
+                else {
+                    return Ok(None);
+                };
+                let krate = response.krate;
+                (
+                    krate.name,
+                    krate.max_stable_version.unwrap_or(krate.max_version),
+                    krate.description,
+                    krate.recent_downloads,
+                    "90 days",
+                )
+            }
+            Ecosystem::Npm => {
+                let Some(latest) = self.get_json::<NpmLatest>(&format!(
+                    "https://registry.npmjs.org/{}/latest",
+                    encoded
+                ))?
+                else {
+                    return Ok(None);
+                };
+                let downloads = self
+                    .get_json::<NpmDownloads>(&format!(
+                        "https://api.npmjs.org/downloads/point/last-week/{}",
+                        encoded
+                    ))
+                    .ok()
+                    .flatten()
+                    .map(|d| d.downloads);
+                (
+                    latest.name,
+                    latest.version,
+                    latest.description,
+                    downloads,
+                    "week",
+                )
+            }
+            Ecosystem::PyPi => {
+                let Some(response) = self
+                    .get_json::<PyPiResponse>(&format!("https://pypi.org/pypi/{}/json", encoded))?
+                else {
+                    return Ok(None);
+                };
+                let downloads = self
+                    .get_json::<PyPiStats>(&format!(
+                        "https://pypistats.org/api/packages/{}/recent",
+                        encoded
+                    ))
+                    .ok()
+                    .flatten()
+                    .map(|s| s.data.last_week);
+                (
+                    response.info.name,
+                    response.info.version,
+                    response.info.summary,
+                    downloads,
+                    "week",
+                )
+            }
+        };
+
+        Ok(Some(PackageInfo {
+            ecosystem,
+            url: ecosystem.page_url(&name),
+            dependency_line: ecosystem.dependency_line(&name, &version),
+            description: description.filter(|d| !d.trim().is_empty()),
+            downloads_period: downloads.map(|_| period.to_string()),
+            downloads,
+            version,
+            name,
+        }))
+    }
+
+    /// Look up a package, using the cache when fresh. Missing packages are
+    /// cached too so typing doesn't re-query every prefix.
+    pub fn lookup(&self, ecosystem: Ecosystem, name: &str) -> Result<Option<PackageInfo>, String> {
+        let key = (ecosystem, name.trim().to_lowercase());
+        if let Some((fetched_at, info)) = self.cache.read().get(&key) {
+            if fetched_at.elapsed() < CACHE_TTL {
+                return Ok(info.clone());
+            }
+        }
+
+        let info = self.fetch(ecosystem, &key.1)?;
+        let mut cache = self.cache.write();
+        cache.retain(|_, (fetched_at, _)| fetched_at.elapsed() < CACHE_TTL);
+        cache.insert(key, (Instant::now(), info.clone()));
+        Ok(info)
+    }
+
+    fn search_blocking(&self, query: &str) -> Vec<SearchResult> {
+        let query = query.trim();
+        let Some((keyword, name)) = query.split_once(' ') else {
+            return vec![];
+        };
+        let Some(ecosystem) = Ecosystem::parse(&keyword.to_lowercase()) else {
+            return vec![];
+        };
+        let name = name.trim();
+        if name.len() < 2 || name.contains(char::is_whitespace) {
+            return vec![];
+        }
+
+        let info = match self.lookup(ecosystem, name) {
+            Ok(Some(info)) => info,
+            Ok(None) => return vec![],
+            Err(e) => {
+                eprintln!("{} lookup failed: {}", ecosystem.name(), e);
+                return vec![];
+            }
+        };
+
+        let mut subtitle = info.description.clone().unwrap_or_default();
+        if let (Some(downloads), Some(period)) = (info.downloads, &info.downloads_period) {
+            let stats = format!("{} downloads / {}", format_count(downloads), period);
+            subtitle = if subtitle.is_empty() {
+                stats
+            } else {
+                format!("{} • {}", subtitle, stats)
+            };
+        }
+
+        vec![
+            SearchResult {
+                id: format!("registry:open:{}", urlencoding::encode(&info.url)),
+                title: format!("{} {}", info.name, info.version),
+                subtitle: Some(format!("{} • {}", ecosystem.name(), subtitle)),
+                icon: ResultIcon::Emoji(ecosystem.icon().to_string()),
+                category: ResultCategory::Plugin,
+                score: 95.0,
+                aria_label: None,
+            },
+            SearchResult {
+                id: format!(
+                    "registry:copy:{}",
+                    urlencoding::encode(&info.dependency_line)
+                ),
+                title: format!("Copy {}", info.dependency_line),
+                subtitle: Some("Copy the dependency line".to_string()),
+                icon: ResultIcon::Emoji("📋".to_string()),
+                category: ResultCategory::Command,
+                score: 94.0,
+                aria_label: None,
+            },
+        ]
+    }
+}
+
+impl Default for RegistryProvider {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl SearchProvider for RegistryProvider {
+    fn id(&self) -> &str {
+        "registry"
+    }
+
+    async fn search(&self, query: &str, ctx: &SearchContext) -> Vec<SearchResult> {
+        ctx.run_blocking(|| self.search_blocking(query))
+    }
+
+    fn execute(&self, result_id: &str) -> Result<(), String> {
+        let action = result_id
+            .strip_prefix("registry:")
+            .ok_or("Invalid registry result")?;
+
+        if let Some(url) = action.strip_prefix("open:") {
+            let url = urlencoding::decode(url).map_err(|e| e.to_string())?;
+            return super::url::open_url(&url);
+        }
+        if let Some(line) = action.strip_prefix("copy:") {
+            let line = urlencoding::decode(line).map_err(|e| e.to_string())?;
+            return crate::clipboard::copy_text(&line);
+        }
+
+        Err(format!("Unknown registry action: {}", action))
+    }
+}
@@ -0,0 +1,192 @@
+use super::{
+    ResultAction, ResultCategory, ResultIcon, SearchContext, SearchProvider, SearchResult,
+};
+use crate::reminders::{self, Reminder, ReminderStore, SNOOZE_OPTIONS};
+use async_trait::async_trait;
+use chrono::{DateTime, Local, TimeZone, Utc};
+use std::sync::Arc;
+
+/// `remind me to <task> at 4pm` sets a reminder; `reminders [words]` lists
+/// pending ones, with snooze and done actions.
+pub struct ReminderProvider {
+    store: Arc<ReminderStore>,
+}
+
+impl ReminderProvider {
+    pub fn new(store: Arc<ReminderStore>) -> Self {
+        Self { store }
+    }
+
+    fn add_result(input: &str) -> SearchResult {
+        let Some((text, due)) = reminders::parse(input, Local::now()) else {
+            return SearchResult {
+                id: "reminders:hint".to_string(),
+                title: "Remind me to …".to_string(),
+                subtitle: Some(
+                    "Add a time: \"at 4pm\", \"in 20 minutes\", \"tomorrow\"".to_string(),
+                ),
+                icon: ResultIcon::Emoji("⏰".to_string()),
+                category: ResultCategory::System,
+                score: 80.0,
+                aria_label: None,
+            };
+        };
+        let due = due.with_timezone(&Utc);
+
+        SearchResult {
+            id: format!("reminders:add:{}:{}", due.timestamp(), text),
+            title: format!("Remind me to {}", text),
+            subtitle: Some(reminders::format_due(due, Utc::now())),
+            icon: ResultIcon::Emoji("⏰".to_string()),
+            category: ResultCategory::System,
+            score: 95.0,
+            aria_label: None,
+        }
+    }
+
+    fn reminder_result(reminder: &Reminder, score: f32) -> SearchResult {
+        let due = reminders::format_due(reminder.due_at, Utc::now());
+        SearchResult {
+            id: format!("reminders:{}", reminder.id),
+            title: reminder.text.clone(),
+            subtitle: Some(if reminder.fired {
+                format!("Due {} • snooze or mark done", due)
+            } else {
+                due
+            }),
+            icon: ResultIcon::Emoji(if reminder.fired { "🔔" } else { "⏰" }.to_string()),
+            category: ResultCategory::System,
+            score,
+            aria_label: None,
+        }
+    }
+
+    fn list_results(&self, filter: &str) -> Vec<SearchResult> {
+        let filter = filter.to_lowercase();
+        let results: Vec<SearchResult> = self
+            .store
+            .list()
+            .iter()
+            .filter(|r| r.text.to_lowercase().contains(&filter))
+            .enumerate()
+            .map(|(i, r)| Self::reminder_result(r, 90.0 - i as f32 * 0.1))
+            .collect();
+
+        if results.is_empty() {
+            return vec![SearchResult {
+                id: "reminders:none".to_string(),
+                title: "No reminders".to_string(),
+                subtitle: Some("Set one with \"remind me to … at 4pm\"".to_string()),
+                icon: ResultIcon::Emoji("⏰".to_string()),
+                category: ResultCategory::System,
+                score: 80.0,
+                aria_label: None,
+            }];
+        }
+        results
+    }
+
+    /// Text and due time from a `reminders:add:` id
+    fn parse_add_id(result_id: &str) -> Option<(&str, DateTime<Utc>)> {
+        let rest = result_id.strip_prefix("reminders:add:")?;
+        let (timestamp, text) = rest.split_once(':')?;
+        let due = Utc.timestamp_opt(timestamp.parse().ok()?, 0).single()?;
+        Some((text, due))
+    }
+
+    fn reminder_id(result_id: &str) -> Option<&str> {
+        result_id
+            .strip_prefix("reminders:")
+            .filter(|id| !id.starts_with("add:") && !["hint", "none"].contains(id))
+    }
+}
+
+#[async_trait]
+impl SearchProvider for ReminderProvider {
+    fn id(&self) -> &str {
+        "reminders"
+    }
+
+    async fn search(&self, query: &str, _ctx: &SearchContext) -> Vec<SearchResult> {
+        let query = query.trim();
+        let lower = query.to_lowercase();
+
+        if let Some(rest) = lower.strip_prefix("remind me") {
+            if rest.is_empty() || rest.starts_with(' ') {
+                // Keep the original casing of the task
+                let input = query.get("remind me".len()..).unwrap_or_default();
+                return vec![Self::add_result(input.trim())];
+            }
+        }
+        match lower.strip_prefix("reminders") {
+            Some(rest) if rest.is_empty() || rest.starts_with(' ') => {
+                self.list_results(rest.trim())
+            }
+            _ => vec![],
+        }
+    }
+
+    fn execute(&self, result_id: &str) -> Result<(), String> {
+        if let Some((text, due)) = Self::parse_add_id(result_id) {
+            self.store.add(text, due);
+            return Ok(());
+        }
+        match Self::reminder_id(result_id) {
+            // Enter on a due reminder dismisses it
+            Some(id) if self.store.list().iter().any(|r| r.id == id && r.fired) => {
+                self.store.complete(id)
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn actions(&self, result_id: &str) -> Vec<ResultAction> {
+        if Self::reminder_id(result_id).is_none() {
+            return Vec::new();
+        }
+        let mut actions: Vec<ResultAction> = SNOOZE_OPTIONS
+            .iter()
+            .map(|(minutes, label)| {
+                ResultAction::new(&format!("snooze-{}", minutes), &format!("Snooze {}", label))
+            })
+            .collect();
+        actions.push(ResultAction::new("done", "Mark Done"));
+        actions
+    }
+
+    fn execute_action(&self, result_id: &str, action_id: &str) -> Result<(), String> {
+        let id = Self::reminder_id(result_id).ok_or("Invalid reminder result")?;
+        if action_id == "done" {
+            return self.store.complete(id);
+        }
+        let minutes = action_id
+            .strip_prefix("snooze-")
+            .and_then(|m| m.parse().ok())
+            .ok_or_else(|| format!("Unknown action: {}", action_id))?;
+        self.store.snooze(id, minutes).map(|_| ())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_add_ids() {
+        let (text, due) =
+            ReminderProvider::parse_add_id("reminders:add:1700000000:call Sam: re budget").unwrap();
+        assert_eq!(text, "call Sam: re budget");
+        assert_eq!(due.timestamp(), 1700000000);
+        assert!(ReminderProvider::parse_add_id("reminders:abc").is_none());
+    }
+
+    #[test]
+    fn recognizes_reminder_ids() {
+        assert_eq!(
+            ReminderProvider::reminder_id("reminders:abc-123"),
+            Some("abc-123")
+        );
+        assert_eq!(ReminderProvider::reminder_id("reminders:none"), None);
+        assert_eq!(ReminderProvider::reminder_id("reminders:add:1:x"), None);
+    }
+}
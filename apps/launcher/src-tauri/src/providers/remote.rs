@@ -0,0 +1,553 @@
+use super::{ResultCategory, ResultIcon, SearchContext, SearchProvider, SearchResult};
+use async_trait::async_trait;
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+const SERVICE_NAME: &str = "launcher-remote";
+const LISTING_TTL: Duration = Duration::from_secs(60);
+/// How long a location that failed isn't tried again, so typing doesn't
+/// wait on it for every keystroke
+const FAILURE_TTL: Duration = Duration::from_secs(15);
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(3);
+const MAX_RECENT: usize = 50;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RemoteKind {
+    /// A mounted SMB/CIFS share (e.g. /Volumes/share or \\server\share)
+    Smb,
+    /// An SFTP server
+    Sftp,
+}
+
+/// A user-configured remote location
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RemoteLocation {
+    pub id: String,
+    pub name: String,
+    pub kind: RemoteKind,
+    /// Mount path for SMB, base directory on the server for SFTP
+    pub path: String,
+    #[serde(default)]
+    pub host: Option<String>,
+    #[serde(default)]
+    pub port: Option<u16>,
+    #[serde(default)]
+    pub username: Option<String>,
+}
+
+type Listing = Result<Vec<RemoteEntry>, String>;
+
+/// A file or folder inside a remote location
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RemoteEntry {
+    pub location_id: String,
+    pub path: String,
+    pub name: String,
+    pub is_dir: bool,
+    pub size: Option<u64>,
+}
+
+/// Result of a connection health check
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RemoteHealth {
+    pub location_id: String,
+    pub reachable: bool,
+    pub latency_ms: Option<u64>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct RemoteConfig {
+    #[serde(default)]
+    locations: Vec<RemoteLocation>,
+    /// Recently opened remote paths, most recent first
+    #[serde(default)]
+    recent: Vec<RemoteEntry>,
+}
+
+impl RemoteConfig {
+    fn config_path() -> PathBuf {
+        dirs::data_dir()
+            .unwrap_or_else(|| PathBuf::from("."))
+            .join("launcher")
+            .join("remote_locations.json")
+    }
+
+    fn load() -> Self {
+        let path = Self::config_path();
+        if path.exists() {
+            match std::fs::read_to_string(&path) {
+                Ok(contents) => match serde_json::from_str(&contents) {
+                    Ok(config) => return config,
+                    Err(e) => eprintln!("Failed to parse remote locations: {}", e),
+                },
+                Err(e) => eprintln!("Failed to read remote locations: {}", e),
+            }
+        }
+        Self::default()
+    }
+
+    fn save(&self) -> Result<(), String> {
+        let path = Self::config_path();
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+        let contents = serde_json::to_string_pretty(self).map_err(|e| e.to_string())?;
+        std::fs::write(&path, contents).map_err(|e| e.to_string())
+    }
+}
+
+/// Searches configured network shares and SFTP servers.
+///
+/// Nothing is indexed up front: directories are listed on demand when the
+/// query names a location (`share/sub/dir`) and kept for a short while.
+pub struct RemoteProvider {
+    config: RwLock<RemoteConfig>,
+    listings: RwLock<HashMap<(String, String), (Instant, Listing)>>,
+}
+
+impl RemoteProvider {
+    pub fn new() -> Self {
+        Self {
+            config: RwLock::new(RemoteConfig::load()),
+            listings: RwLock::new(HashMap::new()),
+        }
+    }
+
+    pub fn list_locations(&self) -> Vec<RemoteLocation> {
+        self.config.read().locations.clone()
+    }
+
+    /// Add or update a location. A password, if given, is stored in the keyring.
+    pub fn save_location(
+        &self,
+        location: RemoteLocation,
+        password: Option<String>,
+    ) -> Result<(), String> {
+        if location.kind == RemoteKind::Sftp && location.host.is_none() {
+            return Err("SFTP locations need a host".to_string());
+        }
+        // Result ids are `remote:{id}:{path}`, and paths may have colons
+        if location.id.is_empty() || location.id.contains(':') {
+            return Err("Location ids can't be empty or contain ':'".to_string());
+        }
+
+        if let Some(password) = password {
+            crate::secrets::set(SERVICE_NAME, &location.id, &password)
+                .map_err(|e| format!("Failed to store password: {}", e))?;
+        }
+
+        let mut config = self.config.write();
+        config.locations.retain(|l| l.id != location.id);
+        config.locations.push(location);
+        config.save()
+    }
+
+    pub fn remove_location(&self, id: &str) -> Result<(), String> {
+        crate::secrets::delete(SERVICE_NAME, id)?;
+        self.listings.write().retain(|(location_id, _), _| location_id != id);
+
+        let mut config = self.config.write();
+        config.locations.retain(|l| l.id != id);
+        config.recent.retain(|r| r.location_id != id);
+        config.save()
+    }
+
+    fn location(&self, id: &str) -> Option<RemoteLocation> {
+        self.config.read().locations.iter().find(|l| l.id == id).cloned()
+    }
+
+    /// Check that a location is reachable and readable
+    pub fn check_health(&self, id: &str) -> RemoteHealth {
+        let started = Instant::now();
+        let result = match self.location(id) {
+            Some(location) => Self::list_dir(&location, "").map(|_| ()),
+            None => Err(format!("Remote location not found: {}", id)),
+        };
+
+        RemoteHealth {
+            location_id: id.to_string(),
+            reachable: result.is_ok(),
+            latency_ms: result
+                .is_ok()
+                .then(|| started.elapsed().as_millis() as u64),
+            error: result.err(),
+        }
+    }
+
+    /// List a directory inside a location, using the cached listing, or
+    /// failure, if fresh
+    pub fn list(&self, location_id: &str, sub_path: &str) -> Listing {
+        let key = (location_id.to_string(), sub_path.to_string());
+        if let Some((fetched, listing)) = self.listings.read().get(&key) {
+            let ttl = if listing.is_ok() {
+                LISTING_TTL
+            } else {
+                FAILURE_TTL
+            };
+            if fetched.elapsed() < ttl {
+                return listing.clone();
+            }
+        }
+
+        let location = self
+            .location(location_id)
+            .ok_or_else(|| format!("Remote location not found: {}", location_id))?;
+        let listing = Self::list_dir(&location, sub_path);
+
+        self.listings
+            .write()
+            .insert(key, (Instant::now(), listing.clone()));
+        listing
+    }
+
+    fn list_dir(location: &RemoteLocation, sub_path: &str) -> Result<Vec<RemoteEntry>, String> {
+        match location.kind {
+            RemoteKind::Smb => Self::list_smb(location, sub_path),
+            RemoteKind::Sftp => Self::list_sftp(location, sub_path),
+        }
+    }
+
+    fn list_smb(location: &RemoteLocation, sub_path: &str) -> Result<Vec<RemoteEntry>, String> {
+        let dir = Path::new(&location.path).join(sub_path);
+
+        // A stale mount can block for a long time; don't hold up the search
+        let (tx, rx) = std::sync::mpsc::channel();
+        std::thread::spawn(move || {
+            let result = std::fs::read_dir(&dir).map(|entries| {
+                entries
+                    .flatten()
+                    .map(|e| {
+                        let metadata = e.metadata().ok();
+                        (
+                            e.file_name().to_string_lossy().to_string(),
+                            metadata.as_ref().map(|m| m.is_dir()).unwrap_or(false),
+                            metadata.map(|m| m.len()),
+                        )
+                    })
+                    .collect::<Vec<_>>()
+            });
+            let _ = tx.send(result);
+        });
+
+        let items = rx
+            .recv_timeout(CONNECT_TIMEOUT)
+            .map_err(|_| format!("{} did not respond", location.name))?
+            .map_err(|e| format!("Failed to list {}: {}", location.name, e))?;
+
+        Ok(items
+            .into_iter()
+            .map(|(name, is_dir, size)| RemoteEntry {
+                location_id: location.id.clone(),
+                path: join_remote(sub_path, &name),
+                name,
+                is_dir,
+                size: if is_dir { None } else { size },
+            })
+            .collect())
+    }
+
+    fn list_sftp(location: &RemoteLocation, sub_path: &str) -> Result<Vec<RemoteEntry>, String> {
+        let host = location.host.as_deref().ok_or("SFTP location has no host")?;
+        let port = location.port.unwrap_or(22);
+        let username = location
+            .username
+            .clone()
+            .unwrap_or_else(whoami::username);
+
+        let addr = std::net::ToSocketAddrs::to_socket_addrs(&(host, port))
+            .map_err(|e| format!("Failed to resolve {}: {}", host, e))?
+            .next()
+            .ok_or_else(|| format!("Failed to resolve {}", host))?;
+        let tcp = std::net::TcpStream::connect_timeout(&addr, CONNECT_TIMEOUT)
+            .map_err(|e| format!("Failed to connect to {}: {}", host, e))?;
+
+        let mut session = ssh2::Session::new().map_err(|e| e.to_string())?;
+        session.set_timeout(CONNECT_TIMEOUT.as_millis() as u32);
+        session.set_tcp_stream(tcp);
+        session
+            .handshake()
+            .map_err(|e| format!("SSH handshake failed: {}", e))?;
+        // Before sending a password to it
+        Self::check_host_key(&session, host, port)?;
+
+        let password = crate::secrets::get(SERVICE_NAME, &location.id);
+        match password {
+            Some(password) => session.userauth_password(&username, &password),
+            None => session.userauth_agent(&username),
+        }
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+        let sftp = session.sftp().map_err(|e| e.to_string())?;
+        let dir = Path::new(&location.path).join(sub_path);
+        let entries = sftp
+            .readdir(&dir)
+            .map_err(|e| format!("Failed to list {}: {}", dir.display(), e))?;
+
+        Ok(entries
+            .into_iter()
+            .filter_map(|(path, stat)| {
+                let name = path.file_name()?.to_string_lossy().to_string();
+                Some(RemoteEntry {
+                    location_id: location.id.clone(),
+                    path: join_remote(sub_path, &name),
+                    is_dir: stat.is_dir(),
+                    size: if stat.is_dir() { None } else { stat.size },
+                    name,
+                })
+            })
+            .collect())
+    }
+
+    /// Refuse servers whose key isn't in `~/.ssh/known_hosts`, or differs
+    /// from the one there
+    fn check_host_key(session: &ssh2::Session, host: &str, port: u16) -> Result<(), String> {
+        let (key, _) = session.host_key().ok_or("The server sent no host key")?;
+        let mut known_hosts = session.known_hosts().map_err(|e| e.to_string())?;
+        if let Some(path) = dirs::home_dir().map(|home| home.join(".ssh").join("known_hosts")) {
+            // A missing file leaves every host unknown
+            let _ = known_hosts.read_file(&path, ssh2::KnownHostFileKind::OpenSSH);
+        }
+
+        match known_hosts.check_port(host, port, key) {
+            ssh2::CheckResult::Match => Ok(()),
+            ssh2::CheckResult::NotFound => Err(format!(
+                "{} isn't a known host; connect to it once with ssh to trust its key",
+                host
+            )),
+            ssh2::CheckResult::Mismatch => Err(format!(
+                "The host key for {} has changed; refusing to connect",
+                host
+            )),
+            ssh2::CheckResult::Failure => Err(format!("Couldn't check the host key for {}", host)),
+        }
+    }
+
+    fn record_recent(&self, entry: RemoteEntry) {
+        let mut config = self.config.write();
+        config
+            .recent
+            .retain(|r| !(r.location_id == entry.location_id && r.path == entry.path));
+        config.recent.insert(0, entry);
+        config.recent.truncate(MAX_RECENT);
+        let _ = config.save();
+    }
+
+    /// Local path or URL the OS can open for an entry
+    fn open_target(location: &RemoteLocation, path: &str) -> String {
+        match location.kind {
+            RemoteKind::Smb => Path::new(&location.path)
+                .join(path)
+                .to_string_lossy()
+                .to_string(),
+            RemoteKind::Sftp => {
+                let host = location.host.as_deref().unwrap_or_default();
+                let user = location
+                    .username
+                    .as_ref()
+                    .map(|u| format!("{}@", u))
+                    .unwrap_or_default();
+                let port = location
+                    .port
+                    .filter(|p| *p != 22)
+                    .map(|p| format!(":{}", p))
+                    .unwrap_or_default();
+                let full = Path::new(&location.path).join(path);
+                format!(
+                    "sftp://{}{}{}/{}",
+                    user,
+                    host,
+                    port,
+                    full.to_string_lossy().trim_start_matches('/')
+                )
+            }
+        }
+    }
+
+    fn entry_result(&self, location: &RemoteLocation, entry: &RemoteEntry, score: f32) -> SearchResult {
+        SearchResult {
+            id: format!("remote:{}:{}", location.id, entry.path),
+            title: entry.name.clone(),
+            subtitle: Some(format!("{} • {}", location.name, entry.path)),
+            icon: ResultIcon::Emoji(if entry.is_dir { "📁" } else { "📄" }.to_string()),
+            category: ResultCategory::File,
+            score,
+            aria_label: None,
+        }
+    }
+
+    fn search_blocking(&self, query: &str) -> Vec<SearchResult> {
+        let query = query.trim();
+        if query.len() < 2 {
+            return vec![];
+        }
+        let query_lower = query.to_lowercase();
+        let locations = self.list_locations();
+
+        // "share/sub/dir/fil" lists share/sub/dir and filters by "fil"
+        if let Some((head, rest)) = query.split_once('/') {
+            if let Some(location) = locations
+                .iter()
+                .find(|l| l.name.eq_ignore_ascii_case(head))
+            {
+                let (dir, filter) = rest.rsplit_once('/').unwrap_or(("", rest));
+                let filter = filter.to_lowercase();
+
+                return match self.list(&location.id, dir) {
+                    Ok(entries) => entries
+                        .iter()
+                        .filter(|e| filter.is_empty() || e.name.to_lowercase().contains(&filter))
+                        .take(20)
+                        .enumerate()
+                        .map(|(idx, e)| self.entry_result(location, e, 70.0 - idx as f32 * 0.5))
+                        .collect(),
+                    Err(e) => vec![SearchResult {
+                        id: format!("remote:{}:", location.id),
+                        title: format!("{} is unavailable", location.name),
+                        subtitle: Some(e),
+                        icon: ResultIcon::Emoji("⚠️".to_string()),
+                        category: ResultCategory::File,
+                        score: 60.0,
+                        aria_label: None,
+                    }],
+                };
+            }
+        }
+
+        let mut results: Vec<SearchResult> = locations
+            .iter()
+            .filter(|l| l.name.to_lowercase().contains(&query_lower))
+            .map(|l| SearchResult {
+                id: format!("remote:{}:", l.id),
+                title: l.name.clone(),
+                subtitle: Some(match l.kind {
+                    RemoteKind::Smb => format!("Network share • {}", l.path),
+                    RemoteKind::Sftp => format!(
+                        "SFTP • {}{}",
+                        l.host.as_deref().unwrap_or_default(),
+                        l.path
+                    ),
+                }),
+                icon: ResultIcon::Emoji("🌐".to_string()),
+                category: ResultCategory::File,
+                score: 65.0,
+                aria_label: None,
+            })
+            .collect();
+
+        let recent = self.config.read().recent.clone();
+        results.extend(
+            recent
+                .iter()
+                .filter(|r| r.name.to_lowercase().contains(&query_lower))
+                .filter_map(|r| {
+                    let location = locations.iter().find(|l| l.id == r.location_id)?;
+                    Some(self.entry_result(location, r, 55.0))
+                })
+                .take(10),
+        );
+
+        results
+    }
+}
+
+/// Location id and path from `remote:{id}:{path}`; the id has no colons,
+/// the path may
+fn parse_result_id(result_id: &str) -> Option<(&str, &str)> {
+    result_id.strip_prefix("remote:")?.split_once(':')
+}
+
+/// Join path segments with '/', which both SMB mounts and SFTP accept
+fn join_remote(base: &str, name: &str) -> String {
+    let base = base.trim_end_matches('/');
+    if base.is_empty() {
+        name.to_string()
+    } else {
+        format!("{}/{}", base, name)
+    }
+}
+
+#[async_trait]
+impl SearchProvider for RemoteProvider {
+    fn id(&self) -> &str {
+        "remote"
+    }
+
+    async fn search(&self, query: &str, ctx: &SearchContext) -> Vec<SearchResult> {
+        ctx.run_blocking(|| self.search_blocking(query))
+    }
+
+    fn execute(&self, result_id: &str) -> Result<(), String> {
+        let (location_id, path) = parse_result_id(result_id).ok_or("Invalid remote result")?;
+        let location = self
+            .location(location_id)
+            .ok_or_else(|| format!("Remote location not found: {}", location_id))?;
+
+        if !path.is_empty() {
+            let cached = self
+                .listings
+                .read()
+                .values()
+                .filter_map(|(_, listing)| listing.as_ref().ok())
+                .flatten()
+                .find(|e| e.location_id == location_id && e.path == path)
+                .cloned();
+            let entry = cached.unwrap_or_else(|| RemoteEntry {
+                location_id: location_id.to_string(),
+                path: path.to_string(),
+                name: path.rsplit('/').next().unwrap_or(path).to_string(),
+                is_dir: false,
+                size: None,
+            });
+            self.record_recent(entry);
+        }
+
+        let target = Self::open_target(&location, path);
+
+        #[cfg(target_os = "linux")]
+        {
+            std::process::Command::new("xdg-open")
+                .arg(&target)
+                .spawn()
+                .map_err(|e| e.to_string())?;
+        }
+
+        #[cfg(target_os = "macos")]
+        {
+            std::process::Command::new("open")
+                .arg(&target)
+                .spawn()
+                .map_err(|e| e.to_string())?;
+        }
+
+        #[cfg(target_os = "windows")]
+        {
+            std::process::Command::new("explorer")
+                .arg(&target)
+                .spawn()
+                .map_err(|e| e.to_string())?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_result_ids_with_colons_in_the_path() {
+        assert_eq!(
+            parse_result_id("remote:nas:photos/12:30 party.jpg"),
+            Some(("nas", "photos/12:30 party.jpg"))
+        );
+        assert_eq!(parse_result_id("remote:nas:"), Some(("nas", "")));
+        assert_eq!(parse_result_id("files:nas"), None);
+        assert_eq!(join_remote("photos/", "a.jpg"), "photos/a.jpg");
+    }
+}
@@ -0,0 +1,154 @@
+use super::files::FileProvider;
+use super::{ResultCategory, ResultIcon, SearchContext, SearchProvider, SearchResult};
+use crate::file_ops::{self, RenamePreview, RenameRule};
+use async_trait::async_trait;
+use parking_lot::RwLock;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Id of the file result action that adds it to or takes it out of the
+/// selection
+pub const SELECT_ACTION: &str = "select-for-rename";
+
+const HELP: &str = "Type a pattern like {name}-{n}.{ext} or s/find/replace/";
+
+/// Bulk rename: `rename: PATTERN` previews new names for the file results
+/// selected with their "Select for bulk rename" action, and renames them
+/// once the "Rename" result is run.
+pub struct RenameProvider {
+    file_provider: Arc<FileProvider>,
+    selection: RwLock<Vec<PathBuf>>,
+}
+
+impl RenameProvider {
+    pub fn new(file_provider: Arc<FileProvider>) -> Self {
+        Self {
+            file_provider,
+            selection: RwLock::new(Vec::new()),
+        }
+    }
+
+    pub fn is_selected(&self, path: &Path) -> bool {
+        self.selection.read().iter().any(|p| p == path)
+    }
+
+    /// Add a file to the selection, or take it out if it's already there
+    pub fn toggle(&self, path: &Path) {
+        let mut selection = self.selection.write();
+        match selection.iter().position(|p| p == path) {
+            Some(idx) => {
+                selection.remove(idx);
+            }
+            None => selection.push(path.to_path_buf()),
+        }
+    }
+
+    fn message(id: &str, title: String, subtitle: &str) -> SearchResult {
+        SearchResult {
+            id: format!("rename:{}", id),
+            title,
+            subtitle: Some(subtitle.to_string()),
+            icon: ResultIcon::Emoji("✏️".to_string()),
+            category: ResultCategory::Command,
+            score: 95.0,
+            aria_label: None,
+        }
+    }
+
+    fn preview_result(idx: usize, preview: &RenamePreview) -> SearchResult {
+        let name = |path: &str| {
+            Path::new(path)
+                .file_name()
+                .map(|n| n.to_string_lossy().into_owned())
+                .unwrap_or_else(|| path.to_string())
+        };
+        let subtitle = match &preview.conflict {
+            Some(conflict) => format!("⚠️ {}", conflict),
+            None => preview.from.clone(),
+        };
+        SearchResult {
+            id: format!("rename:preview:{}", idx),
+            title: format!("{} → {}", name(&preview.from), name(&preview.to)),
+            subtitle: Some(subtitle),
+            icon: ResultIcon::Emoji("📄".to_string()),
+            category: ResultCategory::File,
+            score: 90.0 - idx as f32 * 0.01,
+            aria_label: None,
+        }
+    }
+}
+
+#[async_trait]
+impl SearchProvider for RenameProvider {
+    fn id(&self) -> &str {
+        "rename"
+    }
+
+    async fn search(&self, query: &str, _ctx: &SearchContext) -> Vec<SearchResult> {
+        let Some(pattern) = query.trim_start().strip_prefix("rename:") else {
+            return vec![];
+        };
+        let pattern = pattern.trim();
+
+        let selection = self.selection.read().clone();
+        if selection.is_empty() {
+            return vec![Self::message(
+                "help",
+                "No files selected".to_string(),
+                "Use \"Select for bulk rename\" on file results first",
+            )];
+        }
+        if pattern.is_empty() {
+            return vec![Self::message(
+                "help",
+                format!("{} files selected", selection.len()),
+                HELP,
+            )];
+        }
+        let rule = match RenameRule::parse(pattern) {
+            Ok(rule) => rule,
+            Err(e) => return vec![Self::message("help", e, HELP)],
+        };
+
+        let plan = file_ops::preview_rename(&selection, &rule);
+        let conflicts = plan.iter().filter(|p| p.conflict.is_some()).count();
+        let first = if conflicts > 0 {
+            Self::message(
+                "help",
+                format!("Can't rename: {} conflicts", conflicts),
+                "Change the pattern or the selection",
+            )
+        } else {
+            Self::message(
+                &format!("apply:{}", urlencoding::encode(pattern)),
+                format!("Rename {} files", plan.len()),
+                "Nothing changes until you run this",
+            )
+        };
+
+        std::iter::once(first)
+            .chain(
+                plan.iter()
+                    .enumerate()
+                    .map(|(idx, p)| Self::preview_result(idx, p)),
+            )
+            .collect()
+    }
+
+    fn execute(&self, result_id: &str) -> Result<(), String> {
+        let Some(pattern) = result_id.strip_prefix("rename:apply:") else {
+            return Ok(());
+        };
+        let pattern = urlencoding::decode(pattern).map_err(|e| e.to_string())?;
+        let rule = RenameRule::parse(&pattern)?;
+
+        let selection = self.selection.read().clone();
+        let renamed = file_ops::apply_rename(&selection, &rule)?;
+        for entry in &renamed {
+            let _ = self.file_provider.update_file(Path::new(&entry.from));
+            let _ = self.file_provider.update_file(Path::new(&entry.to));
+        }
+        self.selection.write().clear();
+        Ok(())
+    }
+}
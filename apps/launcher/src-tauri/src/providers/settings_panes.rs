@@ -0,0 +1,412 @@
+use super::{ResultCategory, ResultIcon, SearchContext, SearchProvider, SearchResult};
+use async_trait::async_trait;
+use std::process::Command;
+
+/// Where each platform keeps a settings page: a System Settings URL on
+/// macOS, an `ms-settings:` URI on Windows, a `gnome-control-center` panel
+/// and a KDE System Settings module. `None` where there's no such page.
+struct SettingsPane {
+    id: &'static str,
+    name: &'static str,
+    icon: &'static str,
+    keywords: &'static [&'static str],
+    macos: Option<&'static str>,
+    windows: Option<&'static str>,
+    gnome: Option<&'static str>,
+    kde: Option<&'static str>,
+}
+
+const PANES: &[SettingsPane] = &[
+    SettingsPane {
+        id: "bluetooth",
+        name: "Bluetooth",
+        icon: "🔵",
+        keywords: &["bluetooth", "pair", "devices", "headphones"],
+        macos: Some("com.apple.BluetoothSettings"),
+        windows: Some("bluetooth"),
+        gnome: Some("bluetooth"),
+        kde: Some("kcm_bluetooth"),
+    },
+    SettingsPane {
+        id: "wifi",
+        name: "Wi-Fi",
+        icon: "📶",
+        keywords: &["wifi", "wi-fi", "wireless", "wlan"],
+        macos: Some("com.apple.wifi-settings-extension"),
+        windows: Some("network-wifi"),
+        gnome: Some("wifi"),
+        kde: Some("kcm_networkmanagement"),
+    },
+    SettingsPane {
+        id: "network",
+        name: "Network",
+        icon: "🌐",
+        keywords: &["network", "ethernet", "proxy", "internet"],
+        macos: Some("com.apple.Network-Settings.extension"),
+        windows: Some("network"),
+        gnome: Some("network"),
+        kde: Some("kcm_networkmanagement"),
+    },
+    SettingsPane {
+        id: "display",
+        name: "Displays",
+        icon: "🖥️",
+        keywords: &["display", "monitor", "screen", "resolution", "night light"],
+        macos: Some("com.apple.Displays-Settings.extension"),
+        windows: Some("display"),
+        gnome: Some("display"),
+        kde: Some("kcm_kscreen"),
+    },
+    SettingsPane {
+        id: "sound",
+        name: "Sound",
+        icon: "🔊",
+        keywords: &["sound", "audio", "volume", "speakers", "microphone"],
+        macos: Some("com.apple.Sound-Settings.extension"),
+        windows: Some("sound"),
+        gnome: Some("sound"),
+        kde: Some("kcm_pulseaudio"),
+    },
+    SettingsPane {
+        id: "notifications",
+        name: "Notifications",
+        icon: "🔔",
+        keywords: &["notifications", "alerts", "do not disturb"],
+        macos: Some("com.apple.Notifications-Settings.extension"),
+        windows: Some("notifications"),
+        gnome: Some("notifications"),
+        kde: Some("kcm_notifications"),
+    },
+    SettingsPane {
+        id: "keyboard",
+        name: "Keyboard",
+        icon: "⌨️",
+        keywords: &["keyboard", "input", "shortcuts", "layout"],
+        macos: Some("com.apple.Keyboard-Settings.extension"),
+        windows: Some("typing"),
+        gnome: Some("keyboard"),
+        kde: Some("kcm_keyboard"),
+    },
+    SettingsPane {
+        id: "mouse",
+        name: "Mouse & Trackpad",
+        icon: "🖱️",
+        keywords: &["mouse", "trackpad", "touchpad", "pointer", "scrolling"],
+        macos: Some("com.apple.Trackpad-Settings.extension"),
+        windows: Some("mousetouchpad"),
+        gnome: Some("mouse"),
+        kde: Some("kcm_mouse"),
+    },
+    SettingsPane {
+        id: "power",
+        name: "Power & Battery",
+        icon: "🔋",
+        keywords: &["power", "battery", "energy", "sleep"],
+        macos: Some("com.apple.Battery-Settings.extension"),
+        windows: Some("powersleep"),
+        gnome: Some("power"),
+        kde: Some("kcm_powerdevilprofilesconfig"),
+    },
+    SettingsPane {
+        id: "privacy",
+        name: "Privacy & Security",
+        icon: "🛡️",
+        keywords: &["privacy", "security", "permissions", "location"],
+        macos: Some("com.apple.settings.PrivacySecurity.extension"),
+        windows: Some("privacy"),
+        gnome: Some("privacy"),
+        kde: None,
+    },
+    SettingsPane {
+        id: "datetime",
+        name: "Date & Time",
+        icon: "🕒",
+        keywords: &["date", "time", "clock", "timezone", "time zone"],
+        macos: Some("com.apple.Date-Time-Settings.extension"),
+        windows: Some("dateandtime"),
+        gnome: Some("datetime"),
+        kde: Some("kcm_clock"),
+    },
+    SettingsPane {
+        id: "region",
+        name: "Language & Region",
+        icon: "🗺️",
+        keywords: &["language", "region", "locale", "formats"],
+        macos: Some("com.apple.Localization-Settings.extension"),
+        windows: Some("regionlanguage"),
+        gnome: Some("region"),
+        kde: Some("kcm_regionandlang"),
+    },
+    SettingsPane {
+        id: "users",
+        name: "Users & Accounts",
+        icon: "👤",
+        keywords: &["users", "accounts", "password", "login"],
+        macos: Some("com.apple.Users-Groups-Settings.extension"),
+        windows: Some("yourinfo"),
+        gnome: Some("user-accounts"),
+        kde: Some("kcm_users"),
+    },
+    SettingsPane {
+        id: "printers",
+        name: "Printers & Scanners",
+        icon: "🖨️",
+        keywords: &["printers", "printing", "scanners"],
+        macos: Some("com.apple.Print-Scan-Settings.extension"),
+        windows: Some("printers"),
+        gnome: Some("printers"),
+        kde: Some("kcm_printer_manager"),
+    },
+    SettingsPane {
+        id: "default-apps",
+        name: "Default Apps",
+        icon: "🧩",
+        keywords: &["default apps", "default browser", "file associations"],
+        macos: Some("com.apple.Desktop-Settings.extension"),
+        windows: Some("defaultapps"),
+        gnome: Some("default-apps"),
+        kde: Some("kcm_componentchooser"),
+    },
+    SettingsPane {
+        id: "appearance",
+        name: "Appearance",
+        icon: "🎨",
+        keywords: &[
+            "appearance",
+            "dark mode",
+            "theme",
+            "wallpaper",
+            "background",
+        ],
+        macos: Some("com.apple.Appearance-Settings.extension"),
+        windows: Some("colors"),
+        gnome: Some("background"),
+        kde: Some("kcm_lookandfeel"),
+    },
+    SettingsPane {
+        id: "accessibility",
+        name: "Accessibility",
+        icon: "♿",
+        keywords: &["accessibility", "a11y", "zoom", "contrast", "screen reader"],
+        macos: Some("com.apple.Accessibility-Settings.extension"),
+        windows: Some("easeofaccess"),
+        gnome: Some("universal-access"),
+        kde: Some("kcm_access"),
+    },
+    SettingsPane {
+        id: "updates",
+        name: "Software Update",
+        icon: "⬆️",
+        keywords: &["software update", "updates", "upgrade"],
+        macos: Some("com.apple.Software-Update-Settings.extension"),
+        windows: Some("windowsupdate"),
+        gnome: None,
+        kde: None,
+    },
+    SettingsPane {
+        id: "storage",
+        name: "Storage",
+        icon: "💾",
+        keywords: &["storage", "disk space"],
+        macos: Some("com.apple.settings.Storage"),
+        windows: Some("storagesense"),
+        gnome: None,
+        kde: None,
+    },
+];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Desktop {
+    MacOS,
+    Windows,
+    Gnome,
+    Kde,
+    /// A Linux desktop without a settings app we know how to open
+    Other,
+}
+
+impl Desktop {
+    fn current() -> Self {
+        if cfg!(target_os = "macos") {
+            return Desktop::MacOS;
+        }
+        if cfg!(target_os = "windows") {
+            return Desktop::Windows;
+        }
+        // Colon separated, e.g. `ubuntu:GNOME`
+        let desktop = std::env::var("XDG_CURRENT_DESKTOP")
+            .unwrap_or_default()
+            .to_lowercase();
+        if desktop.contains("kde") {
+            Desktop::Kde
+        } else if ["gnome", "unity", "budgie"]
+            .iter()
+            .any(|d| desktop.contains(d))
+        {
+            Desktop::Gnome
+        } else {
+            Desktop::Other
+        }
+    }
+
+    fn settings_app(self) -> &'static str {
+        match self {
+            Desktop::MacOS => "System Settings",
+            Desktop::Windows => "Settings",
+            Desktop::Gnome => "GNOME Settings",
+            Desktop::Kde => "KDE System Settings",
+            Desktop::Other => "Settings",
+        }
+    }
+
+    /// The pane's page on this desktop
+    fn page(self, pane: &SettingsPane) -> Option<&'static str> {
+        match self {
+            Desktop::MacOS => pane.macos,
+            Desktop::Windows => pane.windows,
+            Desktop::Gnome => pane.gnome,
+            Desktop::Kde => pane.kde,
+            Desktop::Other => None,
+        }
+    }
+
+    fn open(self, page: &str) -> Result<(), String> {
+        let spawned = match self {
+            Desktop::MacOS => Command::new("open")
+                .arg(format!("x-apple.systempreferences:{}", page))
+                .spawn(),
+            Desktop::Windows => Command::new("explorer")
+                .arg(format!("ms-settings:{}", page))
+                .spawn(),
+            Desktop::Gnome => Command::new("gnome-control-center").arg(page).spawn(),
+            // Plasma 5 named the app systemsettings5
+            Desktop::Kde => Command::new("systemsettings")
+                .arg(page)
+                .spawn()
+                .or_else(|_| Command::new("systemsettings5").arg(page).spawn()),
+            Desktop::Other => return Err("No settings app found".to_string()),
+        };
+        spawned
+            .map(|_| ())
+            .map_err(|e| format!("Failed to open settings: {}", e))
+    }
+}
+
+fn score(pane: &SettingsPane, query: &str) -> Option<f32> {
+    let name = pane.name.to_lowercase();
+    if name == query {
+        return Some(95.0);
+    }
+    if name.starts_with(query) {
+        return Some(88.0);
+    }
+    if pane.keywords.contains(&query) {
+        return Some(85.0);
+    }
+    if pane.keywords.iter().any(|k| k.starts_with(query)) {
+        return Some(75.0);
+    }
+    if query.len() >= 4 && name.contains(query) {
+        return Some(70.0);
+    }
+    None
+}
+
+/// Open the OS settings page for what was typed, e.g. "bluetooth" or
+/// "display", on macOS, Windows, GNOME and KDE
+pub struct SettingsPaneProvider {
+    desktop: Desktop,
+}
+
+impl SettingsPaneProvider {
+    pub fn new() -> Self {
+        Self {
+            desktop: Desktop::current(),
+        }
+    }
+
+    fn matches(&self, query: &str) -> Vec<(f32, &'static SettingsPane)> {
+        let mut matches: Vec<_> = PANES
+            .iter()
+            .filter(|pane| self.desktop.page(pane).is_some())
+            .filter_map(|pane| Some((score(pane, query)?, pane)))
+            .collect();
+        // Stable, so equal matches keep the table's order
+        matches.sort_by(|a, b| b.0.total_cmp(&a.0));
+        matches
+    }
+}
+
+impl Default for SettingsPaneProvider {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl SearchProvider for SettingsPaneProvider {
+    fn id(&self) -> &str {
+        "settings"
+    }
+
+    async fn search(&self, query: &str, _ctx: &SearchContext) -> Vec<SearchResult> {
+        let query = query.trim().to_lowercase();
+        if query.len() < 3 {
+            return vec![];
+        }
+
+        self.matches(&query)
+            .into_iter()
+            .take(3)
+            .map(|(score, pane)| SearchResult {
+                id: format!("settings:{}", pane.id),
+                title: format!("{} Settings", pane.name),
+                subtitle: Some(format!("Open in {}", self.desktop.settings_app())),
+                icon: ResultIcon::Emoji(pane.icon.to_string()),
+                category: ResultCategory::System,
+                score,
+                aria_label: None,
+            })
+            .collect()
+    }
+
+    fn execute(&self, result_id: &str) -> Result<(), String> {
+        let id = result_id
+            .strip_prefix("settings:")
+            .ok_or("Invalid settings result")?;
+        let page = PANES
+            .iter()
+            .find(|pane| pane.id == id)
+            .and_then(|pane| self.desktop.page(pane))
+            .ok_or("There's no such settings page here")?;
+        self.desktop.open(page)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_panes_the_desktop_has() {
+        let gnome = SettingsPaneProvider {
+            desktop: Desktop::Gnome,
+        };
+        let ids = |provider: &SettingsPaneProvider, query: &str| -> Vec<&str> {
+            provider.matches(query).iter().map(|(_, p)| p.id).collect()
+        };
+        assert_eq!(ids(&gnome, "bluetooth")[0], "bluetooth");
+        assert_eq!(ids(&gnome, "touchpad"), vec!["mouse"]);
+        assert_eq!(ids(&gnome, "dark mode"), vec!["appearance"]);
+        // GNOME has no update pane; Windows does
+        assert!(ids(&gnome, "updates").is_empty());
+        let windows = SettingsPaneProvider {
+            desktop: Desktop::Windows,
+        };
+        assert_eq!(ids(&windows, "updates"), vec!["updates"]);
+        let other = SettingsPaneProvider {
+            desktop: Desktop::Other,
+        };
+        assert!(ids(&other, "sound").is_empty());
+    }
+}
@@ -1,35 +1,14 @@
-use super::{ResultCategory, ResultIcon, SearchProvider, SearchResult};
+use super::{ResultCategory, ResultIcon, SearchContext, SearchProvider, SearchResult};
 use crate::oauth::OAuthFlow;
-use parking_lot::RwLock;
+use crate::search_cache::{CachePolicy, ResultUrls};
+use async_trait::async_trait;
 use serde::Deserialize;
 use std::collections::HashMap;
 use std::sync::Arc;
 
 pub struct SlackProvider {
     oauth_flow: Arc<OAuthFlow>,
-    cache: RwLock<SearchCache>,
-}
-
-struct SearchCache {
-    query: String,
-    results: Vec<SearchResult>,
-    urls: HashMap<String, String>,
-    timestamp: std::time::Instant,
-}
-
-impl SearchCache {
-    fn new() -> Self {
-        Self {
-            query: String::new(),
-            results: Vec::new(),
-            urls: HashMap::new(),
-            timestamp: std::time::Instant::now(),
-        }
-    }
-
-    fn is_valid(&self, query: &str) -> bool {
-        self.query == query && self.timestamp.elapsed().as_secs() < 60
-    }
+    urls: ResultUrls,
 }
 
 #[derive(Debug, Deserialize)]
@@ -59,11 +38,17 @@ struct SlackChannel {
     name: Option<String>,
 }
 
+#[derive(Debug, Deserialize)]
+struct SlackApiResponse {
+    ok: bool,
+    error: Option<String>,
+}
+
 impl SlackProvider {
     pub fn new(oauth_flow: Arc<OAuthFlow>) -> Self {
         Self {
             oauth_flow,
-            cache: RwLock::new(SearchCache::new()),
+            urls: ResultUrls::new(),
         }
     }
 
@@ -71,6 +56,35 @@ impl SlackProvider {
         self.oauth_flow.get_token_if_valid("slack")
     }
 
+    /// Set the user's status. Slack clears it at `expiration` (Unix time, 0
+    /// for never); an empty text and emoji clear it right away.
+    pub fn set_status(&self, text: &str, emoji: &str, expiration: i64) -> Result<(), String> {
+        let token = self.get_token_sync().ok_or("Slack is not connected")?;
+        let response: SlackApiResponse = reqwest::blocking::Client::new()
+            .post("https://slack.com/api/users.profile.set")
+            .header("Authorization", format!("Bearer {}", token))
+            .json(&serde_json::json!({
+                "profile": {
+                    "status_text": text,
+                    "status_emoji": emoji,
+                    "status_expiration": expiration,
+                }
+            }))
+            .send()
+            .map_err(|e| format!("Slack request failed: {}", e))?
+            .json()
+            .map_err(|e| format!("Failed to parse Slack response: {}", e))?;
+
+        if response.ok {
+            Ok(())
+        } else {
+            Err(format!(
+                "Slack error: {}",
+                response.error.unwrap_or_else(|| "unknown".to_string())
+            ))
+        }
+    }
+
     fn truncate_text(text: &str, max_len: usize) -> String {
         if text.len() <= max_len {
             text.to_string()
@@ -84,14 +98,6 @@ impl SlackProvider {
             return Vec::new();
         }
 
-        // Check cache first
-        {
-            let cache = self.cache.read();
-            if cache.is_valid(query) {
-                return cache.results.clone();
-            }
-        }
-
         // Check if connected to Slack
         if !self.oauth_flow.is_connected("slack") {
             return vec![SearchResult {
@@ -101,6 +107,7 @@ impl SlackProvider {
                 icon: ResultIcon::Emoji("🔗".to_string()),
                 category: ResultCategory::Plugin,
                 score: 50.0,
+                aria_label: None,
             }];
         }
 
@@ -145,6 +152,7 @@ impl SlackProvider {
                                 icon: ResultIcon::Emoji("💬".to_string()),
                                 category: ResultCategory::Plugin,
                                 score: 100.0 - (i as f32 * 5.0),
+                                aria_label: None,
                             }
                         })
                         .collect();
@@ -169,30 +177,36 @@ impl SlackProvider {
             }
         };
 
-        // Update cache
-        {
-            let mut cache = self.cache.write();
-            cache.query = query.to_string();
-            cache.results = results.clone();
-            cache.urls = urls;
-            cache.timestamp = std::time::Instant::now();
-        }
+        self.urls.remember(urls);
 
         results
     }
+
+    fn search_blocking(&self, query: &str) -> Vec<SearchResult> {
+        // Only search Slack if query starts with "sl " prefix
+        if let Some(slack_query) = query.strip_prefix("sl ") {
+            self.search_slack(slack_query.trim())
+        } else {
+            Vec::new()
+        }
+    }
 }
 
+#[async_trait]
 impl SearchProvider for SlackProvider {
     fn id(&self) -> &str {
         "slack"
     }
 
-    fn search(&self, query: &str) -> Vec<SearchResult> {
-        // Only search Slack if query starts with "sl " prefix
-        if let Some(slack_query) = query.strip_prefix("sl ") {
-            self.search_slack(slack_query.trim())
+    async fn search(&self, query: &str, ctx: &SearchContext) -> Vec<SearchResult> {
+        ctx.run_blocking(|| self.search_blocking(query))
+    }
+
+    fn cache_policy(&self, query: &str) -> CachePolicy {
+        if query.starts_with("sl ") && self.oauth_flow.is_connected("slack") {
+            CachePolicy::Remote
         } else {
-            Vec::new()
+            CachePolicy::Never
         }
     }
 
@@ -202,8 +216,7 @@ impl SearchProvider for SlackProvider {
         }
 
         if result_id.starts_with("slack:msg:") {
-            let cache = self.cache.read();
-            if let Some(url) = cache.urls.get(result_id) {
+            if let Some(url) = self.urls.get(result_id).as_deref() {
                 #[cfg(target_os = "linux")]
                 {
                     std::process::Command::new("xdg-open")
@@ -0,0 +1,328 @@
+use super::{ResultCategory, ResultIcon, SearchContext, SearchProvider, SearchResult};
+use crate::search_cache::CachePolicy;
+use crate::vault::SecretVault;
+use async_trait::async_trait;
+use parking_lot::RwLock;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::io::Read;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+const API_BASE: &str = "https://api.stackexchange.com/2.3";
+/// Optional app key from stackapps.com; raises the daily quota
+const VAULT_KEY: &str = "stackexchange_key";
+/// Answers to old questions barely change, and the API quota is small
+const CACHE_TTL: Duration = Duration::from_secs(6 * 60 * 60);
+/// How long to stay quiet once the daily quota is used up
+const QUOTA_COOLDOWN: Duration = Duration::from_secs(60 * 60);
+const MAX_QUESTIONS: usize = 5;
+const MIN_QUERY_LEN: usize = 4;
+
+#[derive(Deserialize)]
+struct ApiResponse<T> {
+    #[serde(default = "Vec::new")]
+    items: Vec<T>,
+    #[serde(default)]
+    quota_remaining: Option<u32>,
+    /// Seconds the API asks us to wait before the next request
+    #[serde(default)]
+    backoff: Option<u64>,
+    #[serde(default)]
+    error_message: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct Question {
+    question_id: u64,
+    title: String,
+    score: i64,
+    answer_count: u32,
+    #[serde(default)]
+    accepted_answer_id: Option<u64>,
+    link: String,
+    #[serde(default)]
+    tags: Vec<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct Answer {
+    question_id: u64,
+    score: i64,
+    #[serde(default)]
+    is_accepted: bool,
+}
+
+/// Titles come back HTML-escaped
+fn unescape_html(text: &str) -> String {
+    text.replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&amp;", "&")
+}
+
+/// Stack Exchange always gzips its responses
+fn decode_body(bytes: &[u8]) -> Result<String, String> {
+    if bytes.starts_with(&[0x1f, 0x8b]) {
+        let mut body = String::new();
+        flate2::read::GzDecoder::new(bytes)
+            .read_to_string(&mut body)
+            .map_err(|e| e.to_string())?;
+        Ok(body)
+    } else {
+        String::from_utf8(bytes.to_vec()).map_err(|e| e.to_string())
+    }
+}
+
+/// Stack Overflow questions for errors and how-tos, triggered with `so:`
+pub struct StackOverflowProvider {
+    vault: Arc<SecretVault>,
+    client: reqwest::blocking::Client,
+    cache: RwLock<HashMap<String, (Instant, Vec<SearchResult>)>>,
+    /// No requests before this instant (API backoff or exhausted quota)
+    blocked_until: RwLock<Option<Instant>>,
+}
+
+impl StackOverflowProvider {
+    pub fn new(vault: Arc<SecretVault>) -> Self {
+        Self {
+            vault,
+            client: reqwest::blocking::Client::builder()
+                .timeout(Duration::from_secs(5))
+                .build()
+                .unwrap_or_default(),
+            cache: RwLock::new(HashMap::new()),
+            blocked_until: RwLock::new(None),
+        }
+    }
+
+    fn block_for(&self, duration: Duration) {
+        let until = Instant::now() + duration;
+        let mut blocked = self.blocked_until.write();
+        if blocked.map_or(true, |b| b < until) {
+            *blocked = Some(until);
+        }
+    }
+
+    fn get<T: serde::de::DeserializeOwned>(
+        &self,
+        path: &str,
+        params: &[(&str, &str)],
+    ) -> Result<Vec<T>, String> {
+        if let Some(until) = *self.blocked_until.read() {
+            if Instant::now() < until {
+                return Err("Stack Exchange rate limit reached, try again later".to_string());
+            }
+        }
+
+        let key = self.vault.get(VAULT_KEY);
+        let mut request = self
+            .client
+            .get(format!("{}{}", API_BASE, path))
+            .query(&[("site", "stackoverflow")])
+            .query(params);
+        if let Some(key) = &key {
+            request = request.query(&[("key", key.as_str())]);
+        }
+
+        let bytes = request
+            .send()
+            .and_then(|r| r.bytes())
+            .map_err(|e| format!("Failed to reach Stack Exchange: {}", e))?;
+        let response: ApiResponse<T> =
+            serde_json::from_str(&decode_body(&bytes)?).map_err(|e| e.to_string())?;
+
+        if let Some(backoff) = response.backoff {
+            self.block_for(Duration::from_secs(backoff));
+        }
+        if response.quota_remaining == Some(0) {
+            self.block_for(QUOTA_COOLDOWN);
+        }
+        if let Some(error) = response.error_message {
+            // Throttle violations come back as errors; back off before retrying
+            self.block_for(Duration::from_secs(60));
+            return Err(format!("Stack Exchange: {}", error));
+        }
+        Ok(response.items)
+    }
+
+    fn search_questions(&self, query: &str) -> Result<Vec<SearchResult>, String> {
+        let questions: Vec<Question> = self.get(
+            "/search/advanced",
+            &[
+                ("q", query),
+                ("order", "desc"),
+                ("sort", "relevance"),
+                ("answers", "1"),
+                ("pagesize", "5"),
+            ],
+        )?;
+        let questions: Vec<Question> = questions.into_iter().take(MAX_QUESTIONS).collect();
+        if questions.is_empty() {
+            return Ok(vec![]);
+        }
+
+        // One batched request for the answers of every question
+        let ids: Vec<String> = questions
+            .iter()
+            .map(|q| q.question_id.to_string())
+            .collect();
+        let answers: Vec<Answer> = self
+            .get(
+                &format!("/questions/{}/answers", ids.join(";")),
+                &[("order", "desc"), ("sort", "votes"), ("pagesize", "50")],
+            )
+            .unwrap_or_default();
+        let mut top_answers: HashMap<u64, &Answer> = HashMap::new();
+        for answer in &answers {
+            let best = top_answers.entry(answer.question_id).or_insert(answer);
+            if answer.score > best.score {
+                *best = answer;
+            }
+        }
+
+        Ok(questions
+            .iter()
+            .enumerate()
+            .map(|(i, question)| {
+                let mut parts = Vec::new();
+                if let Some(answer) = top_answers.get(&question.question_id) {
+                    let accepted = if answer.is_accepted { " ✓" } else { "" };
+                    parts.push(format!("Top answer {:+}{}", answer.score, accepted));
+                    if !answer.is_accepted && question.accepted_answer_id.is_some() {
+                        parts.push("Has accepted answer".to_string());
+                    }
+                }
+                parts.push(format!(
+                    "{} answer{}",
+                    question.answer_count,
+                    if question.answer_count == 1 { "" } else { "s" }
+                ));
+                parts.push(format!("Score {}", question.score));
+                if !question.tags.is_empty() {
+                    parts.push(
+                        question
+                            .tags
+                            .iter()
+                            .take(3)
+                            .cloned()
+                            .collect::<Vec<_>>()
+                            .join(", "),
+                    );
+                }
+
+                SearchResult {
+                    id: format!("so:open:{}", urlencoding::encode(&question.link)),
+                    title: unescape_html(&question.title),
+                    subtitle: Some(parts.join(" • ")),
+                    icon: ResultIcon::Emoji("💬".to_string()),
+                    category: ResultCategory::Plugin,
+                    score: 90.0 - i as f32,
+                    aria_label: None,
+                }
+            })
+            .collect())
+    }
+
+    fn search_blocking(&self, query: &str) -> Vec<SearchResult> {
+        let query = query.trim();
+        let Some(question) = query
+            .strip_prefix("so:")
+            .or_else(|| query.strip_prefix("so "))
+            .map(str::trim)
+        else {
+            return vec![];
+        };
+        if question.len() < MIN_QUERY_LEN {
+            return vec![];
+        }
+
+        let key = question.to_lowercase();
+        if let Some((fetched_at, results)) = self.cache.read().get(&key) {
+            if fetched_at.elapsed() < CACHE_TTL {
+                return results.clone();
+            }
+        }
+
+        match self.search_questions(question) {
+            Ok(results) => {
+                let mut cache = self.cache.write();
+                cache.retain(|_, (fetched_at, _)| fetched_at.elapsed() < CACHE_TTL);
+                cache.insert(key, (Instant::now(), results.clone()));
+                results
+            }
+            Err(e) => {
+                eprintln!("{}", e);
+                vec![SearchResult {
+                    id: format!(
+                        "so:open:{}",
+                        urlencoding::encode(&format!(
+                            "https://stackoverflow.com/search?q={}",
+                            urlencoding::encode(question)
+                        ))
+                    ),
+                    title: format!("Search Stack Overflow for \"{}\"", question),
+                    subtitle: Some(e),
+                    icon: ResultIcon::Emoji("💬".to_string()),
+                    category: ResultCategory::Plugin,
+                    score: 60.0,
+                    aria_label: None,
+                }]
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl SearchProvider for StackOverflowProvider {
+    fn id(&self) -> &str {
+        "so"
+    }
+
+    async fn search(&self, query: &str, ctx: &SearchContext) -> Vec<SearchResult> {
+        ctx.run_blocking(|| self.search_blocking(query))
+    }
+
+    fn cache_policy(&self, query: &str) -> CachePolicy {
+        let query = query.trim_start();
+        if query.starts_with("so:") || query.starts_with("so ") {
+            CachePolicy::Remote
+        } else {
+            CachePolicy::Never
+        }
+    }
+
+    fn execute(&self, result_id: &str) -> Result<(), String> {
+        let url = result_id
+            .strip_prefix("so:open:")
+            .ok_or("Invalid Stack Overflow result")?;
+        let url = urlencoding::decode(url).map_err(|e| e.to_string())?;
+        super::url::open_url(&url)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    #[test]
+    fn decodes_gzipped_and_plain_bodies() {
+        let json = r#"{"items":[],"quota_remaining":299}"#;
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::fast());
+        encoder.write_all(json.as_bytes()).unwrap();
+        let gzipped = encoder.finish().unwrap();
+
+        assert_eq!(decode_body(&gzipped).unwrap(), json);
+        assert_eq!(decode_body(json.as_bytes()).unwrap(), json);
+    }
+
+    #[test]
+    fn unescapes_titles() {
+        assert_eq!(
+            unescape_html("Can&#39;t borrow &quot;x&quot; &amp; &lt;T&gt;"),
+            "Can't borrow \"x\" & <T>"
+        );
+    }
+}
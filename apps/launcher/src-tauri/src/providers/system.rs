@@ -1,4 +1,5 @@
-use super::{ResultCategory, ResultIcon, SearchProvider, SearchResult};
+use super::{ResultCategory, ResultIcon, SearchContext, SearchProvider, SearchResult};
+use async_trait::async_trait;
 use strsim::jaro_winkler;
 
 #[derive(Clone)]
@@ -62,13 +63,6 @@ const SYSTEM_COMMANDS: &[SystemCommand] = &[
         icon: "⚠️",
         keywords: &["force quit", "kill", "processes"],
     },
-    SystemCommand {
-        id: "emptytrash",
-        name: "Empty Trash",
-        description: "Empty the trash/recycle bin",
-        icon: "🗑️",
-        keywords: &["empty trash", "recycle bin", "delete", "clean"],
-    },
 ];
 
 pub struct SystemProvider;
@@ -129,12 +123,13 @@ impl SystemProvider {
     }
 }
 
+#[async_trait]
 impl SearchProvider for SystemProvider {
     fn id(&self) -> &str {
         "system"
     }
 
-    fn search(&self, query: &str) -> Vec<SearchResult> {
+    async fn search(&self, query: &str, _ctx: &SearchContext) -> Vec<SearchResult> {
         if query.trim().len() < 2 {
             return vec![];
         }
@@ -152,6 +147,7 @@ impl SearchProvider for SystemProvider {
                             icon: ResultIcon::Emoji(cmd.icon.to_string()),
                             category: ResultCategory::System,
                             score,
+                            aria_label: None,
                         },
                         score,
                     ))
@@ -305,36 +301,6 @@ impl SearchProvider for SystemProvider {
                     .spawn()
                     .map_err(|e| format!("Failed to open Force Quit: {}", e))?;
             }
-            "emptytrash" => {
-                #[cfg(target_os = "linux")]
-                {
-                    let trash_path = dirs::home_dir()
-                        .map(|h| h.join(".local/share/Trash"))
-                        .ok_or("Could not find home directory")?;
-                    if trash_path.exists() {
-                        std::fs::remove_dir_all(&trash_path)
-                            .map_err(|e| format!("Failed to empty trash: {}", e))?;
-                        std::fs::create_dir_all(trash_path.join("files"))
-                            .map_err(|e| format!("Failed to recreate trash: {}", e))?;
-                        std::fs::create_dir_all(trash_path.join("info"))
-                            .map_err(|e| format!("Failed to recreate trash: {}", e))?;
-                    }
-                }
-                #[cfg(target_os = "macos")]
-                {
-                    std::process::Command::new("osascript")
-                        .args(["-e", "tell app \"Finder\" to empty trash"])
-                        .spawn()
-                        .map_err(|e| format!("Failed to empty trash: {}", e))?;
-                }
-                #[cfg(target_os = "windows")]
-                {
-                    std::process::Command::new("cmd")
-                        .args(["/C", "rd", "/s", "/q", "%SYSTEMDRIVE%\\$Recycle.Bin"])
-                        .spawn()
-                        .map_err(|e| format!("Failed to empty recycle bin: {}", e))?;
-                }
-            }
             _ => return Err(format!("Unknown system command: {}", cmd_id)),
         }
 
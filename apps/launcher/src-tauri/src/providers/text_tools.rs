@@ -0,0 +1,250 @@
+use super::{ResultCategory, ResultIcon, SearchContext, SearchProvider, SearchResult};
+use async_trait::async_trait;
+use parking_lot::RwLock;
+use std::collections::{HashMap, HashSet};
+
+const PREVIEW_CHARS: usize = 80;
+
+/// Split into words on punctuation, whitespace and camelCase boundaries
+fn words(text: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut current = String::new();
+    let mut prev: Option<char> = None;
+
+    for c in text.chars() {
+        if !c.is_alphanumeric() {
+            if !current.is_empty() {
+                words.push(std::mem::take(&mut current));
+            }
+        } else {
+            let boundary =
+                prev.is_some_and(|p| p.is_lowercase() || p.is_numeric()) && c.is_uppercase();
+            if boundary && !current.is_empty() {
+                words.push(std::mem::take(&mut current));
+            }
+            current.push(c);
+        }
+        prev = Some(c);
+    }
+    if !current.is_empty() {
+        words.push(current);
+    }
+    words
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first
+            .to_uppercase()
+            .chain(chars.flat_map(|c| c.to_lowercase()))
+            .collect(),
+        None => String::new(),
+    }
+}
+
+fn title_case(text: &str) -> String {
+    text.split(' ')
+        .map(capitalize)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn camel_case(text: &str) -> String {
+    words(text)
+        .iter()
+        .enumerate()
+        .map(|(i, w)| {
+            if i == 0 {
+                w.to_lowercase()
+            } else {
+                capitalize(w)
+            }
+        })
+        .collect()
+}
+
+fn snake_case(text: &str) -> String {
+    words(text)
+        .iter()
+        .map(|w| w.to_lowercase())
+        .collect::<Vec<_>>()
+        .join("_")
+}
+
+/// Lowercase ASCII words joined by dashes; accents are dropped, not transliterated
+fn slugify(text: &str) -> String {
+    words(text)
+        .iter()
+        .map(|w| {
+            w.chars()
+                .filter(|c| c.is_ascii_alphanumeric())
+                .collect::<String>()
+                .to_lowercase()
+        })
+        .filter(|w| !w.is_empty())
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+fn sort_lines(text: &str) -> String {
+    let mut lines: Vec<&str> = text.lines().collect();
+    lines.sort_by_key(|l| l.to_lowercase());
+    lines.join("\n")
+}
+
+/// Drop repeated lines, keeping the first occurrence in place
+fn unique_lines(text: &str) -> String {
+    let mut seen = HashSet::new();
+    text.lines()
+        .filter(|line| seen.insert(*line))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn counts(text: &str) -> String {
+    let plural = |n: usize, unit: &str| format!("{} {}{}", n, unit, if n == 1 { "" } else { "s" });
+    format!(
+        "{}, {}, {}",
+        plural(text.split_whitespace().count(), "word"),
+        plural(text.chars().count(), "character"),
+        plural(text.lines().count().max(1), "line")
+    )
+}
+
+fn preview(text: &str) -> String {
+    let flat = text.replace('\n', " ↵ ");
+    if flat.chars().count() > PREVIEW_CHARS {
+        format!("{}…", flat.chars().take(PREVIEW_CHARS).collect::<String>())
+    } else {
+        flat
+    }
+}
+
+/// Case conversion, slugs, line sorting and counts for `text: <input>`,
+/// or the clipboard when no input is given. Each result copies its output.
+pub struct TextToolsProvider {
+    /// Full outputs of the last search by result id; ids stay short even for
+    /// a large clipboard
+    outputs: RwLock<HashMap<String, String>>,
+}
+
+impl TextToolsProvider {
+    pub fn new() -> Self {
+        Self {
+            outputs: RwLock::new(HashMap::new()),
+        }
+    }
+
+    fn transform(&self, text: &str, source: &str) -> Vec<SearchResult> {
+        let multiline = text.contains('\n');
+        let mut transforms: Vec<(&str, &str, String)> = vec![
+            ("upper", "UPPERCASE", text.to_uppercase()),
+            ("lower", "lowercase", text.to_lowercase()),
+            ("title", "Title Case", title_case(text)),
+            ("camel", "camelCase", camel_case(text)),
+            ("snake", "snake_case", snake_case(text)),
+            ("slug", "slugify", slugify(text)),
+        ];
+        if multiline {
+            transforms.push(("sort", "Sort lines", sort_lines(text)));
+            transforms.push(("unique", "Unique lines", unique_lines(text)));
+        }
+        transforms.push(("count", "Count", counts(text)));
+
+        let mut outputs = HashMap::new();
+        let results = transforms
+            .into_iter()
+            .enumerate()
+            .map(|(i, (key, name, output))| {
+                let id = format!("text:{}", key);
+                let result = SearchResult {
+                    id: id.clone(),
+                    title: preview(&output),
+                    subtitle: Some(format!("{} of {} • copy", name, source)),
+                    icon: ResultIcon::Emoji("🔤".to_string()),
+                    category: ResultCategory::Command,
+                    score: 90.0 - i as f32 * 0.5,
+                    aria_label: None,
+                };
+                outputs.insert(id, output);
+                result
+            })
+            .collect();
+        *self.outputs.write() = outputs;
+        results
+    }
+}
+
+impl Default for TextToolsProvider {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl SearchProvider for TextToolsProvider {
+    fn id(&self) -> &str {
+        "text"
+    }
+
+    async fn search(&self, query: &str, ctx: &SearchContext) -> Vec<SearchResult> {
+        let Some(input) = query.trim_start().strip_prefix("text:") else {
+            return vec![];
+        };
+        let input = input.trim();
+        if !input.is_empty() {
+            return self.transform(input, "the text");
+        }
+
+        match ctx.run_blocking(|| crate::clipboard::read_text().ok()) {
+            Some(text) if !text.trim().is_empty() => self.transform(&text, "the clipboard"),
+            _ => vec![SearchResult {
+                id: "text:help".to_string(),
+                title: "Text tools".to_string(),
+                subtitle: Some("text: <text>, or copy some text first".to_string()),
+                icon: ResultIcon::Emoji("🔤".to_string()),
+                category: ResultCategory::Command,
+                score: 80.0,
+                aria_label: None,
+            }],
+        }
+    }
+
+    fn execute(&self, result_id: &str) -> Result<(), String> {
+        if result_id == "text:help" {
+            return Ok(());
+        }
+        let output = self
+            .outputs
+            .read()
+            .get(result_id)
+            .cloned()
+            .ok_or("This result has expired, search again")?;
+        crate::clipboard::copy_text(&output)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn converts_case() {
+        assert_eq!(camel_case("hello big-world"), "helloBigWorld");
+        assert_eq!(
+            snake_case("parseHTTPRequest body"),
+            "parse_httprequest_body"
+        );
+        assert_eq!(snake_case("userId2Name"), "user_id2_name");
+        assert_eq!(title_case("the QUICK fox"), "The Quick Fox");
+        assert_eq!(slugify("Héllo, World! 2024"), "hllo-world-2024");
+    }
+
+    #[test]
+    fn sorts_and_dedupes_lines() {
+        assert_eq!(sort_lines("b\nA\nc"), "A\nb\nc");
+        assert_eq!(unique_lines("a\nb\na\nc\nb"), "a\nb\nc");
+        assert_eq!(counts("two words"), "2 words, 9 characters, 1 line");
+    }
+}
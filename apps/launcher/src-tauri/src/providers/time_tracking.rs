@@ -0,0 +1,473 @@
+use super::{ResultCategory, ResultIcon, SearchContext, SearchProvider, SearchResult};
+use crate::vault::SecretVault;
+use async_trait::async_trait;
+use parking_lot::{Mutex, RwLock};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+const API_BASE: &str = "https://api.track.toggl.com/api/v9";
+const VAULT_KEY: &str = "toggl_api_token";
+/// How long the running entry and recent entries are reused between searches
+const CACHE_TTL: Duration = Duration::from_secs(15);
+/// Longest wait before trying Toggl again after refreshes keep failing
+const MAX_BACKOFF: Duration = Duration::from_secs(300);
+
+#[derive(Debug, Clone, Deserialize)]
+struct TogglMe {
+    default_workspace_id: i64,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct TogglTimeEntry {
+    id: i64,
+    workspace_id: i64,
+    #[serde(default)]
+    project_id: Option<i64>,
+    #[serde(default)]
+    description: Option<String>,
+    start: String,
+}
+
+impl TogglTimeEntry {
+    fn description(&self) -> &str {
+        self.description
+            .as_deref()
+            .filter(|d| !d.is_empty())
+            .unwrap_or("(no description)")
+    }
+
+    fn elapsed_secs(&self) -> i64 {
+        chrono::DateTime::parse_from_rfc3339(&self.start)
+            .map(|start| (chrono::Utc::now() - start.with_timezone(&chrono::Utc)).num_seconds())
+            .unwrap_or(0)
+            .max(0)
+    }
+}
+
+/// The running timer, as shown in search and the dashboard widget
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunningTimer {
+    pub id: i64,
+    pub description: String,
+    pub start: String,
+    pub elapsed_secs: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TimeTrackingStatus {
+    pub connected: bool,
+    pub service: String,
+}
+
+struct TimerCache {
+    fetched_at: Instant,
+    running: Option<TogglTimeEntry>,
+    recent: Vec<TogglTimeEntry>,
+}
+
+/// Background refreshes for search, backing off while Toggl keeps failing
+#[derive(Default)]
+struct RefreshState {
+    running: bool,
+    failures: u32,
+    retry_at: Option<Instant>,
+}
+
+/// Toggl Track integration, triggered with `track:`.
+///
+/// Search never waits on Toggl: it shows the last fetched timers and
+/// refreshes them in the background once they're stale.
+pub struct TimeTrackingProvider {
+    vault: Arc<SecretVault>,
+    workspace_id: RwLock<Option<i64>>,
+    cache: Arc<RwLock<Option<TimerCache>>>,
+    refresh: Arc<Mutex<RefreshState>>,
+}
+
+fn request(method: reqwest::Method, path: &str, token: &str) -> reqwest::blocking::RequestBuilder {
+    reqwest::blocking::Client::new()
+        .request(method, format!("{}{}", API_BASE, path))
+        .basic_auth(token, Some("api_token"))
+        .timeout(Duration::from_secs(5))
+}
+
+/// The running entry and recent entries. Blocks for up to 10 seconds.
+fn fetch_timers(token: &str) -> Result<TimerCache, String> {
+    let running: Option<TogglTimeEntry> =
+        request(reqwest::Method::GET, "/me/time_entries/current", token)
+            .send()
+            .and_then(|r| r.error_for_status())
+            .and_then(|r| r.json())
+            .map_err(|e| format!("Failed to fetch running timer: {}", e))?;
+
+    let recent: Vec<TogglTimeEntry> = request(reqwest::Method::GET, "/me/time_entries", token)
+        .send()
+        .and_then(|r| r.error_for_status())
+        .and_then(|r| r.json())
+        .unwrap_or_default();
+
+    Ok(TimerCache {
+        fetched_at: Instant::now(),
+        running,
+        recent,
+    })
+}
+
+fn format_elapsed(secs: i64) -> String {
+    let (h, m, s) = (secs / 3600, (secs % 3600) / 60, secs % 60);
+    if h > 0 {
+        format!("{}:{:02}:{:02}", h, m, s)
+    } else {
+        format!("{}:{:02}", m, s)
+    }
+}
+
+impl TimeTrackingProvider {
+    pub fn new(vault: Arc<SecretVault>) -> Self {
+        Self {
+            vault,
+            workspace_id: RwLock::new(None),
+            cache: Arc::new(RwLock::new(None)),
+            refresh: Arc::new(Mutex::new(RefreshState::default())),
+        }
+    }
+
+    fn token(&self) -> Option<String> {
+        self.vault.get(VAULT_KEY)
+    }
+
+    pub fn status(&self) -> TimeTrackingStatus {
+        TimeTrackingStatus {
+            connected: self.token().is_some(),
+            service: "toggl".to_string(),
+        }
+    }
+
+    fn fetch_me(&self, token: &str) -> Result<TogglMe, String> {
+        let response = request(reqwest::Method::GET, "/me", token)
+            .send()
+            .map_err(|e| format!("Failed to reach Toggl: {}", e))?;
+        if response.status() == reqwest::StatusCode::FORBIDDEN {
+            return Err("Toggl rejected the API token".to_string());
+        }
+        if !response.status().is_success() {
+            return Err(format!("Toggl returned status {}", response.status()));
+        }
+        response.json().map_err(|e| e.to_string())
+    }
+
+    /// Validate and store a Toggl API token
+    pub fn connect(&self, api_token: &str) -> Result<TimeTrackingStatus, String> {
+        let api_token = api_token.trim();
+        let me = self.fetch_me(api_token)?;
+        self.vault.set(VAULT_KEY, api_token)?;
+        *self.workspace_id.write() = Some(me.default_workspace_id);
+        *self.cache.write() = None;
+        Ok(self.status())
+    }
+
+    pub fn disconnect(&self) -> Result<(), String> {
+        *self.workspace_id.write() = None;
+        *self.cache.write() = None;
+        self.vault.delete(VAULT_KEY)
+    }
+
+    fn workspace_id(&self, token: &str) -> Result<i64, String> {
+        if let Some(id) = *self.workspace_id.read() {
+            return Ok(id);
+        }
+        let id = self.fetch_me(token)?.default_workspace_id;
+        *self.workspace_id.write() = Some(id);
+        Ok(id)
+    }
+
+    fn refresh(&self, token: &str) -> Result<(), String> {
+        *self.cache.write() = Some(fetch_timers(token)?);
+        let mut state = self.refresh.lock();
+        state.failures = 0;
+        state.retry_at = None;
+        Ok(())
+    }
+
+    /// Refresh on a background thread, unless one is running or Toggl
+    /// failed recently
+    fn refresh_in_background(&self, token: String) {
+        {
+            let mut state = self.refresh.lock();
+            let backing_off = state.retry_at.is_some_and(|at| Instant::now() < at);
+            if state.running || backing_off {
+                return;
+            }
+            state.running = true;
+        }
+
+        let cache = self.cache.clone();
+        let refresh = self.refresh.clone();
+        std::thread::spawn(move || {
+            let result = fetch_timers(&token);
+            let mut state = refresh.lock();
+            state.running = false;
+            match result {
+                Ok(timers) => {
+                    *cache.write() = Some(timers);
+                    state.failures = 0;
+                    state.retry_at = None;
+                }
+                Err(e) => {
+                    eprintln!("{}", e);
+                    state.failures += 1;
+                    let backoff = CACHE_TTL
+                        .saturating_mul(2u32.saturating_pow(state.failures))
+                        .min(MAX_BACKOFF);
+                    state.retry_at = Some(Instant::now() + backoff);
+                }
+            }
+        });
+    }
+
+    /// Run `f` against the last fetched timer state without waiting, if
+    /// there is any, refreshing it in the background when it's stale
+    fn with_cached<T>(&self, f: impl FnOnce(&TimerCache) -> T) -> Option<T> {
+        let token = self.token()?;
+        let stale = self
+            .cache
+            .read()
+            .as_ref()
+            .map_or(true, |c| c.fetched_at.elapsed() > CACHE_TTL);
+        if stale {
+            self.refresh_in_background(token);
+        }
+        self.cache.read().as_ref().map(f)
+    }
+
+    /// Run `f` against fresh (or recently fetched) timer state
+    fn with_cache<T>(&self, f: impl FnOnce(&TimerCache) -> T) -> Option<T> {
+        let token = self.token()?;
+        let stale = self
+            .cache
+            .read()
+            .as_ref()
+            .map_or(true, |c| c.fetched_at.elapsed() > CACHE_TTL);
+        if stale {
+            if let Err(e) = self.refresh(&token) {
+                eprintln!("{}", e);
+            }
+        }
+        self.cache.read().as_ref().map(f)
+    }
+
+    pub fn running_timer(&self) -> Option<RunningTimer> {
+        self.with_cache(|c| c.running.clone())
+            .flatten()
+            .map(|entry| RunningTimer {
+                id: entry.id,
+                description: entry.description().to_string(),
+                elapsed_secs: entry.elapsed_secs(),
+                start: entry.start,
+            })
+    }
+
+    pub fn start(
+        &self,
+        description: &str,
+        project_id: Option<i64>,
+    ) -> Result<RunningTimer, String> {
+        let token = self.token().ok_or("Toggl is not connected")?;
+        let workspace_id = self.workspace_id(&token)?;
+
+        let entry: TogglTimeEntry = request(
+            reqwest::Method::POST,
+            &format!("/workspaces/{}/time_entries", workspace_id),
+            &token,
+        )
+        .json(&serde_json::json!({
+            "created_with": "launcher",
+            "description": description,
+            "workspace_id": workspace_id,
+            "project_id": project_id,
+            "start": chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+            "duration": -1,
+        }))
+        .send()
+        .and_then(|r| r.error_for_status())
+        .and_then(|r| r.json())
+        .map_err(|e| format!("Failed to start timer: {}", e))?;
+
+        *self.cache.write() = None;
+        Ok(RunningTimer {
+            id: entry.id,
+            description: entry.description().to_string(),
+            elapsed_secs: 0,
+            start: entry.start,
+        })
+    }
+
+    pub fn stop(&self) -> Result<(), String> {
+        let token = self.token().ok_or("Toggl is not connected")?;
+        self.refresh(&token)?;
+        let running = self
+            .cache
+            .read()
+            .as_ref()
+            .and_then(|c| c.running.clone())
+            .ok_or("No timer is running")?;
+
+        request(
+            reqwest::Method::PATCH,
+            &format!(
+                "/workspaces/{}/time_entries/{}/stop",
+                running.workspace_id, running.id
+            ),
+            &token,
+        )
+        .send()
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| format!("Failed to stop timer: {}", e))?;
+
+        *self.cache.write() = None;
+        Ok(())
+    }
+
+    /// Start a new timer with the same description and project as a past entry
+    pub fn continue_entry(&self, entry_id: i64) -> Result<RunningTimer, String> {
+        let entry = self
+            .with_cache(|c| c.recent.iter().find(|e| e.id == entry_id).cloned())
+            .flatten()
+            .ok_or("Time entry not found")?;
+        self.start(entry.description.as_deref().unwrap_or(""), entry.project_id)
+    }
+
+    fn running_result(entry: &TogglTimeEntry, score: f32) -> SearchResult {
+        SearchResult {
+            id: "timer:stop".to_string(),
+            title: format!("Stop \"{}\"", entry.description()),
+            subtitle: Some(format!(
+                "Running for {} • Toggl",
+                format_elapsed(entry.elapsed_secs())
+            )),
+            icon: ResultIcon::Emoji("⏹️".to_string()),
+            category: ResultCategory::Command,
+            score,
+            aria_label: None,
+        }
+    }
+
+    fn search_blocking(&self, query: &str) -> Vec<SearchResult> {
+        let query = query.trim();
+        let description = match query
+            .strip_prefix("track:")
+            .or_else(|| query.strip_prefix("track "))
+        {
+            Some(rest) => rest.trim().to_string(),
+            None if query == "track" => String::new(),
+            None => {
+                // Outside the trigger, only surface an already running timer
+                if query.len() < 2 || self.token().is_none() {
+                    return vec![];
+                }
+                let lower = query.to_lowercase();
+                return self
+                    .with_cached(|c| c.running.clone())
+                    .flatten()
+                    .filter(|e| {
+                        "timer".starts_with(&lower)
+                            || e.description().to_lowercase().contains(&lower)
+                    })
+                    .map(|e| vec![Self::running_result(&e, 70.0)])
+                    .unwrap_or_default();
+            }
+        };
+
+        if self.token().is_none() {
+            return vec![SearchResult {
+                id: "timer:connect".to_string(),
+                title: "Connect Toggl".to_string(),
+                subtitle: Some("Add your Toggl API token in Settings → Accounts".to_string()),
+                icon: ResultIcon::Emoji("🔗".to_string()),
+                category: ResultCategory::Plugin,
+                score: 50.0,
+                aria_label: None,
+            }];
+        }
+
+        let mut results = Vec::new();
+
+        if !description.is_empty() {
+            results.push(SearchResult {
+                id: format!("timer:start:{}", urlencoding::encode(&description)),
+                title: format!("Start \"{}\"", description),
+                subtitle: Some("Start a new Toggl timer".to_string()),
+                icon: ResultIcon::Emoji("▶️".to_string()),
+                category: ResultCategory::Command,
+                score: 95.0,
+                aria_label: None,
+            });
+        }
+
+        let (running, recent) = self
+            .with_cached(|c| (c.running.clone(), c.recent.clone()))
+            .unwrap_or_default();
+
+        if let Some(entry) = &running {
+            results.push(Self::running_result(entry, 90.0));
+        }
+
+        // Recent entries to continue, one per description
+        let filter = description.to_lowercase();
+        let mut seen = std::collections::HashSet::new();
+        for entry in recent
+            .iter()
+            .filter(|e| running.as_ref().map_or(true, |r| r.id != e.id))
+            .filter(|e| e.description().to_lowercase().contains(&filter))
+            .filter(|e| seen.insert(e.description().to_string()))
+            .take(5)
+        {
+            results.push(SearchResult {
+                id: format!("timer:continue:{}", entry.id),
+                title: format!("Continue \"{}\"", entry.description()),
+                subtitle: Some("Start again with the same project".to_string()),
+                icon: ResultIcon::Emoji("🔁".to_string()),
+                category: ResultCategory::Command,
+                score: 80.0 - results.len() as f32,
+                aria_label: None,
+            });
+        }
+
+        results
+    }
+}
+
+#[async_trait]
+impl SearchProvider for TimeTrackingProvider {
+    fn id(&self) -> &str {
+        "timer"
+    }
+
+    async fn search(&self, query: &str, ctx: &SearchContext) -> Vec<SearchResult> {
+        ctx.run_blocking(|| self.search_blocking(query))
+    }
+
+    fn execute(&self, result_id: &str) -> Result<(), String> {
+        let action = result_id
+            .strip_prefix("timer:")
+            .ok_or("Invalid timer result")?;
+
+        if action == "connect" {
+            return Err("Add your Toggl API token in Settings → Accounts".to_string());
+        }
+        if action == "stop" {
+            return self.stop();
+        }
+        if let Some(description) = action.strip_prefix("start:") {
+            let description = urlencoding::decode(description).map_err(|e| e.to_string())?;
+            return self.start(&description, None).map(|_| ());
+        }
+        if let Some(id) = action.strip_prefix("continue:") {
+            let id = id.parse().map_err(|_| "Invalid time entry id")?;
+            return self.continue_entry(id).map(|_| ());
+        }
+
+        Err(format!("Unknown timer action: {}", action))
+    }
+}
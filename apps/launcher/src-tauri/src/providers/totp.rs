@@ -0,0 +1,504 @@
+use super::{ResultCategory, ResultIcon, SearchContext, SearchProvider, SearchResult};
+use crate::vault::SecretVault;
+use async_trait::async_trait;
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use hmac::{Hmac, Mac};
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::Arc;
+
+/// Where earlier versions kept every account in one credential, which
+/// outgrew Windows' 2560-byte limit after a few dozen accounts
+const LEGACY_VAULT_KEY: &str = "totp";
+const EXPORT_VERSION: u32 = 1;
+const PBKDF2_ROUNDS: u32 = 200_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum TotpAlgorithm {
+    Sha1,
+    Sha256,
+    Sha512,
+}
+
+/// A TOTP account, including its shared secret. Never sent to the frontend.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct TotpEntry {
+    id: String,
+    issuer: Option<String>,
+    account: String,
+    /// Raw (base32-decoded) shared secret
+    secret: Vec<u8>,
+    digits: u32,
+    period: u64,
+    algorithm: TotpAlgorithm,
+}
+
+/// A TOTP account and its current code, as shown in the UI
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TotpCode {
+    pub id: String,
+    pub issuer: Option<String>,
+    pub account: String,
+    pub code: String,
+    /// Seconds until the code rotates
+    pub remaining: u64,
+    pub period: u64,
+}
+
+/// Passphrase-encrypted export of all TOTP accounts
+#[derive(Debug, Serialize, Deserialize)]
+struct EncryptedExport {
+    version: u32,
+    salt: String,
+    nonce: String,
+    ciphertext: String,
+}
+
+impl TotpEntry {
+    fn label(&self) -> String {
+        match &self.issuer {
+            Some(issuer) => format!("{} ({})", issuer, self.account),
+            None => self.account.clone(),
+        }
+    }
+
+    fn code_at(&self, unix_time: u64) -> String {
+        let counter = (unix_time / self.period).to_be_bytes();
+
+        let hash = hmac_digest(self.algorithm, &self.secret, &counter);
+
+        // RFC 4226 dynamic truncation
+        let offset = (hash[hash.len() - 1] & 0x0f) as usize;
+        let binary = u32::from_be_bytes([
+            hash[offset] & 0x7f,
+            hash[offset + 1],
+            hash[offset + 2],
+            hash[offset + 3],
+        ]);
+        let code = binary % 10u32.pow(self.digits);
+
+        format!("{:0width$}", code, width = self.digits as usize)
+    }
+
+    fn current(&self) -> TotpCode {
+        let now = chrono::Utc::now().timestamp().max(0) as u64;
+        TotpCode {
+            id: self.id.clone(),
+            issuer: self.issuer.clone(),
+            account: self.account.clone(),
+            code: self.code_at(now),
+            remaining: self.period - (now % self.period),
+            period: self.period,
+        }
+    }
+
+    /// Parse an `otpauth://totp/Issuer:account?secret=...` URI
+    fn from_uri(uri: &str) -> Result<Self, String> {
+        let url = url::Url::parse(uri.trim()).map_err(|e| format!("Invalid URI: {}", e))?;
+        if url.scheme() != "otpauth" {
+            return Err("Expected an otpauth:// URI".to_string());
+        }
+        if url.host_str() != Some("totp") {
+            return Err("Only TOTP accounts are supported".to_string());
+        }
+
+        let label = urlencoding::decode(url.path().trim_start_matches('/'))
+            .map_err(|e| e.to_string())?
+            .to_string();
+        let (label_issuer, account) = match label.split_once(':') {
+            Some((issuer, account)) => {
+                (Some(issuer.trim().to_string()), account.trim().to_string())
+            }
+            None => (None, label.trim().to_string()),
+        };
+
+        let mut secret = None;
+        let mut issuer = label_issuer;
+        let mut digits = 6;
+        let mut period = 30;
+        let mut algorithm = TotpAlgorithm::Sha1;
+
+        for (key, value) in url.query_pairs() {
+            match key.as_ref() {
+                "secret" => secret = Some(base32_decode(&value)?),
+                "issuer" => issuer = Some(value.to_string()),
+                "digits" => digits = value.parse().map_err(|_| "Invalid digits")?,
+                "period" => period = value.parse().map_err(|_| "Invalid period")?,
+                "algorithm" => {
+                    algorithm = match value.to_uppercase().as_str() {
+                        "SHA1" => TotpAlgorithm::Sha1,
+                        "SHA256" => TotpAlgorithm::Sha256,
+                        "SHA512" => TotpAlgorithm::Sha512,
+                        other => return Err(format!("Unsupported algorithm: {}", other)),
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        let secret = secret.ok_or("URI has no secret")?;
+        if !(6..=8).contains(&digits) {
+            return Err("Digits must be between 6 and 8".to_string());
+        }
+        if period == 0 {
+            return Err("Period must be positive".to_string());
+        }
+
+        Ok(Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            issuer,
+            account,
+            secret,
+            digits,
+            period,
+            algorithm,
+        })
+    }
+}
+
+fn hmac_digest(algorithm: TotpAlgorithm, key: &[u8], message: &[u8]) -> Vec<u8> {
+    // HMAC accepts keys of any length, so new_from_slice can't fail
+    match algorithm {
+        TotpAlgorithm::Sha1 => {
+            let mut mac = Hmac::<sha1::Sha1>::new_from_slice(key).unwrap();
+            mac.update(message);
+            mac.finalize().into_bytes().to_vec()
+        }
+        TotpAlgorithm::Sha256 => {
+            let mut mac = Hmac::<sha2::Sha256>::new_from_slice(key).unwrap();
+            mac.update(message);
+            mac.finalize().into_bytes().to_vec()
+        }
+        TotpAlgorithm::Sha512 => {
+            let mut mac = Hmac::<sha2::Sha512>::new_from_slice(key).unwrap();
+            mac.update(message);
+            mac.finalize().into_bytes().to_vec()
+        }
+    }
+}
+
+/// Decode RFC 4648 base32, ignoring case, spaces and padding
+fn base32_decode(input: &str) -> Result<Vec<u8>, String> {
+    const ALPHABET: &[u8; 32] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+
+    let mut output = Vec::new();
+    let mut buffer: u32 = 0;
+    let mut bits = 0;
+
+    for c in input.chars().filter(|c| !c.is_whitespace() && *c != '=' && *c != '-') {
+        let value = ALPHABET
+            .iter()
+            .position(|&a| a == c.to_ascii_uppercase() as u8)
+            .ok_or_else(|| format!("Invalid base32 character: {}", c))?;
+
+        buffer = (buffer << 5) | value as u32;
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            output.push((buffer >> bits) as u8);
+            buffer &= (1 << bits) - 1;
+        }
+    }
+
+    if output.is_empty() {
+        return Err("Secret is empty".to_string());
+    }
+    Ok(output)
+}
+
+fn derive_key(passphrase: &str, salt: &[u8]) -> [u8; 32] {
+    let mut key = [0u8; 32];
+    pbkdf2::pbkdf2_hmac::<sha2::Sha256>(passphrase.as_bytes(), salt, PBKDF2_ROUNDS, &mut key);
+    key
+}
+
+fn vault_key(id: &str) -> String {
+    format!("totp:{}", id)
+}
+
+/// Time-based one-time passwords, triggered with `otp:`.
+///
+/// Each account is its own vault credential; the list of account ids,
+/// which isn't secret, is a store on disk.
+pub struct TotpProvider {
+    vault: Arc<SecretVault>,
+    index_path: PathBuf,
+    entries: RwLock<Vec<TotpEntry>>,
+}
+
+impl TotpProvider {
+    pub fn new(vault: Arc<SecretVault>) -> Self {
+        let index_path = dirs::data_dir()
+            .unwrap_or_else(|| PathBuf::from("."))
+            .join("launcher")
+            .join("totp_accounts.json");
+        let ids: Vec<String> = crate::journal::load_json_store(&index_path).unwrap_or_default();
+        let entries = ids
+            .iter()
+            .filter_map(|id| vault.get(&vault_key(id)))
+            .filter_map(|json| serde_json::from_str(&json).ok())
+            .collect();
+
+        let provider = Self {
+            vault,
+            index_path,
+            entries: RwLock::new(entries),
+        };
+        if ids.is_empty() {
+            provider.migrate_legacy();
+        }
+        provider
+    }
+
+    /// Split a single-credential account list into one credential each
+    fn migrate_legacy(&self) {
+        let Some(legacy) = self
+            .vault
+            .get(LEGACY_VAULT_KEY)
+            .and_then(|json| serde_json::from_str::<Vec<TotpEntry>>(&json).ok())
+        else {
+            return;
+        };
+        match self.add_entries(legacy) {
+            Ok(_) => {
+                let _ = self.vault.delete(LEGACY_VAULT_KEY);
+            }
+            Err(e) => eprintln!("Failed to migrate TOTP accounts: {}", e),
+        }
+    }
+
+    fn save_index(&self, entries: &[TotpEntry]) -> Result<(), String> {
+        let ids: Vec<&str> = entries.iter().map(|e| e.id.as_str()).collect();
+        let json = serde_json::to_string_pretty(&ids).map_err(|e| e.to_string())?;
+        crate::journal::write_json_store(&self.index_path, &json)
+    }
+
+    /// Store new accounts, then list them, and only then show them; if
+    /// anything fails, nothing is added
+    fn add_entries(&self, new: Vec<TotpEntry>) -> Result<usize, String> {
+        let mut entries = self.entries.write();
+        let mut saved: Vec<String> = Vec::new();
+        let forget = |saved: &[String]| {
+            for id in saved {
+                let _ = self.vault.delete(&vault_key(id));
+            }
+        };
+
+        for entry in &new {
+            let stored = serde_json::to_string(entry)
+                .map_err(|e| e.to_string())
+                .and_then(|json| self.vault.set(&vault_key(&entry.id), &json));
+            if let Err(e) = stored {
+                forget(&saved);
+                return Err(e);
+            }
+            saved.push(entry.id.clone());
+        }
+
+        let added = new.len();
+        let mut updated = entries.clone();
+        updated.extend(new);
+        if let Err(e) = self.save_index(&updated) {
+            forget(&saved);
+            return Err(e);
+        }
+        *entries = updated;
+        Ok(added)
+    }
+
+    pub fn codes(&self) -> Vec<TotpCode> {
+        self.entries.read().iter().map(|e| e.current()).collect()
+    }
+
+    /// Add an account from an otpauth:// URI, returning its current code
+    pub fn import_uri(&self, uri: &str) -> Result<TotpCode, String> {
+        let entry = TotpEntry::from_uri(uri)?;
+        let code = entry.current();
+        self.add_entries(vec![entry])?;
+        Ok(code)
+    }
+
+    pub fn remove(&self, id: &str) -> Result<(), String> {
+        let mut entries = self.entries.write();
+        let updated: Vec<TotpEntry> = entries.iter().filter(|e| e.id != id).cloned().collect();
+        self.save_index(&updated)?;
+        *entries = updated;
+        // Unlisted, so a credential left behind is never read again
+        if let Err(e) = self.vault.delete(&vault_key(id)) {
+            eprintln!("Failed to delete TOTP secret {}: {}", id, e);
+        }
+        Ok(())
+    }
+
+    /// Export all accounts encrypted with AES-256-GCM under a passphrase
+    pub fn export_encrypted(&self, passphrase: &str) -> Result<String, String> {
+        use aes_gcm::aead::{Aead, KeyInit};
+        use aes_gcm::{Aes256Gcm, Key, Nonce};
+
+        if passphrase.len() < 8 {
+            return Err("Passphrase must be at least 8 characters".to_string());
+        }
+
+        let plaintext =
+            serde_json::to_vec(&*self.entries.read()).map_err(|e| e.to_string())?;
+        let salt: [u8; 16] = rand::random();
+        let nonce: [u8; 12] = rand::random();
+
+        let key = derive_key(passphrase, &salt);
+        let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&key));
+        let ciphertext = cipher
+            .encrypt(Nonce::from_slice(&nonce), plaintext.as_ref())
+            .map_err(|_| "Failed to encrypt export".to_string())?;
+
+        serde_json::to_string_pretty(&EncryptedExport {
+            version: EXPORT_VERSION,
+            salt: BASE64.encode(salt),
+            nonce: BASE64.encode(nonce),
+            ciphertext: BASE64.encode(ciphertext),
+        })
+        .map_err(|e| e.to_string())
+    }
+
+    /// Import accounts from an encrypted export. Returns how many were added.
+    pub fn import_encrypted(&self, data: &str, passphrase: &str) -> Result<usize, String> {
+        use aes_gcm::aead::{Aead, KeyInit};
+        use aes_gcm::{Aes256Gcm, Key, Nonce};
+
+        let export: EncryptedExport =
+            serde_json::from_str(data).map_err(|e| format!("Invalid export file: {}", e))?;
+        if export.version != EXPORT_VERSION {
+            return Err(format!("Unsupported export version: {}", export.version));
+        }
+
+        let decode = |s: &str| BASE64.decode(s).map_err(|e| format!("Invalid export file: {}", e));
+        let salt = decode(&export.salt)?;
+        let nonce = decode(&export.nonce)?;
+        let ciphertext = decode(&export.ciphertext)?;
+        if nonce.len() != 12 {
+            return Err("Invalid export file: bad nonce".to_string());
+        }
+
+        let key = derive_key(passphrase, &salt);
+        let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&key));
+        let plaintext = cipher
+            .decrypt(Nonce::from_slice(&nonce), ciphertext.as_ref())
+            .map_err(|_| "Wrong passphrase or corrupted export".to_string())?;
+
+        let imported: Vec<TotpEntry> =
+            serde_json::from_slice(&plaintext).map_err(|e| e.to_string())?;
+
+        let mut new: Vec<TotpEntry> = Vec::new();
+        {
+            let entries = self.entries.read();
+            for entry in imported {
+                let duplicate = entries
+                    .iter()
+                    .chain(&new)
+                    .any(|e| e.secret == entry.secret && e.account == entry.account);
+                if !duplicate {
+                    new.push(entry);
+                }
+            }
+        }
+
+        self.add_entries(new)
+    }
+}
+
+#[async_trait]
+impl SearchProvider for TotpProvider {
+    fn id(&self) -> &str {
+        "totp"
+    }
+
+    async fn search(&self, query: &str, _ctx: &SearchContext) -> Vec<SearchResult> {
+        let query = query.trim();
+        let filter = match query
+            .strip_prefix("otp:")
+            .or_else(|| query.strip_prefix("otp "))
+        {
+            Some(rest) => rest.trim().to_lowercase(),
+            None if query == "otp" => String::new(),
+            None => return vec![],
+        };
+
+        self.entries
+            .read()
+            .iter()
+            .filter(|e| filter.is_empty() || e.label().to_lowercase().contains(&filter))
+            .map(|e| {
+                let code = e.current();
+                SearchResult {
+                    id: format!("totp:{}", e.id),
+                    // "123 456" is easier to read at a glance than "123456"
+                    title: format!(
+                        "{} {}",
+                        &code.code[..code.code.len() / 2],
+                        &code.code[code.code.len() / 2..]
+                    ),
+                    subtitle: Some(format!(
+                        "{} • {}s left • Enter to copy",
+                        e.label(),
+                        code.remaining
+                    )),
+                    icon: ResultIcon::Emoji("🔐".to_string()),
+                    category: ResultCategory::System,
+                    score: 90.0,
+                    aria_label: None,
+                }
+            })
+            .collect()
+    }
+
+    fn execute(&self, result_id: &str) -> Result<(), String> {
+        let id = result_id
+            .strip_prefix("totp:")
+            .ok_or("Invalid TOTP result")?;
+
+        let code = self
+            .entries
+            .read()
+            .iter()
+            .find(|e| e.id == id)
+            .map(|e| e.current().code)
+            .ok_or("TOTP account not found")?;
+
+        crate::clipboard::copy_text(&code)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rfc6238_sha1_vectors() {
+        let entry = TotpEntry {
+            id: "test".to_string(),
+            issuer: None,
+            account: "test".to_string(),
+            secret: b"12345678901234567890".to_vec(),
+            digits: 8,
+            period: 30,
+            algorithm: TotpAlgorithm::Sha1,
+        };
+
+        assert_eq!(entry.code_at(59), "94287082");
+        assert_eq!(entry.code_at(1111111109), "07081804");
+        assert_eq!(entry.code_at(20000000000), "65353130");
+    }
+
+    #[test]
+    fn parses_otpauth_uri() {
+        let entry = TotpEntry::from_uri(
+            "otpauth://totp/ACME%20Co:john@example.com?secret=JBSWY3DPEHPK3PXP&digits=6",
+        )
+        .unwrap();
+
+        assert_eq!(entry.issuer.as_deref(), Some("ACME Co"));
+        assert_eq!(entry.account, "john@example.com");
+        assert_eq!(entry.secret, b"Hello!\xde\xad\xbe\xef");
+    }
+}
@@ -0,0 +1,640 @@
+use super::{ResultCategory, ResultIcon, SearchContext, SearchProvider, SearchResult};
+use crate::vault::SecretVault;
+use async_trait::async_trait;
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Live status changes quickly; keep cached answers short-lived
+const CACHE_TTL: Duration = Duration::from_secs(60);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// One end of a flight
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FlightEndpoint {
+    pub airport: String,
+    pub iata: Option<String>,
+    pub scheduled: Option<String>,
+    pub estimated: Option<String>,
+    pub terminal: Option<String>,
+    pub gate: Option<String>,
+    /// Minutes
+    pub delay: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FlightStatus {
+    pub flight: String,
+    pub airline: Option<String>,
+    pub status: String,
+    pub departure: FlightEndpoint,
+    pub arrival: FlightEndpoint,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Journey {
+    pub origin: String,
+    pub destination: String,
+    pub departure: Option<String>,
+    pub arrival: Option<String>,
+    /// Minutes
+    pub departure_delay: Option<i64>,
+    /// Line names of each leg, e.g. ["ICE 707"]
+    pub lines: Vec<String>,
+    pub cancelled: bool,
+}
+
+/// A data source for flight status
+pub trait FlightSource: Send + Sync {
+    fn id(&self) -> &str;
+    fn name(&self) -> &str;
+    /// Vault key for the source's API key, if it needs one
+    fn api_key_name(&self) -> Option<&str>;
+    fn flight_status(&self, flight: &str, api_key: Option<&str>) -> Result<FlightStatus, String>;
+}
+
+/// A data source for train and public transport connections
+pub trait JourneySource: Send + Sync {
+    fn id(&self) -> &str;
+    fn name(&self) -> &str;
+    fn api_key_name(&self) -> Option<&str>;
+    fn journeys(&self, from: &str, to: &str, api_key: Option<&str>)
+        -> Result<Vec<Journey>, String>;
+    /// Page with the full timetable for a connection
+    fn timetable_url(&self, from: &str, to: &str) -> String;
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransportSourceInfo {
+    pub id: String,
+    pub name: String,
+    pub kind: String,
+    pub needs_api_key: bool,
+    pub configured: bool,
+}
+
+fn http_client() -> reqwest::blocking::Client {
+    reqwest::blocking::Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .build()
+        .unwrap_or_default()
+}
+
+/// Flight status from aviationstack.com (free API key required)
+pub struct AviationStackSource;
+
+#[derive(Deserialize)]
+struct AviationStackResponse {
+    #[serde(default)]
+    data: Vec<AviationStackFlight>,
+    error: Option<AviationStackError>,
+}
+
+#[derive(Deserialize)]
+struct AviationStackError {
+    message: String,
+}
+
+#[derive(Deserialize)]
+struct AviationStackFlight {
+    flight_status: Option<String>,
+    departure: AviationStackEndpoint,
+    arrival: AviationStackEndpoint,
+    airline: Option<AviationStackName>,
+    flight: AviationStackFlightCode,
+}
+
+#[derive(Deserialize)]
+struct AviationStackName {
+    name: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct AviationStackFlightCode {
+    iata: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct AviationStackEndpoint {
+    airport: Option<String>,
+    iata: Option<String>,
+    scheduled: Option<String>,
+    estimated: Option<String>,
+    terminal: Option<String>,
+    gate: Option<String>,
+    delay: Option<i64>,
+}
+
+impl From<AviationStackEndpoint> for FlightEndpoint {
+    fn from(e: AviationStackEndpoint) -> Self {
+        Self {
+            airport: e.airport.unwrap_or_default(),
+            iata: e.iata,
+            scheduled: e.scheduled,
+            estimated: e.estimated,
+            terminal: e.terminal,
+            gate: e.gate,
+            delay: e.delay,
+        }
+    }
+}
+
+impl FlightSource for AviationStackSource {
+    fn id(&self) -> &str {
+        "aviationstack"
+    }
+
+    fn name(&self) -> &str {
+        "aviationstack"
+    }
+
+    fn api_key_name(&self) -> Option<&str> {
+        Some("aviationstack_api_key")
+    }
+
+    fn flight_status(&self, flight: &str, api_key: Option<&str>) -> Result<FlightStatus, String> {
+        let api_key = api_key.ok_or("Add an aviationstack API key in Settings")?;
+        // The free plan is HTTP only
+        let response: AviationStackResponse = http_client()
+            .get("http://api.aviationstack.com/v1/flights")
+            .query(&[("access_key", api_key), ("flight_iata", flight)])
+            .send()
+            .and_then(|r| r.json())
+            .map_err(|e| format!("Failed to reach aviationstack: {}", e))?;
+        if let Some(error) = response.error {
+            return Err(error.message);
+        }
+
+        // Several days of the same flight may come back; the first is the latest
+        let found = response
+            .data
+            .into_iter()
+            .next()
+            .ok_or_else(|| format!("No flight {} found", flight))?;
+        Ok(FlightStatus {
+            flight: found.flight.iata.unwrap_or_else(|| flight.to_string()),
+            airline: found.airline.and_then(|a| a.name),
+            status: found.flight_status.unwrap_or_else(|| "unknown".to_string()),
+            departure: found.departure.into(),
+            arrival: found.arrival.into(),
+        })
+    }
+}
+
+/// Deutsche Bahn connections through the public transport.rest API (no key)
+pub struct DbTransportRestSource;
+
+const DB_REST_URL: &str = "https://v6.db.transport.rest";
+
+#[derive(Deserialize)]
+struct RestLocation {
+    id: Option<String>,
+    name: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct RestJourneys {
+    #[serde(default)]
+    journeys: Vec<RestJourney>,
+}
+
+#[derive(Deserialize)]
+struct RestJourney {
+    legs: Vec<RestLeg>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RestLeg {
+    origin: RestLocation,
+    destination: RestLocation,
+    departure: Option<String>,
+    planned_departure: Option<String>,
+    departure_delay: Option<i64>,
+    arrival: Option<String>,
+    planned_arrival: Option<String>,
+    line: Option<RestLine>,
+    #[serde(default)]
+    cancelled: bool,
+    #[serde(default)]
+    walking: bool,
+}
+
+#[derive(Deserialize)]
+struct RestLine {
+    name: Option<String>,
+}
+
+impl DbTransportRestSource {
+    fn station_id(&self, name: &str) -> Result<(String, String), String> {
+        let locations: Vec<RestLocation> = http_client()
+            .get(format!("{}/locations", DB_REST_URL))
+            .query(&[
+                ("query", name),
+                ("results", "1"),
+                ("addresses", "false"),
+                ("poi", "false"),
+            ])
+            .send()
+            .and_then(|r| r.error_for_status())
+            .and_then(|r| r.json())
+            .map_err(|e| format!("Failed to look up {}: {}", name, e))?;
+        locations
+            .into_iter()
+            .find_map(|l| Some((l.id?, l.name?)))
+            .ok_or_else(|| format!("No station found for \"{}\"", name))
+    }
+}
+
+impl JourneySource for DbTransportRestSource {
+    fn id(&self) -> &str {
+        "db"
+    }
+
+    fn name(&self) -> &str {
+        "Deutsche Bahn"
+    }
+
+    fn api_key_name(&self) -> Option<&str> {
+        None
+    }
+
+    fn journeys(
+        &self,
+        from: &str,
+        to: &str,
+        _api_key: Option<&str>,
+    ) -> Result<Vec<Journey>, String> {
+        let (from_id, from_name) = self.station_id(from)?;
+        let (to_id, to_name) = self.station_id(to)?;
+
+        let response: RestJourneys = http_client()
+            .get(format!("{}/journeys", DB_REST_URL))
+            .query(&[
+                ("from", from_id.as_str()),
+                ("to", to_id.as_str()),
+                ("results", "3"),
+            ])
+            .send()
+            .and_then(|r| r.error_for_status())
+            .and_then(|r| r.json())
+            .map_err(|e| format!("Failed to fetch connections: {}", e))?;
+
+        Ok(response
+            .journeys
+            .into_iter()
+            .filter_map(|journey| {
+                let legs: Vec<&RestLeg> = journey.legs.iter().filter(|l| !l.walking).collect();
+                let first = legs.first()?;
+                let last = legs.last()?;
+                Some(Journey {
+                    origin: first
+                        .origin
+                        .name
+                        .clone()
+                        .unwrap_or_else(|| from_name.clone()),
+                    destination: last
+                        .destination
+                        .name
+                        .clone()
+                        .unwrap_or_else(|| to_name.clone()),
+                    departure: first
+                        .departure
+                        .clone()
+                        .or_else(|| first.planned_departure.clone()),
+                    arrival: last
+                        .arrival
+                        .clone()
+                        .or_else(|| last.planned_arrival.clone()),
+                    departure_delay: first.departure_delay.map(|secs| secs / 60),
+                    lines: legs
+                        .iter()
+                        .filter_map(|l| l.line.as_ref()?.name.clone())
+                        .collect(),
+                    cancelled: legs.iter().any(|l| l.cancelled),
+                })
+            })
+            .collect())
+    }
+
+    fn timetable_url(&self, from: &str, to: &str) -> String {
+        format!(
+            "https://www.bahn.de/buchung/fahrplan/suche#so={}&zo={}",
+            urlencoding::encode(from),
+            urlencoding::encode(to)
+        )
+    }
+}
+
+enum TransportQuery {
+    Flight(String),
+    Train { from: String, to: String },
+}
+
+fn parse_query(query: &str) -> Option<TransportQuery> {
+    let lower = query.to_lowercase();
+    if let Some(code) = lower.strip_prefix("flight ") {
+        // "BA283" or "BA 283"
+        let code: String = code.split_whitespace().collect::<String>().to_uppercase();
+        let valid = code.len() >= 3
+            && code.len() <= 7
+            && code.chars().all(|c| c.is_ascii_alphanumeric())
+            && code.chars().last().map_or(false, |c| c.is_ascii_digit());
+        return valid.then_some(TransportQuery::Flight(code));
+    }
+
+    let rest = query
+        .get(..6)
+        .filter(|p| p.eq_ignore_ascii_case("train "))
+        .map(|_| &query[6..])?;
+    let (from, to) = ["→", "->", " to "]
+        .iter()
+        .find_map(|sep| rest.split_once(sep))?;
+    let (from, to) = (from.trim(), to.trim());
+    (from.len() >= 2 && to.len() >= 2).then(|| TransportQuery::Train {
+        from: from.to_string(),
+        to: to.to_string(),
+    })
+}
+
+fn format_time(timestamp: &str) -> String {
+    chrono::DateTime::parse_from_rfc3339(timestamp)
+        .map(|t| t.format("%H:%M").to_string())
+        .unwrap_or_else(|_| timestamp.to_string())
+}
+
+/// Flight status (`flight BA283`) and train connections (`train berlin → hamburg`)
+pub struct TransportProvider {
+    vault: Arc<SecretVault>,
+    flight_sources: Vec<Box<dyn FlightSource>>,
+    journey_sources: Vec<Box<dyn JourneySource>>,
+    cache: RwLock<HashMap<String, (Instant, Vec<SearchResult>)>>,
+}
+
+impl TransportProvider {
+    pub fn new(vault: Arc<SecretVault>) -> Self {
+        Self {
+            vault,
+            flight_sources: vec![Box::new(AviationStackSource)],
+            journey_sources: vec![Box::new(DbTransportRestSource)],
+            cache: RwLock::new(HashMap::new()),
+        }
+    }
+
+    fn api_key(&self, key_name: Option<&str>) -> Option<String> {
+        key_name.and_then(|name| self.vault.get(name))
+    }
+
+    pub fn sources(&self) -> Vec<TransportSourceInfo> {
+        let flights = self.flight_sources.iter().map(|s| TransportSourceInfo {
+            id: s.id().to_string(),
+            name: s.name().to_string(),
+            kind: "flight".to_string(),
+            needs_api_key: s.api_key_name().is_some(),
+            configured: s.api_key_name().is_none() || self.api_key(s.api_key_name()).is_some(),
+        });
+        let journeys = self.journey_sources.iter().map(|s| TransportSourceInfo {
+            id: s.id().to_string(),
+            name: s.name().to_string(),
+            kind: "train".to_string(),
+            needs_api_key: s.api_key_name().is_some(),
+            configured: s.api_key_name().is_none() || self.api_key(s.api_key_name()).is_some(),
+        });
+        flights.chain(journeys).collect()
+    }
+
+    fn key_name_for(&self, source_id: &str) -> Result<String, String> {
+        self.flight_sources
+            .iter()
+            .filter(|s| s.id() == source_id)
+            .map(|s| s.api_key_name())
+            .chain(
+                self.journey_sources
+                    .iter()
+                    .filter(|s| s.id() == source_id)
+                    .map(|s| s.api_key_name()),
+            )
+            .next()
+            .ok_or_else(|| format!("Unknown transport source: {}", source_id))?
+            .map(str::to_string)
+            .ok_or_else(|| format!("{} doesn't need an API key", source_id))
+    }
+
+    pub fn set_api_key(&self, source_id: &str, api_key: &str) -> Result<(), String> {
+        self.vault
+            .set(&self.key_name_for(source_id)?, api_key.trim())?;
+        self.refresh();
+        Ok(())
+    }
+
+    pub fn remove_api_key(&self, source_id: &str) -> Result<(), String> {
+        self.vault.delete(&self.key_name_for(source_id)?)?;
+        self.refresh();
+        Ok(())
+    }
+
+    /// Drop cached answers so the next search fetches live data
+    pub fn refresh(&self) {
+        self.cache.write().clear();
+    }
+
+    fn flight_results(&self, flight: &str) -> Vec<SearchResult> {
+        let url = format!(
+            "https://www.flightradar24.com/data/flights/{}",
+            flight.to_lowercase()
+        );
+        let mut errors = Vec::new();
+
+        for source in &self.flight_sources {
+            let key = self.api_key(source.api_key_name());
+            match source.flight_status(flight, key.as_deref()) {
+                Ok(status) => {
+                    let departure = status
+                        .departure
+                        .estimated
+                        .as_deref()
+                        .or(status.departure.scheduled.as_deref())
+                        .map(format_time)
+                        .unwrap_or_default();
+                    let arrival = status
+                        .arrival
+                        .estimated
+                        .as_deref()
+                        .or(status.arrival.scheduled.as_deref())
+                        .map(format_time)
+                        .unwrap_or_default();
+                    let mut subtitle = format!(
+                        "{} {} → {} {}",
+                        status
+                            .departure
+                            .iata
+                            .as_deref()
+                            .unwrap_or(&status.departure.airport),
+                        departure,
+                        status
+                            .arrival
+                            .iata
+                            .as_deref()
+                            .unwrap_or(&status.arrival.airport),
+                        arrival
+                    );
+                    if let Some(gate) = &status.departure.gate {
+                        subtitle = format!("{} • Gate {}", subtitle, gate);
+                    }
+                    if let Some(delay) = status.departure.delay.filter(|d| *d > 0) {
+                        subtitle = format!("{} • {} min late", subtitle, delay);
+                    }
+
+                    return vec![SearchResult {
+                        id: format!("transport:open:{}", urlencoding::encode(&url)),
+                        title: format!(
+                            "{} {} — {}",
+                            status.airline.as_deref().unwrap_or("Flight"),
+                            status.flight,
+                            status.status
+                        ),
+                        subtitle: Some(format!("{} • {}", subtitle, source.name())),
+                        icon: ResultIcon::Emoji("✈️".to_string()),
+                        category: ResultCategory::Plugin,
+                        score: 95.0,
+                        aria_label: None,
+                    }];
+                }
+                Err(e) => errors.push(e),
+            }
+        }
+
+        // No source could answer; still offer the flight in the browser
+        vec![SearchResult {
+            id: format!("transport:open:{}", urlencoding::encode(&url)),
+            title: format!("Track flight {}", flight),
+            subtitle: Some(errors.join(" • ")).filter(|s| !s.is_empty()),
+            icon: ResultIcon::Emoji("✈️".to_string()),
+            category: ResultCategory::Plugin,
+            score: 80.0,
+            aria_label: None,
+        }]
+    }
+
+    fn journey_results(&self, from: &str, to: &str) -> Vec<SearchResult> {
+        for source in &self.journey_sources {
+            let url = source.timetable_url(from, to);
+            let key = self.api_key(source.api_key_name());
+            let journeys = match source.journeys(from, to, key.as_deref()) {
+                Ok(journeys) if !journeys.is_empty() => journeys,
+                Ok(_) => continue,
+                Err(e) => {
+                    eprintln!("{}: {}", source.name(), e);
+                    continue;
+                }
+            };
+
+            return journeys
+                .iter()
+                .enumerate()
+                .map(|(i, journey)| {
+                    let mut subtitle = format!("{} → {}", journey.origin, journey.destination);
+                    if !journey.lines.is_empty() {
+                        subtitle = format!("{} • {}", subtitle, journey.lines.join(", "));
+                    }
+                    let status = if journey.cancelled {
+                        " (cancelled)".to_string()
+                    } else {
+                        match journey.departure_delay.filter(|d| *d > 0) {
+                            Some(delay) => format!(" (+{})", delay),
+                            None => String::new(),
+                        }
+                    };
+                    SearchResult {
+                        id: format!("transport:open:{}", urlencoding::encode(&url)),
+                        title: format!(
+                            "{}{} → {}",
+                            journey
+                                .departure
+                                .as_deref()
+                                .map(format_time)
+                                .unwrap_or_default(),
+                            status,
+                            journey
+                                .arrival
+                                .as_deref()
+                                .map(format_time)
+                                .unwrap_or_default()
+                        ),
+                        subtitle: Some(format!("{} • {}", subtitle, source.name())),
+                        icon: ResultIcon::Emoji("🚆".to_string()),
+                        category: ResultCategory::Plugin,
+                        score: 95.0 - i as f32,
+                        aria_label: None,
+                    }
+                })
+                .collect();
+        }
+        vec![]
+    }
+
+    fn search_blocking(&self, query: &str) -> Vec<SearchResult> {
+        let Some(parsed) = parse_query(query.trim()) else {
+            return vec![];
+        };
+        let key = query.trim().to_lowercase();
+        if let Some((fetched_at, results)) = self.cache.read().get(&key) {
+            if fetched_at.elapsed() < CACHE_TTL {
+                return results.clone();
+            }
+        }
+
+        let results = match parsed {
+            TransportQuery::Flight(flight) => self.flight_results(&flight),
+            TransportQuery::Train { from, to } => self.journey_results(&from, &to),
+        };
+        let mut cache = self.cache.write();
+        cache.retain(|_, (fetched_at, _)| fetched_at.elapsed() < CACHE_TTL);
+        cache.insert(key, (Instant::now(), results.clone()));
+        results
+    }
+}
+
+#[async_trait]
+impl SearchProvider for TransportProvider {
+    fn id(&self) -> &str {
+        "transport"
+    }
+
+    async fn search(&self, query: &str, ctx: &SearchContext) -> Vec<SearchResult> {
+        ctx.run_blocking(|| self.search_blocking(query))
+    }
+
+    fn execute(&self, result_id: &str) -> Result<(), String> {
+        let url = result_id
+            .strip_prefix("transport:open:")
+            .ok_or("Invalid transport result")?;
+        let url = urlencoding::decode(url).map_err(|e| e.to_string())?;
+        super::url::open_url(&url)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_flight_and_train_queries() {
+        assert!(matches!(
+            parse_query("flight ba 283"),
+            Some(TransportQuery::Flight(code)) if code == "BA283"
+        ));
+        assert!(parse_query("flight to").is_none());
+        assert!(matches!(
+            parse_query("train berlin → hamburg"),
+            Some(TransportQuery::Train { from, to }) if from == "berlin" && to == "hamburg"
+        ));
+        assert!(matches!(
+            parse_query("Train Köln to München Hbf"),
+            Some(TransportQuery::Train { from, to }) if from == "Köln" && to == "München Hbf"
+        ));
+        assert!(parse_query("train berlin").is_none());
+    }
+}
@@ -1,4 +1,5 @@
-use super::{ResultCategory, ResultIcon, SearchProvider, SearchResult};
+use super::{ResultCategory, ResultIcon, SearchContext, SearchProvider, SearchResult};
+use async_trait::async_trait;
 use url::Url;
 
 pub struct UrlProvider;
@@ -101,12 +102,13 @@ impl UrlProvider {
     }
 }
 
+#[async_trait]
 impl SearchProvider for UrlProvider {
     fn id(&self) -> &str {
         "url"
     }
 
-    fn search(&self, query: &str) -> Vec<SearchResult> {
+    async fn search(&self, query: &str, _ctx: &SearchContext) -> Vec<SearchResult> {
         if !Self::is_url_like(query) {
             return vec![];
         }
@@ -124,39 +126,53 @@ impl SearchProvider for UrlProvider {
             icon: ResultIcon::Emoji("🌐".to_string()),
             category: ResultCategory::URL,
             score: 95.0, // High priority for URLs
+            aria_label: None,
         }]
     }
 
     fn execute(&self, result_id: &str) -> Result<(), String> {
         if let Some(url) = result_id.strip_prefix("url:") {
-            #[cfg(target_os = "linux")]
-            {
-                std::process::Command::new("xdg-open")
-                    .arg(url)
-                    .spawn()
-                    .map_err(|e| format!("Failed to open URL: {}", e))?;
-            }
-
-            #[cfg(target_os = "macos")]
-            {
-                std::process::Command::new("open")
-                    .arg(url)
-                    .spawn()
-                    .map_err(|e| format!("Failed to open URL: {}", e))?;
-            }
-
-            #[cfg(target_os = "windows")]
-            {
-                std::process::Command::new("cmd")
-                    .args(["/C", "start", "", url])
-                    .spawn()
-                    .map_err(|e| format!("Failed to open URL: {}", e))?;
-            }
-
-            Ok(())
+            open_url(url)
         } else {
             Err("Invalid URL result".to_string())
         }
     }
 }
 
+/// Open a URL in the default browser
+pub fn open_url(url: &str) -> Result<(), String> {
+    #[cfg(target_os = "linux")]
+    {
+        std::process::Command::new("xdg-open")
+            .arg(url)
+            .spawn()
+            .map_err(|e| format!("Failed to open URL: {}", e))?;
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        std::process::Command::new("open")
+            .arg(url)
+            .spawn()
+            .map_err(|e| format!("Failed to open URL: {}", e))?;
+    }
+
+    // Not through `cmd /C start`, which would run anything after a `&`
+    #[cfg(target_os = "windows")]
+    {
+        tauri_plugin_opener::open_url(url, None::<&str>)
+            .map_err(|e| format!("Failed to open URL: {}", e))?;
+    }
+
+    Ok(())
+}
+
+/// Open a URL that came from elsewhere, like a feed, only if it's a web page;
+/// `file:` and app links are refused
+pub fn open_web_url(url: &str) -> Result<(), String> {
+    let parsed = Url::parse(url).map_err(|e| format!("Invalid URL: {}", e))?;
+    if !matches!(parsed.scheme(), "http" | "https") {
+        return Err(format!("Won't open {} links", parsed.scheme()));
+    }
+    open_url(parsed.as_str())
+}
@@ -0,0 +1,659 @@
+use super::{
+    ResultAction, ResultCategory, ResultIcon, SearchContext, SearchProvider, SearchResult,
+};
+use async_trait::async_trait;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::process::Command;
+use std::time::{Duration, Instant};
+
+/// How often the background thread re-reads VPN states
+pub const POLL_INTERVAL: Duration = Duration::from_secs(15);
+
+/// Reading states spawns a CLI per tool; reuse them while typing
+const CACHE_TTL: Duration = Duration::from_secs(5);
+
+const KEYWORDS: &[&str] = &["vpn", "tailscale", "wireguard", "openvpn"];
+const EXIT_NODE_KEYWORDS: &[&str] = &["exit node", "tailscale exit"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VpnKind {
+    WireGuard,
+    OpenVpn,
+    Tailscale,
+    /// A VPN managed by the OS network settings (IKEv2, L2TP, ...)
+    Other,
+}
+
+impl VpnKind {
+    fn id(self) -> &'static str {
+        match self {
+            Self::WireGuard => "wireguard",
+            Self::OpenVpn => "openvpn",
+            Self::Tailscale => "tailscale",
+            Self::Other => "other",
+        }
+    }
+
+    fn parse(id: &str) -> Option<Self> {
+        [Self::WireGuard, Self::OpenVpn, Self::Tailscale, Self::Other]
+            .into_iter()
+            .find(|k| k.id() == id)
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Self::WireGuard => "WireGuard",
+            Self::OpenVpn => "OpenVPN",
+            Self::Tailscale => "Tailscale",
+            Self::Other => "VPN",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VpnConnection {
+    pub kind: VpnKind,
+    pub name: String,
+    /// `None` when the tool can't report whether the profile is up
+    pub connected: Option<bool>,
+}
+
+/// A Tailscale peer that offers to route all traffic
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExitNode {
+    pub name: String,
+    pub ip: String,
+    pub online: bool,
+    pub active: bool,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct VpnStatus {
+    pub connections: Vec<VpnConnection>,
+    pub exit_nodes: Vec<ExitNode>,
+}
+
+/// Stdout of a command that ran successfully
+fn output(command: &mut Command) -> Option<String> {
+    let output = command.output().ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+fn spawn(command: &mut Command) -> Result<(), String> {
+    command
+        .spawn()
+        .map(|_| ())
+        .map_err(|e| format!("Failed to run {:?}: {}", command.get_program(), e))
+}
+
+fn tailscale() -> Command {
+    #[cfg(target_os = "macos")]
+    {
+        // The App Store build doesn't put the CLI on PATH
+        let bundled = "/Applications/Tailscale.app/Contents/MacOS/Tailscale";
+        if std::path::Path::new(bundled).exists() {
+            return Command::new(bundled);
+        }
+    }
+    Command::new("tailscale")
+}
+
+/// Connection state and exit nodes from `tailscale status --json`
+fn parse_tailscale(json: &str) -> Option<(VpnConnection, Vec<ExitNode>)> {
+    let status: serde_json::Value = serde_json::from_str(json).ok()?;
+    let connection = VpnConnection {
+        kind: VpnKind::Tailscale,
+        name: "Tailscale".to_string(),
+        connected: Some(status["BackendState"] == "Running"),
+    };
+
+    let mut exit_nodes: Vec<ExitNode> = status["Peer"]
+        .as_object()
+        .into_iter()
+        .flat_map(|peers| peers.values())
+        .filter(|peer| peer["ExitNodeOption"].as_bool().unwrap_or(false))
+        .filter_map(|peer| {
+            let dns_name = peer["DNSName"].as_str().unwrap_or_default();
+            let name = match dns_name.split('.').next() {
+                Some(label) if !label.is_empty() => label,
+                _ => peer["HostName"].as_str()?,
+            };
+            Some(ExitNode {
+                name: name.to_string(),
+                ip: peer["TailscaleIPs"][0].as_str()?.to_string(),
+                online: peer["Online"].as_bool().unwrap_or(false),
+                active: peer["ExitNode"].as_bool().unwrap_or(false),
+            })
+        })
+        .collect();
+    exit_nodes.sort_by(|a, b| b.online.cmp(&a.online).then_with(|| a.name.cmp(&b.name)));
+
+    Some((connection, exit_nodes))
+}
+
+/// Split a line of `nmcli -t` output, where literal colons are escaped
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn nmcli_fields(line: &str) -> Vec<String> {
+    let mut fields = vec![String::new()];
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => fields.last_mut().unwrap().extend(chars.next()),
+            ':' => fields.push(String::new()),
+            _ => fields.last_mut().unwrap().push(c),
+        }
+    }
+    fields
+}
+
+/// VPN profiles in `nmcli -t -f NAME,TYPE,ACTIVE connection show` output.
+/// `service_type` looks up which plugin a generic "vpn" profile uses.
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_nmcli(output: &str, service_type: impl Fn(&str) -> String) -> Vec<VpnConnection> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let fields = nmcli_fields(line);
+            let [name, kind, active] = fields.as_slice() else {
+                return None;
+            };
+            let kind = match kind.as_str() {
+                "wireguard" => VpnKind::WireGuard,
+                "vpn" if service_type(name).ends_with(".openvpn") => VpnKind::OpenVpn,
+                "vpn" => VpnKind::Other,
+                _ => return None,
+            };
+            Some(VpnConnection {
+                kind,
+                name: name.clone(),
+                connected: Some(active == "yes"),
+            })
+        })
+        .collect()
+}
+
+/// VPN services in `scutil --nc list` output, e.g.
+/// `* (Connected) 7E5A… VPN (com.wireguard.macos) "Home" [VPN:com.wireguard.macos]`
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+fn parse_scutil(output: &str) -> Vec<VpnConnection> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let state = line.split_once('(')?.1.split_once(')')?.0;
+            let mut quoted = line.split('"');
+            let name = quoted.nth(1)?;
+            let lower = line.to_lowercase();
+            let kind = if lower.contains("wireguard") {
+                VpnKind::WireGuard
+            } else if lower.contains("openvpn") {
+                VpnKind::OpenVpn
+            } else if lower.contains("tailscale") {
+                // Reported by the Tailscale CLI instead
+                return None;
+            } else {
+                VpnKind::Other
+            };
+            Some(VpnConnection {
+                kind,
+                name: name.to_string(),
+                connected: Some(state == "Connected"),
+            })
+        })
+        .collect()
+}
+
+#[cfg(target_os = "linux")]
+fn system_connections() -> Vec<VpnConnection> {
+    let Some(list) =
+        output(Command::new("nmcli").args(["-t", "-f", "NAME,TYPE,ACTIVE", "connection", "show"]))
+    else {
+        return Vec::new();
+    };
+    parse_nmcli(&list, |name| {
+        output(Command::new("nmcli").args([
+            "-g",
+            "vpn.service-type",
+            "connection",
+            "show",
+            "id",
+            name,
+        ]))
+        .unwrap_or_default()
+        .trim()
+        .to_string()
+    })
+}
+
+#[cfg(target_os = "macos")]
+fn system_connections() -> Vec<VpnConnection> {
+    output(Command::new("scutil").args(["--nc", "list"]))
+        .map(|list| parse_scutil(&list))
+        .unwrap_or_default()
+}
+
+/// Running WireGuard tunnels (each is a `WireGuardTunnel$<name>` service)
+/// and OpenVPN GUI profiles, whose state the GUI doesn't expose
+#[cfg(target_os = "windows")]
+fn system_connections() -> Vec<VpnConnection> {
+    let mut connections = Vec::new();
+
+    if let Some(services) = output(Command::new("sc").args(["query", "state=", "all"])) {
+        let mut current: Option<String> = None;
+        for line in services.lines().map(str::trim) {
+            if let Some(service) = line.strip_prefix("SERVICE_NAME:") {
+                current = service
+                    .trim()
+                    .strip_prefix("WireGuardTunnel$")
+                    .map(String::from);
+            } else if let (Some(name), Some(state)) = (&current, line.strip_prefix("STATE")) {
+                connections.push(VpnConnection {
+                    kind: VpnKind::WireGuard,
+                    name: name.clone(),
+                    connected: Some(state.contains("RUNNING")),
+                });
+                current = None;
+            }
+        }
+    }
+
+    let profiles = dirs::home_dir()
+        .unwrap_or_default()
+        .join("OpenVPN")
+        .join("config");
+    for entry in walkdir::WalkDir::new(profiles)
+        .max_depth(2)
+        .into_iter()
+        .flatten()
+    {
+        let path = entry.path();
+        if path
+            .extension()
+            .is_some_and(|e| e.eq_ignore_ascii_case("ovpn"))
+        {
+            if let Some(name) = path.file_stem() {
+                connections.push(VpnConnection {
+                    kind: VpnKind::OpenVpn,
+                    name: name.to_string_lossy().into_owned(),
+                    connected: None,
+                });
+            }
+        }
+    }
+
+    connections
+}
+
+/// Read every detected VPN and the Tailscale exit nodes
+pub fn read_status() -> VpnStatus {
+    let mut status = VpnStatus {
+        connections: system_connections(),
+        exit_nodes: Vec::new(),
+    };
+    if let Some((connection, exit_nodes)) = output(tailscale().args(["status", "--json"]))
+        .as_deref()
+        .and_then(parse_tailscale)
+    {
+        status.connections.push(connection);
+        status.exit_nodes = exit_nodes;
+    }
+    status
+}
+
+#[cfg(target_os = "linux")]
+fn set_system_connected(connection: &VpnConnection, connect: bool) -> Result<(), String> {
+    let verb = if connect { "up" } else { "down" };
+    spawn(Command::new("nmcli").args(["connection", verb, "id", &connection.name]))
+}
+
+#[cfg(target_os = "macos")]
+fn set_system_connected(connection: &VpnConnection, connect: bool) -> Result<(), String> {
+    let verb = if connect { "start" } else { "stop" };
+    spawn(Command::new("scutil").args(["--nc", verb, &connection.name]))
+}
+
+#[cfg(target_os = "windows")]
+fn set_system_connected(connection: &VpnConnection, connect: bool) -> Result<(), String> {
+    match (connection.kind, connect) {
+        // Tunnel services need admin rights; starting one needs its config,
+        // which only the WireGuard app can read
+        (VpnKind::WireGuard, false) => spawn(Command::new("powershell").args([
+            "-NoProfile",
+            "-Command",
+            &format!(
+                "Start-Process wireguard -Verb RunAs -ArgumentList '/uninstalltunnelservice','{}'",
+                connection.name.replace('\'', "''")
+            ),
+        ])),
+        (VpnKind::WireGuard, true) => spawn(&mut Command::new("wireguard")),
+        (VpnKind::OpenVpn, _) => {
+            let verb = if connect { "connect" } else { "disconnect" };
+            let profile = format!("{}.ovpn", connection.name);
+            spawn(Command::new("openvpn-gui").args(["--command", verb, &profile]))
+        }
+        _ => Err(format!("Can't control {}", connection.name)),
+    }
+}
+
+fn set_connected(connection: &VpnConnection, connect: bool) -> Result<(), String> {
+    match connection.kind {
+        VpnKind::Tailscale => spawn(tailscale().arg(if connect { "up" } else { "down" })),
+        _ => set_system_connected(connection, connect),
+    }
+}
+
+/// Route traffic through the exit node with this Tailscale IP, or stop
+/// using one when `ip` is empty
+fn set_exit_node(ip: &str) -> Result<(), String> {
+    spawn(tailscale().args(["set", &format!("--exit-node={}", ip)]))
+}
+
+fn icon(connected: Option<bool>) -> ResultIcon {
+    ResultIcon::Emoji(
+        if connected == Some(true) {
+            "🔒"
+        } else {
+            "🔓"
+        }
+        .to_string(),
+    )
+}
+
+/// `vpn` lists configured VPNs (WireGuard, OpenVPN, Tailscale and the OS's
+/// own) and toggles them; `exit node` picks a Tailscale exit node.
+pub struct VpnProvider {
+    cached: Mutex<Option<(Instant, VpnStatus)>>,
+}
+
+impl VpnProvider {
+    pub fn new() -> Self {
+        Self {
+            cached: Mutex::new(None),
+        }
+    }
+
+    /// The current states, re-read when the cached ones are stale or
+    /// `refresh` is set
+    pub fn status(&self, refresh: bool) -> VpnStatus {
+        if let Some((read_at, status)) = self.cached.lock().as_ref() {
+            if !refresh && read_at.elapsed() < CACHE_TTL {
+                return status.clone();
+            }
+        }
+        let status = read_status();
+        *self.cached.lock() = Some((Instant::now(), status.clone()));
+        status
+    }
+
+    /// Re-read the states, returning them if they changed since the last read
+    pub fn poll(&self) -> Option<VpnStatus> {
+        let previous = self.cached.lock().as_ref().map(|(_, s)| s.clone());
+        let status = self.status(true);
+        match previous {
+            Some(previous) if previous != status => Some(status),
+            _ => None,
+        }
+    }
+
+    fn connection_result(
+        connection: &VpnConnection,
+        status: &VpnStatus,
+        score: f32,
+    ) -> SearchResult {
+        let mut parts = vec![connection.kind.label().to_string()];
+        parts.push(
+            match connection.connected {
+                Some(true) => "Connected • Enter to disconnect",
+                Some(false) => "Disconnected • Enter to connect",
+                None => "Enter to connect",
+            }
+            .to_string(),
+        );
+        if connection.kind == VpnKind::Tailscale {
+            if let Some(node) = status.exit_nodes.iter().find(|n| n.active) {
+                parts.push(format!("Exit node {}", node.name));
+            }
+        }
+
+        SearchResult {
+            id: format!("vpn:{}:{}", connection.kind.id(), connection.name),
+            title: connection.name.clone(),
+            subtitle: Some(parts.join(" • ")),
+            icon: icon(connection.connected),
+            category: ResultCategory::System,
+            score,
+            aria_label: None,
+        }
+    }
+
+    fn exit_node_results(status: &VpnStatus, filter: &str) -> Vec<SearchResult> {
+        let using_one = status.exit_nodes.iter().any(|n| n.active);
+        let mut results = vec![SearchResult {
+            id: "vpn:exit-node:".to_string(),
+            title: "No exit node".to_string(),
+            subtitle: Some(
+                if using_one {
+                    "Route traffic directly"
+                } else {
+                    "Current"
+                }
+                .to_string(),
+            ),
+            icon: ResultIcon::Emoji("🌐".to_string()),
+            category: ResultCategory::System,
+            score: 80.0,
+            aria_label: None,
+        }];
+
+        let nodes = status
+            .exit_nodes
+            .iter()
+            .filter(|n| n.name.to_lowercase().contains(filter));
+        for (i, node) in nodes.enumerate() {
+            let state = match (node.active, node.online) {
+                (true, _) => "Current",
+                (false, true) => "Online",
+                (false, false) => "Offline",
+            };
+            results.push(SearchResult {
+                id: format!("vpn:exit-node:{}", node.ip),
+                title: node.name.clone(),
+                subtitle: Some(format!("Exit node • {} • {}", state, node.ip)),
+                icon: ResultIcon::Emoji("🛰️".to_string()),
+                category: ResultCategory::System,
+                score: 90.0 - i as f32 * 0.1,
+                aria_label: None,
+            });
+        }
+        results
+    }
+
+    fn results(status: &VpnStatus, query: &str) -> Vec<SearchResult> {
+        if let Some(rest) = EXIT_NODE_KEYWORDS
+            .iter()
+            .find_map(|k| query.strip_prefix(k))
+        {
+            if status.exit_nodes.is_empty() {
+                return vec![];
+            }
+            return Self::exit_node_results(status, rest.trim());
+        }
+
+        // "vpn home" filters by name; "tailscale" and friends by kind
+        let (keyword, filter) = query.split_once(' ').unwrap_or((query, ""));
+        let filter = filter.trim();
+        let connections: Vec<&VpnConnection> = status
+            .connections
+            .iter()
+            .filter(|c| keyword == "vpn" || c.kind.id().starts_with(keyword))
+            .filter(|c| c.name.to_lowercase().contains(filter))
+            .collect();
+
+        if connections.is_empty() {
+            if !filter.is_empty() {
+                return vec![];
+            }
+            return vec![SearchResult {
+                id: "vpn:none".to_string(),
+                title: "No VPNs found".to_string(),
+                subtitle: Some(
+                    "Looked for WireGuard, OpenVPN, Tailscale and system VPNs".to_string(),
+                ),
+                icon: ResultIcon::Emoji("🔓".to_string()),
+                category: ResultCategory::System,
+                score: 70.0,
+                aria_label: None,
+            }];
+        }
+
+        connections
+            .into_iter()
+            .enumerate()
+            .map(|(i, c)| Self::connection_result(c, status, 90.0 - i as f32 * 0.1))
+            .collect()
+    }
+
+    fn connection(&self, result_id: &str) -> Result<VpnConnection, String> {
+        let (kind, name) = result_id
+            .strip_prefix("vpn:")
+            .and_then(|rest| rest.split_once(':'))
+            .ok_or("Invalid VPN result")?;
+        let kind = VpnKind::parse(kind).ok_or("Invalid VPN result")?;
+        self.status(false)
+            .connections
+            .into_iter()
+            .find(|c| c.kind == kind && c.name == name)
+            .ok_or_else(|| format!("VPN {} no longer exists", name))
+    }
+
+    fn toggle(&self, result_id: &str, connect: Option<bool>) -> Result<(), String> {
+        let connection = self.connection(result_id)?;
+        let connect = connect.unwrap_or(connection.connected != Some(true));
+        set_connected(&connection, connect)?;
+        // Pick up the new state on the next search or poll
+        *self.cached.lock() = None;
+        Ok(())
+    }
+}
+
+impl Default for VpnProvider {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn matches(query: &str) -> bool {
+    if EXIT_NODE_KEYWORDS.iter().any(|k| query.starts_with(k)) {
+        return true;
+    }
+    let keyword = query.split(' ').next().unwrap_or_default();
+    if KEYWORDS.contains(&keyword) {
+        return true;
+    }
+    // A partial keyword, while nothing follows it
+    query.len() >= 3 && KEYWORDS.iter().any(|k| k.starts_with(query))
+}
+
+#[async_trait]
+impl SearchProvider for VpnProvider {
+    fn id(&self) -> &str {
+        "vpn"
+    }
+
+    async fn search(&self, query: &str, ctx: &SearchContext) -> Vec<SearchResult> {
+        let query = query.trim().to_lowercase();
+        if !matches(&query) {
+            return vec![];
+        }
+        // Complete a partial keyword so "tails" lists Tailscale
+        let query = match KEYWORDS.iter().find(|k| k.starts_with(query.as_str())) {
+            Some(keyword) => keyword.to_string(),
+            None => query,
+        };
+
+        match ctx.run_blocking(|| Some(self.status(false))) {
+            Some(status) => Self::results(&status, &query),
+            None => vec![],
+        }
+    }
+
+    fn execute(&self, result_id: &str) -> Result<(), String> {
+        if result_id == "vpn:none" {
+            return Ok(());
+        }
+        if let Some(ip) = result_id.strip_prefix("vpn:exit-node:") {
+            set_exit_node(ip)?;
+            *self.cached.lock() = None;
+            return Ok(());
+        }
+        self.toggle(result_id, None)
+    }
+
+    fn actions(&self, result_id: &str) -> Vec<ResultAction> {
+        if result_id == "vpn:none" || result_id.starts_with("vpn:exit-node:") {
+            return Vec::new();
+        }
+        vec![
+            ResultAction::new("connect", "Connect"),
+            ResultAction::new("disconnect", "Disconnect"),
+        ]
+    }
+
+    fn execute_action(&self, result_id: &str, action_id: &str) -> Result<(), String> {
+        match action_id {
+            "connect" => self.toggle(result_id, Some(true)),
+            "disconnect" => self.toggle(result_id, Some(false)),
+            _ => Err(format!("Unknown action: {}", action_id)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_system_vpn_lists() {
+        let nmcli = "Home\\: WG:wireguard:yes\nOffice:vpn:no\nWi-Fi:802-11-wireless:yes\n";
+        let connections = parse_nmcli(nmcli, |_| "org.freedesktop.NetworkManager.openvpn".into());
+        assert_eq!(connections.len(), 2);
+        assert_eq!(connections[0].name, "Home: WG");
+        assert_eq!(connections[0].connected, Some(true));
+        assert_eq!(connections[1].kind, VpnKind::OpenVpn);
+
+        let scutil = "Available network connection services in the current set (*=enabled):\n\
+            * (Connected)      7E5A VPN (com.wireguard.macos) \"Home\" [VPN:com.wireguard.macos]\n\
+            * (Disconnected)   1B2C IPSec \"Work\" [IPSec]\n";
+        let connections = parse_scutil(scutil);
+        assert_eq!(connections.len(), 2);
+        assert_eq!(connections[0].kind, VpnKind::WireGuard);
+        assert_eq!(connections[1].connected, Some(false));
+    }
+
+    #[test]
+    fn lists_tailscale_exit_nodes() {
+        let json = r#"{
+            "BackendState": "Running",
+            "Peer": {
+                "a": {"DNSName": "nas.tail1.ts.net.", "HostName": "NAS",
+                      "TailscaleIPs": ["100.64.0.2"], "Online": false,
+                      "ExitNodeOption": true, "ExitNode": false},
+                "b": {"DNSName": "vps.tail1.ts.net.", "HostName": "vps",
+                      "TailscaleIPs": ["100.64.0.3"], "Online": true,
+                      "ExitNodeOption": true, "ExitNode": true},
+                "c": {"DNSName": "phone.tail1.ts.net.", "TailscaleIPs": ["100.64.0.4"],
+                      "Online": true, "ExitNodeOption": false}
+            }
+        }"#;
+        let (connection, nodes) = parse_tailscale(json).unwrap();
+        assert_eq!(connection.connected, Some(true));
+        let names: Vec<&str> = nodes.iter().map(|n| n.name.as_str()).collect();
+        assert_eq!(names, ["vps", "nas"]);
+        assert!(nodes[0].active);
+        assert!(matches("exit node") && matches("tails") && !matches("vpnx"));
+    }
+}
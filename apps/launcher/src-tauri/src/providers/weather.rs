@@ -0,0 +1,183 @@
+use super::{ResultCategory, ResultIcon, SearchContext, SearchProvider, SearchResult};
+use crate::weather::{AirQualityReport, Location, WeatherClient, WeatherReport};
+use async_trait::async_trait;
+use std::sync::Arc;
+
+const MIN_PLACE_LEN: usize = 3;
+
+enum WeatherQuery<'a> {
+    Weather(&'a str),
+    AirQuality(&'a str),
+}
+
+fn parse_query(query: &str) -> Option<WeatherQuery<'_>> {
+    let lower = query.to_lowercase();
+    let place = |prefix: &str| {
+        if !lower.starts_with(prefix) {
+            return None;
+        }
+        let rest = query.get(prefix.len()..)?.trim_start();
+        // "weather in tokyo"
+        let rest = match rest.get(..3) {
+            Some(word) if word.eq_ignore_ascii_case("in ") => &rest[3..],
+            _ => rest,
+        };
+        Some(rest.trim())
+    };
+
+    let parsed = if let Some(place) = place("weather:").or_else(|| place("weather ")) {
+        WeatherQuery::Weather(place)
+    } else if let Some(place) = place("aqi:")
+        .or_else(|| place("aqi "))
+        .or_else(|| place("air quality "))
+    {
+        WeatherQuery::AirQuality(place)
+    } else {
+        return None;
+    };
+
+    match parsed {
+        WeatherQuery::Weather(p) | WeatherQuery::AirQuality(p) if p.len() < MIN_PLACE_LEN => None,
+        parsed => Some(parsed),
+    }
+}
+
+/// Inline weather and air quality, e.g. "weather tokyo" or "aqi berlin"
+pub struct WeatherProvider {
+    client: Arc<WeatherClient>,
+}
+
+impl WeatherProvider {
+    pub fn new(client: Arc<WeatherClient>) -> Self {
+        Self { client }
+    }
+
+    fn forecast_url(location: &Location, overlay: Option<&str>) -> String {
+        let position = format!("{:.3},{:.3},9", location.latitude, location.longitude);
+        match overlay {
+            Some(overlay) => format!("https://www.windy.com/?{},{}", overlay, position),
+            None => format!("https://www.windy.com/?{}", position),
+        }
+    }
+
+    fn weather_result(report: &WeatherReport) -> SearchResult {
+        let current = &report.current;
+        let mut subtitle = format!(
+            "{} • Feels like {:.0}° • Humidity {:.0}% • Wind {:.0} km/h",
+            current.description, current.apparent_temperature, current.humidity, current.wind_speed
+        );
+        if let Some(today) = report.daily.first() {
+            subtitle = format!(
+                "{} • H {:.0}° L {:.0}°",
+                subtitle, today.temperature_max, today.temperature_min
+            );
+        }
+
+        SearchResult {
+            id: format!(
+                "weather:open:{}",
+                urlencoding::encode(&Self::forecast_url(&report.location, None))
+            ),
+            title: format!(
+                "{:.0}°C in {}",
+                current.temperature,
+                report.location.label()
+            ),
+            subtitle: Some(subtitle),
+            icon: ResultIcon::Emoji(current.icon.clone()),
+            category: ResultCategory::Plugin,
+            score: 95.0,
+            aria_label: None,
+        }
+    }
+
+    fn air_quality_result(report: &AirQualityReport) -> SearchResult {
+        let aqi = report
+            .us_aqi
+            .map(|aqi| format!("AQI {:.0}", aqi))
+            .unwrap_or_else(|| "AQI unavailable".to_string());
+        let mut details = vec![report.category.clone()];
+        if let Some(pm2_5) = report.pm2_5 {
+            details.push(format!("PM2.5 {:.1} µg/m³", pm2_5));
+        }
+        if let Some(pm10) = report.pm10 {
+            details.push(format!("PM10 {:.1} µg/m³", pm10));
+        }
+
+        SearchResult {
+            id: format!(
+                "weather:open:{}",
+                urlencoding::encode(&Self::forecast_url(&report.location, Some("pm2p5")))
+            ),
+            title: format!("{} in {}", aqi, report.location.label()),
+            subtitle: Some(details.join(" • ")),
+            icon: ResultIcon::Emoji("🌬️".to_string()),
+            category: ResultCategory::Plugin,
+            score: 95.0,
+            aria_label: None,
+        }
+    }
+
+    fn search_blocking(&self, query: &str) -> Vec<SearchResult> {
+        let result = match parse_query(query.trim()) {
+            Some(WeatherQuery::Weather(place)) => self
+                .client
+                .weather(place)
+                .map(|report| Self::weather_result(&report)),
+            Some(WeatherQuery::AirQuality(place)) => self
+                .client
+                .air_quality(place)
+                .map(|report| Self::air_quality_result(&report)),
+            None => return vec![],
+        };
+
+        match result {
+            Ok(result) => vec![result],
+            Err(e) => {
+                eprintln!("Weather lookup failed: {}", e);
+                vec![]
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl SearchProvider for WeatherProvider {
+    fn id(&self) -> &str {
+        "weather"
+    }
+
+    async fn search(&self, query: &str, ctx: &SearchContext) -> Vec<SearchResult> {
+        ctx.run_blocking(|| self.search_blocking(query))
+    }
+
+    fn execute(&self, result_id: &str) -> Result<(), String> {
+        let url = result_id
+            .strip_prefix("weather:open:")
+            .ok_or("Invalid weather result")?;
+        let url = urlencoding::decode(url).map_err(|e| e.to_string())?;
+        super::url::open_url(&url)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn place(query: &str) -> Option<(&'static str, &str)> {
+        parse_query(query).map(|q| match q {
+            WeatherQuery::Weather(p) => ("weather", p),
+            WeatherQuery::AirQuality(p) => ("aqi", p),
+        })
+    }
+
+    #[test]
+    fn parses_inline_weather_queries() {
+        assert_eq!(place("weather tokyo"), Some(("weather", "tokyo")));
+        assert_eq!(place("Weather in New York"), Some(("weather", "New York")));
+        assert_eq!(place("aqi berlin"), Some(("aqi", "berlin")));
+        assert_eq!(place("air quality in Delhi"), Some(("aqi", "Delhi")));
+        assert_eq!(place("weather to"), None);
+        assert_eq!(place("weatherman"), None);
+    }
+}
@@ -1,4 +1,5 @@
-use super::{ResultCategory, ResultIcon, SearchProvider, SearchResult};
+use super::{ResultCategory, ResultIcon, SearchContext, SearchProvider, SearchResult};
+use async_trait::async_trait;
 
 /// Search engines supported for web search
 #[derive(Clone, Copy)]
@@ -12,7 +13,20 @@ pub enum SearchEngine {
 }
 
 impl SearchEngine {
-    fn name(&self) -> &'static str {
+    /// The engine for a lowercase name as used in result ids, e.g. `duckduckgo`
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "google" => Some(SearchEngine::Google),
+            "duckduckgo" => Some(SearchEngine::DuckDuckGo),
+            "bing" => Some(SearchEngine::Bing),
+            "github" => Some(SearchEngine::GitHub),
+            "stackoverflow" | "stack overflow" => Some(SearchEngine::StackOverflow),
+            "youtube" => Some(SearchEngine::YouTube),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
         match self {
             SearchEngine::Google => "Google",
             SearchEngine::DuckDuckGo => "DuckDuckGo",
@@ -23,7 +37,7 @@ impl SearchEngine {
         }
     }
 
-    fn icon(&self) -> &'static str {
+    pub fn icon(&self) -> &'static str {
         match self {
             SearchEngine::Google => "🔍",
             SearchEngine::DuckDuckGo => "🦆",
@@ -34,7 +48,7 @@ impl SearchEngine {
         }
     }
 
-    fn search_url(&self, query: &str) -> String {
+    pub fn search_url(&self, query: &str) -> String {
         let encoded_query = urlencoding::encode(query);
         match self {
             SearchEngine::Google => format!("https://www.google.com/search?q={}", encoded_query),
@@ -64,19 +78,17 @@ impl SearchEngine {
     }
 }
 
-pub struct WebSearchProvider {
-    default_engine: SearchEngine,
-}
+/// Searches the web through engine shortcuts like `g:` or `yt:`; searching
+/// the web for any query is a fallback, see `FallbackProvider`
+pub struct WebSearchProvider;
 
 impl WebSearchProvider {
     pub fn new() -> Self {
-        Self {
-            default_engine: SearchEngine::Google,
-        }
+        Self
     }
 
     /// Check if query matches a search engine shortcut
-    fn detect_engine_shortcut(query: &str) -> Option<(SearchEngine, &str)> {
+    pub fn detect_engine_shortcut(query: &str) -> Option<(SearchEngine, &str)> {
         let query_lower = query.to_lowercase();
 
         let shortcuts = [
@@ -107,64 +119,36 @@ impl WebSearchProvider {
     }
 }
 
+#[async_trait]
 impl SearchProvider for WebSearchProvider {
     fn id(&self) -> &str {
         "websearch"
     }
 
-    fn search(&self, query: &str) -> Vec<SearchResult> {
+    async fn search(&self, query: &str, _ctx: &SearchContext) -> Vec<SearchResult> {
         let trimmed = query.trim();
         if trimmed.is_empty() || trimmed.len() < 2 {
             return vec![];
         }
 
-        let mut results = Vec::new();
-
         // Check for explicit search engine shortcut
         if let Some((engine, search_query)) = Self::detect_engine_shortcut(query) {
-            results.push(SearchResult {
-                id: format!("websearch:{}:{}", engine.name().to_lowercase(), search_query),
+            return vec![SearchResult {
+                id: format!(
+                    "websearch:{}:{}",
+                    engine.name().to_lowercase(),
+                    search_query
+                ),
                 title: format!("Search {} for \"{}\"", engine.name(), search_query),
                 subtitle: Some(engine.search_url(search_query)),
                 icon: ResultIcon::Emoji(engine.icon().to_string()),
                 category: ResultCategory::WebSearch,
                 score: 85.0,
-            });
-            return results;
+                aria_label: None,
+            }];
         }
 
-        // Only show web search suggestions if query is long enough and looks like a search
-        // This is a "fallback" search that appears when other results might not match
-        if trimmed.len() >= 3 {
-            // Add default search engine
-            results.push(SearchResult {
-                id: format!(
-                    "websearch:{}:{}",
-                    self.default_engine.name().to_lowercase(),
-                    trimmed
-                ),
-                title: format!("Search {} for \"{}\"", self.default_engine.name(), trimmed),
-                subtitle: Some("Web search".to_string()),
-                icon: ResultIcon::Emoji(self.default_engine.icon().to_string()),
-                category: ResultCategory::WebSearch,
-                // Lower score so it appears below more specific results
-                score: 15.0,
-            });
-
-            // Add DuckDuckGo as alternative if Google is default
-            if matches!(self.default_engine, SearchEngine::Google) {
-                results.push(SearchResult {
-                    id: format!("websearch:duckduckgo:{}", trimmed),
-                    title: format!("Search DuckDuckGo for \"{}\"", trimmed),
-                    subtitle: Some("Private web search".to_string()),
-                    icon: ResultIcon::Emoji("🦆".to_string()),
-                    category: ResultCategory::WebSearch,
-                    score: 10.0,
-                });
-            }
-        }
-
-        results
+        vec![]
     }
 
     fn execute(&self, result_id: &str) -> Result<(), String> {
@@ -180,15 +164,8 @@ impl SearchProvider for WebSearchProvider {
 
         let (engine_name, query) = (parts[0], parts[1]);
 
-        let engine = match engine_name {
-            "google" => SearchEngine::Google,
-            "duckduckgo" => SearchEngine::DuckDuckGo,
-            "bing" => SearchEngine::Bing,
-            "github" => SearchEngine::GitHub,
-            "stackoverflow" | "stack overflow" => SearchEngine::StackOverflow,
-            "youtube" => SearchEngine::YouTube,
-            _ => return Err(format!("Unknown search engine: {}", engine_name)),
-        };
+        let engine = SearchEngine::from_name(engine_name)
+            .ok_or_else(|| format!("Unknown search engine: {}", engine_name))?;
 
         let url = engine.search_url(query);
 
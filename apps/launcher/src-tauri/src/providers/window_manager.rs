@@ -0,0 +1,124 @@
+use super::{ResultCategory, ResultIcon, SearchContext, SearchProvider, SearchResult};
+use crate::ax;
+use crate::context::ContextTracker;
+use crate::settings::SettingsStore;
+use crate::window_manager::{self, WindowAction};
+use async_trait::async_trait;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// The launcher hides after running a result; give focus time to return to
+/// the window being moved
+const HIDE_DELAY: Duration = Duration::from_millis(200);
+
+fn icon(action: WindowAction) -> &'static str {
+    match action {
+        WindowAction::LeftHalf => "◧",
+        WindowAction::RightHalf => "◨",
+        WindowAction::Maximize => "⬜",
+        WindowAction::NextMonitor => "🖥️",
+    }
+}
+
+fn score(query: &str, action: WindowAction) -> Option<f32> {
+    let title = action.title().to_lowercase();
+    if title.starts_with(query) {
+        return Some(90.0);
+    }
+    if title.contains(query) {
+        return Some(80.0);
+    }
+    action
+        .keywords()
+        .iter()
+        .any(|k| k.starts_with(query) || query.starts_with(k))
+        .then_some(75.0)
+}
+
+/// Snap, maximize and move the focused window between displays
+pub struct WindowManagerProvider {
+    settings: Arc<SettingsStore>,
+    context_tracker: Arc<ContextTracker>,
+}
+
+impl WindowManagerProvider {
+    pub fn new(settings: Arc<SettingsStore>, context_tracker: Arc<ContextTracker>) -> Self {
+        Self {
+            settings,
+            context_tracker,
+        }
+    }
+}
+
+#[async_trait]
+impl SearchProvider for WindowManagerProvider {
+    fn id(&self) -> &str {
+        "window"
+    }
+
+    async fn search(&self, query: &str, _ctx: &SearchContext) -> Vec<SearchResult> {
+        let query = query.trim().to_lowercase();
+        if query.len() < 3 {
+            return vec![];
+        }
+        let hotkeys = self.settings.get().window_hotkeys;
+        let trusted = ax::is_trusted();
+
+        WindowAction::ALL
+            .into_iter()
+            .filter_map(|action| {
+                let score = score(&query, action)?;
+                let subtitle = if !trusted {
+                    "Allow Accessibility access to move windows".to_string()
+                } else {
+                    match hotkeys.get(action.id()) {
+                        Some(hotkey) => format!("Move the focused window • {}", hotkey),
+                        None => "Move the focused window".to_string(),
+                    }
+                };
+                Some(SearchResult {
+                    id: format!("window:{}", action.id()),
+                    title: action.title().to_string(),
+                    subtitle: Some(subtitle),
+                    icon: ResultIcon::Emoji(icon(action).to_string()),
+                    category: ResultCategory::System,
+                    score,
+                    aria_label: None,
+                })
+            })
+            .collect()
+    }
+
+    fn execute(&self, result_id: &str) -> Result<(), String> {
+        let action = result_id
+            .strip_prefix("window:")
+            .and_then(WindowAction::from_id)
+            .ok_or("Invalid window command")?;
+        if !ax::is_trusted() {
+            return ax::open_settings();
+        }
+
+        let app = self.context_tracker.active().app.map(|app| app.name);
+        std::thread::spawn(move || {
+            std::thread::sleep(HIDE_DELAY);
+            if let Err(e) = window_manager::apply(action, app.as_deref()) {
+                eprintln!("Window command failed: {}", e);
+            }
+        });
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_titles_and_keywords() {
+        assert_eq!(score("left", WindowAction::LeftHalf), Some(90.0));
+        assert_eq!(score("maxim", WindowAction::Maximize), Some(90.0));
+        assert_eq!(score("snap left", WindowAction::LeftHalf), Some(75.0));
+        assert_eq!(score("snap left", WindowAction::RightHalf), None);
+        assert_eq!(score("display", WindowAction::NextMonitor), Some(80.0));
+    }
+}
@@ -0,0 +1,200 @@
+use super::{ResultCategory, ResultIcon, SearchContext, SearchProvider, SearchResult};
+use crate::ax;
+use crate::window_manager::{self, OpenWindow};
+use async_trait::async_trait;
+use fuzzy_matcher::skim::SkimMatcherV2;
+use fuzzy_matcher::FuzzyMatcher;
+use parking_lot::RwLock;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+const PREFIX: &str = "win:";
+/// How often the list is read again while the switcher is showing, and how
+/// long a search reuses the last read
+pub const REFRESH_INTERVAL: Duration = Duration::from_secs(1);
+/// The launcher hides after running a result; focus the window once it has
+const HIDE_DELAY: Duration = Duration::from_millis(200);
+const MAX_RESULTS: usize = 50;
+
+/// The window's app, unless the title is missing or already names it
+fn subtitle(window: &OpenWindow) -> Option<String> {
+    if window.app.is_empty() || window.title.is_empty() || window.app == window.title {
+        return None;
+    }
+    Some(window.app.clone())
+}
+
+/// Switch to an open window: `win:` lists them front to back, `win: term`
+/// narrows them down by title and app
+pub struct WindowsProvider {
+    /// Last read of the window list, and when it was made
+    windows: RwLock<Option<(Instant, Vec<OpenWindow>)>>,
+    /// Whether the latest query was for the switcher, so the list is only
+    /// kept live while it's on screen
+    watching: AtomicBool,
+    matcher: SkimMatcherV2,
+}
+
+impl WindowsProvider {
+    pub fn new() -> Self {
+        Self {
+            windows: RwLock::new(None),
+            watching: AtomicBool::new(false),
+            matcher: SkimMatcherV2::default(),
+        }
+    }
+
+    /// Read the window list, keeping the last one if it can't be read
+    fn read(&self) -> Vec<OpenWindow> {
+        match window_manager::open_windows() {
+            Ok(windows) => {
+                *self.windows.write() = Some((Instant::now(), windows.clone()));
+                windows
+            }
+            Err(e) => {
+                eprintln!("Failed to list windows: {}", e);
+                self.cached().unwrap_or_default()
+            }
+        }
+    }
+
+    fn cached(&self) -> Option<Vec<OpenWindow>> {
+        self.windows
+            .read()
+            .as_ref()
+            .map(|(_, windows)| windows.clone())
+    }
+
+    fn windows(&self) -> Vec<OpenWindow> {
+        let fresh = self
+            .windows
+            .read()
+            .as_ref()
+            .filter(|(read_at, _)| read_at.elapsed() < REFRESH_INTERVAL)
+            .map(|(_, windows)| windows.clone());
+        fresh.unwrap_or_else(|| self.read())
+    }
+
+    /// Read the list again if the switcher is showing; true when it changed
+    pub fn refresh(&self) -> bool {
+        if !self.watching.load(Ordering::Relaxed) {
+            return false;
+        }
+        let before = self.cached();
+        before.as_ref() != Some(&self.read())
+    }
+
+    fn result(&self, window: &OpenWindow, score: f32) -> SearchResult {
+        let title = if window.title.is_empty() {
+            window.app.clone()
+        } else {
+            window.title.clone()
+        };
+        SearchResult {
+            id: format!("windows:{}", window.id),
+            title,
+            subtitle: subtitle(window),
+            icon: ResultIcon::Emoji("🪟".to_string()),
+            category: ResultCategory::Application,
+            score,
+            aria_label: None,
+        }
+    }
+}
+
+impl Default for WindowsProvider {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl SearchProvider for WindowsProvider {
+    fn id(&self) -> &str {
+        "windows"
+    }
+
+    async fn search(&self, query: &str, ctx: &SearchContext) -> Vec<SearchResult> {
+        let Some(filter) = query.trim_start().strip_prefix(PREFIX) else {
+            self.watching.store(false, Ordering::Relaxed);
+            return vec![];
+        };
+        self.watching.store(true, Ordering::Relaxed);
+        let filter = filter.trim();
+        let windows = ctx.run_blocking(|| self.windows());
+
+        if filter.is_empty() {
+            return windows
+                .iter()
+                .take(MAX_RESULTS)
+                .enumerate()
+                .map(|(i, window)| self.result(window, 90.0 - i as f32 * 0.1))
+                .collect();
+        }
+
+        let mut matches: Vec<(i64, usize, &OpenWindow)> = windows
+            .iter()
+            .enumerate()
+            .filter_map(|(i, window)| {
+                let text = format!("{} {}", window.title, window.app);
+                Some((self.matcher.fuzzy_match(&text, filter)?, i, window))
+            })
+            .collect();
+        // Better matches first, then front to back
+        matches.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)));
+        matches
+            .into_iter()
+            .take(MAX_RESULTS)
+            .enumerate()
+            .map(|(i, (_, _, window))| self.result(window, 90.0 - i as f32 * 0.1))
+            .collect()
+    }
+
+    fn execute(&self, result_id: &str) -> Result<(), String> {
+        let id = result_id
+            .strip_prefix("windows:")
+            .ok_or("Invalid window result")?;
+        let window = self
+            .cached()
+            .unwrap_or_default()
+            .into_iter()
+            .find(|w| w.id == id)
+            .ok_or("That window has closed")?;
+        if !ax::is_trusted() {
+            return ax::open_settings();
+        }
+
+        std::thread::spawn(move || {
+            std::thread::sleep(HIDE_DELAY);
+            if let Err(e) = window_manager::focus(&window) {
+                eprintln!("Failed to switch windows: {}", e);
+            }
+        });
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shows_the_app_only_when_it_adds_something() {
+        let window = |title: &str, app: &str| OpenWindow {
+            id: "1".to_string(),
+            title: title.to_string(),
+            app: app.to_string(),
+            pid: None,
+        };
+        assert_eq!(
+            subtitle(&window("README.md - Code", "code")),
+            Some("code".to_string())
+        );
+        assert_eq!(subtitle(&window("Slack", "Slack")), None);
+        assert_eq!(subtitle(&window("", "Finder")), None);
+
+        let provider = WindowsProvider::new();
+        assert_eq!(provider.result(&window("", "Finder"), 90.0).title, "Finder");
+        assert!(!provider.refresh());
+    }
+}
@@ -0,0 +1,131 @@
+use super::{ResultCategory, ResultIcon, SearchContext, SearchProvider, SearchResult};
+use crate::ax;
+use crate::workspaces::{self, Workspace};
+use async_trait::async_trait;
+use std::time::Duration;
+
+/// The launcher hides after running a result; let focus return to the
+/// window being moved, and keep the launcher out of the desktop switch
+const HIDE_DELAY: Duration = Duration::from_millis(200);
+
+const KEYWORDS: [&str; 3] = ["workspace", "desktop", "space"];
+
+/// What's typed after a keyword, e.g. "2" in "desktop 2"; None when the
+/// query doesn't start with one
+fn filter(query: &str) -> Option<String> {
+    let query = query.trim().to_lowercase();
+    KEYWORDS.iter().find_map(|keyword| {
+        let rest = query.strip_prefix(keyword)?;
+        let rest = rest.strip_prefix('s').unwrap_or(rest);
+        (rest.is_empty() || rest.starts_with(' ')).then(|| rest.trim().to_string())
+    })
+}
+
+fn matches(workspace: &Workspace, filter: &str) -> bool {
+    filter.is_empty()
+        || workspace.name.to_lowercase().contains(filter)
+        || (workspace.index + 1).to_string() == filter
+}
+
+/// `workspace`, `desktop` or `space`, optionally followed by a name or
+/// number, lists virtual desktops to switch to or move the focused window to
+pub struct WorkspaceProvider;
+
+impl WorkspaceProvider {
+    pub fn new() -> Self {
+        Self
+    }
+
+    fn results(filter: &str) -> Vec<SearchResult> {
+        let workspaces = match workspaces::list() {
+            Ok(workspaces) => workspaces,
+            Err(e) => {
+                eprintln!("Failed to list workspaces: {}", e);
+                return vec![];
+            }
+        };
+
+        let mut results = Vec::new();
+        for (i, workspace) in workspaces.iter().filter(|w| matches(w, filter)).enumerate() {
+            let score = 85.0 - i as f32 * 0.1;
+            results.push(SearchResult {
+                id: format!("workspace:switch:{}", workspace.index),
+                title: workspace.name.clone(),
+                subtitle: Some(if workspace.current {
+                    "Current workspace".to_string()
+                } else {
+                    "Switch to this workspace".to_string()
+                }),
+                icon: ResultIcon::Emoji("🗂️".to_string()),
+                category: ResultCategory::System,
+                score,
+                aria_label: None,
+            });
+            if workspaces::supports_move() && !workspace.current {
+                results.push(SearchResult {
+                    id: format!("workspace:move:{}", workspace.index),
+                    title: format!("Move Window to {}", workspace.name),
+                    subtitle: Some("Send the focused window to this workspace".to_string()),
+                    icon: ResultIcon::Emoji("📤".to_string()),
+                    category: ResultCategory::System,
+                    score: score - 5.0,
+                    aria_label: None,
+                });
+            }
+        }
+        results
+    }
+}
+
+#[async_trait]
+impl SearchProvider for WorkspaceProvider {
+    fn id(&self) -> &str {
+        "workspace"
+    }
+
+    async fn search(&self, query: &str, ctx: &SearchContext) -> Vec<SearchResult> {
+        let Some(filter) = filter(query) else {
+            return vec![];
+        };
+        ctx.run_blocking(|| Self::results(&filter))
+    }
+
+    fn execute(&self, result_id: &str) -> Result<(), String> {
+        let rest = result_id
+            .strip_prefix("workspace:")
+            .ok_or("Invalid workspace result")?;
+        let (kind, index) = rest.split_once(':').ok_or("Invalid workspace result")?;
+        let index: usize = index.parse().map_err(|_| "Invalid workspace result")?;
+        let run: fn(usize) -> Result<(), String> = match kind {
+            "switch" => workspaces::switch_to,
+            "move" => workspaces::move_window_to,
+            _ => return Err("Invalid workspace result".to_string()),
+        };
+        // Switching on macOS presses keys through System Events
+        if !ax::is_trusted() {
+            return ax::open_settings();
+        }
+
+        std::thread::spawn(move || {
+            std::thread::sleep(HIDE_DELAY);
+            if let Err(e) = run(index) {
+                eprintln!("Workspace command failed: {}", e);
+            }
+        });
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_keyword_filters() {
+        assert_eq!(filter("workspace"), Some(String::new()));
+        assert_eq!(filter("Desktops"), Some(String::new()));
+        assert_eq!(filter("space 2"), Some("2".to_string()));
+        assert_eq!(filter("spacebar"), None);
+        assert_eq!(filter("desk"), None);
+    }
+}
@@ -0,0 +1,184 @@
+//! Ranking results from many providers. Providers score on their own
+//! scales: the calculator answers with 1000, apps with up to 100, fuzzy
+//! matchers with whatever their matcher returns. Each provider's scores are
+//! mapped to 0–1 first, then weighted and boosted on that common scale,
+//! with ties broken by category and past use. Intent results go above the
+//! rest.
+
+use crate::frecency;
+use crate::providers::{ResultCategory, SearchResult};
+use crate::settings::{AppProfile, ProviderSettings, UserSettings};
+use std::cmp::Ordering;
+use std::collections::HashSet;
+
+/// The scale most providers score on, and the one context and frecency
+/// boosts are given in. A provider scoring higher is scaled by its own top
+/// score instead.
+const NOMINAL_SCALE: f32 = 100.0;
+
+/// Results from one provider, or from the intent router when `provider` is None
+pub type ProviderResults = (Option<String>, Vec<SearchResult>);
+
+/// Map one provider's scores to 0–1
+fn normalize(results: &mut [SearchResult]) {
+    let top = results
+        .iter()
+        .map(|r| r.score)
+        .fold(NOMINAL_SCALE, f32::max);
+    for result in results {
+        result.score = (result.score / top).clamp(0.0, 1.0);
+    }
+}
+
+/// Weigh a provider's results and keep its best `max_results`
+fn apply_provider_settings(results: &mut Vec<SearchResult>, provider: &ProviderSettings) {
+    for result in results.iter_mut() {
+        result.score *= provider.weight;
+    }
+    if let Some(max_results) = provider.max_results {
+        results.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(Ordering::Equal));
+        results.truncate(max_results);
+    }
+}
+
+/// Order among results that score the same: answers first, web searches last
+fn category_rank(category: &ResultCategory) -> u8 {
+    match category {
+        ResultCategory::Calculator => 0,
+        ResultCategory::Application => 1,
+        ResultCategory::Command => 2,
+        ResultCategory::System => 3,
+        ResultCategory::File => 4,
+        ResultCategory::Plugin => 5,
+        ResultCategory::GitHub => 6,
+        ResultCategory::URL => 7,
+        ResultCategory::WebSearch => 8,
+    }
+}
+
+/// Rank every provider's results into one list, best first, without
+/// duplicates. `frecency` gives a result's boost from past use.
+pub fn rank(
+    finished: Vec<ProviderResults>,
+    settings: &UserSettings,
+    profile: Option<&AppProfile>,
+    frecency: impl Fn(&str) -> f64,
+) -> Vec<SearchResult> {
+    let mut ranked: Vec<(SearchResult, f64)> = Vec::new();
+    let mut intents: Vec<SearchResult> = Vec::new();
+
+    for (provider_id, mut results) in finished {
+        normalize(&mut results);
+        let Some(provider_id) = provider_id else {
+            intents.extend(results);
+            continue;
+        };
+        apply_provider_settings(&mut results, &settings.provider(&provider_id));
+
+        for mut result in results {
+            if let Some(profile) = profile {
+                result.score += profile.boost_for(&provider_id, &result.id) / NOMINAL_SCALE;
+            }
+            let used = frecency(&result.id)
+                * frecency::provider_weight(&result.id, &settings.frecency_weights);
+            result.score += (used / NOMINAL_SCALE as f64) as f32;
+            ranked.push((result, used));
+        }
+    }
+
+    ranked.sort_by(|(a, a_used), (b, b_used)| {
+        b.score
+            .partial_cmp(&a.score)
+            .unwrap_or(Ordering::Equal)
+            .then_with(|| category_rank(&a.category).cmp(&category_rank(&b.category)))
+            .then_with(|| b_used.partial_cmp(a_used).unwrap_or(Ordering::Equal))
+    });
+
+    // Intent results above the rest, in their own order
+    let top = ranked.first().map_or(0.0, |(r, _)| r.score);
+    intents.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(Ordering::Equal));
+    for result in &mut intents {
+        result.score += top + 1.0;
+    }
+
+    let mut all_results = intents;
+    all_results.extend(ranked.into_iter().map(|(result, _)| result));
+
+    // An intent result may also come back from its provider's own fuzzy search
+    let mut seen = HashSet::new();
+    all_results.retain(|r| seen.insert(r.id.clone()));
+    all_results
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::ResultIcon;
+
+    fn result(id: &str, category: ResultCategory, score: f32) -> SearchResult {
+        SearchResult {
+            id: id.to_string(),
+            title: id.to_string(),
+            subtitle: None,
+            icon: ResultIcon::Emoji("🔎".to_string()),
+            category,
+            score,
+            aria_label: None,
+        }
+    }
+
+    #[test]
+    fn ranks_providers_on_one_scale() {
+        let finished = vec![
+            (
+                Some("calculator".to_string()),
+                vec![result("calc:4", ResultCategory::Calculator, 1000.0)],
+            ),
+            (
+                Some("apps".to_string()),
+                vec![
+                    result("app:calendar", ResultCategory::Application, 100.0),
+                    result("app:calibre", ResultCategory::Application, 60.0),
+                ],
+            ),
+            (
+                Some("fallback".to_string()),
+                vec![result(
+                    "websearch:google:cal",
+                    ResultCategory::WebSearch,
+                    15.0,
+                )],
+            ),
+            (
+                None,
+                vec![result("app:calibre", ResultCategory::Application, 0.0)],
+            ),
+        ];
+        let settings = UserSettings::default();
+
+        let ranked = rank(finished.clone(), &settings, None, |_| 0.0);
+        let ids: Vec<&str> = ranked.iter().map(|r| r.id.as_str()).collect();
+        // The intent first, and only once; the calculator wins its tie with
+        // the exact app match
+        assert_eq!(
+            ids,
+            [
+                "app:calibre",
+                "calc:4",
+                "app:calendar",
+                "websearch:google:cal"
+            ]
+        );
+        assert_eq!(ranked[1].score, 1.0);
+
+        // Past use counts on the same scale as the match itself
+        let ranked = rank(finished, &settings, None, |id| {
+            if id == "websearch:google:cal" {
+                200.0
+            } else {
+                0.0
+            }
+        });
+        assert_eq!(ranked[1].id, "websearch:google:cal");
+    }
+}
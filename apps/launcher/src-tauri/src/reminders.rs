@@ -0,0 +1,301 @@
+//! Reminders set in plain language, e.g. "remind me to submit the report
+//! at 4pm". Stored in reminders.json and announced with a system
+//! notification when due; a fired reminder stays listed until it's marked
+//! done or snoozed.
+
+use chrono::{DateTime, Datelike, Duration, Local, NaiveTime, TimeZone, Utc, Weekday};
+use parking_lot::RwLock;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use uuid::Uuid;
+
+lazy_static::lazy_static! {
+    static ref IN_RE: Regex =
+        Regex::new(r"(?i)\bin (\d+) ?(minutes?|mins?|m|hours?|hrs?|h|days?|d)\b").unwrap();
+    static ref AT_RE: Regex = Regex::new(r"(?i)\bat (\d{1,2})(?::(\d{2}))? ?(am|pm)?\b").unwrap();
+    static ref DAY_RE: Regex = Regex::new(concat!(
+        r"(?i)\b(?:(today|tonight|tomorrow)",
+        r"|on (monday|tuesday|wednesday|thursday|friday|saturday|sunday))\b"
+    ))
+    .unwrap();
+}
+
+/// Snooze lengths offered on due reminders, in minutes
+pub const SNOOZE_OPTIONS: &[(i64, &str)] = &[(10, "10 minutes"), (60, "1 hour")];
+
+/// Time used when only a day is given
+const DEFAULT_HOUR: u32 = 9;
+const TONIGHT_HOUR: u32 = 20;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Reminder {
+    pub id: String,
+    pub text: String,
+    pub due_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+    /// The notification has been shown
+    #[serde(default)]
+    pub fired: bool,
+}
+
+fn parse_time(hour: u32, minute: u32, meridiem: Option<&str>) -> Option<NaiveTime> {
+    let hour = match meridiem {
+        Some("pm") if hour < 12 => hour + 12,
+        Some("am") if hour == 12 => 0,
+        Some(_) => hour,
+        // "at 4" means the afternoon; nobody sets reminders for 4 am that way
+        None if (1..=7).contains(&hour) => hour + 12,
+        None => hour,
+    };
+    NaiveTime::from_hms_opt(hour, minute, 0)
+}
+
+fn weekday(name: &str) -> Option<Weekday> {
+    name.parse().ok()
+}
+
+fn at_local(date: chrono::NaiveDate, time: NaiveTime) -> Option<DateTime<Local>> {
+    Local.from_local_datetime(&date.and_time(time)).earliest()
+}
+
+/// Split a reminder into what to remember and when, relative to `now`.
+/// Understands "in 20 minutes", "at 4pm", "tomorrow", "tonight" and
+/// "on friday", alone or combined ("tomorrow at 9:30").
+pub fn parse(input: &str, now: DateTime<Local>) -> Option<(String, DateTime<Local>)> {
+    let mut text = input.trim().to_string();
+    let mut spans = Vec::new();
+
+    let due = if let Some(caps) = IN_RE.captures(&text) {
+        let amount: i64 = caps[1].parse().ok()?;
+        let duration = match caps[2].to_lowercase().chars().next() {
+            Some('m') => Duration::minutes(amount),
+            Some('h') => Duration::hours(amount),
+            _ => Duration::days(amount),
+        };
+        spans.push(caps.get(0)?.range());
+        now + duration
+    } else {
+        let day = DAY_RE.captures(&text).and_then(|caps| {
+            spans.push(caps.get(0)?.range());
+            Some(caps.get(1).or(caps.get(2))?.as_str().to_lowercase())
+        });
+        let time = match AT_RE.captures(&text) {
+            Some(caps) => {
+                spans.push(caps.get(0)?.range());
+                let hour = caps[1].parse().ok()?;
+                let minute = caps.get(2).map_or(Some(0), |m| m.as_str().parse().ok())?;
+                let meridiem = caps.get(3).map(|m| m.as_str().to_lowercase());
+                Some(parse_time(hour, minute, meridiem.as_deref())?)
+            }
+            None => None,
+        };
+
+        let today = now.date_naive();
+        let default_time = |hour| NaiveTime::from_hms_opt(hour, 0, 0);
+        match day.as_deref() {
+            None => {
+                let due = at_local(today, time?)?;
+                // A time that has passed means tomorrow
+                if due <= now {
+                    at_local(today + Duration::days(1), time?)?
+                } else {
+                    due
+                }
+            }
+            Some("today") => at_local(today, time.or(default_time(DEFAULT_HOUR))?)?,
+            Some("tonight") => at_local(today, time.or(default_time(TONIGHT_HOUR))?)?,
+            Some("tomorrow") => at_local(
+                today + Duration::days(1),
+                time.or(default_time(DEFAULT_HOUR))?,
+            )?,
+            Some(name) => {
+                let target = weekday(name)?;
+                let ahead = (7 + target.num_days_from_monday() as i64
+                    - today.weekday().num_days_from_monday() as i64)
+                    % 7;
+                // "on monday" said on a Monday means next week
+                let ahead = if ahead == 0 { 7 } else { ahead };
+                at_local(
+                    today + Duration::days(ahead),
+                    time.or(default_time(DEFAULT_HOUR))?,
+                )?
+            }
+        }
+    };
+
+    spans.sort_by_key(|span| std::cmp::Reverse(span.start));
+    for span in spans {
+        text.replace_range(span, " ");
+    }
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    let text = text
+        .strip_prefix("to ")
+        .or_else(|| text.strip_prefix("that "))
+        .unwrap_or(&text)
+        .to_string();
+
+    (!text.is_empty()).then_some((text, due))
+}
+
+/// When a reminder is due, for subtitles: "Today 16:00", "Tomorrow 09:00",
+/// "Fri 16 Oct 09:00"
+pub fn format_due(due_at: DateTime<Utc>, now: DateTime<Utc>) -> String {
+    let due = due_at.with_timezone(&Local);
+    let today = now.with_timezone(&Local).date_naive();
+    let time = due.format("%H:%M");
+    match (due.date_naive() - today).num_days() {
+        0 => format!("Today {}", time),
+        1 => format!("Tomorrow {}", time),
+        _ => due.format("%a %-d %b %H:%M").to_string(),
+    }
+}
+
+pub struct ReminderStore {
+    reminders: RwLock<Vec<Reminder>>,
+    path: PathBuf,
+}
+
+impl ReminderStore {
+    pub fn new() -> Self {
+        let path = dirs::data_dir()
+            .unwrap_or_else(|| PathBuf::from("."))
+            .join("launcher")
+            .join("reminders.json");
+
+        let reminders = crate::journal::load_json_store(&path).unwrap_or_default();
+
+        Self {
+            reminders: RwLock::new(reminders),
+            path,
+        }
+    }
+
+    fn save(&self) {
+        let reminders = self.reminders.read();
+
+        if let Ok(json) = serde_json::to_string_pretty(&*reminders) {
+            if let Err(e) = crate::journal::write_json_store(&self.path, &json) {
+                eprintln!("Failed to save reminders: {}", e);
+            }
+        }
+    }
+
+    /// All reminders not yet done, soonest first
+    pub fn list(&self) -> Vec<Reminder> {
+        let mut reminders = self.reminders.read().clone();
+        reminders.sort_by_key(|r| r.due_at);
+        reminders
+    }
+
+    pub fn add(&self, text: &str, due_at: DateTime<Utc>) -> Reminder {
+        let reminder = Reminder {
+            id: Uuid::new_v4().to_string(),
+            text: text.to_string(),
+            due_at,
+            created_at: Utc::now(),
+            fired: false,
+        };
+        self.reminders.write().push(reminder.clone());
+        self.save();
+        reminder
+    }
+
+    /// Parse and add a reminder such as "submit the report at 4pm"
+    pub fn add_text(&self, input: &str) -> Result<Reminder, String> {
+        let (text, due) = parse(input, Local::now())
+            .ok_or("Say what and when, e.g. \"call Sam at 4pm\" or \"in 20 minutes\"")?;
+        Ok(self.add(&text, due.with_timezone(&Utc)))
+    }
+
+    /// Move a reminder `minutes` from now and let it fire again
+    pub fn snooze(&self, id: &str, minutes: i64) -> Result<Reminder, String> {
+        let mut reminders = self.reminders.write();
+        let reminder = reminders
+            .iter_mut()
+            .find(|r| r.id == id)
+            .ok_or("Reminder not found")?;
+        reminder.due_at = Utc::now() + Duration::minutes(minutes);
+        reminder.fired = false;
+        let reminder = reminder.clone();
+        drop(reminders);
+        self.save();
+        Ok(reminder)
+    }
+
+    /// Mark a reminder done, removing it
+    pub fn complete(&self, id: &str) -> Result<(), String> {
+        let mut reminders = self.reminders.write();
+        let before = reminders.len();
+        reminders.retain(|r| r.id != id);
+        if reminders.len() == before {
+            return Err("Reminder not found".to_string());
+        }
+        drop(reminders);
+        self.save();
+        Ok(())
+    }
+
+    /// Reminders that just became due; each is returned once
+    pub fn take_due(&self) -> Vec<Reminder> {
+        let now = Utc::now();
+        let mut reminders = self.reminders.write();
+        let due: Vec<Reminder> = reminders
+            .iter_mut()
+            .filter(|r| !r.fired && r.due_at <= now)
+            .map(|r| {
+                r.fired = true;
+                r.clone()
+            })
+            .collect();
+        drop(reminders);
+        if !due.is_empty() {
+            self.save();
+        }
+        due
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(hour: u32, minute: u32) -> DateTime<Local> {
+        // A Wednesday
+        Local
+            .with_ymd_and_hms(2024, 5, 15, hour, minute, 0)
+            .unwrap()
+    }
+
+    #[test]
+    fn parses_text_and_time() {
+        let now = at(10, 0);
+        let (text, due) = parse("to submit the report at 4pm", now).unwrap();
+        assert_eq!(text, "submit the report");
+        assert_eq!(due, at(16, 0));
+
+        let (text, due) = parse("call Sam in 20 minutes", now).unwrap();
+        assert_eq!(text, "call Sam");
+        assert_eq!(due, at(10, 20));
+
+        // Past times roll over to tomorrow
+        let (_, due) = parse("stretch at 9:30am", now).unwrap();
+        assert_eq!(due, at(9, 30) + Duration::days(1));
+    }
+
+    #[test]
+    fn parses_days() {
+        let now = at(10, 0);
+        let (text, due) = parse("tomorrow water the plants", now).unwrap();
+        assert_eq!(text, "water the plants");
+        assert_eq!(due, at(DEFAULT_HOUR, 0) + Duration::days(1));
+
+        let (_, due) = parse("pay rent on friday at 5pm", now).unwrap();
+        assert_eq!(due, at(17, 0) + Duration::days(2));
+        let (_, due) = parse("standup on wednesday", now).unwrap();
+        assert_eq!(due, at(DEFAULT_HOUR, 0) + Duration::days(7));
+
+        assert!(parse("submit the report", now).is_none());
+        assert!(parse("at 4pm", now).is_none());
+    }
+}
@@ -0,0 +1,550 @@
+//! Schedules that run results and quicklinks without being asked: on a cron
+//! expression, at startup, when the network changes, when the machine is
+//! plugged in or when a device connects or disconnects. The schedules live
+//! in settings; this module decides which are due and keeps the history of
+//! runs in schedule_runs.json. Running them is up to the caller, which has
+//! the providers.
+
+use crate::devices::ConnectedDevice;
+use crate::settings::{DeviceBus, Schedule, ScheduleTrigger};
+use chrono::{DateTime, Datelike, Duration, Local, NaiveDate, TimeZone, Timelike, Utc};
+use parking_lot::{Mutex, RwLock};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::net::{IpAddr, UdpSocket};
+use std::path::PathBuf;
+
+/// How often `tick` should be called
+pub const TICK_INTERVAL: std::time::Duration = std::time::Duration::from_secs(20);
+/// Runs kept in the history
+const HISTORY_LEN: usize = 100;
+
+const MONTH_NAMES: &[&str] = &[
+    "jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec",
+];
+const WEEKDAY_NAMES: &[&str] = &["sun", "mon", "tue", "wed", "thu", "fri", "sat"];
+
+/// A parsed five-field cron expression. Each field is a bit set of the
+/// values it matches.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cron {
+    minutes: u64,
+    hours: u64,
+    days: u64,
+    months: u64,
+    weekdays: u64,
+    /// Day of month and day of week were both given, so either may match
+    either_day: bool,
+}
+
+/// Values of one field, e.g. `*/15`, `1-5` or `mon,wed,fri`. `names` are
+/// accepted in place of numbers, counting from `min`.
+fn parse_field(field: &str, min: u32, max: u32, names: &[&str]) -> Result<u64, String> {
+    let value = |s: &str| {
+        names
+            .iter()
+            .position(|name| *name == s)
+            .map(|i| i as u32 + min)
+            .or_else(|| s.parse().ok())
+            .ok_or_else(|| format!("Invalid cron value: {}", s))
+    };
+
+    let mut bits = 0;
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => (
+                range,
+                step.parse::<usize>()
+                    .ok()
+                    .filter(|step| *step > 0)
+                    .ok_or_else(|| format!("Invalid cron step: {}", part))?,
+            ),
+            None => (part, 1),
+        };
+        let (start, end) = match range.split_once('-') {
+            _ if range == "*" => (min, max),
+            Some((start, end)) => (value(start)?, value(end)?),
+            // `5/10` means from 5 on
+            None if step > 1 => (value(range)?, max),
+            None => (value(range)?, value(range)?),
+        };
+        if start < min || end > max || start > end {
+            return Err(format!("Cron value out of range: {}", part));
+        }
+        for v in (start..=end).step_by(step) {
+            bits |= 1 << v;
+        }
+    }
+    Ok(bits)
+}
+
+fn has(bits: u64, value: u32) -> bool {
+    bits & (1 << value) != 0
+}
+
+impl Cron {
+    /// Parse `minute hour day month weekday`, or one of `@hourly`,
+    /// `@daily`, `@weekly`, `@monthly` and `@yearly`
+    pub fn parse(expression: &str) -> Result<Self, String> {
+        let expression = expression.trim().to_lowercase();
+        let expression = match expression.as_str() {
+            "@hourly" => "0 * * * *",
+            "@daily" | "@midnight" => "0 0 * * *",
+            "@weekly" => "0 0 * * 0",
+            "@monthly" => "0 0 1 * *",
+            "@yearly" | "@annually" => "0 0 1 1 *",
+            other => other,
+        };
+        let fields: Vec<&str> = expression.split_whitespace().collect();
+        let [minute, hour, day, month, weekday] = fields[..] else {
+            return Err("A cron expression has five fields: minute hour day month weekday".into());
+        };
+
+        let mut weekdays = parse_field(weekday, 0, 7, WEEKDAY_NAMES)?;
+        // Both 0 and 7 are Sunday
+        if has(weekdays, 7) {
+            weekdays |= 1;
+        }
+        Ok(Self {
+            minutes: parse_field(minute, 0, 59, &[])?,
+            hours: parse_field(hour, 0, 23, &[])?,
+            days: parse_field(day, 1, 31, &[])?,
+            months: parse_field(month, 1, 12, MONTH_NAMES)?,
+            weekdays,
+            either_day: !day.starts_with('*') && !weekday.starts_with('*'),
+        })
+    }
+
+    fn day_matches(&self, date: NaiveDate) -> bool {
+        let day = has(self.days, date.day());
+        let weekday = has(self.weekdays, date.weekday().num_days_from_sunday());
+        if self.either_day {
+            day || weekday
+        } else {
+            day && weekday
+        }
+    }
+
+    /// The first matching minute after `after`, skipping ahead by days and
+    /// hours that can't match
+    pub fn next_after(&self, after: DateTime<Local>) -> Option<DateTime<Local>> {
+        let mut t = after.naive_local().with_second(0)?.with_nanosecond(0)? + Duration::minutes(1);
+        // Long enough for a February 29th
+        let limit = t + Duration::days(5 * 366);
+        while t < limit {
+            let date = t.date();
+            if !has(self.months, t.month()) {
+                let (year, month) = match t.month() {
+                    12 => (t.year() + 1, 1),
+                    month => (t.year(), month + 1),
+                };
+                t = NaiveDate::from_ymd_opt(year, month, 1)?.and_hms_opt(0, 0, 0)?;
+            } else if !self.day_matches(date) {
+                t = date.succ_opt()?.and_hms_opt(0, 0, 0)?;
+            } else if !has(self.hours, t.hour()) {
+                t = date.and_hms_opt(t.hour(), 0, 0)? + Duration::hours(1);
+            } else if !has(self.minutes, t.minute()) {
+                t += Duration::minutes(1);
+            } else if let Some(local) = Local.from_local_datetime(&t).earliest() {
+                return Some(local);
+            } else {
+                // Skipped by a daylight saving change
+                t += Duration::minutes(1);
+            }
+        }
+        None
+    }
+}
+
+/// Check a schedule before it is saved
+pub fn validate(schedule: &Schedule) -> Result<(), String> {
+    if schedule.name.trim().is_empty() {
+        return Err("Schedule name can't be empty".to_string());
+    }
+    if let ScheduleTrigger::Cron { expression } = &schedule.trigger {
+        Cron::parse(expression)?;
+    }
+    Ok(())
+}
+
+/// What set off a run
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RunReason {
+    Cron,
+    Startup,
+    NetworkChange,
+    AcPower,
+    DeviceConnected,
+    DeviceDisconnected,
+    /// Run from the UI
+    Manual,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScheduleRun {
+    pub schedule_id: String,
+    pub name: String,
+    pub reason: RunReason,
+    pub at: DateTime<Utc>,
+    /// Why the run failed, if it did
+    pub error: Option<String>,
+}
+
+/// What changed since the last tick
+#[derive(Default)]
+struct Changes {
+    network_changed: bool,
+    plugged_in: bool,
+    connected: Vec<ConnectedDevice>,
+    disconnected: Vec<ConnectedDevice>,
+}
+
+/// Buses that enabled schedules watch for devices, so only those are read
+pub fn device_buses(schedules: &[Schedule]) -> HashSet<DeviceBus> {
+    schedules
+        .iter()
+        .filter(|s| s.enabled)
+        .filter_map(|s| match &s.trigger {
+            ScheduleTrigger::DeviceConnected { device }
+            | ScheduleTrigger::DeviceDisconnected { device } => Some(device.bus),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Local address of the route to the internet, which changes with the
+/// network; None offline. Connecting a UDP socket only picks the route,
+/// nothing is sent.
+fn local_address() -> Option<IpAddr> {
+    let socket = UdpSocket::bind("0.0.0.0:0").ok()?;
+    socket.connect("1.1.1.1:53").ok()?;
+    socket.local_addr().ok().map(|addr| addr.ip())
+}
+
+pub struct Scheduler {
+    history: RwLock<VecDeque<ScheduleRun>>,
+    path: PathBuf,
+    /// Next cron time by schedule id, with the expression it is for
+    next_runs: Mutex<HashMap<String, (String, DateTime<Local>)>>,
+    /// Network address and power source at the last tick
+    network: Mutex<Option<Option<IpAddr>>>,
+    on_battery: Mutex<Option<bool>>,
+    /// Devices connected at the last tick, for the buses that were read
+    devices: Mutex<HashMap<DeviceBus, HashSet<ConnectedDevice>>>,
+}
+
+impl Scheduler {
+    pub fn new() -> Self {
+        let path = dirs::data_dir()
+            .unwrap_or_else(|| PathBuf::from("."))
+            .join("launcher")
+            .join("schedule_runs.json");
+
+        let history = crate::journal::load_json_store(&path).unwrap_or_default();
+
+        Self {
+            history: RwLock::new(history),
+            path,
+            next_runs: Mutex::new(HashMap::new()),
+            network: Mutex::new(None),
+            on_battery: Mutex::new(None),
+            devices: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn save(&self) {
+        let history = self.history.read();
+
+        if let Ok(json) = serde_json::to_string_pretty(&*history) {
+            if let Err(e) = crate::journal::write_json_store(&self.path, &json) {
+                eprintln!("Failed to save schedule history: {}", e);
+            }
+        }
+    }
+
+    /// Schedules to run as the launcher starts
+    pub fn startup(&self, schedules: &[Schedule]) -> Vec<Schedule> {
+        schedules
+            .iter()
+            .filter(|s| s.enabled && s.trigger == ScheduleTrigger::Startup)
+            .cloned()
+            .collect()
+    }
+
+    /// Schedules that became due since the last tick, checking the network,
+    /// power source and `devices` (read for [`device_buses`]) for changes
+    pub fn tick(
+        &self,
+        schedules: &[Schedule],
+        on_battery: bool,
+        devices: HashMap<DeviceBus, HashSet<ConnectedDevice>>,
+    ) -> Vec<(Schedule, RunReason)> {
+        let network = local_address();
+        let network_changed = {
+            let mut last = self.network.lock();
+            // Going offline isn't a change worth running anything for
+            let changed = network.is_some() && last.is_some_and(|previous| previous != network);
+            *last = Some(network);
+            changed
+        };
+        let plugged_in = {
+            let mut last = self.on_battery.lock();
+            let plugged_in = *last == Some(true) && !on_battery;
+            *last = Some(on_battery);
+            plugged_in
+        };
+        let (connected, disconnected) = self.device_changes(devices);
+        let changes = Changes {
+            network_changed,
+            plugged_in,
+            connected,
+            disconnected,
+        };
+        self.due_at(schedules, Local::now(), &changes)
+    }
+
+    /// Devices connected and disconnected since the last read. A bus read
+    /// for the first time only sets what's there.
+    fn device_changes(
+        &self,
+        devices: HashMap<DeviceBus, HashSet<ConnectedDevice>>,
+    ) -> (Vec<ConnectedDevice>, Vec<ConnectedDevice>) {
+        let mut last = self.devices.lock();
+        let mut connected = Vec::new();
+        let mut disconnected = Vec::new();
+        for (bus, now) in &devices {
+            if let Some(before) = last.get(bus) {
+                connected.extend(now.difference(before).cloned());
+                disconnected.extend(before.difference(now).cloned());
+            }
+        }
+        *last = devices;
+        (connected, disconnected)
+    }
+
+    fn due_at(
+        &self,
+        schedules: &[Schedule],
+        now: DateTime<Local>,
+        changes: &Changes,
+    ) -> Vec<(Schedule, RunReason)> {
+        let mut next_runs = self.next_runs.lock();
+        next_runs.retain(|id, _| schedules.iter().any(|s| s.enabled && &s.id == id));
+
+        let mut due = Vec::new();
+        for schedule in schedules.iter().filter(|s| s.enabled) {
+            let reason = match &schedule.trigger {
+                ScheduleTrigger::Cron { expression } => {
+                    let Ok(cron) = Cron::parse(expression) else {
+                        continue;
+                    };
+                    let next = next_runs
+                        .get(&schedule.id)
+                        .filter(|(for_expression, _)| for_expression == expression)
+                        .map(|(_, next)| *next);
+                    // A new or changed schedule waits for its next time
+                    if next.is_some_and(|next| next > now) {
+                        continue;
+                    }
+                    match cron.next_after(now) {
+                        Some(after) => {
+                            next_runs.insert(schedule.id.clone(), (expression.clone(), after))
+                        }
+                        None => next_runs.remove(&schedule.id),
+                    };
+                    if next.is_none() {
+                        continue;
+                    }
+                    RunReason::Cron
+                }
+                ScheduleTrigger::NetworkChange if changes.network_changed => {
+                    RunReason::NetworkChange
+                }
+                ScheduleTrigger::AcPower if changes.plugged_in => RunReason::AcPower,
+                ScheduleTrigger::DeviceConnected { device }
+                    if changes.connected.iter().any(|d| d.matches(device)) =>
+                {
+                    RunReason::DeviceConnected
+                }
+                ScheduleTrigger::DeviceDisconnected { device }
+                    if changes.disconnected.iter().any(|d| d.matches(device)) =>
+                {
+                    RunReason::DeviceDisconnected
+                }
+                _ => continue,
+            };
+            due.push((schedule.clone(), reason));
+        }
+        due
+    }
+
+    /// Add a run to the history
+    pub fn record(
+        &self,
+        schedule: &Schedule,
+        reason: RunReason,
+        result: Result<(), String>,
+    ) -> ScheduleRun {
+        let run = ScheduleRun {
+            schedule_id: schedule.id.clone(),
+            name: schedule.name.clone(),
+            reason,
+            at: Utc::now(),
+            error: result.err(),
+        };
+        let mut history = self.history.write();
+        history.push_front(run.clone());
+        history.truncate(HISTORY_LEN);
+        drop(history);
+        self.save();
+        run
+    }
+
+    /// Past runs, newest first
+    pub fn history(&self) -> Vec<ScheduleRun> {
+        self.history.read().iter().cloned().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::settings::{DeviceMatch, ScheduleTarget};
+
+    fn at(day: u32, hour: u32, minute: u32) -> DateTime<Local> {
+        // May 2024; the 15th is a Wednesday
+        Local
+            .with_ymd_and_hms(2024, 5, day, hour, minute, 0)
+            .unwrap()
+    }
+
+    fn scheduler() -> Scheduler {
+        Scheduler {
+            history: RwLock::new(VecDeque::new()),
+            path: std::env::temp_dir().join("launcher-test-schedule-runs.json"),
+            next_runs: Mutex::new(HashMap::new()),
+            network: Mutex::new(None),
+            on_battery: Mutex::new(None),
+            devices: Mutex::new(HashMap::new()),
+        }
+    }
+
+    #[test]
+    fn finds_next_cron_times() {
+        let weekdays = Cron::parse("30 9 * * mon-fri").unwrap();
+        assert_eq!(weekdays.next_after(at(15, 8, 0)), Some(at(15, 9, 30)));
+        assert_eq!(weekdays.next_after(at(15, 9, 30)), Some(at(16, 9, 30)));
+        // Friday evening to Monday morning
+        assert_eq!(weekdays.next_after(at(17, 18, 0)), Some(at(20, 9, 30)));
+
+        let quarter_hours = Cron::parse("*/15 * * * *").unwrap();
+        assert_eq!(
+            quarter_hours.next_after(at(15, 10, 7)),
+            Some(at(15, 10, 15))
+        );
+        assert_eq!(
+            Cron::parse("@monthly").unwrap().next_after(at(15, 0, 0)),
+            Some(Local.with_ymd_and_hms(2024, 6, 1, 0, 0, 0).unwrap())
+        );
+        // Either the 1st or a Sunday
+        let either = Cron::parse("0 12 1 * sun").unwrap();
+        assert_eq!(either.next_after(at(15, 0, 0)), Some(at(19, 12, 0)));
+
+        assert!(Cron::parse("* * * *").is_err());
+        assert!(Cron::parse("60 * * * *").is_err());
+        assert!(Cron::parse("*/0 * * * *").is_err());
+    }
+
+    #[test]
+    fn runs_cron_schedules_once_when_due() {
+        let scheduler = scheduler();
+        let schedule = |id: &str, trigger| Schedule {
+            id: id.to_string(),
+            name: id.to_string(),
+            trigger,
+            target: ScheduleTarget::Quicklink {
+                name: "gh".to_string(),
+                query: String::new(),
+            },
+            enabled: true,
+            notify_on_failure: true,
+        };
+        let schedules = vec![
+            schedule(
+                "hourly",
+                ScheduleTrigger::Cron {
+                    expression: "0 * * * *".to_string(),
+                },
+            ),
+            schedule("plugged", ScheduleTrigger::AcPower),
+        ];
+
+        let none = Changes::default();
+        assert!(scheduler
+            .due_at(&schedules, at(15, 9, 50), &none)
+            .is_empty());
+        let plugged_in = Changes {
+            plugged_in: true,
+            ..Default::default()
+        };
+        let due = scheduler.due_at(&schedules, at(15, 10, 0), &plugged_in);
+        let due: Vec<_> = due.iter().map(|(s, r)| (s.id.as_str(), *r)).collect();
+        assert_eq!(
+            due,
+            vec![("hourly", RunReason::Cron), ("plugged", RunReason::AcPower)]
+        );
+        assert!(scheduler
+            .due_at(&schedules, at(15, 10, 0), &none)
+            .is_empty());
+        // Missed while asleep, run once on waking
+        assert_eq!(scheduler.due_at(&schedules, at(15, 14, 20), &none).len(), 1);
+    }
+
+    #[test]
+    fn runs_device_triggers_on_changes() {
+        let scheduler = scheduler();
+        let dock = ConnectedDevice {
+            bus: DeviceBus::Usb,
+            id: "2188:0747".to_string(),
+            name: "CalDigit TS4".to_string(),
+        };
+        let usb = |devices: &[&ConnectedDevice]| {
+            HashMap::from([(DeviceBus::Usb, devices.iter().copied().cloned().collect())])
+        };
+
+        // Already plugged in at startup isn't a change
+        assert_eq!(scheduler.device_changes(usb(&[&dock])), (vec![], vec![]));
+        assert_eq!(
+            scheduler.device_changes(usb(&[])),
+            (vec![], vec![dock.clone()])
+        );
+        let (connected, _) = scheduler.device_changes(usb(&[&dock]));
+        assert_eq!(connected, vec![dock.clone()]);
+
+        let schedules = vec![Schedule {
+            id: "dock".to_string(),
+            name: "Dock".to_string(),
+            trigger: ScheduleTrigger::DeviceConnected {
+                device: DeviceMatch {
+                    bus: DeviceBus::Usb,
+                    id: "2188:0747".to_string(),
+                    name: String::new(),
+                },
+            },
+            target: ScheduleTarget::Quicklink {
+                name: "gh".to_string(),
+                query: String::new(),
+            },
+            enabled: true,
+            notify_on_failure: true,
+        }];
+        assert_eq!(device_buses(&schedules), HashSet::from([DeviceBus::Usb]));
+        let changes = Changes {
+            connected,
+            ..Default::default()
+        };
+        let due = scheduler.due_at(&schedules, at(15, 10, 0), &changes);
+        assert_eq!(due[0].1, RunReason::DeviceConnected);
+    }
+}
@@ -0,0 +1,258 @@
+//! Provider results kept while the user types. Providers opt in per query
+//! with `SearchProvider::cache_policy`: a query asked again within its TTL,
+//! e.g. after a character is typed and deleted, is answered without asking
+//! the provider. Web APIs are also only called once typing pauses, and a
+//! longer query starts from a shorter one's results, narrowed to what still
+//! matches, until the API answers: results for `gh fire` stand in for
+//! `gh firef`.
+
+use crate::cache::ManagedCache;
+use crate::providers::SearchResult;
+use parking_lot::{Mutex, RwLock};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+const REMOTE_TTL: Duration = Duration::from_secs(60);
+/// How long typing has to pause before a web API is called
+const REMOTE_DEBOUNCE: Duration = Duration::from_millis(150);
+const MAX_ENTRIES: usize = 256;
+/// Results can stay on screen long after they were cached
+const URL_RETENTION: Duration = Duration::from_secs(60 * 60);
+
+/// How a provider's results for a query may be reused
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CachePolicy {
+    /// Asked on every keystroke, e.g. when results follow the clock, the
+    /// running processes or the index
+    Never,
+    /// Reused for this long
+    For(Duration),
+    /// A web API call: reused for a minute, made once typing pauses, and
+    /// stood in for by a shorter query's results
+    Remote,
+}
+
+impl CachePolicy {
+    fn ttl(&self) -> Option<Duration> {
+        match self {
+            Self::Never => None,
+            Self::For(ttl) => Some(*ttl),
+            Self::Remote => Some(REMOTE_TTL),
+        }
+    }
+
+    /// How long to wait before asking the provider; a newer keystroke
+    /// cancels the wait
+    pub fn debounce(&self) -> Duration {
+        match self {
+            Self::Remote => REMOTE_DEBOUNCE,
+            _ => Duration::ZERO,
+        }
+    }
+}
+
+struct Entry {
+    at: Instant,
+    ttl: Duration,
+    results: Vec<SearchResult>,
+}
+
+impl Entry {
+    fn is_fresh(&self) -> bool {
+        self.at.elapsed() < self.ttl
+    }
+
+    fn cost(&self) -> usize {
+        self.results
+            .iter()
+            .map(|r| {
+                std::mem::size_of::<SearchResult>()
+                    + r.id.len()
+                    + r.title.len()
+                    + r.subtitle.as_ref().map_or(0, String::len)
+            })
+            .sum()
+    }
+}
+
+/// Results by provider id and query
+#[derive(Default)]
+pub struct SearchCache {
+    entries: Mutex<HashMap<(String, String), Entry>>,
+}
+
+impl SearchCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Fresh results `provider` gave for exactly `query`
+    pub fn get(&self, provider: &str, query: &str) -> Option<Vec<SearchResult>> {
+        self.entries
+            .lock()
+            .get(&(provider.to_string(), query.to_string()))
+            .filter(|entry| entry.is_fresh())
+            .map(|entry| entry.results.clone())
+    }
+
+    /// Stand-ins for `query` from the longest shorter query it starts with,
+    /// narrowed to those that still match the word being typed
+    pub fn seed(&self, provider: &str, query: &str) -> Option<Vec<SearchResult>> {
+        let entries = self.entries.lock();
+        let (_, entry) = entries
+            .iter()
+            .filter(|((p, q), entry)| {
+                p == provider
+                    && q.len() < query.len()
+                    && query.starts_with(q.as_str())
+                    && entry.is_fresh()
+            })
+            .max_by_key(|((_, q), _)| q.len())?;
+        Some(narrow(&entry.results, query)).filter(|results| !results.is_empty())
+    }
+
+    /// Keep a provider's results for as long as its policy allows
+    pub fn store(
+        &self,
+        provider: &str,
+        query: &str,
+        results: &[SearchResult],
+        policy: CachePolicy,
+    ) {
+        let Some(ttl) = policy.ttl() else {
+            return;
+        };
+        let mut entries = self.entries.lock();
+        entries.retain(|_, entry| entry.is_fresh());
+        if entries.len() >= MAX_ENTRIES {
+            remove_oldest(&mut entries);
+        }
+        entries.insert(
+            (provider.to_string(), query.to_string()),
+            Entry {
+                at: Instant::now(),
+                ttl,
+                results: results.to_vec(),
+            },
+        );
+    }
+}
+
+/// Drop the entry cached longest ago, returning its cost
+fn remove_oldest(entries: &mut HashMap<(String, String), Entry>) -> usize {
+    let Some(key) = entries
+        .iter()
+        .min_by_key(|(_, entry)| entry.at)
+        .map(|(key, _)| key.clone())
+    else {
+        return 0;
+    };
+    entries.remove(&key).map_or(0, |entry| entry.cost())
+}
+
+/// Results that still match the last word of `query`
+fn narrow(results: &[SearchResult], query: &str) -> Vec<SearchResult> {
+    let Some(word) = query.split_whitespace().last().map(str::to_lowercase) else {
+        return results.to_vec();
+    };
+    results
+        .iter()
+        .filter(|r| {
+            r.title.to_lowercase().contains(&word)
+                || r.subtitle
+                    .as_ref()
+                    .is_some_and(|s| s.to_lowercase().contains(&word))
+        })
+        .cloned()
+        .collect()
+}
+
+impl ManagedCache for SearchCache {
+    fn cache_name(&self) -> &str {
+        "search_results"
+    }
+
+    fn cost(&self) -> usize {
+        self.entries.lock().values().map(Entry::cost).sum()
+    }
+
+    fn evict(&self, target_bytes: usize) -> usize {
+        let mut entries = self.entries.lock();
+        let mut total: usize = entries.values().map(Entry::cost).sum();
+        let mut freed = 0;
+        while total > target_bytes && !entries.is_empty() {
+            let cost = remove_oldest(&mut entries);
+            total -= cost;
+            freed += cost;
+        }
+        freed
+    }
+}
+
+/// Where results open, for providers whose result ids don't say. Kept well
+/// past the results' TTL, since results served from the cache skip the
+/// provider's own search.
+#[derive(Default)]
+pub struct ResultUrls {
+    urls: RwLock<HashMap<String, (Instant, String)>>,
+}
+
+impl ResultUrls {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn remember(&self, urls: HashMap<String, String>) {
+        let now = Instant::now();
+        let mut known = self.urls.write();
+        known.retain(|_, (at, _)| at.elapsed() < URL_RETENTION);
+        known.extend(urls.into_iter().map(|(id, url)| (id, (now, url))));
+    }
+
+    pub fn get(&self, result_id: &str) -> Option<String> {
+        self.urls.read().get(result_id).map(|(_, url)| url.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::{ResultCategory, ResultIcon};
+
+    fn result(title: &str) -> SearchResult {
+        SearchResult {
+            id: format!("github:repo:{}", title),
+            title: title.to_string(),
+            subtitle: None,
+            icon: ResultIcon::Emoji("📦".to_string()),
+            category: ResultCategory::GitHub,
+            score: 50.0,
+            aria_label: None,
+        }
+    }
+
+    #[test]
+    fn reuses_and_narrows_results() {
+        let cache = SearchCache::new();
+        let results = [
+            result("mozilla/firefox"),
+            result("firebase/firebase-js-sdk"),
+        ];
+        cache.store("github", "gh fire", &results, CachePolicy::Remote);
+        cache.store("apps", "gh fire", &results, CachePolicy::Never);
+
+        assert_eq!(cache.get("github", "gh fire").map(|r| r.len()), Some(2));
+        assert!(cache.get("apps", "gh fire").is_none());
+        assert!(cache.get("github", "gh firef").is_none());
+
+        let seeded = cache.seed("github", "gh firef").unwrap();
+        assert_eq!(seeded.len(), 1);
+        assert_eq!(seeded[0].title, "mozilla/firefox");
+        assert!(cache.seed("github", "gh fir").is_none());
+        assert!(cache.seed("github", "gh firex").is_none());
+
+        let freed = cache.evict(0);
+        assert!(freed > 0);
+        assert_eq!(cache.cost(), 0);
+    }
+}
@@ -0,0 +1,363 @@
+//! Where the launcher keeps secrets: OAuth tokens, API keys, the account
+//! session and remote location passwords. They go to the OS keychain:
+//! Keychain on macOS, Credential Manager on Windows and the Secret Service
+//! (GNOME Keyring, KWallet) through libsecret's D-Bus API on Linux.
+//!
+//! Headless Linux machines often run no Secret Service. There secrets are
+//! kept in a file encrypted with AES-256-GCM instead, unless the user turned
+//! the `secret_file_fallback` setting off. Secrets in the file move into the
+//! keychain once it can be reached, see [`migrate_to_keychain`].
+//!
+//! The file's key is derived from `LAUNCHER_SECRETS_PASSPHRASE` when that is
+//! set. Otherwise it's a random key in a file of its own that only the user
+//! can read. That keeps secrets out of copies of the secrets file, such as
+//! backups, but not from anyone who can read the whole data directory.
+
+use aes_gcm::aead::{Aead, KeyInit};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use keyring::Entry;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+const PASSPHRASE_VAR: &str = "LAUNCHER_SECRETS_PASSPHRASE";
+const SECRETS_FILE: &str = "secrets.enc";
+const KEY_FILE: &str = "secrets.key";
+const FILE_VERSION: u32 = 1;
+const PBKDF2_ROUNDS: u32 = 200_000;
+
+static FILE_FALLBACK: AtomicBool = AtomicBool::new(true);
+
+lazy_static::lazy_static! {
+    static ref FILE: SecretFile = SecretFile::new(
+        dirs::data_dir()
+            .unwrap_or_else(|| PathBuf::from("."))
+            .join("launcher"),
+        std::env::var(PASSPHRASE_VAR).ok().filter(|p| !p.is_empty()),
+    );
+}
+
+#[derive(Serialize, Deserialize)]
+struct EncryptedSecrets {
+    version: u32,
+    /// Salt for the passphrase; unused with a key file
+    salt: String,
+    nonce: String,
+    ciphertext: String,
+}
+
+/// Secrets by `service/account`, encrypted as one JSON object
+struct SecretFile {
+    dir: PathBuf,
+    passphrase: Option<String>,
+    /// Held across each read-modify-write of the file
+    lock: Mutex<()>,
+    /// Derived key and the salt it was derived with
+    key: Mutex<Option<(Vec<u8>, [u8; 32])>>,
+}
+
+/// Write a file only the user can read, replacing any old one in one step
+fn write_private(path: &Path, contents: &[u8]) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+
+    let temp = path.with_extension("tmp");
+    let _ = std::fs::remove_file(&temp);
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    {
+        let mut file = options.open(&temp).map_err(|e| e.to_string())?;
+        file.write_all(contents).map_err(|e| e.to_string())?;
+        file.sync_all().map_err(|e| e.to_string())?;
+    }
+    std::fs::rename(&temp, path).map_err(|e| e.to_string())
+}
+
+impl SecretFile {
+    fn new(dir: PathBuf, passphrase: Option<String>) -> Self {
+        Self {
+            dir,
+            passphrase,
+            lock: Mutex::new(()),
+            key: Mutex::new(None),
+        }
+    }
+
+    fn path(&self) -> PathBuf {
+        self.dir.join(SECRETS_FILE)
+    }
+
+    fn key(&self, salt: &[u8]) -> Result<[u8; 32], String> {
+        let mut cached = self.key.lock();
+        if let Some((cached_salt, key)) = cached.as_ref() {
+            if cached_salt == salt {
+                return Ok(*key);
+            }
+        }
+
+        let key = match &self.passphrase {
+            Some(passphrase) => {
+                let mut key = [0u8; 32];
+                pbkdf2::pbkdf2_hmac::<sha2::Sha256>(
+                    passphrase.as_bytes(),
+                    salt,
+                    PBKDF2_ROUNDS,
+                    &mut key,
+                );
+                key
+            }
+            None => self.key_file()?,
+        };
+        *cached = Some((salt.to_vec(), key));
+        Ok(key)
+    }
+
+    fn key_file(&self) -> Result<[u8; 32], String> {
+        let path = self.dir.join(KEY_FILE);
+        if let Ok(encoded) = std::fs::read_to_string(&path) {
+            return BASE64
+                .decode(encoded.trim())
+                .ok()
+                .and_then(|key| key.try_into().ok())
+                .ok_or_else(|| format!("{} is corrupted", path.display()));
+        }
+        // A new key can't open secrets encrypted with the lost one
+        if self.path().exists() {
+            return Err(format!(
+                "{} is missing; set {} if the secrets were saved with a passphrase",
+                path.display(),
+                PASSPHRASE_VAR
+            ));
+        }
+
+        let key: [u8; 32] = rand::random();
+        write_private(&path, BASE64.encode(key).as_bytes())?;
+        Ok(key)
+    }
+
+    /// The salt the file is encrypted with and its secrets, or a new salt
+    /// and nothing if there is no file yet
+    fn load(&self) -> Result<(Vec<u8>, BTreeMap<String, String>), String> {
+        let Ok(data) = std::fs::read_to_string(self.path()) else {
+            let salt: [u8; 16] = rand::random();
+            return Ok((salt.to_vec(), BTreeMap::new()));
+        };
+
+        let file: EncryptedSecrets =
+            serde_json::from_str(&data).map_err(|e| format!("Invalid secrets file: {}", e))?;
+        if file.version != FILE_VERSION {
+            return Err(format!(
+                "Unsupported secrets file version: {}",
+                file.version
+            ));
+        }
+        let decode = |s: &str| {
+            BASE64
+                .decode(s)
+                .map_err(|e| format!("Invalid secrets file: {}", e))
+        };
+        let salt = decode(&file.salt)?;
+        let nonce = decode(&file.nonce)?;
+        let ciphertext = decode(&file.ciphertext)?;
+        if nonce.len() != 12 {
+            return Err("Invalid secrets file: bad nonce".to_string());
+        }
+
+        let key = self.key(&salt)?;
+        let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&key));
+        let plaintext = cipher
+            .decrypt(Nonce::from_slice(&nonce), ciphertext.as_ref())
+            .map_err(|_| "Couldn't decrypt secrets file: wrong key or passphrase".to_string())?;
+        let secrets = serde_json::from_slice(&plaintext)
+            .map_err(|e| format!("Invalid secrets file: {}", e))?;
+        Ok((salt, secrets))
+    }
+
+    fn save(&self, salt: &[u8], secrets: &BTreeMap<String, String>) -> Result<(), String> {
+        if secrets.is_empty() {
+            return match std::fs::remove_file(self.path()) {
+                Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.to_string()),
+                _ => Ok(()),
+            };
+        }
+
+        let plaintext = serde_json::to_vec(secrets).map_err(|e| e.to_string())?;
+        let nonce: [u8; 12] = rand::random();
+        let key = self.key(salt)?;
+        let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&key));
+        let ciphertext = cipher
+            .encrypt(Nonce::from_slice(&nonce), plaintext.as_ref())
+            .map_err(|_| "Failed to encrypt secrets".to_string())?;
+
+        let json = serde_json::to_string_pretty(&EncryptedSecrets {
+            version: FILE_VERSION,
+            salt: BASE64.encode(salt),
+            nonce: BASE64.encode(nonce),
+            ciphertext: BASE64.encode(ciphertext),
+        })
+        .map_err(|e| e.to_string())?;
+        write_private(&self.path(), json.as_bytes())
+    }
+
+    fn get(&self, key: &str) -> Option<String> {
+        let _guard = self.lock.lock();
+        if !self.path().exists() {
+            return None;
+        }
+        match self.load() {
+            Ok((_, mut secrets)) => secrets.remove(key),
+            Err(e) => {
+                eprintln!("Failed to read secrets file: {}", e);
+                None
+            }
+        }
+    }
+
+    fn set(&self, key: &str, secret: &str) -> Result<(), String> {
+        let _guard = self.lock.lock();
+        let (salt, mut secrets) = self.load()?;
+        secrets.insert(key.to_string(), secret.to_string());
+        self.save(&salt, &secrets)
+    }
+
+    fn remove(&self, key: &str) -> Result<(), String> {
+        let _guard = self.lock.lock();
+        if !self.path().exists() {
+            return Ok(());
+        }
+        let (salt, mut secrets) = self.load()?;
+        if secrets.remove(key).is_some() {
+            self.save(&salt, &secrets)?;
+        }
+        Ok(())
+    }
+
+    /// Hand each secret to `store`, keeping only those it couldn't take
+    fn drain(&self, store: impl Fn(&str, &str) -> bool) -> Result<usize, String> {
+        let _guard = self.lock.lock();
+        if !self.path().exists() {
+            return Ok(0);
+        }
+        let (salt, mut secrets) = self.load()?;
+        let before = secrets.len();
+        secrets.retain(|key, secret| !store(key, secret));
+        let moved = before - secrets.len();
+        if moved > 0 {
+            self.save(&salt, &secrets)?;
+        }
+        Ok(moved)
+    }
+}
+
+fn file_key(service: &str, account: &str) -> String {
+    format!("{}/{}", service, account)
+}
+
+/// The keychain couldn't be reached at all, rather than not having the secret
+fn unavailable(error: &keyring::Error) -> bool {
+    matches!(
+        error,
+        keyring::Error::PlatformFailure(_) | keyring::Error::NoStorageAccess(_)
+    )
+}
+
+/// Whether secrets may be kept in the encrypted file while the keychain is
+/// unavailable. Secrets already in the file can still be read either way.
+pub fn set_file_fallback(enabled: bool) {
+    FILE_FALLBACK.store(enabled, Ordering::Relaxed);
+}
+
+pub fn get(service: &str, account: &str) -> Option<String> {
+    match Entry::new(service, account).and_then(|entry| entry.get_password()) {
+        Ok(secret) => Some(secret),
+        // Maybe saved while the keychain was unavailable
+        Err(_) => FILE.get(&file_key(service, account)),
+    }
+}
+
+pub fn set(service: &str, account: &str, secret: &str) -> Result<(), String> {
+    let key = file_key(service, account);
+    match Entry::new(service, account).and_then(|entry| entry.set_password(secret)) {
+        Ok(()) => {
+            // Don't leave an older copy in the file
+            if let Err(e) = FILE.remove(&key) {
+                eprintln!("Failed to clear {} from secrets file: {}", key, e);
+            }
+            Ok(())
+        }
+        Err(e) if unavailable(&e) && FILE_FALLBACK.load(Ordering::Relaxed) => {
+            FILE.set(&key, secret)
+        }
+        Err(e) => Err(format!("Keychain unavailable: {}", e)),
+    }
+}
+
+pub fn delete(service: &str, account: &str) -> Result<(), String> {
+    if let Ok(entry) = Entry::new(service, account) {
+        let _ = entry.delete_credential();
+    }
+    FILE.remove(&file_key(service, account))
+}
+
+/// Move secrets kept in the encrypted file into the keychain, returning how
+/// many moved. Those the keychain still can't take stay in the file.
+pub fn migrate_to_keychain() -> Result<usize, String> {
+    FILE.drain(|key, secret| {
+        let Some((service, account)) = key.split_once('/') else {
+            return false;
+        };
+        Entry::new(service, account)
+            .and_then(|entry| entry.set_password(secret))
+            .is_ok()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encrypts_secrets_at_rest() {
+        let dir =
+            std::env::temp_dir().join(format!("launcher-test-secrets-{}", rand::random::<u32>()));
+        let file = SecretFile::new(dir.clone(), None);
+        file.set("launcher-oauth/github", "gho_token").unwrap();
+        file.set("launcher-vault/api-key", "sk-key").unwrap();
+
+        let raw = std::fs::read_to_string(dir.join(SECRETS_FILE)).unwrap();
+        assert!(!raw.contains("gho_token") && !raw.contains("github"));
+
+        // Read back with the key file, as after a restart
+        let reopened = SecretFile::new(dir.clone(), None);
+        assert_eq!(
+            reopened.get("launcher-oauth/github").as_deref(),
+            Some("gho_token")
+        );
+
+        // Moved secrets leave the file, and the file goes with the last one
+        assert_eq!(
+            reopened.drain(|key, _| key.starts_with("launcher-oauth/")),
+            Ok(1)
+        );
+        assert_eq!(reopened.get("launcher-oauth/github"), None);
+        reopened.remove("launcher-vault/api-key").unwrap();
+        assert!(!dir.join(SECRETS_FILE).exists());
+
+        let passphrase = SecretFile::new(dir.clone(), Some("correct horse".to_string()));
+        passphrase.set("launcher-vault/api-key", "sk-key").unwrap();
+        let wrong = SecretFile::new(dir.clone(), Some("battery staple".to_string()));
+        assert!(wrong.load().is_err());
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
@@ -0,0 +1,152 @@
+use crate::providers::{ResultCategory, SearchResult};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Results shown per section before "Show all"
+const DEFAULT_SECTION_LIMIT: usize = 3;
+/// Page size when paging through a single section
+pub const SECTION_PAGE_SIZE: usize = 20;
+
+/// A group of related results, e.g. "Applications" or a single plugin
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResultSection {
+    pub id: String,
+    pub title: String,
+    pub results: Vec<SearchResult>,
+    /// Total number of results available in this section
+    pub total: usize,
+    /// Pass to `search_section` to get the next page ("Show all from X")
+    pub continuation: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GroupedSearchResponse {
+    pub query: String,
+    pub sections: Vec<ResultSection>,
+}
+
+/// Section id and title for a result
+fn section_for(result: &SearchResult, plugin_names: &HashMap<String, String>) -> (String, String) {
+    // Plugin results are "plugin:<plugin_id>:<result_id>" and get a section each
+    if let Some(rest) = result.id.strip_prefix("plugin:") {
+        let plugin_id = rest.split(':').next().unwrap_or(rest);
+        let title = plugin_names
+            .get(plugin_id)
+            .cloned()
+            .unwrap_or_else(|| plugin_id.to_string());
+        return (format!("plugin:{}", plugin_id), title);
+    }
+
+    let (id, title) = match result.category {
+        ResultCategory::Calculator => ("calculator", "Calculator"),
+        ResultCategory::Application => ("apps", "Applications"),
+        ResultCategory::File => ("files", "Files"),
+        ResultCategory::Command | ResultCategory::System => ("commands", "Commands"),
+        ResultCategory::Plugin => ("plugins", "Plugins"),
+        ResultCategory::GitHub => ("github", "GitHub"),
+        ResultCategory::URL => ("links", "Links"),
+        ResultCategory::WebSearch => ("web", "Web Search"),
+    };
+    (id.to_string(), title.to_string())
+}
+
+fn section_limit(section_id: &str) -> usize {
+    match section_id {
+        "apps" => 6,
+        "files" | "commands" => 5,
+        "calculator" => 2,
+        _ => DEFAULT_SECTION_LIMIT,
+    }
+}
+
+fn continuation_token(section_id: &str, offset: usize) -> String {
+    format!("{}@{}", section_id, offset)
+}
+
+/// Split a continuation token into (section id, offset)
+pub fn parse_continuation(token: &str) -> Result<(String, usize), String> {
+    let (section, offset) = token
+        .rsplit_once('@')
+        .ok_or_else(|| format!("Invalid continuation token: {}", token))?;
+    let offset = offset
+        .parse()
+        .map_err(|_| format!("Invalid continuation token: {}", token))?;
+    Ok((section.to_string(), offset))
+}
+
+/// Group ranked results into sections. Sections are ordered by their best
+/// result, and results keep their ranking within a section.
+pub fn group_results(
+    results: Vec<SearchResult>,
+    plugin_names: &HashMap<String, String>,
+) -> Vec<ResultSection> {
+    let mut sections: Vec<(ResultSection, Vec<SearchResult>)> = Vec::new();
+
+    for result in results {
+        let (id, title) = section_for(&result, plugin_names);
+        match sections.iter_mut().find(|(s, _)| s.id == id) {
+            Some((_, items)) => items.push(result),
+            None => sections.push((
+                ResultSection {
+                    id,
+                    title,
+                    results: Vec::new(),
+                    total: 0,
+                    continuation: None,
+                },
+                vec![result],
+            )),
+        }
+    }
+
+    sections
+        .into_iter()
+        .map(|(mut section, items)| {
+            let limit = section_limit(&section.id);
+            section.total = items.len();
+            if items.len() > limit {
+                section.continuation = Some(continuation_token(&section.id, limit));
+            }
+            section.results = items.into_iter().take(limit).collect();
+            section
+        })
+        .collect()
+}
+
+/// One page of a single section, starting at the continuation token's offset
+pub fn section_page(
+    results: Vec<SearchResult>,
+    plugin_names: &HashMap<String, String>,
+    token: &str,
+) -> Result<ResultSection, String> {
+    let (section_id, offset) = parse_continuation(token)?;
+
+    let mut title = String::new();
+    let items: Vec<SearchResult> = results
+        .into_iter()
+        .filter(|r| {
+            let (id, section_title) = section_for(r, plugin_names);
+            if id == section_id {
+                title = section_title;
+                true
+            } else {
+                false
+            }
+        })
+        .collect();
+
+    let total = items.len();
+    let next = offset + SECTION_PAGE_SIZE;
+
+    Ok(ResultSection {
+        id: section_id.clone(),
+        title,
+        results: items
+            .into_iter()
+            .skip(offset)
+            .take(SECTION_PAGE_SIZE)
+            .collect(),
+        total,
+        continuation: (next < total).then(|| continuation_token(&section_id, next)),
+    })
+}
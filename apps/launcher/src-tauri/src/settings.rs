@@ -1,6 +1,8 @@
 use parking_lot::RwLock;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::path::PathBuf;
+use std::sync::Arc;
 use uuid::Uuid;
 
 /// Theme mode preference
@@ -170,6 +172,366 @@ pub enum BackgroundType {
     Image,
 }
 
+/// Which background subsystems pause while in low-power mode
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PowerPauseOverrides {
+    #[serde(default = "default_true")]
+    pub indexing: bool,
+    #[serde(default = "default_true")]
+    pub widget_refresh: bool,
+    #[serde(default = "default_true")]
+    pub marketplace_refresh: bool,
+    #[serde(default = "default_true")]
+    pub provider_sync: bool,
+}
+
+impl Default for PowerPauseOverrides {
+    fn default() -> Self {
+        Self {
+            indexing: true,
+            widget_refresh: true,
+            marketplace_refresh: true,
+            provider_sync: true,
+        }
+    }
+}
+
+/// Battery-aware background behavior
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PowerSettings {
+    /// Enable low-power mode detection
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Battery percentage at or below which low-power mode kicks in (when on battery)
+    #[serde(default = "default_battery_threshold")]
+    pub battery_threshold: u8,
+    /// Also enter low-power mode when the OS power saver is active
+    #[serde(default = "default_true")]
+    pub pause_on_power_saver: bool,
+    /// Per-subsystem overrides
+    #[serde(default)]
+    pub pause: PowerPauseOverrides,
+}
+
+fn default_battery_threshold() -> u8 {
+    20
+}
+
+impl Default for PowerSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            battery_threshold: 20,
+            pause_on_power_saver: true,
+            pause: PowerPauseOverrides::default(),
+        }
+    }
+}
+
+/// Overrides for the OS's motion and contrast preferences; unset follows
+/// the OS
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AccessibilitySettings {
+    #[serde(default)]
+    pub reduce_motion: Option<bool>,
+    #[serde(default)]
+    pub high_contrast: Option<bool>,
+}
+
+/// What a `focus` session turns on until it ends
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FocusSettings {
+    /// Length used when `focus` is run without one
+    #[serde(default = "default_focus_minutes")]
+    pub default_minutes: u32,
+    #[serde(default = "default_true")]
+    pub do_not_disturb: bool,
+    /// Pause indexing, widget refresh and provider syncs
+    #[serde(default = "default_true")]
+    pub pause_background: bool,
+    /// Set a Slack status that expires with the session
+    #[serde(default = "default_true")]
+    pub slack_status: bool,
+    #[serde(default = "default_focus_status_text")]
+    pub slack_status_text: String,
+    #[serde(default = "default_focus_status_emoji")]
+    pub slack_status_emoji: String,
+}
+
+fn default_focus_minutes() -> u32 {
+    25
+}
+
+fn default_focus_status_text() -> String {
+    "Focusing".to_string()
+}
+
+fn default_focus_status_emoji() -> String {
+    ":headphones:".to_string()
+}
+
+impl Default for FocusSettings {
+    fn default() -> Self {
+        Self {
+            default_minutes: default_focus_minutes(),
+            do_not_disturb: true,
+            pause_background: true,
+            slack_status: true,
+            slack_status_text: default_focus_status_text(),
+            slack_status_emoji: default_focus_status_emoji(),
+        }
+    }
+}
+
+/// Resources a plugin call may use before it's stopped, so a misbehaving
+/// plugin can't hang search. Plugins can ask for more in their manifest.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PluginLimits {
+    /// Largest WASM memory per plugin; 0 for no limit
+    #[serde(default = "default_plugin_memory_mb")]
+    pub memory_mb: u32,
+    /// Longest a single call may run; 0 for no limit
+    #[serde(default = "default_plugin_timeout_ms")]
+    pub timeout_ms: u64,
+    /// Instructions (roughly) a single call may execute; 0 for no limit
+    #[serde(default = "default_plugin_fuel")]
+    pub fuel: u64,
+}
+
+fn default_plugin_memory_mb() -> u32 {
+    64
+}
+
+fn default_plugin_timeout_ms() -> u64 {
+    3000
+}
+
+fn default_plugin_fuel() -> u64 {
+    2_000_000_000
+}
+
+impl Default for PluginLimits {
+    fn default() -> Self {
+        Self {
+            memory_mb: default_plugin_memory_mb(),
+            timeout_ms: default_plugin_timeout_ms(),
+            fuel: default_plugin_fuel(),
+        }
+    }
+}
+
+/// How search treats one provider
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ProviderSettings {
+    /// Off keeps the provider out of search without disconnecting its
+    /// account, e.g. Slack or Notion
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Multiplies the provider's scores; above 1 ranks its results higher
+    #[serde(default = "default_provider_weight")]
+    pub weight: f32,
+    /// Most results it adds to a search, its best ones; None for no limit
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_results: Option<usize>,
+}
+
+fn default_provider_weight() -> f32 {
+    1.0
+}
+
+impl Default for ProviderSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            weight: default_provider_weight(),
+            max_results: None,
+        }
+    }
+}
+
+/// Which shell commands results may run. Patterns match the whole command,
+/// with `*` standing for any text; a denied command never runs, even if
+/// it's also allowed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommandPolicy {
+    /// When not empty, only commands matching one of these may run
+    #[serde(default)]
+    pub allow: Vec<String>,
+    #[serde(default = "default_denied_commands")]
+    pub deny: Vec<String>,
+}
+
+/// A few obviously destructive commands. Patterns are easy to get around,
+/// so these only catch mistakes; plugin commands are kept in check by the
+/// confirmation each one needs.
+fn default_denied_commands() -> Vec<String> {
+    [
+        "sudo *",
+        "rm -rf /*",
+        "rm -rf ~*",
+        "mkfs*",
+        "*| sh",
+        "*| bash",
+        "*|sh",
+        "*|bash",
+    ]
+    .map(String::from)
+    .to_vec()
+}
+
+impl Default for CommandPolicy {
+    fn default() -> Self {
+        Self {
+            allow: Vec::new(),
+            deny: default_denied_commands(),
+        }
+    }
+}
+
+/// How Codex, dev servers and terminals run in a folder that isn't trusted
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Sandbox {
+    /// Read files anywhere, change nothing
+    #[default]
+    ReadOnly,
+    /// Also change files inside the folder
+    WorkspaceWrite,
+}
+
+/// Behavior applied when the launcher is summoned over a matching application
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AppProfile {
+    pub name: String,
+    /// Case-insensitive substrings matched against the frontmost app's name
+    /// or identifier, e.g. "terminal", "iterm", "firefox", "com.google.chrome"
+    pub apps: Vec<String>,
+    /// Trigger to pre-fill in the search box, e.g. "ssh "
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trigger: Option<String>,
+    /// Points out of 100 added to results, keyed by provider id or result id
+    /// prefix
+    #[serde(default)]
+    pub provider_boosts: HashMap<String, f32>,
+}
+
+/// A named URL template opened from search, e.g. `gh {query}`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Quicklink {
+    pub name: String,
+    /// URL with `{query}` where the text typed after the name goes
+    pub url: String,
+}
+
+/// A keyword that puts a result first in search, e.g. `ff` for Firefox
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SearchAlias {
+    /// Matched against the whole query, ignoring case
+    pub keyword: String,
+    /// The result as it was when the alias was made; shown when its
+    /// provider doesn't come back with it, and run by its id
+    pub result: crate::providers::SearchResult,
+}
+
+/// Offered below the results of any query, for when nothing else matches
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Fallback {
+    /// A built-in engine by name: `google`, `duckduckgo`, `bing`, `github`,
+    /// `stackoverflow` or `youtube`
+    WebSearch { engine: String },
+    /// Ask the AI assistant, in the launcher window
+    AskAi,
+    /// Search inside indexed files, as a `content:` query does
+    FileContents,
+    /// A URL with `{query}` where the query goes, like a quicklink's
+    Url { name: String, url: String },
+}
+
+fn default_fallbacks() -> Vec<Fallback> {
+    vec![
+        Fallback::WebSearch {
+            engine: "google".to_string(),
+        },
+        Fallback::WebSearch {
+            engine: "duckduckgo".to_string(),
+        },
+    ]
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DeviceBus {
+    Bluetooth,
+    Usb,
+}
+
+/// Which devices set off a device trigger; empty fields match any device
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DeviceMatch {
+    pub bus: DeviceBus,
+    /// Bluetooth address, or USB `vendor:product` id such as `046d:c52b`
+    #[serde(default)]
+    pub id: String,
+    /// Case-insensitive part of the device's name, e.g. "AirPods"
+    #[serde(default)]
+    pub name: String,
+}
+
+/// When a schedule runs
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ScheduleTrigger {
+    /// Five-field cron expression in local time, e.g. `30 9 * * mon-fri`,
+    /// or a shorthand such as `@daily`
+    Cron { expression: String },
+    /// Each time the launcher starts
+    Startup,
+    /// When the machine joins a different network or comes back online
+    NetworkChange,
+    /// When the machine is plugged in
+    AcPower,
+    /// When a matching device connects, e.g. headphones or a dock
+    DeviceConnected { device: DeviceMatch },
+    /// When a matching device disconnects
+    DeviceDisconnected { device: DeviceMatch },
+}
+
+/// What a schedule runs
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ScheduleTarget {
+    /// A search result such as an app, workspace or command, by result id;
+    /// with `action_id`, one of its actions instead of opening it
+    Result {
+        result_id: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        action_id: Option<String>,
+    },
+    /// A quicklink by name, with the text to put in its `{query}`
+    Quicklink {
+        name: String,
+        #[serde(default)]
+        query: String,
+    },
+}
+
+/// Something the launcher runs on its own, on a schedule or an event
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Schedule {
+    /// Assigned when the schedule is first saved
+    #[serde(default)]
+    pub id: String,
+    pub name: String,
+    pub trigger: ScheduleTrigger,
+    pub target: ScheduleTarget,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Show a notification when a run fails
+    #[serde(default = "default_true")]
+    pub notify_on_failure: bool,
+}
+
 /// User settings that persist across sessions
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UserSettings {
@@ -208,6 +570,121 @@ pub struct UserSettings {
     // Launcher theme
     #[serde(default)]
     pub launcher_theme: LauncherTheme,
+
+    // Accessibility
+    #[serde(default)]
+    pub accessibility: AccessibilitySettings,
+
+    // Power management
+    #[serde(default)]
+    pub power: PowerSettings,
+
+    // Memory
+    /// Upper bound for memory held by in-process caches, in megabytes
+    #[serde(default = "default_memory_budget_mb")]
+    pub memory_budget_mb: usize,
+
+    // Context
+    /// Per-application profiles; the first one matching the frontmost app wins
+    #[serde(default)]
+    pub app_profiles: Vec<AppProfile>,
+
+    // Capture
+    /// Folder for screenshots and recordings; defaults to ~/Pictures/Screenshots
+    #[serde(default)]
+    pub capture_folder: Option<String>,
+
+    // Clipboard
+    /// Record copied text for the `clip:` history
+    #[serde(default = "default_true")]
+    pub clipboard_history: bool,
+
+    // Quicklinks
+    #[serde(default)]
+    pub quicklinks: Vec<Quicklink>,
+
+    // Aliases
+    /// Keywords that put a result first when typed as the whole query
+    #[serde(default)]
+    pub aliases: Vec<SearchAlias>,
+
+    // Fallbacks
+    /// Offered below the results, in this order
+    #[serde(default = "default_fallbacks")]
+    pub fallbacks: Vec<Fallback>,
+
+    // Focus
+    #[serde(default)]
+    pub focus: FocusSettings,
+
+    // Schedules
+    #[serde(default)]
+    pub schedules: Vec<Schedule>,
+
+    // Calculator
+    /// How often exchange rates for currency conversion are fetched again,
+    /// in hours
+    #[serde(default = "default_currency_refresh_hours")]
+    pub currency_refresh_hours: u32,
+    /// Locale for numbers and unit names in the calculator, e.g. `de-DE`;
+    /// the system's when unset
+    #[serde(default)]
+    pub calculator_locale: Option<String>,
+
+    // Ranking
+    /// How much past use boosts results, by result id prefix (e.g. `app`,
+    /// `file`); unlisted providers use the built-in weights
+    #[serde(default)]
+    pub frecency_weights: HashMap<String, f64>,
+    /// How long a provider may take to answer, in ms, by provider id, before
+    /// it counts as slow; others get the time search waits for a first answer
+    #[serde(default)]
+    pub provider_budgets_ms: HashMap<String, u64>,
+    /// On/off, weight and result limit by provider id; unlisted providers
+    /// are on with the defaults
+    #[serde(default)]
+    pub provider_settings: HashMap<String, ProviderSettings>,
+
+    // Plugins
+    /// Reload plugins whenever their files change on disk, for plugin
+    /// development with `launcher-plugin dev --watch`
+    #[serde(default)]
+    pub plugin_dev_mode: bool,
+    /// Refuse to install plugins that aren't signed, unless the marketplace
+    /// has verified them
+    #[serde(default)]
+    pub require_signed_plugins: bool,
+    /// Applied when a plugin is loaded, so changes take effect on reload
+    #[serde(default)]
+    pub plugin_limits: PluginLimits,
+    /// Most a plugin may keep in its data directory and config, in MB; 0
+    /// for no limit. Also applied when a plugin is loaded.
+    #[serde(default)]
+    pub plugin_storage_quota_mb: u64,
+    /// Quotas in MB for single plugins, by plugin id, replacing the above
+    #[serde(default)]
+    pub plugin_storage_quotas: HashMap<String, u64>,
+    /// Shell commands plugin results may run; they also always ask first
+    #[serde(default)]
+    pub command_policy: CommandPolicy,
+
+    // Workspace trust
+    /// Sandbox for Codex, dev servers and terminals in folders that aren't
+    /// trusted; trusted folders run without one
+    #[serde(default)]
+    pub untrusted_sandbox: Sandbox,
+
+    // Window management
+    /// Global hotkeys for window commands, by command id (e.g. `left-half`)
+    #[serde(default)]
+    pub window_hotkeys: HashMap<String, String>,
+
+    // Secrets
+    /// Keep tokens and passwords in an encrypted file when the OS keychain
+    /// can't be reached, as on Linux without a Secret Service; when off,
+    /// storing them fails instead
+    #[serde(default = "default_true")]
+    pub secret_file_fallback: bool,
 }
 
 fn default_true() -> bool {
@@ -222,6 +699,14 @@ fn default_suggested_apps_count() -> usize {
     8
 }
 
+fn default_memory_budget_mb() -> usize {
+    256
+}
+
+fn default_currency_refresh_hours() -> u32 {
+    6
+}
+
 impl Default for UserSettings {
     fn default() -> Self {
         Self {
@@ -238,11 +723,44 @@ impl Default for UserSettings {
             theme_mode: ThemeMode::System,
             custom_shortcut: None,
             launcher_theme: LauncherTheme::default(),
+            accessibility: AccessibilitySettings::default(),
+            power: PowerSettings::default(),
+            memory_budget_mb: default_memory_budget_mb(),
+            app_profiles: Vec::new(),
+            capture_folder: None,
+            clipboard_history: true,
+            quicklinks: Vec::new(),
+            aliases: Vec::new(),
+            fallbacks: default_fallbacks(),
+            focus: FocusSettings::default(),
+            schedules: Vec::new(),
+            currency_refresh_hours: default_currency_refresh_hours(),
+            calculator_locale: None,
+            frecency_weights: HashMap::new(),
+            provider_budgets_ms: HashMap::new(),
+            provider_settings: HashMap::new(),
+            plugin_dev_mode: false,
+            require_signed_plugins: false,
+            plugin_limits: PluginLimits::default(),
+            plugin_storage_quota_mb: 0,
+            plugin_storage_quotas: HashMap::new(),
+            command_policy: CommandPolicy::default(),
+            untrusted_sandbox: Sandbox::default(),
+            window_hotkeys: HashMap::new(),
+            secret_file_fallback: true,
         }
     }
 }
 
 impl UserSettings {
+    /// How search treats `provider_id`, with the defaults if it isn't listed
+    pub fn provider(&self, provider_id: &str) -> ProviderSettings {
+        self.provider_settings
+            .get(provider_id)
+            .cloned()
+            .unwrap_or_default()
+    }
+
     fn default_widget_layout() -> Vec<WidgetPlacement> {
         vec![
             WidgetPlacement {
@@ -285,10 +803,14 @@ impl UserSettings {
     }
 }
 
+/// Called with the settings before and after each change
+pub type SettingsListener = Arc<dyn Fn(&UserSettings, &UserSettings) + Send + Sync>;
+
 /// Settings store with persistence
 pub struct SettingsStore {
     settings: RwLock<UserSettings>,
     path: PathBuf,
+    listener: RwLock<Option<SettingsListener>>,
 }
 
 impl SettingsStore {
@@ -303,12 +825,12 @@ impl SettingsStore {
         Self {
             settings: RwLock::new(settings),
             path,
+            listener: RwLock::new(None),
         }
     }
 
     fn load_from_file(path: &PathBuf) -> Option<UserSettings> {
-        let content = std::fs::read_to_string(path).ok()?;
-        serde_json::from_str(&content).ok()
+        crate::journal::load_json_store(path)
     }
 
     pub fn get(&self) -> UserSettings {
@@ -316,8 +838,7 @@ impl SettingsStore {
     }
 
     pub fn set(&self, settings: UserSettings) {
-        *self.settings.write() = settings;
-        self.save();
+        self.update(|s| *s = settings);
     }
 
     pub fn update<F>(&self, updater: F)
@@ -325,26 +846,39 @@ impl SettingsStore {
         F: FnOnce(&mut UserSettings),
     {
         let mut settings = self.settings.write();
+        let before = self.listener.read().is_some().then(|| settings.clone());
         updater(&mut settings);
+        let change = before.map(|before| (before, settings.clone()));
         drop(settings);
         self.save();
+
+        if let Some((before, after)) = change {
+            let listener = self.listener.read().clone();
+            if let Some(listener) = listener {
+                listener(&before, &after);
+            }
+        }
+    }
+
+    pub fn set_listener(
+        &self,
+        listener: impl Fn(&UserSettings, &UserSettings) + Send + Sync + 'static,
+    ) {
+        *self.listener.write() = Some(Arc::new(listener));
     }
 
     pub fn save(&self) {
         let settings = self.settings.read();
 
-        if let Some(parent) = self.path.parent() {
-            let _ = std::fs::create_dir_all(parent);
-        }
-
         if let Ok(json) = serde_json::to_string_pretty(&*settings) {
-            let _ = std::fs::write(&self.path, json);
+            if let Err(e) = crate::journal::write_json_store(&self.path, &json) {
+                eprintln!("Failed to save settings: {}", e);
+            }
         }
     }
 
     pub fn reset(&self) {
-        *self.settings.write() = UserSettings::default();
-        self.save();
+        self.update(|s| *s = UserSettings::default());
     }
 
     // Window position helpers
@@ -442,12 +976,113 @@ impl SettingsStore {
         });
     }
 
+    /// Pin an app at `position`, or last if there are fewer pins now
+    pub fn pin_app_at(&self, app_id: String, position: usize) {
+        self.update(|s| {
+            if !s.pinned_apps.contains(&app_id) {
+                let position = position.min(s.pinned_apps.len());
+                s.pinned_apps.insert(position, app_id);
+            }
+        });
+    }
+
     pub fn unpin_app(&self, app_id: &str) {
         self.update(|s| {
             s.pinned_apps.retain(|id| id != app_id);
         });
     }
 
+    /// Put removed widgets back where they were, skipping any still placed
+    pub fn restore_widgets(&self, placements: Vec<WidgetPlacement>) {
+        self.update(|s| {
+            for placement in placements {
+                let placed = s
+                    .widget_layout
+                    .iter()
+                    .any(|w| w.instance_id == placement.instance_id);
+                if !placed {
+                    s.widget_layout.push(placement);
+                }
+            }
+        });
+    }
+
+    // Quicklink helpers
+    /// Add a quicklink, replacing one with the same name
+    pub fn add_quicklink(&self, quicklink: Quicklink) {
+        self.update(|s| {
+            s.quicklinks
+                .retain(|q| !q.name.eq_ignore_ascii_case(&quicklink.name));
+            s.quicklinks.push(quicklink);
+        });
+    }
+
+    /// Replace the quicklink named `name` where it stands, e.g. after an edit
+    pub fn replace_quicklink(&self, name: &str, quicklink: Quicklink) {
+        self.update(|s| {
+            if let Some(existing) = s
+                .quicklinks
+                .iter_mut()
+                .find(|q| q.name.eq_ignore_ascii_case(name))
+            {
+                *existing = quicklink;
+            }
+        });
+    }
+
+    pub fn remove_quicklink(&self, name: &str) {
+        self.update(|s| {
+            s.quicklinks.retain(|q| !q.name.eq_ignore_ascii_case(name));
+        });
+    }
+
+    /// Save an alias, replacing one with the same keyword
+    pub fn add_alias(&self, alias: SearchAlias) {
+        self.update(|s| {
+            s.aliases
+                .retain(|a| !a.keyword.eq_ignore_ascii_case(&alias.keyword));
+            s.aliases.push(alias);
+        });
+    }
+
+    pub fn remove_alias(&self, keyword: &str) {
+        self.update(|s| {
+            s.aliases
+                .retain(|a| !a.keyword.eq_ignore_ascii_case(keyword));
+        });
+    }
+
+    pub fn set_fallbacks(&self, fallbacks: Vec<Fallback>) {
+        self.update(|s| s.fallbacks = fallbacks);
+    }
+
+    /// Save how search treats a provider; the defaults aren't stored
+    pub fn set_provider_settings(&self, provider_id: &str, provider: ProviderSettings) {
+        self.update(|s| {
+            if provider == ProviderSettings::default() {
+                s.provider_settings.remove(provider_id);
+            } else {
+                s.provider_settings
+                    .insert(provider_id.to_string(), provider);
+            }
+        });
+    }
+
+    /// Save a schedule, replacing the one with the same id
+    pub fn save_schedule(&self, schedule: Schedule) {
+        self.update(|s| {
+            let existing = s.schedules.iter().position(|x| x.id == schedule.id);
+            match existing {
+                Some(index) => s.schedules[index] = schedule,
+                None => s.schedules.push(schedule),
+            }
+        });
+    }
+
+    pub fn remove_schedule(&self, id: &str) {
+        self.update(|s| s.schedules.retain(|x| x.id != id));
+    }
+
     pub fn reorder_pinned_apps(&self, app_ids: Vec<String>) {
         self.update(|s| {
             s.pinned_apps = app_ids;
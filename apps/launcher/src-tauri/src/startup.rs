@@ -0,0 +1,109 @@
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// Number of consecutive crashed startups before safe mode kicks in
+const CRASH_THRESHOLD: u32 = 3;
+
+/// How long the app has to stay up for a startup to count as successful
+pub const STABLE_AFTER_SECS: u64 = 30;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SafeModeReason {
+    /// Started with `--safe-mode`
+    Requested,
+    /// The previous startups crashed before becoming stable
+    RepeatedCrashes,
+}
+
+/// How the launcher was started, reported to the UI for the safe mode banner
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StartupMode {
+    pub safe_mode: bool,
+    pub reason: Option<SafeModeReason>,
+    pub consecutive_crashes: u32,
+}
+
+/// Persisted between runs to detect startups that never became stable
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct StartupState {
+    consecutive_crashes: u32,
+    /// Set at startup and cleared once the app is stable
+    pending: bool,
+}
+
+fn state_path() -> PathBuf {
+    dirs::data_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("launcher")
+        .join("startup_state.json")
+}
+
+fn load_state() -> StartupState {
+    std::fs::read_to_string(state_path())
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn save_state(state: &StartupState) {
+    let path = state_path();
+    if let Some(parent) = path.parent() {
+        let _ = std::fs::create_dir_all(parent);
+    }
+    if let Ok(json) = serde_json::to_string(state) {
+        let _ = std::fs::write(path, json);
+    }
+}
+
+/// Decide the startup mode, without recording anything, so a second
+/// instance that exits right away leaves no trace.
+///
+/// A startup that is still pending when the next one begins crashed (or was
+/// killed) before [`mark_stable`] ran, so it counts towards the threshold.
+pub fn detect() -> StartupMode {
+    let state = load_state();
+    let consecutive_crashes = state.consecutive_crashes + u32::from(state.pending);
+
+    let reason = if std::env::args().any(|arg| arg == "--safe-mode") {
+        Some(SafeModeReason::Requested)
+    } else if consecutive_crashes >= CRASH_THRESHOLD {
+        Some(SafeModeReason::RepeatedCrashes)
+    } else {
+        None
+    };
+
+    StartupMode {
+        safe_mode: reason.is_some(),
+        reason,
+        consecutive_crashes,
+    }
+}
+
+/// Record that a startup is in progress; called once this is the only
+/// instance running
+pub fn begin(mode: &StartupMode) {
+    save_state(&StartupState {
+        consecutive_crashes: mode.consecutive_crashes,
+        pending: true,
+    });
+}
+
+/// Record that the current run is healthy, once it has stayed up for a
+/// while or quits cleanly.
+///
+/// A normal run resets the crash counter. A safe mode run keeps it, so the
+/// next launch stays in safe mode until the user leaves it with [`reset`].
+pub fn mark_stable(mode: &StartupMode) {
+    let mut state = load_state();
+    state.pending = false;
+    if !mode.safe_mode {
+        state.consecutive_crashes = 0;
+    }
+    save_state(&state);
+}
+
+/// Forget previous crashes so the next launch starts normally
+pub fn reset() {
+    save_state(&StartupState::default());
+}
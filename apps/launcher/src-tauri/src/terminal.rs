@@ -2,6 +2,7 @@
 //!
 //! Provides cross-platform terminal support using portable-pty.
 
+use crate::settings::Sandbox;
 use parking_lot::Mutex;
 use portable_pty::{native_pty_system, CommandBuilder, PtyPair, PtySize};
 use std::collections::HashMap;
@@ -71,13 +72,14 @@ impl TerminalManager {
         }
     }
 
-    /// Spawn a new terminal session
+    /// Spawn a new terminal session; `sandbox` is None in a trusted folder
     pub fn spawn_terminal(
         &self,
         id: TerminalId,
         cols: u16,
         rows: u16,
         cwd: Option<String>,
+        sandbox: Option<Sandbox>,
     ) -> Result<(), String> {
         // Check if session already exists
         if self.sessions.lock().contains_key(&id) {
@@ -96,7 +98,9 @@ impl TerminalManager {
             .map_err(|e| format!("Failed to open PTY: {}", e))?;
 
         let shell = Self::get_default_shell();
-        let mut cmd = CommandBuilder::new(&shell);
+        let (program, args) = crate::trust::sandboxed_command(sandbox, &shell, &[])?;
+        let mut cmd = CommandBuilder::new(program);
+        cmd.args(args);
 
         // Set working directory if specified
         if let Some(dir) = cwd {
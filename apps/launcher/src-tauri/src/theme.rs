@@ -198,3 +198,87 @@ fn get_macos_theme() -> SystemTheme {
         window_blur_supported: true,
     }
 }
+
+/// Whether to cut down animations and raise contrast
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct AccessibilityPreferences {
+    pub reduce_motion: bool,
+    pub high_contrast: bool,
+}
+
+pub fn get_system_accessibility() -> AccessibilityPreferences {
+    #[cfg(target_os = "linux")]
+    {
+        get_linux_accessibility()
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        get_windows_accessibility()
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        get_macos_accessibility()
+    }
+
+    #[cfg(not(any(target_os = "linux", target_os = "windows", target_os = "macos")))]
+    {
+        AccessibilityPreferences::default()
+    }
+}
+
+/// Trimmed output of a command that reads an OS setting
+#[cfg(any(target_os = "linux", target_os = "windows", target_os = "macos"))]
+fn read_setting(program: &str, args: &[&str]) -> Option<String> {
+    let output = Command::new(program).args(args).output().ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+#[cfg(target_os = "linux")]
+fn get_linux_accessibility() -> AccessibilityPreferences {
+    let gsettings = |schema: &str, key: &str| read_setting("gsettings", &["get", schema, key]);
+
+    AccessibilityPreferences {
+        reduce_motion: gsettings("org.gnome.desktop.interface", "enable-animations").as_deref()
+            == Some("false"),
+        high_contrast: gsettings("org.gnome.desktop.a11y.interface", "high-contrast").as_deref()
+            == Some("true"),
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn get_windows_accessibility() -> AccessibilityPreferences {
+    // `reg query` prints the value last: "    Flags    REG_SZ    126"
+    let value = |key: &str, name: &str| {
+        read_setting("reg", &["query", key, "/v", name])
+            .and_then(|output| output.split_whitespace().last().map(String::from))
+    };
+    // Bit 0 of the flags is "high contrast on"
+    let high_contrast = value(r"HKCU\Control Panel\Accessibility\HighContrast", "Flags")
+        .and_then(|flags| flags.parse::<u32>().ok())
+        .is_some_and(|flags| flags & 1 != 0);
+
+    AccessibilityPreferences {
+        // Turning off animation effects also turns off the minimize animation
+        reduce_motion: value(r"HKCU\Control Panel\Desktop\WindowMetrics", "MinAnimate").as_deref()
+            == Some("0"),
+        high_contrast,
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn get_macos_accessibility() -> AccessibilityPreferences {
+    let enabled = |key: &str| {
+        read_setting("defaults", &["read", "com.apple.universalaccess", key]).as_deref()
+            == Some("1")
+    };
+
+    AccessibilityPreferences {
+        reduce_motion: enabled("reduceMotion"),
+        high_contrast: enabled("increaseContrast"),
+    }
+}
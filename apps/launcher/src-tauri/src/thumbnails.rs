@@ -0,0 +1,404 @@
+//! Thumbnails for results whose icon is an image file.
+//!
+//! Loading the files themselves through the asset protocol made the webview
+//! decode full-size photos for a 20px icon, and a long list of them stalled
+//! scrolling. Thumbnails are served from the `thumb://` protocol instead:
+//! requests queue up newest first, so the rows on screen are drawn before
+//! ones already scrolled past, and a few worker threads shrink the images
+//! off the webview's thread. The top results of a search are made before
+//! they're asked for. Rows show their placeholder emoji until the image
+//! arrives; a request pushed out of a full queue is answered with 503 and
+//! the placeholder stays.
+
+use crate::cache::ManagedCache;
+use crate::providers::{ResultIcon, SearchResult};
+use parking_lot::{Condvar, Mutex};
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, VecDeque};
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::UNIX_EPOCH;
+use tauri::http::{header, Request, Response, StatusCode};
+use tauri::UriSchemeResponder;
+
+/// Protocol name, as in `thumb://localhost/<path>?size=40`
+pub const SCHEME: &str = "thumb";
+
+/// Rows draw icons at 20px; this leaves room for 2x displays
+const DEFAULT_SIZE: u32 = 40;
+const MAX_SIZE: u32 = 256;
+const WORKERS: usize = 2;
+/// Requests waiting beyond this many are for rows long scrolled past
+const MAX_WAITING: usize = 48;
+/// Results of a search whose thumbnails are made before they're asked for
+const PREWARM_COUNT: usize = 8;
+const MEMORY_LIMIT: usize = 32 * 1024 * 1024;
+const MAX_SOURCE_BYTES: u64 = 64 * 1024 * 1024;
+/// SVGs are sent as they are; the webview draws them sharp at any size
+const MAX_SVG_BYTES: u64 = 512 * 1024;
+const RASTER_EXTENSIONS: &[&str] = &["png", "jpg", "jpeg", "webp"];
+
+/// Whether files with this extension get a thumbnail instead of an emoji
+pub fn has_thumbnail(extension: &str) -> bool {
+    extension == "svg" || RASTER_EXTENSIONS.contains(&extension)
+}
+
+#[derive(Debug, Clone)]
+pub struct Thumbnail {
+    bytes: Arc<Vec<u8>>,
+    content_type: &'static str,
+}
+
+/// What a thumbnail request is answered with
+pub enum Reply {
+    Image(Thumbnail),
+    /// Pushed out of a full queue; asking again later may work
+    Busy,
+    /// Missing, too large, or not an image that can be drawn
+    Unavailable,
+}
+
+type Key = (PathBuf, u32);
+type Respond = Box<dyn FnOnce(Reply) + Send>;
+
+#[derive(Default)]
+struct Queue {
+    /// Asked for by the webview, newest first
+    requested: VecDeque<Key>,
+    /// Top results nothing has asked for yet
+    prewarm: VecDeque<Key>,
+    waiters: HashMap<Key, Vec<Respond>>,
+}
+
+#[derive(Default)]
+struct Memory {
+    entries: HashMap<Key, Thumbnail>,
+    /// Least recently used first
+    order: VecDeque<Key>,
+    bytes: usize,
+}
+
+pub struct Thumbnails {
+    dir: PathBuf,
+    queue: Mutex<Queue>,
+    ready: Condvar,
+    memory: Mutex<Memory>,
+}
+
+impl Thumbnails {
+    pub fn new() -> Self {
+        // Under the cache dir, so clearing caches removes them too
+        let dir = dirs::cache_dir()
+            .unwrap_or_else(|| PathBuf::from("."))
+            .join("launcher")
+            .join("thumbnails");
+        Self {
+            dir,
+            queue: Mutex::new(Queue::default()),
+            ready: Condvar::new(),
+            memory: Mutex::new(Memory::default()),
+        }
+    }
+
+    /// Start the worker threads
+    pub fn start(self: &Arc<Self>) {
+        for i in 0..WORKERS {
+            let thumbnails = self.clone();
+            let spawned = std::thread::Builder::new()
+                .name(format!("thumbnails-{}", i))
+                .spawn(move || thumbnails.work());
+            if let Err(e) = spawned {
+                eprintln!("Failed to start thumbnail worker: {}", e);
+            }
+        }
+    }
+
+    fn work(&self) {
+        loop {
+            let key = {
+                let mut queue = self.queue.lock();
+                loop {
+                    let next = queue
+                        .requested
+                        .pop_front()
+                        .or_else(|| queue.prewarm.pop_front());
+                    match next {
+                        Some(key) => break key,
+                        None => self.ready.wait(&mut queue),
+                    }
+                }
+            };
+
+            // A prewarmed copy may have been made since this was queued
+            let thumbnail = self.remembered(&key).or_else(|| self.make(&key));
+            if let Some(thumbnail) = &thumbnail {
+                self.remember(key.clone(), thumbnail.clone());
+            }
+            let waiters = self.queue.lock().waiters.remove(&key).unwrap_or_default();
+            for respond in waiters {
+                respond(match &thumbnail {
+                    Some(thumbnail) => Reply::Image(thumbnail.clone()),
+                    None => Reply::Unavailable,
+                });
+            }
+        }
+    }
+
+    /// Ask for the thumbnail of `path`, answered from memory at once or by
+    /// a worker once it's made
+    pub fn request(&self, path: PathBuf, size: u32, respond: Respond) {
+        let key = (path, size);
+        if let Some(thumbnail) = self.remembered(&key) {
+            return respond(Reply::Image(thumbnail));
+        }
+
+        let dropped = {
+            let mut queue = self.queue.lock();
+            queue.prewarm.retain(|k| *k != key);
+            queue.requested.retain(|k| *k != key);
+            queue.requested.push_front(key.clone());
+            queue.waiters.entry(key).or_default().push(respond);
+
+            let mut dropped = Vec::new();
+            while queue.requested.len() > MAX_WAITING {
+                if let Some(oldest) = queue.requested.pop_back() {
+                    dropped.extend(queue.waiters.remove(&oldest).unwrap_or_default());
+                }
+            }
+            dropped
+        };
+        self.ready.notify_one();
+        for respond in dropped {
+            respond(Reply::Busy);
+        }
+    }
+
+    /// Make thumbnails for the top `results` while nothing else is asked
+    /// for, replacing those of the previous search
+    pub fn prewarm(&self, results: &[SearchResult]) {
+        let keys: VecDeque<Key> = results
+            .iter()
+            .take(PREWARM_COUNT)
+            .filter_map(|result| match &result.icon {
+                ResultIcon::Thumbnail { path, .. } => Some((PathBuf::from(path), DEFAULT_SIZE)),
+                _ => None,
+            })
+            .filter(|key| !self.memory.lock().entries.contains_key(key))
+            .collect();
+        if keys.is_empty() {
+            return;
+        }
+        self.queue.lock().prewarm = keys;
+        self.ready.notify_all();
+    }
+
+    fn remembered(&self, key: &Key) -> Option<Thumbnail> {
+        let mut memory = self.memory.lock();
+        let thumbnail = memory.entries.get(key).cloned()?;
+        memory.order.retain(|k| k != key);
+        memory.order.push_back(key.clone());
+        Some(thumbnail)
+    }
+
+    fn remember(&self, key: Key, thumbnail: Thumbnail) {
+        let mut memory = self.memory.lock();
+        if memory.entries.contains_key(&key) {
+            return;
+        }
+        memory.bytes += thumbnail.bytes.len();
+        memory.order.push_back(key.clone());
+        memory.entries.insert(key, thumbnail);
+        while memory.bytes > MEMORY_LIMIT {
+            if shrink(&mut memory) == 0 {
+                break;
+            }
+        }
+    }
+
+    fn make(&self, (path, size): &Key) -> Option<Thumbnail> {
+        let metadata = std::fs::metadata(path).ok().filter(|m| m.is_file())?;
+        let extension = path.extension()?.to_str()?.to_lowercase();
+        if extension == "svg" {
+            if metadata.len() > MAX_SVG_BYTES {
+                return None;
+            }
+            return Some(Thumbnail {
+                bytes: Arc::new(std::fs::read(path).ok()?),
+                content_type: "image/svg+xml",
+            });
+        }
+        if !RASTER_EXTENSIONS.contains(&extension.as_str()) || metadata.len() > MAX_SOURCE_BYTES {
+            return None;
+        }
+
+        let modified = metadata
+            .modified()
+            .ok()
+            .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+            .map(|d| d.as_secs())
+            .unwrap_or_default();
+        let stem = disk_stem(path, modified, metadata.len(), *size);
+        let cached = self.dir.join(format!("{}.png", stem));
+        let png = match std::fs::read(&cached) {
+            Ok(png) => png,
+            Err(_) => {
+                let png = render(&std::fs::read(path).ok()?, *size).ok()?;
+                if std::fs::create_dir_all(&self.dir).is_ok() {
+                    let _ = std::fs::write(&cached, &png);
+                }
+                png
+            }
+        };
+        Some(Thumbnail {
+            bytes: Arc::new(png),
+            content_type: "image/png",
+        })
+    }
+}
+
+impl Default for Thumbnails {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Drop the least recently used thumbnail, returning its size
+fn shrink(memory: &mut Memory) -> usize {
+    let Some(key) = memory.order.pop_front() else {
+        return 0;
+    };
+    let freed = memory
+        .entries
+        .remove(&key)
+        .map_or(0, |thumbnail| thumbnail.bytes.len());
+    memory.bytes -= freed;
+    freed
+}
+
+impl ManagedCache for Thumbnails {
+    fn cache_name(&self) -> &str {
+        "thumbnails"
+    }
+
+    fn cost(&self) -> usize {
+        self.memory.lock().bytes
+    }
+
+    fn evict(&self, target_bytes: usize) -> usize {
+        let mut memory = self.memory.lock();
+        let mut freed = 0;
+        while memory.bytes > target_bytes && !memory.order.is_empty() {
+            freed += shrink(&mut memory);
+        }
+        freed
+    }
+}
+
+/// Name of a thumbnail on disk; a changed file gets a new one
+fn disk_stem(path: &Path, modified: u64, len: u64, size: u32) -> String {
+    let key = format!("{}\n{}\n{}\n{}", path.display(), modified, len, size);
+    let hash = format!("{:x}", Sha256::digest(key.as_bytes()));
+    hash[..16].to_string()
+}
+
+/// Shrink an image to fit `size`, as PNG
+fn render(bytes: &[u8], size: u32) -> Result<Vec<u8>, String> {
+    let image = image::load_from_memory(bytes).map_err(|e| format!("Not an image: {}", e))?;
+    let image = if image.width() > size || image.height() > size {
+        image.thumbnail(size, size)
+    } else {
+        image
+    };
+    let mut png = Vec::new();
+    image
+        .write_to(&mut Cursor::new(&mut png), image::ImageFormat::Png)
+        .map_err(|e| format!("Failed to encode thumbnail: {}", e))?;
+    Ok(png)
+}
+
+/// The file and size a `thumb://` request asks for
+fn parse_request(request: &Request<Vec<u8>>) -> Option<(PathBuf, u32)> {
+    let uri = request.uri();
+    let path = urlencoding::decode(uri.path().trim_start_matches('/')).ok()?;
+    if path.is_empty() {
+        return None;
+    }
+    let size = uri
+        .query()
+        .unwrap_or_default()
+        .split('&')
+        .find_map(|pair| pair.strip_prefix("size="))
+        .and_then(|size| size.parse::<u32>().ok())
+        .unwrap_or(DEFAULT_SIZE)
+        .clamp(1, MAX_SIZE);
+    Some((PathBuf::from(path.into_owned()), size))
+}
+
+fn status(status: StatusCode) -> Response<Vec<u8>> {
+    let mut response = Response::new(Vec::new());
+    *response.status_mut() = status;
+    response
+}
+
+/// Answer a `thumb://` request without blocking the webview
+pub fn handle(thumbnails: &Thumbnails, request: Request<Vec<u8>>, responder: UriSchemeResponder) {
+    let Some((path, size)) = parse_request(&request) else {
+        return responder.respond(status(StatusCode::BAD_REQUEST));
+    };
+    thumbnails.request(
+        path,
+        size,
+        Box::new(move |reply| {
+            let response = match reply {
+                Reply::Image(thumbnail) => Response::builder()
+                    .header(header::CONTENT_TYPE, thumbnail.content_type)
+                    .header(header::CACHE_CONTROL, "max-age=3600")
+                    .body(thumbnail.bytes.to_vec())
+                    .unwrap_or_else(|_| status(StatusCode::INTERNAL_SERVER_ERROR)),
+                Reply::Busy => status(StatusCode::SERVICE_UNAVAILABLE),
+                Reply::Unavailable => status(StatusCode::NOT_FOUND),
+            };
+            responder.respond(response);
+        }),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn queues_newest_request_first_and_drops_oldest() {
+        let thumbnails = Thumbnails::new();
+        let busy = Arc::new(Mutex::new(Vec::new()));
+        for i in 0..MAX_WAITING + 2 {
+            let busy = busy.clone();
+            thumbnails.request(
+                PathBuf::from(format!("/photos/{}.jpg", i)),
+                DEFAULT_SIZE,
+                Box::new(move |reply| {
+                    if matches!(reply, Reply::Busy) {
+                        busy.lock().push(i);
+                    }
+                }),
+            );
+        }
+
+        // No workers were started, so only the overflow has been answered
+        assert_eq!(*busy.lock(), [0, 1]);
+        let queue = thumbnails.queue.lock();
+        assert_eq!(
+            queue.requested.front().map(|(path, _)| path.as_path()),
+            Some(Path::new("/photos/49.jpg"))
+        );
+        assert_eq!(queue.waiters.len(), MAX_WAITING);
+
+        let mut png = Vec::new();
+        image::DynamicImage::new_rgba8(400, 200)
+            .write_to(&mut Cursor::new(&mut png), image::ImageFormat::Png)
+            .unwrap();
+        let thumbnail = image::load_from_memory(&render(&png, 40).unwrap()).unwrap();
+        assert_eq!((thumbnail.width(), thumbnail.height()), (40, 20));
+    }
+}
@@ -0,0 +1,276 @@
+//! Folders the user trusts to run Codex, dev servers and terminals in
+//! without a sandbox. The first time something starts in another folder
+//! the user is asked whether to trust it; if not, it runs sandboxed,
+//! read-only or workspace-write per the `untrusted_sandbox` setting.
+//! Trusting a folder trusts everything under it.
+
+use crate::settings::{Sandbox, SettingsStore};
+use chrono::{DateTime, Utc};
+use parking_lot::{Mutex, RwLock};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::Arc;
+use tokio::sync::oneshot;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrustedFolder {
+    pub path: PathBuf,
+    pub trusted_at: DateTime<Utc>,
+}
+
+/// Asks the user whether to trust a folder something is starting in
+#[derive(Debug, Clone)]
+pub struct TrustPrompt {
+    pub path: PathBuf,
+    /// What was started: `codex`, `dev-server` or `terminal`
+    pub purpose: String,
+    /// How it runs until the folder is trusted
+    pub sandbox: Sandbox,
+}
+
+/// Shows a prompt and sends whether the user trusted the folder
+type PromptListener = Arc<dyn Fn(TrustPrompt, oneshot::Sender<bool>) + Send + Sync>;
+
+/// The folder as the filesystem names it, so `~/code/../code` and symlinks
+/// match the folder they lead to
+fn resolve(folder: &Path) -> PathBuf {
+    folder
+        .canonicalize()
+        .unwrap_or_else(|_| folder.to_path_buf())
+}
+
+fn covered(folders: &[TrustedFolder], folder: &Path) -> bool {
+    folders
+        .iter()
+        .any(|trusted| folder.starts_with(&trusted.path))
+}
+
+/// The `--sandbox` policy for `codex exec`; None is a trusted folder
+pub fn codex_policy(sandbox: Option<Sandbox>) -> &'static str {
+    match sandbox {
+        Some(Sandbox::ReadOnly) => "read-only",
+        Some(Sandbox::WorkspaceWrite) => "workspace-write",
+        None => "danger-full-access",
+    }
+}
+
+/// `codex sandbox`'s name for this platform, if it can sandbox here
+fn sandbox_platform() -> Option<&'static str> {
+    if cfg!(target_os = "macos") {
+        Some("macos")
+    } else if cfg!(target_os = "linux") {
+        Some("linux")
+    } else {
+        None
+    }
+}
+
+lazy_static::lazy_static! {
+    // Checked once; installing Codex takes effect after a restart
+    static ref CODEX_INSTALLED: bool = Command::new("codex")
+        .arg("-V")
+        .output()
+        .is_ok_and(|output| output.status.success());
+}
+
+/// Whether `sandboxed_command` can sandbox anything here
+pub fn can_sandbox() -> bool {
+    sandbox_platform().is_some() && *CODEX_INSTALLED
+}
+
+/// The program and arguments that run `program` in `sandbox`, through
+/// `codex sandbox` (Seatbelt on macOS, Landlock and seccomp on Linux). The
+/// sandbox also cuts off the network, so a dev server may not start until
+/// its folder is trusted. Without a sandbox to run in, on Windows or when
+/// Codex isn't installed, nothing runs until the folder is trusted.
+pub fn sandboxed_command(
+    sandbox: Option<Sandbox>,
+    program: &str,
+    args: &[String],
+) -> Result<(String, Vec<String>), String> {
+    let Some(sandbox) = sandbox else {
+        return Ok((program.to_string(), args.to_vec()));
+    };
+    let Some(platform) = sandbox_platform() else {
+        return Err(
+            "Sandboxing isn't available on this platform; trust the folder to run this in it"
+                .to_string(),
+        );
+    };
+    if !*CODEX_INSTALLED {
+        return Err(
+            "Sandboxing needs Codex installed; install it or trust the folder to run this in it"
+                .to_string(),
+        );
+    }
+
+    let mut wrapped = vec!["sandbox".to_string(), platform.to_string()];
+    if sandbox == Sandbox::WorkspaceWrite {
+        // Writable working directory, as `codex exec --full-auto` has
+        wrapped.push("--full-auto".to_string());
+    }
+    wrapped.push("--".to_string());
+    wrapped.push(program.to_string());
+    wrapped.extend(args.iter().cloned());
+    Ok(("codex".to_string(), wrapped))
+}
+
+pub struct WorkspaceTrust {
+    folders: RwLock<Vec<TrustedFolder>>,
+    /// Folders asked about but not trusted yet, so each is asked once
+    prompted: Mutex<HashSet<PathBuf>>,
+    prompt_listener: RwLock<Option<PromptListener>>,
+    settings: Arc<SettingsStore>,
+    path: PathBuf,
+}
+
+impl WorkspaceTrust {
+    pub fn new(settings: Arc<SettingsStore>) -> Self {
+        let path = dirs::data_dir()
+            .unwrap_or_else(|| PathBuf::from("."))
+            .join("launcher")
+            .join("trusted_folders.json");
+
+        Self {
+            folders: RwLock::new(crate::journal::load_json_store(&path).unwrap_or_default()),
+            prompted: Mutex::new(HashSet::new()),
+            prompt_listener: RwLock::new(None),
+            settings,
+            path,
+        }
+    }
+
+    /// Called when something starts in a folder that isn't trusted, to ask
+    /// the user whether to trust it
+    pub fn set_prompt_listener(
+        &self,
+        listener: impl Fn(TrustPrompt, oneshot::Sender<bool>) + Send + Sync + 'static,
+    ) {
+        *self.prompt_listener.write() = Some(Arc::new(listener));
+    }
+
+    pub fn folders(&self) -> Vec<TrustedFolder> {
+        self.folders.read().clone()
+    }
+
+    /// Trust `folder` and everything under it; sessions and servers
+    /// already running keep their sandbox until restarted
+    pub fn trust(&self, folder: &str) -> Result<(), String> {
+        let folder = resolve(Path::new(folder));
+        if !folder.is_dir() {
+            return Err(format!("Not a folder: {}", folder.display()));
+        }
+        {
+            let mut folders = self.folders.write();
+            if covered(&folders, &folder) {
+                return Ok(());
+            }
+            // Folders under it are covered from now on
+            folders.retain(|trusted| !trusted.path.starts_with(&folder));
+            folders.push(TrustedFolder {
+                path: folder.clone(),
+                trusted_at: Utc::now(),
+            });
+        }
+        self.prompted
+            .lock()
+            .retain(|asked| !asked.starts_with(&folder));
+        self.save()
+    }
+
+    /// Stop trusting a folder; folders under it stay trusted only if they
+    /// were trusted on their own
+    pub fn untrust(&self, folder: &str) -> Result<(), String> {
+        let folder = resolve(Path::new(folder));
+        {
+            let mut folders = self.folders.write();
+            let before = folders.len();
+            folders.retain(|trusted| trusted.path != folder);
+            if folders.len() == before {
+                return Err(format!("{} is not a trusted folder", folder.display()));
+            }
+        }
+        self.save()
+    }
+
+    /// The sandbox to run `purpose` in `folder` with; None when the folder
+    /// is trusted. The first time an untrusted folder comes up the user is
+    /// asked whether to trust it, and `purpose` waits for the answer.
+    pub async fn sandbox_for(&self, folder: &Path, purpose: &str) -> Option<Sandbox> {
+        let folder = resolve(folder);
+        if covered(&self.folders.read(), &folder) {
+            return None;
+        }
+        let sandbox = self.settings.get().untrusted_sandbox;
+        if !self.prompted.lock().insert(folder.clone()) {
+            return Some(sandbox);
+        }
+        let listener = self.prompt_listener.read().clone();
+        let Some(listener) = listener else {
+            return Some(sandbox);
+        };
+
+        let (reply, answer) = oneshot::channel();
+        listener(
+            TrustPrompt {
+                path: folder.clone(),
+                purpose: purpose.to_string(),
+                sandbox,
+            },
+            reply,
+        );
+        if !answer.await.unwrap_or(false) {
+            return Some(sandbox);
+        }
+        match self.trust(&folder.to_string_lossy()) {
+            Ok(()) => None,
+            Err(e) => {
+                eprintln!("Failed to trust {}: {}", folder.display(), e);
+                Some(sandbox)
+            }
+        }
+    }
+
+    fn save(&self) -> Result<(), String> {
+        let json = serde_json::to_string_pretty(&*self.folders.read())
+            .map_err(|e| format!("Failed to serialize trusted folders: {}", e))?;
+        crate::journal::write_json_store(&self.path, &json)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn trusts_subfolders_and_wraps_untrusted_commands() {
+        let folders = vec![TrustedFolder {
+            path: PathBuf::from("/home/me/code"),
+            trusted_at: Utc::now(),
+        }];
+        assert!(covered(&folders, Path::new("/home/me/code")));
+        assert!(covered(&folders, Path::new("/home/me/code/launcher/src")));
+        assert!(!covered(&folders, Path::new("/home/me/code-old")));
+        assert!(!covered(&folders, Path::new("/home/me")));
+
+        let args = vec!["run".to_string(), "dev".to_string()];
+        assert_eq!(
+            sandboxed_command(None, "npm", &args),
+            ("npm".to_string(), args.clone())
+        );
+        assert_eq!(
+            codex_policy(Some(Sandbox::WorkspaceWrite)),
+            "workspace-write"
+        );
+        if cfg!(target_os = "linux") && codex_installed() {
+            let (program, wrapped) = sandboxed_command(Some(Sandbox::WorkspaceWrite), "npm", &args);
+            assert_eq!(program, "codex");
+            assert_eq!(
+                wrapped,
+                ["sandbox", "linux", "--full-auto", "--", "npm", "run", "dev"]
+            );
+        }
+    }
+}
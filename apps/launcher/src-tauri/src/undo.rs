@@ -0,0 +1,195 @@
+//! Undo for destructive launcher actions: unpinning an app, removing a
+//! widget or quicklink, trashing a file, disabling a plugin and editing a
+//! result in place, like renaming a file. Each records what it takes to put
+//! things back, newest first, and `undo_last_action` reverses the latest one.
+//! The history survives restarts so a change made just before quitting can
+//! still be taken back.
+
+use crate::settings::{Quicklink, SearchAlias, WidgetPlacement};
+use chrono::{DateTime, Utc};
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::path::PathBuf;
+
+/// Actions kept, newest first
+const HISTORY_LEN: usize = 50;
+
+/// A destructive action and what's needed to reverse it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum UndoableAction {
+    UnpinApp {
+        app_id: String,
+        position: usize,
+    },
+    RemoveWidgets {
+        placements: Vec<WidgetPlacement>,
+    },
+    RemoveQuicklink {
+        quicklink: Quicklink,
+    },
+    RemoveAlias {
+        alias: SearchAlias,
+    },
+    TrashFile {
+        path: PathBuf,
+        /// Where it went, on macOS where the trash can't be searched
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        trashed: Option<PathBuf>,
+    },
+    DisablePlugin {
+        id: String,
+        name: String,
+    },
+    RenameFile {
+        from: PathBuf,
+        to: PathBuf,
+    },
+    EditQuicklink {
+        before: Quicklink,
+        name: String,
+    },
+}
+
+impl UndoableAction {
+    /// What was done, as the undo button and history show it
+    fn describe(&self) -> String {
+        match self {
+            UndoableAction::UnpinApp { app_id, .. } => {
+                let name = app_id.rsplit(['/', '\\']).next().unwrap_or(app_id);
+                format!("Unpin {}", name)
+            }
+            UndoableAction::RemoveWidgets { placements } if placements.len() == 1 => {
+                format!("Remove {} widget", placements[0].widget_type)
+            }
+            UndoableAction::RemoveWidgets { placements } => {
+                format!("Remove {} widgets", placements.len())
+            }
+            UndoableAction::RemoveQuicklink { quicklink } => {
+                format!("Delete quicklink {}", quicklink.name)
+            }
+            UndoableAction::RemoveAlias { alias } => format!("Delete alias {}", alias.keyword),
+            UndoableAction::TrashFile { path, .. } => {
+                let name = path.file_name().unwrap_or(path.as_os_str());
+                format!("Move {} to Trash", name.to_string_lossy())
+            }
+            UndoableAction::DisablePlugin { name, .. } => format!("Disable {}", name),
+            UndoableAction::RenameFile { from, to } => {
+                let from = from.file_name().unwrap_or(from.as_os_str());
+                let to = to.file_name().unwrap_or(to.as_os_str());
+                format!(
+                    "Rename {} to {}",
+                    from.to_string_lossy(),
+                    to.to_string_lossy()
+                )
+            }
+            UndoableAction::EditQuicklink { before, .. } => {
+                format!("Edit quicklink {}", before.name)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UndoEntry {
+    pub at: DateTime<Utc>,
+    pub description: String,
+    pub action: UndoableAction,
+}
+
+pub struct UndoStack {
+    history: RwLock<VecDeque<UndoEntry>>,
+    path: PathBuf,
+}
+
+impl UndoStack {
+    pub fn new() -> Self {
+        let path = dirs::data_dir()
+            .unwrap_or_else(|| PathBuf::from("."))
+            .join("launcher")
+            .join("undo_history.json");
+
+        let history = crate::journal::load_json_store(&path).unwrap_or_default();
+
+        Self {
+            history: RwLock::new(history),
+            path,
+        }
+    }
+
+    fn save(&self) {
+        let history = self.history.read();
+
+        if let Ok(json) = serde_json::to_string_pretty(&*history) {
+            if let Err(e) = crate::journal::write_json_store(&self.path, &json) {
+                eprintln!("Failed to save undo history: {}", e);
+            }
+        }
+    }
+
+    pub fn record(&self, action: UndoableAction) {
+        {
+            let mut history = self.history.write();
+            history.push_front(UndoEntry {
+                at: Utc::now(),
+                description: action.describe(),
+                action,
+            });
+            history.truncate(HISTORY_LEN);
+        }
+        self.save();
+    }
+
+    /// Take the latest action off the stack to reverse it
+    pub fn pop(&self) -> Option<UndoEntry> {
+        let entry = self.history.write().pop_front();
+        if entry.is_some() {
+            self.save();
+        }
+        entry
+    }
+
+    /// Put back an entry that can't be reversed just now
+    pub fn push(&self, entry: UndoEntry) {
+        self.history.write().push_front(entry);
+        self.save();
+    }
+
+    /// Newest first
+    pub fn history(&self) -> Vec<UndoEntry> {
+        self.history.read().iter().cloned().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_a_bounded_stack() {
+        let stack = UndoStack {
+            history: RwLock::new(VecDeque::new()),
+            path: std::env::temp_dir().join("launcher-test-undo-history.json"),
+        };
+        for i in 0..HISTORY_LEN + 5 {
+            stack.record(UndoableAction::TrashFile {
+                path: PathBuf::from(format!("/tmp/file-{}.txt", i)),
+                trashed: None,
+            });
+        }
+        stack.record(UndoableAction::UnpinApp {
+            app_id: "/Applications/Safari.app".to_string(),
+            position: 2,
+        });
+
+        assert_eq!(stack.history().len(), HISTORY_LEN);
+        let latest = stack.pop().unwrap();
+        assert_eq!(latest.description, "Unpin Safari.app");
+        assert_eq!(
+            stack.pop().unwrap().description,
+            format!("Move file-{}.txt to Trash", HISTORY_LEN + 4)
+        );
+        assert_eq!(stack.history().len(), HISTORY_LEN - 2);
+    }
+}
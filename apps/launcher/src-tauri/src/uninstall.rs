@@ -0,0 +1,442 @@
+//! Uninstalling apps from their search results. Running the "uninstall"
+//! action only builds a plan and asks; the plan is shown in a dialog and
+//! runs once the user agrees.
+//!
+//! - Windows: the app's Apps & Features (ARP) uninstaller, or
+//!   `Remove-AppxPackage` for Store apps
+//! - macOS: the bundle and its support files go to the Trash and installer
+//!   receipts are forgotten
+//! - Linux: the owning Flatpak, Snap or apt package is removed
+
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// The app an uninstall is for, from the app index
+#[derive(Debug, Clone)]
+pub struct UninstallTarget {
+    pub name: String,
+    /// Desktop entry, `.app` bundle, Start Menu shortcut, or
+    /// `shell:AppsFolder\<AUMID>` for Store apps
+    pub path: String,
+}
+
+/// How a plan is carried out; each platform builds only some of these
+#[derive(Debug, Clone)]
+#[allow(dead_code)]
+enum Method {
+    /// Run a program; `wait` is false for uninstallers that show their own
+    /// window and outlive the process that started them
+    Run {
+        program: String,
+        args: Vec<String>,
+        wait: bool,
+    },
+    /// A Windows command line as written in the registry
+    CommandLine(String),
+    Trash {
+        bundle: PathBuf,
+        leftovers: Vec<PathBuf>,
+        receipts: Vec<String>,
+    },
+}
+
+/// What uninstalling an app will do, shown in the confirmation dialog
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UninstallPlan {
+    pub result_id: String,
+    pub name: String,
+    pub message: String,
+    /// Commands that will run and files that will be trashed
+    pub steps: Vec<String>,
+    #[serde(skip)]
+    method: Option<Method>,
+}
+
+/// Stdout of a command that ran successfully
+fn output(command: &mut Command) -> Result<String, String> {
+    let output = command
+        .output()
+        .map_err(|e| format!("Failed to run {:?}: {}", command.get_program(), e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "{:?} failed: {}",
+            command.get_program(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Where a desktop entry came from, judging by the folder it's exported to
+#[derive(Debug, PartialEq)]
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+enum LinuxSource {
+    Flatpak {
+        id: String,
+        user: bool,
+    },
+    Snap(String),
+    /// Look the file up in the dpkg database
+    System,
+}
+
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn linux_source(desktop_path: &str) -> LinuxSource {
+    let path = Path::new(desktop_path);
+    let stem = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+    if desktop_path.contains("/flatpak/exports/share/applications/") {
+        let user = dirs::home_dir().is_some_and(|home| path.starts_with(home));
+        return LinuxSource::Flatpak { id: stem, user };
+    }
+    if desktop_path.contains("/snapd/desktop/applications/") {
+        // Named <snap>_<app>.desktop
+        let snap = stem.split('_').next().unwrap_or(&stem).to_string();
+        return LinuxSource::Snap(snap);
+    }
+    LinuxSource::System
+}
+
+/// Package name from `dpkg -S` output such as
+/// `firefox-esr: /usr/share/applications/firefox-esr.desktop`
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_dpkg_owner(output: &str) -> Option<String> {
+    let line = output.lines().next()?;
+    let (packages, _) = line.split_once(": /")?;
+    let package = packages.split(',').next()?.trim();
+    (!package.is_empty()).then(|| package.to_string())
+}
+
+#[cfg(target_os = "linux")]
+fn platform_plan(target: &UninstallTarget) -> Result<(Method, Vec<String>), String> {
+    let (program, args): (&str, Vec<String>) = match linux_source(&target.path) {
+        LinuxSource::Flatpak { id, user } => {
+            let mut args = vec!["uninstall".to_string(), "-y".to_string()];
+            if user {
+                args.push("--user".to_string());
+            }
+            args.extend(["--noninteractive".to_string(), id]);
+            ("flatpak", args)
+        }
+        LinuxSource::Snap(snap) => ("pkexec", vec!["snap".into(), "remove".into(), snap]),
+        LinuxSource::System => {
+            let owner = output(Command::new("dpkg").args(["-S", &target.path]))
+                .ok()
+                .and_then(|out| parse_dpkg_owner(&out))
+                .ok_or_else(|| {
+                    format!(
+                        "Couldn't find the package that installed {}. Only Flatpak, Snap and \
+                         apt packages can be uninstalled from here.",
+                        target.name
+                    )
+                })?;
+            let args = vec!["apt-get".into(), "remove".into(), "-y".into(), owner];
+            ("pkexec", args)
+        }
+    };
+    let step = format!("{} {}", program, args.join(" "));
+    let method = Method::Run {
+        program: program.to_string(),
+        args,
+        wait: true,
+    };
+    Ok((method, vec![step]))
+}
+
+/// Package ids from `pkgutil --file-info` output, one `pkgid: <id>` line
+/// per receipt
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+fn parse_pkgutil_ids(output: &str) -> Vec<String> {
+    output
+        .lines()
+        .filter_map(|line| line.strip_prefix("pkgid:"))
+        .map(|id| id.trim().to_string())
+        .filter(|id| !id.is_empty())
+        .collect()
+}
+
+/// Files apps leave in ~/Library, by app name or bundle id
+#[cfg(target_os = "macos")]
+fn mac_leftovers(name: &str, bundle_id: Option<&str>) -> Vec<PathBuf> {
+    let Some(library) = dirs::home_dir().map(|h| h.join("Library")) else {
+        return Vec::new();
+    };
+    let mut paths = vec![
+        library.join("Application Support").join(name),
+        library.join("Logs").join(name),
+    ];
+    if let Some(id) = bundle_id {
+        paths.extend([
+            library.join("Application Support").join(id),
+            library.join("Caches").join(id),
+            library.join("Containers").join(id),
+            library.join("HTTPStorages").join(id),
+            library.join("Preferences").join(format!("{}.plist", id)),
+            library
+                .join("Saved Application State")
+                .join(format!("{}.savedState", id)),
+        ]);
+    }
+    paths.into_iter().filter(|p| p.exists()).collect()
+}
+
+#[cfg(target_os = "macos")]
+fn platform_plan(target: &UninstallTarget) -> Result<(Method, Vec<String>), String> {
+    let bundle = PathBuf::from(&target.path);
+    if bundle.starts_with("/System") {
+        return Err(format!(
+            "{} is part of macOS and can't be uninstalled",
+            target.name
+        ));
+    }
+    let info = bundle.join("Contents").join("Info");
+    let bundle_id = output(
+        Command::new("defaults")
+            .arg("read")
+            .arg(&info)
+            .arg("CFBundleIdentifier"),
+    )
+    .ok()
+    .map(|id| id.trim().to_string())
+    .filter(|id| !id.is_empty());
+    let leftovers = mac_leftovers(&target.name, bundle_id.as_deref());
+    let receipts = output(Command::new("pkgutil").arg("--file-info").arg(&bundle))
+        .map(|out| parse_pkgutil_ids(&out))
+        .unwrap_or_default();
+
+    let mut steps = vec![format!("Move {} to the Trash", bundle.display())];
+    steps.extend(
+        leftovers
+            .iter()
+            .map(|p| format!("Move {} to the Trash", p.display())),
+    );
+    steps.extend(
+        receipts
+            .iter()
+            .map(|id| format!("Forget installer receipt {}", id)),
+    );
+    let method = Method::Trash {
+        bundle,
+        leftovers,
+        receipts,
+    };
+    Ok((method, steps))
+}
+
+/// Single-quoted PowerShell string
+#[cfg(target_os = "windows")]
+fn ps_quote(s: &str) -> String {
+    format!("'{}'", s.replace('\'', "''"))
+}
+
+/// The ARP entry for an app name as `DisplayName|UninstallString`: an
+/// exact name match if there is one, else one containing the name
+#[cfg(target_os = "windows")]
+const WIN_ARP_SCRIPT: &str = r#"
+$keys = @(
+  'HKCU:\Software\Microsoft\Windows\CurrentVersion\Uninstall\*',
+  'HKLM:\Software\Microsoft\Windows\CurrentVersion\Uninstall\*',
+  'HKLM:\Software\WOW6432Node\Microsoft\Windows\CurrentVersion\Uninstall\*'
+)
+$entries = Get-ItemProperty $keys -ErrorAction SilentlyContinue | Where-Object { $_.DisplayName -and $_.UninstallString }
+$entry = $entries | Where-Object { $_.DisplayName -eq $name } | Select-Object -First 1
+if (-not $entry) { $entry = $entries | Where-Object { $_.DisplayName -like "*$name*" } | Select-Object -First 1 }
+if ($entry) { "$($entry.DisplayName)|$($entry.UninstallString)" }
+"#;
+
+#[cfg(target_os = "windows")]
+fn platform_plan(target: &UninstallTarget) -> Result<(Method, Vec<String>), String> {
+    if let Some(aumid) = target.path.strip_prefix("shell:AppsFolder\\") {
+        let family = aumid.split('!').next().unwrap_or(aumid);
+        let script = format!(
+            "Get-AppxPackage | Where-Object PackageFamilyName -eq {} | Remove-AppxPackage",
+            ps_quote(family)
+        );
+        let method = Method::Run {
+            program: "powershell".to_string(),
+            args: vec!["-NoProfile".into(), "-Command".into(), script],
+            wait: true,
+        };
+        return Ok((method, vec![format!("Remove the {} app package", family)]));
+    }
+
+    let script = format!("$name = {}\n{}", ps_quote(&target.name), WIN_ARP_SCRIPT);
+    let found = Command::new("powershell")
+        .args(["-NoProfile", "-Command", &script])
+        .output()
+        .map_err(|e| format!("Failed to run powershell: {}", e))?;
+    let found = String::from_utf8_lossy(&found.stdout).trim().to_string();
+    let (display_name, command_line) = found.split_once('|').ok_or_else(|| {
+        format!(
+            "{} isn't listed in Apps & Features, so it has no uninstaller to run",
+            target.name
+        )
+    })?;
+    let step = format!("Run the {} uninstaller: {}", display_name, command_line);
+    Ok((Method::CommandLine(command_line.to_string()), vec![step]))
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+fn platform_plan(_target: &UninstallTarget) -> Result<(Method, Vec<String>), String> {
+    Err("Uninstalling apps is not supported on this platform".to_string())
+}
+
+/// Work out how to uninstall an app, without changing anything
+pub fn plan(result_id: &str, target: &UninstallTarget) -> Result<UninstallPlan, String> {
+    let (method, steps) = platform_plan(target)?;
+    let message = match &method {
+        Method::CommandLine(_) => format!(
+            "Start the uninstaller for {}? It will guide you through removing the app.",
+            target.name
+        ),
+        Method::Trash { .. } => format!(
+            "Move {} and its support files to the Trash? You may be asked for an \
+             administrator password.",
+            target.name
+        ),
+        Method::Run { .. } => format!(
+            "Uninstall {}? You may be asked for an administrator password.",
+            target.name
+        ),
+    };
+    Ok(UninstallPlan {
+        result_id: result_id.to_string(),
+        name: target.name.clone(),
+        message,
+        steps,
+        method: Some(method),
+    })
+}
+
+#[cfg(target_os = "windows")]
+fn run_command_line(command_line: &str) -> Result<(), String> {
+    use std::os::windows::process::CommandExt;
+    // Passed through as written; the registry value carries its own quoting
+    Command::new("cmd")
+        .arg("/C")
+        .raw_arg(command_line)
+        .spawn()
+        .map(|_| ())
+        .map_err(|e| format!("Failed to start the uninstaller: {}", e))
+}
+
+#[cfg(not(target_os = "windows"))]
+fn run_command_line(_command_line: &str) -> Result<(), String> {
+    Err("Uninstaller command lines only exist on Windows".to_string())
+}
+
+/// Move a bundle to the Trash through Finder, which asks for a password
+/// when the bundle belongs to another user
+#[cfg(target_os = "macos")]
+fn finder_trash(bundle: &Path) -> Result<(), String> {
+    output(Command::new("osascript").args([
+        "-l",
+        "JavaScript",
+        "-e",
+        "function run(argv) { Application('Finder').delete(Path(argv[0])); }",
+        &bundle.to_string_lossy(),
+    ]))
+    .map(|_| ())
+}
+
+#[cfg(not(target_os = "macos"))]
+fn finder_trash(_bundle: &Path) -> Result<(), String> {
+    Err("Moving apps to the Trash is only supported on macOS".to_string())
+}
+
+/// Carry out a plan the user confirmed, returning a summary
+pub fn run(plan: &UninstallPlan) -> Result<String, String> {
+    match plan.method.as_ref().ok_or("Uninstall plan is incomplete")? {
+        Method::Run {
+            program,
+            args,
+            wait: true,
+        } => {
+            output(Command::new(program).args(args))?;
+            Ok(format!("{} was uninstalled", plan.name))
+        }
+        Method::Run { program, args, .. } => {
+            Command::new(program)
+                .args(args)
+                .spawn()
+                .map_err(|e| format!("Failed to start the uninstaller: {}", e))?;
+            Ok(format!("Started the uninstaller for {}", plan.name))
+        }
+        Method::CommandLine(command_line) => {
+            run_command_line(command_line)?;
+            Ok(format!("Started the uninstaller for {}", plan.name))
+        }
+        Method::Trash {
+            bundle,
+            leftovers,
+            receipts,
+        } => {
+            finder_trash(bundle)?;
+            // The app is gone at this point; leftovers are best effort
+            if !leftovers.is_empty() {
+                if let Err(e) = trash::delete_all(leftovers) {
+                    eprintln!("Failed to trash support files of {}: {}", plan.name, e);
+                }
+            }
+            if !receipts.is_empty() {
+                let forget = receipts
+                    .iter()
+                    .map(|id| format!("pkgutil --forget '{}'", id.replace('\'', "")))
+                    .collect::<Vec<_>>()
+                    .join("; ");
+                let script = format!(
+                    "do shell script \"{}\" with administrator privileges",
+                    forget
+                );
+                if let Err(e) = output(Command::new("osascript").args(["-e", &script])) {
+                    eprintln!("Failed to forget receipts of {}: {}", plan.name, e);
+                }
+            }
+            Ok(format!("{} was moved to the Trash", plan.name))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_linux_package_sources() {
+        assert_eq!(
+            linux_source("/var/lib/flatpak/exports/share/applications/org.gimp.GIMP.desktop"),
+            LinuxSource::Flatpak {
+                id: "org.gimp.GIMP".to_string(),
+                user: false
+            }
+        );
+        assert_eq!(
+            linux_source("/var/lib/snapd/desktop/applications/firefox_firefox.desktop"),
+            LinuxSource::Snap("firefox".to_string())
+        );
+        assert_eq!(
+            linux_source("/usr/share/applications/gimp.desktop"),
+            LinuxSource::System
+        );
+        assert_eq!(
+            parse_dpkg_owner("firefox-esr: /usr/share/applications/firefox-esr.desktop\n"),
+            Some("firefox-esr".to_string())
+        );
+        assert_eq!(
+            parse_dpkg_owner("libfoo:amd64, libfoo-dev: /usr/share/applications/foo.desktop"),
+            Some("libfoo:amd64".to_string())
+        );
+    }
+
+    #[test]
+    fn parses_pkgutil_receipts() {
+        let output = "volume: /\npath: /Applications/Foo.app\n\npkgid: com.foo.pkg\npkg-version: 1.0\n\npkgid: com.foo.helper\n";
+        assert_eq!(
+            parse_pkgutil_ids(output),
+            vec!["com.foo.pkg".to_string(), "com.foo.helper".to_string()]
+        );
+    }
+}
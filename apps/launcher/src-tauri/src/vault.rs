@@ -0,0 +1,53 @@
+use parking_lot::RwLock;
+use std::collections::HashMap;
+
+const SERVICE_NAME: &str = "launcher-vault";
+
+/// Small secret store backed by the OS keychain (Keychain, Credential
+/// Manager, Secret Service), or its encrypted file fallback. Values are
+/// cached in memory after first read.
+pub struct SecretVault {
+    cache: RwLock<HashMap<String, String>>,
+}
+
+impl SecretVault {
+    pub fn new() -> Self {
+        Self {
+            cache: RwLock::new(HashMap::new()),
+        }
+    }
+
+    fn keyring_key(key: &str) -> String {
+        format!("{}:{}", SERVICE_NAME, key)
+    }
+
+    pub fn get(&self, key: &str) -> Option<String> {
+        if let Some(value) = self.cache.read().get(key) {
+            return Some(value.clone());
+        }
+
+        let value = crate::secrets::get(SERVICE_NAME, &Self::keyring_key(key))?;
+
+        self.cache.write().insert(key.to_string(), value.clone());
+        Some(value)
+    }
+
+    pub fn set(&self, key: &str, value: &str) -> Result<(), String> {
+        crate::secrets::set(SERVICE_NAME, &Self::keyring_key(key), value)
+            .map_err(|e| format!("Failed to store secret: {}", e))?;
+
+        self.cache.write().insert(key.to_string(), value.to_string());
+        Ok(())
+    }
+
+    pub fn delete(&self, key: &str) -> Result<(), String> {
+        self.cache.write().remove(key);
+        crate::secrets::delete(SERVICE_NAME, &Self::keyring_key(key))
+    }
+}
+
+impl Default for SecretVault {
+    fn default() -> Self {
+        Self::new()
+    }
+}
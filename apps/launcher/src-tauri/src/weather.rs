@@ -0,0 +1,341 @@
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+const GEOCODING_URL: &str = "https://geocoding-api.open-meteo.com/v1/search";
+const FORECAST_URL: &str = "https://api.open-meteo.com/v1/forecast";
+const AIR_QUALITY_URL: &str = "https://air-quality-api.open-meteo.com/v1/air-quality";
+
+/// Conditions change slowly; don't refetch on every keystroke
+const WEATHER_TTL: Duration = Duration::from_secs(10 * 60);
+const GEOCODING_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(4);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Location {
+    pub name: String,
+    #[serde(default)]
+    pub admin1: Option<String>,
+    #[serde(default)]
+    pub country: Option<String>,
+    pub latitude: f64,
+    pub longitude: f64,
+}
+
+impl Location {
+    pub fn label(&self) -> String {
+        match &self.country {
+            Some(country) => format!("{}, {}", self.name, country),
+            None => self.name.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CurrentWeather {
+    pub temperature: f64,
+    pub apparent_temperature: f64,
+    pub humidity: f64,
+    pub wind_speed: f64,
+    pub weather_code: u32,
+    pub description: String,
+    pub icon: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DailyForecast {
+    pub date: String,
+    pub weather_code: u32,
+    pub description: String,
+    pub icon: String,
+    pub temperature_max: f64,
+    pub temperature_min: f64,
+    pub precipitation_probability: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WeatherReport {
+    pub location: Location,
+    pub current: CurrentWeather,
+    pub daily: Vec<DailyForecast>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AirQualityReport {
+    pub location: Location,
+    pub european_aqi: Option<f64>,
+    pub us_aqi: Option<f64>,
+    pub pm2_5: Option<f64>,
+    pub pm10: Option<f64>,
+    /// e.g. "Good" or "Unhealthy", from the US AQI
+    pub category: String,
+}
+
+#[derive(Deserialize)]
+struct GeocodingResponse {
+    #[serde(default)]
+    results: Vec<Location>,
+}
+
+#[derive(Deserialize)]
+struct ForecastResponse {
+    current: ForecastCurrent,
+    daily: ForecastDaily,
+}
+
+#[derive(Deserialize)]
+struct ForecastCurrent {
+    temperature_2m: f64,
+    apparent_temperature: f64,
+    relative_humidity_2m: f64,
+    wind_speed_10m: f64,
+    weather_code: u32,
+}
+
+#[derive(Deserialize)]
+struct ForecastDaily {
+    time: Vec<String>,
+    weather_code: Vec<u32>,
+    temperature_2m_max: Vec<f64>,
+    temperature_2m_min: Vec<f64>,
+    #[serde(default)]
+    precipitation_probability_max: Vec<Option<f64>>,
+}
+
+#[derive(Deserialize)]
+struct AirQualityResponse {
+    current: AirQualityCurrent,
+}
+
+#[derive(Deserialize)]
+struct AirQualityCurrent {
+    european_aqi: Option<f64>,
+    us_aqi: Option<f64>,
+    pm2_5: Option<f64>,
+    pm10: Option<f64>,
+}
+
+/// Description and emoji for a WMO weather interpretation code
+pub fn describe_weather_code(code: u32) -> (&'static str, &'static str) {
+    match code {
+        0 => ("Clear sky", "☀️"),
+        1 => ("Mainly clear", "🌤️"),
+        2 => ("Partly cloudy", "⛅"),
+        3 => ("Overcast", "☁️"),
+        45 | 48 => ("Fog", "🌫️"),
+        51 | 53 | 55 | 56 | 57 => ("Drizzle", "🌦️"),
+        61 | 63 | 65 | 66 | 67 => ("Rain", "🌧️"),
+        71 | 73 | 75 | 77 => ("Snow", "🌨️"),
+        80..=82 => ("Rain showers", "🌦️"),
+        85 | 86 => ("Snow showers", "🌨️"),
+        95..=99 => ("Thunderstorm", "⛈️"),
+        _ => ("Unknown", "🌡️"),
+    }
+}
+
+/// US EPA category for an AQI value
+pub fn aqi_category(us_aqi: f64) -> &'static str {
+    match us_aqi as u32 {
+        0..=50 => "Good",
+        51..=100 => "Moderate",
+        101..=150 => "Unhealthy for sensitive groups",
+        151..=200 => "Unhealthy",
+        201..=300 => "Very unhealthy",
+        _ => "Hazardous",
+    }
+}
+
+struct Cached<T> {
+    fetched_at: Instant,
+    value: T,
+}
+
+/// Open-Meteo client with per-location caching. No API key needed.
+pub struct WeatherClient {
+    client: reqwest::blocking::Client,
+    locations: RwLock<HashMap<String, Cached<Option<Location>>>>,
+    weather: RwLock<HashMap<String, Cached<WeatherReport>>>,
+    air_quality: RwLock<HashMap<String, Cached<AirQualityReport>>>,
+}
+
+fn cached<T: Clone>(
+    cache: &RwLock<HashMap<String, Cached<T>>>,
+    key: &str,
+    ttl: Duration,
+) -> Option<T> {
+    cache
+        .read()
+        .get(key)
+        .filter(|c| c.fetched_at.elapsed() < ttl)
+        .map(|c| c.value.clone())
+}
+
+fn store<T>(cache: &RwLock<HashMap<String, Cached<T>>>, key: String, value: T) {
+    cache.write().insert(
+        key,
+        Cached {
+            fetched_at: Instant::now(),
+            value,
+        },
+    );
+}
+
+impl WeatherClient {
+    pub fn new() -> Self {
+        Self {
+            client: reqwest::blocking::Client::builder()
+                .timeout(REQUEST_TIMEOUT)
+                .build()
+                .unwrap_or_default(),
+            locations: RwLock::new(HashMap::new()),
+            weather: RwLock::new(HashMap::new()),
+            air_quality: RwLock::new(HashMap::new()),
+        }
+    }
+
+    fn get<T: serde::de::DeserializeOwned>(
+        &self,
+        url: &str,
+        query: &[(&str, String)],
+    ) -> Result<T, String> {
+        self.client
+            .get(url)
+            .query(query)
+            .send()
+            .and_then(|r| r.error_for_status())
+            .and_then(|r| r.json())
+            .map_err(|e| format!("Open-Meteo request failed: {}", e))
+    }
+
+    /// Look up a place by name. Misses are cached too.
+    pub fn locate(&self, place: &str) -> Result<Option<Location>, String> {
+        let key = place.trim().to_lowercase();
+        if let Some(location) = cached(&self.locations, &key, GEOCODING_TTL) {
+            return Ok(location);
+        }
+
+        let response: GeocodingResponse = self.get(
+            GEOCODING_URL,
+            &[
+                ("name", key.clone()),
+                ("count", "1".to_string()),
+                ("language", "en".to_string()),
+                ("format", "json".to_string()),
+            ],
+        )?;
+        let location = response.results.into_iter().next();
+        store(&self.locations, key, location.clone());
+        Ok(location)
+    }
+
+    fn coordinates(location: &Location) -> [(&'static str, String); 2] {
+        [
+            ("latitude", location.latitude.to_string()),
+            ("longitude", location.longitude.to_string()),
+        ]
+    }
+
+    pub fn weather(&self, place: &str) -> Result<WeatherReport, String> {
+        let key = place.trim().to_lowercase();
+        if let Some(report) = cached(&self.weather, &key, WEATHER_TTL) {
+            return Ok(report);
+        }
+        let location = self
+            .locate(place)?
+            .ok_or_else(|| format!("Couldn't find \"{}\"", place.trim()))?;
+
+        let mut query = Self::coordinates(&location).to_vec();
+        query.extend([
+            (
+                "current",
+                "temperature_2m,apparent_temperature,relative_humidity_2m,wind_speed_10m,weather_code"
+                    .to_string(),
+            ),
+            (
+                "daily",
+                "weather_code,temperature_2m_max,temperature_2m_min,precipitation_probability_max"
+                    .to_string(),
+            ),
+            ("timezone", "auto".to_string()),
+            ("forecast_days", "7".to_string()),
+        ]);
+        let response: ForecastResponse = self.get(FORECAST_URL, &query)?;
+
+        let (description, icon) = describe_weather_code(response.current.weather_code);
+        let current = CurrentWeather {
+            temperature: response.current.temperature_2m,
+            apparent_temperature: response.current.apparent_temperature,
+            humidity: response.current.relative_humidity_2m,
+            wind_speed: response.current.wind_speed_10m,
+            weather_code: response.current.weather_code,
+            description: description.to_string(),
+            icon: icon.to_string(),
+        };
+
+        let daily = &response.daily;
+        let daily = (0..daily.time.len())
+            .filter_map(|i| {
+                let code = *daily.weather_code.get(i)?;
+                let (description, icon) = describe_weather_code(code);
+                Some(DailyForecast {
+                    date: daily.time[i].clone(),
+                    weather_code: code,
+                    description: description.to_string(),
+                    icon: icon.to_string(),
+                    temperature_max: *daily.temperature_2m_max.get(i)?,
+                    temperature_min: *daily.temperature_2m_min.get(i)?,
+                    precipitation_probability: daily
+                        .precipitation_probability_max
+                        .get(i)
+                        .copied()
+                        .flatten(),
+                })
+            })
+            .collect();
+
+        let report = WeatherReport {
+            location,
+            current,
+            daily,
+        };
+        store(&self.weather, key, report.clone());
+        Ok(report)
+    }
+
+    pub fn air_quality(&self, place: &str) -> Result<AirQualityReport, String> {
+        let key = place.trim().to_lowercase();
+        if let Some(report) = cached(&self.air_quality, &key, WEATHER_TTL) {
+            return Ok(report);
+        }
+        let location = self
+            .locate(place)?
+            .ok_or_else(|| format!("Couldn't find \"{}\"", place.trim()))?;
+
+        let mut query = Self::coordinates(&location).to_vec();
+        query.push(("current", "european_aqi,us_aqi,pm2_5,pm10".to_string()));
+        let response: AirQualityResponse = self.get(AIR_QUALITY_URL, &query)?;
+
+        let current = response.current;
+        let report = AirQualityReport {
+            location,
+            category: current
+                .us_aqi
+                .map(aqi_category)
+                .unwrap_or("Unknown")
+                .to_string(),
+            european_aqi: current.european_aqi,
+            us_aqi: current.us_aqi,
+            pm2_5: current.pm2_5,
+            pm10: current.pm10,
+        };
+        store(&self.air_quality, key, report.clone());
+        Ok(report)
+    }
+}
+
+impl Default for WeatherClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,758 @@
+//! Moving and resizing the focused window: snap to the left or right half,
+//! maximize, or move to the next display. Run from search or from per-command
+//! global hotkeys set in `window_hotkeys`. Also lists open windows and
+//! focuses one for the window switcher.
+//!
+//! The window's frame and the displays' work areas are read through each
+//! platform's scripting tools, the new frame is computed here, and then set
+//! the same way.
+
+use serde::{Deserialize, Serialize};
+use std::process::Command;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum WindowAction {
+    LeftHalf,
+    RightHalf,
+    Maximize,
+    NextMonitor,
+}
+
+impl WindowAction {
+    pub const ALL: [WindowAction; 4] = [
+        WindowAction::LeftHalf,
+        WindowAction::RightHalf,
+        WindowAction::Maximize,
+        WindowAction::NextMonitor,
+    ];
+
+    /// Id used in result ids and the `window_hotkeys` setting
+    pub fn id(&self) -> &'static str {
+        match self {
+            WindowAction::LeftHalf => "left-half",
+            WindowAction::RightHalf => "right-half",
+            WindowAction::Maximize => "maximize",
+            WindowAction::NextMonitor => "next-monitor",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|a| a.id() == id)
+    }
+
+    pub fn title(&self) -> &'static str {
+        match self {
+            WindowAction::LeftHalf => "Left Half",
+            WindowAction::RightHalf => "Right Half",
+            WindowAction::Maximize => "Maximize Window",
+            WindowAction::NextMonitor => "Move to Next Display",
+        }
+    }
+
+    pub fn keywords(&self) -> &'static [&'static str] {
+        match self {
+            WindowAction::LeftHalf => &["snap left", "window left", "tile left"],
+            WindowAction::RightHalf => &["snap right", "window right", "tile right"],
+            WindowAction::Maximize => &["fill", "fullscreen", "zoom", "window max"],
+            WindowAction::NextMonitor => &["next monitor", "next screen", "other display"],
+        }
+    }
+}
+
+/// A window frame or a display's work area, in screen coordinates with the
+/// origin at the top left of the primary display
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Rect {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+impl Rect {
+    fn contains(&self, x: f64, y: f64) -> bool {
+        x >= self.x && x < self.x + self.width && y >= self.y && y < self.y + self.height
+    }
+
+    fn overlap(&self, other: &Rect) -> f64 {
+        let width = (self.x + self.width).min(other.x + other.width) - self.x.max(other.x);
+        let height = (self.y + self.height).min(other.y + other.height) - self.y.max(other.y);
+        width.max(0.0) * height.max(0.0)
+    }
+}
+
+/// Index of the display a window is on: the one holding its center, or
+/// else the one it overlaps most
+fn screen_index(window: &Rect, screens: &[Rect]) -> usize {
+    let (cx, cy) = (
+        window.x + window.width / 2.0,
+        window.y + window.height / 2.0,
+    );
+    screens
+        .iter()
+        .position(|s| s.contains(cx, cy))
+        .or_else(|| {
+            (0..screens.len()).max_by(|a, b| {
+                window
+                    .overlap(&screens[*a])
+                    .total_cmp(&window.overlap(&screens[*b]))
+            })
+        })
+        .unwrap_or(0)
+}
+
+/// Where `action` puts a window currently at `window`
+pub fn target_frame(action: WindowAction, window: Rect, screens: &[Rect]) -> Result<Rect, String> {
+    if screens.is_empty() {
+        return Err("No displays found".to_string());
+    }
+    let index = screen_index(&window, screens);
+    let screen = screens[index];
+    let half = (screen.width / 2.0).floor();
+
+    Ok(match action {
+        WindowAction::LeftHalf => Rect {
+            width: half,
+            ..screen
+        },
+        WindowAction::RightHalf => Rect {
+            x: screen.x + half,
+            width: screen.width - half,
+            ..screen
+        },
+        WindowAction::Maximize => screen,
+        WindowAction::NextMonitor => {
+            if screens.len() < 2 {
+                return Err("There's only one display".to_string());
+            }
+            // Same relative position and size on the next display
+            let next = screens[(index + 1) % screens.len()];
+            let scale_x = next.width / screen.width;
+            let scale_y = next.height / screen.height;
+            Rect {
+                x: next.x + (window.x - screen.x) * scale_x,
+                y: next.y + (window.y - screen.y) * scale_y,
+                width: (window.width * scale_x).min(next.width),
+                height: (window.height * scale_y).min(next.height),
+            }
+        }
+    })
+}
+
+/// Stdout of a command that ran successfully
+fn output(command: &mut Command) -> Result<String, String> {
+    let output = command
+        .output()
+        .map_err(|e| format!("Failed to run {:?}: {}", command.get_program(), e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "{:?} failed: {}",
+            command.get_program(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// The focused window and every display's work area
+#[derive(Debug, Deserialize)]
+struct Layout {
+    window: Rect,
+    screens: Vec<Rect>,
+}
+
+/// `xrandr --listactivemonitors` lines such as
+/// ` 0: +*DP-1 2560/597x1440/336+0+0  DP-1`
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_xrandr_monitors(output: &str) -> Vec<Rect> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let geometry = line.split_whitespace().nth(2)?;
+            let (width, rest) = geometry.split_once('x')?;
+            let mut parts = rest.split('+');
+            let height = parts.next()?;
+            let number = |s: &str| s.split('/').next()?.parse::<f64>().ok();
+            Some(Rect {
+                x: number(parts.next()?)?,
+                y: number(parts.next()?)?,
+                width: number(width)?,
+                height: number(height)?,
+            })
+        })
+        .collect()
+}
+
+/// Process to act on; the frontmost one when no name is given
+#[cfg(target_os = "macos")]
+const MAC_PROCESS: &str = r#"
+function targetWindow(name) {
+  const se = Application('System Events');
+  const proc = name ? se.processes.byName(name) : se.processes.whose({ frontmost: true })[0];
+  return proc.windows[0];
+}
+"#;
+
+/// NSScreen frames start at the bottom left of the primary display; flip
+/// them to match window positions
+#[cfg(target_os = "macos")]
+const MAC_LAYOUT_SCRIPT: &str = r#"
+ObjC.import('AppKit');
+function run(argv) {
+  const win = targetWindow(argv[0]);
+  const [x, y] = win.position();
+  const [width, height] = win.size();
+  const screens = $.NSScreen.screens.js;
+  const primaryHeight = screens[0].frame.size.height;
+  const areas = screens.map(s => {
+    const f = s.visibleFrame;
+    return {
+      x: f.origin.x,
+      y: primaryHeight - f.origin.y - f.size.height,
+      width: f.size.width,
+      height: f.size.height,
+    };
+  });
+  return JSON.stringify({ window: { x, y, width, height }, screens: areas });
+}
+"#;
+
+#[cfg(target_os = "macos")]
+const MAC_SET_FRAME_SCRIPT: &str = r#"
+function run(argv) {
+  const win = targetWindow(argv[0]);
+  win.position = [Number(argv[1]), Number(argv[2])];
+  win.size = [Number(argv[3]), Number(argv[4])];
+}
+"#;
+
+#[cfg(target_os = "macos")]
+fn layout(app: Option<&str>) -> Result<Layout, String> {
+    let script = format!("{}{}", MAC_PROCESS, MAC_LAYOUT_SCRIPT);
+    let json = output(Command::new("osascript").args([
+        "-l",
+        "JavaScript",
+        "-e",
+        &script,
+        app.unwrap_or(""),
+    ]))?;
+    serde_json::from_str(&json).map_err(|e| format!("Couldn't read the window layout: {}", e))
+}
+
+#[cfg(target_os = "macos")]
+fn set_frame(app: Option<&str>, frame: Rect) -> Result<(), String> {
+    let script = format!("{}{}", MAC_PROCESS, MAC_SET_FRAME_SCRIPT);
+    let values = [frame.x, frame.y, frame.width, frame.height].map(|v| v.round().to_string());
+    output(
+        Command::new("osascript")
+            .args(["-l", "JavaScript", "-e", &script, app.unwrap_or("")])
+            .args(values),
+    )
+    .map(|_| ())
+}
+
+#[cfg(target_os = "linux")]
+fn layout(_app: Option<&str>) -> Result<Layout, String> {
+    // X11 only, like the frontmost app detection
+    let geometry =
+        output(Command::new("xdotool").args(["getactivewindow", "getwindowgeometry", "--shell"]))?;
+    let field = |key: &str| -> Result<f64, String> {
+        geometry
+            .lines()
+            .find_map(|l| l.strip_prefix(key)?.strip_prefix('=')?.parse().ok())
+            .ok_or_else(|| format!("xdotool didn't report the window's {}", key))
+    };
+    let window = Rect {
+        x: field("X")?,
+        y: field("Y")?,
+        width: field("WIDTH")?,
+        height: field("HEIGHT")?,
+    };
+    let screens =
+        parse_xrandr_monitors(&output(Command::new("xrandr").arg("--listactivemonitors"))?);
+    Ok(Layout { window, screens })
+}
+
+#[cfg(target_os = "linux")]
+fn set_frame(_app: Option<&str>, frame: Rect) -> Result<(), String> {
+    let [x, y, width, height] =
+        [frame.x, frame.y, frame.width, frame.height].map(|v| v.round().to_string());
+    output(Command::new("xdotool").args([
+        "getactivewindow",
+        "windowsize",
+        &width,
+        &height,
+        "windowmove",
+        &x,
+        &y,
+    ]))
+    .map(|_| ())
+}
+
+#[cfg(target_os = "windows")]
+const WIN32_TYPES: &str = r#"
+Add-Type -AssemblyName System.Windows.Forms
+Add-Type @'
+using System;
+using System.Runtime.InteropServices;
+public struct WinRect { public int Left, Top, Right, Bottom; }
+public static class WinApi {
+  [DllImport("user32.dll")] public static extern IntPtr GetForegroundWindow();
+  [DllImport("user32.dll")] public static extern bool GetWindowRect(IntPtr hWnd, out WinRect rect);
+  [DllImport("user32.dll")] public static extern bool ShowWindow(IntPtr hWnd, int cmd);
+  [DllImport("user32.dll")] public static extern bool MoveWindow(IntPtr hWnd, int x, int y, int w, int h, bool repaint);
+}
+'@
+$hwnd = [WinApi]::GetForegroundWindow()
+"#;
+
+#[cfg(target_os = "windows")]
+const WIN_LAYOUT_SCRIPT: &str = r#"
+$rect = New-Object WinRect
+[void][WinApi]::GetWindowRect($hwnd, [ref]$rect)
+$window = @{ x = $rect.Left; y = $rect.Top; width = $rect.Right - $rect.Left; height = $rect.Bottom - $rect.Top }
+$screens = @([System.Windows.Forms.Screen]::AllScreens | Sort-Object { -not $_.Primary } | ForEach-Object {
+  $a = $_.WorkingArea
+  @{ x = $a.X; y = $a.Y; width = $a.Width; height = $a.Height }
+})
+@{ window = $window; screens = $screens } | ConvertTo-Json -Compress -Depth 3
+"#;
+
+#[cfg(target_os = "windows")]
+fn layout(_app: Option<&str>) -> Result<Layout, String> {
+    let script = format!("{}{}", WIN32_TYPES, WIN_LAYOUT_SCRIPT);
+    let json = output(Command::new("powershell").args(["-NoProfile", "-Command", &script]))?;
+    serde_json::from_str(&json).map_err(|e| format!("Couldn't read the window layout: {}", e))
+}
+
+#[cfg(target_os = "windows")]
+fn set_frame(_app: Option<&str>, frame: Rect) -> Result<(), String> {
+    // Restore first; a maximized window ignores MoveWindow
+    let script = format!(
+        "{}[void][WinApi]::ShowWindow($hwnd, 9)\n[void][WinApi]::MoveWindow($hwnd, {}, {}, {}, {}, $true)",
+        WIN32_TYPES,
+        frame.x.round(),
+        frame.y.round(),
+        frame.width.round(),
+        frame.height.round()
+    );
+    output(Command::new("powershell").args(["-NoProfile", "-Command", &script])).map(|_| ())
+}
+
+/// Apply `action` to the focused window. `app` names the app whose window
+/// to move on macOS, where the launcher may still be frontmost; other
+/// platforms act on the foreground window.
+pub fn apply(action: WindowAction, app: Option<&str>) -> Result<(), String> {
+    if !crate::ax::is_trusted() {
+        return Err("Allow Accessibility access to move windows".to_string());
+    }
+    let layout = layout(app)?;
+    let frame = target_frame(action, layout.window, &layout.screens)?;
+    set_frame(app, frame)
+}
+
+/// An application window that can be switched to
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OpenWindow {
+    /// X11 window id, sway container id, Hyprland address, `CGWindowNumber`
+    /// or `HWND`, whichever the platform focuses windows by
+    pub id: String,
+    pub title: String,
+    /// Owning application's name, class or app id
+    pub app: String,
+    pub pid: Option<u32>,
+}
+
+/// `wmctrl -lpx` lines: id, desktop, pid, `instance.Class`, host and title,
+/// e.g. `0x03e00003  0 4242   Navigator.firefox  host Mozilla Firefox`.
+/// Windows on every desktop (-1) are panels and docks.
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_wmctrl(output: &str) -> Vec<OpenWindow> {
+    fn field(s: &str) -> Option<(&str, &str)> {
+        let s = s.trim_start();
+        let end = s.find(char::is_whitespace).unwrap_or(s.len());
+        (end > 0).then_some((&s[..end], &s[end..]))
+    }
+
+    output
+        .lines()
+        .filter_map(|line| {
+            let (id, rest) = field(line)?;
+            let (desktop, rest) = field(rest)?;
+            let (pid, rest) = field(rest)?;
+            let (class, rest) = field(rest)?;
+            let (_host, title) = field(rest)?;
+            if desktop == "-1" {
+                return None;
+            }
+            Some(OpenWindow {
+                id: id.to_string(),
+                title: title.trim().to_string(),
+                app: class.rsplit('.').next().unwrap_or(class).to_string(),
+                pid: pid.parse().ok().filter(|&pid| pid > 0),
+            })
+        })
+        .collect()
+}
+
+/// Windows in `swaymsg -t get_tree -r` output: leaf containers, tiled or
+/// floating, that have a process
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_sway_tree(json: &str) -> Vec<OpenWindow> {
+    fn walk(node: &serde_json::Value, windows: &mut Vec<OpenWindow>) {
+        if let (Some(id), Some(pid)) = (node["id"].as_u64(), node["pid"].as_u64()) {
+            // Wayland clients have an app id, Xwayland ones a window class
+            let app = node["app_id"]
+                .as_str()
+                .or(node["window_properties"]["class"].as_str())
+                .unwrap_or_default();
+            windows.push(OpenWindow {
+                id: id.to_string(),
+                title: node["name"].as_str().unwrap_or_default().to_string(),
+                app: app.to_string(),
+                pid: u32::try_from(pid).ok(),
+            });
+        }
+        for key in ["nodes", "floating_nodes"] {
+            for child in node[key].as_array().into_iter().flatten() {
+                walk(child, windows);
+            }
+        }
+    }
+
+    let Ok(tree) = serde_json::from_str::<serde_json::Value>(json) else {
+        return Vec::new();
+    };
+    let mut windows = Vec::new();
+    walk(&tree, &mut windows);
+    windows
+}
+
+/// `hyprctl clients -j` output, leaving out unmapped and hidden clients
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_hyprland_clients(json: &str) -> Vec<OpenWindow> {
+    let Ok(clients) = serde_json::from_str::<Vec<serde_json::Value>>(json) else {
+        return Vec::new();
+    };
+    clients
+        .iter()
+        .filter(|c| c["mapped"].as_bool() != Some(false) && c["hidden"].as_bool() != Some(true))
+        .filter_map(|c| {
+            Some(OpenWindow {
+                id: c["address"].as_str()?.to_string(),
+                title: c["title"].as_str().unwrap_or_default().to_string(),
+                app: c["class"].as_str().unwrap_or_default().to_string(),
+                pid: c["pid"].as_u64().and_then(|pid| u32::try_from(pid).ok()),
+            })
+        })
+        .collect()
+}
+
+/// The Wayland compositor, when it's one the launcher can ask for windows.
+/// Others only let Wayland clients list windows through the wlr
+/// foreign-toplevel protocol, so there the list falls back to wmctrl and
+/// only shows Xwayland windows.
+#[cfg(target_os = "linux")]
+#[derive(Debug, Clone, Copy)]
+enum Compositor {
+    Sway,
+    Hyprland,
+}
+
+#[cfg(target_os = "linux")]
+fn compositor() -> Option<Compositor> {
+    if std::env::var_os("HYPRLAND_INSTANCE_SIGNATURE").is_some() {
+        Some(Compositor::Hyprland)
+    } else if std::env::var_os("SWAYSOCK").is_some() {
+        Some(Compositor::Sway)
+    } else {
+        None
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn list_windows() -> Result<Vec<OpenWindow>, String> {
+    Ok(match compositor() {
+        Some(Compositor::Hyprland) => {
+            parse_hyprland_clients(&output(Command::new("hyprctl").args(["clients", "-j"]))?)
+        }
+        Some(Compositor::Sway) => parse_sway_tree(&output(
+            Command::new("swaymsg").args(["-t", "get_tree", "-r"]),
+        )?),
+        None => parse_wmctrl(&output(Command::new("wmctrl").arg("-lpx"))?),
+    })
+}
+
+#[cfg(target_os = "linux")]
+fn raise_window(window: &OpenWindow) -> Result<(), String> {
+    let mut command = match compositor() {
+        Some(Compositor::Hyprland) => {
+            let mut command = Command::new("hyprctl");
+            command.args(["dispatch", "focuswindow", &format!("address:{}", window.id)]);
+            command
+        }
+        Some(Compositor::Sway) => {
+            let mut command = Command::new("swaymsg");
+            command.arg(format!("[con_id={}] focus", window.id));
+            command
+        }
+        None => {
+            let mut command = Command::new("wmctrl");
+            command.args(["-ia", &window.id]);
+            command
+        }
+    };
+    output(&mut command).map(|_| ())
+}
+
+/// Windows in the current space, front to back. Titles need Screen
+/// Recording access; without it they come back empty.
+#[cfg(target_os = "macos")]
+const MAC_LIST_WINDOWS_SCRIPT: &str = r#"
+ObjC.import('CoreGraphics');
+function run() {
+  const options = $.kCGWindowListOptionOnScreenOnly | $.kCGWindowListExcludeDesktopElements;
+  const info = $.CGWindowListCopyWindowInfo(options, $.kCGNullWindowID);
+  const windows = ObjC.deepUnwrap(ObjC.castRefToObject(info)) || [];
+  return JSON.stringify(windows
+    .filter(w => w.kCGWindowLayer === 0)
+    .map(w => ({
+      id: String(w.kCGWindowNumber),
+      title: w.kCGWindowName || '',
+      app: w.kCGWindowOwnerName || '',
+      pid: w.kCGWindowOwnerPID,
+    })));
+}
+"#;
+
+/// CoreGraphics can't focus a window; bring its app forward and raise the
+/// window with the same title through Accessibility
+#[cfg(target_os = "macos")]
+const MAC_RAISE_WINDOW_SCRIPT: &str = r#"
+function run(argv) {
+  const proc = Application('System Events').processes.whose({ unixId: Number(argv[0]) })[0];
+  proc.frontmost = true;
+  const win = proc.windows().find(w => w.name() === argv[1]);
+  if (win) win.actions.byName('AXRaise').perform();
+}
+"#;
+
+#[cfg(target_os = "macos")]
+fn list_windows() -> Result<Vec<OpenWindow>, String> {
+    let json = output(Command::new("osascript").args([
+        "-l",
+        "JavaScript",
+        "-e",
+        MAC_LIST_WINDOWS_SCRIPT,
+    ]))?;
+    serde_json::from_str(&json).map_err(|e| format!("Couldn't read the window list: {}", e))
+}
+
+#[cfg(target_os = "macos")]
+fn raise_window(window: &OpenWindow) -> Result<(), String> {
+    let pid = window.pid.ok_or("The window's app isn't known")?;
+    output(Command::new("osascript").args([
+        "-l",
+        "JavaScript",
+        "-e",
+        MAC_RAISE_WINDOW_SCRIPT,
+        &pid.to_string(),
+        &window.title,
+    ]))
+    .map(|_| ())
+}
+
+/// Visible, titled top-level windows, leaving out owned popups and the
+/// cloaked windows of suspended Store apps
+#[cfg(target_os = "windows")]
+const WIN_LIST_WINDOWS_SCRIPT: &str = r#"
+Add-Type @'
+using System;
+using System.Text;
+using System.Collections.Generic;
+using System.Runtime.InteropServices;
+public class WinInfo { public long Id; public string Title; public uint Pid; }
+public static class WinList {
+  delegate bool EnumProc(IntPtr hWnd, IntPtr lParam);
+  [DllImport("user32.dll")] static extern bool EnumWindows(EnumProc proc, IntPtr lParam);
+  [DllImport("user32.dll")] static extern bool IsWindowVisible(IntPtr hWnd);
+  [DllImport("user32.dll")] static extern IntPtr GetWindow(IntPtr hWnd, uint cmd);
+  [DllImport("user32.dll")] static extern int GetWindowTextLength(IntPtr hWnd);
+  [DllImport("user32.dll", CharSet = CharSet.Unicode)] static extern int GetWindowText(IntPtr hWnd, StringBuilder text, int count);
+  [DllImport("user32.dll")] static extern uint GetWindowThreadProcessId(IntPtr hWnd, out uint pid);
+  [DllImport("dwmapi.dll")] static extern int DwmGetWindowAttribute(IntPtr hWnd, int attr, out int value, int size);
+  public static List<WinInfo> All() {
+    var windows = new List<WinInfo>();
+    EnumWindows((hWnd, _) => {
+      int length = GetWindowTextLength(hWnd);
+      int cloaked;
+      if (!IsWindowVisible(hWnd) || length == 0 || GetWindow(hWnd, 4) != IntPtr.Zero) return true;
+      if (DwmGetWindowAttribute(hWnd, 14, out cloaked, 4) == 0 && cloaked != 0) return true;
+      var title = new StringBuilder(length + 1);
+      GetWindowText(hWnd, title, title.Capacity);
+      uint pid;
+      GetWindowThreadProcessId(hWnd, out pid);
+      windows.Add(new WinInfo { Id = hWnd.ToInt64(), Title = title.ToString(), Pid = pid });
+      return true;
+    }, IntPtr.Zero);
+    return windows;
+  }
+}
+'@
+$windows = [WinList]::All() | ForEach-Object {
+  $proc = Get-Process -Id $_.Pid -ErrorAction SilentlyContinue
+  [pscustomobject]@{ id = [string]$_.Id; title = $_.Title; app = [string]$proc.ProcessName; pid = $_.Pid }
+}
+ConvertTo-Json -Compress -InputObject @($windows)
+"#;
+
+#[cfg(target_os = "windows")]
+const WIN_FOCUS_TYPES: &str = r#"
+Add-Type @'
+using System;
+using System.Runtime.InteropServices;
+public static class WinFocus {
+  [DllImport("user32.dll")] public static extern bool IsIconic(IntPtr hWnd);
+  [DllImport("user32.dll")] public static extern bool ShowWindow(IntPtr hWnd, int cmd);
+  [DllImport("user32.dll")] public static extern bool SetForegroundWindow(IntPtr hWnd);
+}
+'@
+"#;
+
+/// Restore minimized windows, which stay minimized when brought forward
+#[cfg(target_os = "windows")]
+const WIN_RAISE_WINDOW_SCRIPT: &str = r#"
+if ([WinFocus]::IsIconic($hwnd)) { [void][WinFocus]::ShowWindow($hwnd, 9) }
+[void][WinFocus]::SetForegroundWindow($hwnd)
+"#;
+
+#[cfg(target_os = "windows")]
+fn list_windows() -> Result<Vec<OpenWindow>, String> {
+    let json = output(Command::new("powershell").args([
+        "-NoProfile",
+        "-Command",
+        WIN_LIST_WINDOWS_SCRIPT,
+    ]))?;
+    serde_json::from_str(&json).map_err(|e| format!("Couldn't read the window list: {}", e))
+}
+
+#[cfg(target_os = "windows")]
+fn raise_window(window: &OpenWindow) -> Result<(), String> {
+    let hwnd: i64 = window.id.parse().map_err(|_| "Invalid window id")?;
+    let script = format!(
+        "{}$hwnd = [IntPtr]{}{}",
+        WIN_FOCUS_TYPES, hwnd, WIN_RAISE_WINDOW_SCRIPT
+    );
+    output(Command::new("powershell").args(["-NoProfile", "-Command", &script])).map(|_| ())
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+fn list_windows() -> Result<Vec<OpenWindow>, String> {
+    Err("Windows can't be listed on this platform".to_string())
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+fn raise_window(_window: &OpenWindow) -> Result<(), String> {
+    Err("Windows can't be focused on this platform".to_string())
+}
+
+/// Other apps' windows, leaving out the launcher's own
+pub fn open_windows() -> Result<Vec<OpenWindow>, String> {
+    let own = std::process::id();
+    let mut windows = list_windows()?;
+    windows.retain(|w| w.pid != Some(own));
+    Ok(windows)
+}
+
+/// Bring `window` to the front and focus it
+pub fn focus(window: &OpenWindow) -> Result<(), String> {
+    if !crate::ax::is_trusted() {
+        return Err("Allow Accessibility access to switch windows".to_string());
+    }
+    raise_window(window)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rect(x: f64, y: f64, width: f64, height: f64) -> Rect {
+        Rect {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    #[test]
+    fn computes_target_frames() {
+        let screens = [
+            rect(0.0, 25.0, 1440.0, 875.0),
+            rect(1440.0, 0.0, 1920.0, 1080.0),
+        ];
+        let window = rect(100.0, 100.0, 800.0, 600.0);
+
+        let left = target_frame(WindowAction::LeftHalf, window, &screens).unwrap();
+        assert_eq!(left, rect(0.0, 25.0, 720.0, 875.0));
+        let right = target_frame(WindowAction::RightHalf, window, &screens).unwrap();
+        assert_eq!(right, rect(720.0, 25.0, 720.0, 875.0));
+        let max = target_frame(WindowAction::Maximize, window, &screens).unwrap();
+        assert_eq!(max, screens[0]);
+
+        // Moves to the second display, then wraps around to the first
+        let moved = target_frame(WindowAction::NextMonitor, window, &screens).unwrap();
+        assert!(screens[1].contains(moved.x, moved.y));
+        let back = target_frame(WindowAction::NextMonitor, moved, &screens).unwrap();
+        assert!(screens[0].contains(back.x, back.y));
+
+        assert!(target_frame(WindowAction::NextMonitor, window, &screens[..1]).is_err());
+    }
+
+    #[test]
+    fn parses_xrandr_monitors() {
+        let output = "Monitors: 2\n 0: +*DP-1 2560/597x1440/336+0+0  DP-1\n 1: +HDMI-1 1920/527x1080/296+2560+0  HDMI-1\n";
+        assert_eq!(
+            parse_xrandr_monitors(output),
+            vec![
+                rect(0.0, 0.0, 2560.0, 1440.0),
+                rect(2560.0, 0.0, 1920.0, 1080.0)
+            ]
+        );
+    }
+
+    #[test]
+    fn parses_window_lists() {
+        let wmctrl = "0x03e00003  0 4242   Navigator.firefox     host Mozilla  Firefox\n\
+                      0x01a00004 -1 1001   xfce4-panel.Xfce4-panel  host xfce4-panel\n";
+        assert_eq!(
+            parse_wmctrl(wmctrl),
+            vec![OpenWindow {
+                id: "0x03e00003".to_string(),
+                title: "Mozilla  Firefox".to_string(),
+                app: "firefox".to_string(),
+                pid: Some(4242),
+            }]
+        );
+
+        let sway = r#"{"id": 1, "nodes": [{"id": 4, "nodes": [
+            {"id": 7, "pid": 300, "app_id": "foot", "name": "~"}],
+            "floating_nodes": [{"id": 9, "pid": 301, "app_id": null, "name": "Steam",
+                                "window_properties": {"class": "steam"}}]}]}"#;
+        let windows = parse_sway_tree(sway);
+        assert_eq!(windows.len(), 2);
+        assert_eq!(
+            (windows[0].id.as_str(), windows[0].app.as_str()),
+            ("7", "foot")
+        );
+        assert_eq!(windows[1].app, "steam");
+
+        let hyprland = r#"[{"address": "0x5612", "title": "nvim", "class": "kitty", "pid": 77,
+            "mapped": true, "hidden": false}, {"address": "0x5613", "title": "",
+            "class": "", "pid": 78, "mapped": false, "hidden": false}]"#;
+        let windows = parse_hyprland_clients(hyprland);
+        assert_eq!(windows.len(), 1);
+        assert_eq!(windows[0].id, "0x5612");
+    }
+}
@@ -0,0 +1,321 @@
+//! Virtual desktops: Windows virtual desktops, macOS Spaces, and X11 or Sway
+//! workspaces. Listing and switching work everywhere; moving the focused
+//! window to another workspace needs a window manager that allows it, which
+//! rules out Windows and macOS.
+//!
+//! Windows and macOS have no public API for switching, so the launcher steps
+//! through desktops with the system's own shortcuts (Ctrl+Win+Arrow and
+//! Ctrl+Arrow).
+
+use serde::{Deserialize, Serialize};
+use std::process::Command;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Workspace {
+    /// Position in the list, from 0
+    pub index: usize,
+    pub name: String,
+    pub current: bool,
+}
+
+/// Whether `move_window_to` can work on this platform
+pub fn supports_move() -> bool {
+    cfg!(target_os = "linux")
+}
+
+/// Stdout of a command that ran successfully
+fn output(command: &mut Command) -> Result<String, String> {
+    let output = command
+        .output()
+        .map_err(|e| format!("Failed to run {:?}: {}", command.get_program(), e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "{:?} failed: {}",
+            command.get_program(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Arrow presses from the current workspace to `target`: negative is left
+#[cfg_attr(target_os = "linux", allow(dead_code))]
+fn steps_to(workspaces: &[Workspace], target: &Workspace) -> i64 {
+    let current = workspaces
+        .iter()
+        .position(|w| w.current)
+        .unwrap_or(target.index);
+    target.index as i64 - current as i64
+}
+
+/// `wmctrl -d` lines such as
+/// `0  * DG: 3840x1080  VP: 0,0  WA: 0,25 3840x1055  Web`
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_wmctrl_desktops(output: &str) -> Vec<Workspace> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let index = fields.first()?.parse().ok()?;
+            let current = *fields.get(1)? == "*";
+            // The work area is "N/A" or a position and a size
+            let wa = fields.iter().position(|f| *f == "WA:")?;
+            let name_at = if fields.get(wa + 1) == Some(&"N/A") {
+                wa + 2
+            } else {
+                wa + 3
+            };
+            let name = fields.get(name_at..).unwrap_or_default().join(" ");
+            Some(Workspace {
+                index,
+                name: if name.is_empty() {
+                    format!("Workspace {}", index + 1)
+                } else {
+                    name
+                },
+                current,
+            })
+        })
+        .collect()
+}
+
+#[cfg(target_os = "linux")]
+fn is_sway() -> bool {
+    std::env::var_os("SWAYSOCK").is_some()
+}
+
+#[cfg(target_os = "linux")]
+#[derive(Deserialize)]
+struct SwayWorkspace {
+    name: String,
+    focused: bool,
+}
+
+#[cfg(target_os = "linux")]
+fn swaymsg(command: String) -> Result<(), String> {
+    output(Command::new("swaymsg").arg(command)).map(|_| ())
+}
+
+#[cfg(target_os = "linux")]
+pub fn list() -> Result<Vec<Workspace>, String> {
+    if is_sway() {
+        let json = output(Command::new("swaymsg").args(["-t", "get_workspaces"]))?;
+        let workspaces: Vec<SwayWorkspace> =
+            serde_json::from_str(&json).map_err(|e| format!("Couldn't read workspaces: {}", e))?;
+        return Ok(workspaces
+            .into_iter()
+            .enumerate()
+            .map(|(index, w)| Workspace {
+                index,
+                name: w.name,
+                current: w.focused,
+            })
+            .collect());
+    }
+    // Any EWMH window manager on X11
+    Ok(parse_wmctrl_desktops(&output(
+        Command::new("wmctrl").arg("-d"),
+    )?))
+}
+
+#[cfg(target_os = "linux")]
+fn switch(_workspaces: &[Workspace], target: &Workspace) -> Result<(), String> {
+    if is_sway() {
+        return swaymsg(format!("workspace \"{}\"", target.name));
+    }
+    output(Command::new("wmctrl").args(["-s", &target.index.to_string()])).map(|_| ())
+}
+
+#[cfg(target_os = "linux")]
+fn move_window(target: &Workspace) -> Result<(), String> {
+    if is_sway() {
+        return swaymsg(format!("move container to workspace \"{}\"", target.name));
+    }
+    output(Command::new("wmctrl").args(["-r", ":ACTIVE:", "-t", &target.index.to_string()]))
+        .map(|_| ())
+}
+
+/// Spaces of the main display from the Dock's preferences. Full-screen apps
+/// are spaces too, and Ctrl+Arrow steps through them, so they're kept.
+#[cfg(target_os = "macos")]
+const MAC_LIST_SCRIPT: &str = r#"
+ObjC.import('Foundation');
+function run() {
+  const defaults = $.NSUserDefaults.alloc.initWithSuiteName('com.apple.spaces');
+  const config = ObjC.deepUnwrap(defaults.objectForKey('SpacesDisplayConfiguration'));
+  const monitor = config['Management Data'].Monitors.find(m => m.Spaces && m.Spaces.length);
+  const current = monitor['Current Space'].ManagedSpaceID;
+  let desktop = 0;
+  return JSON.stringify(monitor.Spaces.map((space, index) => ({
+    index,
+    name: space.type === 0 ? 'Desktop ' + (++desktop) : 'Full-Screen App',
+    current: space.ManagedSpaceID === current,
+  })));
+}
+"#;
+
+/// Press Ctrl+Arrow argv[1] times; key code 123 is left, 124 right
+#[cfg(target_os = "macos")]
+const MAC_SWITCH_SCRIPT: &str = r#"
+function run(argv) {
+  const se = Application('System Events');
+  for (let i = 0; i < Number(argv[1]); i++) {
+    se.keyCode(Number(argv[0]), { using: 'control down' });
+    delay(0.4);
+  }
+}
+"#;
+
+#[cfg(target_os = "macos")]
+pub fn list() -> Result<Vec<Workspace>, String> {
+    let json = output(Command::new("osascript").args(["-l", "JavaScript", "-e", MAC_LIST_SCRIPT]))?;
+    serde_json::from_str(&json).map_err(|e| format!("Couldn't read Spaces: {}", e))
+}
+
+#[cfg(target_os = "macos")]
+fn switch(workspaces: &[Workspace], target: &Workspace) -> Result<(), String> {
+    if !crate::ax::is_trusted() {
+        return Err("Allow Accessibility access to switch Spaces".to_string());
+    }
+    let steps = steps_to(workspaces, target);
+    let key = if steps < 0 { "123" } else { "124" };
+    output(Command::new("osascript").args([
+        "-l",
+        "JavaScript",
+        "-e",
+        MAC_SWITCH_SCRIPT,
+        key,
+        &steps.abs().to_string(),
+    ]))
+    .map(|_| ())
+}
+
+/// Desktop ids are GUIDs packed into one binary value; the names live in
+/// per-desktop subkeys. Windows 10 keeps the current desktop per session.
+#[cfg(target_os = "windows")]
+const WIN_LIST_SCRIPT: &str = r#"
+$key = 'HKCU:\Software\Microsoft\Windows\CurrentVersion\Explorer\VirtualDesktops'
+$ids = (Get-ItemProperty $key).VirtualDesktopIDs
+$current = (Get-ItemProperty $key -ErrorAction SilentlyContinue).CurrentVirtualDesktop
+if (-not $current) {
+  $session = (Get-Process -Id $PID).SessionId
+  $current = (Get-ItemProperty "HKCU:\Software\Microsoft\Windows\CurrentVersion\Explorer\SessionInfo\$session\VirtualDesktops").CurrentVirtualDesktop
+}
+$currentId = if ($current) { [Guid]::new([byte[]]$current) } else { $null }
+$desktops = @(for ($i = 0; $i -lt $ids.Length / 16; $i++) {
+  $id = [Guid]::new([byte[]]$ids[($i * 16)..($i * 16 + 15)])
+  $name = (Get-ItemProperty "$key\Desktops\{$id}" -ErrorAction SilentlyContinue).Name
+  if (-not $name) { $name = "Desktop $($i + 1)" }
+  @{ index = $i; name = $name; current = ($id -eq $currentId) }
+})
+ConvertTo-Json -Compress -InputObject $desktops
+"#;
+
+/// Press Ctrl+Win+Arrow; `{0}` is the arrow's virtual key, `{1}` the count
+#[cfg(target_os = "windows")]
+const WIN_SWITCH_SCRIPT: &str = r#"
+Add-Type @'
+using System.Runtime.InteropServices;
+public static class Keys {
+  [DllImport("user32.dll")] public static extern void keybd_event(byte vk, byte scan, uint flags, System.UIntPtr extra);
+}
+'@
+for ($i = 0; $i -lt {1}; $i++) {
+  foreach ($vk in 0x11, 0x5B, {0}) { [Keys]::keybd_event($vk, 0, 0, [UIntPtr]::Zero) }
+  foreach ($vk in {0}, 0x5B, 0x11) { [Keys]::keybd_event($vk, 0, 2, [UIntPtr]::Zero) }
+  Start-Sleep -Milliseconds 150
+}
+"#;
+
+#[cfg(target_os = "windows")]
+pub fn list() -> Result<Vec<Workspace>, String> {
+    let json =
+        output(Command::new("powershell").args(["-NoProfile", "-Command", WIN_LIST_SCRIPT]))?;
+    if json.trim().is_empty() {
+        // Only the default desktop, which isn't written to the registry
+        return Ok(vec![Workspace {
+            index: 0,
+            name: "Desktop 1".to_string(),
+            current: true,
+        }]);
+    }
+    serde_json::from_str(&json).map_err(|e| format!("Couldn't read virtual desktops: {}", e))
+}
+
+#[cfg(target_os = "windows")]
+fn switch(workspaces: &[Workspace], target: &Workspace) -> Result<(), String> {
+    let steps = steps_to(workspaces, target);
+    let key = if steps < 0 { "0x25" } else { "0x27" };
+    let script = WIN_SWITCH_SCRIPT
+        .replace("{0}", key)
+        .replace("{1}", &steps.abs().to_string());
+    output(Command::new("powershell").args(["-NoProfile", "-Command", &script])).map(|_| ())
+}
+
+#[cfg(not(target_os = "linux"))]
+fn move_window(_target: &Workspace) -> Result<(), String> {
+    Err("Moving windows between desktops isn't supported on this platform".to_string())
+}
+
+fn find(workspaces: &[Workspace], index: usize) -> Result<&Workspace, String> {
+    workspaces
+        .iter()
+        .find(|w| w.index == index)
+        .ok_or_else(|| format!("Workspace {} no longer exists", index + 1))
+}
+
+/// Switch to the workspace at `index`
+pub fn switch_to(index: usize) -> Result<(), String> {
+    let workspaces = list()?;
+    let target = find(&workspaces, index)?;
+    if target.current {
+        return Ok(());
+    }
+    switch(&workspaces, target)
+}
+
+/// Move the focused window to the workspace at `index`, staying where we are
+pub fn move_window_to(index: usize) -> Result<(), String> {
+    let workspaces = list()?;
+    move_window(find(&workspaces, index)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn workspace(index: usize, name: &str, current: bool) -> Workspace {
+        Workspace {
+            index,
+            name: name.to_string(),
+            current,
+        }
+    }
+
+    #[test]
+    fn parses_wmctrl_desktops() {
+        let output = "0  * DG: 3840x1080  VP: 0,0  WA: 0,25 3840x1055  Web\n\
+                      1  - DG: 3840x1080  VP: N/A  WA: N/A  Code and Docs\n\
+                      2  - DG: 3840x1080  VP: 0,0  WA: 0,25 3840x1055\n";
+        assert_eq!(
+            parse_wmctrl_desktops(output),
+            vec![
+                workspace(0, "Web", true),
+                workspace(1, "Code and Docs", false),
+                workspace(2, "Workspace 3", false),
+            ]
+        );
+    }
+
+    #[test]
+    fn counts_steps_from_the_current_workspace() {
+        let workspaces = [
+            workspace(0, "Desktop 1", false),
+            workspace(1, "Desktop 2", true),
+            workspace(2, "Desktop 3", false),
+            workspace(3, "Desktop 4", false),
+        ];
+        assert_eq!(steps_to(&workspaces, &workspaces[3]), 2);
+        assert_eq!(steps_to(&workspaces, &workspaces[0]), -1);
+    }
+}
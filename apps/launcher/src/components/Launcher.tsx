@@ -17,6 +17,7 @@ import { AIChat } from "./ai";
 import { CodexChat } from "./codex";
 import { Dashboard } from "./dashboard";
 import { cn } from "@/lib/utils";
+import type { ActiveContext } from "@/types";
 
 interface InstallStatus {
   pluginId: string;
@@ -25,8 +26,15 @@ interface InstallStatus {
 }
 
 export function Launcher() {
-  const { loadTheme, hideWindow, results, indexingStatus, setupIndexingListener, query } =
-    useLauncherStore();
+  const {
+    loadTheme,
+    hideWindow,
+    results,
+    indexingStatus,
+    setupIndexingListener,
+    setupSearchListener,
+    query,
+  } = useLauncherStore();
   const { initialize: initAuth, setupAuthListener } = useAuthStore();
   const { isAIMode } = useAIStore();
   const { isCodexMode } = useCodexStore();
@@ -94,6 +102,10 @@ export function Launcher() {
   useEffect(() => {
     loadTheme();
     setupIndexingListener();
+    let unlistenSearch: (() => void) | undefined;
+    setupSearchListener().then((unlisten) => {
+      unlistenSearch = unlisten;
+    });
     initAuth();
     loadSettings();
     
@@ -136,6 +148,16 @@ export function Launcher() {
       unlistenInstall = fn;
     });
 
+    // Pre-fill the trigger of the profile for the app we were opened over
+    let unlistenContext: (() => void) | undefined;
+    listen<ActiveContext>("launcher-context", (event) => {
+      const trigger = event.payload.profile?.trigger;
+      const { query, setQuery } = useLauncherStore.getState();
+      if (trigger && !query) setQuery(trigger);
+    }).then((fn) => {
+      unlistenContext = fn;
+    });
+
     const handleClickOutside = (e: MouseEvent) => {
       const target = e.target as HTMLElement;
       // Get current settings from store (need fresh value, not stale closure)
@@ -156,11 +178,13 @@ export function Launcher() {
       window.removeEventListener("open-settings", handleOpenSettings);
       if (unlistenAuth) unlistenAuth();
       if (unlistenInstall) unlistenInstall();
+      if (unlistenContext) unlistenContext();
+      if (unlistenSearch) unlistenSearch();
       if (unlistenMove) unlistenMove();
       if (unlistenResize) unlistenResize();
       if (saveTimeoutRef.current) clearTimeout(saveTimeoutRef.current);
     };
-  }, [loadTheme, hideWindow, setupIndexingListener, settingsOpen, initAuth, setupAuthListener, loadSettings, saveWindowState]);
+  }, [loadTheme, hideWindow, setupIndexingListener, setupSearchListener, settingsOpen, initAuth, setupAuthListener, loadSettings, saveWindowState]);
 
   const hasResults = results.length > 0;
   const hasCalcResult = results.some((r) => r.category === "Calculator");
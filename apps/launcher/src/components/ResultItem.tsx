@@ -1,3 +1,4 @@
+import { useState } from "react";
 import { motion } from "framer-motion";
 import { Calculator, AppWindow, File, Terminal, Puzzle } from "lucide-react";
 import { convertFileSrc } from "@tauri-apps/api/core";
@@ -29,6 +30,39 @@ function getCategoryIcon(category: ResultCategory) {
   }
 }
 
+/** Thumbnails are made at 2x the 20px they're drawn at */
+const THUMBNAIL_SIZE = 40;
+
+/**
+ * An image file's thumbnail over its placeholder emoji. The placeholder
+ * stays if the thumbnail fails or the backend was too busy to make it.
+ */
+function Thumbnail({ path, placeholder }: { path: string; placeholder: string }) {
+  const [loaded, setLoaded] = useState(false);
+  const [failed, setFailed] = useState(false);
+  const src = `${convertFileSrc(path, "thumb")}?size=${THUMBNAIL_SIZE}`;
+
+  return (
+    <span className="relative flex h-5 w-5 items-center justify-center">
+      {!loaded && <span className="text-xl">{placeholder}</span>}
+      {!failed && (
+        <img
+          src={src}
+          alt=""
+          loading="lazy"
+          decoding="async"
+          className={cn(
+            "absolute inset-0 h-5 w-5 object-contain",
+            !loaded && "opacity-0"
+          )}
+          onLoad={() => setLoaded(true)}
+          onError={() => setFailed(true)}
+        />
+      )}
+    </span>
+  );
+}
+
 function renderIcon(result: SearchResult) {
   const { icon, category } = result;
 
@@ -52,6 +86,10 @@ function renderIcon(result: SearchResult) {
     );
   }
 
+  if (icon.type === "Thumbnail") {
+    return <Thumbnail path={icon.value.path} placeholder={icon.value.placeholder} />;
+  }
+
   if (icon.type === "Text") {
     return <span className="text-sm font-medium truncate max-w-[24px]">{icon.value}</span>;
   }
@@ -74,6 +112,9 @@ export function ResultItem({
       transition={{ duration: 0.15, delay: index * 0.02 }}
       onClick={onExecute}
       onMouseEnter={onSelect}
+      role="option"
+      aria-selected={isSelected}
+      aria-label={result.aria_label ?? undefined}
       className={cn(
         "flex items-center gap-3 px-4 py-2.5 cursor-pointer transition-colors",
         "rounded-lg mx-2",
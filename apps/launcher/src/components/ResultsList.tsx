@@ -24,6 +24,8 @@ export function ResultsList() {
   return (
     <div
       ref={listRef}
+      role="listbox"
+      aria-label="Results"
       className="flex flex-col py-2 max-h-[360px] overflow-y-auto"
     >
       <AnimatePresence mode="popLayout">
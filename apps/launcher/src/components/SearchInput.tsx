@@ -1,4 +1,5 @@
 import { useRef, useEffect, useState, useCallback } from "react";
+import { invoke } from "@tauri-apps/api/core";
 import { Search, Sparkles, Send, Terminal, Settings, Zap, RefreshCw, Puzzle, LogOut, Palette } from "lucide-react";
 import { useLauncherStore } from "@/stores/launcher";
 import { useAIStore } from "@/stores/ai";
@@ -18,10 +19,10 @@ const commandIcons: Record<string, React.ReactNode> = {
 
 export function SearchInput() {
   const inputRef = useRef<HTMLInputElement>(null);
-  const { query, setQuery, moveSelection, executeSelected, hideWindow, results, matchedCommand, matchingCommands } =
+  const { query, setQuery, moveSelection, executeSelected, hideWindow, results, selectedIndex, matchedCommand, matchingCommands } =
     useLauncherStore();
   const { isAIMode, isStreaming, sendMessage, exitAIMode, enterAIMode } = useAIStore();
-  const { enterCodexMode } = useCodexStore();
+  const { enterCodexMode, askAboutResult } = useCodexStore();
   const [aiInput, setAiInput] = useState("");
 
   // Check if we have a matched command trigger
@@ -139,11 +140,25 @@ export function SearchInput() {
         e.preventDefault();
         hideWindow();
         break;
+      case "j":
+        // Ask AI about the selected result
+        if ((e.metaKey || e.ctrlKey) && results[selectedIndex]) {
+          e.preventDefault();
+          askAboutResult(results[selectedIndex]);
+          setQuery("");
+        }
+        break;
       case "Tab":
         // Tab to enter AI mode if there's a query
         if (query.trim() && e.shiftKey) {
           e.preventDefault();
           handleEnterAIMode();
+        } else if (query.startsWith("browse:") || query === "~" || query.startsWith("~/")) {
+          // Complete paths in the file navigator like a shell
+          e.preventDefault();
+          invoke<string | null>("complete_path", { query }).then((completed) => {
+            if (completed) setQuery(completed);
+          });
         }
         break;
     }
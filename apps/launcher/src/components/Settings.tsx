@@ -1,10 +1,11 @@
 import { useState, useEffect } from "react";
-import { invoke } from "@tauri-apps/api/core";
+import { invoke, convertFileSrc } from "@tauri-apps/api/core";
 import { listen } from "@tauri-apps/api/event";
 import { openUrl } from "@tauri-apps/plugin-opener";
-import { Settings as SettingsIcon, X, FolderOpen, Plug, HardDrive, Link2, ExternalLink, Check, Loader2, ChevronDown, ChevronUp, Save, Plus, Trash2, RefreshCw, Store, Download, Star, Search, Terminal, WifiOff, BadgeCheck, Sparkles, Keyboard, RotateCcw, AlertCircle } from "lucide-react";
+import { ask } from "@tauri-apps/plugin-dialog";
+import { Settings as SettingsIcon, X, FolderOpen, Plug, HardDrive, Link2, ExternalLink, Check, Loader2, ChevronDown, ChevronUp, Save, Plus, Trash2, RefreshCw, Store, Download, Star, Search, Terminal, WifiOff, BadgeCheck, Sparkles, Keyboard, RotateCcw, AlertCircle, Wrench } from "lucide-react";
 import { motion, AnimatePresence } from "framer-motion";
-import type { IndexConfig, PluginManifest, OAuthProviderInfo, OAuthCredentials, RegistryPlugin, PluginUpdate, MarketplaceResponse, ShortcutResult } from "@/types";
+import type { IndexConfig, PluginManifest, PluginHealthReport, PluginStorageUsage, OAuthProviderInfo, OAuthCredentials, RegistryPlugin, PluginUpdate, MarketplaceResponse, ShortcutResult } from "@/types";
 import { cn } from "@/lib/utils";
 import { CodexSettings } from "./codex";
 
@@ -623,16 +624,75 @@ function IndexSettings({ config, onConfigChange }: { config: IndexConfig; onConf
   );
 }
 
+function formatSize(bytes: number): string {
+  if (bytes < 1024) return `${bytes} B`;
+  if (bytes < 1024 * 1024) return `${(bytes / 1024).toFixed(1)} KB`;
+  if (bytes < 1024 * 1024 * 1024) return `${(bytes / (1024 * 1024)).toFixed(1)} MB`;
+  return `${(bytes / (1024 * 1024 * 1024)).toFixed(1)} GB`;
+}
+
 function PluginSettings({ plugins, pluginsDir, onRefresh }: { plugins: PluginManifest[]; pluginsDir: string; onRefresh: () => void }) {
   const [toggling, setToggling] = useState<string | null>(null);
   const [updates, setUpdates] = useState<PluginUpdate[]>([]);
   const [_checkingUpdates, setCheckingUpdates] = useState(false);
   const [updating, setUpdating] = useState<string | null>(null);
+  const [health, setHealth] = useState<Record<string, PluginHealthReport>>({});
+  const [storage, setStorage] = useState<Record<string, PluginStorageUsage>>({});
+  const [clearing, setClearing] = useState<string | null>(null);
 
   useEffect(() => {
     checkForUpdates();
+    loadHealth();
+    loadStorage();
   }, [plugins]);
 
+  // The runtime disables plugins that keep failing
+  useEffect(() => {
+    const unlisten = listen<PluginHealthReport>("plugin-quarantined", () => {
+      onRefresh();
+    });
+    return () => {
+      unlisten.then(fn => fn());
+    };
+  }, []);
+
+  const loadHealth = async () => {
+    try {
+      const reports = await Promise.all(
+        plugins.map(p => invoke<PluginHealthReport>("get_plugin_health", { id: p.id }))
+      );
+      setHealth(Object.fromEntries(reports.map(r => [r.plugin_id, r])));
+    } catch (error) {
+      console.error("Failed to load plugin health:", error);
+    }
+  };
+
+  const loadStorage = async () => {
+    try {
+      const usage = await invoke<PluginStorageUsage[]>("get_plugin_storage_usage");
+      setStorage(Object.fromEntries(usage.map(u => [u.plugin_id, u])));
+    } catch (error) {
+      console.error("Failed to load plugin storage usage:", error);
+    }
+  };
+
+  const handleClearData = async (pluginId: string, name: string) => {
+    const confirmed = await ask(`Delete everything ${name} has stored, including its settings?`, {
+      title: "Clear plugin data",
+      kind: "warning",
+    });
+    if (!confirmed) return;
+    setClearing(pluginId);
+    try {
+      await invoke("clear_plugin_data", { id: pluginId });
+      loadStorage();
+    } catch (error) {
+      console.error("Failed to clear plugin data:", error);
+    } finally {
+      setClearing(null);
+    }
+  };
+
   const checkForUpdates = async () => {
     setCheckingUpdates(true);
     try {
@@ -721,6 +781,41 @@ function PluginSettings({ plugins, pluginsDir, onRefresh }: { plugins: PluginMan
                       by {plugin.author}
                     </p>
                   )}
+                  {health[plugin.id]?.total_errors > 0 && (
+                    <p
+                      className={cn(
+                        "text-xs mt-0.5 flex items-center gap-1",
+                        health[plugin.id].quarantined_at ? "text-red-400" : "text-yellow-400"
+                      )}
+                      title={health[plugin.id].recent_errors[0]?.message}
+                    >
+                      <AlertCircle className="h-3 w-3" />
+                      {health[plugin.id].quarantined_at
+                        ? `Crashed ${health[plugin.id].consecutive_errors} times, disabled`
+                        : `${health[plugin.id].total_errors} failed ${health[plugin.id].total_errors === 1 ? "call" : "calls"}`}
+                    </p>
+                  )}
+                  {storage[plugin.id] && (() => {
+                    const usage = storage[plugin.id];
+                    const stored = usage.data_bytes + usage.config_bytes;
+                    return (
+                      <p className="text-xs text-muted-foreground mt-0.5 flex items-center gap-1">
+                        <HardDrive className="h-3 w-3" />
+                        {formatSize(stored)} stored
+                        {usage.quota_bytes !== null && ` of ${formatSize(usage.quota_bytes)}`}
+                        {` · ${formatSize(usage.package_bytes)} installed`}
+                        {stored > 0 && (
+                          <button
+                            onClick={() => handleClearData(plugin.id, plugin.name)}
+                            disabled={clearing === plugin.id}
+                            className="ml-1 text-red-400 hover:underline disabled:opacity-50"
+                          >
+                            Clear data
+                          </button>
+                        )}
+                      </p>
+                    );
+                  })()}
                 </div>
                 <div className="flex items-center gap-2 ml-3">
                   {getUpdateForPlugin(plugin.id) && (
@@ -1132,6 +1227,24 @@ function MarketplaceSettings({ installedPlugins, onRefresh }: { installedPlugins
 
   useEffect(() => {
     loadMarketplace();
+
+    // Background refreshes and icon caching update the plugins shown
+    // without reloading the list under the user
+    const syncFromBackend = async () => {
+      const response = await invoke<MarketplaceResponse>("list_marketplace_plugins");
+      const fresh = new Map(response.plugins.map(p => [p.id, p]));
+      setMarketplacePlugins(plugins => plugins.map(p => fresh.get(p.id) ?? p));
+      setIsOffline(response.is_offline);
+      setLastUpdated(response.last_updated);
+    };
+    const unlisteners = [
+      listen("marketplace-updated", syncFromBackend),
+      listen("marketplace-icons-updated", syncFromBackend),
+    ];
+
+    return () => {
+      unlisteners.forEach(p => p.then(fn => fn()));
+    };
   }, []);
 
   const loadMarketplace = async () => {
@@ -1319,12 +1432,26 @@ function MarketplaceSettings({ installedPlugins, onRefresh }: { installedPlugins
         <div className="space-y-2">
           {filteredPlugins.map((plugin) => {
             const isInstalled = installedIds.has(plugin.id);
+            const isDev = plugin.source === "local_dev";
             return (
               <div
                 key={plugin.id}
-                className="p-3 bg-muted/20 rounded-md"
+                className={cn(
+                  "p-3 bg-muted/20 rounded-md",
+                  isDev && "border border-dashed border-orange-500/40"
+                )}
               >
                 <div className="flex items-start justify-between">
+                  {plugin.icon_path && (
+                    <img
+                      src={convertFileSrc(plugin.icon_path)}
+                      alt=""
+                      className="w-10 h-10 mr-3 rounded object-contain shrink-0"
+                      onError={(e) => {
+                        e.currentTarget.style.display = 'none';
+                      }}
+                    />
+                  )}
                   <div className="flex-1 min-w-0">
                     <div className="flex items-center gap-2">
                       <h4 className="font-medium">{plugin.name}</h4>
@@ -1341,7 +1468,15 @@ function MarketplaceSettings({ installedPlugins, onRefresh }: { installedPlugins
                           <Sparkles className="h-3 w-3" />
                         </span>
                       )}
-                      {isInstalled && (
+                      {isDev ? (
+                        <span
+                          className="flex items-center gap-1 text-xs px-1.5 py-0.5 bg-orange-500/20 text-orange-400 rounded"
+                          title={`Unsigned build from ${plugin.dev?.source_dir}`}
+                        >
+                          <Wrench className="h-3 w-3" />
+                          Local dev
+                        </span>
+                      ) : isInstalled && (
                         <span className="text-xs px-1.5 py-0.5 bg-green-500/20 text-green-400 rounded">
                           Installed
                         </span>
@@ -1350,6 +1485,21 @@ function MarketplaceSettings({ installedPlugins, onRefresh }: { installedPlugins
                     <p className="text-xs text-muted-foreground mt-0.5">
                       {plugin.description || "No description"}
                     </p>
+                    {plugin.dev && (
+                      <p
+                        className={cn(
+                          "text-xs mt-1 font-mono truncate",
+                          plugin.dev.error ? "text-red-400" : "text-muted-foreground"
+                        )}
+                        title={plugin.dev.error ?? plugin.dev.source_dir}
+                      >
+                        {plugin.dev.error
+                          ? `Reload failed: ${plugin.dev.error}`
+                          : plugin.dev.reloaded_at
+                            ? `Reloaded at ${new Date(plugin.dev.reloaded_at * 1000).toLocaleTimeString()}`
+                            : "Waiting for a new build"}
+                      </p>
+                    )}
                     <div className="flex items-center gap-3 mt-1.5 text-xs text-muted-foreground">
                       {plugin.author && (
                         <span>by {plugin.author}</span>
@@ -13,6 +13,8 @@ function getIconDisplay(icon: ResultIcon): string {
       return icon.value.charAt(0).toUpperCase();
     case "Path":
       return "📁";
+    case "Thumbnail":
+      return icon.value.placeholder;
     default:
       return "📱";
   }
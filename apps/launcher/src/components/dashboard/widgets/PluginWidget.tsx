@@ -1,8 +1,10 @@
 import { useState, useEffect, useCallback } from "react";
 import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
+import { openUrl } from "@tauri-apps/plugin-opener";
 import { Loader2, AlertCircle } from "lucide-react";
 import { cn } from "@/lib/utils";
-import type { WidgetData, WidgetItem } from "@/types";
+import type { PowerStatus, WidgetData, WidgetItem } from "@/types";
 
 interface PluginWidgetProps {
   pluginId: string;
@@ -11,10 +13,32 @@ interface PluginWidgetProps {
   refreshInterval?: number;
 }
 
+/** Whether low-power mode or a focus session has paused widget refresh */
+function useRefreshPaused() {
+  const [paused, setPaused] = useState(false);
+
+  useEffect(() => {
+    const update = () =>
+      invoke<PowerStatus>("get_power_status")
+        .then((status) => setPaused(status.paused.includes("widget_refresh")))
+        .catch(console.error);
+    update();
+
+    // Focus sessions pause subsystems without changing the power state
+    const unlisteners = [listen("power-state-changed", update), listen("focus-changed", update)];
+    return () => {
+      unlisteners.forEach((unlisten) => unlisten.then((fn) => fn()));
+    };
+  }, []);
+
+  return paused;
+}
+
 export function PluginWidget({ pluginId, widgetId, config, refreshInterval = 0 }: PluginWidgetProps) {
   const [data, setData] = useState<WidgetData | null>(null);
   const [error, setError] = useState<string | null>(null);
   const [isLoading, setIsLoading] = useState(true);
+  const refreshPaused = useRefreshPaused();
 
   const loadWidgetData = useCallback(async () => {
     try {
@@ -36,12 +60,12 @@ export function PluginWidget({ pluginId, widgetId, config, refreshInterval = 0 }
   useEffect(() => {
     loadWidgetData();
 
-    // Set up refresh interval if specified
-    if (refreshInterval > 0) {
+    // Set up refresh interval if specified, unless low-power mode paused it
+    if (refreshInterval > 0 && !refreshPaused) {
       const interval = setInterval(loadWidgetData, refreshInterval * 1000);
       return () => clearInterval(interval);
     }
-  }, [loadWidgetData, refreshInterval]);
+  }, [loadWidgetData, refreshInterval, refreshPaused]);
 
   if (isLoading) {
     return (
@@ -183,16 +207,26 @@ function GridWidget({ data }: { data: WidgetData }) {
 }
 
 function CustomWidget({ data }: { data: WidgetData }) {
-  // For custom widgets, we render sanitized HTML
-  // In a real implementation, you'd want to use a proper sanitizer like DOMPurify
+  // The backend has already sanitized the HTML against an allowlist
   if (!data.html) {
     return null;
   }
 
+  // Open links in the browser instead of navigating the launcher window
+  const handleClick = (e: React.MouseEvent<HTMLDivElement>) => {
+    const link = (e.target as HTMLElement).closest("a");
+    if (!link) return;
+    e.preventDefault();
+    const href = link.getAttribute("href");
+    if (href) {
+      openUrl(href).catch((err) => console.error("Failed to open link:", err));
+    }
+  };
+
   return (
-    <div 
+    <div
       className="h-full p-2 overflow-auto"
-      // Note: In production, sanitize this HTML!
+      onClick={handleClick}
       dangerouslySetInnerHTML={{ __html: data.html }}
     />
   );
@@ -11,6 +11,7 @@ import type {
   BunInstallStatus,
   DevServerInfo,
 } from "@/types/codex";
+import type { SearchResult } from "@/types";
 
 interface CodexState {
   // Installation state
@@ -48,6 +49,7 @@ interface CodexState {
 
   // Session actions
   startSession: (workingDir: string) => Promise<void>;
+  askAboutResult: (result: SearchResult, question?: string) => Promise<void>;
   sendMessage: (message: string) => Promise<void>;
   stopSession: () => Promise<void>;
   pollOutput: () => Promise<void>;
@@ -204,6 +206,24 @@ export const useCodexStore = create<CodexState>((set, get) => ({
     }
   },
 
+  askAboutResult: async (result: SearchResult, question?: string) => {
+    try {
+      // Reuses the session already running in the result's folder
+      const session = await invoke<SessionInfo>("codex_ask_about_result", { result, question });
+      const reused = get().currentSession?.id === session.id;
+      set({
+        isCodexMode: true,
+        currentSession: session,
+        isSessionActive: true,
+        selectedWorkingDir: session.working_dir,
+        messages: reused ? get().messages : [],
+      });
+      if (!reused) get().pollOutput();
+    } catch (error) {
+      console.error("Failed to ask Codex about result:", error);
+    }
+  },
+
   sendMessage: async (message: string) => {
     const { currentSession, messages } = get();
     if (!currentSession) {
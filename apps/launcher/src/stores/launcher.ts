@@ -22,9 +22,20 @@ interface LauncherState {
   hideWindow: () => Promise<void>;
   loadTheme: () => Promise<void>;
   setupIndexingListener: () => Promise<void>;
+  setupSearchListener: () => Promise<() => void>;
   reset: () => void;
 }
 
+/**
+ * Where the selection goes when results change under it: the same result
+ * if it's still there, otherwise the same position
+ */
+function keepSelection(previous: SearchResult[], selectedIndex: number, results: SearchResult[]) {
+  const id = previous[selectedIndex]?.id;
+  const index = id === undefined ? -1 : results.findIndex((r) => r.id === id);
+  return index >= 0 ? index : Math.min(selectedIndex, Math.max(results.length - 1, 0));
+}
+
 export const useLauncherStore = create<LauncherState>((set, get) => ({
   query: "",
   results: [],
@@ -71,8 +82,11 @@ export const useLauncherStore = create<LauncherState>((set, get) => ({
     set({ isLoading: true });
     try {
       const results = await invoke<SearchResult[]>("search", { query });
+      // A newer keystroke may have started (and cancelled this) search
+      if (get().query !== query) return;
       set({ results, selectedIndex: 0, isLoading: false });
     } catch (error) {
+      if (get().query !== query) return;
       console.error("Search error:", error);
       set({ results: [], isLoading: false });
     }
@@ -137,5 +151,37 @@ export const useLauncherStore = create<LauncherState>((set, get) => ({
     });
   },
 
+  setupSearchListener: async () => {
+    // Slow providers report back after the first batch of results
+    const unlistenResults = await listen<{ query: string; results: SearchResult[] }>(
+      "search-results-updated",
+      (event) => {
+        const { query, results, selectedIndex } = get();
+        if (event.payload.query !== query) return;
+        set({
+          results: event.payload.results,
+          selectedIndex: keepSelection(results, selectedIndex, event.payload.results),
+        });
+      }
+    );
+    // The window switcher's list changed while it's showing
+    const unlistenWindows = await listen("windows-changed", async () => {
+      const { query } = get();
+      if (!query.trimStart().startsWith("win:")) return;
+      try {
+        const results = await invoke<SearchResult[]>("search", { query });
+        const { query: current, results: previous, selectedIndex } = get();
+        if (current !== query) return;
+        set({ results, selectedIndex: keepSelection(previous, selectedIndex, results) });
+      } catch (error) {
+        console.error("Window list refresh error:", error);
+      }
+    });
+    return () => {
+      unlistenResults();
+      unlistenWindows();
+    };
+  },
+
   reset: () => set({ query: "", results: [], selectedIndex: 0 }),
 }));
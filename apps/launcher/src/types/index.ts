@@ -14,12 +14,30 @@ export interface SearchResult {
   icon: ResultIcon;
   category: ResultCategory;
   score: number;
+  aria_label: string | null; // Filled in by the backend when the provider leaves it out
 }
 
+/** Secondary action on a result, from `get_actions`; run with `execute_action` */
+export interface ResultAction {
+  id: string;
+  title: string;
+  shortcut: string | null;
+}
+
+/**
+ * Inline edit to a result, run with `mutate_result`; `get_result_mutations`
+ * lists the `type`s a result supports
+ */
+export type ResultMutation =
+  | { type: "rename"; name: string }
+  | { type: "set_url"; url: string };
+
 export type ResultIcon =
   | { type: "Text"; value: string }
   | { type: "Path"; value: string }
-  | { type: "Emoji"; value: string };
+  | { type: "Emoji"; value: string }
+  /** An image file, served small by the `thumb` protocol */
+  | { type: "Thumbnail"; value: { path: string; placeholder: string } };
 
 export type ResultCategory =
   | "Calculator"
@@ -45,6 +63,28 @@ export interface Command {
   icon: string | null;
   source: CommandSource;
   enabled: boolean;
+  aria_label: string | null;
+}
+
+/** Per-app profile from settings, matched against the frontmost app */
+export interface AppProfile {
+  name: string;
+  apps: string[];
+  trigger?: string; // Pre-filled in the search box when the launcher opens over a matching app
+  provider_boosts: Record<string, number>;
+}
+
+/** The app the launcher was summoned over, sent with `launcher-context` */
+export interface ActiveContext {
+  app: { name: string; identifier: string | null } | null;
+  profile: AppProfile | null;
+}
+
+/** From `get_power_status` and the `power-state-changed` event */
+export interface PowerStatus {
+  state: { on_battery: boolean; battery_percent: number | null; power_saver: boolean };
+  low_power: boolean;
+  paused: ("indexing" | "widget_refresh" | "marketplace_refresh" | "provider_sync")[];
 }
 
 export interface SystemTheme {
@@ -53,6 +93,31 @@ export interface SystemTheme {
   window_blur_supported: boolean;
 }
 
+/** From `get_accessibility_preferences`: the OS's preferences unless overridden in settings */
+export interface AccessibilityPreferences {
+  reduce_motion: boolean;
+  high_contrast: boolean;
+}
+
+export type GitStatus = "modified" | "added" | "deleted" | "renamed" | "untracked" | "conflicted";
+
+/** One file or folder from `browse_directory` */
+export interface BrowseEntry {
+  path: string;
+  name: string;
+  is_dir: boolean;
+  size: number | null;
+  icon: ResultIcon;
+  git_status: GitStatus | null;
+}
+
+export interface DirectoryPage {
+  path: string;
+  entries: BrowseEntry[];
+  total: number;
+  next_offset: number | null;
+}
+
 export interface IndexingStatus {
   is_indexing: boolean;
   files_indexed: number;
@@ -81,10 +146,39 @@ export interface PluginInfo {
 
 export type PluginManifest = PluginInfo;
 
+export interface PluginError {
+  at: number; // Unix timestamp in seconds
+  call: string;
+  message: string;
+}
+
+export interface PluginHealthReport {
+  plugin_id: string;
+  consecutive_errors: number;
+  total_errors: number;
+  quarantined_at: number | null; // Set when disabled for failing repeatedly
+  recent_errors: PluginError[]; // Newest first
+}
+
+export interface PluginStorageUsage {
+  plugin_id: string;
+  package_bytes: number;
+  data_bytes: number; // Files in the plugin's data directory
+  config_bytes: number;
+  quota_bytes: number | null; // Limit on data and config together
+}
+
+// From get_plugin_config; save edits with set_plugin_config
+export interface PluginSettingsValues {
+  schema: Record<string, unknown> | null; // JSON Schema from the manifest's settings_schema
+  values: Record<string, unknown>; // Current values, schema defaults filled in
+}
+
 export interface OAuthProviderInfo {
   id: string;
   name: string;
   connected: boolean;
+  custom: boolean; // Registered by the user rather than built in
 }
 
 export interface OAuthCredentials {
@@ -99,6 +193,7 @@ export interface RegistryPlugin {
   author: string | null;
   description: string | null;
   icon_url: string | null;
+  icon_path: string | null; // Cached copy of icon_url
   homepage: string | null;
   repository: string | null;
   download_url: string;
@@ -109,6 +204,17 @@ export interface RegistryPlugin {
   rating: number | null;
   verified: boolean;
   featured: boolean;
+  source: PluginSource;
+  dev?: DevStatus; // Only for local_dev entries
+}
+
+// "local_dev": installed by `launcher-plugin dev`, unsigned and not from the store
+export type PluginSource = "marketplace" | "local_dev";
+
+export interface DevStatus {
+  source_dir: string;
+  reloaded_at: number | null; // Unix timestamp in seconds
+  error: string | null; // Why the last build failed to load
 }
 
 export interface MarketplaceResponse {
@@ -221,7 +327,8 @@ export interface WidgetData {
   title?: string;
   value?: string;
   subtitle?: string;
-  html?: string;
+  html?: string; // Sanitized by the backend; only allowlisted tags and attributes
+  aria_label?: string;
 }
 
 export interface WidgetItem {
@@ -230,6 +337,7 @@ export interface WidgetItem {
   subtitle?: string;
   icon?: string;
   action?: string;
+  aria_label?: string;
 }
 
 // Shortcut configuration result from backend
@@ -2,7 +2,7 @@
  * Plugin Versions API
  * 
  * GET - List all versions for a plugin
- * POST - Upload a new version (direct WASM upload)
+ * POST - Upload a new version (direct WASM or packaged zip upload)
  */
 
 import { NextRequest, NextResponse } from "next/server";
@@ -79,15 +79,18 @@ export async function POST(
     // Parse multipart form data
     const formData = await request.formData();
     const wasmFile = formData.get("wasm") as File | null;
+    const packageFile = formData.get("package") as File | null;
+    const expectedChecksum = formData.get("checksum") as string | null;
     const version = formData.get("version") as string | null;
     const changelog = formData.get("changelog") as string | null;
     const isPrerelease = formData.get("prerelease") === "true";
     const permissionsJson = formData.get("permissions") as string | null;
 
     // Validate required fields
-    if (!wasmFile) {
+    const uploadFile = packageFile ?? wasmFile;
+    if (!uploadFile) {
       return NextResponse.json(
-        { error: "WASM file is required" },
+        { error: "WASM file or package is required" },
         { status: 400 }
       );
     }
@@ -122,21 +125,37 @@ export async function POST(
       );
     }
 
-    // Read the WASM file
-    const wasmBuffer = Buffer.from(await wasmFile.arrayBuffer());
+    // Read the uploaded file
+    const fileBuffer = Buffer.from(await uploadFile.arrayBuffer());
 
-    // Validate it's a valid WASM file (magic bytes)
-    const wasmMagic = Buffer.from([0x00, 0x61, 0x73, 0x6d]); // \0asm
-    if (!wasmBuffer.slice(0, 4).equals(wasmMagic)) {
+    // Validate magic bytes: a zip from `launcher-plugin package`, or raw WASM
+    if (packageFile) {
+      const zipMagic = Buffer.from([0x50, 0x4b, 0x03, 0x04]); // PK\3\4
+      if (!fileBuffer.slice(0, 4).equals(zipMagic)) {
+        return NextResponse.json(
+          { error: "Invalid package. File is not a zip archive." },
+          { status: 400 }
+        );
+      }
+    } else {
+      const wasmMagic = Buffer.from([0x00, 0x61, 0x73, 0x6d]); // \0asm
+      if (!fileBuffer.slice(0, 4).equals(wasmMagic)) {
+        return NextResponse.json(
+          { error: "Invalid WASM file. File does not have valid WebAssembly magic bytes." },
+          { status: 400 }
+        );
+      }
+    }
+
+    // Calculate checksum, and make sure the file arrived intact
+    const checksum = createHash("sha256").update(fileBuffer).digest("hex");
+    if (expectedChecksum && expectedChecksum.toLowerCase() !== checksum) {
       return NextResponse.json(
-        { error: "Invalid WASM file. File does not have valid WebAssembly magic bytes." },
+        { error: "Checksum mismatch. The upload was corrupted in transit." },
         { status: 400 }
       );
     }
 
-    // Calculate checksum
-    const checksum = createHash("sha256").update(wasmBuffer).digest("hex");
-
     // Parse permissions if provided
     let permissions: string[] = [];
     if (permissionsJson) {
@@ -154,8 +173,8 @@ export async function POST(
     const uploadResult = await uploadPluginFile(
       pluginId,
       version,
-      wasmBuffer,
-      "plugin.wasm"
+      fileBuffer,
+      packageFile ? "plugin.zip" : "plugin.wasm"
     );
 
     // Mark existing latest version as not latest
@@ -179,7 +198,7 @@ export async function POST(
         version,
         downloadUrl: uploadResult.url,
         checksum,
-        fileSize: wasmBuffer.length,
+        fileSize: fileBuffer.length,
         permissions,
         changelog: changelog || null,
         isLatest: !isPrerelease,
@@ -0,0 +1,18 @@
+import { NextRequest, NextResponse } from "next/server";
+import { getPluginChanges } from "@/lib/plugins/registry";
+
+// GET /api/plugins/changes?since=<ISO time> - Plugins changed or removed since then
+export async function GET(request: NextRequest) {
+  try {
+    const since = new Date(new URL(request.url).searchParams.get("since") || "");
+    if (isNaN(since.getTime())) {
+      return NextResponse.json({ error: "since must be an ISO timestamp" }, { status: 400 });
+    }
+
+    const changes = await getPluginChanges(since);
+    return NextResponse.json(changes);
+  } catch (error) {
+    console.error("Error listing plugin changes:", error);
+    return NextResponse.json({ error: "Failed to list plugin changes" }, { status: 500 });
+  }
+}
@@ -2,6 +2,7 @@ import { NextRequest, NextResponse } from "next/server";
 import { searchPlugins, createPlugin } from "@/lib/plugins/registry";
 import type { CreatePluginInput } from "@/lib/plugins/types";
 import { getAuthUserWithName } from "@/lib/auth";
+import { createHash } from "crypto";
 
 // GET /api/plugins - Search/list plugins
 export async function GET(request: NextRequest) {
@@ -28,7 +29,16 @@ export async function GET(request: NextRequest) {
       offset,
     });
 
-    return NextResponse.json(result);
+    // Lets the launcher skip re-downloading a catalog it already has
+    const body = JSON.stringify(result);
+    const etag = `"${createHash("sha1").update(body).digest("hex")}"`;
+    if (request.headers.get("if-none-match") === etag) {
+      return new NextResponse(null, { status: 304, headers: { ETag: etag } });
+    }
+
+    return new NextResponse(body, {
+      headers: { "Content-Type": "application/json", ETag: etag },
+    });
   } catch (error) {
     console.error("Error searching plugins:", error);
     return NextResponse.json({ error: "Failed to search plugins" }, { status: 500 });
@@ -16,7 +16,7 @@ import {
   asc,
   sql,
 } from "@launcher/db";
-import { ilike } from "drizzle-orm";
+import { gt, ilike, ne } from "drizzle-orm";
 import { uploadPluginFile, deletePluginFiles } from "./storage";
 import type {
   PluginListItem,
@@ -28,6 +28,7 @@ import type {
   CreateVersionInput,
   SubmitRatingInput,
   PluginSearchOptions,
+  PluginChanges,
 } from "./types";
 import { createHash } from "crypto";
 
@@ -50,6 +51,7 @@ export async function searchPlugins(options: PluginSearchOptions = {}): Promise<
     sortBy = "downloads",
     limit = 20,
     offset = 0,
+    updatedSince,
   } = options;
 
   // Build conditions
@@ -93,6 +95,10 @@ export async function searchPlugins(options: PluginSearchOptions = {}): Promise<
     conditions.push(eq(plugins.featured, featured));
   }
 
+  if (updatedSince) {
+    conditions.push(gt(plugins.updatedAt, updatedSince));
+  }
+
   // Determine sort order
   let orderBy;
   switch (sortBy) {
@@ -157,6 +163,31 @@ export async function searchPlugins(options: PluginSearchOptions = {}): Promise<
   };
 }
 
+/**
+ * Catalog changes since `since`, so the launcher doesn't have to download the
+ * whole catalog again. Hard-deleted plugins aren't reported; clients drop
+ * those on their next full sync.
+ */
+export async function getPluginChanges(since: Date, limit = 100): Promise<PluginChanges> {
+  const db = getDb();
+  // Taken first so changes made while we query are sent again next time
+  const serverTime = new Date();
+
+  const changed = await searchPlugins({ updatedSince: since, sortBy: "newest", limit });
+
+  const removed = await db
+    .select({ id: plugins.id })
+    .from(plugins)
+    .where(and(ne(plugins.status, "published"), gt(plugins.updatedAt, since)));
+
+  return {
+    plugins: changed.plugins,
+    removed: removed.map((p) => p.id),
+    serverTime: serverTime.toISOString(),
+    hasMore: changed.hasMore,
+  };
+}
+
 /**
  * Get plugin details by ID
  */
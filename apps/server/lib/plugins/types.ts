@@ -109,5 +109,19 @@ export interface PluginSearchOptions {
   sortBy?: "downloads" | "rating" | "newest" | "weekly";
   limit?: number;
   offset?: number;
+  /** Only plugins changed after this time */
+  updatedSince?: Date;
+}
+
+/** Catalog changes since a client's last sync */
+export interface PluginChanges {
+  /** Plugins added or changed since then */
+  plugins: PluginListItem[];
+  /** Plugins no longer listed */
+  removed: string[];
+  /** Pass as `since` on the next request */
+  serverTime: string;
+  /** Too many changes to send; fetch the full catalog instead */
+  hasMore: boolean;
 }
 
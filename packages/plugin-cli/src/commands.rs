@@ -1,5 +1,8 @@
 //! CLI command implementations
 
+use crate::harness;
+use crate::marketplace;
+use crate::signing;
 use crate::templates;
 use colored::*;
 use serde::{Deserialize, Serialize};
@@ -28,6 +31,8 @@ pub struct PluginProvides {
     pub actions: Vec<String>,
     #[serde(default)]
     pub ai_tools: Vec<String>,
+    #[serde(default)]
+    pub fallback: bool,
 }
 
 /// Detect plugin language from current directory
@@ -230,7 +235,63 @@ fn build_typescript_plugin() -> Result<(), String> {
 }
 
 /// Build and install plugin for development
-pub fn dev_plugin() -> Result<(), String> {
+pub fn dev_plugin(watch: bool) -> Result<(), String> {
+    build_and_install()?;
+    
+    if !watch {
+        println!("  Restart Launcher to load the plugin");
+        return Ok(());
+    }
+    
+    println!("\n{} Watching for changes (Ctrl+C to stop)", "→".blue());
+    println!("  Turn on plugin dev mode in Launcher to reload the plugin automatically");
+    println!("  and see it in the marketplace under \"Local dev\"");
+    
+    let mut last_change = latest_source_change();
+    loop {
+        std::thread::sleep(WATCH_INTERVAL);
+        
+        let change = latest_source_change();
+        if change <= last_change {
+            continue;
+        }
+        last_change = change;
+        
+        println!("\n{} Change detected, rebuilding...", "→".blue());
+        // Keep watching after a failed build; the next save may fix it
+        if let Err(e) = build_and_install() {
+            eprintln!("{} {}", "Error:".red().bold(), e);
+        }
+    }
+}
+
+/// Written next to a dev install; must match Launcher
+const DEV_MARKER: &str = "dev.json";
+
+/// How often `dev --watch` checks the sources for changes
+const WATCH_INTERVAL: std::time::Duration = std::time::Duration::from_millis(500);
+
+/// Newest modification time among the plugin's sources, skipping build
+/// output and dependencies
+fn latest_source_change() -> Option<std::time::SystemTime> {
+    let entry = load_manifest().map(|m| m.entry).unwrap_or_default();
+    
+    walkdir::WalkDir::new(".")
+        .into_iter()
+        .filter_entry(|e| {
+            let name = e.file_name().to_string_lossy();
+            !matches!(name.as_ref(), "target" | "node_modules" | ".git" | "dist")
+        })
+        .flatten()
+        .filter(|e| e.file_type().is_file())
+        .filter(|e| e.path().strip_prefix(".").ok() != Some(Path::new(&entry)))
+        .filter(|e| e.path().extension().and_then(|x| x.to_str()) != Some("zip"))
+        .filter_map(|e| e.metadata().ok()?.modified().ok())
+        .max()
+}
+
+/// Build the plugin and copy it into Launcher's plugins directory
+fn build_and_install() -> Result<(), String> {
     // Build first
     build_plugin(false)?;
     
@@ -262,14 +323,28 @@ pub fn dev_plugin() -> Result<(), String> {
     fs::copy(&manifest.entry, target_dir.join(&manifest.entry))
         .map_err(|e| format!("Failed to copy WASM file: {}", e))?;
     
+    // Marks the install as a dev build, so Launcher lists it under "Local dev"
+    // in the marketplace instead of passing it off as a store plugin
+    let source = std::env::current_dir()
+        .map_err(|e| format!("Failed to get current directory: {}", e))?;
+    let marker = serde_json::json!({ "source": source });
+    let marker = serde_json::to_vec_pretty(&marker).map_err(|e| e.to_string())?;
+    fs::write(target_dir.join(DEV_MARKER), marker)
+        .map_err(|e| format!("Failed to write {}: {}", DEV_MARKER, e))?;
+    
     println!("{} Plugin installed for development", "✓".green().bold());
-    println!("  Restart Launcher to load the plugin");
     
     Ok(())
 }
 
 /// Package plugin for distribution
 pub fn package_plugin(output: Option<&str>) -> Result<(), String> {
+    write_package(output)?;
+    Ok(())
+}
+
+/// Build in release mode and zip the manifest and WASM, returning the zip path
+fn write_package(output: Option<&str>) -> Result<PathBuf, String> {
     // Build in release mode first
     build_plugin(true)?;
     
@@ -312,6 +387,170 @@ pub fn package_plugin(output: Option<&str>) -> Result<(), String> {
     
     println!("{} Package created: {} ({:.1} KB)", "✓".green().bold(), output_file, size as f64 / 1024.0);
     
+    Ok(PathBuf::from(output_file))
+}
+
+/// Sign a package zip with the developer's ed25519 key
+pub fn sign_plugin(package: Option<&str>, key: Option<&str>) -> Result<(), String> {
+    let package = match package {
+        Some(package) => PathBuf::from(package),
+        None => {
+            let manifest = load_manifest()?;
+            PathBuf::from(format!("{}-{}.zip", manifest.id, manifest.version))
+        }
+    };
+    if !package.exists() {
+        return Err(format!(
+            "{} not found. Run `launcher-plugin package` first.",
+            package.display()
+        ));
+    }
+    
+    let key_path = key.map(PathBuf::from).unwrap_or_else(signing::default_key_path);
+    let (signing_key, created) = signing::load_or_create_key(&key_path)?;
+    if created {
+        println!("{} Created signing key: {}", "→".blue(), key_path.display());
+        println!("  Back it up; Launcher refuses updates signed with a different key.");
+    }
+    
+    println!("\n{} Signing: {}", "→".blue(), package.display());
+    signing::sign_package(&package, &signing_key)?;
+    
+    println!("{} Package signed", "✓".green().bold());
+    println!("  Public key: {}", signing::public_key(&signing_key).cyan());
+    
+    Ok(())
+}
+
+/// Save a marketplace API key for `publish`
+pub fn login(token: Option<&str>) -> Result<(), String> {
+    let token = match token {
+        Some(token) => token.trim().to_string(),
+        None => {
+            println!("{} Create an API key at {}", "→".blue(), marketplace::api_keys_url().cyan());
+            print!("Paste your API key: ");
+            std::io::Write::flush(&mut std::io::stdout()).map_err(|e| e.to_string())?;
+            let mut line = String::new();
+            std::io::stdin()
+                .read_line(&mut line)
+                .map_err(|e| format!("Failed to read API key: {}", e))?;
+            line.trim().to_string()
+        }
+    };
+    
+    if !token.starts_with("lnch_") {
+        return Err("That doesn't look like a Launcher API key (they start with lnch_)".to_string());
+    }
+    
+    let credentials = marketplace::Credentials {
+        token,
+        api_url: marketplace::api_url(),
+    };
+    let path = marketplace::save_credentials(&credentials)?;
+    
+    println!("{} Logged in to {}", "✓".green().bold(), credentials.api_url);
+    println!("  Key saved to {}", path.display());
+    
+    Ok(())
+}
+
+/// Package the plugin and upload it to the marketplace as a new version
+pub fn publish_plugin(changelog: Option<&str>, prerelease: bool) -> Result<(), String> {
+    let credentials = marketplace::load_credentials()?;
+    let client = marketplace::MarketplaceClient::new(credentials)?;
+    
+    let package = write_package(None)?;
+    let manifest = load_manifest()?;
+    
+    // Sign with the developer's key if they've made one with `sign`
+    let key_path = signing::default_key_path();
+    if key_path.exists() {
+        let (signing_key, _) = signing::load_or_create_key(&key_path)?;
+        signing::sign_package(&package, &signing_key)?;
+        println!("{} Package signed", "✓".green().bold());
+    }
+    
+    let bytes = fs::read(&package)
+        .map_err(|e| format!("Failed to read package: {}", e))?;
+    let checksum = marketplace::sha256_hex(&bytes);
+    
+    println!("
+{} Publishing {} v{}", "→".blue(), manifest.name.cyan(), manifest.version);
+    println!("  SHA-256: {}", checksum.dimmed());
+    
+    if client.create_plugin(&manifest)? {
+        println!("  {} Created marketplace listing: {}", "✓".green(), manifest.id);
+    }
+    
+    let published = client.upload_version(&manifest, &package, &checksum, changelog, prerelease)?;
+    if published.checksum != checksum {
+        return Err(format!(
+            "Checksum mismatch: uploaded {} but the marketplace stored {}",
+            checksum, published.checksum
+        ));
+    }
+    
+    println!(
+        "\n{} Published {} v{} ({:.1} KB)",
+        "✓".green().bold(),
+        manifest.id,
+        published.version,
+        published.file_size as f64 / 1024.0
+    );
+    println!("  {}", published.download_url.dimmed());
+    if prerelease {
+        println!("  Marked as a prerelease; it won't be offered as the latest version.");
+    }
+    
+    Ok(())
+}
+
+/// Run the fixture queries in tests/queries.json against the built plugin
+pub fn test_plugin(queries: Option<&str>, no_build: bool) -> Result<(), String> {
+    if !no_build {
+        build_plugin(false)?;
+    }
+    
+    let manifest = load_manifest()?;
+    let queries_path = Path::new(queries.unwrap_or("tests/queries.json"));
+    let fixtures = harness::load_fixtures(queries_path)?;
+    
+    println!("\n{} Testing plugin: {}", "→".blue(), manifest.name.cyan());
+    
+    let mut plugin = harness::load(Path::new(&manifest.entry))?;
+    
+    // Launcher only logs a failing init, but a test run should fail on it
+    if plugin.function_exists("init") {
+        plugin.call::<(), ()>("init", ())
+            .map_err(|e| format!("init failed: {}", e))?;
+        println!("  {} init", "✓".green());
+    }
+    
+    if !plugin.function_exists("search") {
+        return Err("Plugin has no search function".to_string());
+    }
+    
+    let mut failed = 0;
+    for fixture in &fixtures {
+        let problems = harness::run_query(&mut plugin, fixture);
+        if problems.is_empty() {
+            println!("  {} search {:?}", "✓".green(), fixture.query);
+        } else {
+            failed += 1;
+            println!("  {} search {:?}", "✗".red(), fixture.query);
+            for problem in &problems {
+                println!("      {}", problem);
+            }
+        }
+    }
+    
+    let passed = fixtures.len() - failed;
+    if failed > 0 {
+        return Err(format!("{} of {} queries failed", failed, fixtures.len()));
+    }
+    
+    println!("\n{} {} queries passed", "✓".green().bold(), passed);
+    
     Ok(())
 }
 
@@ -441,6 +680,10 @@ pub fn info_plugin() -> Result<(), String> {
     if !manifest.provides.actions.is_empty() {
         println!("  {}: {}", "Actions".bold(), manifest.provides.actions.join(", "));
     }
+
+    if manifest.provides.fallback {
+        println!("  {}: only when nothing else matches", "Search".bold());
+    }
     
     // Check build status
     let wasm_exists = Path::new(&manifest.entry).exists();
@@ -0,0 +1,269 @@
+//! Test harness for `launcher-plugin test`
+//!
+//! Loads the built WASM in a bare Extism host, the same way Launcher does,
+//! and runs fixture queries against it. Host functions are stubbed: logs
+//! and notifications are printed, config starts empty, and network, file
+//! and OAuth calls fail so tests never depend on the outside world.
+
+use colored::*;
+use extism::{host_fn, Function, Manifest, Plugin, UserData, Wasm, PTR};
+use serde::Deserialize;
+use serde_json::Value;
+use std::path::Path;
+
+/// One entry of `tests/queries.json`
+#[derive(Debug, Clone, Deserialize)]
+pub struct QueryFixture {
+    pub query: String,
+    /// Fewest results the query must return
+    #[serde(default)]
+    pub min_results: Option<usize>,
+    /// Most results the query may return
+    #[serde(default)]
+    pub max_results: Option<usize>,
+    /// Result ids that must be among the results
+    #[serde(default)]
+    pub expect_ids: Vec<String>,
+}
+
+/// Action types Launcher understands, from `PluginAction`
+const ACTION_TYPES: &[&str] = &["open_url", "copy", "run_command", "custom"];
+
+fn error(message: &str) -> String {
+    serde_json::json!({ "error": message }).to_string()
+}
+
+fn ok() -> String {
+    serde_json::json!({ "ok": null }).to_string()
+}
+
+host_fn!(host_log(_user_data: (); level: String, message: String) {
+    println!("    {} {}", format!("[{}]", level).dimmed(), message.dimmed());
+    Ok(())
+});
+
+host_fn!(host_http_request(_user_data: (); _request_json: String) -> String {
+    Ok(error("Network access is not available in tests"))
+});
+
+host_fn!(host_get_config(_user_data: ();) -> String {
+    Ok(serde_json::json!({ "values": {} }).to_string())
+});
+
+host_fn!(host_set_config(_user_data: (); _config_json: String) -> String {
+    Ok(ok())
+});
+
+host_fn!(host_show_notification(_user_data: (); title: String, body: String) -> String {
+    println!("    {} {}: {}", "[notification]".dimmed(), title.dimmed(), body.dimmed());
+    Ok(ok())
+});
+
+host_fn!(host_read_clipboard(_user_data: ();) -> String {
+    Ok(error("The clipboard is not available in tests"))
+});
+
+host_fn!(host_write_clipboard(_user_data: (); text: String) -> String {
+    println!("    {} {}", "[clipboard]".dimmed(), text.dimmed());
+    Ok(ok())
+});
+
+host_fn!(host_get_oauth_token(_user_data: (); _provider: String) -> String {
+    Ok(error("OAuth is not available in tests"))
+});
+
+host_fn!(host_read_file(_user_data: (); _path: String) -> String {
+    Ok(error("File access is not available in tests"))
+});
+
+host_fn!(host_write_file(_user_data: (); _path: String, _contents: String) -> String {
+    Ok(error("File access is not available in tests"))
+});
+
+/// Stand-ins for the functions the SDKs import from Launcher
+fn host_functions() -> Vec<Function> {
+    let user_data = UserData::new(());
+    vec![
+        Function::new("host_log", [PTR, PTR], [], user_data.clone(), host_log),
+        Function::new(
+            "host_http_request",
+            [PTR],
+            [PTR],
+            user_data.clone(),
+            host_http_request,
+        ),
+        Function::new(
+            "host_get_config",
+            [],
+            [PTR],
+            user_data.clone(),
+            host_get_config,
+        ),
+        Function::new(
+            "host_set_config",
+            [PTR],
+            [PTR],
+            user_data.clone(),
+            host_set_config,
+        ),
+        Function::new(
+            "host_show_notification",
+            [PTR, PTR],
+            [PTR],
+            user_data.clone(),
+            host_show_notification,
+        ),
+        Function::new(
+            "host_read_clipboard",
+            [],
+            [PTR],
+            user_data.clone(),
+            host_read_clipboard,
+        ),
+        Function::new(
+            "host_write_clipboard",
+            [PTR],
+            [PTR],
+            user_data.clone(),
+            host_write_clipboard,
+        ),
+        Function::new(
+            "host_get_oauth_token",
+            [PTR],
+            [PTR],
+            user_data.clone(),
+            host_get_oauth_token,
+        ),
+        Function::new(
+            "host_read_file",
+            [PTR],
+            [PTR],
+            user_data.clone(),
+            host_read_file,
+        ),
+        Function::new(
+            "host_write_file",
+            [PTR, PTR],
+            [PTR],
+            user_data,
+            host_write_file,
+        ),
+    ]
+}
+
+/// Load a plugin's WASM file with the stub host functions
+pub fn load(wasm_path: &Path) -> Result<Plugin, String> {
+    let wasm = std::fs::read(wasm_path)
+        .map_err(|e| format!("Failed to read {}: {}", wasm_path.display(), e))?;
+    let manifest = Manifest::new([Wasm::data(wasm)]);
+    Plugin::new(&manifest, host_functions(), true)
+        .map_err(|e| format!("Failed to load {}: {}", wasm_path.display(), e))
+}
+
+/// Read the fixture queries
+pub fn load_fixtures(path: &Path) -> Result<Vec<QueryFixture>, String> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    serde_json::from_str(&content).map_err(|e| format!("Failed to parse {}: {}", path.display(), e))
+}
+
+/// Problems with a `search` output, checked against the SearchOutput schema:
+/// `{ "results": [{ id, title, subtitle?, icon?, score?, category?, action? }] }`
+pub fn validate_search_output(output: &Value) -> Vec<String> {
+    let mut problems = Vec::new();
+    let Some(results) = output.get("results") else {
+        problems.push("missing \"results\"".to_string());
+        return problems;
+    };
+    let Some(results) = results.as_array() else {
+        problems.push("\"results\" is not an array".to_string());
+        return problems;
+    };
+
+    for (i, result) in results.iter().enumerate() {
+        let at = |field: &str| format!("results[{}].{}", i, field);
+        if !result.is_object() {
+            problems.push(format!("results[{}] is not an object", i));
+            continue;
+        }
+
+        for field in ["id", "title"] {
+            match result.get(field).and_then(Value::as_str) {
+                Some(value) if !value.is_empty() => {}
+                Some(_) => problems.push(format!("{} is empty", at(field))),
+                None => problems.push(format!("{} must be a string", at(field))),
+            }
+        }
+        for field in ["subtitle", "icon", "category"] {
+            match result.get(field) {
+                None | Some(Value::Null) | Some(Value::String(_)) => {}
+                Some(_) => problems.push(format!("{} must be a string", at(field))),
+            }
+        }
+        match result.get("score") {
+            None | Some(Value::Null) | Some(Value::Number(_)) => {}
+            Some(_) => problems.push(format!("{} must be a number", at("score"))),
+        }
+        match result.get("action") {
+            None | Some(Value::Null) => {}
+            Some(action) => {
+                let kind = action.get("type").and_then(Value::as_str);
+                if !kind.is_some_and(|k| ACTION_TYPES.contains(&k)) {
+                    problems.push(format!(
+                        "{} must be one of {}",
+                        at("action.type"),
+                        ACTION_TYPES.join(", ")
+                    ));
+                }
+                if !action.get("value").is_some_and(Value::is_string) {
+                    problems.push(format!("{} must be a string", at("action.value")));
+                }
+            }
+        }
+    }
+    problems
+}
+
+/// Run one fixture query and return what's wrong with the answer
+pub fn run_query(plugin: &mut Plugin, fixture: &QueryFixture) -> Vec<String> {
+    let input = serde_json::json!({ "query": fixture.query }).to_string();
+    let output = match plugin.call::<&str, &str>("search", &input) {
+        Ok(output) => output.to_string(),
+        Err(e) => return vec![format!("search failed: {}", e)],
+    };
+    let output: Value = match serde_json::from_str(&output) {
+        Ok(output) => output,
+        Err(e) => return vec![format!("output is not JSON: {}", e)],
+    };
+
+    let mut problems = validate_search_output(&output);
+    if !problems.is_empty() {
+        return problems;
+    }
+
+    let results = output["results"].as_array().cloned().unwrap_or_default();
+    if let Some(min) = fixture.min_results {
+        if results.len() < min {
+            problems.push(format!(
+                "expected at least {} results, got {}",
+                min,
+                results.len()
+            ));
+        }
+    }
+    if let Some(max) = fixture.max_results {
+        if results.len() > max {
+            problems.push(format!(
+                "expected at most {} results, got {}",
+                max,
+                results.len()
+            ));
+        }
+    }
+    for id in &fixture.expect_ids {
+        if !results.iter().any(|r| r["id"].as_str() == Some(id)) {
+            problems.push(format!("missing result {}", id));
+        }
+    }
+    problems
+}
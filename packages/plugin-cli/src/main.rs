@@ -3,6 +3,9 @@
 //! A command-line tool for developing Launcher plugins in Rust or TypeScript.
 
 mod commands;
+mod harness;
+mod marketplace;
+mod signing;
 mod templates;
 
 use clap::{Parser, Subcommand};
@@ -37,7 +40,11 @@ enum Commands {
         release: bool,
     },
     /// Build and install plugin locally for development
-    Dev,
+    Dev {
+        /// Rebuild and reinstall whenever a source file changes
+        #[arg(short, long)]
+        watch: bool,
+    },
     /// Package the plugin for distribution
     Package {
         /// Output file path
@@ -50,6 +57,38 @@ enum Commands {
         #[arg(short, long, default_value = "ts")]
         lang: String,
     },
+    /// Run fixture queries against the built plugin
+    Test {
+        /// Fixture file (defaults to tests/queries.json)
+        #[arg(short, long)]
+        queries: Option<String>,
+        /// Test the existing build instead of building first
+        #[arg(long)]
+        no_build: bool,
+    },
+    /// Sign a packaged plugin with your ed25519 key
+    Sign {
+        /// Package to sign (defaults to <id>-<version>.zip)
+        package: Option<String>,
+        /// Signing key file (created on first use)
+        #[arg(short, long)]
+        key: Option<String>,
+    },
+    /// Save a marketplace API key for publishing
+    Login {
+        /// API key (prompted for if omitted)
+        #[arg(long)]
+        token: Option<String>,
+    },
+    /// Package the plugin and publish it to the marketplace
+    Publish {
+        /// Release notes for this version
+        #[arg(short, long)]
+        changelog: Option<String>,
+        /// Publish without making it the latest version
+        #[arg(long)]
+        prerelease: bool,
+    },
     /// Check the plugin for issues
     Check,
     /// Show plugin information
@@ -62,9 +101,13 @@ fn main() {
     let result = match cli.command {
         Commands::New { name, lang, output } => commands::new_plugin(&name, &lang, output.as_deref()),
         Commands::Build { release } => commands::build_plugin(release),
-        Commands::Dev => commands::dev_plugin(),
+        Commands::Dev { watch } => commands::dev_plugin(watch),
         Commands::Package { output } => commands::package_plugin(output.as_deref()),
         Commands::Init { lang } => commands::init_plugin(&lang),
+        Commands::Test { queries, no_build } => commands::test_plugin(queries.as_deref(), no_build),
+        Commands::Sign { package, key } => commands::sign_plugin(package.as_deref(), key.as_deref()),
+        Commands::Login { token } => commands::login(token.as_deref()),
+        Commands::Publish { changelog, prerelease } => commands::publish_plugin(changelog.as_deref(), prerelease),
         Commands::Check => commands::check_plugin(),
         Commands::Info => commands::info_plugin(),
     };
@@ -0,0 +1,206 @@
+//! Marketplace API client for `launcher-plugin login` and `publish`
+//!
+//! Authenticates with a Launcher API key (`lnch_…`) created in the web
+//! dashboard. The key is stored in the user's config directory and sent as
+//! a bearer token, the same way the desktop app talks to the API.
+
+use crate::commands::PluginManifest;
+use reqwest::blocking::{multipart, Client};
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+const DEFAULT_API_URL: &str = "http://localhost:3001";
+const DEFAULT_WEB_URL: &str = "http://localhost:3000";
+
+/// Packages can be a few megabytes; give slow uplinks time
+const UPLOAD_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// Base URL of the marketplace API, overridable with `LAUNCHER_API_URL`
+pub fn api_url() -> String {
+    std::env::var("LAUNCHER_API_URL")
+        .unwrap_or_else(|_| DEFAULT_API_URL.to_string())
+        .trim_end_matches('/')
+        .to_string()
+}
+
+/// Dashboard page where API keys are created
+pub fn api_keys_url() -> String {
+    let web_url = std::env::var("LAUNCHER_WEB_URL").unwrap_or_else(|_| DEFAULT_WEB_URL.to_string());
+    format!("{}/app/dashboard/api-keys", web_url.trim_end_matches('/'))
+}
+
+/// Saved by `launcher-plugin login`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Credentials {
+    pub token: String,
+    pub api_url: String,
+}
+
+fn credentials_path() -> PathBuf {
+    dirs::config_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("launcher")
+        .join("cli-credentials.json")
+}
+
+pub fn load_credentials() -> Result<Credentials, String> {
+    let content = fs::read_to_string(credentials_path())
+        .map_err(|_| "Not logged in. Run `launcher-plugin login` first.".to_string())?;
+    serde_json::from_str(&content)
+        .map_err(|e| format!("Failed to read saved credentials: {}", e))
+}
+
+pub fn save_credentials(credentials: &Credentials) -> Result<PathBuf, String> {
+    let path = credentials_path();
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create config directory: {}", e))?;
+    }
+    let content = serde_json::to_string_pretty(credentials).map_err(|e| e.to_string())?;
+    fs::write(&path, content).map_err(|e| format!("Failed to save credentials: {}", e))?;
+
+    // The key can publish as you; keep it private
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let _ = fs::set_permissions(&path, fs::Permissions::from_mode(0o600));
+    }
+    Ok(path)
+}
+
+/// Hex-encoded SHA-256, the checksum the marketplace stores and Launcher verifies
+pub fn sha256_hex(bytes: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(bytes))
+}
+
+/// A published version as the API returns it
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PublishedVersion {
+    pub version: String,
+    pub download_url: String,
+    pub checksum: String,
+    pub file_size: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct UploadResponse {
+    version: PublishedVersion,
+}
+
+#[derive(Debug, Deserialize)]
+struct ErrorResponse {
+    error: String,
+}
+
+/// The API's error message, or the status if the body isn't one
+fn api_error(status: StatusCode, body: &str) -> String {
+    match serde_json::from_str::<ErrorResponse>(body) {
+        Ok(e) => format!("{} ({})", e.error, status),
+        Err(_) => format!("Server returned {}", status),
+    }
+}
+
+pub struct MarketplaceClient {
+    client: Client,
+    credentials: Credentials,
+}
+
+impl MarketplaceClient {
+    pub fn new(credentials: Credentials) -> Result<Self, String> {
+        let client = Client::builder()
+            .timeout(UPLOAD_TIMEOUT)
+            .build()
+            .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+        Ok(Self { client, credentials })
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!("{}{}", self.credentials.api_url, path)
+    }
+
+    /// Register the plugin listing. Returns false if it already exists.
+    pub fn create_plugin(&self, manifest: &PluginManifest) -> Result<bool, String> {
+        let body = serde_json::json!({
+            "id": manifest.id,
+            "name": manifest.name,
+            "description": manifest.description,
+        });
+        let response = self.client
+            .post(self.url("/api/plugins"))
+            .bearer_auth(&self.credentials.token)
+            .json(&body)
+            .send()
+            .map_err(|e| format!("Failed to reach the marketplace: {}", e))?;
+
+        let status = response.status();
+        match status {
+            StatusCode::CONFLICT => Ok(false),
+            StatusCode::UNAUTHORIZED => {
+                Err("API key was rejected. Run `launcher-plugin login` again.".to_string())
+            }
+            s if s.is_success() => Ok(true),
+            _ => Err(api_error(status, &response.text().unwrap_or_default())),
+        }
+    }
+
+    /// Upload a packaged zip as a new version of the plugin
+    pub fn upload_version(
+        &self,
+        manifest: &PluginManifest,
+        package: &Path,
+        checksum: &str,
+        changelog: Option<&str>,
+        prerelease: bool,
+    ) -> Result<PublishedVersion, String> {
+        let file_name = package
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| "plugin.zip".to_string());
+        let bytes = fs::read(package)
+            .map_err(|e| format!("Failed to read {}: {}", package.display(), e))?;
+        let part = multipart::Part::bytes(bytes)
+            .file_name(file_name)
+            .mime_str("application/zip")
+            .map_err(|e| e.to_string())?;
+
+        let permissions = serde_json::to_string(&manifest.permissions).map_err(|e| e.to_string())?;
+        let mut form = multipart::Form::new()
+            .part("package", part)
+            .text("version", manifest.version.clone())
+            .text("checksum", checksum.to_string())
+            .text("permissions", permissions)
+            .text("prerelease", prerelease.to_string());
+        if let Some(changelog) = changelog {
+            form = form.text("changelog", changelog.to_string());
+        }
+
+        let response = self.client
+            .post(self.url(&format!("/api/plugins/{}/versions", manifest.id)))
+            .bearer_auth(&self.credentials.token)
+            .multipart(form)
+            .send()
+            .map_err(|e| format!("Failed to upload package: {}", e))?;
+
+        let status = response.status();
+        let body = response.text().unwrap_or_default();
+        match status {
+            StatusCode::CONFLICT => Err(format!(
+                "Version {} is already published. Bump the version in manifest.json.",
+                manifest.version
+            )),
+            StatusCode::FORBIDDEN => Err(format!(
+                "Plugin id \"{}\" belongs to another account",
+                manifest.id
+            )),
+            s if s.is_success() => serde_json::from_str::<UploadResponse>(&body)
+                .map(|r| r.version)
+                .map_err(|e| format!("Invalid response from the marketplace: {}", e)),
+            _ => Err(api_error(status, &body)),
+        }
+    }
+}
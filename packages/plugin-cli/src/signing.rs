@@ -0,0 +1,132 @@
+//! Package signing for `launcher-plugin sign`
+//!
+//! Adds `signature.json` to a package zip: an ed25519 signature over the
+//! SHA-256 of every other file in it. Launcher checks it before extracting
+//! the package and pins the key per plugin id, so keep the key safe; updates
+//! signed with a different key are refused.
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use ed25519_dalek::{Signer, SigningKey};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+
+pub const SIGNATURE_FILE: &str = "signature.json";
+
+/// First line of the signed payload; must match Launcher
+const PAYLOAD_HEADER: &str = "launcher-plugin-signature-v1";
+
+#[derive(Serialize)]
+struct PackageSignature {
+    public_key: String,
+    signature: String,
+}
+
+/// Where `sign` keeps the key it generates
+pub fn default_key_path() -> PathBuf {
+    dirs::config_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("launcher")
+        .join("plugin-signing-key")
+}
+
+/// Load a base64 signing key, or create one at `path` if there is none.
+/// Returns the key and whether it was just created.
+pub fn load_or_create_key(path: &Path) -> Result<(SigningKey, bool), String> {
+    if path.exists() {
+        let content = fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read signing key {}: {}", path.display(), e))?;
+        let bytes: [u8; 32] = STANDARD
+            .decode(content.trim())
+            .ok()
+            .and_then(|bytes| bytes.try_into().ok())
+            .ok_or_else(|| format!("{} is not a valid signing key", path.display()))?;
+        return Ok((SigningKey::from_bytes(&bytes), false));
+    }
+
+    let key = SigningKey::from_bytes(&rand::random::<[u8; 32]>());
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+    }
+    fs::write(path, STANDARD.encode(key.to_bytes()))
+        .map_err(|e| format!("Failed to save signing key: {}", e))?;
+
+    // Anyone with the key can publish updates users will accept
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let _ = fs::set_permissions(path, fs::Permissions::from_mode(0o600));
+    }
+    Ok((key, true))
+}
+
+pub fn public_key(key: &SigningKey) -> String {
+    STANDARD.encode(key.verifying_key().to_bytes())
+}
+
+/// A header, then `<sha256> <path>` for every file, sorted by path
+fn signed_payload(file_hashes: &BTreeMap<String, String>) -> Vec<u8> {
+    let mut payload = format!("{}\n", PAYLOAD_HEADER);
+    for (name, hash) in file_hashes {
+        payload.push_str(&format!("{} {}\n", hash, name));
+    }
+    payload.into_bytes()
+}
+
+/// Sign a package zip in place, replacing any earlier signature
+pub fn sign_package(package: &Path, key: &SigningKey) -> Result<(), String> {
+    let file = fs::File::open(package)
+        .map_err(|e| format!("Failed to open {}: {}", package.display(), e))?;
+    let mut archive = zip::ZipArchive::new(file)
+        .map_err(|e| format!("Failed to read {}: {}", package.display(), e))?;
+
+    let mut file_hashes = BTreeMap::new();
+    for i in 0..archive.len() {
+        let mut entry = archive.by_index(i)
+            .map_err(|e| format!("Failed to read package: {}", e))?;
+        if entry.is_dir() || entry.name() == SIGNATURE_FILE {
+            continue;
+        }
+        let mut contents = Vec::new();
+        entry.read_to_end(&mut contents)
+            .map_err(|e| format!("Failed to read {}: {}", entry.name(), e))?;
+        file_hashes.insert(entry.name().to_string(), format!("{:x}", Sha256::digest(&contents)));
+    }
+
+    let signature = PackageSignature {
+        public_key: public_key(key),
+        signature: STANDARD.encode(key.sign(&signed_payload(&file_hashes)).to_bytes()),
+    };
+
+    // Copy everything but an old signature into a new zip, then swap it in
+    let signed_path = package.with_extension("zip.signing");
+    let out = fs::File::create(&signed_path)
+        .map_err(|e| format!("Failed to create {}: {}", signed_path.display(), e))?;
+    let mut zip = zip::ZipWriter::new(out);
+    for i in 0..archive.len() {
+        let entry = archive.by_index_raw(i)
+            .map_err(|e| format!("Failed to read package: {}", e))?;
+        if entry.name() == SIGNATURE_FILE {
+            continue;
+        }
+        zip.raw_copy_file(entry)
+            .map_err(|e| format!("Failed to copy package contents: {}", e))?;
+    }
+
+    let options = zip::write::SimpleFileOptions::default()
+        .compression_method(zip::CompressionMethod::Deflated);
+    zip.start_file(SIGNATURE_FILE, options)
+        .map_err(|e| format!("Failed to add signature: {}", e))?;
+    let json = serde_json::to_vec_pretty(&signature).map_err(|e| e.to_string())?;
+    zip.write_all(&json)
+        .map_err(|e| format!("Failed to write signature: {}", e))?;
+    zip.finish()
+        .map_err(|e| format!("Failed to finalize zip: {}", e))?;
+
+    fs::rename(&signed_path, package)
+        .map_err(|e| format!("Failed to replace {}: {}", package.display(), e))
+}
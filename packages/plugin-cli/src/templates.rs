@@ -175,6 +175,8 @@ dist/
     fs::write(dir.join(".gitignore"), gitignore)
         .map_err(|e| format!("Failed to write .gitignore: {}", e))?;
     
+    write_test_queries(dir)?;
+    
     // Create README.md
     let readme = format!(r#"# {}
 
@@ -192,6 +194,9 @@ npm run build
 # Install locally for testing
 npm run dev
 
+# Run the queries in tests/queries.json against the build
+launcher-plugin test
+
 # Package for distribution
 npm run package
 ```
@@ -317,6 +322,8 @@ Cargo.lock
     fs::write(dir.join(".gitignore"), gitignore)
         .map_err(|e| format!("Failed to write .gitignore: {}", e))?;
     
+    write_test_queries(dir)?;
+    
     // Create README.md
     let readme = format!(r#"# {}
 
@@ -334,6 +341,9 @@ launcher-plugin build --release
 # Install locally for testing
 launcher-plugin dev
 
+# Run the queries in tests/queries.json against the build
+launcher-plugin test
+
 # Package for distribution
 launcher-plugin package
 ```
@@ -349,6 +359,24 @@ After installing the plugin in Launcher, you can search for "example" to see it
     Ok(())
 }
 
+/// Create tests/queries.json with fixtures for `launcher-plugin test`
+fn write_test_queries(dir: &Path) -> Result<(), String> {
+    fs::create_dir_all(dir.join("tests"))
+        .map_err(|e| format!("Failed to create tests directory: {}", e))?;
+    
+    let queries = r#"[
+  {
+    "query": "hello",
+    "min_results": 2,
+    "expect_ids": ["example-1", "example-2"]
+  }
+]
+"#;
+    
+    fs::write(dir.join("tests/queries.json"), queries)
+        .map_err(|e| format!("Failed to write tests/queries.json: {}", e))
+}
+
 /// Convert kebab-case to Title Case
 fn title_case(s: &str) -> String {
     s.split('-')
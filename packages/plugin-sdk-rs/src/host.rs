@@ -3,19 +3,12 @@
 
 use crate::types::{HttpRequest, HttpResponse, LogLevel, PluginConfig};
 use extism_pdk::*;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
 
-/// Log a message to the host console
+/// Log a message to the host console, tagged with the plugin id
 pub fn log(level: LogLevel, message: &str) {
-    let _ = extism_pdk::log!(
-        match level {
-            LogLevel::Debug => extism_pdk::LogLevel::Debug,
-            LogLevel::Info => extism_pdk::LogLevel::Info,
-            LogLevel::Warn => extism_pdk::LogLevel::Warn,
-            LogLevel::Error => extism_pdk::LogLevel::Error,
-        },
-        "{}",
-        message
-    );
+    let _ = unsafe { host_log(&level.to_string(), message) };
 }
 
 /// Log a debug message
@@ -61,18 +54,77 @@ macro_rules! log {
     };
 }
 
-// Import host functions
+// Host functions provided by the Launcher runtime. Every fallible call
+// answers with a JSON envelope (`{"ok": ...}` or `{"error": "..."}`) so a
+// failure on the host side surfaces as a `HostError` instead of aborting
+// the plugin call.
 #[host_fn]
 extern "ExtismHost" {
+    fn host_log(level: &str, message: &str);
     fn host_http_request(request_json: &str) -> String;
     fn host_get_config() -> String;
-    fn host_set_config(config_json: &str);
-    fn host_show_notification(title: &str, body: &str);
+    fn host_set_config(config_json: &str) -> String;
+    fn host_show_notification(title: &str, body: &str) -> String;
+    fn host_read_clipboard() -> String;
+    fn host_write_clipboard(text: &str) -> String;
     fn host_get_oauth_token(provider: &str) -> String;
+    fn host_read_file(path: &str) -> String;
+    fn host_write_file(path: &str, contents: &str) -> String;
+}
+
+/// Error returned by the host bindings
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HostError {
+    /// The host function could not be called (missing import, bad memory)
+    Call(String),
+    /// The host ran the call and reported an error, e.g. a missing permission
+    Host(String),
+    /// A value could not be converted to or from JSON
+    Json(String),
+}
+
+impl std::fmt::Display for HostError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HostError::Call(e) => write!(f, "Host call failed: {}", e),
+            HostError::Host(e) => write!(f, "{}", e),
+            HostError::Json(e) => write!(f, "Invalid JSON: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for HostError {}
+
+impl From<serde_json::Error> for HostError {
+    fn from(e: serde_json::Error) -> Self {
+        HostError::Json(e.to_string())
+    }
+}
+
+/// Envelope the host wraps fallible results in
+#[derive(Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum HostResponse<T> {
+    Ok(T),
+    Error(String),
+}
+
+/// Decode a host envelope into its value or a `HostError::Host`
+fn unwrap_response<T: DeserializeOwned>(response: Result<String, Error>) -> Result<T, HostError> {
+    let json = response.map_err(|e| HostError::Call(e.to_string()))?;
+    match serde_json::from_str(&json)? {
+        HostResponse::Ok(value) => Ok(value),
+        HostResponse::Error(message) => Err(HostError::Host(message)),
+    }
 }
 
 /// Make an HTTP request
-/// 
+///
+/// A non-2xx status is still `Ok`; check `HttpResponse::is_success`.
+///
+/// # Note
+/// Requires the `network` permission in the plugin manifest.
+///
 /// # Example
 /// ```rust,ignore
 /// let response = http_request(HttpRequest::get("https://api.example.com/data"))?;
@@ -80,28 +132,40 @@ extern "ExtismHost" {
 ///     let data: MyData = response.json()?;
 /// }
 /// ```
-pub fn http_request(request: HttpRequest) -> Result<HttpResponse, Error> {
-    let request_json = serde_json::to_string(&request)
-        .map_err(|e| Error::msg(format!("Failed to serialize request: {}", e)))?;
-    
-    let response_json = unsafe { host_http_request(&request_json)? };
-    
-    serde_json::from_str(&response_json)
-        .map_err(|e| Error::msg(format!("Failed to parse response: {}", e)))
+pub fn http_request(request: HttpRequest) -> Result<HttpResponse, HostError> {
+    let request_json = serde_json::to_string(&request)?;
+    unwrap_response(unsafe { host_http_request(&request_json) })
 }
 
 /// Convenience function for GET requests
-pub fn http_get(url: &str) -> Result<HttpResponse, Error> {
+pub fn http_get(url: &str) -> Result<HttpResponse, HostError> {
     http_request(HttpRequest::get(url))
 }
 
 /// Convenience function for GET requests with bearer token
-pub fn http_get_with_token(url: &str, token: &str) -> Result<HttpResponse, Error> {
+pub fn http_get_with_token(url: &str, token: &str) -> Result<HttpResponse, HostError> {
     http_request(HttpRequest::get(url).with_bearer_token(token))
 }
 
+/// GET a URL and parse a successful response body as JSON
+///
+/// # Example
+/// ```rust,ignore
+/// let repos: Vec<Repo> = http_get_json("https://api.github.com/users/me/repos")?;
+/// ```
+pub fn http_get_json<T: DeserializeOwned>(url: &str) -> Result<T, HostError> {
+    let response = http_get(url)?;
+    if !response.is_success() {
+        return Err(HostError::Host(format!(
+            "HTTP {} from {}",
+            response.status, url
+        )));
+    }
+    Ok(response.json()?)
+}
+
 /// Convenience function for POST requests
-pub fn http_post(url: &str, body: Option<&str>) -> Result<HttpResponse, Error> {
+pub fn http_post(url: &str, body: Option<&str>) -> Result<HttpResponse, HostError> {
     let mut req = HttpRequest::post(url);
     if let Some(body) = body {
         req = req.with_body(body);
@@ -110,49 +174,127 @@ pub fn http_post(url: &str, body: Option<&str>) -> Result<HttpResponse, Error> {
 }
 
 /// Convenience function for POST requests with JSON body
-pub fn http_post_json<T: serde::Serialize>(url: &str, data: &T) -> Result<HttpResponse, Error> {
-    let req = HttpRequest::post(url)
-        .with_json(data)
-        .map_err(|e| Error::msg(format!("Failed to serialize JSON: {}", e)))?;
-    http_request(req)
+pub fn http_post_json<T: Serialize>(url: &str, data: &T) -> Result<HttpResponse, HostError> {
+    http_request(HttpRequest::post(url).with_json(data)?)
 }
 
 /// Get the plugin's configuration
-pub fn get_config() -> Result<PluginConfig, Error> {
-    let config_json = unsafe { host_get_config()? };
-    serde_json::from_str(&config_json)
-        .map_err(|e| Error::msg(format!("Failed to parse config: {}", e)))
+pub fn get_config() -> Result<PluginConfig, HostError> {
+    let config_json = unsafe { host_get_config() }.map_err(|e| HostError::Call(e.to_string()))?;
+    Ok(serde_json::from_str(&config_json)?)
+}
+
+/// Set the plugin's configuration, replacing all stored values
+pub fn set_config(config: &PluginConfig) -> Result<(), HostError> {
+    let config_json = serde_json::to_string(config)?;
+    unwrap_response(unsafe { host_set_config(&config_json) })
+}
+
+/// Get a single configuration value, `None` if unset or of another type
+///
+/// # Example
+/// ```rust,ignore
+/// let api_key: Option<String> = get_config_value("api_key")?;
+/// ```
+pub fn get_config_value<T: DeserializeOwned>(key: &str) -> Result<Option<T>, HostError> {
+    Ok(get_config()?.get(key))
 }
 
-/// Set the plugin's configuration
-pub fn set_config(config: &PluginConfig) -> Result<(), Error> {
-    let config_json = serde_json::to_string(config)
-        .map_err(|e| Error::msg(format!("Failed to serialize config: {}", e)))?;
-    unsafe { host_set_config(&config_json)? };
-    Ok(())
+/// Set a single configuration value, keeping the others
+pub fn set_config_value<T: Serialize>(key: &str, value: T) -> Result<(), HostError> {
+    let mut config = get_config()?;
+    config.set(key, value)?;
+    set_config(&config)
 }
 
 /// Show a system notification
-/// 
+///
 /// # Note
-/// Requires the `notifications` permission in the plugin manifest.
-pub fn show_notification(title: &str, body: &str) -> Result<(), Error> {
-    unsafe { host_show_notification(title, body)? };
-    Ok(())
+/// Requires the `notifications` permission in the plugin manifest. The
+/// title is prefixed with the plugin's name, and a plugin may show 5
+/// notifications a minute; more fail until the minute is up.
+pub fn show_notification(title: &str, body: &str) -> Result<(), HostError> {
+    unwrap_response(unsafe { host_show_notification(title, body) })
+}
+
+/// Show a system notification; shorthand for [`show_notification`]
+///
+/// # Example
+/// ```rust,ignore
+/// notify("Build finished", "main passed in 4m 12s")?;
+/// ```
+pub fn notify(title: &str, body: &str) -> Result<(), HostError> {
+    show_notification(title, body)
+}
+
+/// Read the text on the clipboard
+///
+/// # Note
+/// Requires the `clipboard` permission in the plugin manifest. Fails while
+/// the clipboard holds something a password manager marked as secret.
+pub fn read_clipboard() -> Result<String, HostError> {
+    unwrap_response(unsafe { host_read_clipboard() })
+}
+
+/// Put text on the clipboard, replacing what's there
+///
+/// # Note
+/// Requires the `clipboard` permission in the plugin manifest. Text over
+/// 1 MiB is refused.
+///
+/// # Example
+/// ```rust,ignore
+/// copy_to_clipboard(&short_link)?;
+/// ```
+pub fn copy_to_clipboard(text: &str) -> Result<(), HostError> {
+    unwrap_response(unsafe { host_write_clipboard(text) })
 }
 
 /// Get an OAuth token for a provider
-/// 
+///
 /// # Note
 /// Requires the `oauth:provider` permission in the plugin manifest.
-/// 
+///
 /// # Example
 /// ```rust,ignore
 /// let token = get_oauth_token("github")?;
 /// let response = http_get_with_token("https://api.github.com/user", &token)?;
 /// ```
-pub fn get_oauth_token(provider: &str) -> Result<String, Error> {
-    unsafe { host_get_oauth_token(provider) }
+pub fn get_oauth_token(provider: &str) -> Result<String, HostError> {
+    unwrap_response(unsafe { host_get_oauth_token(provider) })
+}
+
+/// Read a text file from the plugin's data directory
+///
+/// Paths are relative to the data directory; absolute paths and `..`
+/// escapes are rejected by the host.
+///
+/// # Note
+/// Requires the `filesystem:read` permission in the plugin manifest.
+pub fn read_file(path: &str) -> Result<String, HostError> {
+    unwrap_response(unsafe { host_read_file(path) })
+}
+
+/// Write a text file in the plugin's data directory, creating parent
+/// directories as needed
+///
+/// # Note
+/// Requires the `filesystem:write` permission in the plugin manifest.
+pub fn write_file(path: &str, contents: &str) -> Result<(), HostError> {
+    unwrap_response(unsafe { host_write_file(path, contents) })
 }
 
+/// Read and parse a JSON file from the plugin's data directory
+///
+/// # Example
+/// ```rust,ignore
+/// let cache: Option<Cache> = read_json("cache.json").ok();
+/// ```
+pub fn read_json<T: DeserializeOwned>(path: &str) -> Result<T, HostError> {
+    Ok(serde_json::from_str(&read_file(path)?)?)
+}
 
+/// Serialize a value as pretty JSON into the plugin's data directory
+pub fn write_json<T: Serialize>(path: &str, value: &T) -> Result<(), HostError> {
+    write_file(path, &serde_json::to_string_pretty(value)?)
+}
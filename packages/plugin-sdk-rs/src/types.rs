@@ -25,6 +25,10 @@ pub struct SearchResult {
     /// Action to execute when selected
     #[serde(skip_serializing_if = "Option::is_none")]
     pub action: Option<PluginAction>,
+    /// What screen readers announce; the launcher builds one from the
+    /// title and subtitle if this is left out
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub aria_label: Option<String>,
 }
 
 impl SearchResult {
@@ -38,6 +42,7 @@ impl SearchResult {
             score: None,
             category: None,
             action: None,
+            aria_label: None,
         }
     }
 
@@ -88,6 +93,12 @@ impl SearchResult {
         self.action = Some(PluginAction::Custom(action.into()));
         self
     }
+
+    /// Set what screen readers announce for the result
+    pub fn with_aria_label(mut self, label: impl Into<String>) -> Self {
+        self.aria_label = Some(label.into());
+        self
+    }
 }
 
 /// Action to execute when a search result is selected
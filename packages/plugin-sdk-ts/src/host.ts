@@ -16,12 +16,27 @@ declare const Host: {
     host_log?: (level: string, message: string) => void;
     host_http_request?: (requestJson: string) => string;
     host_get_config?: () => string;
-    host_set_config?: (configJson: string) => void;
-    host_show_notification?: (title: string, body: string) => void;
+    host_set_config?: (configJson: string) => string;
+    host_show_notification?: (title: string, body: string) => string;
+    host_read_clipboard?: () => string;
+    host_write_clipboard?: (text: string) => string;
     host_get_oauth_token?: (provider: string) => string;
+    host_read_file?: (path: string) => string;
+    host_write_file?: (path: string, contents: string) => string;
   };
 };
 
+/**
+ * Fallible host calls answer with `{"ok": value}` or `{"error": message}`
+ */
+function unwrap<T>(responseJson: string): T {
+  const response = JSON.parse(responseJson);
+  if (typeof response === 'object' && response !== null && 'error' in response) {
+    throw new Error(response.error);
+  }
+  return response.ok as T;
+}
+
 /**
  * Log a message to the host console
  */
@@ -55,8 +70,7 @@ export function httpRequest(request: HttpRequest): HttpResponse {
   try {
     const fns = Host.getFunctions();
     if (fns.host_http_request) {
-      const responseJson = fns.host_http_request(JSON.stringify(request));
-      return JSON.parse(responseJson);
+      return unwrap<HttpResponse>(fns.host_http_request(JSON.stringify(request)));
     }
     throw new Error('HTTP requests not available');
   } catch (e) {
@@ -104,7 +118,7 @@ export function setConfig(config: PluginConfig): void {
   try {
     const fns = Host.getFunctions();
     if (fns.host_set_config) {
-      fns.host_set_config(JSON.stringify(config));
+      unwrap<null>(fns.host_set_config(JSON.stringify(config)));
     }
   } catch (e) {
     throw new Error(`Failed to set config: ${e}`);
@@ -119,13 +133,46 @@ export function showNotification(title: string, body: string): void {
   try {
     const fns = Host.getFunctions();
     if (fns.host_show_notification) {
-      fns.host_show_notification(title, body);
+      unwrap<null>(fns.host_show_notification(title, body));
     }
   } catch (e) {
     throw new Error(`Failed to show notification: ${e}`);
   }
 }
 
+/**
+ * Read the text on the clipboard; fails while it holds a password
+ * @requires clipboard permission
+ */
+export function readClipboard(): string {
+  try {
+    const fns = Host.getFunctions();
+    if (fns.host_read_clipboard) {
+      return unwrap<string>(fns.host_read_clipboard());
+    }
+    throw new Error('Clipboard not available');
+  } catch (e) {
+    throw new Error(`Failed to read clipboard: ${e}`);
+  }
+}
+
+/**
+ * Put text on the clipboard, replacing what's there
+ * @requires clipboard permission
+ */
+export function copyToClipboard(text: string): void {
+  try {
+    const fns = Host.getFunctions();
+    if (fns.host_write_clipboard) {
+      unwrap<null>(fns.host_write_clipboard(text));
+      return;
+    }
+    throw new Error('Clipboard not available');
+  } catch (e) {
+    throw new Error(`Failed to copy to clipboard: ${e}`);
+  }
+}
+
 /**
  * Get an OAuth token for a provider
  * @requires oauth:provider permission
@@ -134,7 +181,7 @@ export function getOAuthToken(provider: string): string {
   try {
     const fns = Host.getFunctions();
     if (fns.host_get_oauth_token) {
-      return fns.host_get_oauth_token(provider);
+      return unwrap<string>(fns.host_get_oauth_token(provider));
     }
     throw new Error('OAuth not available');
   } catch (e) {
@@ -142,4 +189,35 @@ export function getOAuthToken(provider: string): string {
   }
 }
 
+/**
+ * Read a text file from the plugin's data directory
+ * @requires filesystem:read permission
+ */
+export function readFile(path: string): string {
+  try {
+    const fns = Host.getFunctions();
+    if (fns.host_read_file) {
+      return unwrap<string>(fns.host_read_file(path));
+    }
+    throw new Error('File access not available');
+  } catch (e) {
+    throw new Error(`Failed to read ${path}: ${e}`);
+  }
+}
 
+/**
+ * Write a text file in the plugin's data directory
+ * @requires filesystem:write permission
+ */
+export function writeFile(path: string, contents: string): void {
+  try {
+    const fns = Host.getFunctions();
+    if (fns.host_write_file) {
+      unwrap<null>(fns.host_write_file(path, contents));
+      return;
+    }
+    throw new Error('File access not available');
+  } catch (e) {
+    throw new Error(`Failed to write ${path}: ${e}`);
+  }
+}
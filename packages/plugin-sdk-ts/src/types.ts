@@ -20,6 +20,8 @@ export interface SearchResult {
   category?: string;
   /** Action to execute when selected */
   action?: PluginAction;
+  /** What screen readers announce; built from the title and subtitle if left out */
+  aria_label?: string;
 }
 
 /**
@@ -81,6 +83,8 @@ export interface PluginProvides {
   actions?: string[];
   /** AI tools - tool names that this plugin provides */
   ai_tools?: string[];
+  /** Only searched when no other provider found anything */
+  fallback?: boolean;
 }
 
 /**